@@ -492,6 +492,30 @@ fn limb_lshift_with_carry_prevent_overflow(bits: u32, head: u32) -> Script {
     }
 }
 
+#[cfg(any(feature = "fuzzing", test))]
+impl<const N_BITS: u32, const LIMB_SIZE: u32> BigIntImpl<N_BITS, LIMB_SIZE> {
+    /// Verifies that `add` matches the native sum (modulo 2^N_BITS) of the given little-endian byte slices
+    pub fn test_add_givenbytes(a_bytes: &[u8], b_bytes: &[u8]) {
+        use core::ops::{Rem, Shl};
+        use num_bigint::BigUint;
+        use num_traits::One;
+
+        let a = Self::biguint_from_le_bytes(a_bytes);
+        let b = Self::biguint_from_le_bytes(b_bytes);
+        let c = (a.clone() + b.clone()).rem(BigUint::one().shl(N_BITS));
+
+        let script = script! {
+            { Self::push_u32_le(&a.to_u32_digits()) }
+            { Self::push_u32_le(&b.to_u32_digits()) }
+            { Self::add(1, 0) }
+            { Self::push_u32_le(&c.to_u32_digits()) }
+            { Self::equalverify(1, 0) }
+            OP_TRUE
+        };
+        run(script);
+    }
+}
+
 #[cfg(test)]
 mod test {
     use crate::bigint::{U254, U64};
@@ -571,4 +595,11 @@ mod test {
             run(script);
         }
     }
+
+    #[test]
+    fn test_add_givenbytes() {
+        U254::test_add_givenbytes(&[0xff; 32], &[0xff; 32]);
+        U254::test_add_givenbytes(&[], &[0x01]);
+        U64::test_add_givenbytes(&[0xff; 8], &[0x01]);
+    }
 }
@@ -88,6 +88,38 @@ impl<const N_BITS: u32, const LIMB_SIZE: u32> BigIntImpl<N_BITS, LIMB_SIZE> {
     }
 }
 
+#[cfg(any(feature = "fuzzing", test))]
+impl<const N_BITS: u32, const LIMB_SIZE: u32> BigIntImpl<N_BITS, LIMB_SIZE> {
+    /// Verifies that every comparison matches the native ordering of the given little-endian byte slices
+    pub fn test_cmp_givenbytes(a_bytes: &[u8], b_bytes: &[u8]) {
+        use core::cmp::Ordering;
+
+        let a = Self::biguint_from_le_bytes(a_bytes);
+        let b = Self::biguint_from_le_bytes(b_bytes);
+        let ordering = a.cmp(&b);
+
+        let comparisons = [
+            (Self::equal(1, 0), ordering == Ordering::Equal),
+            (Self::notequal(1, 0), ordering != Ordering::Equal),
+            (Self::lessthan(1, 0), ordering == Ordering::Less),
+            (Self::lessthanorequal(1, 0), ordering != Ordering::Greater),
+            (Self::greaterthan(1, 0), ordering == Ordering::Greater),
+            (Self::greaterthanorequal(1, 0), ordering != Ordering::Less),
+        ];
+
+        for (comparison, expected) in comparisons {
+            let script = script! {
+                { Self::push_u32_le(&a.to_u32_digits()) }
+                { Self::push_u32_le(&b.to_u32_digits()) }
+                { comparison }
+                { expected as u32 }
+                OP_EQUAL
+            };
+            run(script);
+        }
+    }
+}
+
 #[cfg(test)]
 mod test {
     use crate::bigint::{U254, U64};
@@ -345,4 +377,11 @@ mod test {
         };
         run(script);
     }
+
+    #[test]
+    fn test_cmp_givenbytes() {
+        U254::test_cmp_givenbytes(&[0xff; 32], &[0xff; 32]);
+        U254::test_cmp_givenbytes(&[0x01], &[0x00, 0x01]);
+        U64::test_cmp_givenbytes(&[0xff; 8], &[]);
+    }
 }
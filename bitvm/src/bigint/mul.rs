@@ -39,6 +39,30 @@ impl<const N_BITS: u32, const LIMB_SIZE: u32> BigIntImpl<N_BITS, LIMB_SIZE> {
     }
 }
 
+#[cfg(any(feature = "fuzzing", test))]
+impl<const N_BITS: u32, const LIMB_SIZE: u32> BigIntImpl<N_BITS, LIMB_SIZE> {
+    /// Verifies that `mul` matches the native product (modulo 2^N_BITS) of the given little-endian byte slices
+    pub fn test_mul_givenbytes(a_bytes: &[u8], b_bytes: &[u8]) {
+        use core::ops::{Mul, Rem, Shl};
+        use num_bigint::BigUint;
+        use num_traits::One;
+
+        let a = Self::biguint_from_le_bytes(a_bytes);
+        let b = Self::biguint_from_le_bytes(b_bytes);
+        let c = (a.clone().mul(b.clone())).rem(BigUint::one().shl(N_BITS));
+
+        let script = script! {
+            { Self::push_u32_le(&a.to_u32_digits()) }
+            { Self::push_u32_le(&b.to_u32_digits()) }
+            { Self::mul() }
+            { Self::push_u32_le(&c.to_u32_digits()) }
+            { Self::equalverify(1, 0) }
+            OP_TRUE
+        };
+        crate::run(script);
+    }
+}
+
 #[cfg(test)]
 mod test {
     use crate::bigint::{U254, U64};
@@ -84,4 +108,11 @@ mod test {
             run(script);
         }
     }
+
+    #[test]
+    fn test_mul_givenbytes() {
+        U254::test_mul_givenbytes(&[0xff; 32], &[0xff; 32]);
+        U64::test_mul_givenbytes(&[0xff; 8], &[0xff; 8]);
+        U64::test_mul_givenbytes(&[], &[0xff; 8]);
+    }
 }
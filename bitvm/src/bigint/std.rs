@@ -534,6 +534,17 @@ pub fn extract_digits(start_index: u32, window: u32) -> Script {
     }
 }
 
+#[cfg(any(feature = "fuzzing", test))]
+impl<const N_BITS: u32, const LIMB_SIZE: u32> BigIntImpl<N_BITS, LIMB_SIZE> {
+    /// Interprets the little-endian byte slice as a number, truncated to `N_BITS` bits
+    pub fn biguint_from_le_bytes(bytes: &[u8]) -> BigUint {
+        use core::ops::{Rem, Shl};
+        use num_traits::One;
+
+        BigUint::from_bytes_le(bytes).rem(BigUint::one().shl(N_BITS))
+    }
+}
+
 #[cfg(test)]
 mod test {
     use crate::bigint::std::extract_digits;
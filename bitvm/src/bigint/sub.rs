@@ -66,6 +66,30 @@ pub fn limb_sub_noborrow(head_offset: u32) -> Script {
     }
 }
 
+#[cfg(any(feature = "fuzzing", test))]
+impl<const N_BITS: u32, const LIMB_SIZE: u32> BigIntImpl<N_BITS, LIMB_SIZE> {
+    /// Verifies that `sub` matches the native difference (modulo 2^N_BITS) of the given little-endian byte slices
+    pub fn test_sub_givenbytes(a_bytes: &[u8], b_bytes: &[u8]) {
+        use core::ops::{Rem, Shl};
+        use num_bigint::BigUint;
+        use num_traits::One;
+
+        let a = Self::biguint_from_le_bytes(a_bytes);
+        let b = Self::biguint_from_le_bytes(b_bytes);
+        let c = (BigUint::one().shl(N_BITS) + &a - &b).rem(BigUint::one().shl(N_BITS));
+
+        let script = script! {
+            { Self::push_u32_le(&a.to_u32_digits()) }
+            { Self::push_u32_le(&b.to_u32_digits()) }
+            { Self::sub(1, 0) }
+            { Self::push_u32_le(&c.to_u32_digits()) }
+            { Self::equalverify(1, 0) }
+            OP_TRUE
+        };
+        run(script);
+    }
+}
+
 #[cfg(test)]
 mod test {
     use crate::bigint::{U254, U64};
@@ -134,4 +158,11 @@ mod test {
             run(script);
         }
     }
+
+    #[test]
+    fn test_sub_givenbytes() {
+        U254::test_sub_givenbytes(&[], &[0xff; 32]);
+        U254::test_sub_givenbytes(&[0xff; 32], &[0xff; 32]);
+        U64::test_sub_givenbytes(&[0x01], &[0xff; 8]);
+    }
 }
@@ -410,6 +410,31 @@ fp_lc_mul!(Mul, 4, 4, [true]);
 fp_lc_mul!(Mul2LC, 3, 3, [true, true]);
 fp_lc_mul!(Mul4LC, 3, 3, [true, true, true, true]);
 
+#[cfg(any(feature = "fuzzing", test))]
+impl Fq {
+    /// Verifies that `hinted_mul` matches the native product of the field elements built from the given bytes
+    pub fn test_hinted_mul_givenbytes(a_bytes: &[u8], b_bytes: &[u8]) {
+        use crate::bn254::utils::fq_elements_from_bytes;
+
+        let a = fq_elements_from_bytes(a_bytes, 1)[0];
+        let b = fq_elements_from_bytes(b_bytes, 1)[0];
+        let c = a * b;
+
+        let (hinted_mul, hints) = Fq::hinted_mul(1, a, 0, b);
+        let script = script! {
+            for hint in hints {
+                { hint.push() }
+            }
+            { Fq::push(a) }
+            { Fq::push(b) }
+            { hinted_mul }
+            { Fq::push(c) }
+            { Fq::equal(0, 1) }
+        };
+        run(script);
+    }
+}
+
 #[cfg(test)]
 mod test {
     use crate::bn254::fp254impl::Fp254Impl;
@@ -1015,4 +1040,10 @@ mod test {
             max_stack
         );
     }
+
+    #[test]
+    fn test_hinted_mul_givenbytes() {
+        Fq::test_hinted_mul_givenbytes(&[0xff; 32], &[0xff; 32]);
+        Fq::test_hinted_mul_givenbytes(&[], &[0x01]);
+    }
 }
@@ -284,6 +284,33 @@ impl Fq12 {
     }    
 }
 
+#[cfg(any(feature = "fuzzing", test))]
+impl Fq12 {
+    /// Verifies that `hinted_mul` matches the native product of the field elements built from the given bytes
+    pub fn test_hinted_mul_givenbytes(a_bytes: &[u8], b_bytes: &[u8]) {
+        use crate::bn254::utils::fq_elements_from_bytes;
+        use crate::treepp::run;
+
+        let a = ark_bn254::Fq12::from_base_prime_field_elems(fq_elements_from_bytes(a_bytes, 12)).unwrap();
+        let b = ark_bn254::Fq12::from_base_prime_field_elems(fq_elements_from_bytes(b_bytes, 12)).unwrap();
+        let c = a * b;
+
+        let (hinted_mul, hints) = Fq12::hinted_mul(12, a, 0, b);
+        let script = script! {
+            for hint in hints {
+                { hint.push() }
+            }
+            { Fq12::push(a) }
+            { Fq12::push(b) }
+            { hinted_mul }
+            { Fq12::push(c) }
+            { Fq12::equalverify() }
+            OP_TRUE
+        };
+        run(script);
+    }
+}
+
 #[cfg(test)]
 mod test {
     use crate::bn254::fq12::Fq12;
@@ -450,4 +477,9 @@ mod test {
             }
         }
     }
+
+    #[test]
+    fn test_bn254_fq12_hinted_mul_givenbytes() {
+        Fq12::test_hinted_mul_givenbytes(&[0xff; 384], &[0x01; 100]);
+    }
 }
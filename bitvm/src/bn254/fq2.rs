@@ -295,6 +295,34 @@ impl Fq2 {
     }
 }
 
+#[cfg(any(feature = "fuzzing", test))]
+impl Fq2 {
+    /// Verifies that `hinted_mul` matches the native product of the field elements built from the given bytes
+    pub fn test_hinted_mul_givenbytes(a_bytes: &[u8], b_bytes: &[u8]) {
+        use crate::bn254::utils::fq_elements_from_bytes;
+        use crate::treepp::run;
+        use ark_ff::Field;
+
+        let a = ark_bn254::Fq2::from_base_prime_field_elems(fq_elements_from_bytes(a_bytes, 2)).unwrap();
+        let b = ark_bn254::Fq2::from_base_prime_field_elems(fq_elements_from_bytes(b_bytes, 2)).unwrap();
+        let c = a * b;
+
+        let (hinted_mul, hints) = Fq2::hinted_mul(2, a, 0, b);
+        let script = script! {
+            for hint in hints {
+                { hint.push() }
+            }
+            { Fq2::push(a) }
+            { Fq2::push(b) }
+            { hinted_mul }
+            { Fq2::push(c) }
+            { Fq2::equalverify() }
+            OP_TRUE
+        };
+        run(script);
+    }
+}
+
 #[cfg(test)]
 mod test {
     use crate::bn254::fq2::Fq2;
@@ -574,4 +602,10 @@ mod test {
             run(script);
         }
     }
+
+    #[test]
+    fn test_bn254_fq2_hinted_mul_givenbytes() {
+        Fq2::test_hinted_mul_givenbytes(&[0xff; 64], &[0xff; 64]);
+        Fq2::test_hinted_mul_givenbytes(&[], &[0x01]);
+    }
 }
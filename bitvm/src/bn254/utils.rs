@@ -73,3 +73,14 @@ pub fn fq_to_bits(fq: BigInt<4>, limb_size: usize) -> Vec<u32> {
         })
         .collect()
 }
+
+#[cfg(any(feature = "fuzzing", test))]
+/// Builds `count` field elements from consecutive 32-byte little-endian chunks of the given bytes (zero padded), reduced modulo the field order
+pub fn fq_elements_from_bytes(bytes: &[u8], count: usize) -> Vec<ark_bn254::Fq> {
+    let mut bytes = bytes.to_vec();
+    bytes.resize(count * 32, 0);
+    bytes
+        .chunks(32)
+        .map(|chunk| ark_bn254::Fq::from(num_bigint::BigUint::from_bytes_le(chunk)))
+        .collect()
+}
@@ -2,6 +2,8 @@ pub mod blake3;
 pub mod blake3_u32;
 pub mod blake3_u4;
 pub mod blake3_u4_compact;
+pub mod ripemd160;
 pub mod sha256;
 pub mod sha256_u4;
 pub mod sha256_u4_stack;
+pub mod sha512;
@@ -0,0 +1,312 @@
+use crate::hash::sha256::{push_reverse_bytes_to_alt, sha256, u32_not};
+use crate::treepp::{script, Script};
+use crate::u32::{
+    u32_add::u32_add_drop,
+    u32_and::u32_and,
+    u32_rrot::u32_rrot,
+    u32_std::{u32_drop, u32_fromaltstack, u32_pick, u32_push, u32_roll, u32_toaltstack},
+    u32_xor::{u32_xor, u8_drop_xor_table, u8_push_xor_table},
+};
+
+/// Message word selection of the left and right lines
+const R_LEFT: [u32; 80] = [
+    0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15,
+    7, 4, 13, 1, 10, 6, 15, 3, 12, 0, 9, 5, 2, 14, 11, 8,
+    3, 10, 14, 4, 9, 15, 8, 1, 2, 7, 0, 6, 13, 11, 5, 12,
+    1, 9, 11, 10, 0, 8, 12, 4, 13, 3, 7, 15, 14, 5, 6, 2,
+    4, 0, 5, 9, 7, 12, 2, 10, 14, 1, 3, 8, 11, 6, 15, 13,
+];
+
+const R_RIGHT: [u32; 80] = [
+    5, 14, 7, 0, 9, 2, 11, 4, 13, 6, 15, 8, 1, 10, 3, 12,
+    6, 11, 3, 7, 0, 13, 5, 10, 14, 15, 8, 12, 4, 9, 1, 2,
+    15, 5, 1, 3, 7, 14, 6, 9, 11, 8, 12, 2, 10, 0, 4, 13,
+    8, 6, 4, 1, 3, 11, 15, 0, 5, 12, 2, 13, 9, 7, 10, 14,
+    12, 15, 10, 4, 1, 5, 8, 7, 6, 2, 13, 14, 0, 3, 9, 11,
+];
+
+/// Rotation amounts of the left and right lines
+const S_LEFT: [usize; 80] = [
+    11, 14, 15, 12, 5, 8, 7, 9, 11, 13, 14, 15, 6, 7, 9, 8,
+    7, 6, 8, 13, 11, 9, 7, 15, 7, 12, 15, 9, 11, 7, 13, 12,
+    11, 13, 6, 7, 14, 9, 13, 15, 14, 8, 13, 6, 5, 12, 7, 5,
+    11, 12, 14, 15, 14, 15, 9, 8, 9, 14, 5, 6, 8, 6, 5, 12,
+    9, 15, 5, 11, 6, 8, 13, 12, 5, 12, 13, 14, 11, 8, 5, 6,
+];
+
+const S_RIGHT: [usize; 80] = [
+    8, 9, 9, 11, 13, 15, 15, 5, 7, 7, 8, 11, 14, 14, 12, 6,
+    9, 13, 15, 7, 12, 8, 9, 11, 7, 7, 12, 7, 6, 15, 13, 11,
+    9, 7, 15, 11, 8, 6, 6, 14, 12, 13, 5, 14, 13, 13, 7, 5,
+    15, 5, 8, 11, 14, 14, 6, 14, 6, 9, 12, 9, 12, 5, 15, 8,
+    8, 5, 12, 9, 12, 5, 14, 6, 8, 13, 6, 5, 15, 13, 11, 11,
+];
+
+const K_LEFT: [u32; 5] = [0x00000000, 0x5a827999, 0x6ed9eba1, 0x8f1bbcdc, 0xa953fd4e];
+
+const K_RIGHT: [u32; 5] = [0x50a28be6, 0x5c4dd124, 0x6d703ef3, 0x7a6d76e9, 0x00000000];
+
+const INITSTATE: [u32; 5] = [0x67452301, 0xefcdab89, 0x98badcfe, 0x10325476, 0xc3d2e1f0];
+
+/// Stack size (as expected by u32_xor and u32_and) at the start of a step: the state, the message
+/// words and the working variables are the 26 u32 elements above the xor table
+const STEP_STACK_SIZE: u32 = 1 + 5 + 16 + 5;
+
+/// Rolls combining the results of the two lines into the new state, see ripemd160_transform
+const COMBINE_ROLLS: [[u32; 3]; 5] = [[13, 3, 6], [11, 3, 5], [9, 3, 7], [7, 5, 6], [6, 5, 5]];
+
+/// ripemd160 takes indefinite length input on the top of stack (first byte on top, as pushed by
+/// push_bytes_hex) and returns the 160 bit (20 byte) digest in the same order
+pub fn ripemd160(num_bytes: usize) -> Script {
+    assert!(num_bytes < 512);
+    let padding = ripemd160_padding(num_bytes);
+    let num_blocks = (num_bytes + padding.len()) / 64;
+
+    script! {
+        // the padding goes to the altstack first to end up behind the input
+        for byte in padding.iter() {
+            { *byte }
+        }
+        for _ in 0..padding.len() {
+            OP_TOALTSTACK
+        }
+        {push_reverse_bytes_to_alt(num_bytes)}
+
+        {u8_push_xor_table()}
+        {ripemd160_init()}
+        // top of stack: [state[0-4]]
+        for _ in 0..num_blocks {
+            // the next 64 bytes are the 16 little endian u32 words of the block
+            for _ in 0..16 {
+                OP_FROMALTSTACK
+                OP_FROMALTSTACK
+                OP_FROMALTSTACK
+                OP_FROMALTSTACK
+                OP_SWAP
+                OP_2SWAP
+                OP_SWAP
+            }
+            {ripemd160_transform()}
+        }
+
+        // the digest is the little endian state, i.e. state[4] first and state[0] on top
+        for i in 1..5 {
+            {u32_roll(i)}
+        }
+        for _ in 0..5 {
+            {u32_toaltstack()}
+        }
+        {u8_drop_xor_table()}
+        for _ in 0..5 {
+            {u32_fromaltstack()}
+        }
+    }
+}
+
+/// HASH160 (ripemd160 of sha256) of the num_bytes input on the top of stack, as used for p2pkh and p2wpkh
+pub fn hash160(num_bytes: usize) -> Script {
+    script! {
+        {sha256(num_bytes)}
+        {ripemd160(32)}
+    }
+}
+
+/// Padding bytes of a num_bytes message: 0x80, zeros up to 56 modulo 64 and the bit length as little endian u64
+pub fn ripemd160_padding(num_bytes: usize) -> Vec<u8> {
+    let zeros = (55 + 64 - num_bytes % 64) % 64;
+    let mut padding = vec![0x80];
+    padding.extend(vec![0; zeros]);
+    padding.extend((num_bytes as u64 * 8).to_le_bytes());
+    padding
+}
+
+/// push all init state into stack, state[0] being the deepest
+pub fn ripemd160_init() -> Script {
+    script! {
+        for x in INITSTATE {
+            {u32_push(x)}
+        }
+    }
+}
+
+/// ripemd160 transform
+/// input: [state[0], ..., state[4], x[0], ..., x[15]]
+/// output: [state[0], ..., state[4]]
+pub fn ripemd160_transform() -> Script {
+    script! {
+        {ripemd160_line(false)}
+        for _ in 0..5 {
+            {u32_toaltstack()}
+        }
+        {ripemd160_line(true)}
+        for _ in 0..5 {
+            {u32_toaltstack()}
+        }
+
+        // drop the message words
+        for _ in 0..16 {
+            {u32_drop()}
+        }
+        for _ in 0..10 {
+            {u32_fromaltstack()}
+        }
+
+        // [state[0-4], ar, br, cr, dr, er, al, bl, cl, dl, el] ->
+        // [state[1] + cl + dr, state[2] + dl + er, state[3] + el + ar, state[4] + al + br, state[0] + bl + cr]
+        for rolls in COMBINE_ROLLS {
+            {u32_roll(rolls[0])}
+            {u32_roll(rolls[1])}
+            {u32_add_drop(1, 0)}
+            {u32_roll(rolls[2])}
+            {u32_add_drop(1, 0)}
+        }
+    }
+}
+
+/// The 80 steps of the left or right line on working variables [a, b, c, d, e] copied from the state
+fn ripemd160_line(right: bool) -> Script {
+    let (r, s, k) = if right {
+        (R_RIGHT, S_RIGHT, K_RIGHT)
+    } else {
+        (R_LEFT, S_LEFT, K_LEFT)
+    };
+    // the right line goes through the boolean functions in reverse order
+    let round = |j: usize| if right { 4 - j / 16 } else { j / 16 };
+
+    script! {
+        for _ in 0..5 {
+            {u32_pick(20)}
+        }
+
+        for j in 0..80 {
+            // t = rotl(a + f(b, c, d) + x[r[j]] + k[j / 16], s[j]) + e
+            {ripemd160_f(round(j), 3, 2, 1, STEP_STACK_SIZE)}
+            {u32_roll(5)}
+            {u32_add_drop(1, 0)}
+
+            {u32_pick(20 - r[j])}
+            {u32_add_drop(1, 0)}
+
+            if k[j / 16] != 0 {
+                {u32_push(k[j / 16])}
+                {u32_add_drop(1, 0)}
+            }
+
+            {u32_rrot(32 - s[j])}
+            {u32_pick(1)}
+            {u32_add_drop(1, 0)}
+
+            // [b, c, d, e, t] -> [e, t, b, rotl(c, 10), d]
+            {u32_roll(4)}
+            {u32_roll(4)}
+            {u32_rrot(22)}
+            {u32_roll(4)}
+        }
+    }
+}
+
+/// Push the boolean function of the given round on the x-th, y-th and z-th u32 elements
+pub fn ripemd160_f(round: usize, x: u32, y: u32, z: u32, stack_size: u32) -> Script {
+    match round {
+        // x ^ y ^ z
+        0 => script! {
+            {u32_pick(x)}
+            {u32_xor(y + 1, 0, stack_size + 1)}
+            {u32_xor(z + 1, 0, stack_size + 1)}
+        },
+        // (x & y) | (~x & z), computed as z ^ (x & (y ^ z))
+        1 => script! {
+            {u32_pick(z)}
+            {u32_xor(y + 1, 0, stack_size + 1)}
+            {u32_and(x + 1, 0, stack_size + 1)}
+            {u32_xor(z + 1, 0, stack_size + 1)}
+        },
+        // (x | ~y) ^ z, computed as ~(~x & y) ^ z
+        2 => script! {
+            {u32_pick(x)}
+            {u32_not()}
+            {u32_and(y + 1, 0, stack_size + 1)}
+            {u32_not()}
+            {u32_xor(z + 1, 0, stack_size + 1)}
+        },
+        // (x & z) | (y & ~z), computed as y ^ (z & (x ^ y))
+        3 => script! {
+            {u32_pick(y)}
+            {u32_xor(x + 1, 0, stack_size + 1)}
+            {u32_and(z + 1, 0, stack_size + 1)}
+            {u32_xor(y + 1, 0, stack_size + 1)}
+        },
+        // x ^ (y | ~z), computed as x ^ ~(~y & z)
+        4 => script! {
+            {u32_pick(y)}
+            {u32_not()}
+            {u32_and(z + 1, 0, stack_size + 1)}
+            {u32_not()}
+            {u32_xor(x + 1, 0, stack_size + 1)}
+        },
+        _ => unreachable!("ripemd160 has five rounds"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::hash::blake3::push_bytes_hex;
+    use crate::treepp::execute_script;
+    use bitcoin::hashes::{hash160, ripemd160, Hash};
+
+    fn check_digest(input: &[u8], hash_script: Script, expected: &[u8]) {
+        let script = script! {
+            {push_bytes_hex(&hex::encode(input))}
+            {hash_script}
+            {push_bytes_hex(&hex::encode(expected))}
+            for i in (1..=expected.len()).rev() {
+                {i}
+                OP_ROLL
+                OP_EQUALVERIFY
+            }
+            OP_TRUE
+        };
+        let res = execute_script(script);
+        assert!(res.success);
+    }
+
+    fn check_ripemd160(input: &[u8]) {
+        let expected = ripemd160::Hash::hash(input).to_byte_array();
+        check_digest(input, ripemd160(input.len()), &expected);
+    }
+
+    #[test]
+    fn test_ripemd160_script_sizes() {
+        println!("ripemd160 transform: {} bytes", ripemd160_transform().len());
+        for num_bytes in [0, 32, 55, 56, 119, 120, 511] {
+            println!("ripemd160({}): {} bytes", num_bytes, ripemd160(num_bytes).len());
+        }
+        println!("hash160(33): {} bytes", hash160(33).len());
+    }
+
+    #[test]
+    fn test_ripemd160() {
+        check_ripemd160(b"");
+        check_ripemd160(b"abc");
+        check_ripemd160(b"message digest");
+    }
+
+    #[test]
+    fn test_ripemd160_multiple_blocks() {
+        // around the padding boundary of a single block
+        check_ripemd160(&[0xab; 55]);
+        check_ripemd160(&[0xab; 56]);
+        check_ripemd160(&(0..=255).collect::<Vec<u8>>()[..130]);
+    }
+
+    #[test]
+    fn test_hash160() {
+        // a compressed public key, as committed to by p2pkh outputs
+        let pubkey =
+            hex::decode("0279be667ef9dcbbac55a06295ce870b07029bfcdb2dce28d959f2815b16f81798")
+                .unwrap();
+        let expected = hash160::Hash::hash(&pubkey).to_byte_array();
+        check_digest(&pubkey, hash160(pubkey.len()), &expected);
+    }
+}
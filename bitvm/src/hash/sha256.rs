@@ -911,6 +911,38 @@ pub fn maj(x: u32, y: u32, z: u32, stack_depth: u32) -> Script {
     }
 }
 
+#[cfg(any(feature = "fuzzing", test))]
+//verifies that the hash of the input byte slice matches with the sha2 implementation.
+pub fn test_sha256_givenbyteslice(input_bytes: &[u8]) {
+    use crate::hash::blake3::push_bytes_hex;
+    use crate::treepp::execute_script;
+    use sha2::{Digest, Sha256};
+
+    let expected_hash = Sha256::digest(input_bytes);
+
+    let script = script! {
+        {push_bytes_hex(&hex::encode(input_bytes))}
+        {sha256(input_bytes.len())}
+        {push_bytes_hex(&hex::encode(expected_hash))}
+        for _ in 0..32 {
+            OP_TOALTSTACK
+        }
+
+        for i in 1..32 {
+            {i}
+            OP_ROLL
+        }
+
+        for _ in 0..32 {
+            OP_FROMALTSTACK
+            OP_EQUALVERIFY
+        }
+        OP_TRUE
+    };
+    let res = execute_script(script);
+    assert!(res.success);
+}
+
 #[cfg(test)]
 mod tests {
     use crate::hash::blake3::push_bytes_hex;
@@ -1210,4 +1242,12 @@ mod tests {
         let res = execute_script(script);
         assert!(res.success);
     }
+
+    #[test]
+    fn test_sha256_against_sha2() {
+        // around the padding boundary of a single block
+        test_sha256_givenbyteslice(&[0xab; 55]);
+        test_sha256_givenbyteslice(&[0xab; 56]);
+        test_sha256_givenbyteslice(b"abc");
+    }
 }
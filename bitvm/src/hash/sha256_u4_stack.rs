@@ -732,6 +732,39 @@ pub fn sha256_stack(
     stack.get_script()
 }
 
+#[cfg(any(feature = "fuzzing", test))]
+//verifies that the hash of the input byte slice matches with the sha2 implementation.
+pub fn test_sha256_stack_givenbyteslice(input_bytes: &[u8], use_add_table: bool, use_full_xor: bool) {
+    use crate::execute_script;
+    use bitcoin_script::Script as StructuredScript;
+    use sha2::{Digest, Sha256};
+
+    let push_nibbles = |bytes: &[u8]| {
+        script! {
+            for byte in bytes {
+                { byte >> 4 }
+                { byte & 0xf }
+            }
+        }
+    };
+
+    let expected_hash = Sha256::digest(input_bytes);
+
+    let mut stack = StackTracker::new();
+    stack.custom(push_nibbles(input_bytes), 0, false, 0, "message");
+    sha256_stack(&mut stack, input_bytes.len() as u32, use_add_table, use_full_xor);
+
+    stack.to_altstack_count(64);
+    let mut expected = stack.var(64, push_nibbles(&expected_hash), "expected");
+    let mut result = stack.from_altstack_joined(64, "res");
+    stack.op_true();
+    stack.equals(&mut result, true, &mut expected, true);
+    assert!(stack.run().success);
+
+    let res = execute_script(StructuredScript::new("").push_script(stack.get_script().compile()));
+    assert!(res.success);
+}
+
 #[cfg(test)]
 mod tests {
     use bitcoin_script::Script as StructuredScript;
@@ -784,44 +817,7 @@ mod tests {
         }
     }
     fn test_sha256(hex_in: &str, use_add_table: bool, use_full_xor: bool) {
-        let mut hasher = Sha256::new();
-        let data = hex::decode(hex_in).unwrap();
-        hasher.update(&data);
-        let result = hasher.finalize();
-        let res = hex::encode(result);
-        println!("Result: {}", res);
-
-        let mut stack = StackTracker::new();
-        stack.custom(
-            script! {
-                {u4_hex_to_nibbles(hex_in)}
-            },
-            0,
-            false,
-            0,
-            "message",
-        );
-        // stack.custom(y, 0, false, 0, "message");
-        let s = sha256_stack(
-            &mut stack,
-            hex_in.len() as u32 / 2,
-            use_add_table,
-            use_full_xor,
-        );
-        println!("script len{}", s.len());
-
-        stack.to_altstack_count(64);
-        let mut expected = stack.var(64, u4_hex_to_nibbles(res.as_str()), "expected");
-        let mut result = stack.from_altstack_joined(64, "res");
-        stack.debug();
-        stack.op_true();
-        stack.equals(&mut result, true, &mut expected, true);
-        let res = stack.run();
-        assert!(res.success);
-        let s = stack.get_script();
-        println!("{}", s.len());
-        let res = execute_script(StructuredScript::new("").push_script(s.compile()));
-        assert!(res.success);
+        test_sha256_stack_givenbyteslice(&hex::decode(hex_in).unwrap(), use_add_table, use_full_xor);
     }
     #[test]
     fn foostack80() {
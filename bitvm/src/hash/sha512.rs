@@ -0,0 +1,310 @@
+use crate::hash::sha256::push_reverse_bytes_to_alt;
+use crate::treepp::{script, Script};
+use crate::u32::u32_xor::{u8_drop_xor_table, u8_push_xor_table};
+use crate::u64::{
+    u64_add::u64_add_drop,
+    u64_and::u64_and,
+    u64_rrot::u64_rrot,
+    u64_shift::u64_shr,
+    u64_std::{u64_drop, u64_dup, u64_fromaltstack, u64_pick, u64_push, u64_roll, u64_toaltstack},
+    u64_xor::u64_xor,
+};
+
+const K: [u64; 80] = [
+    0x428a2f98d728ae22, 0x7137449123ef65cd, 0xb5c0fbcfec4d3b2f, 0xe9b5dba58189dbbc,
+    0x3956c25bf348b538, 0x59f111f1b605d019, 0x923f82a4af194f9b, 0xab1c5ed5da6d8118,
+    0xd807aa98a3030242, 0x12835b0145706fbe, 0x243185be4ee4b28c, 0x550c7dc3d5ffb4e2,
+    0x72be5d74f27b896f, 0x80deb1fe3b1696b1, 0x9bdc06a725c71235, 0xc19bf174cf692694,
+    0xe49b69c19ef14ad2, 0xefbe4786384f25e3, 0x0fc19dc68b8cd5b5, 0x240ca1cc77ac9c65,
+    0x2de92c6f592b0275, 0x4a7484aa6ea6e483, 0x5cb0a9dcbd41fbd4, 0x76f988da831153b5,
+    0x983e5152ee66dfab, 0xa831c66d2db43210, 0xb00327c898fb213f, 0xbf597fc7beef0ee4,
+    0xc6e00bf33da88fc2, 0xd5a79147930aa725, 0x06ca6351e003826f, 0x142929670a0e6e70,
+    0x27b70a8546d22ffc, 0x2e1b21385c26c926, 0x4d2c6dfc5ac42aed, 0x53380d139d95b3df,
+    0x650a73548baf63de, 0x766a0abb3c77b2a8, 0x81c2c92e47edaee6, 0x92722c851482353b,
+    0xa2bfe8a14cf10364, 0xa81a664bbc423001, 0xc24b8b70d0f89791, 0xc76c51a30654be30,
+    0xd192e819d6ef5218, 0xd69906245565a910, 0xf40e35855771202a, 0x106aa07032bbd1b8,
+    0x19a4c116b8d2d0c8, 0x1e376c085141ab53, 0x2748774cdf8eeb99, 0x34b0bcb5e19b48a8,
+    0x391c0cb3c5c95a63, 0x4ed8aa4ae3418acb, 0x5b9cca4f7763e373, 0x682e6ff3d6b2b8a3,
+    0x748f82ee5defb2fc, 0x78a5636f43172f60, 0x84c87814a1f0ab72, 0x8cc702081a6439ec,
+    0x90befffa23631e28, 0xa4506cebde82bde9, 0xbef9a3f7b2c67915, 0xc67178f2e372532b,
+    0xca273eceea26619c, 0xd186b8c721c0c207, 0xeada7dd6cde0eb1e, 0xf57d4f7fee6ed178,
+    0x06f067aa72176fba, 0x0a637dc5a2c898a6, 0x113f9804bef90dae, 0x1b710b35131c471b,
+    0x28db77f523047d84, 0x32caab7b40c72493, 0x3c9ebe0a15c9bebc, 0x431d67c49c100d4c,
+    0x4cc5d4becb3e42b6, 0x597f299cfc657e2a, 0x5fcb6fab3ad6faec, 0x6c44198c4a475817,
+];
+
+const INITSTATE: [u64; 8] = [
+    0x6a09e667f3bcc908, 0xbb67ae8584caa73b, 0x3c6ef372fe94f82b, 0xa54ff53a5f1d36f1,
+    0x510e527fade682d1, 0x9b05688c2b3e6c1f, 0x1f83d9abfb41bd6b, 0x5be0cd19137e2179,
+];
+
+/// Stack size (as expected by u64_xor and u64_and) at the start of a round: the state, the message
+/// schedule window and the working variables are the 32 u64 elements above the xor table
+const ROUND_STACK_SIZE: u32 = 1 + 8 + 16 + 8;
+
+/// The message and its padding are kept on the altstack next to the state and the schedule,
+/// which limits the input to three blocks under the 1000 element stack limit
+const MAX_BLOCKS: usize = 3;
+
+/// sha512 takes indefinite length input on the top of stack (first byte on top, as pushed by
+/// push_bytes_hex) and returns the 512 bit (64 byte) digest in the same order
+pub fn sha512(num_bytes: usize) -> Script {
+    let padding = sha512_padding(num_bytes);
+    let num_blocks = (num_bytes + padding.len()) / 128;
+    assert!(num_blocks <= MAX_BLOCKS, "sha512 input of {} bytes is too long", num_bytes);
+
+    script! {
+        // the padding goes to the altstack first to end up behind the input
+        for byte in padding.iter() {
+            { *byte }
+        }
+        for _ in 0..padding.len() {
+            OP_TOALTSTACK
+        }
+        {push_reverse_bytes_to_alt(num_bytes)}
+
+        {u8_push_xor_table()}
+        {sha512_init()}
+        // top of stack: [state[0-7]]
+        for _ in 0..num_blocks {
+            // the next 128 bytes are the 16 big endian u64 words of the block
+            for _ in 0..128 {
+                OP_FROMALTSTACK
+            }
+            {sha512_transform()}
+        }
+
+        // reverse the digest to the input byte order
+        {push_reverse_bytes_to_alt(64)}
+        {u8_drop_xor_table()}
+        for _ in 0..64 {
+            OP_FROMALTSTACK
+        }
+    }
+}
+
+/// Padding bytes of a num_bytes message: 0x80, zeros up to 112 modulo 128 and the bit length as big endian u128
+pub fn sha512_padding(num_bytes: usize) -> Vec<u8> {
+    let zeros = (111 + 128 - num_bytes % 128) % 128;
+    let mut padding = vec![0x80];
+    padding.extend(vec![0; zeros]);
+    padding.extend((num_bytes as u128 * 8).to_be_bytes());
+    padding
+}
+
+/// push all init state into stack, state[0] being the deepest
+pub fn sha512_init() -> Script {
+    script! {
+        for x in INITSTATE {
+            {u64_push(x)}
+        }
+    }
+}
+
+/// sha512 transform
+/// input: [state[0], ..., state[7], m[0], ..., m[15]]
+/// output: [state[0], ..., state[7]]
+pub fn sha512_transform() -> Script {
+    let m_depth = |t: usize| if t < 16 { 24 - t as u32 } else { 9 };
+
+    script! {
+        // copy the state as the working variables [h, g, f, e, d, c, b, a]
+        for i in 0..8 {
+            {u64_pick(16 + 2 * i)}
+        }
+
+        for t in 0..80 {
+            if t >= 16 {
+                // the schedule only keeps the last 16 words, m[t-1] being right below the working variables
+                // m[t] = sig1(m[t-2]) + m[t-7] + sig0(m[t-15]) + m[t-16]
+                {u64_pick(9)}
+                {small_sigma(19, 61, 6, ROUND_STACK_SIZE + 1)}
+
+                {u64_pick(15)}
+                {u64_add_drop(1, 0)}
+
+                {u64_pick(23)}
+                {small_sigma(1, 8, 7, ROUND_STACK_SIZE + 2)}
+                {u64_add_drop(1, 0)}
+
+                {u64_roll(24)}
+                {u64_add_drop(1, 0)}
+
+                // put the working variables back on top of m[t]
+                for _ in 0..8 {
+                    {u64_roll(8)}
+                }
+            }
+
+            // t1 = h + ep1(e) + ch(e, f, g) + k[t] + m[t]
+            {u64_pick(4)}
+            {big_sigma(14, 18, 41, ROUND_STACK_SIZE + 1)}
+            {ch(5, 6, 7, ROUND_STACK_SIZE + 1)}
+            {u64_add_drop(1, 0)}
+
+            {u64_pick(8)}
+            {u64_add_drop(1, 0)}
+
+            {u64_push(K[t])}
+            {u64_add_drop(1, 0)}
+
+            {u64_pick(m_depth(t))}
+            {u64_add_drop(1, 0)}
+
+            // t2 = ep0(a) + maj(a, b, c)
+            {u64_pick(1)}
+            {big_sigma(28, 34, 39, ROUND_STACK_SIZE + 2)}
+            {maj(2, 3, 4, ROUND_STACK_SIZE + 2)}
+            {u64_add_drop(1, 0)}
+
+            // [h, g, f, e, d, c, b, a, t1, t2] -> [g, f, e, d + t1, c, b, a, t1 + t2]
+            {u64_roll(9)}
+            {u64_drop()}
+            {u64_toaltstack()}
+            {u64_dup()}
+            {u64_toaltstack()}
+            {u64_roll(4)}
+            {u64_add_drop(1, 0)}
+            for _ in 0..3 {
+                {u64_roll(3)}
+            }
+            {u64_fromaltstack()}
+            {u64_fromaltstack()}
+            {u64_add_drop(1, 0)}
+        }
+
+        // drop the message schedule
+        for _ in 0..8 {
+            {u64_toaltstack()}
+        }
+        for _ in 0..16 {
+            {u64_drop()}
+        }
+
+        // add the working variables to the old state, which gets reversed in the process
+        for i in 0..8 {
+            {u64_fromaltstack()}
+            {u64_roll(i + 1)}
+            {u64_add_drop(1, 0)}
+        }
+        for i in 1..8 {
+            {u64_roll(i)}
+        }
+    }
+}
+
+/// Change top element x to ROTRIGHT(x,r1) ^ ROTRIGHT(x,r2) ^ ROTRIGHT(x,r3)
+pub fn big_sigma(r1: usize, r2: usize, r3: usize, stack_size: u32) -> Script {
+    script! {
+        {u64_dup()}
+        {u64_rrot(r1)}
+        {u64_pick(1)}
+        {u64_rrot(r2)}
+        {u64_xor(1, 0, stack_size + 2)}
+        {u64_roll(2)}
+        {u64_rrot(r3)}
+        {u64_xor(1, 0, stack_size + 2)}
+
+        // clean stack
+        {u64_toaltstack()}
+        {u64_drop()}
+        {u64_drop()}
+        {u64_fromaltstack()}
+    }
+}
+
+/// Change top element x to ROTRIGHT(x,r1) ^ ROTRIGHT(x,r2) ^ ((x) >> s)
+pub fn small_sigma(r1: usize, r2: usize, s: usize, stack_size: u32) -> Script {
+    script! {
+        {u64_dup()}
+        {u64_rrot(r1)}
+        {u64_pick(1)}
+        {u64_rrot(r2)}
+        {u64_xor(1, 0, stack_size + 2)}
+        {u64_roll(2)}
+        {u64_shr(s)}
+        {u64_xor(1, 0, stack_size + 2)}
+
+        // clean stack
+        {u64_toaltstack()}
+        {u64_drop()}
+        {u64_drop()}
+        {u64_fromaltstack()}
+    }
+}
+
+/// Push ((x) & (y)) ^ (~(x) & (z)), computed as z ^ (x & (y ^ z))
+pub fn ch(x: u32, y: u32, z: u32, stack_size: u32) -> Script {
+    script! {
+        {u64_pick(z)}
+        {u64_xor(y + 1, 0, stack_size + 1)}
+        {u64_and(x + 1, 0, stack_size + 1)}
+        {u64_xor(z + 1, 0, stack_size + 1)}
+    }
+}
+
+/// Push ((x) & (y)) ^ ((x) & (z)) ^ ((y) & (z)), computed as (x & y) + (z & (x ^ y)) since the two terms share no bits
+pub fn maj(x: u32, y: u32, z: u32, stack_size: u32) -> Script {
+    script! {
+        {u64_pick(x)}
+        {u64_xor(y + 1, 0, stack_size + 1)}
+        {u64_and(z + 1, 0, stack_size + 1)}
+
+        {u64_pick(x + 1)}
+        {u64_and(y + 2, 0, stack_size + 2)}
+        {u64_add_drop(1, 0)}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::hash::blake3::push_bytes_hex;
+    use crate::treepp::execute_script;
+    use bitcoin::hashes::{sha512, Hash};
+
+    fn check_sha512(input: &[u8]) {
+        let expected = sha512::Hash::hash(input).to_byte_array();
+        let script = script! {
+            {push_bytes_hex(&hex::encode(input))}
+            {sha512(input.len())}
+            {push_bytes_hex(&hex::encode(expected))}
+            for i in (1..=64).rev() {
+                {i}
+                OP_ROLL
+                OP_EQUALVERIFY
+            }
+            OP_TRUE
+        };
+        let res = execute_script(script);
+        assert!(res.success);
+    }
+
+    #[test]
+    fn test_sha512_script_sizes() {
+        println!("sha512 transform: {} bytes", sha512_transform().len());
+        for num_bytes in [0, 64, 111, 112, 239, 240, 367] {
+            println!("sha512({}): {} bytes", num_bytes, sha512(num_bytes).len());
+        }
+    }
+
+    #[test]
+    fn test_sha512() {
+        check_sha512(b"");
+        check_sha512(b"abc");
+    }
+
+    #[test]
+    fn test_sha512_multiple_blocks() {
+        // around the padding boundary of a single block
+        check_sha512(&[0xab; 111]);
+        check_sha512(&[0xab; 112]);
+        check_sha512(&(0..=255).collect::<Vec<u8>>()[..200]);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_sha512_too_long() {
+        sha512(368);
+    }
+}
@@ -27,6 +27,7 @@ pub mod pseudo;
 pub mod signatures;
 pub mod u32;
 pub mod u4;
+pub mod u64;
 
 /// A wrapper for the stack types to print them better.
 pub struct FmtStack(pub Stack);
@@ -27,11 +27,17 @@ impl WinternitzSecret {
         let mut rng = rand::rngs::OsRng;
         rand::RngCore::fill_bytes(&mut rng, &mut buffer);
 
+        Self::from_bytes(&buffer, message_size)
+    }
+
+    /// Create a secret from caller provided 160 bit key material, e.g. the output of a key
+    /// derivation function. The same bytes always yield the same secret.
+    pub fn from_bytes(secret_bytes: &[u8; 20], message_size: usize) -> Self {
         // Best parameters depend on the stack depth, without that limitation best option is LOG_D = 4 and used Winternitz version here
         //let parameters = WINTERNITZ_HASH_PARAMETERS;
         let parameters = Parameters::new_by_bit_length(message_size as u32 * 8, LOG_D);
         WinternitzSecret {
-            secret_key: hex::encode(buffer).into(),
+            secret_key: hex::encode(secret_bytes).into(),
             parameters,
         }
     }
@@ -183,6 +189,18 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_winternitz_secret_from_bytes_is_deterministic() {
+        let secret_bytes = [7u8; 20];
+        let secret = WinternitzSecret::from_bytes(&secret_bytes, 4);
+        let same_secret = WinternitzSecret::from_bytes(&secret_bytes, 4);
+        let other_secret = WinternitzSecret::from_bytes(&[8u8; 20], 4);
+
+        assert!(secret == same_secret);
+        assert!(secret != other_secret);
+        assert_eq!(secret.secret_key, hex::encode(secret_bytes).into_bytes());
+    }
+
     #[test]
     fn test_winternitz_public_key_from_secret() {
         let secret = WinternitzSecret::new(BLAKE3_HASH_LENGTH);
@@ -57,8 +57,18 @@ impl Parameters {
         (self.message_length * self.block_length + 7) / 8
     }
 
+    /// Number of blocks of the actual message
+    pub const fn message_length(&self) -> u32 {
+        self.message_length
+    }
+
+    /// Number of blocks of the checksum part
+    pub const fn checksum_length(&self) -> u32 {
+        self.checksum_length
+    }
+
     /// Total number of blocks, i.e. sum of the number of blocks in the actual message and the checksum
-    pub fn total_length(&self) -> u32 {
+    pub const fn total_length(&self) -> u32 {
         self.message_length + self.checksum_length
     }
 }
@@ -472,6 +482,57 @@ impl Converter for ToBytesConverter {
     }
 }
 
+#[cfg(any(feature = "fuzzing", test))]
+/// Signs the message with every verifier and converter combination and checks that the verification leaves the original message on the stack
+pub fn test_winternitz_givenbytes(secret_key: &[u8], message: &[u8], block_length: u32) {
+    assert!(!message.is_empty(), "Message can not be empty");
+    let ps = Parameters::new_by_bit_length(message.len() as u32 * 8, block_length);
+    let secret_key = secret_key.to_vec();
+    let message = message.to_vec();
+    let public_key = generate_public_key(&ps, &secret_key);
+
+    let mut message_bytes = message.clone();
+    message_bytes.resize(ps.byte_message_length() as usize, 0);
+    let bytes_checker = script! {
+        for i in 0..message_bytes.len() {
+            { message_bytes[i] }
+            if i == message_bytes.len() - 1 {
+                OP_EQUAL
+            } else {
+                OP_EQUALVERIFY
+            }
+        }
+    };
+    let message_digits = bytes_to_u32s(ps.message_length, ps.block_length, &message);
+    let digits_checker = script! {
+        for i in 0..message_digits.len() {
+            { message_digits[i] }
+            if i == message_digits.len() - 1 {
+                OP_EQUAL
+            } else {
+                OP_EQUALVERIFY
+            }
+        }
+    };
+
+    fn check<VERIFIER: Verifier, CONVERTER: Converter>(ps: &Parameters, secret_key: &SecretKey, public_key: &PublicKey, message: &Vec<u8>, message_checker: &Script) {
+        let o = Winternitz::<VERIFIER, CONVERTER>::new();
+        let s = script! {
+            { o.sign(ps, secret_key, message) }
+            { o.checksig_verify(ps, public_key) }
+            { message_checker.clone() }
+        };
+        assert!(execute_script(s).success);
+    }
+
+    check::<ListpickVerifier, VoidConverter>(&ps, &secret_key, &public_key, &message, &digits_checker);
+    check::<BruteforceVerifier, VoidConverter>(&ps, &secret_key, &public_key, &message, &digits_checker);
+    check::<BinarysearchVerifier, VoidConverter>(&ps, &secret_key, &public_key, &message, &digits_checker);
+    check::<ListpickVerifier, ToBytesConverter>(&ps, &secret_key, &public_key, &message, &bytes_checker);
+    check::<BruteforceVerifier, ToBytesConverter>(&ps, &secret_key, &public_key, &message, &bytes_checker);
+    check::<BinarysearchVerifier, ToBytesConverter>(&ps, &secret_key, &public_key, &message, &bytes_checker);
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
@@ -718,4 +779,13 @@ mod test {
             );
         }
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_winternitz_against_message() {
+        let secret_key = hex::decode(SAMPLE_SECRET_KEY).unwrap();
+        for block_length in 4..=8 {
+            test_winternitz_givenbytes(&secret_key, &[0xff; 20], block_length);
+            test_winternitz_givenbytes(&secret_key, &[0x00, 0x01, 0x80], block_length);
+        }
+    }
+}
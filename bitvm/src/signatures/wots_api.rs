@@ -1,5 +1,5 @@
 use paste::paste;
-use crate::treepp::Script;
+use crate::treepp::{script, Script};
 use crate::signatures::{
     winternitz,
     winternitz_hash::{WINTERNITZ_MESSAGE_VERIFIER, WINTERNITZ_MESSAGE_COMPACT_VERIFIER},
@@ -11,7 +11,119 @@ pub trait SignatureImpl {
     fn to_compact_script(self) -> Script;
 }
 
-/// Macro that implements the WOTS module for a given message length (in bytes).
+impl SignatureImpl for Vec<([u8; 20], u8)> {
+    fn to_script(self) -> Script {
+        script! {
+            for (preimage, digit) in self {
+                { preimage.to_vec() }
+                { digit }
+            }
+        }
+    }
+
+    fn to_compact_script(self) -> Script {
+        script! {
+            for (preimage, _) in self {
+                { preimage.to_vec() }
+            }
+        }
+    }
+}
+
+/// WOTS for messages of `MSG_LEN` bytes, signed with digits of `BLOCK_LEN` bits (in the closed range 4, 8).
+/// Larger digits shrink the witness (fewer digits to reveal) at the cost of a larger verification script.
+/// For example:
+/// - `Wots::<32, 4>` is the 256-bit WOTS used by `wots256`.
+/// - `Wots::<32, 8>` halves the number of message digits of the same 256-bit message.
+pub struct Wots<const MSG_LEN: u32, const BLOCK_LEN: u32>;
+
+impl<const MSG_LEN: u32, const BLOCK_LEN: u32> Wots<MSG_LEN, BLOCK_LEN> {
+    /// Winternitz parameters, the checksum length is derived from the message and block length.
+    pub const PARAMETERS: winternitz::Parameters =
+        winternitz::Parameters::new_by_bit_length(MSG_LEN * 8, BLOCK_LEN);
+    /// Number of digits of the message itself.
+    pub const MSG_DIGITS: u32 = Self::PARAMETERS.message_length();
+    /// Number of checksum digits.
+    pub const CHECKSUM_DIGITS: u32 = Self::PARAMETERS.checksum_length();
+    /// Total number of "digits" in the signature.
+    pub const N_DIGITS: u32 = Self::PARAMETERS.total_length();
+
+    fn decode_secret(secret: &str) -> Vec<u8> {
+        match hex::decode(secret) {
+            Ok(bytes) => bytes,
+            Err(_) => panic!("Invalid hex string for secret"),
+        }
+    }
+
+    /// Create a verification script for a WOTS public key, leaving the message digits on the stack (first digit on top).
+    pub fn checksig_verify(public_key: &[[u8; 20]]) -> Script {
+        assert_eq!(public_key.len(), Self::N_DIGITS as usize, "Invalid public key length");
+        WINTERNITZ_MESSAGE_VERIFIER.checksig_verify(&Self::PARAMETERS, &public_key.to_vec())
+    }
+
+    /// Generate a signature for a message using the provided secret.
+    pub fn get_signature(secret: &str, msg_bytes: &[u8]) -> Vec<([u8; 20], u8)> {
+        assert_eq!(msg_bytes.len(), MSG_LEN as usize, "Invalid message length");
+        let sigs = WINTERNITZ_MESSAGE_VERIFIER.sign(
+            &Self::PARAMETERS,
+            &Self::decode_secret(secret),
+            &msg_bytes.to_vec(),
+        );
+        assert_eq!(sigs.len(), 2 * Self::N_DIGITS as usize);
+
+        // Iterate over the signature pieces two at a time.
+        let mut sigs_vec: Vec<([u8; 20], u8)> = Vec::new();
+        for i in (0..sigs.len()).step_by(2) {
+            let preimage: [u8; 20] = if sigs[i].len() == 0 {
+                [0; 20]
+            } else {
+                sigs[i].try_into().unwrap()
+            };
+            let digit_arr: [u8; 1] = if sigs[i + 1].len() == 0 {
+                [0]
+            } else {
+                sigs[i + 1].try_into().unwrap()
+            };
+            sigs_vec.push((preimage, digit_arr[0]));
+        }
+        sigs_vec
+    }
+
+    /// Generate a WOTS public key using the provided secret.
+    pub fn generate_public_key(secret: &str) -> Vec<[u8; 20]> {
+        winternitz::generate_public_key(&Self::PARAMETERS, &Self::decode_secret(secret))
+    }
+
+    /// Create a verification script for the compact WOTS public key.
+    pub fn compact_checksig_verify(public_key: &[[u8; 20]]) -> Script {
+        assert_eq!(public_key.len(), Self::N_DIGITS as usize, "Invalid public key length");
+        WINTERNITZ_MESSAGE_COMPACT_VERIFIER.checksig_verify(&Self::PARAMETERS, &public_key.to_vec())
+    }
+
+    /// Generate a compact signature (just the 20-byte preimages) for a message.
+    pub fn get_compact_signature(secret: &str, msg_bytes: &[u8]) -> Vec<[u8; 20]> {
+        assert_eq!(msg_bytes.len(), MSG_LEN as usize, "Invalid message length");
+        let sigs = WINTERNITZ_MESSAGE_COMPACT_VERIFIER.sign(
+            &Self::PARAMETERS,
+            &Self::decode_secret(secret),
+            &msg_bytes.to_vec(),
+        );
+        assert_eq!(sigs.len(), Self::N_DIGITS as usize);
+
+        let mut sigs_vec: Vec<[u8; 20]> = Vec::new();
+        for i in 0..sigs.len() {
+            let preimage: [u8; 20] = if sigs[i].len() == 0 {
+                [0; 20]
+            } else {
+                sigs[i].try_into().unwrap()
+            };
+            sigs_vec.push(preimage);
+        }
+        sigs_vec
+    }
+}
+
+/// Macro that implements the WOTS module for a given message length (in bytes) with 4-bit digits.
 /// For example:
 /// - For 160-bit WOTS, use 20 bytes.
 /// - For 256-bit WOTS, use 32 bytes.
@@ -22,10 +134,13 @@ macro_rules! impl_wots {
                 use super::*;
                 use bitcoin_script::script;
 
+                /// Generic WOTS instance backing this module.
+                type Instance = Wots<MSG_LEN, 4>;
+
                 /// Message length in bytes.
                 pub const MSG_LEN: u32 = $MSG_LEN;
                 /// Total number of "digits" in the signature.
-                pub const N_DIGITS: u32 = 2 * MSG_LEN + 4; // remove 4 hardcoded value later
+                pub const N_DIGITS: u32 = Instance::N_DIGITS;
 
                 /// Public key is an array of 20-byte arrays.
                 pub type PublicKey = [[u8; 20]; N_DIGITS as usize];
@@ -53,51 +168,17 @@ macro_rules! impl_wots {
 
                 /// Create a verification script for a WOTS public key.
                 pub fn checksig_verify(public_key: PublicKey) -> Script {
-                    let params = winternitz::Parameters::new(MSG_LEN * 2, 4);
-                    WINTERNITZ_MESSAGE_VERIFIER.checksig_verify(&params, &public_key.to_vec())
+                    Instance::checksig_verify(&public_key)
                 }
 
                 /// Generate a signature for a message using the provided secret.
                 pub fn get_signature(secret: &str, msg_bytes: &[u8]) -> Signature {
-                    let secret_key = match hex::decode(secret) {
-                        Ok(bytes) => bytes,
-                        Err(_) => panic!("Invalid hex string for secret"),
-                    };
-
-                    let sigs = WINTERNITZ_MESSAGE_VERIFIER.sign(
-                        &winternitz::Parameters::new(MSG_LEN * 2, 4),
-                        &secret_key,
-                        &msg_bytes.to_vec(),
-                    );
-                    assert_eq!(sigs.len(), 2 * N_DIGITS as usize);
-
-                    // Iterate over the signature pieces two at a time.
-                    let mut sigs_vec: Vec<([u8; 20], u8)> = Vec::new();
-                    for i in (0..sigs.len()).step_by(2) {
-                        let preimage: [u8; 20] = if sigs[i].len() == 0 {
-                            [0; 20]
-                        } else {
-                            sigs[i].try_into().unwrap()
-                        };
-                        let digit_arr: [u8; 1] = if sigs[i + 1].len() == 0 {
-                            [0]
-                        } else {
-                            sigs[i + 1].try_into().unwrap()
-                        };
-                        sigs_vec.push((preimage, digit_arr[0]));
-                    }
-                    sigs_vec.try_into().unwrap()
+                    Instance::get_signature(secret, msg_bytes).try_into().unwrap()
                 }
 
                 /// Generate a WOTS public key using the provided secret.
                 pub fn generate_public_key(secret: &str) -> PublicKey {
-                    let secret_key = match hex::decode(secret) {
-                        Ok(bytes) => bytes,
-                        Err(_) => panic!("Invalid hex string for secret"),
-                    };
-                    let params = winternitz::Parameters::new(MSG_LEN * 2, 4);
-                    let pubkey_vec = winternitz::generate_public_key(&params, &secret_key);
-                    pubkey_vec.try_into().unwrap()
+                    Instance::generate_public_key(secret).try_into().unwrap()
                 }
 
                 /// A sub-module for the compact signature variant.
@@ -109,35 +190,12 @@ macro_rules! impl_wots {
 
                     /// Create a verification script for the compact WOTS public key.
                     pub fn checksig_verify(public_key: PublicKey) -> Script {
-                        let params = winternitz::Parameters::new(MSG_LEN * 2, 4);
-                        WINTERNITZ_MESSAGE_COMPACT_VERIFIER.checksig_verify(&params, &public_key.to_vec())
+                        Instance::compact_checksig_verify(&public_key)
                     }
 
                     /// Generate a compact signature for a message.
                     pub fn get_signature(secret: &str, msg_bytes: &[u8]) -> Signature {
-                        let secret_key = match hex::decode(secret) {
-                            Ok(bytes) => bytes,
-                            Err(_) => panic!("Invalid hex string for secret"),
-                        };
-
-                        let sigs = WINTERNITZ_MESSAGE_COMPACT_VERIFIER.sign(
-                            &winternitz::Parameters::new(MSG_LEN * 2, 4),
-                            &secret_key,
-                            &msg_bytes.to_vec(),
-                        );
-                        assert_eq!(sigs.len(), N_DIGITS as usize);
-
-                        let mut sigs_vec: Vec<[u8; 20]> = Vec::new();
-                        // Iterate over the signature pieces using step_by.
-                        for i in 0..sigs.len() {
-                            let preimage: [u8; 20] = if sigs[i].len() == 0 {
-                                [0; 20]
-                            } else {
-                                sigs[i].try_into().unwrap()
-                            };
-                            sigs_vec.push(preimage);
-                        }
-                        sigs_vec.try_into().unwrap()
+                        Instance::get_compact_signature(secret, msg_bytes).try_into().unwrap()
                     }
                 }
             }
@@ -147,4 +205,111 @@ macro_rules! impl_wots {
 
 // Expand the macro for the two variants.
 impl_wots!(wots160, 20);
-impl_wots!(wots256, 32);
\ No newline at end of file
+impl_wots!(wots256, 32);
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::execute_script;
+    use crate::signatures::utils::bytes_to_u32s;
+    use rand::{Rng, SeedableRng};
+    use rand_chacha::ChaCha20Rng;
+
+    const SAMPLE_SECRET: &str = "b138982ce17ac813d505b5b40b665d404e9528e7";
+
+    /// Signs a random message, verifies the (compact) signature and checks the digits left on the stack.
+    fn sign_and_verify<const MSG_LEN: u32, const BLOCK_LEN: u32>(prng: &mut ChaCha20Rng) {
+        let message: Vec<u8> = (0..MSG_LEN).map(|_| prng.gen()).collect();
+        let digits = bytes_to_u32s(
+            Wots::<MSG_LEN, BLOCK_LEN>::MSG_DIGITS,
+            BLOCK_LEN,
+            &message,
+        );
+        let public_key = Wots::<MSG_LEN, BLOCK_LEN>::generate_public_key(SAMPLE_SECRET);
+        assert_eq!(public_key.len(), Wots::<MSG_LEN, BLOCK_LEN>::N_DIGITS as usize);
+
+        let message_checker = script! {
+            for i in 0..digits.len() {
+                { digits[i] }
+                if i == digits.len() - 1 {
+                    OP_EQUAL
+                } else {
+                    OP_EQUALVERIFY
+                }
+            }
+        };
+
+        let s = script! {
+            { Wots::<MSG_LEN, BLOCK_LEN>::get_signature(SAMPLE_SECRET, &message).to_script() }
+            { Wots::<MSG_LEN, BLOCK_LEN>::checksig_verify(&public_key) }
+            { message_checker.clone() }
+        };
+        println!(
+            "Wots<{}, {}> checksig_verify script size: {} bytes",
+            MSG_LEN,
+            BLOCK_LEN,
+            Wots::<MSG_LEN, BLOCK_LEN>::checksig_verify(&public_key).len()
+        );
+        assert!(execute_script(s).success);
+
+        let s = script! {
+            for preimage in Wots::<MSG_LEN, BLOCK_LEN>::get_compact_signature(SAMPLE_SECRET, &message) {
+                { preimage.to_vec() }
+            }
+            { Wots::<MSG_LEN, BLOCK_LEN>::compact_checksig_verify(&public_key) }
+            { message_checker }
+        };
+        assert!(execute_script(s).success);
+    }
+
+    #[test]
+    fn test_wots_n_digits() {
+        // The checksum digit count comes from the parameters, 4-bit digits need 4 of them for these lengths.
+        assert_eq!(wots160::N_DIGITS, 2 * wots160::MSG_LEN + 4);
+        assert_eq!(wots256::N_DIGITS, 2 * wots256::MSG_LEN + 4);
+        assert_eq!(Wots::<32, 8>::MSG_DIGITS, 32);
+        assert_eq!(Wots::<32, 8>::CHECKSUM_DIGITS, 3);
+        assert_eq!(Wots::<4, 4>::N_DIGITS, 8 + Wots::<4, 4>::CHECKSUM_DIGITS);
+    }
+
+    #[test]
+    fn test_generic_wots() {
+        let mut prng = ChaCha20Rng::seed_from_u64(37);
+        sign_and_verify::<4, 4>(&mut prng);
+        sign_and_verify::<4, 8>(&mut prng);
+        sign_and_verify::<20, 4>(&mut prng);
+        sign_and_verify::<20, 5>(&mut prng);
+        sign_and_verify::<32, 6>(&mut prng);
+        sign_and_verify::<32, 7>(&mut prng);
+        sign_and_verify::<32, 8>(&mut prng);
+    }
+
+    #[test]
+    fn test_wots256_matches_generic() {
+        let message = [0xa5u8; 32];
+        let public_key = wots256::generate_public_key(SAMPLE_SECRET);
+        assert_eq!(public_key.to_vec(), Wots::<32, 4>::generate_public_key(SAMPLE_SECRET));
+
+        let s = script! {
+            { wots256::get_signature(SAMPLE_SECRET, &message).to_script() }
+            { wots256::checksig_verify(public_key) }
+            for _ in 0..wots256::MSG_LEN {
+                OP_2DROP
+            }
+            OP_TRUE
+        };
+        assert!(execute_script(s).success);
+
+        let s = script! {
+            for preimage in wots256::compact::get_signature(SAMPLE_SECRET, &message) {
+                { preimage.to_vec() }
+            }
+            { wots256::compact::checksig_verify(public_key) }
+            for _ in 0..wots256::MSG_LEN {
+                OP_2DROP
+            }
+            OP_TRUE
+        };
+        assert!(execute_script(s).success);
+    }
+}
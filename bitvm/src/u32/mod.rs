@@ -1,6 +1,9 @@
 pub mod u32_add;
 pub mod u32_and;
+pub mod u32_cmp;
+pub mod u32_mul;
 pub mod u32_rrot;
+pub mod u32_shift;
 pub mod u32_std;
 pub mod u32_xor;
 pub mod u32_zip;
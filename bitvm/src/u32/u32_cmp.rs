@@ -0,0 +1,81 @@
+use crate::treepp::*;
+use crate::u32::u32_zip::u32_zip;
+
+/// Compares two numbers given as zipped u8 pairs, the least significant pair being on top and the byte of the first number (the deeper one before zipping) being on top of each pair
+/// Returns 1 if the first number is less than (or equal to, if or_equal is set) the second number, 0 otherwise
+pub fn u8_zipped_lessthan(byte_count: u32, or_equal: bool) -> Script {
+    assert!(byte_count > 0);
+    script! {
+        // Least significant bytes decide only if all the others are equal
+        if or_equal {
+            OP_GREATERTHANOREQUAL
+        } else {
+            OP_GREATERTHAN
+        }
+
+        for _ in 1..byte_count {
+            OP_ROT
+            OP_ROT
+            OP_2DUP
+            OP_EQUAL
+            OP_IF
+                OP_2DROP
+            OP_ELSE
+                OP_GREATERTHAN
+                OP_NIP
+            OP_ENDIF
+        }
+    }
+}
+
+/// Returns 1 if the second to top u32 element is less than the top u32 element, 0 otherwise (in the same order as OP_LESSTHAN)
+pub fn u32_lt() -> Script {
+    script! {
+        {u32_zip(1, 0)}
+        {u8_zipped_lessthan(4, false)}
+    }
+}
+
+/// Returns 1 if the second to top u32 element is less than or equal to the top u32 element, 0 otherwise (in the same order as OP_LESSTHANOREQUAL)
+pub fn u32_lte() -> Script {
+    script! {
+        {u32_zip(1, 0)}
+        {u8_zipped_lessthan(4, true)}
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::u32::u32_std::u32_push;
+    use rand::Rng;
+
+    #[test]
+    fn test_u32_lt() {
+        println!("u32_lt: {} bytes", u32_lt().len());
+        println!("u32_lte: {} bytes", u32_lte().len());
+        let mut rng = rand::thread_rng();
+        for _ in 0..1000 {
+            let x: u32 = rng.gen();
+            // make equal prefixes likely to exercise the lower bytes
+            let y: u32 = match rng.gen_range(0..3) {
+                0 => rng.gen(),
+                1 => x ^ rng.gen_range(0..256),
+                _ => x,
+            };
+            let script = script! {
+                { u32_push(x) }
+                { u32_push(y) }
+                { u32_lt() }
+                { (x < y) as u32 }
+                OP_EQUALVERIFY
+                { u32_push(x) }
+                { u32_push(y) }
+                { u32_lte() }
+                { (x <= y) as u32 }
+                OP_EQUAL
+            };
+            run(script);
+        }
+    }
+}
@@ -0,0 +1,160 @@
+use crate::treepp::*;
+use crate::u32::u32_rrot::u8_extract_1bit;
+
+/// Multiplication of two u8 elements at the top of the stack, the result (less than 2^16) is a single element
+pub fn u8_mul() -> Script {
+    script! {
+        // ⋯ A B
+        0
+        OP_SWAP
+        // ⋯ A 0 B
+        for _ in 0..8 {
+            // Double the accumulator and add A if the next most significant bit of B is set
+            {u8_extract_1bit()}
+            // ⋯ A acc B' bit
+            OP_ROT
+            OP_DUP
+            OP_ADD
+            OP_SWAP
+            // ⋯ A B' 2*acc bit
+            OP_IF
+                2 OP_PICK
+                OP_ADD
+            OP_ENDIF
+            OP_SWAP
+            // ⋯ A acc B'
+        }
+        OP_DROP
+        OP_NIP
+    }
+}
+
+/// Splits the top element (which should be less than 2^max_bits) into its least significant byte and the rest (shifted by 8 bits), the rest being on top
+pub fn u8_split_carry(max_bits: u32) -> Script {
+    assert!((9..32).contains(&max_bits));
+    script! {
+        0
+        OP_SWAP
+        // ⋯ carry X
+        for i in (8..max_bits).rev() {
+            { 1 << i }
+            OP_2DUP
+            OP_GREATERTHANOREQUAL
+            OP_IF
+                OP_SUB
+                OP_SWAP
+                { 1 << (i - 8) }
+                OP_ADD
+                OP_SWAP
+            OP_ELSE
+                OP_DROP
+            OP_ENDIF
+        }
+        OP_SWAP
+    }
+}
+
+/// Multiplication of the top two u32 elements, the 64-bit result is given as two u32 elements, the most significant one being deeper
+/// Calculates the result byte by byte, column k being the sum of the products A_i * B_j with i + j = k plus the carry of column k - 1
+pub fn u32_mul() -> Script {
+    script! {
+        // ⋯ A₃ A₂ A₁ A₀ B₃ B₂ B₁ B₀ carry
+        0
+        for k in 0..7 {
+            for i in 0..4 {
+                if k >= i && k - i < 4 {
+                    { 4 + i + 1 } OP_PICK
+                    { k - i + 2 } OP_PICK
+                    {u8_mul()}
+                    OP_ADD
+                }
+            }
+            // Each column is at most 4 * 255² plus a carry less than 2^11
+            {u8_split_carry(19)}
+            OP_SWAP
+            OP_TOALTSTACK
+        }
+        // The last carry is the most significant byte
+        OP_TOALTSTACK
+        OP_2DROP
+        OP_2DROP
+        OP_2DROP
+        OP_2DROP
+        for _ in 0..8 {
+            OP_FROMALTSTACK
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::u32::u32_std::{u32_equal, u32_equalverify, u32_push};
+    use rand::Rng;
+
+    #[test]
+    fn test_u8_mul_exhaustive() {
+        println!("u8_mul: {} bytes", u8_mul().len());
+        for a in 0..256 {
+            for b in 0..256 {
+                let script = script! {
+                    { a }
+                    { b }
+                    { u8_mul() }
+                    { a * b }
+                    OP_EQUAL
+                };
+                run(script);
+            }
+        }
+    }
+
+    #[test]
+    fn test_u8_split_carry() {
+        let mut rng = rand::thread_rng();
+        for _ in 0..1000 {
+            let x: u32 = rng.gen_range(0..1 << 19);
+            let script = script! {
+                { x }
+                { u8_split_carry(19) }
+                { x >> 8 }
+                OP_EQUALVERIFY
+                { x & 0xff }
+                OP_EQUAL
+            };
+            run(script);
+        }
+    }
+
+    #[test]
+    fn test_u32_mul() {
+        println!("u32_mul: {} bytes", u32_mul().len());
+        let mut rng = rand::thread_rng();
+        for _ in 0..100 {
+            let x: u32 = rng.gen();
+            let y: u32 = rng.gen();
+            let product = x as u64 * y as u64;
+            let script = script! {
+                { u32_push(x) }
+                { u32_push(y) }
+                { u32_mul() }
+                { u32_push(product as u32) }
+                { u32_equalverify() }
+                { u32_push((product >> 32) as u32) }
+                { u32_equal() }
+            };
+            run(script);
+        }
+
+        let script = script! {
+            { u32_push(u32::MAX) }
+            { u32_push(u32::MAX) }
+            { u32_mul() }
+            { u32_push(1) }
+            { u32_equalverify() }
+            { u32_push(u32::MAX - 1) }
+            { u32_equal() }
+        };
+        run(script);
+    }
+}
@@ -0,0 +1,117 @@
+use crate::treepp::*;
+use crate::u32::u32_rrot::u8_extract_hbit;
+
+/// Logical left shift of an u32 element by shift_num bits
+pub fn u32_shl(shift_num: usize) -> Script {
+    assert!((0..32).contains(&shift_num));
+    let remainder = shift_num % 8;
+    let byte_shift = shift_num / 8;
+
+    script! {
+        // Shift every byte by the remainder, carrying the overflowing bits into the next byte
+        if remainder != 0 {
+            // ⋯ X₃ X₂ X₁ X₀
+            {u8_extract_hbit(remainder)}
+            OP_SWAP
+            OP_TOALTSTACK
+
+            for _ in 0..2 {
+                OP_SWAP
+                {u8_extract_hbit(remainder)}
+                OP_ROT
+                OP_ROT
+                OP_ADD
+                OP_TOALTSTACK
+            }
+
+            OP_SWAP
+            {u8_extract_hbit(remainder)}
+            OP_DROP
+            OP_ADD
+
+            OP_FROMALTSTACK
+            OP_FROMALTSTACK
+            OP_FROMALTSTACK
+        }
+
+        // Drop the most significant bytes and fill the least significant ones with zeros
+        for i in 0..byte_shift {
+            {3 - i} OP_ROLL OP_DROP
+        }
+        for _ in 0..byte_shift {
+            OP_0
+        }
+    }
+}
+
+/// Logical right shift of an u32 element by shift_num bits
+pub fn u32_shr(shift_num: usize) -> Script {
+    assert!((0..32).contains(&shift_num));
+    let remainder = shift_num % 8;
+    let byte_shift = shift_num / 8;
+
+    script! {
+        // Shift every byte by the remainder, carrying the underflowing bits into the previous byte
+        if remainder != 0 {
+            // ⋯ X₃ X₂ X₁ X₀
+            {u8_extract_hbit(8 - remainder)}
+            OP_NIP
+
+            for _ in 0..3 {
+                OP_SWAP
+                {u8_extract_hbit(8 - remainder)}
+                OP_ROT
+                OP_ROT
+                OP_ADD
+                OP_TOALTSTACK
+            }
+
+            OP_FROMALTSTACK
+            OP_FROMALTSTACK
+            OP_FROMALTSTACK
+        }
+
+        // Drop the least significant bytes and fill the most significant ones with zeros
+        for _ in 0..byte_shift {
+            OP_DROP
+        }
+        for _ in 0..byte_shift {
+            OP_0
+        }
+        for _ in byte_shift..4 {
+            3 OP_ROLL
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::u32::u32_std::{u32_equal, u32_push};
+    use rand::Rng;
+
+    #[test]
+    fn test_u32_shift() {
+        for i in 0..32 {
+            println!("u32_shl({}): {} bytes, u32_shr({}): {} bytes", i, u32_shl(i).len(), i, u32_shr(i).len());
+        }
+        let mut rng = rand::thread_rng();
+        for _ in 0..100 {
+            let x: u32 = rng.gen();
+            for i in 0..32 {
+                let script = script! {
+                    { u32_push(x) }
+                    { u32_shl(i) }
+                    { u32_push(x << i) }
+                    { u32_equal() }
+                    OP_VERIFY
+                    { u32_push(x) }
+                    { u32_shr(i) }
+                    { u32_push(x >> i) }
+                    { u32_equal() }
+                };
+                run(script);
+            }
+        }
+    }
+}
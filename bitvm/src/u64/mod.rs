@@ -0,0 +1,40 @@
+pub mod u64_add;
+pub mod u64_and;
+pub mod u64_cmp;
+pub mod u64_rrot;
+pub mod u64_shift;
+pub mod u64_std;
+pub mod u64_xor;
+pub mod u64_zip;
+
+#[cfg(test)]
+mod test {
+    use crate::u32::{u32_add::u32_add_drop, u32_cmp::u32_lt, u32_mul::u32_mul, u32_rrot::u32_rrot, u32_shift::{u32_shl, u32_shr}, u32_xor::u32_xor};
+    use crate::u4::{u4_add::u4_add, u4_logic::u4_xor_u32, u4_rot::u4_rrot};
+    use crate::u64::{u64_add::u64_add_drop, u64_cmp::u64_lt, u64_rrot::u64_rrot, u64_xor::u64_xor};
+
+    // Script sizes of the byte based gadgets next to the nibble based u4 equivalents (without the lookup tables)
+    #[test]
+    fn test_script_sizes_against_u4() {
+        println!("add u32: u32_add_drop {} bytes, u4_add {} bytes", u32_add_drop(1, 0).len(), u4_add(8, vec![0, 8], 0, false).len());
+        println!("add u64: u64_add_drop {} bytes, u4_add {} bytes", u64_add_drop(1, 0).len(), u4_add(16, vec![0, 16], 0, false).len());
+        println!("xor u32: u32_xor {} bytes, u4_xor_u32 {} bytes", u32_xor(0, 1, 3).len(), u4_xor_u32(vec![0, 8], 16, false).len());
+        println!("xor u64: u64_xor {} bytes, 2 x u4_xor_u32 {} bytes", u64_xor(0, 1, 3).len(), 2 * u4_xor_u32(vec![0, 8], 16, false).len());
+        for shift in [1, 7, 8, 13, 16, 31] {
+            println!(
+                "shift {}: u32_shl {} bytes, u32_shr {} bytes, u32_rrot {} bytes, u4_rrot (shift) {} bytes, u4_rrot {} bytes",
+                shift,
+                u32_shl(shift).len(),
+                u32_shr(shift).len(),
+                u32_rrot(shift).len(),
+                u4_rrot(shift as u32, 0, 8, true).len(),
+                u4_rrot(shift as u32, 0, 8, false).len()
+            );
+        }
+        for rot in [1, 8, 19, 32, 41, 61] {
+            println!("u64_rrot({}): {} bytes", rot, u64_rrot(rot).len());
+        }
+        println!("u32_lt: {} bytes, u64_lt: {} bytes", u32_lt().len(), u64_lt().len());
+        println!("u32_mul: {} bytes", u32_mul().len());
+    }
+}
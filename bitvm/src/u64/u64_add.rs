@@ -0,0 +1,75 @@
+use crate::treepp::*;
+use crate::u32::u32_add::{u8_add, u8_add_carry};
+use crate::u64::u64_zip::{u64_copy_zip, u64_zip};
+
+/// Addition of the zipped bytes of two u64 values, the least significant pair being on top
+fn u64_add_zipped() -> Script {
+    script! {
+        for _ in 0..7 {
+            u8_add_carry
+            OP_SWAP
+            OP_TOALTSTACK
+
+            // Add the carry to the next byte
+            OP_ADD
+        }
+        u8_add
+
+        for _ in 0..7 {
+            OP_FROMALTSTACK
+        }
+    }
+}
+
+/// Modulo 2^64 addition of a-th and b-th u64 values, keeps the a-th element at stack
+pub fn u64_add(a: u32, b: u32) -> Script {
+    assert_ne!(a, b);
+    script! {
+        {u64_copy_zip(a, b)}
+        {u64_add_zipped()}
+    }
+}
+
+/// Modulo 2^64 addition of a-th and b-th u64 values
+pub fn u64_add_drop(a: u32, b: u32) -> Script {
+    assert_ne!(a, b);
+    script! {
+        {u64_zip(a, b)}
+        {u64_add_zipped()}
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::u64::u64_std::{u64_equal, u64_equalverify, u64_push};
+    use rand::Rng;
+
+    #[test]
+    fn test_u64_add() {
+        println!("u64_add_drop: {} bytes", u64_add_drop(1, 0).len());
+        let mut rng = rand::thread_rng();
+        for _ in 0..1000 {
+            let x: u64 = rng.gen();
+            let y: u64 = rng.gen();
+            let script_add_drop = script! {
+                { u64_push(x) }
+                { u64_push(y) }
+                { u64_add_drop(1, 0) }
+                { u64_push(x.wrapping_add(y)) }
+                { u64_equal() }
+            };
+            let script_add = script! {
+                { u64_push(x) }
+                { u64_push(y) }
+                { u64_add(1, 0) }
+                { u64_push(x.wrapping_add(y)) }
+                { u64_equalverify() }
+                { u64_push(x) }
+                { u64_equal() }
+            };
+            run(script_add_drop);
+            run(script_add);
+        }
+    }
+}
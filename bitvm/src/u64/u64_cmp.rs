@@ -0,0 +1,54 @@
+use crate::treepp::*;
+use crate::u32::u32_cmp::u8_zipped_lessthan;
+use crate::u64::u64_zip::u64_zip;
+
+/// Returns 1 if the second to top u64 element is less than the top u64 element, 0 otherwise (in the same order as OP_LESSTHAN)
+pub fn u64_lt() -> Script {
+    script! {
+        {u64_zip(1, 0)}
+        {u8_zipped_lessthan(8, false)}
+    }
+}
+
+/// Returns 1 if the second to top u64 element is less than or equal to the top u64 element, 0 otherwise (in the same order as OP_LESSTHANOREQUAL)
+pub fn u64_lte() -> Script {
+    script! {
+        {u64_zip(1, 0)}
+        {u8_zipped_lessthan(8, true)}
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::u64::u64_std::u64_push;
+    use rand::Rng;
+
+    #[test]
+    fn test_u64_lt() {
+        println!("u64_lt: {} bytes", u64_lt().len());
+        let mut rng = rand::thread_rng();
+        for _ in 0..1000 {
+            let x: u64 = rng.gen();
+            // make equal prefixes likely to exercise the lower bytes
+            let y: u64 = match rng.gen_range(0..3) {
+                0 => rng.gen(),
+                1 => x ^ rng.gen_range(0..1 << 16),
+                _ => x,
+            };
+            let script = script! {
+                { u64_push(x) }
+                { u64_push(y) }
+                { u64_lt() }
+                { (x < y) as u32 }
+                OP_EQUALVERIFY
+                { u64_push(x) }
+                { u64_push(y) }
+                { u64_lte() }
+                { (x <= y) as u32 }
+                OP_EQUAL
+            };
+            run(script);
+        }
+    }
+}
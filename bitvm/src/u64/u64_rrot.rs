@@ -0,0 +1,63 @@
+use crate::treepp::*;
+use crate::u32::u32_rrot::u8_extract_hbit;
+
+/// Rotates the bits of a u64 number by rot_num
+pub fn u64_rrot(rot_num: usize) -> Script {
+    assert!((0..64).contains(&rot_num));
+    let remainder = rot_num % 8;
+    let offset = rot_num / 8;
+
+    script! {
+        // Rotate the bytes, each 7 OP_ROLL being a left rotation by 8 bits
+        for _ in 0..(8 - offset) % 8 {
+            7 OP_ROLL
+        }
+
+        if remainder != 0 {
+            // ⋯ X₇ X₆ X₅ X₄ X₃ X₂ X₁ X₀ -> ⋯ X₀' H₀ X₁' H₁ ⋯ X₇' H₇, where Hᵢ = Xᵢ >> remainder
+            // and Xᵢ' holds the low remainder bits of Xᵢ moved to the top of the byte
+            for i in 0..8 {
+                if i != 0 {
+                    {2 * i} OP_ROLL
+                }
+                {u8_extract_hbit(8 - remainder)}
+            }
+
+            // The i-th byte of the result is Hᵢ + X'ᵢ₊₁, starting from the most significant one
+            15 OP_ROLL
+            OP_ADD
+            for i in 1..8 {
+                {i + 1} OP_ROLL
+                {i + 1} OP_ROLL
+                OP_ADD
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::u64::u64_std::{u64_equal, u64_push};
+    use rand::Rng;
+
+    #[test]
+    fn test_u64_rrot() {
+        for i in 0..64 {
+            println!("u64_rrot({}): {} bytes", i, u64_rrot(i).len());
+        }
+        let mut rng = rand::thread_rng();
+        for _ in 0..100 {
+            let x: u64 = rng.gen();
+            for i in 0..64 {
+                let script = script! {
+                    { u64_push(x) }
+                    { u64_rrot(i) }
+                    { u64_push(x.rotate_right(i as u32)) }
+                    { u64_equal() }
+                };
+                run(script);
+            }
+        }
+    }
+}
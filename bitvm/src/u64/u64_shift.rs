@@ -0,0 +1,87 @@
+use crate::treepp::*;
+use crate::u32::u32_add::u32_add_drop;
+use crate::u32::u32_shift::{u32_shl, u32_shr};
+use crate::u32::u32_std::{
+    u32_drop, u32_dup, u32_fromaltstack, u32_pick, u32_push, u32_roll, u32_toaltstack,
+};
+
+/// Logical left shift of an u64 element by shift_num bits
+pub fn u64_shl(shift_num: usize) -> Script {
+    assert!((0..64).contains(&shift_num));
+
+    script! {
+        // ⋯ Xₕ Xₗ
+        if shift_num >= 32 {
+            {u32_roll(1)}
+            {u32_drop()}
+            {u32_shl(shift_num - 32)}
+            {u32_push(0)}
+        } else if shift_num != 0 {
+            // high half: (Xₕ << n) + (Xₗ >> (32 - n))
+            {u32_dup()}
+            {u32_shr(32 - shift_num)}
+            {u32_roll(2)}
+            {u32_shl(shift_num)}
+            {u32_add_drop(1, 0)}
+
+            // low half: Xₗ << n
+            {u32_roll(1)}
+            {u32_shl(shift_num)}
+        }
+    }
+}
+
+/// Logical right shift of an u64 element by shift_num bits
+pub fn u64_shr(shift_num: usize) -> Script {
+    assert!((0..64).contains(&shift_num));
+
+    script! {
+        // ⋯ Xₕ Xₗ
+        if shift_num >= 32 {
+            {u32_drop()}
+            {u32_shr(shift_num - 32)}
+            {u32_push(0)}
+            {u32_roll(1)}
+        } else if shift_num != 0 {
+            // low half: (Xₗ >> n) + (Xₕ << (32 - n))
+            {u32_pick(1)}
+            {u32_shl(32 - shift_num)}
+            {u32_roll(1)}
+            {u32_shr(shift_num)}
+            {u32_add_drop(1, 0)}
+
+            // high half: Xₕ >> n
+            {u32_toaltstack()}
+            {u32_shr(shift_num)}
+            {u32_fromaltstack()}
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::u64::u64_std::{u64_equal, u64_equalverify, u64_push};
+    use rand::Rng;
+
+    #[test]
+    fn test_u64_shift() {
+        let mut rng = rand::thread_rng();
+        for _ in 0..20 {
+            let x: u64 = rng.gen();
+            for i in 0..64 {
+                let script = script! {
+                    { u64_push(x) }
+                    { u64_shl(i) }
+                    { u64_push(x << i) }
+                    { u64_equalverify() }
+                    { u64_push(x) }
+                    { u64_shr(i) }
+                    { u64_push(x >> i) }
+                    { u64_equal() }
+                };
+                run(script);
+            }
+        }
+    }
+}
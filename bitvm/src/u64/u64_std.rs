@@ -0,0 +1,150 @@
+use crate::treepp::*;
+use crate::u32::u32_std::u32_push;
+
+/// Pushes a value as u64 element onto the stack, i.e. two u32 elements with the least significant one being on top
+pub fn u64_push(value: u64) -> Script {
+    script! {
+        {u32_push((value >> 32) as u32)}
+        {u32_push(value as u32)}
+    }
+}
+
+/// Marks transaction as invalid if the top two u64 stack values are not equal
+pub fn u64_equalverify() -> Script {
+    script! {
+        for i in 0..8 {
+            {8 - i}
+            OP_ROLL
+            OP_EQUALVERIFY
+        }
+    }
+}
+
+/// Returns 1 if the top two u64 are equal, 0 otherwise
+pub fn u64_equal() -> Script {
+    script! {
+        for i in 0..8 {
+            {8 - i}
+            OP_ROLL
+            OP_EQUAL
+            if i != 7 {
+                OP_TOALTSTACK
+            }
+        }
+        for _ in 0..7 {
+            OP_FROMALTSTACK
+            OP_BOOLAND
+        }
+    }
+}
+
+/// Puts the top u64 element onto the top of the alt stack. Removes it from the main stack.
+pub fn u64_toaltstack() -> Script {
+    script! {
+        for _ in 0..8 {
+            OP_TOALTSTACK
+        }
+    }
+}
+
+/// Puts the top u64 element of the alt stack onto the top of the main stack. Removes it from the alt stack.
+pub fn u64_fromaltstack() -> Script {
+    script! {
+        for _ in 0..8 {
+            OP_FROMALTSTACK
+        }
+    }
+}
+
+/// Duplicates the top u64 stack element
+pub fn u64_dup() -> Script {
+    u64_pick(0)
+}
+
+/// Removes the top u64 element from the stack.
+pub fn u64_drop() -> Script {
+    script! {
+        OP_2DROP
+        OP_2DROP
+        OP_2DROP
+        OP_2DROP
+    }
+}
+
+/// Moves u64 element n back in the stack to the top.
+pub fn u64_roll(n: u32) -> Script {
+    let n = (n + 1) * 8 - 1;
+    script! {
+        for _ in 0..8 {
+            {n} OP_ROLL
+        }
+    }
+}
+
+/// Copies u64 element n back in the stack to the top.
+pub fn u64_pick(n: u32) -> Script {
+    let n = (n + 1) * 8 - 1;
+    script! {
+        for _ in 0..8 {
+            {n} OP_PICK
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use rand::Rng;
+
+    #[test]
+    fn test_u64_push() {
+        let script = script! {
+            { u64_push(0x0102030405060708) }
+            for i in (1..=8).rev() {
+                { i }
+                OP_EQUALVERIFY
+            }
+            OP_TRUE
+        };
+        run(script);
+    }
+
+    #[test]
+    fn test_u64_equal() {
+        let mut rng = rand::thread_rng();
+        for _ in 0..100 {
+            let x: u64 = rng.gen();
+            let y: u64 = x ^ (1 << rng.gen_range(0..64));
+            let script = script! {
+                { u64_push(x) }
+                { u64_push(x) }
+                { u64_equalverify() }
+                { u64_push(x) }
+                { u64_push(y) }
+                { u64_equal() }
+                OP_NOT
+            };
+            run(script);
+        }
+    }
+
+    #[test]
+    fn test_u64_roll_pick() {
+        let script = script! {
+            { u64_push(1) }
+            { u64_push(2) }
+            { u64_pick(1) }
+            { u64_push(1) }
+            { u64_equalverify() }
+            { u64_roll(1) }
+            { u64_push(1) }
+            { u64_equalverify() }
+            { u64_dup() }
+            { u64_toaltstack() }
+            { u64_fromaltstack() }
+            { u64_equalverify() }
+            OP_TRUE
+        };
+        run(script);
+    }
+}
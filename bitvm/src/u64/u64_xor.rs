@@ -0,0 +1,54 @@
+use crate::treepp::*;
+use crate::u32::u32_xor::u8_xor;
+use crate::u64::u64_zip::u64_copy_zip;
+
+/// Bitwise XOR of a-th and b-th u64 elements from the top, keeps a-th element in the stack
+/// Expects u8_xor_table on the stack to use u8_xor, and stack_size as a parameter to locate the table (which should be equal to 1 + number of the u64 elements in the stack after the table)
+pub fn u64_xor(a: u32, b: u32, stack_size: u32) -> Script {
+    assert_ne!(a, b);
+    script! {
+        {u64_copy_zip(a, b)}
+
+        for i in 0..8 {
+            {u8_xor(8 * stack_size - 2 * i)}
+            if i != 7 {
+                OP_TOALTSTACK
+            }
+        }
+
+        for _ in 0..7 {
+            OP_FROMALTSTACK
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::u32::u32_xor::{u8_drop_xor_table, u8_push_xor_table};
+    use crate::u64::u64_std::{u64_drop, u64_equal, u64_push};
+    use rand::Rng;
+
+    #[test]
+    fn test_u64_xor() {
+        println!("u64 xor: {} bytes", u64_xor(0, 1, 3).len());
+        let mut rng = rand::thread_rng();
+        for _ in 0..1000 {
+            let x: u64 = rng.gen();
+            let y: u64 = rng.gen();
+            let script = script! {
+                { u8_push_xor_table() }
+                { u64_push(x) }
+                { u64_push(y) }
+                { u64_xor(0, 1, 3) }
+                { u64_push(x ^ y) }
+                { u64_equal() }
+                OP_TOALTSTACK
+                { u64_drop() } // drop y
+                { u8_drop_xor_table() }
+                OP_FROMALTSTACK
+            };
+            run(script);
+        }
+    }
+}
@@ -0,0 +1,88 @@
+use crate::treepp::{script, Script};
+
+/// Zips the a-th and b-th u64 elements from the top (without preserving order)
+/// Assuming a is smaller than b and x_i denoting the i-th byte of the x-th number (x_0 being the most significant one):
+/// Input:  ... (a u64 elements) a_0 ... a_7 ... (b - a - 1 u64 elements) b_0 ... b_7
+/// Output: b_0 a_0 b_1 a_1 ... b_7 a_7 ... (b - 1 u64 elements and rest of the stack)
+pub fn u64_zip(mut a: u32, mut b: u32) -> Script {
+    assert_ne!(a, b);
+    if a > b {
+        (a, b) = (b, a);
+    }
+
+    a = (a + 1) * 8 - 1;
+    b = (b + 1) * 8 - 1;
+
+    script! {
+        for i in 0..8 {
+            {a + i} OP_ROLL {b} OP_ROLL
+        }
+    }
+}
+
+/// Zips the a-th and b-th u64 elements from the top and keep the one chosen (given as the first parameter) in the stack (without preserving order)
+/// Assuming a is smaller than b and x_i denoting the i-th byte of the x-th number (x_0 being the most significant one):
+/// Input:  ... (a u64 elements) a_0 ... a_7 ... (b - a - 1 u64 elements) b_0 ... b_7
+/// Output: b_0 a_0 b_1 a_1 ... b_7 a_7 ... (b u64 elements including the element that is chosen to stay and rest of the stack)
+pub fn u64_copy_zip(a: u32, b: u32) -> Script {
+    assert_ne!(a, b);
+    if a < b {
+        let a = (a + 1) * 8 - 1;
+        let b = (b + 1) * 8 - 1;
+        script! {
+            for i in 0..8 {
+                {a + i} OP_PICK {b + 1 + i} OP_ROLL
+            }
+        }
+    } else {
+        let a_roll = (b + 1) * 8 - 1;
+        let b_pick = (a + 1) * 8 - 1;
+        script! {
+            for i in 0..8 {
+                {a_roll + i} OP_ROLL {b_pick} OP_PICK
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::treepp::run;
+    use crate::u64::u64_std::{u64_equalverify, u64_push};
+
+    #[test]
+    fn test_u64_zip() {
+        let x = 0x0001020304050607u64;
+        let y = 0x1011121314151617u64;
+        let script = script! {
+            { u64_push(x) }
+            { u64_push(y) }
+            { u64_zip(1, 0) }
+            for i in (0..8).rev() {
+                { i }
+                OP_EQUALVERIFY
+                { 0x10 + i }
+                OP_EQUALVERIFY
+            }
+            OP_TRUE
+        };
+        run(script);
+
+        let script = script! {
+            { u64_push(x) }
+            { u64_push(y) }
+            { u64_copy_zip(0, 1) }
+            for i in (0..8).rev() {
+                { i }
+                OP_EQUALVERIFY
+                { 0x10 + i }
+                OP_EQUALVERIFY
+            }
+            { u64_push(y) }
+            { u64_equalverify() }
+            OP_TRUE
+        };
+        run(script);
+    }
+}
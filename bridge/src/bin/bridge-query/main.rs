@@ -1,4 +1,4 @@
-use bitcoin::Network;
+use bitcoin::{Network, PublicKey};
 use bridge::{client::cli::query_command::QueryCommand, constants::DestinationNetwork};
 use clap::{arg, command};
 use std::error::Error;
@@ -18,7 +18,15 @@ async fn main() -> Result<(), Box<dyn Error>> {
         .subcommand(QueryCommand::peg_in_graphs_command())
         .arg(arg!(-e --environment <ENVIRONMENT> "Specify the Bitcoin and L2 network environment (mainnet, testnet, local)").required(false)
         .default_value("testnet"))
-        .arg(arg!(-p --prefix <PREFIX> "Prefix for local file cache path").required(false));
+        .arg(arg!(-p --prefix <PREFIX> "Prefix for local file cache path").required(false))
+        .arg(
+            arg!(--operators [OPERATOR_PUBKEYS] "Comma-separated list of the operator public keys whose data is accepted from the data store")
+                .required(false)
+                .num_args(0..1000)
+                .value_delimiter(',')
+                .value_parser(clap::value_parser!(PublicKey))
+                .env("OPERATORS"),
+        );
 
     let matches = command.clone().get_matches();
     let (source_network, destination_network) =
@@ -32,8 +40,13 @@ async fn main() -> Result<(), Box<dyn Error>> {
             }
         };
     let prefix = matches.get_one::<String>("prefix").map(|s| s.as_str());
+    let operators = matches
+        .get_many::<PublicKey>("operators")
+        .map(|x| x.cloned().collect::<Vec<PublicKey>>())
+        .unwrap_or_default();
 
-    let mut query = QueryCommand::new(source_network, destination_network, prefix).await;
+    let mut query =
+        QueryCommand::new(source_network, destination_network, prefix, &operators).await;
     let resp = match matches.subcommand() {
         Some(("depositor", sub)) => query.handle_depositor(sub).await,
         Some(("withdrawer", sub)) => query.handle_withdrawer(sub, destination_network).await,
@@ -0,0 +1,59 @@
+use bitcoin::{Network, PublicKey};
+use bridge::{
+    client::{cli::query_command::create_query_client, rpc::server::RpcServer},
+    constants::DestinationNetwork,
+};
+use clap::{arg, command, value_parser};
+use std::{error::Error, time::Duration};
+use tokio::net::TcpListener;
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn Error>> {
+    let command = command!() // requires `cargo` feature
+        .propagate_version(true)
+        .arg(arg!(-e --environment <ENVIRONMENT> "Specify the Bitcoin and L2 network environment (mainnet, testnet, local)").required(false)
+        .default_value("testnet"))
+        .arg(arg!(-p --prefix <PREFIX> "Prefix for local file cache path").required(false))
+        .arg(
+            arg!(--operators [OPERATOR_PUBKEYS] "Comma-separated list of the operator public keys whose data is accepted from the data store")
+                .required(false)
+                .num_args(0..1000)
+                .value_delimiter(',')
+                .value_parser(value_parser!(PublicKey))
+                .env("OPERATORS"),
+        )
+        .arg(arg!(-b --bind <ADDRESS> "Address to listen on for JSON-RPC requests").required(false)
+        .default_value("127.0.0.1:8080"))
+        .arg(arg!(-s --"sync-interval" <SECONDS> "Interval between two syncs with the data store and L2 chain").required(false)
+        .value_parser(value_parser!(u64).range(1..))
+        .default_value("60"));
+
+    let matches = command.clone().get_matches();
+    let (source_network, destination_network) =
+        match matches.get_one::<String>("environment").unwrap().as_str() {
+            "mainnet" => (Network::Bitcoin, DestinationNetwork::Ethereum),
+            "testnet" => (Network::Testnet, DestinationNetwork::EthereumSepolia),
+            "local" => (Network::Regtest, DestinationNetwork::Local),
+            _ => {
+                eprintln!("Invalid environment. Use mainnet, testnet.");
+                std::process::exit(1);
+            }
+        };
+    let prefix = matches.get_one::<String>("prefix").map(|s| s.as_str());
+    let operators = matches
+        .get_many::<PublicKey>("operators")
+        .map(|x| x.cloned().collect::<Vec<PublicKey>>())
+        .unwrap_or_default();
+    let address = matches.get_one::<String>("bind").unwrap();
+    let sync_interval = Duration::from_secs(*matches.get_one::<u64>("sync-interval").unwrap());
+
+    let client = create_query_client(source_network, destination_network, prefix, &operators).await;
+    let listener = TcpListener::bind(address).await?;
+    println!("Serving JSON-RPC requests on {}", listener.local_addr()?);
+
+    RpcServer::new(client, destination_network, sync_interval)
+        .serve(listener)
+        .await;
+
+    Ok(())
+}
@@ -1,5 +1,5 @@
 use bitcoin::PublicKey;
-use bridge::client::cli::client_command::{ClientCommand, CommonArgs};
+use bridge::client::cli::client_command::{get_networks, ClientCommand, CommonArgs};
 use bridge::client::cli::key_command::KeysCommand;
 use clap::{arg, command};
 use std::error::Error;
@@ -24,17 +24,56 @@ async fn main() -> Result<(), Box<dyn Error>> {
                 .value_parser(clap::value_parser!(PublicKey))
                 .env("VERIFIERS"),
         )
+        .arg(
+            arg!(--operators [OPERATOR_PUBKEYS] "Comma-separated list of the operator public keys expected to create a peg-out graph for every peg-in. Their data is accepted from the data store")
+                .required(false)
+                .num_args(0..1000)
+                .value_delimiter(',')
+                .value_parser(clap::value_parser!(PublicKey))
+                .env("OPERATORS"),
+        )
+        .arg(
+            arg!(--"operator-selection" <POLICY> "Policy selecting the one peg-out graph of a peg-in that may be reimbursed")
+                .required(false)
+                .value_parser(["peg-out-event", "first-kick-off"])
+                .default_value("peg-out-event")
+                .env("OPERATOR_SELECTION"),
+        )
+        .arg(
+            arg!(--"l2-bridge-address" <ADDRESS> "Address of the bridge contract on the L2 chain emitting the peg-out burn events. Burns must then be proven against the finalized L2 block before kick-off 1, and unproven kick-offs get challenged")
+                .required(false)
+                .env("L2_BRIDGE_ADDRESS"),
+        )
+        .arg(
+            arg!(--"l2-chain-id" <CHAIN_ID> "Chain ID of the EVM network the bridge contract is deployed on, when it isn't the default one of the environment. The network is configured by the BRIDGE_CHAIN_ADAPTOR_EVM_* environment variables")
+                .required(false)
+                .value_parser(clap::value_parser!(u64))
+                .env("L2_CHAIN_ID"),
+        )
         .arg(arg!(-e --environment <ENVIRONMENT> "Specify the Bitcoin network environment (mainnet, testnet, regtest)").required(false).default_value("testnet").env("ENVIRONMENT"))
         .arg(arg!(-p --"user-profile" <USER_PROFILE> "Name of the protocol participant (e.g. 'operator_one', 'verifier_0'). Used as a namespace separator in the local file path for storing private and public client data").required(false).default_value("default_user").env("USER_PROFILE"))
         .subcommand(KeysCommand::get_command())
+        .subcommand(KeysCommand::get_commitment_seed_command())
+        .subcommand(KeysCommand::get_wallet_command())
         .subcommand(ClientCommand::get_operator_address_command())
         .subcommand(ClientCommand::get_operator_utxos_command())
         .subcommand(ClientCommand::get_depositor_address_command())
         .subcommand(ClientCommand::get_depositor_utxos_command())
         .subcommand(ClientCommand::get_initiate_peg_in_command())
         .subcommand(ClientCommand::get_create_peg_out_graph_command())
+        .subcommand(ClientCommand::get_regenerate_commitments_command())
         .subcommand(ClientCommand::get_push_nonces_command())
         .subcommand(ClientCommand::get_push_signature_command())
+        .subcommand(ClientCommand::get_signing_coordinator_command())
+        .subcommand(ClientCommand::get_signing_session_command())
+        .subcommand(ClientCommand::get_rescan_command())
+        .subcommand(ClientCommand::get_consolidate_command())
+        .subcommand(ClientCommand::get_export_psbt_command())
+        .subcommand(ClientCommand::get_import_psbt_command())
+        .subcommand(ClientCommand::get_graph_export_command())
+        .subcommand(ClientCommand::get_fee_table_command())
+        .subcommand(ClientCommand::get_challenge_crowdfunding_command())
+        .subcommand(ClientCommand::get_recover_command())
         .subcommand(ClientCommand::get_mock_l2_pegout_event_command())
         .subcommand(ClientCommand::get_status_command())
         .subcommand(ClientCommand::get_broadcast_command())
@@ -48,6 +87,12 @@ async fn main() -> Result<(), Box<dyn Error>> {
         verifiers: matches
             .get_many::<PublicKey>("verifiers")
             .map(|x| x.cloned().collect::<Vec<PublicKey>>()),
+        operators: matches
+            .get_many::<PublicKey>("operators")
+            .map(|x| x.cloned().collect::<Vec<PublicKey>>()),
+        operator_selection: matches.get_one::<String>("operator-selection").cloned(),
+        l2_bridge_address: matches.get_one::<String>("l2-bridge-address").cloned(),
+        l2_chain_id: matches.get_one::<u64>("l2-chain-id").copied(),
         environment: matches.get_one::<String>("environment").cloned(),
         path_prefix: matches.get_one::<String>("user-profile").cloned(),
     };
@@ -55,6 +100,13 @@ async fn main() -> Result<(), Box<dyn Error>> {
     if let Some(sub_matches) = matches.subcommand_matches("keys") {
         let keys_command = KeysCommand::new(global_args.key_dir);
         keys_command.handle_command(sub_matches)?;
+    } else if let Some(sub_matches) = matches.subcommand_matches("commitment-seed") {
+        let keys_command = KeysCommand::new(global_args.key_dir);
+        keys_command.handle_commitment_seed_command(sub_matches)?;
+    } else if let Some(sub_matches) = matches.subcommand_matches("wallet") {
+        let (network, _) = get_networks(global_args.environment.as_deref());
+        let keys_command = KeysCommand::new(global_args.key_dir);
+        keys_command.handle_wallet_command(sub_matches, network)?;
     } else if matches.subcommand_matches("get-operator-address").is_some() {
         let mut client_command = ClientCommand::new(global_args).await;
         let _ = client_command.handle_get_operator_address().await;
@@ -80,6 +132,11 @@ async fn main() -> Result<(), Box<dyn Error>> {
         let _ = client_command
             .handle_create_peg_out_graph_command(sub_matches)
             .await;
+    } else if let Some(sub_matches) = matches.subcommand_matches("regenerate-commitments") {
+        let mut client_command = ClientCommand::new(global_args).await;
+        let _ = client_command
+            .handle_regenerate_commitments_command(sub_matches)
+            .await;
     } else if let Some(sub_matches) = matches.subcommand_matches("push-nonces") {
         let mut client_command = ClientCommand::new(global_args).await;
         let _ = client_command.handle_push_nonces_command(sub_matches).await;
@@ -88,6 +145,44 @@ async fn main() -> Result<(), Box<dyn Error>> {
         let _ = client_command
             .handle_push_signature_command(sub_matches)
             .await;
+    } else if let Some(sub_matches) = matches.subcommand_matches("signing-coordinator") {
+        let mut client_command = ClientCommand::new(global_args).await;
+        let _ = client_command
+            .handle_signing_coordinator_command(sub_matches)
+            .await;
+    } else if let Some(sub_matches) = matches.subcommand_matches("signing-session") {
+        let mut client_command = ClientCommand::new(global_args).await;
+        let _ = client_command
+            .handle_signing_session_command(sub_matches)
+            .await;
+    } else if let Some(sub_matches) = matches.subcommand_matches("rescan") {
+        let mut client_command = ClientCommand::new(global_args).await;
+        let _ = client_command.handle_rescan_command(sub_matches).await;
+    } else if let Some(sub_matches) = matches.subcommand_matches("consolidate") {
+        let mut client_command = ClientCommand::new(global_args).await;
+        let _ = client_command.handle_consolidate_command(sub_matches).await;
+    } else if let Some(sub_matches) = matches.subcommand_matches("export-psbt") {
+        let mut client_command = ClientCommand::new(global_args).await;
+        let _ = client_command.handle_export_psbt_command(sub_matches).await;
+    } else if let Some(sub_matches) = matches.subcommand_matches("import-psbt") {
+        let mut client_command = ClientCommand::new(global_args).await;
+        let _ = client_command.handle_import_psbt_command(sub_matches).await;
+    } else if let Some(sub_matches) = matches.subcommand_matches("graph-export") {
+        let mut client_command = ClientCommand::new(global_args).await;
+        let _ = client_command
+            .handle_graph_export_command(sub_matches)
+            .await;
+    } else if let Some(sub_matches) = matches.subcommand_matches("fee-table") {
+        let mut client_command = ClientCommand::new(global_args).await;
+        let _ = client_command.handle_fee_table_command(sub_matches).await;
+    } else if let Some(sub_matches) = matches.subcommand_matches("challenge-crowdfunding") {
+        let mut client_command = ClientCommand::new(global_args).await;
+        let _ = client_command
+            .handle_challenge_crowdfunding_command(sub_matches)
+            .await;
+    } else if let Some(sub_matches) = matches.subcommand_matches("recover") {
+        let mut client_command = ClientCommand::new(global_args).await;
+        let _ = client_command.handle_recover_command(sub_matches).await;
     } else if let Some(sub_matches) = matches.subcommand_matches("mock-l2-pegout-event") {
         let mut client_command = ClientCommand::new(global_args).await;
         let _ = client_command
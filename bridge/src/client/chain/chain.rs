@@ -1,7 +1,8 @@
-use bitcoin::{Amount, OutPoint, PubkeyHash, PublicKey};
+use alloy::primitives::B256;
+use bitcoin::{Amount, OutPoint, PubkeyHash, PublicKey, Txid};
 use serde::{Deserialize, Serialize};
 
-use super::{chain_adaptor::ChainAdaptor, mock_adaptor::MockAdaptor};
+use super::{chain_adaptor::ChainAdaptor, l2_proof::L2Proof, mock_adaptor::MockAdaptor};
 
 #[derive(Serialize, Deserialize, Eq, PartialEq, Clone, Debug)]
 pub struct PegOutEvent {
@@ -60,4 +61,18 @@ impl Chain {
     pub async fn get_peg_in_minted(&self) -> Result<Vec<PegInEvent>, String> {
         self.adaptor.get_peg_in_minted_event().await
     }
+
+    pub async fn get_finalized_block_hash(&self) -> Result<Option<B256>, String> {
+        self.adaptor.get_finalized_block_hash().await
+    }
+
+    pub async fn get_peg_out_burnt_proofs(
+        &self,
+        source_txids: &[Txid],
+        finalized_block_hash: &B256,
+    ) -> Result<Vec<L2Proof>, String> {
+        self.adaptor
+            .get_peg_out_burnt_proofs(source_txids, finalized_block_hash)
+            .await
+    }
 }
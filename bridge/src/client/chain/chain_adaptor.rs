@@ -1,12 +1,15 @@
+use alloy::primitives::B256;
 use async_trait::async_trait;
+use bitcoin::Txid;
 
 use crate::constants::DestinationNetwork;
 
 use super::chain::PegInEvent;
 use super::chain::PegOutBurntEvent;
 use super::chain::PegOutEvent;
-use super::ethereum_adaptor::EthereumAdaptor;
-use super::ethereum_adaptor::EthereumInitConfig;
+use super::evm_adaptor::EvmAdaptor;
+use super::evm_adaptor::EvmAdaptorConfig;
+use super::l2_proof::L2Proof;
 use super::mock_adaptor::MockAdaptor;
 use super::mock_adaptor::MockAdaptorConfig;
 
@@ -15,16 +18,37 @@ pub trait ChainAdaptor {
     async fn get_peg_out_init_event(&self) -> Result<Vec<PegOutEvent>, String>;
     async fn get_peg_out_burnt_event(&self) -> Result<Vec<PegOutBurntEvent>, String>;
     async fn get_peg_in_minted_event(&self) -> Result<Vec<PegInEvent>, String>;
+    // Hash of the finalized L2 block burns are proven against, `None` for the adaptors unable to
+    // build proofs. It is the trust anchor of the proofs, not read from the node they are built
+    // from.
+    async fn get_finalized_block_hash(&self) -> Result<Option<B256>, String> { Ok(None) }
+    // Inclusion proofs, anchored at `finalized_block_hash`, of the `PegOutBurnt` events of the
+    // peg-outs of the peg-ins confirmed in `source_txids`.
+    async fn get_peg_out_burnt_proofs(
+        &self,
+        _source_txids: &[Txid],
+        _finalized_block_hash: &B256,
+    ) -> Result<Vec<L2Proof>, String> {
+        Ok(vec![])
+    }
 }
 
+// Ethereum, Ethereum Sepolia and the other EVM networks are read by an `EvmAdaptor`, configured
+// by the environment unless `evm_config` is given, see `EvmAdaptorConfig::from_env`.
 pub fn get_chain_adaptor(
     network: DestinationNetwork,
-    ethereum_config: Option<EthereumInitConfig>,
     mock_adaptor_config: Option<MockAdaptorConfig>,
+    evm_config: Option<EvmAdaptorConfig>,
 ) -> Box<dyn ChainAdaptor> {
-    match network {
-        DestinationNetwork::Ethereum => Box::new(EthereumAdaptor::new(ethereum_config)),
-        DestinationNetwork::EthereumSepolia => Box::new(EthereumAdaptor::new(ethereum_config)),
-        DestinationNetwork::Local => Box::new(MockAdaptor::new(mock_adaptor_config)),
+    match network.chain_id() {
+        None => Box::new(MockAdaptor::new(mock_adaptor_config)),
+        Some(chain_id) => {
+            let config = evm_config.unwrap_or_else(|| EvmAdaptorConfig::from_env(chain_id));
+            assert_eq!(
+                config.chain_id, chain_id,
+                "EVM adaptor config of another chain"
+            );
+            Box::new(EvmAdaptor::new(config))
+        }
     }
 }
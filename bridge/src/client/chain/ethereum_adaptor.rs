@@ -3,12 +3,17 @@ use std::str::FromStr;
 use alloy::rpc::types::Log;
 
 use super::{
-    chain::PegInEvent, chain::PegOutBurntEvent, chain::PegOutEvent, chain_adaptor::ChainAdaptor,
+    chain::PegInEvent,
+    chain::PegOutBurntEvent,
+    chain::PegOutEvent,
+    chain_adaptor::ChainAdaptor,
+    l2_proof::L2Proof,
+    l2_proof_builder::{get_block_hash, L2LogPosition, L2ProofBuilder},
 };
 use alloy::sol_types::SolEvent;
 use alloy::{
     eips::BlockNumberOrTag,
-    primitives::Address as EvmAddress,
+    primitives::{Address as EvmAddress, B256},
     providers::{Provider, ProviderBuilder, RootProvider},
     rpc::types::Filter,
     sol,
@@ -54,6 +59,7 @@ pub struct EthereumAdaptor {
     bridge_creation_block: u64,
     provider: RootProvider<Http<Client>>,
     to_block: Option<BlockNumberOrTag>,
+    proof_builder: L2ProofBuilder,
 }
 
 pub struct EthereumInitConfig {
@@ -107,39 +113,12 @@ impl ChainAdaptor for EthereumAdaptor {
             .unwrap()
             .iter()
             .filter_map(|e| {
-                let withdrawer_address = Address::from_str(&e.inner.data.destination_address)
-                    .unwrap()
-                    .assume_checked();
-                let operator_public_key =
-                    PublicKey::from_slice(e.inner.data.operator_pubKey.as_ref()).unwrap();
-                match withdrawer_address.pubkey_hash() {
-                    Some(withdrawer_public_key_hash) => {
-                        let mut txid_vec = e.inner.data.source_outpoint.txId.to_vec();
-                        txid_vec.reverse();
-                        Some(PegOutEvent {
-                            withdrawer_chain_address: e.inner.data.withdrawer.to_string(),
-                            withdrawer_destination_address: e
-                                .inner
-                                .data
-                                .destination_address
-                                .to_string(),
-                            withdrawer_public_key_hash,
-                            source_outpoint: OutPoint {
-                                txid: Txid::from_slice(&txid_vec).unwrap(),
-                                vout: e.inner.data.source_outpoint.vOut.to::<u32>(),
-                            },
-                            amount: Amount::from_str_in(
-                                e.inner.data.amount.to_string().as_str(),
-                                Denomination::Satoshi,
-                            )
-                            .unwrap(),
-                            operator_public_key,
-                            timestamp: u32::try_from(e.block_timestamp.unwrap()).unwrap(),
-                            tx_hash: e.transaction_hash.unwrap().to_vec(),
-                        })
-                    }
-                    None => None,
-                }
+                to_peg_out_event(
+                    &e.inner.data,
+                    u32::try_from(e.block_timestamp.unwrap()).unwrap(),
+                    e.transaction_hash.unwrap().to_vec(),
+                )
+                .ok()
             })
             .collect();
 
@@ -152,31 +131,17 @@ impl ChainAdaptor for EthereumAdaptor {
             return Err(sol_events.unwrap_err().to_string());
         }
 
-        let peg_out_burnt_events = sol_events
+        sol_events
             .unwrap()
             .iter()
             .map(|e| {
-                let operator_public_key =
-                    PublicKey::from_slice(e.inner.data.operator_pubKey.as_ref()).unwrap();
-                PegOutBurntEvent {
-                    withdrawer_chain_address: e.inner.data.withdrawer.to_string(),
-                    source_outpoint: OutPoint {
-                        txid: Txid::from_slice(e.inner.data.source_outpoint.txId.as_ref()).unwrap(),
-                        vout: e.inner.data.source_outpoint.vOut.to::<u32>(),
-                    },
-                    amount: Amount::from_str_in(
-                        e.inner.data.amount.to_string().as_str(),
-                        Denomination::Satoshi,
-                    )
-                    .unwrap(),
-                    operator_public_key,
-                    timestamp: u32::try_from(e.block_timestamp.unwrap()).unwrap(),
-                    tx_hash: e.transaction_hash.unwrap().to_vec(),
-                }
+                to_peg_out_burnt_event(
+                    &e.inner.data,
+                    u32::try_from(e.block_timestamp.unwrap()).unwrap(),
+                    e.transaction_hash.unwrap().to_vec(),
+                )
             })
-            .collect();
-
-        Ok(peg_out_burnt_events)
+            .collect()
     }
 
     async fn get_peg_in_minted_event(&self) -> Result<Vec<PegInEvent>, String> {
@@ -185,25 +150,118 @@ impl ChainAdaptor for EthereumAdaptor {
             return Err(sol_events.unwrap_err().to_string());
         }
 
-        let peg_in_minted_events = sol_events
+        sol_events
             .unwrap()
             .iter()
-            .map(|e| PegInEvent {
-                depositor: e.inner.data.depositor.to_string(),
-                amount: Amount::from_str_in(
-                    e.inner.data.amount.to_string().as_str(),
-                    Denomination::Satoshi,
-                )
-                .unwrap(),
-                depositor_pubkey: PublicKey::from_slice(e.inner.data.depositorPubKey.as_ref())
-                    .unwrap(),
+            .map(|e| to_peg_in_event(&e.inner.data))
+            .collect()
+    }
+
+    // Events are read up to the same block.
+    async fn get_finalized_block_hash(&self) -> Result<Option<B256>, String> {
+        let block = self.to_block.unwrap_or(BlockNumberOrTag::Finalized);
+        get_block_hash(&self.provider, block).await.map(Some)
+    }
+
+    async fn get_peg_out_burnt_proofs(
+        &self,
+        source_txids: &[Txid],
+        finalized_block_hash: &B256,
+    ) -> Result<Vec<L2Proof>, String> {
+        let positions = self
+            .get_sol_events::<IBridge::PegOutBurnt>()
+            .await?
+            .iter()
+            .filter(|e| {
+                to_source_outpoint(&e.inner.data.source_outpoint)
+                    .is_ok_and(|outpoint| source_txids.contains(&outpoint.txid))
             })
-            .collect();
+            .map(L2LogPosition::from_log)
+            .collect::<Result<Vec<_>, _>>()?;
 
-        Ok(peg_in_minted_events)
+        self.proof_builder
+            .build(&self.provider, &positions, finalized_block_hash)
+            .await
     }
 }
 
+/// Converts a `PegOutInitiated` event of the bridge contract emitted by the L2 transaction
+/// `tx_hash` in a block of `timestamp`. Only withdrawals to P2PKH addresses are supported.
+pub fn to_peg_out_event(
+    event: &IBridge::PegOutInitiated,
+    timestamp: u32,
+    tx_hash: Vec<u8>,
+) -> Result<PegOutEvent, String> {
+    let withdrawer_address = Address::from_str(&event.destination_address)
+        .map_err(|e| e.to_string())?
+        .assume_checked();
+    let withdrawer_public_key_hash = withdrawer_address
+        .pubkey_hash()
+        .ok_or_else(|| format!("Unsupported destination address {withdrawer_address}"))?;
+    let operator_public_key =
+        PublicKey::from_slice(event.operator_pubKey.as_ref()).map_err(|e| e.to_string())?;
+
+    Ok(PegOutEvent {
+        withdrawer_chain_address: event.withdrawer.to_string(),
+        withdrawer_destination_address: event.destination_address.to_string(),
+        withdrawer_public_key_hash,
+        source_outpoint: to_source_outpoint(&event.source_outpoint)?,
+        amount: Amount::from_str_in(event.amount.to_string().as_str(), Denomination::Satoshi)
+            .map_err(|e| e.to_string())?,
+        operator_public_key,
+        timestamp,
+        tx_hash,
+    })
+}
+
+/// Converts the source outpoint of a peg-out event. The contract stores the txid in reversed
+/// byte order, the way it is displayed.
+pub fn to_source_outpoint(outpoint: &IBridge::Outpoint) -> Result<OutPoint, String> {
+    let mut txid_vec = outpoint.txId.to_vec();
+    txid_vec.reverse();
+
+    Ok(OutPoint {
+        txid: Txid::from_slice(&txid_vec).map_err(|e| e.to_string())?,
+        vout: outpoint
+            .vOut
+            .try_into()
+            .map_err(|_| String::from("Source outpoint index out of range"))?,
+    })
+}
+
+/// Converts a `PegInMinted` event of the bridge contract.
+pub fn to_peg_in_event(event: &IBridge::PegInMinted) -> Result<PegInEvent, String> {
+    Ok(PegInEvent {
+        depositor: event.depositor.to_string(),
+        amount: Amount::from_str_in(event.amount.to_string().as_str(), Denomination::Satoshi)
+            .map_err(|e| e.to_string())?,
+        depositor_pubkey: PublicKey::from_slice(event.depositorPubKey.as_ref())
+            .map_err(|e| e.to_string())?,
+    })
+}
+
+/// Converts a `PegOutBurnt` event of the bridge contract emitted by the L2 transaction `tx_hash`
+/// in a block of `timestamp`.
+pub fn to_peg_out_burnt_event(
+    event: &IBridge::PegOutBurnt,
+    timestamp: u32,
+    tx_hash: Vec<u8>,
+) -> Result<PegOutBurntEvent, String> {
+    let operator_public_key =
+        PublicKey::from_slice(event.operator_pubKey.as_ref()).map_err(|e| e.to_string())?;
+    let amount = Amount::from_str_in(event.amount.to_string().as_str(), Denomination::Satoshi)
+        .map_err(|e| e.to_string())?;
+
+    Ok(PegOutBurntEvent {
+        withdrawer_chain_address: event.withdrawer.to_string(),
+        source_outpoint: to_source_outpoint(&event.source_outpoint)?,
+        amount,
+        operator_public_key,
+        timestamp,
+        tx_hash,
+    })
+}
+
 impl EthereumAdaptor {
     pub fn new(config: Option<EthereumInitConfig>) -> Self {
         if let Some(_config) = config {
@@ -238,6 +296,7 @@ impl EthereumAdaptor {
             bridge_creation_block: config.bridge_creation_block,
             provider: ProviderBuilder::new().on_http(config.rpc_url),
             to_block: config.to_block,
+            proof_builder: L2ProofBuilder::default(),
         }
     }
 }
@@ -0,0 +1,621 @@
+use std::{
+    collections::HashMap,
+    fs,
+    path::{Path, PathBuf},
+};
+
+use alloy::{
+    dyn_abi::{DynSolValue, EventExt},
+    eips::BlockNumberOrTag,
+    json_abi::{Event, JsonAbi},
+    primitives::{Address as EvmAddress, Bytes, B256, U256},
+    providers::{Provider, ProviderBuilder, RootProvider},
+    rpc::types::{Filter, Log},
+    transports::http::{reqwest::Url, Client, Http},
+};
+use async_trait::async_trait;
+use bitcoin::Txid;
+use serde::{Deserialize, Serialize};
+use tokio::sync::{Mutex, MutexGuard};
+
+use crate::{
+    client::files::BRIDGE_DATA_DIRECTORY_NAME,
+    constants::{ETHEREUM_CHAIN_ID, ETHEREUM_SEPOLIA_CHAIN_ID},
+};
+
+use super::{
+    chain::{PegInEvent, PegOutBurntEvent, PegOutEvent},
+    chain_adaptor::ChainAdaptor,
+    ethereum_adaptor::{to_peg_in_event, to_peg_out_burnt_event, to_peg_out_event, IBridge},
+    l2_proof::L2Proof,
+    l2_proof_builder::{get_block_hash, L2LogPosition, L2ProofBuilder},
+};
+
+pub const DEFAULT_CONFIRMATIONS: u64 = 12;
+pub const DEFAULT_MAX_BLOCK_RANGE: u64 = 1_000;
+
+// The events of the `IBridge` interface, see `EvmEventAbi`.
+const BRIDGE_EVENT_ABI: &str = r#"[
+    {
+        "type": "event",
+        "name": "PegOutInitiated",
+        "inputs": [
+            { "name": "withdrawer", "type": "address", "indexed": true },
+            { "name": "destination_address", "type": "string", "indexed": false },
+            {
+                "name": "source_outpoint",
+                "type": "tuple",
+                "indexed": false,
+                "components": [
+                    { "name": "txId", "type": "bytes32" },
+                    { "name": "vOut", "type": "uint256" }
+                ]
+            },
+            { "name": "amount", "type": "uint256", "indexed": false },
+            { "name": "operator_pubKey", "type": "bytes", "indexed": false }
+        ],
+        "anonymous": false
+    },
+    {
+        "type": "event",
+        "name": "PegOutBurnt",
+        "inputs": [
+            { "name": "withdrawer", "type": "address", "indexed": true },
+            {
+                "name": "source_outpoint",
+                "type": "tuple",
+                "indexed": false,
+                "components": [
+                    { "name": "txId", "type": "bytes32" },
+                    { "name": "vOut", "type": "uint256" }
+                ]
+            },
+            { "name": "amount", "type": "uint256", "indexed": false },
+            { "name": "operator_pubKey", "type": "bytes", "indexed": false }
+        ],
+        "anonymous": false
+    },
+    {
+        "type": "event",
+        "name": "PegInMinted",
+        "inputs": [
+            { "name": "depositor", "type": "address", "indexed": true },
+            { "name": "amount", "type": "uint256", "indexed": false },
+            { "name": "depositorPubKey", "type": "bytes32", "indexed": false }
+        ],
+        "anonymous": false
+    }
+]"#;
+
+/// The bridge contract events read by `EvmAdaptor`.
+///
+/// Contracts may declare the events with their parameters in another order, indexed differently
+/// or with additional parameters. The parameters the bridge needs are looked up by their names in
+/// the `IBridge` interface.
+#[derive(Clone, Debug)]
+pub struct EvmEventAbi {
+    pub peg_out_initiated: Event,
+    pub peg_out_burnt: Event,
+    pub peg_in_minted: Event,
+}
+
+impl Default for EvmEventAbi {
+    fn default() -> Self { Self::from_json(BRIDGE_EVENT_ABI).expect("Invalid bridge event ABI") }
+}
+
+impl EvmEventAbi {
+    /// Reads the `PegOutInitiated`, `PegOutBurnt` and `PegInMinted` events from the JSON ABI of
+    /// the bridge contract.
+    pub fn from_json(json: &str) -> Result<Self, String> {
+        let abi = serde_json::from_str::<JsonAbi>(json).map_err(|e| e.to_string())?;
+        let event = |name: &str| {
+            abi.event(name)
+                .and_then(|events| events.first())
+                .cloned()
+                .ok_or_else(|| format!("Event {name} is missing from the ABI"))
+        };
+
+        Ok(Self {
+            peg_out_initiated: event("PegOutInitiated")?,
+            peg_out_burnt: event("PegOutBurnt")?,
+            peg_in_minted: event("PegInMinted")?,
+        })
+    }
+
+    fn selectors(&self) -> Vec<B256> {
+        vec![
+            self.peg_out_initiated.selector(),
+            self.peg_out_burnt.selector(),
+            self.peg_in_minted.selector(),
+        ]
+    }
+}
+
+/// An EVM chain the bridge contract is deployed on.
+#[derive(Clone)]
+pub struct EvmAdaptorConfig {
+    pub chain_id: u64,
+    pub rpc_url: Url,
+    // Node, operated independently of the one at `rpc_url`, the finalized block burns are proven
+    // against is read from. Proofs are only built with it, the node they are built from could
+    // otherwise forge the headers they are anchored at.
+    pub finality_rpc_url: Option<Url>,
+    pub bridge_address: EvmAddress,
+    pub bridge_creation_block: u64,
+    // Blocks built on top of a block before its events are read.
+    pub confirmations: u64,
+    // Largest block range of a single `eth_getLogs` request, as limited by most providers.
+    pub max_block_range: u64,
+    pub event_abi: EvmEventAbi,
+    // File keeping the events read so far and the next block to scan. Without it, every adaptor
+    // scans from the bridge creation block again.
+    pub cursor_path: Option<PathBuf>,
+}
+
+impl EvmAdaptorConfig {
+    /// Reads the configuration of the chain `chain_id` from the `BRIDGE_CHAIN_ADAPTOR_EVM_*`
+    /// environment variables. Ethereum and Ethereum Sepolia fall back to the
+    /// `BRIDGE_CHAIN_ADAPTOR_ETHEREUM_*` ones they were configured with before.
+    pub fn from_env(chain_id: u64) -> Self {
+        dotenv::dotenv().ok();
+        let is_ethereum = [ETHEREUM_CHAIN_ID, ETHEREUM_SEPOLIA_CHAIN_ID].contains(&chain_id);
+        let var = |name: &str| {
+            dotenv::var(format!("BRIDGE_CHAIN_ADAPTOR_EVM_{name}")).or_else(|e| match is_ethereum {
+                true => dotenv::var(format!("BRIDGE_CHAIN_ADAPTOR_ETHEREUM_{name}")),
+                false => Err(e),
+            })
+        };
+        let rpc_url =
+            var("RPC_URL").expect("Failed to read BRIDGE_CHAIN_ADAPTOR_EVM_RPC_URL variable");
+        let finality_rpc_url = var("FINALITY_RPC_URL").ok().map(|url| {
+            url.parse::<Url>()
+                .expect("Invalid BRIDGE_CHAIN_ADAPTOR_EVM_FINALITY_RPC_URL variable")
+        });
+        let bridge_address = var("BRIDGE_ADDRESS")
+            .expect("Failed to read BRIDGE_CHAIN_ADAPTOR_EVM_BRIDGE_ADDRESS variable")
+            .parse::<EvmAddress>()
+            .expect("Invalid BRIDGE_CHAIN_ADAPTOR_EVM_BRIDGE_ADDRESS variable");
+        let bridge_creation_block = var("BRIDGE_CREATION")
+            .expect("Failed to read BRIDGE_CHAIN_ADAPTOR_EVM_BRIDGE_CREATION variable");
+        let number_or = |name: &str, default: u64| {
+            var(name)
+                .map(|value| {
+                    value.parse::<u64>().unwrap_or_else(|_| {
+                        panic!("Invalid BRIDGE_CHAIN_ADAPTOR_EVM_{name} variable")
+                    })
+                })
+                .unwrap_or(default)
+        };
+        let event_abi = match var("EVENT_ABI") {
+            Ok(path) => EvmEventAbi::from_json(
+                &fs::read_to_string(path).expect("Failed to read the bridge event ABI file"),
+            )
+            .expect("Invalid bridge event ABI"),
+            Err(_) => EvmEventAbi::default(),
+        };
+        let cursor_path = match var("CURSOR_PATH") {
+            Ok(path) => PathBuf::from(path),
+            Err(_) => default_cursor_path(chain_id, &bridge_address),
+        };
+
+        Self {
+            chain_id,
+            rpc_url: rpc_url
+                .parse::<Url>()
+                .expect("Invalid BRIDGE_CHAIN_ADAPTOR_EVM_RPC_URL variable"),
+            finality_rpc_url,
+            bridge_address,
+            bridge_creation_block: bridge_creation_block
+                .parse::<u64>()
+                .expect("Invalid BRIDGE_CHAIN_ADAPTOR_EVM_BRIDGE_CREATION variable"),
+            confirmations: number_or("CONFIRMATIONS", DEFAULT_CONFIRMATIONS),
+            max_block_range: number_or("MAX_BLOCK_RANGE", DEFAULT_MAX_BLOCK_RANGE),
+            event_abi,
+            cursor_path: Some(cursor_path),
+        }
+    }
+}
+
+pub fn default_cursor_path(chain_id: u64, bridge_address: &EvmAddress) -> PathBuf {
+    Path::new(BRIDGE_DATA_DIRECTORY_NAME)
+        .join(format!("evm_{chain_id}"))
+        .join(format!("{bridge_address}-cursor.json"))
+}
+
+/// Reads the bridge contract events of any EVM chain.
+///
+/// Blocks are scanned incrementally: each call only requests the logs of the blocks confirmed
+/// since the previous one, in ranges of at most `max_block_range` blocks. The events read so far
+/// are kept with the scan cursor, which is persisted after every range.
+pub struct EvmAdaptor {
+    config: EvmAdaptorConfig,
+    provider: RootProvider<Http<Client>>,
+    finality_provider: Option<RootProvider<Http<Client>>>,
+    // Loaded on the first scan.
+    cursor: Mutex<Option<EvmScanCursor>>,
+    proof_builder: L2ProofBuilder,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+struct EvmScanCursor {
+    chain_id: u64,
+    bridge_address: EvmAddress,
+    next_block: u64,
+    peg_out_init_events: Vec<PegOutEvent>,
+    peg_out_burnt_events: Vec<PegOutBurntEvent>,
+    // Position of each of `peg_out_burnt_events` on the chain, to prove them.
+    #[serde(default)]
+    peg_out_burnt_positions: Vec<L2LogPosition>,
+    peg_in_minted_events: Vec<PegInEvent>,
+}
+
+// Parameters of a decoded event by name.
+struct EventParams(HashMap<String, DynSolValue>);
+
+impl EvmAdaptor {
+    pub fn new(config: EvmAdaptorConfig) -> Self {
+        assert!(
+            config.max_block_range > 0,
+            "The maximum block range must be positive"
+        );
+        Self {
+            provider: ProviderBuilder::new().on_http(config.rpc_url.clone()),
+            finality_provider: config
+                .finality_rpc_url
+                .clone()
+                .map(|url| ProviderBuilder::new().on_http(url)),
+            config,
+            cursor: Mutex::new(None),
+            proof_builder: L2ProofBuilder::default(),
+        }
+    }
+
+    pub fn config(&self) -> &EvmAdaptorConfig { &self.config }
+
+    /// The next block to scan.
+    pub async fn next_block(&self) -> Option<u64> {
+        self.cursor
+            .lock()
+            .await
+            .as_ref()
+            .map(|cursor| cursor.next_block)
+    }
+
+    // Scans the blocks confirmed since the previous scan, returns the cursor holding all the
+    // events read.
+    async fn scan(&self) -> Result<MutexGuard<'_, Option<EvmScanCursor>>, String> {
+        let mut guard = self.cursor.lock().await;
+        if guard.is_none() {
+            *guard = Some(self.load_cursor().await?);
+        }
+        let cursor = guard.as_mut().unwrap();
+
+        let latest_block = self
+            .provider
+            .get_block_number()
+            .await
+            .map_err(|e| e.to_string())?;
+        let Some(confirmed_block) = latest_block.checked_sub(self.config.confirmations) else {
+            return Ok(guard);
+        };
+
+        while cursor.next_block <= confirmed_block {
+            let from_block = cursor.next_block;
+            let to_block =
+                confirmed_block.min(from_block.saturating_add(self.config.max_block_range - 1));
+            let filter = Filter::new()
+                .address(self.config.bridge_address)
+                .event_signature(self.config.event_abi.selectors())
+                .from_block(BlockNumberOrTag::Number(from_block))
+                .to_block(BlockNumberOrTag::Number(to_block));
+            let logs = self
+                .provider
+                .get_logs(&filter)
+                .await
+                .map_err(|e| e.to_string())?;
+
+            // The events of a range are only kept once all of them are decoded.
+            let mut range_cursor = cursor.clone();
+            for log in logs {
+                self.add_event(&mut range_cursor, &log).await?;
+            }
+            range_cursor.next_block = to_block + 1;
+            self.save_cursor(&range_cursor)?;
+            *cursor = range_cursor;
+        }
+
+        Ok(guard)
+    }
+
+    async fn add_event(&self, cursor: &mut EvmScanCursor, log: &Log) -> Result<(), String> {
+        let abi = &self.config.event_abi;
+        let Some(selector) = log.inner.data.topics().first() else {
+            return Ok(());
+        };
+        let transaction_hash = log.transaction_hash.ok_or("Log without transaction hash")?;
+        let tx_hash = transaction_hash.to_vec();
+
+        // Events the contract emitted with unexpected values are skipped, they can't be fixed by
+        // scanning again.
+        let result = if *selector == abi.peg_out_initiated.selector() {
+            let event = EventParams::decode(&abi.peg_out_initiated, log)?.peg_out_initiated()?;
+            to_peg_out_event(&event, self.block_timestamp(log).await?, tx_hash)
+                .map(|event| cursor.peg_out_init_events.push(event))
+        } else if *selector == abi.peg_out_burnt.selector() {
+            let event = EventParams::decode(&abi.peg_out_burnt, log)?.peg_out_burnt()?;
+            let position = L2LogPosition::from_log(log)?;
+            to_peg_out_burnt_event(&event, self.block_timestamp(log).await?, tx_hash).map(|event| {
+                cursor.peg_out_burnt_events.push(event);
+                cursor.peg_out_burnt_positions.push(position);
+            })
+        } else if *selector == abi.peg_in_minted.selector() {
+            let event = EventParams::decode(&abi.peg_in_minted, log)?.peg_in_minted()?;
+            to_peg_in_event(&event).map(|event| cursor.peg_in_minted_events.push(event))
+        } else {
+            Ok(())
+        };
+        if let Err(e) = result {
+            eprintln!("Skipping bridge event of transaction {transaction_hash}: {e}");
+        }
+
+        Ok(())
+    }
+
+    // Not all nodes include the block timestamp in logs.
+    async fn block_timestamp(&self, log: &Log) -> Result<u32, String> {
+        let timestamp = match log.block_timestamp {
+            Some(timestamp) => timestamp,
+            None => {
+                let block_number = log.block_number.ok_or("Log without block number")?;
+                self.provider
+                    .get_block_by_number(BlockNumberOrTag::Number(block_number), false)
+                    .await
+                    .map_err(|e| e.to_string())?
+                    .ok_or_else(|| format!("Block {block_number} not found"))?
+                    .header
+                    .timestamp
+            }
+        };
+
+        u32::try_from(timestamp).map_err(|_| format!("Block timestamp {timestamp} out of range"))
+    }
+
+    async fn load_cursor(&self) -> Result<EvmScanCursor, String> {
+        let chain_id = self
+            .provider
+            .get_chain_id()
+            .await
+            .map_err(|e| e.to_string())?;
+        if chain_id != self.config.chain_id {
+            return Err(format!(
+                "Expected chain ID {}, the RPC node serves chain ID {chain_id}",
+                self.config.chain_id
+            ));
+        }
+
+        let saved_cursor = match &self.config.cursor_path {
+            Some(path) if path.exists() => Some(
+                serde_json::from_str::<EvmScanCursor>(
+                    &fs::read_to_string(path).map_err(|e| e.to_string())?,
+                )
+                .map_err(|e| e.to_string())?,
+            ),
+            _ => None,
+        };
+        match saved_cursor {
+            Some(cursor)
+                if cursor.chain_id != self.config.chain_id
+                    || cursor.bridge_address != self.config.bridge_address =>
+            {
+                Err(format!(
+                    "The scan cursor belongs to the bridge {} of chain ID {}",
+                    cursor.bridge_address, cursor.chain_id
+                ))
+            }
+            // Saved before the positions of the burn events were kept, scanned again.
+            Some(cursor)
+                if cursor.peg_out_burnt_positions.len() != cursor.peg_out_burnt_events.len() =>
+            {
+                Ok(self.new_cursor())
+            }
+            Some(cursor) => Ok(cursor),
+            None => Ok(self.new_cursor()),
+        }
+    }
+
+    fn new_cursor(&self) -> EvmScanCursor {
+        EvmScanCursor {
+            chain_id: self.config.chain_id,
+            bridge_address: self.config.bridge_address,
+            next_block: self.config.bridge_creation_block,
+            peg_out_init_events: vec![],
+            peg_out_burnt_events: vec![],
+            peg_out_burnt_positions: vec![],
+            peg_in_minted_events: vec![],
+        }
+    }
+
+    // Burn events are proven as declared by `IBridge`, contracts declaring them otherwise can't
+    // be proven. Neither can any burn without a finality node.
+    fn can_prove_burn_events(&self) -> bool {
+        self.finality_provider.is_some()
+            && self.config.event_abi.peg_out_burnt == EvmEventAbi::default().peg_out_burnt
+    }
+
+    fn save_cursor(&self, cursor: &EvmScanCursor) -> Result<(), String> {
+        let Some(path) = &self.config.cursor_path else {
+            return Ok(());
+        };
+        if let Some(directory) = path.parent() {
+            fs::create_dir_all(directory).map_err(|e| e.to_string())?;
+        }
+        let contents = serde_json::to_string(cursor).map_err(|e| e.to_string())?;
+        // Written aside first, so that an interrupted write doesn't lose the cursor.
+        let temporary_path = path.with_extension("tmp");
+        fs::write(&temporary_path, contents).map_err(|e| e.to_string())?;
+        fs::rename(&temporary_path, path).map_err(|e| e.to_string())
+    }
+}
+
+#[async_trait]
+impl ChainAdaptor for EvmAdaptor {
+    async fn get_peg_out_init_event(&self) -> Result<Vec<PegOutEvent>, String> {
+        let cursor = self.scan().await?;
+        Ok(cursor.as_ref().unwrap().peg_out_init_events.clone())
+    }
+
+    async fn get_peg_out_burnt_event(&self) -> Result<Vec<PegOutBurntEvent>, String> {
+        let cursor = self.scan().await?;
+        Ok(cursor.as_ref().unwrap().peg_out_burnt_events.clone())
+    }
+
+    async fn get_peg_in_minted_event(&self) -> Result<Vec<PegInEvent>, String> {
+        let cursor = self.scan().await?;
+        Ok(cursor.as_ref().unwrap().peg_in_minted_events.clone())
+    }
+
+    // The finalized block of the finality node, see `EvmAdaptorConfig::finality_rpc_url`.
+    async fn get_finalized_block_hash(&self) -> Result<Option<B256>, String> {
+        match &self.finality_provider {
+            Some(finality_provider) if self.can_prove_burn_events() => {
+                get_block_hash(finality_provider, BlockNumberOrTag::Finalized)
+                    .await
+                    .map(Some)
+            }
+            _ => Ok(None),
+        }
+    }
+
+    async fn get_peg_out_burnt_proofs(
+        &self,
+        source_txids: &[Txid],
+        finalized_block_hash: &B256,
+    ) -> Result<Vec<L2Proof>, String> {
+        if !self.can_prove_burn_events() {
+            return Ok(vec![]);
+        }
+        let positions = {
+            let cursor = self.scan().await?;
+            let cursor = cursor.as_ref().unwrap();
+            cursor
+                .peg_out_burnt_events
+                .iter()
+                .zip(&cursor.peg_out_burnt_positions)
+                .filter(|(event, _)| source_txids.contains(&event.source_outpoint.txid))
+                .map(|(_, position)| *position)
+                .collect::<Vec<_>>()
+        };
+
+        self.proof_builder
+            .build(&self.provider, &positions, finalized_block_hash)
+            .await
+    }
+}
+
+impl EventParams {
+    fn decode(event: &Event, log: &Log) -> Result<Self, String> {
+        let data = &log.inner.data;
+        let decoded = event
+            .decode_log_parts(data.topics().iter().copied(), &data.data, true)
+            .map_err(|e| format!("Failed to decode {} event: {e}", event.name))?;
+
+        let mut indexed = decoded.indexed.into_iter();
+        let mut body = decoded.body.into_iter();
+        let params = event
+            .inputs
+            .iter()
+            .map(|param| {
+                let value = match param.indexed {
+                    true => indexed.next(),
+                    false => body.next(),
+                };
+                value
+                    .map(|value| (param.name.clone(), value))
+                    .ok_or_else(|| format!("Missing {} parameter {}", event.name, param.name))
+            })
+            .collect::<Result<_, _>>()?;
+
+        Ok(Self(params))
+    }
+
+    fn get(&self, name: &str) -> Result<&DynSolValue, String> {
+        self.0
+            .get(name)
+            .ok_or_else(|| format!("Missing event parameter {name}"))
+    }
+
+    fn invalid(name: &str) -> String { format!("Invalid event parameter {name}") }
+
+    fn address(&self, name: &str) -> Result<EvmAddress, String> {
+        self.get(name)?
+            .as_address()
+            .ok_or_else(|| Self::invalid(name))
+    }
+
+    fn uint(&self, name: &str) -> Result<U256, String> {
+        self.get(name)?
+            .as_uint()
+            .map(|(value, _)| value)
+            .ok_or_else(|| Self::invalid(name))
+    }
+
+    fn string(&self, name: &str) -> Result<String, String> {
+        self.get(name)?
+            .as_str()
+            .map(String::from)
+            .ok_or_else(|| Self::invalid(name))
+    }
+
+    fn bytes(&self, name: &str) -> Result<Bytes, String> {
+        self.get(name)?
+            .as_bytes()
+            .map(Bytes::copy_from_slice)
+            .ok_or_else(|| Self::invalid(name))
+    }
+
+    fn bytes32(&self, name: &str) -> Result<B256, String> {
+        match self.get(name)?.as_fixed_bytes() {
+            Some((bytes, 32)) => Ok(B256::from_slice(bytes)),
+            _ => Err(Self::invalid(name)),
+        }
+    }
+
+    fn outpoint(&self, name: &str) -> Result<IBridge::Outpoint, String> {
+        match self.get(name)?.as_tuple() {
+            Some([DynSolValue::FixedBytes(tx_id, 32), DynSolValue::Uint(v_out, _)]) => {
+                Ok(IBridge::Outpoint {
+                    txId: *tx_id,
+                    vOut: *v_out,
+                })
+            }
+            _ => Err(Self::invalid(name)),
+        }
+    }
+
+    fn peg_out_initiated(&self) -> Result<IBridge::PegOutInitiated, String> {
+        Ok(IBridge::PegOutInitiated {
+            withdrawer: self.address("withdrawer")?,
+            destination_address: self.string("destination_address")?,
+            source_outpoint: self.outpoint("source_outpoint")?,
+            amount: self.uint("amount")?,
+            operator_pubKey: self.bytes("operator_pubKey")?,
+        })
+    }
+
+    fn peg_out_burnt(&self) -> Result<IBridge::PegOutBurnt, String> {
+        Ok(IBridge::PegOutBurnt {
+            withdrawer: self.address("withdrawer")?,
+            source_outpoint: self.outpoint("source_outpoint")?,
+            amount: self.uint("amount")?,
+            operator_pubKey: self.bytes("operator_pubKey")?,
+        })
+    }
+
+    fn peg_in_minted(&self) -> Result<IBridge::PegInMinted, String> {
+        Ok(IBridge::PegInMinted {
+            depositor: self.address("depositor")?,
+            amount: self.uint("amount")?,
+            depositorPubKey: self.bytes32("depositorPubKey")?,
+        })
+    }
+}
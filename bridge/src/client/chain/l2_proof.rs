@@ -0,0 +1,340 @@
+use alloy::{
+    primitives::{keccak256, Address as EvmAddress, B256},
+    sol_types::SolEvent,
+};
+use serde::{Deserialize, Serialize};
+use std::mem::size_of;
+
+use crate::error::L2Error;
+
+use super::{
+    chain::PegOutBurntEvent,
+    ethereum_adaptor::{to_peg_out_burnt_event, IBridge},
+};
+
+// Indexes of the block header fields used to verify the proof, see the Ethereum yellow paper.
+const HEADER_PARENT_HASH_INDEX: usize = 0;
+const HEADER_TRANSACTIONS_ROOT_INDEX: usize = 4;
+const HEADER_RECEIPTS_ROOT_INDEX: usize = 5;
+const HEADER_TIMESTAMP_INDEX: usize = 11;
+
+const BRANCH_NODE_LENGTH: usize = 17;
+const SHORT_NODE_LENGTH: usize = 2; // extension or leaf node
+
+/// Inclusion proof of a `PegOutBurnt` event of the bridge contract in a finalized L2 block.
+/// The transaction and its receipt are proven with Merkle-Patricia trie proofs against the roots
+/// of their block header, which is linked to the finalized block by its descendants' headers.
+#[derive(Serialize, Deserialize, Eq, PartialEq, Clone, Debug)]
+pub struct L2Proof {
+    // RLP encoded block headers, from the block of the burn transaction to the finalized block.
+    pub block_headers: Vec<Vec<u8>>,
+    pub transaction_index: u64,
+    // Trie nodes on the path to the transaction, from the root of the transactions trie.
+    pub transaction_proof: Vec<Vec<u8>>,
+    // Trie nodes on the path to the receipt, from the root of the receipts trie.
+    pub receipt_proof: Vec<Vec<u8>>,
+    // Index of the burn event among the logs of the receipt.
+    pub log_index: usize,
+}
+
+/// L2 chain state the proofs are verified against.
+#[derive(Eq, PartialEq, Clone, Debug)]
+pub struct L2ProofConfig {
+    pub bridge_address: EvmAddress,
+    pub finalized_block_hash: B256,
+}
+
+struct BlockHeader {
+    transactions_root: B256,
+    receipts_root: B256,
+    timestamp: u64,
+}
+
+impl L2Proof {
+    /// Verifies the proof and returns the proven burn event. Its `tx_hash` is the hash of the
+    /// proven L2 transaction and its `timestamp` the one of its block.
+    pub fn verify(&self, config: &L2ProofConfig) -> Result<PegOutBurntEvent, L2Error> {
+        let header = self.verify_block_headers(&config.finalized_block_hash)?;
+
+        let key = rlp_encode_u64(self.transaction_index);
+        let transaction =
+            verify_trie_proof(&header.transactions_root, &key, &self.transaction_proof)
+                .ok_or(L2Error::InvalidTrieProof)?;
+        let receipt = verify_trie_proof(&header.receipts_root, &key, &self.receipt_proof)
+            .ok_or(L2Error::InvalidTrieProof)?;
+
+        let (address, topics, data) = decode_receipt_log(receipt, self.log_index)?;
+        if address != config.bridge_address
+            || topics.first() != Some(&IBridge::PegOutBurnt::SIGNATURE_HASH)
+        {
+            return Err(L2Error::BurnEventNotFound);
+        }
+        let event = IBridge::PegOutBurnt::decode_raw_log(topics, data, true)
+            .map_err(|e| L2Error::InvalidBurnEvent(e.to_string()))?;
+        let timestamp =
+            u32::try_from(header.timestamp).map_err(|_| L2Error::InvalidBlockHeader(0))?;
+
+        to_peg_out_burnt_event(&event, timestamp, keccak256(transaction).to_vec())
+            .map_err(L2Error::InvalidBurnEvent)
+    }
+
+    // Checks that every header is the parent of the next one and that the last one is the
+    // finalized block. Returns the first header, the one of the burn transaction's block.
+    fn verify_block_headers(&self, finalized_block_hash: &B256) -> Result<BlockHeader, L2Error> {
+        let mut headers = self.block_headers.iter().enumerate().rev();
+        let (_, finalized_header) = headers.next().ok_or(L2Error::BlockNotFinalized)?;
+        if keccak256(finalized_header) != *finalized_block_hash {
+            return Err(L2Error::BlockNotFinalized);
+        }
+
+        let mut child_header = finalized_header;
+        for (index, header) in headers {
+            let parent_hash = header_field(child_header, HEADER_PARENT_HASH_INDEX)
+                .ok_or(L2Error::InvalidBlockHeader(index + 1))?;
+            if parent_hash != keccak256(header).as_slice() {
+                return Err(L2Error::InvalidBlockHeader(index));
+            }
+            child_header = header;
+        }
+
+        let field = |field_index| {
+            header_field(child_header, field_index).ok_or(L2Error::InvalidBlockHeader(0))
+        };
+        let hash = |field_index| {
+            field(field_index).and_then(|value| {
+                <[u8; 32]>::try_from(value)
+                    .map(B256::from)
+                    .map_err(|_| L2Error::InvalidBlockHeader(0))
+            })
+        };
+        Ok(BlockHeader {
+            transactions_root: hash(HEADER_TRANSACTIONS_ROOT_INDEX)?,
+            receipts_root: hash(HEADER_RECEIPTS_ROOT_INDEX)?,
+            timestamp: rlp_decode_u64(field(HEADER_TIMESTAMP_INDEX)?)
+                .ok_or(L2Error::InvalidBlockHeader(0))?,
+        })
+    }
+}
+
+// An RLP item: `payload` is the content of a string, or the encoded items of a list.
+struct RlpItem<'a> {
+    raw: &'a [u8],
+    payload: &'a [u8],
+    is_list: bool,
+}
+
+// Decodes the first RLP item of `data`, returns it with the remaining data.
+fn rlp_decode_item(data: &[u8]) -> Option<(RlpItem, &[u8])> {
+    let (&prefix, rest) = data.split_first()?;
+    let (is_list, length_of_length, length) = match prefix {
+        0x00..=0x7f => {
+            let item = RlpItem {
+                raw: &data[..1],
+                payload: &data[..1],
+                is_list: false,
+            };
+            return Some((item, rest));
+        }
+        0x80..=0xb7 => (false, 0, (prefix - 0x80) as usize),
+        0xb8..=0xbf => {
+            let length_of_length = (prefix - 0xb7) as usize;
+            (
+                false,
+                length_of_length,
+                rlp_decode_length(rest.get(..length_of_length)?)?,
+            )
+        }
+        0xc0..=0xf7 => (true, 0, (prefix - 0xc0) as usize),
+        0xf8..=0xff => {
+            let length_of_length = (prefix - 0xf7) as usize;
+            (
+                true,
+                length_of_length,
+                rlp_decode_length(rest.get(..length_of_length)?)?,
+            )
+        }
+    };
+
+    let end = 1 + length_of_length.checked_add(length)?;
+    let item = RlpItem {
+        raw: data.get(..end)?,
+        payload: data.get(1 + length_of_length..end)?,
+        is_list,
+    };
+    Some((item, &data[end..]))
+}
+
+fn rlp_decode_length(bytes: &[u8]) -> Option<usize> {
+    rlp_decode_u64(bytes).and_then(|length| usize::try_from(length).ok())
+}
+
+// Decodes `data` made of a single RLP list, returns its items.
+fn rlp_decode_list(data: &[u8]) -> Option<Vec<RlpItem>> {
+    match rlp_decode_item(data)? {
+        (list, []) if list.is_list => {
+            let mut items = vec![];
+            let mut rest = list.payload;
+            while !rest.is_empty() {
+                let (item, remaining) = rlp_decode_item(rest)?;
+                items.push(item);
+                rest = remaining;
+            }
+            Some(items)
+        }
+        _ => None,
+    }
+}
+
+fn rlp_decode_u64(bytes: &[u8]) -> Option<u64> {
+    if bytes.len() > size_of::<u64>() || bytes.first() == Some(&0) {
+        return None;
+    }
+    Some(
+        bytes
+            .iter()
+            .fold(0, |value, byte| (value << 8) | *byte as u64),
+    )
+}
+
+// Keys of the transactions and receipts tries are the RLP encoded transaction indexes.
+pub fn rlp_encode_u64(value: u64) -> Vec<u8> {
+    let bytes = value.to_be_bytes();
+    let bytes = &bytes[bytes.iter().take_while(|byte| **byte == 0).count()..];
+    match bytes {
+        [byte] if *byte < 0x80 => vec![*byte],
+        _ => [&[0x80 + bytes.len() as u8], bytes].concat(),
+    }
+}
+
+pub(super) fn header_field(header: &[u8], index: usize) -> Option<&[u8]> {
+    let fields = rlp_decode_list(header)?;
+    fields
+        .get(index)
+        .filter(|field| !field.is_list)
+        .map(|field| field.payload)
+}
+
+#[derive(Clone, Copy)]
+enum NodeReference<'a> {
+    Hash(B256),
+    Inline(&'a [u8]), // nodes shorter than 32 bytes are embedded in their parent
+}
+
+impl<'a> NodeReference<'a> {
+    fn from_item(item: &RlpItem<'a>) -> Option<Self> {
+        if item.is_list {
+            Some(NodeReference::Inline(item.raw))
+        } else {
+            <[u8; 32]>::try_from(item.payload)
+                .ok()
+                .map(|hash| NodeReference::Hash(B256::from(hash)))
+        }
+    }
+}
+
+// Walks the Merkle-Patricia trie of `root` along `key` using the `proof` nodes, returns the value
+// stored at `key`.
+fn verify_trie_proof<'a>(root: &B256, key: &[u8], proof: &'a [Vec<u8>]) -> Option<&'a [u8]> {
+    let nibbles = to_nibbles(key);
+    let mut path = &nibbles[..];
+    let mut proof_nodes = proof.iter();
+    let mut reference = NodeReference::Hash(*root);
+
+    loop {
+        let node = match reference {
+            NodeReference::Hash(hash) => {
+                let node = proof_nodes.next()?;
+                if keccak256(node) != hash {
+                    return None;
+                }
+                node.as_slice()
+            }
+            NodeReference::Inline(node) => node,
+        };
+
+        let items = rlp_decode_list(node)?;
+        match items.len() {
+            BRANCH_NODE_LENGTH => match path.split_first() {
+                Some((nibble, rest)) => {
+                    reference = NodeReference::from_item(&items[*nibble as usize])?;
+                    path = rest;
+                }
+                None => {
+                    let value = &items[BRANCH_NODE_LENGTH - 1];
+                    return (!value.is_list && !value.payload.is_empty()).then_some(value.payload);
+                }
+            },
+            SHORT_NODE_LENGTH => {
+                let (is_leaf, node_path) = decode_hex_prefix(items[0].payload)?;
+                path = path.strip_prefix(node_path.as_slice())?;
+                if is_leaf {
+                    return (path.is_empty() && !items[1].is_list).then_some(items[1].payload);
+                }
+                reference = NodeReference::from_item(&items[1])?;
+            }
+            _ => return None,
+        }
+    }
+}
+
+pub(super) fn to_nibbles(bytes: &[u8]) -> Vec<u8> {
+    bytes
+        .iter()
+        .flat_map(|byte| [byte >> 4, byte & 0x0f])
+        .collect()
+}
+
+// Decodes the hex-prefix encoded path of a leaf or extension node.
+fn decode_hex_prefix(encoded: &[u8]) -> Option<(bool, Vec<u8>)> {
+    let (first, rest) = encoded.split_first()?;
+    let flag = first >> 4;
+    if flag > 3 {
+        return None;
+    }
+
+    let mut nibbles = vec![];
+    if flag & 1 == 1 {
+        nibbles.push(first & 0x0f);
+    }
+    nibbles.extend(to_nibbles(rest));
+    Some((flag & 2 == 2, nibbles))
+}
+
+// Returns the emitter, topics and data of the log `log_index` of a successful transaction
+// receipt.
+fn decode_receipt_log(
+    receipt: &[u8],
+    log_index: usize,
+) -> Result<(EvmAddress, Vec<B256>, &[u8]), L2Error> {
+    // Typed receipts are prefixed with the transaction type.
+    let receipt = match receipt.first() {
+        Some(0x00..=0x7f) => &receipt[1..],
+        _ => receipt,
+    };
+    let fields = rlp_decode_list(receipt).ok_or(L2Error::InvalidReceipt)?;
+    let [status, _cumulative_gas_used, _logs_bloom, logs] = &fields[..] else {
+        return Err(L2Error::InvalidReceipt);
+    };
+    if status.payload != [1] {
+        return Err(L2Error::FailedTransaction);
+    }
+
+    let logs = rlp_decode_list(logs.raw).ok_or(L2Error::InvalidReceipt)?;
+    let log = logs.get(log_index).ok_or(L2Error::BurnEventNotFound)?;
+    let log_fields = rlp_decode_list(log.raw).ok_or(L2Error::InvalidReceipt)?;
+    let [address, topics, data] = &log_fields[..] else {
+        return Err(L2Error::InvalidReceipt);
+    };
+
+    let address = <[u8; 20]>::try_from(address.payload)
+        .map(EvmAddress::from)
+        .map_err(|_| L2Error::InvalidReceipt)?;
+    let topics = rlp_decode_list(topics.raw)
+        .ok_or(L2Error::InvalidReceipt)?
+        .iter()
+        .map(|topic| <[u8; 32]>::try_from(topic.payload).map(B256::from))
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|_| L2Error::InvalidReceipt)?;
+
+    Ok((address, topics, data.payload))
+}
@@ -0,0 +1,545 @@
+use std::{collections::BTreeMap, sync::Mutex};
+
+use alloy::{
+    eips::BlockNumberOrTag,
+    primitives::{keccak256, B256},
+    providers::{Provider, RootProvider},
+    rpc::types::Log,
+    transports::http::{Client, Http},
+};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+
+use super::l2_proof::{header_field, rlp_encode_u64, to_nibbles, L2Proof};
+
+// Block header fields in their RLP order, and whether they are quantities. The fields after
+// `nonce` were added by later forks, a header has all of them up to the ones of its fork.
+const HEADER_FIELDS: [(&str, bool); 21] = [
+    ("parentHash", false),
+    ("sha3Uncles", false),
+    ("miner", false),
+    ("stateRoot", false),
+    ("transactionsRoot", false),
+    ("receiptsRoot", false),
+    ("logsBloom", false),
+    ("difficulty", true),
+    ("number", true),
+    ("gasLimit", true),
+    ("gasUsed", true),
+    ("timestamp", true),
+    ("extraData", false),
+    ("mixHash", false),
+    ("nonce", false),
+    ("baseFeePerGas", true),
+    ("withdrawalsRoot", false),
+    ("blobGasUsed", true),
+    ("excessBlobGas", true),
+    ("parentBeaconBlockRoot", false),
+    ("requestsHash", false),
+];
+const REQUIRED_HEADER_FIELDS: usize = 15;
+
+const HASH_REFERENCE_LENGTH: usize = 32; // shorter trie nodes are embedded in their parent
+const LEGACY_TRANSACTION_TYPE: u64 = 0;
+
+/// Position of a log on the L2 chain.
+#[derive(Serialize, Deserialize, Eq, PartialEq, Clone, Copy, Debug)]
+pub struct L2LogPosition {
+    pub block_number: u64,
+    pub transaction_index: u64,
+    // Index of the log among all the logs of its block.
+    pub log_index: u64,
+}
+
+impl L2LogPosition {
+    pub fn from_log<T>(log: &Log<T>) -> Result<Self, String> {
+        Ok(Self {
+            block_number: log.block_number.ok_or("Log without block number")?,
+            transaction_index: log
+                .transaction_index
+                .ok_or("Log without transaction index")?,
+            log_index: log.log_index.ok_or("Log without log index")?,
+        })
+    }
+}
+
+/// Builds the `L2Proof`s of logs from the blocks, raw transactions and receipts served by an L2
+/// node. The headers of finalized blocks never change, they are only fetched once.
+#[derive(Default)]
+pub struct L2ProofBuilder {
+    // RLP encoded headers of finalized blocks by number.
+    headers: Mutex<BTreeMap<u64, Vec<u8>>>,
+}
+
+impl L2ProofBuilder {
+    /// Proves the logs at `positions` against the finalized block `finalized_block_hash`. The
+    /// logs of the blocks after it are skipped.
+    pub async fn build(
+        &self,
+        provider: &RootProvider<Http<Client>>,
+        positions: &[L2LogPosition],
+        finalized_block_hash: &B256,
+    ) -> Result<Vec<L2Proof>, String> {
+        let finalized_block = request(
+            provider,
+            "eth_getBlockByHash",
+            json!([finalized_block_hash, false]),
+        )
+        .await?;
+        // Its header is checked against its hash when encoded, the headers of its ancestors are
+        // linked to it.
+        if hash(&finalized_block, "hash")? != *finalized_block_hash {
+            return Err(String::from("The L2 node served another finalized block"));
+        }
+        let finalized_block_number = quantity(&finalized_block, "number")?;
+        let positions = positions
+            .iter()
+            .filter(|position| position.block_number <= finalized_block_number)
+            .collect::<Vec<_>>();
+        let Some(first_block_number) = positions.iter().map(|position| position.block_number).min()
+        else {
+            return Ok(vec![]);
+        };
+
+        let headers = self
+            .block_headers(provider, first_block_number, &finalized_block)
+            .await?;
+
+        let mut blocks = BTreeMap::new();
+        for position in &positions {
+            if !blocks.contains_key(&position.block_number) {
+                let block = get_block_contents(provider, position.block_number).await?;
+                blocks.insert(position.block_number, block);
+            }
+        }
+
+        positions
+            .iter()
+            .map(|position| {
+                let block = &blocks[&position.block_number];
+                let log_index = usize::try_from(position.transaction_index)
+                    .ok()
+                    .and_then(|index| block.log_indexes.get(index))
+                    .and_then(|log_indexes| {
+                        log_indexes
+                            .iter()
+                            .position(|log_index| *log_index == position.log_index)
+                    })
+                    .ok_or_else(|| format!("Log {position:?} not found"))?;
+
+                Ok(L2Proof {
+                    block_headers: headers[(position.block_number - first_block_number) as usize..]
+                        .to_vec(),
+                    transaction_index: position.transaction_index,
+                    transaction_proof: ordered_trie_proof(
+                        &block.transactions,
+                        position.transaction_index,
+                    )
+                    .1,
+                    receipt_proof: ordered_trie_proof(&block.receipts, position.transaction_index)
+                        .1,
+                    log_index,
+                })
+            })
+            .collect()
+    }
+
+    // The RLP encoded headers from the block `first_block_number` to `finalized_block`, each the
+    // parent of the next one.
+    async fn block_headers(
+        &self,
+        provider: &RootProvider<Http<Client>>,
+        first_block_number: u64,
+        finalized_block: &Value,
+    ) -> Result<Vec<Vec<u8>>, String> {
+        let finalized_block_number = quantity(finalized_block, "number")?;
+        let finalized_header = encode_block_header(finalized_block)?;
+
+        let missing_block_numbers = {
+            let headers = self.headers.lock().unwrap();
+            (first_block_number..finalized_block_number)
+                .filter(|number| !headers.contains_key(number))
+                .collect::<Vec<_>>()
+        };
+        for number in missing_block_numbers {
+            let block = request(
+                provider,
+                "eth_getBlockByNumber",
+                json!([BlockNumberOrTag::Number(number), false]),
+            )
+            .await?;
+            let header = encode_block_header(&block)?;
+            self.headers.lock().unwrap().insert(number, header);
+        }
+
+        let mut headers = self.headers.lock().unwrap();
+        headers.insert(finalized_block_number, finalized_header);
+        let chain = headers
+            .range(first_block_number..=finalized_block_number)
+            .map(|(_, header)| header.clone())
+            .collect::<Vec<_>>();
+        if chain
+            .windows(2)
+            .any(|pair| header_field(&pair[1], 0) != Some(keccak256(&pair[0]).as_slice()))
+        {
+            // Not fetched from the finalized chain, none of them can be trusted.
+            headers.clear();
+            return Err(String::from("The L2 block headers are not linked"));
+        }
+
+        Ok(chain)
+    }
+}
+
+/// Hash of the block `block`, e.g. of the finalized block, computed from its header.
+pub async fn get_block_hash(
+    provider: &RootProvider<Http<Client>>,
+    block: BlockNumberOrTag,
+) -> Result<B256, String> {
+    let block = request(provider, "eth_getBlockByNumber", json!([block, false])).await?;
+    encode_block_header(&block).map(keccak256)
+}
+
+// The contents of a block its logs are proven with.
+struct BlockContents {
+    transactions: Vec<Vec<u8>>,
+    receipts: Vec<Vec<u8>>,
+    // Indexes in the block of the logs of each receipt.
+    log_indexes: Vec<Vec<u64>>,
+}
+
+// Fetches the raw transactions and the receipts of the block `number`. The roots of their tries
+// are checked against the block header.
+async fn get_block_contents(
+    provider: &RootProvider<Http<Client>>,
+    number: u64,
+) -> Result<BlockContents, String> {
+    let block_number = BlockNumberOrTag::Number(number);
+    let block = request(
+        provider,
+        "eth_getBlockByNumber",
+        json!([block_number, false]),
+    )
+    .await?;
+    let transaction_count = block
+        .get("transactions")
+        .and_then(Value::as_array)
+        .ok_or("Block without transactions")?
+        .len();
+
+    let mut transactions = Vec::with_capacity(transaction_count);
+    for index in 0..transaction_count {
+        let transaction = request(
+            provider,
+            "eth_getRawTransactionByBlockNumberAndIndex",
+            json!([block_number, format!("{index:#x}")]),
+        )
+        .await?;
+        transactions.push(hex_bytes(
+            transaction.as_str().ok_or("Invalid raw transaction")?,
+        )?);
+    }
+    if ordered_trie_root(&transactions) != hash(&block, "transactionsRoot")? {
+        return Err(format!(
+            "Transactions of block {number} don't match its header"
+        ));
+    }
+
+    let receipt_values = request(provider, "eth_getBlockReceipts", json!([block_number])).await?;
+    let receipt_values = receipt_values.as_array().ok_or("Invalid block receipts")?;
+    let receipts = receipt_values
+        .iter()
+        .map(encode_receipt)
+        .collect::<Result<Vec<_>, _>>()?;
+    if ordered_trie_root(&receipts) != hash(&block, "receiptsRoot")? {
+        return Err(format!("Receipts of block {number} don't match its header"));
+    }
+    let log_indexes = receipt_values
+        .iter()
+        .map(|receipt| {
+            logs(receipt)?
+                .iter()
+                .map(|log| quantity(log, "logIndex"))
+                .collect::<Result<Vec<_>, _>>()
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    Ok(BlockContents {
+        transactions,
+        receipts,
+        log_indexes,
+    })
+}
+
+async fn request(
+    provider: &RootProvider<Http<Client>>,
+    method: &'static str,
+    params: Value,
+) -> Result<Value, String> {
+    let result: Value = provider
+        .client()
+        .request(method, params)
+        .await
+        .map_err(|e| e.to_string())?;
+    match result {
+        Value::Null => Err(format!("No result for {method}")),
+        result => Ok(result),
+    }
+}
+
+/// RLP encodes the header of a block returned by `eth_getBlockByNumber`, checked against the
+/// hash of the block.
+pub fn encode_block_header(block: &Value) -> Result<Vec<u8>, String> {
+    let mut fields = vec![];
+    for (index, (name, is_quantity)) in HEADER_FIELDS.iter().enumerate() {
+        let value = match block.get(*name).and_then(Value::as_str) {
+            Some(value) => value,
+            None if index >= REQUIRED_HEADER_FIELDS => break,
+            None => return Err(format!("Block header without {name}")),
+        };
+        fields.push(match is_quantity {
+            true => rlp_encode_bytes(&quantity_bytes(value)?),
+            false => rlp_encode_bytes(&hex_bytes(value)?),
+        });
+    }
+
+    let header = rlp_encode_list(&fields);
+    if keccak256(&header) != hash(block, "hash")? {
+        return Err(format!(
+            "Unsupported header of block {}",
+            field(block, "number")?
+        ));
+    }
+    Ok(header)
+}
+
+/// Encodes a receipt returned by `eth_getBlockReceipts` the way it is stored in the receipts
+/// trie.
+pub fn encode_receipt(receipt: &Value) -> Result<Vec<u8>, String> {
+    let logs = logs(receipt)?
+        .iter()
+        .map(|log| {
+            let topics = log
+                .get("topics")
+                .and_then(Value::as_array)
+                .ok_or("Log without topics")?
+                .iter()
+                .map(|topic| {
+                    hex_bytes(topic.as_str().ok_or("Invalid log topic")?)
+                        .map(|topic| rlp_encode_bytes(&topic))
+                })
+                .collect::<Result<Vec<_>, _>>()?;
+            Ok(rlp_encode_list(&[
+                rlp_encode_bytes(&bytes(log, "address")?),
+                rlp_encode_list(&topics),
+                rlp_encode_bytes(&bytes(log, "data")?),
+            ]))
+        })
+        .collect::<Result<Vec<_>, String>>()?;
+
+    let encoded = rlp_encode_list(&[
+        rlp_encode_bytes(&quantity_bytes(field(receipt, "status")?)?),
+        rlp_encode_bytes(&quantity_bytes(field(receipt, "cumulativeGasUsed")?)?),
+        rlp_encode_bytes(&bytes(receipt, "logsBloom")?),
+        rlp_encode_list(&logs),
+    ]);
+    // Typed receipts are prefixed with the transaction type.
+    match receipt.get("type").map(|_| quantity(receipt, "type")) {
+        None => Ok(encoded),
+        Some(Ok(LEGACY_TRANSACTION_TYPE)) => Ok(encoded),
+        Some(Ok(transaction_type)) => u8::try_from(transaction_type)
+            .map(|transaction_type| [vec![transaction_type], encoded].concat())
+            .map_err(|_| format!("Invalid transaction type {transaction_type}")),
+        Some(Err(e)) => Err(e),
+    }
+}
+
+fn logs(receipt: &Value) -> Result<&Vec<Value>, String> {
+    receipt
+        .get("logs")
+        .and_then(Value::as_array)
+        .ok_or_else(|| String::from("Receipt without logs"))
+}
+
+fn field<'a>(value: &'a Value, name: &str) -> Result<&'a str, String> {
+    value
+        .get(name)
+        .and_then(Value::as_str)
+        .ok_or_else(|| format!("Missing {name}"))
+}
+
+fn bytes(value: &Value, name: &str) -> Result<Vec<u8>, String> { hex_bytes(field(value, name)?) }
+
+fn hash(value: &Value, name: &str) -> Result<B256, String> {
+    bytes(value, name)?
+        .as_slice()
+        .try_into()
+        .map(B256::from)
+        .map_err(|_| format!("Invalid {name}"))
+}
+
+fn quantity(value: &Value, name: &str) -> Result<u64, String> {
+    let quantity = field(value, name)?;
+    quantity
+        .strip_prefix("0x")
+        .and_then(|hex| u64::from_str_radix(hex, 16).ok())
+        .ok_or_else(|| format!("Invalid {name} {quantity}"))
+}
+
+fn hex_bytes(hex: &str) -> Result<Vec<u8>, String> {
+    hex::decode(hex.strip_prefix("0x").unwrap_or(hex)).map_err(|e| e.to_string())
+}
+
+// Big endian bytes of a quantity of any size, without leading zeros.
+fn quantity_bytes(quantity: &str) -> Result<Vec<u8>, String> {
+    let digits = quantity
+        .strip_prefix("0x")
+        .ok_or_else(|| format!("Invalid quantity {quantity}"))?
+        .trim_start_matches('0');
+    match digits.len() % 2 {
+        0 => hex_bytes(digits),
+        _ => hex_bytes(&format!("0{digits}")),
+    }
+}
+
+pub fn rlp_encode_bytes(bytes: &[u8]) -> Vec<u8> {
+    match bytes {
+        [byte] if *byte < 0x80 => vec![*byte],
+        _ => [rlp_length_prefix(0x80, bytes.len()), bytes.to_vec()].concat(),
+    }
+}
+
+pub fn rlp_encode_list(items: &[Vec<u8>]) -> Vec<u8> {
+    let payload = items.concat();
+    [rlp_length_prefix(0xc0, payload.len()), payload].concat()
+}
+
+fn rlp_length_prefix(offset: u8, length: usize) -> Vec<u8> {
+    if length < 56 {
+        return vec![offset + length as u8];
+    }
+    let bytes = length.to_be_bytes();
+    let bytes = &bytes[bytes.iter().take_while(|byte| **byte == 0).count()..];
+    [vec![offset + 55 + bytes.len() as u8], bytes.to_vec()].concat()
+}
+
+/// Root of the Merkle-Patricia trie of `values` keyed by their RLP encoded indexes, like the
+/// transactions and receipts tries of a block.
+pub fn ordered_trie_root(values: &[Vec<u8>]) -> B256 { ordered_trie_proof(values, 0).0 }
+
+/// Root of the trie of `values`, see `ordered_trie_root`, and the nodes on the path to the value
+/// at `index` from the root, as verified by `L2Proof`.
+pub fn ordered_trie_proof(values: &[Vec<u8>], index: u64) -> (B256, Vec<Vec<u8>>) {
+    if values.is_empty() {
+        return (keccak256(rlp_encode_bytes(&[])), vec![]);
+    }
+
+    let mut entries = values
+        .iter()
+        .enumerate()
+        .map(|(index, value)| (to_nibbles(&rlp_encode_u64(index as u64)), value.as_slice()))
+        .collect::<Vec<TrieEntry>>();
+    entries.sort();
+    let key = to_nibbles(&rlp_encode_u64(index));
+    let (root, proof) = encode_trie_node(&entries, 0, Some(&key));
+
+    (keccak256(&root), [vec![root], proof].concat())
+}
+
+// The path of a value in nibbles, and the value.
+type TrieEntry<'a> = (Vec<u8>, &'a [u8]);
+
+// Encodes the node of `entries`, sorted and sharing the first `depth` nibbles of their paths.
+// Returns it with the nodes below it on the path to `key`, if `key` is under this node.
+fn encode_trie_node(
+    entries: &[TrieEntry],
+    depth: usize,
+    key: Option<&[u8]>,
+) -> (Vec<u8>, Vec<Vec<u8>>) {
+    if let [(path, value)] = entries {
+        let leaf = rlp_encode_list(&[
+            rlp_encode_bytes(&encode_hex_prefix(&path[depth..], true)),
+            rlp_encode_bytes(value),
+        ]);
+        return (leaf, vec![]);
+    }
+
+    let first_path = &entries[0].0;
+    let shared_length = (depth..first_path.len())
+        .take_while(|index| {
+            entries
+                .iter()
+                .all(|(path, _)| path.get(*index) == Some(&first_path[*index]))
+        })
+        .count();
+    if shared_length > 0 {
+        let shared_path = &first_path[depth..depth + shared_length];
+        let child_key =
+            key.filter(|key| key.get(depth..depth + shared_length) == Some(shared_path));
+        let (child, child_proof) = encode_trie_node(entries, depth + shared_length, child_key);
+        let extension = rlp_encode_list(&[
+            rlp_encode_bytes(&encode_hex_prefix(shared_path, false)),
+            node_reference(&child),
+        ]);
+        let proof = match child_key {
+            Some(_) => path_proof(&child, child_proof),
+            None => vec![],
+        };
+        return (extension, proof);
+    }
+
+    let mut items = vec![];
+    let mut proof = vec![];
+    for nibble in 0..16 {
+        let children = entries
+            .iter()
+            .filter(|(path, _)| path.get(depth) == Some(&nibble))
+            .cloned()
+            .collect::<Vec<_>>();
+        if children.is_empty() {
+            items.push(rlp_encode_bytes(&[]));
+            continue;
+        }
+        let child_key = key.filter(|key| key.get(depth) == Some(&nibble));
+        let (child, child_proof) = encode_trie_node(&children, depth + 1, child_key);
+        if child_key.is_some() {
+            proof = path_proof(&child, child_proof);
+        }
+        items.push(node_reference(&child));
+    }
+    let value = entries
+        .iter()
+        .find(|(path, _)| path.len() == depth)
+        .map_or(&[][..], |(_, value)| *value);
+    items.push(rlp_encode_bytes(value));
+
+    (rlp_encode_list(&items), proof)
+}
+
+// A child node is referenced by its hash, unless it is embedded.
+fn node_reference(node: &[u8]) -> Vec<u8> {
+    match node.len() < HASH_REFERENCE_LENGTH {
+        true => node.to_vec(),
+        false => rlp_encode_bytes(keccak256(node).as_slice()),
+    }
+}
+
+// The proof nodes from `node` down: embedded nodes are part of their parent.
+fn path_proof(node: &[u8], proof_below: Vec<Vec<u8>>) -> Vec<Vec<u8>> {
+    match node.len() < HASH_REFERENCE_LENGTH {
+        true => proof_below,
+        false => [vec![node.to_vec()], proof_below].concat(),
+    }
+}
+
+fn encode_hex_prefix(nibbles: &[u8], is_leaf: bool) -> Vec<u8> {
+    let flag = if is_leaf { 2 } else { 0 };
+    let (first, rest) = match nibbles.split_first() {
+        Some((nibble, rest)) if nibbles.len() % 2 == 1 => (((flag + 1) << 4) | nibble, rest),
+        _ => (flag << 4, nibbles),
+    };
+
+    std::iter::once(first)
+        .chain(rest.chunks(2).map(|pair| (pair[0] << 4) | pair[1]))
+        .collect()
+}
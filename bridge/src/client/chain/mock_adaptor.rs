@@ -1,8 +1,11 @@
+use alloy::primitives::B256;
 use async_trait::async_trait;
+use bitcoin::Txid;
 
 use super::{
     chain::{PegInEvent, PegOutBurntEvent, PegOutEvent},
     chain_adaptor::ChainAdaptor,
+    l2_proof::L2Proof,
 };
 
 pub struct MockAdaptor {
@@ -13,6 +16,8 @@ pub struct MockAdaptorConfig {
     pub peg_out_init_events: Option<Vec<PegOutEvent>>,
     pub peg_out_burnt_events: Option<Vec<PegOutBurntEvent>>,
     pub peg_out_minted_events: Option<Vec<PegInEvent>>,
+    pub peg_out_burnt_proofs: Option<Vec<L2Proof>>,
+    pub finalized_block_hash: Option<B256>,
 }
 
 impl MockAdaptor {
@@ -49,4 +54,25 @@ impl ChainAdaptor for MockAdaptor {
 
         Ok(vec![])
     }
+
+    async fn get_finalized_block_hash(&self) -> Result<Option<B256>, String> {
+        Ok(self
+            .config
+            .as_ref()
+            .and_then(|config| config.finalized_block_hash))
+    }
+
+    async fn get_peg_out_burnt_proofs(
+        &self,
+        _source_txids: &[Txid],
+        _finalized_block_hash: &B256,
+    ) -> Result<Vec<L2Proof>, String> {
+        if let Some(_config) = &self.config {
+            if let Some(_burnt_proofs) = &_config.peg_out_burnt_proofs {
+                return Ok(_burnt_proofs.clone());
+            }
+        }
+
+        Ok(vec![])
+    }
 }
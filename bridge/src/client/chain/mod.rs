@@ -1,4 +1,7 @@
 pub mod chain;
 pub mod chain_adaptor;
 pub mod ethereum_adaptor;
+pub mod evm_adaptor;
+pub mod l2_proof;
+pub mod l2_proof_builder;
 pub mod mock_adaptor;
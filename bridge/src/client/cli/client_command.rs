@@ -1,29 +1,51 @@
-use super::key_command::{Config, KeysCommand};
+use super::key_command::{role_graph_index, Config, KeysCommand};
 use super::utils::get_mock_chain_service;
 use crate::client::chain::chain_adaptor::get_chain_adaptor;
 use crate::client::client::BitVMClient;
 use crate::client::esplora::get_esplora_url;
-use crate::commitments::CommitmentMessageId;
+use crate::client::events::Role;
+use crate::client::signing_session::{
+    coordinator::{SigningCoordinator, DEFAULT_ROUND_TIMEOUT},
+    peer::run_verifier_session,
+    transport::{connect, serve_coordinator},
+};
+use crate::client::wallet::{
+    keys::HdWallet,
+    rescan::{rescan, DEFAULT_GAP_LIMIT},
+};
 use crate::common::ZkProofVerifyingKey;
 use crate::constants::DestinationNetwork;
 use crate::contexts::base::generate_keys_from_secret;
+use crate::graphs::export::GraphExportFormat;
+use crate::graphs::operator_set::{OperatorSelectionPolicy, OperatorSet};
 use crate::proof::{get_proof, invalidate_proof};
 use crate::transactions::base::Input;
+use alloy::primitives::Address as EvmAddress;
 use ark_serialize::CanonicalDeserialize;
 
-use bitcoin::{Address, PublicKey};
+use bitcoin::{consensus::encode::serialize_hex, psbt::Psbt, Address, Amount, PublicKey};
 use bitcoin::{Network, OutPoint};
 use clap::{arg, ArgMatches, Command};
 use colored::Colorize;
+use std::collections::HashMap;
+use std::fs;
 use std::io::{self, Write};
 use std::str::FromStr;
-use std::time::Duration;
+use std::time::{Duration, Instant};
 use tokio::io::{AsyncBufReadExt, BufReader};
+use tokio::net::TcpListener;
 use tokio::time::sleep;
 
+// Seconds between two rounds of `recover --watch`, a fraction of the block interval.
+const RECOVERY_POLL_INTERVAL_SECS: u64 = 30;
+
 pub struct CommonArgs {
     pub key_dir: Option<String>,
     pub verifiers: Option<Vec<PublicKey>>,
+    pub operators: Option<Vec<PublicKey>>,
+    pub operator_selection: Option<String>,
+    pub l2_bridge_address: Option<String>,
+    pub l2_chain_id: Option<u64>,
     pub environment: Option<String>,
     pub path_prefix: Option<String>,
 }
@@ -31,18 +53,36 @@ pub struct CommonArgs {
 pub struct ClientCommand {
     client: BitVMClient,
     config: Config,
+    n_of_n_public_keys: Vec<PublicKey>,
+}
+
+/// Source and destination networks of `environment`. Exits if the environment is invalid.
+pub fn get_networks(environment: Option<&str>) -> (Network, DestinationNetwork) {
+    match environment {
+        Some("mainnet") => (Network::Bitcoin, DestinationNetwork::Ethereum),
+        Some("testnet") => (Network::Testnet, DestinationNetwork::EthereumSepolia),
+        Some("regtest") => (Network::Regtest, DestinationNetwork::Local),
+        _ => {
+            eprintln!("Invalid environment. Use mainnet, testnet or regtest.");
+            std::process::exit(1);
+        }
+    }
 }
 
 impl ClientCommand {
     pub async fn new(common_args: CommonArgs) -> Self {
-        let (source_network, destination_network) = match common_args.environment.as_deref() {
-            Some("mainnet") => (Network::Bitcoin, DestinationNetwork::Ethereum),
-            Some("testnet") => (Network::Testnet, DestinationNetwork::EthereumSepolia),
-            Some("regtest") => (Network::Regtest, DestinationNetwork::Local),
-            _ => {
-                eprintln!("Invalid environment. Use mainnet, testnet or regtest.");
-                std::process::exit(1);
+        let (source_network, destination_network) =
+            get_networks(common_args.environment.as_deref());
+        // The adaptor of an EVM network is configured by the BRIDGE_CHAIN_ADAPTOR_EVM_* variables.
+        let (destination_network, chain_adaptor) = match common_args.l2_chain_id {
+            Some(chain_id) => {
+                let network = DestinationNetwork::Evm { chain_id };
+                (network, get_chain_adaptor(network, None, None))
             }
+            None => (
+                destination_network,
+                get_chain_adaptor(DestinationNetwork::Local, None, None), // TODO: Will be replaced with a destination network specific adaptor once Ethereum support is added.
+            ),
         };
 
         let keys_command = KeysCommand::new(common_args.key_dir);
@@ -62,20 +102,59 @@ impl ClientCommand {
             Some(get_esplora_url(source_network)),
             source_network,
             destination_network,
-            Some(get_chain_adaptor(DestinationNetwork::Local, None, None)), // TODO: Will be replaced with a destination network specific adaptor once Ethereum support is added.
+            Some(chain_adaptor),
             &n_of_n_public_keys,
             config.keys.depositor.as_deref(),
             config.keys.operator.as_deref(),
+            config.keys.commitment_seed.as_deref(),
             config.keys.verifier.as_deref(),
             config.keys.withdrawer.as_deref(),
             common_args.path_prefix.as_deref(),
             verifying_key,
+            config.parameters.for_network(source_network),
         )
         .await;
 
+        let mut client = match bitvm_client {
+            Ok(client) => client,
+            Err(err) => {
+                eprintln!("Failed to create client: {err}");
+                std::process::exit(1);
+            }
+        };
+        if let Some(operators) = &common_args.operators {
+            let selection_policy = common_args
+                .operator_selection
+                .as_deref()
+                .map(|name| {
+                    OperatorSelectionPolicy::from_name(name)
+                        .expect("Invalid operator selection policy")
+                })
+                .unwrap_or_default();
+            client.set_operator_set(OperatorSet::new(operators, selection_policy));
+        }
+        if let Some(bridge_address) = &common_args.l2_bridge_address {
+            client.set_l2_bridge_address(
+                EvmAddress::from_str(bridge_address).expect("Invalid L2 bridge address"),
+            );
+        }
+        if let Some(master_key) = &config.keys.master_key {
+            let wallet = HdWallet::from_xprv(master_key).expect("Invalid master key in config");
+            let graph_index = config.keys.graph_index.unwrap_or(0);
+            // Index 0 is the signing key of the role, the following indexes are funding addresses.
+            for role in [Role::Depositor, Role::Operator] {
+                let role_graph_index = role_graph_index(role, graph_index);
+                let keypairs = (1..=DEFAULT_GAP_LIMIT)
+                    .map(|index| wallet.keypair(role, role_graph_index, index))
+                    .collect();
+                client.add_funding_keys(role, keypairs);
+            }
+        }
+
         Self {
-            client: bitvm_client,
+            client,
             config,
+            n_of_n_public_keys,
         }
     }
 
@@ -218,10 +297,10 @@ impl ClientCommand {
         Command::new("create-peg-out")
             .short_flag('t')
             .about("Create peg-out graph for specified peg-in graph")
-            .after_help("")
+            .after_help("If no utxo is specified, the peg-out confirm input is funded from the operator's wallet.")
             .arg(
                 arg!(-u --utxo <UTXO> "Specify the utxo to spend from. Format: <TXID>:<VOUT>")
-                    .required(true),
+                    .required(false),
             )
             .arg(
                 arg!(-i --peg_in_id <PEG_IN_GRAPH_ID> "Specify the peg-in graph ID").required(true),
@@ -234,22 +313,34 @@ impl ClientCommand {
     ) -> io::Result<()> {
         self.client.sync().await;
 
-        let utxo = sub_matches.get_one::<String>("utxo").unwrap();
         let peg_in_id = sub_matches.get_one::<String>("peg_in_id").unwrap();
-        let outpoint = OutPoint::from_str(utxo).unwrap();
 
-        let tx = self.client.esplora.get_tx(&outpoint.txid).await.unwrap();
-        let tx = tx.unwrap();
-        let input = Input {
-            outpoint,
-            amount: tx.output[outpoint.vout as usize].value,
-        };
+        let peg_out_id = match sub_matches.get_one::<String>("utxo") {
+            Some(utxo) => {
+                let outpoint = OutPoint::from_str(utxo).unwrap();
+                let tx = self.client.esplora.get_tx(&outpoint.txid).await.unwrap();
+                let tx = tx.unwrap();
+                let input = Input {
+                    outpoint,
+                    amount: tx.output[outpoint.vout as usize].value,
+                };
 
-        let peg_out_id = self.client.create_peg_out_graph(
-            peg_in_id,
-            input,
-            CommitmentMessageId::generate_commitment_secrets(),
-        );
+                let commitment_secrets = self.client.generate_commitment_secrets(peg_in_id);
+                self.client
+                    .create_peg_out_graph(peg_in_id, input, commitment_secrets)
+            }
+            None => match self
+                .client
+                .create_peg_out_graph_with_funding(peg_in_id)
+                .await
+            {
+                Ok(peg_out_id) => peg_out_id,
+                Err(e) => {
+                    eprintln!("Failed to create peg-out graph: {e}");
+                    return Ok(());
+                }
+            },
+        };
 
         self.client.flush().await;
 
@@ -257,6 +348,40 @@ impl ClientCommand {
         Ok(())
     }
 
+    pub fn get_regenerate_commitments_command() -> Command {
+        Command::new("regenerate-commitments")
+            .about("Regenerate the operator's commitment secrets from the operator commitment seed")
+            .after_help("Regenerate the Winternitz commitment secrets of the operator's peg-out graphs from the configured operator commitment seed, e.g. after the private data file was lost. Without a graph ID, the secrets of all peg-out graphs missing from the private data are regenerated.")
+            .arg(arg!(-i --id <GRAPH_ID> "Specify the peg-out graph ID").required(false))
+    }
+
+    pub async fn handle_regenerate_commitments_command(
+        &mut self,
+        sub_matches: &ArgMatches,
+    ) -> io::Result<()> {
+        self.client.sync().await;
+
+        match sub_matches.get_one::<String>("id") {
+            Some(graph_id) => match self.client.regenerate_commitment_secrets(graph_id) {
+                Ok(_) => println!("Regenerated commitment secrets for peg-out graph {graph_id}"),
+                Err(e) => eprintln!("Failed to regenerate commitment secrets: {e}"),
+            },
+            None => match self.client.regenerate_missing_commitment_secrets() {
+                Ok(graph_ids) if graph_ids.is_empty() => {
+                    println!("No peg-out graphs with missing commitment secrets found.")
+                }
+                Ok(graph_ids) => {
+                    for graph_id in graph_ids {
+                        println!("Regenerated commitment secrets for peg-out graph {graph_id}");
+                    }
+                }
+                Err(e) => eprintln!("Failed to regenerate commitment secrets: {e}"),
+            },
+        }
+
+        Ok(())
+    }
+
     pub fn get_push_nonces_command() -> Command {
         Command::new("push-nonces")
             .short_flag('c')
@@ -290,12 +415,427 @@ impl ClientCommand {
         let graph_id = sub_matches.get_one::<String>("id").unwrap();
 
         self.client.sync().await;
-        self.client.push_verifier_signature(graph_id);
-        self.client.flush().await;
+        match self.client.push_verifier_signature(graph_id) {
+            Ok(()) => self.client.flush().await,
+            Err(e) => eprintln!("Failed to sign graph {graph_id}: {e}"),
+        }
+
+        Ok(())
+    }
+
+    pub fn get_signing_coordinator_command() -> Command {
+        Command::new("signing-coordinator")
+            .about("Coordinate the MuSig2 signing session of a graph")
+            .after_help("Relay the nonces and partial signatures of the verifiers for the n-of-n inputs of the graph until all of them have signed, or a round times out.")
+            .arg(arg!(-g --graph_id <GRAPH_ID> "Graph ID").required(true))
+            .arg(arg!(-l --listen <ADDRESS> "Address to listen on for verifiers, e.g. 0.0.0.0:9100").required(true))
+            .arg(
+                arg!(--timeout <SECONDS> "Round timeout in seconds, 300 by default")
+                    .required(false)
+                    .value_parser(clap::value_parser!(u64)),
+            )
+    }
+
+    pub async fn handle_signing_coordinator_command(
+        &mut self,
+        sub_matches: &ArgMatches,
+    ) -> io::Result<()> {
+        self.client.sync().await;
+
+        let graph_id = sub_matches.get_one::<String>("graph_id").unwrap();
+        let address = sub_matches.get_one::<String>("listen").unwrap();
+        let round_timeout = sub_matches
+            .get_one::<u64>("timeout")
+            .map(|seconds| Duration::from_secs(*seconds))
+            .unwrap_or(DEFAULT_ROUND_TIMEOUT);
+
+        let Some(graph) = self.client.data().graph(graph_id) else {
+            eprintln!("error: Graph {graph_id} not found.");
+            return Ok(());
+        };
+        let mut coordinator = SigningCoordinator::new(round_timeout);
+        coordinator.open_session(graph, &self.n_of_n_public_keys, Instant::now());
+
+        let listener = TcpListener::bind(address).await?;
+        println!(
+            "Coordinating signing session for graph {graph_id} on {}",
+            listener.local_addr()?
+        );
+        let coordinator = serve_coordinator(listener, coordinator).await;
+        println!(
+            "Signing session ended in round {:?}",
+            coordinator.round(graph_id).unwrap()
+        );
+
+        Ok(())
+    }
+
+    pub fn get_signing_session_command() -> Command {
+        Command::new("signing-session")
+            .about("Pre-sign a graph as verifier in a MuSig2 signing session")
+            .after_help("Exchange nonces and partial signatures for the n-of-n inputs of the graph through a signing coordinator, then save the signed graph.")
+            .arg(arg!(-g --graph_id <GRAPH_ID> "Graph ID").required(true))
+            .arg(arg!(-c --coordinator <ADDRESS> "Address of the signing coordinator").required(true))
+            .arg(
+                arg!(--timeout <SECONDS> "Round timeout in seconds, 300 by default")
+                    .required(false)
+                    .value_parser(clap::value_parser!(u64)),
+            )
+    }
+
+    pub async fn handle_signing_session_command(
+        &mut self,
+        sub_matches: &ArgMatches,
+    ) -> io::Result<()> {
+        self.client.sync().await;
+
+        let graph_id = sub_matches.get_one::<String>("graph_id").unwrap();
+        let address = sub_matches.get_one::<String>("coordinator").unwrap();
+        let round_timeout = sub_matches
+            .get_one::<u64>("timeout")
+            .map(|seconds| Duration::from_secs(*seconds))
+            .unwrap_or(DEFAULT_ROUND_TIMEOUT);
+
+        let (sender, mut receiver) = connect(address.as_str()).await?;
+        match run_verifier_session(
+            &mut self.client,
+            graph_id,
+            &sender,
+            &mut receiver,
+            round_timeout,
+        )
+        .await
+        {
+            Ok(()) => {
+                self.client.flush().await;
+                println!("Signed graph {graph_id}");
+            }
+            Err(e) => eprintln!("Signing session failed: {e}"),
+        }
+
+        Ok(())
+    }
+
+    pub fn get_rescan_command() -> Command {
+        Command::new("rescan")
+            .about("Discover the used addresses of the wallet")
+            .after_help("Walk the derivation indexes of every role until the gap limit of consecutive unused indexes is reached and list the addresses that have transactions. Requires a master key, see the wallet command.")
+            .arg(
+                arg!(--"gap-limit" <GAP_LIMIT> "Number of consecutive unused indexes to stop after, 20 by default")
+                    .required(false)
+                    .value_parser(clap::value_parser!(u32).range(1..=1000)),
+            )
+    }
+
+    pub async fn handle_rescan_command(&mut self, sub_matches: &ArgMatches) -> io::Result<()> {
+        let gap_limit = sub_matches
+            .get_one::<u32>("gap-limit")
+            .copied()
+            .unwrap_or(DEFAULT_GAP_LIMIT);
+        let Some(master_key) = &self.config.keys.master_key else {
+            eprintln!("error: No master key is configured.");
+            return Ok(());
+        };
+        let wallet = HdWallet::from_xprv(master_key).expect("Invalid master key in config");
+
+        let roles = [
+            Role::Depositor,
+            Role::Operator,
+            Role::Verifier,
+            Role::Withdrawer,
+        ];
+        match rescan(
+            &self.client.esplora,
+            &wallet,
+            self.client.source_network,
+            &roles,
+            gap_limit,
+        )
+        .await
+        {
+            Ok(result) => {
+                for used_address in &result.used_addresses {
+                    println!(
+                        "{:?} {}: {} ({} transactions)",
+                        used_address.role,
+                        wallet.derivation_path(
+                            used_address.role,
+                            used_address.graph_index,
+                            used_address.index
+                        ),
+                        used_address.address,
+                        used_address.tx_count
+                    );
+                }
+                for role in roles {
+                    let next_graph_index = result.next_graph_indexes[&role];
+                    for graph_index in 0..next_graph_index {
+                        println!(
+                            "{:?} graph index {}: next unused index {}",
+                            role,
+                            graph_index,
+                            result.next_indexes[&(role, graph_index)]
+                        );
+                    }
+                    println!("{:?}: next unused graph index {}", role, next_graph_index);
+                }
+            }
+            Err(e) => eprintln!("Failed to rescan wallet: {e}"),
+        }
+
+        Ok(())
+    }
+
+    pub fn get_consolidate_command() -> Command {
+        Command::new("consolidate")
+            .about("Consolidate the utxos of a funding wallet")
+            .after_help("Spend all the utxos of the role's signing and funding addresses to a single output at its signing address.")
+            .arg(
+                arg!(-r --role <ROLE> "Role whose wallet to consolidate")
+                    .required(true)
+                    .value_parser(["depositor", "operator", "verifier", "withdrawer"]),
+            )
+    }
+
+    pub async fn handle_consolidate_command(&mut self, sub_matches: &ArgMatches) -> io::Result<()> {
+        let role = match sub_matches.get_one::<String>("role").unwrap().as_str() {
+            "depositor" => Role::Depositor,
+            "operator" => Role::Operator,
+            "verifier" => Role::Verifier,
+            _ => Role::Withdrawer,
+        };
+
+        if let Err(e) = self.client.consolidate_utxos(role).await {
+            eprintln!("Failed to consolidate utxos: {e}");
+        }
+
+        Ok(())
+    }
+
+    pub fn get_export_psbt_command() -> Command {
+        Command::new("export-psbt")
+            .about("Export a graph transaction as a PSBT")
+            .after_help("Export a transaction of a peg-in or peg-out graph as a binary PSBT (BIP174) for signing with an external signer. Taproot inputs include the leaf script, control block and internal key (BIP371). Transaction names are PegInDeposit, PegInRefund, PegInConfirm, PegOut, PegOutConfirm, KickOff1, KickOff2, StartTime, StartTimeTimeout, KickOffTimeout, Challenge, AssertInitial, AssertCommit1, AssertCommit2, AssertFinal, Take1, Take2, Disprove and DisproveChain.")
+            .arg(arg!(-i --id <GRAPH_ID> "Specify the peg-in or peg-out graph ID").required(true))
+            .arg(arg!(-t --tx <TX_NAME> "Specify the transaction name").required(true))
+            .arg(arg!(-o --output <FILE> "Specify the file to write the PSBT to").required(true))
+    }
+
+    pub async fn handle_export_psbt_command(&mut self, sub_matches: &ArgMatches) -> io::Result<()> {
+        let graph_id = sub_matches.get_one::<String>("id").unwrap();
+        let tx_name = sub_matches.get_one::<String>("tx").unwrap();
+        let output = sub_matches.get_one::<String>("output").unwrap();
+
+        self.client.sync().await;
+        match self.client.export_psbt(graph_id, tx_name) {
+            Ok(psbt) => {
+                fs::write(output, psbt.serialize())?;
+                println!("Exported {tx_name} PSBT to {output}");
+            }
+            Err(e) => eprintln!("Failed to export PSBT: {e}"),
+        }
+
+        Ok(())
+    }
+
+    pub fn get_graph_export_command() -> Command {
+        Command::new("graph-export")
+            .about("Export the transaction graph of a peg-in or peg-out graph")
+            .after_help("Print the transactions of a peg-in or peg-out graph as a Graphviz DOT or Mermaid diagram. Each edge is an output spent by another transaction, labeled with its connector, amount and relative timelock. Transactions outside of the graph, e.g. funding transactions, are shown in grey. With --status, the transactions are colored by their confirmation status.")
+            .arg(arg!(<GRAPH_ID> "Specify the peg-in or peg-out graph ID").required(true))
+            .arg(
+                arg!(--format <FORMAT> "Specify the output format")
+                    .required(false)
+                    .value_parser(["dot", "mermaid"])
+                    .default_value("dot"),
+            )
+            .arg(arg!(--status "Color the transactions by confirmation status").required(false))
+    }
+
+    pub async fn handle_graph_export_command(
+        &mut self,
+        sub_matches: &ArgMatches,
+    ) -> io::Result<()> {
+        let graph_id = sub_matches.get_one::<String>("GRAPH_ID").unwrap();
+        let format = sub_matches
+            .get_one::<String>("format")
+            .and_then(|format| GraphExportFormat::from_name(format))
+            .unwrap();
+
+        self.client.sync().await;
+        match self
+            .client
+            .export_graph(graph_id, format, sub_matches.get_flag("status"))
+            .await
+        {
+            Ok(export) => print!("{export}"),
+            Err(e) => eprintln!("Failed to export graph: {e}"),
+        }
+
+        Ok(())
+    }
+
+    pub fn get_fee_table_command() -> Command {
+        Command::new("fee-table")
+            .about("Regenerate the relay fee constants from the vsizes of graph transactions")
+            .after_help("Build the transactions of a peg-in or peg-out graph with dummy witnesses of the size of the real ones, measure their vsizes and print the MIN_RELAY_FEE_* constants of transactions::base. For a peg-out graph, the transactions of its peg-in graph are measured as well. Stale constants and the resulting PEG_OUT_FEE are printed as comments.")
+            .arg(arg!(<GRAPH_ID> "Specify the peg-in or peg-out graph ID").required(true))
+    }
+
+    pub async fn handle_fee_table_command(&mut self, sub_matches: &ArgMatches) -> io::Result<()> {
+        let graph_id = sub_matches.get_one::<String>("GRAPH_ID").unwrap();
+
+        self.client.sync().await;
+        match self.client.fee_table(graph_id) {
+            Ok(fee_table) => print!("{fee_table}"),
+            Err(e) => eprintln!("Failed to measure the graph transactions: {e}"),
+        }
+
+        Ok(())
+    }
+
+    pub fn get_import_psbt_command() -> Command {
+        Command::new("import-psbt")
+            .about("Import a signed PSBT of a graph transaction")
+            .after_help("Verify the signatures of a signed binary PSBT exported with export-psbt and finalize the transaction witnesses. The finalized transaction is printed as hex, or broadcast if requested.")
+            .arg(arg!(-i --id <GRAPH_ID> "Specify the peg-in or peg-out graph ID").required(true))
+            .arg(arg!(-t --tx <TX_NAME> "Specify the transaction name").required(true))
+            .arg(arg!(-f --file <FILE> "Specify the signed PSBT file").required(true))
+            .arg(arg!(--broadcast "Broadcast the finalized transaction").required(false))
+    }
+
+    pub async fn handle_import_psbt_command(&mut self, sub_matches: &ArgMatches) -> io::Result<()> {
+        let graph_id = sub_matches.get_one::<String>("id").unwrap();
+        let tx_name = sub_matches.get_one::<String>("tx").unwrap();
+        let file = sub_matches.get_one::<String>("file").unwrap();
+
+        let psbt = match Psbt::deserialize(&fs::read(file)?) {
+            Ok(psbt) => psbt,
+            Err(e) => {
+                eprintln!("Invalid PSBT file {file}: {e}");
+                return Ok(());
+            }
+        };
+
+        self.client.sync().await;
+        if sub_matches.get_flag("broadcast") {
+            if let Err(e) = self.client.broadcast_psbt(graph_id, tx_name, &psbt).await {
+                eprintln!("Failed to broadcast transaction: {e}");
+            }
+        } else {
+            match self.client.import_psbt(graph_id, tx_name, &psbt) {
+                Ok(tx) => println!("{}", serialize_hex(&tx)),
+                Err(e) => eprintln!("Failed to import PSBT: {e}"),
+            }
+        }
 
         Ok(())
     }
 
+    pub fn get_challenge_crowdfunding_command() -> Command {
+        Command::new("challenge-crowdfunding")
+            .about("Crowdfund the challenge transaction of a peg-out graph with other verifiers")
+            .after_help("Verifiers share the funding of a challenge transaction by appending inputs signed with SIGHASH_ALL|ANYONECANPAY to it in the data store. contribute funds an input from the verifier's wallet, for the amount still missing by default, and pushes it. broadcast sends the challenge transaction once the contributions fund it, which automatic mode does too. If the challenge window closes first, the contributions stay in the contributors' wallets.")
+            .arg(arg!(-g --graph_id <GRAPH_ID> "Peg-out graph ID").required(true))
+            .subcommand(
+                Command::new("contribute")
+                    .about("Contribute to the challenge transaction")
+                    .arg(
+                        arg!(-a --amount <SATS> "Specify the amount to contribute, capped to the amount still missing")
+                            .required(false)
+                            .value_parser(clap::value_parser!(u64)),
+                    ),
+            )
+            .subcommand(Command::new("status").about("Show the crowdfunding status"))
+            .subcommand(Command::new("broadcast").about("Broadcast the crowdfunded challenge"))
+            .subcommand_required(true)
+    }
+
+    pub async fn handle_challenge_crowdfunding_command(
+        &mut self,
+        sub_matches: &ArgMatches,
+    ) -> io::Result<()> {
+        let graph_id = sub_matches.get_one::<String>("graph_id").unwrap();
+
+        self.client.sync().await;
+        match sub_matches.subcommand() {
+            Some(("contribute", contribute_matches)) => {
+                let amount = contribute_matches
+                    .get_one::<u64>("amount")
+                    .map(|amount| Amount::from_sat(*amount));
+                match self.client.contribute_to_challenge(graph_id, amount).await {
+                    Ok(_) => self.client.flush().await,
+                    Err(e) => eprintln!("Failed to contribute to the challenge: {e}"),
+                }
+            }
+            Some(("status", _)) => {
+                match self.client.challenge_crowdfunding_status(graph_id).await {
+                    Ok(status) => {
+                        println!("Peg-out graph ID: {graph_id} crowdfunding status: {status}")
+                    }
+                    Err(e) => eprintln!("Failed to get the crowdfunding status: {e}"),
+                }
+            }
+            Some(("broadcast", _)) => {
+                if let Err(e) = self.client.broadcast_crowdfunded_challenge(graph_id).await {
+                    eprintln!("Failed to broadcast the crowdfunded challenge: {e}");
+                }
+            }
+            _ => unreachable!(),
+        }
+
+        Ok(())
+    }
+
+    pub fn get_recover_command() -> Command {
+        Command::new("recover")
+            .about("Refund the depositor's peg-ins stuck without n-of-n signatures")
+            .after_help("A peg-in is stuck when its peg-in confirm transaction is still missing n-of-n signatures a number of blocks after the deposit confirmed, one day by default. The refund of a stuck peg-in is broadcast once the connector Z timelock matures, which automatic mode does too. With --watch, the recovery statuses are polled until every refund is confirmed.")
+            .arg(arg!(-g --graph_id <GRAPH_ID> "Peg-in graph ID, all of the depositor's peg-ins if not specified").required(false))
+            .arg(
+                arg!(-d --deadline <BLOCKS> "Specify the number of blocks after the deposit within which the peg-in confirm transaction must be signed")
+                    .required(false)
+                    .value_parser(clap::value_parser!(u32)),
+            )
+            .arg(arg!(-w --watch "Keep recovering until no peg-in recovery is pending").required(false))
+    }
+
+    pub async fn handle_recover_command(&mut self, sub_matches: &ArgMatches) -> io::Result<()> {
+        let graph_id = sub_matches.get_one::<String>("graph_id");
+        if let Some(deadline) = sub_matches.get_one::<u32>("deadline") {
+            self.client.set_peg_in_signing_deadline(*deadline);
+        }
+
+        let mut last_statuses = HashMap::new();
+        loop {
+            self.client.sync().await;
+
+            let mut pending = false;
+            for (peg_in_graph_id, status) in self.client.recover_peg_ins(graph_id).await {
+                match status {
+                    Ok(status) => {
+                        pending |= status.is_pending();
+                        if last_statuses.get(&peg_in_graph_id) != Some(&status) {
+                            println!(
+                                "Peg-in graph ID: {peg_in_graph_id} recovery status: {status}"
+                            );
+                            last_statuses.insert(peg_in_graph_id, status);
+                        }
+                    }
+                    Err(e) => {
+                        pending = true;
+                        eprintln!("Failed to recover peg-in graph {peg_in_graph_id}: {e}");
+                    }
+                }
+            }
+
+            if !sub_matches.get_flag("watch") || !pending {
+                return Ok(());
+            }
+            sleep(Duration::from_secs(RECOVERY_POLL_INTERVAL_SECS)).await;
+        }
+    }
+
     pub fn get_mock_l2_pegout_event_command() -> Command {
         Command::new("mock-l2-pegout-event")
             .short_flag('x')
@@ -347,6 +887,9 @@ impl ClientCommand {
 
             self.client.process_peg_ins().await;
             self.client.process_peg_outs().await;
+            self.client
+                .process_challenge_crowdfundings_as_verifier()
+                .await;
 
             // A bit inefficient, but fine for now: only flush if data changed
             if self.client.data() != &old_data {
@@ -376,12 +919,13 @@ impl ClientCommand {
                 Command::new("tx")
                     .about("Broadcast transactions")
                     .arg(arg!(-g --graph_id <GRAPH_ID> "Peg-out graph ID").required(true))
-                    .arg(arg!(-u --utxo <UTXO> "Specify the utxo to spend from. Format: <TXID>:<VOUT>").required(false))
+                    .arg(arg!(-u --utxo <UTXO> "Specify the utxo to spend from, funded from the wallet if not specified. Format: <TXID>:<VOUT>").required(false))
                     .arg(arg!(-a --address <ADDRESS> "Specify the reward address to receive BTC reward").required(false))
                     .subcommand(Command::new("peg_out").about("Broadcast peg-out"))
                     .subcommand(Command::new("peg_out_confirm").about("Broadcast peg-out confirm"))
                     .subcommand(Command::new("kick_off_1").about("Broadcast kick off 1"))
                     .subcommand(Command::new("kick_off_2").about("Broadcast kick off 2"))
+                    .subcommand(Command::new("challenge").about("Broadcast challenge, crowdfunded from the wallet"))
                     .subcommand(Command::new("start_time").about("Broadcast start time"))
                     .subcommand(Command::new("assert_initial").about("Broadcast assert initial"))
                     .subcommand(
@@ -416,21 +960,30 @@ impl ClientCommand {
             Some(("refund", _)) => self.client.broadcast_peg_in_refund(graph_id).await,
             Some(("confirm", _)) => self.client.broadcast_peg_in_confirm(graph_id).await,
             Some(("peg_out", _)) => {
-                let utxo = subcommand.unwrap().1.get_one::<String>("utxo").unwrap();
-                let outpoint = OutPoint::from_str(utxo).unwrap();
-                let tx = self.client.esplora.get_tx(&outpoint.txid).await.unwrap();
-                let tx = tx.unwrap();
-                let input = Input {
-                    outpoint,
-                    amount: tx.output[outpoint.vout as usize].value,
+                let result = match subcommand.unwrap().1.get_one::<String>("utxo") {
+                    Some(utxo) => {
+                        let outpoint = OutPoint::from_str(utxo).unwrap();
+                        let tx = self.client.esplora.get_tx(&outpoint.txid).await.unwrap();
+                        let tx = tx.unwrap();
+                        let input = Input {
+                            outpoint,
+                            amount: tx.output[outpoint.vout as usize].value,
+                        };
+                        self.client.broadcast_peg_out(graph_id, input).await
+                    }
+                    None => self.client.broadcast_peg_out_with_funding(graph_id).await,
                 };
-                let result = self.client.broadcast_peg_out(graph_id, input).await;
                 self.client.flush().await;
                 result
             }
             Some(("peg_out_confirm", _)) => self.client.broadcast_peg_out_confirm(graph_id).await,
             Some(("kick_off_1", _)) => self.client.broadcast_kick_off_1(graph_id).await,
             Some(("kick_off_2", _)) => self.client.broadcast_kick_off_2(graph_id).await,
+            Some(("challenge", _)) => {
+                let result = self.client.broadcast_challenge_with_funding(graph_id).await;
+                self.client.flush().await;
+                result
+            }
             Some(("start_time", _)) => self.client.broadcast_start_time(graph_id).await,
             Some(("assert_initial", _)) => self.client.broadcast_assert_initial(graph_id).await,
             Some(("assert_commit_1", _)) => {
@@ -533,6 +1086,10 @@ impl ClientCommand {
                 let key_dir = matches.get_one::<String>("key-dir").cloned();
                 let keys_command = KeysCommand::new(key_dir);
                 keys_command.handle_command(sub_matches)?;
+            } else if let Some(sub_matches) = matches.subcommand_matches("commitment-seed") {
+                let key_dir = matches.get_one::<String>("key-dir").cloned();
+                let keys_command = KeysCommand::new(key_dir);
+                keys_command.handle_commitment_seed_command(sub_matches)?;
             } else if matches.subcommand_matches("get-operator-address").is_some() {
                 self.handle_get_operator_address().await?;
             } else if matches.subcommand_matches("get-operator-utxos").is_some() {
@@ -549,10 +1106,38 @@ impl ClientCommand {
             } else if let Some(sub_matches) = matches.subcommand_matches("create-peg-out") {
                 self.handle_create_peg_out_graph_command(sub_matches)
                     .await?;
+            } else if let Some(sub_matches) = matches.subcommand_matches("regenerate-commitments") {
+                self.handle_regenerate_commitments_command(sub_matches)
+                    .await?;
             } else if let Some(sub_matches) = matches.subcommand_matches("push-nonces") {
                 self.handle_push_nonces_command(sub_matches).await?;
             } else if let Some(sub_matches) = matches.subcommand_matches("push-signatures") {
                 self.handle_push_signature_command(sub_matches).await?;
+            } else if let Some(sub_matches) = matches.subcommand_matches("signing-coordinator") {
+                self.handle_signing_coordinator_command(sub_matches).await?;
+            } else if let Some(sub_matches) = matches.subcommand_matches("signing-session") {
+                self.handle_signing_session_command(sub_matches).await?;
+            } else if let Some(sub_matches) = matches.subcommand_matches("wallet") {
+                let key_dir = matches.get_one::<String>("key-dir").cloned();
+                let keys_command = KeysCommand::new(key_dir);
+                keys_command.handle_wallet_command(sub_matches, self.client.source_network)?;
+            } else if let Some(sub_matches) = matches.subcommand_matches("rescan") {
+                self.handle_rescan_command(sub_matches).await?;
+            } else if let Some(sub_matches) = matches.subcommand_matches("consolidate") {
+                self.handle_consolidate_command(sub_matches).await?;
+            } else if let Some(sub_matches) = matches.subcommand_matches("export-psbt") {
+                self.handle_export_psbt_command(sub_matches).await?;
+            } else if let Some(sub_matches) = matches.subcommand_matches("import-psbt") {
+                self.handle_import_psbt_command(sub_matches).await?;
+            } else if let Some(sub_matches) = matches.subcommand_matches("graph-export") {
+                self.handle_graph_export_command(sub_matches).await?;
+            } else if let Some(sub_matches) = matches.subcommand_matches("fee-table") {
+                self.handle_fee_table_command(sub_matches).await?;
+            } else if let Some(sub_matches) = matches.subcommand_matches("challenge-crowdfunding") {
+                self.handle_challenge_crowdfunding_command(sub_matches)
+                    .await?;
+            } else if let Some(sub_matches) = matches.subcommand_matches("recover") {
+                self.handle_recover_command(sub_matches).await?;
             } else if let Some(sub_matches) = matches.subcommand_matches("mock-l2-pegout-event") {
                 self.handle_mock_l2_pegout_event_command(sub_matches)
                     .await?;
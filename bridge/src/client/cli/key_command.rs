@@ -1,4 +1,5 @@
 use bitcoin::{Network, PublicKey};
+use clap::builder::PossibleValuesParser;
 use clap::{arg, ArgGroup, ArgMatches, Command};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
@@ -8,22 +9,52 @@ use std::io::{self, Read, Write};
 use std::path::PathBuf;
 use toml;
 
+use crate::client::events::Role;
+use crate::client::wallet::keys::HdWallet;
+use crate::commitments::CommitmentSeed;
 use crate::contexts::base::generate_keys_from_secret;
+use crate::parameters::ProtocolParameters;
 
 #[derive(Serialize, Deserialize, Default)]
 pub struct Config {
     pub keys: Keys,
+    #[serde(default)]
+    pub parameters: Parameters,
 }
 
 #[derive(Serialize, Deserialize, Default)]
 pub struct Keys {
+    // BIP32 master key the other keys were derived from, if any.
+    pub master_key: Option<String>,
+    // Graph index of the master key the depositor and withdrawer keys were derived at.
+    pub graph_index: Option<u32>,
     pub depositor: Option<String>,
     pub operator: Option<String>,
+    pub commitment_seed: Option<String>,
     pub verifier: Option<String>,
     pub withdrawer: Option<String>,
     pub verifying_key: Option<String>,
 }
 
+/// Optional protocol parameter overrides per environment. Environments without an entry use
+/// `ProtocolParameters::for_network`.
+#[derive(Serialize, Deserialize, Default)]
+pub struct Parameters {
+    pub mainnet: Option<ProtocolParameters>,
+    pub testnet: Option<ProtocolParameters>,
+    pub regtest: Option<ProtocolParameters>,
+}
+
+impl Parameters {
+    pub fn for_network(&self, network: Network) -> Option<ProtocolParameters> {
+        match network {
+            Network::Bitcoin => self.mainnet,
+            Network::Regtest => self.regtest,
+            _ => self.testnet,
+        }
+    }
+}
+
 const BRIDGE_KEY_DIR_NAME: &str = ".bitvm-bridge";
 const BRIDGE_TOML: &str = "bridge.toml";
 
@@ -105,6 +136,16 @@ impl KeysCommand {
                 print_user_key(keys.get(name).unwrap(), name);
                 name = "WITHDRAWER";
                 print_user_key(keys.get(name).unwrap(), name);
+                if config.keys.master_key.is_some() {
+                    println!("[MASTER KEY]:");
+                    println!("          Configured (use 'wallet backup' to export it)");
+                    println!();
+                }
+                if config.keys.commitment_seed.is_some() {
+                    println!("[COMMITMENT SEED]:");
+                    println!("          Configured (use 'commitment-seed backup' to export it)");
+                    println!();
+                }
                 name = "VERIFYING KEY";
                 print_verifying_key(keys.get(name).unwrap(), name);
             } else {
@@ -191,11 +232,292 @@ impl KeysCommand {
         key.len() == 64 && key.chars().all(|c| c.is_ascii_hexdigit())
     }
 
+    pub fn get_commitment_seed_command() -> Command {
+        Command::new("commitment-seed")
+            .about("Manage the operator master seed for commitment secrets")
+            .after_help("The operator's Winternitz commitment secrets of every peg-out graph are derived from this seed. Back it up to be able to regenerate the commitment secrets if the private data file is lost.")
+            .subcommand(
+                Command::new("generate")
+                    .about("Generate a new random commitment seed")
+                    .arg(arg!(--force "Overwrite an existing commitment seed").required(false)),
+            )
+            .subcommand(
+                Command::new("backup")
+                    .about("Write the commitment seed to a backup file")
+                    .arg(arg!(-f --file <FILE> "Backup file path").required(true)),
+            )
+            .subcommand(
+                Command::new("restore")
+                    .about("Restore the commitment seed from a backup file")
+                    .arg(arg!(-f --file <FILE> "Backup file path").required(true))
+                    .arg(arg!(--force "Overwrite an existing commitment seed").required(false)),
+            )
+            .subcommand_required(true)
+    }
+
+    pub fn handle_commitment_seed_command(&self, sub_matches: &ArgMatches) -> io::Result<()> {
+        let mut config = self.read_config()?;
+
+        match sub_matches.subcommand() {
+            Some(("generate", args)) => {
+                if config.keys.commitment_seed.is_some() && !args.get_flag("force") {
+                    eprintln!("error: A commitment seed is already configured. Use --force to overwrite it. Commitment secrets of existing peg-out graphs can only be regenerated from the old seed.");
+                    return Ok(());
+                }
+                config.keys.commitment_seed = Some(CommitmentSeed::new().to_hex());
+                println!("Commitment seed generated successfully! Back it up with 'commitment-seed backup'.");
+                self.write_config(&config)
+            }
+            Some(("backup", args)) => {
+                let file = args.get_one::<String>("file").unwrap();
+                match &config.keys.commitment_seed {
+                    Some(seed) => {
+                        let mut backup_file =
+                            OpenOptions::new().create_new(true).write(true).open(file)?;
+                        backup_file.write_all(seed.as_bytes())?;
+                        println!("Commitment seed written to {}", file);
+                    }
+                    None => eprintln!("error: No commitment seed is configured."),
+                }
+                Ok(())
+            }
+            Some(("restore", args)) => {
+                if config.keys.commitment_seed.is_some() && !args.get_flag("force") {
+                    eprintln!("error: A commitment seed is already configured. Use --force to overwrite it.");
+                    return Ok(());
+                }
+                let file = args.get_one::<String>("file").unwrap();
+                let seed = fs::read_to_string(file)?;
+                match CommitmentSeed::from_hex(&seed) {
+                    Ok(seed) => {
+                        config.keys.commitment_seed = Some(seed.to_hex());
+                        println!("Commitment seed restored successfully!");
+                        self.write_config(&config)
+                    }
+                    Err(e) => {
+                        eprintln!("error: {}", e);
+                        Ok(())
+                    }
+                }
+            }
+            _ => unreachable!(),
+        }
+    }
+
+    pub fn get_wallet_command() -> Command {
+        Command::new("wallet")
+            .about("Manage the BIP32 master key all participant keys are derived from")
+            .after_help("The depositor, operator, verifier and withdrawer keys and the operator commitment seed are derived from one master key along paths m/86'/<coin type>'/<role account>'/<graph index>/<index>, with role accounts 0 (depositor), 1 (operator), 2 (verifier) and 3 (withdrawer). Index 0 is the key the role signs the graphs of the graph index with. Depositors and withdrawers can use a new graph index for every peg-in or peg-out, verifiers and operators always use graph index 0 since their keys are registered with the other participants. Graph index 0 follows BIP86. Backing up the mnemonic or master key is enough to restore all of them. The network of the master key follows the --environment option.")
+            .subcommand(
+                Command::new("generate")
+                    .about("Generate a new random mnemonic and derive all keys from it")
+                    .arg(graph_index_arg())
+                    .arg(arg!(--force "Overwrite existing keys").required(false)),
+            )
+            .subcommand(
+                Command::new("restore")
+                    .about("Restore the master key from a BIP39 mnemonic or an xprv and derive all keys from it")
+                    .arg(arg!(-m --mnemonic <MNEMONIC> "BIP39 mnemonic, quoted").required(false))
+                    .arg(arg!(--passphrase <PASSPHRASE> "BIP39 passphrase").required(false).requires("mnemonic"))
+                    .arg(arg!(-x --xprv <XPRV> "BIP32 master key").required(false))
+                    .group(ArgGroup::new("source").args(["mnemonic", "xprv"]).required(true))
+                    .arg(graph_index_arg())
+                    .arg(arg!(--force "Overwrite existing keys").required(false)),
+            )
+            .subcommand(
+                Command::new("graph")
+                    .about("Derive the depositor and withdrawer keys of another graph index from the configured master key")
+                    .arg(graph_index_arg().required(true).default_value(None)),
+            )
+            .subcommand(
+                Command::new("backup")
+                    .about("Write the master key to a backup file")
+                    .arg(arg!(-f --file <FILE> "Backup file path").required(true)),
+            )
+            .subcommand(
+                Command::new("address")
+                    .about("Show the derived funding addresses of a role")
+                    .arg(
+                        arg!(-r --role <ROLE> "Role to derive the addresses of")
+                            .required(true)
+                            .value_parser(PossibleValuesParser::new([
+                                "depositor",
+                                "operator",
+                                "verifier",
+                                "withdrawer",
+                            ])),
+                    )
+                    .arg(graph_index_arg())
+                    .arg(
+                        arg!(-i --index <INDEX> "First derivation index")
+                            .required(false)
+                            .value_parser(clap::value_parser!(u32).range(0..0x8000_0000))
+                            .default_value("0"),
+                    )
+                    .arg(
+                        arg!(-n --count <COUNT> "Number of addresses")
+                            .required(false)
+                            .value_parser(clap::value_parser!(u32).range(1..=1000))
+                            .default_value("1"),
+                    ),
+            )
+            .subcommand_required(true)
+    }
+
+    pub fn handle_wallet_command(
+        &self,
+        sub_matches: &ArgMatches,
+        network: Network,
+    ) -> io::Result<()> {
+        let mut config = self.read_config()?;
+
+        let (wallet, graph_index) = match sub_matches.subcommand() {
+            Some(("generate", args)) => {
+                if !args.get_flag("force") && has_keys(&config) {
+                    eprintln!("error: Keys are already configured. Use --force to overwrite them. Graphs created with the old keys can only be processed with the old keys.");
+                    return Ok(());
+                }
+                let (wallet, mnemonic) = HdWallet::generate(network);
+                println!("Mnemonic: {}", mnemonic);
+                println!("Write down the mnemonic and keep it safe, it is not stored in the config and is the only way to restore the keys other than 'wallet backup'.");
+                (wallet, *args.get_one::<u32>("graph-index").unwrap())
+            }
+            Some(("restore", args)) => {
+                if !args.get_flag("force") && has_keys(&config) {
+                    eprintln!("error: Keys are already configured. Use --force to overwrite them.");
+                    return Ok(());
+                }
+                let wallet = match args.get_one::<String>("mnemonic") {
+                    Some(mnemonic) => HdWallet::from_mnemonic(
+                        mnemonic,
+                        args.get_one::<String>("passphrase")
+                            .map_or("", |passphrase| passphrase.as_str()),
+                        network,
+                    ),
+                    None => HdWallet::from_xprv(args.get_one::<String>("xprv").unwrap()),
+                };
+                match wallet {
+                    Ok(wallet) => (wallet, *args.get_one::<u32>("graph-index").unwrap()),
+                    Err(e) => {
+                        eprintln!("error: {}", e);
+                        return Ok(());
+                    }
+                }
+            }
+            Some(("backup", args)) => {
+                let file = args.get_one::<String>("file").unwrap();
+                match &config.keys.master_key {
+                    Some(master_key) => {
+                        let mut backup_file =
+                            OpenOptions::new().create_new(true).write(true).open(file)?;
+                        backup_file.write_all(master_key.as_bytes())?;
+                        println!("Master key written to {}", file);
+                    }
+                    None => eprintln!("error: No master key is configured."),
+                }
+                return Ok(());
+            }
+            Some(("address", args)) => {
+                let Some(master_key) = &config.keys.master_key else {
+                    eprintln!("error: No master key is configured.");
+                    return Ok(());
+                };
+                let wallet = HdWallet::from_xprv(master_key).expect("Invalid master key in config");
+                let role = role_from_str(args.get_one::<String>("role").unwrap());
+                let graph_index = *args.get_one::<u32>("graph-index").unwrap();
+                let first_index = *args.get_one::<u32>("index").unwrap();
+                let count = *args.get_one::<u32>("count").unwrap();
+                for index in first_index..first_index.saturating_add(count).min(0x8000_0000) {
+                    println!(
+                        "{}: {}",
+                        wallet.derivation_path(role, graph_index, index),
+                        wallet.bip86_address(role, graph_index, index, network)
+                    );
+                }
+                return Ok(());
+            }
+            Some(("graph", args)) => {
+                let Some(master_key) = &config.keys.master_key else {
+                    eprintln!("error: No master key is configured.");
+                    return Ok(());
+                };
+                let wallet = HdWallet::from_xprv(master_key).expect("Invalid master key in config");
+                (wallet, *args.get_one::<u32>("graph-index").unwrap())
+            }
+            _ => unreachable!(),
+        };
+
+        config.keys.master_key = Some(wallet.to_xprv());
+        config.keys.graph_index = Some(graph_index);
+        for role in [
+            Role::Depositor,
+            Role::Operator,
+            Role::Verifier,
+            Role::Withdrawer,
+        ] {
+            let role_graph_index = role_graph_index(role, graph_index);
+            let secret_key = Some(wallet.signing_secret_key(role, role_graph_index));
+            match role {
+                Role::Depositor => config.keys.depositor = secret_key,
+                Role::Operator => config.keys.operator = secret_key,
+                Role::Verifier => config.keys.verifier = secret_key,
+                Role::Withdrawer => config.keys.withdrawer = secret_key,
+            }
+            println!(
+                "{:?} key {} derived at {}",
+                role,
+                wallet.public_key(role, role_graph_index, 0),
+                wallet.derivation_path(role, role_graph_index, 0)
+            );
+        }
+        config.keys.commitment_seed = Some(wallet.commitment_seed().to_hex());
+        self.write_config(&config)?;
+
+        println!("Keys and commitment seed saved successfully! Back up the mnemonic or the master key with 'wallet backup'.");
+
+        Ok(())
+    }
+
     // TODO: This is TBD. Verifying key validation is unclear at the moment.
     // We'll add it once circuit design is finalized and we can run a Groth16 setup.
     fn validate_verifying_key(&self, _key: &str) -> bool { true }
 }
 
+fn has_keys(config: &Config) -> bool {
+    config.keys.master_key.is_some()
+        || config.keys.depositor.is_some()
+        || config.keys.operator.is_some()
+        || config.keys.verifier.is_some()
+        || config.keys.withdrawer.is_some()
+        || config.keys.commitment_seed.is_some()
+}
+
+fn graph_index_arg() -> clap::Arg {
+    arg!(-g --"graph-index" <GRAPH_INDEX> "Graph index to derive the depositor and withdrawer keys at")
+        .required(false)
+        .value_parser(clap::value_parser!(u32).range(0..0x8000_0000))
+        .default_value("0")
+}
+
+/// Graph index the keys of `role` are derived at, when the depositor and withdrawer keys use
+/// `graph_index`.
+pub fn role_graph_index(role: Role, graph_index: u32) -> u32 {
+    match role {
+        Role::Depositor | Role::Withdrawer => graph_index,
+        Role::Operator | Role::Verifier => 0,
+    }
+}
+
+fn role_from_str(role: &str) -> Role {
+    match role {
+        "depositor" => Role::Depositor,
+        "operator" => Role::Operator,
+        "verifier" => Role::Verifier,
+        "withdrawer" => Role::Withdrawer,
+        _ => unreachable!(),
+    }
+}
+
 // TODO: Technically this should use the source network specified by the user. However, since this
 // is only used in console output as an ID, we can leave it for now.
 fn pubkey_of(private_key: &str) -> PublicKey {
@@ -30,34 +30,56 @@ const FAKE_SECRET: &str = "10000000000000000000000000000000000000000000000000000
 
 const QUERY_COMMAND_PATH_PREFIX: &str = "query_command";
 
+/// Creates the read-only client used to serve queries. It only knows the public keys of the
+/// n-of-n verifier set and uses throwaway secrets for the other roles. The data store entries
+/// of `operators` are accepted besides the verifiers'.
+pub async fn create_query_client(
+    source_network: Network,
+    destination_network: DestinationNetwork,
+    path_prefix: Option<&str>,
+    operators: &[PublicKey],
+) -> BitVMClient {
+    let (_, verifier_0_public_key) = generate_keys_from_secret(source_network, VERIFIER_0_SECRET);
+
+    let n_of_n_public_keys: Vec<PublicKey> = vec![verifier_0_public_key];
+
+    let mut client = BitVMClient::new(
+        Some(get_esplora_url(source_network)),
+        source_network,
+        destination_network,
+        Some(get_chain_adaptor(DestinationNetwork::Local, None, None)), // TODO: Update this according to the requirements for query command.
+        &n_of_n_public_keys,
+        Some(FAKE_SECRET),
+        Some(FAKE_SECRET),
+        None,
+        Some(VERIFIER_0_SECRET),
+        Some(FAKE_SECRET),
+        path_prefix.or(Some(QUERY_COMMAND_PATH_PREFIX)),
+        None,
+        None,
+    )
+    .await
+    .expect("Failed to create query client");
+    client.add_trusted_authors(operators);
+
+    client
+}
+
 impl QueryCommand {
     pub async fn new(
         source_network: Network,
         destination_network: DestinationNetwork,
         path_prefix: Option<&str>,
+        operators: &[PublicKey],
     ) -> Self {
-        let (_, verifier_0_public_key) =
-            generate_keys_from_secret(source_network, VERIFIER_0_SECRET);
-
-        let n_of_n_public_keys: Vec<PublicKey> = vec![verifier_0_public_key];
-
-        let bitvm_client = BitVMClient::new(
-            Some(get_esplora_url(source_network)),
-            source_network,
-            destination_network,
-            Some(get_chain_adaptor(DestinationNetwork::Local, None, None)), // TODO: Update this according to the requirements for query command.
-            &n_of_n_public_keys,
-            Some(FAKE_SECRET),
-            Some(FAKE_SECRET),
-            Some(VERIFIER_0_SECRET),
-            Some(FAKE_SECRET),
-            path_prefix.or(Some(QUERY_COMMAND_PATH_PREFIX)),
-            None,
-        )
-        .await;
-
         Self {
-            client: bitvm_client,
+            client: create_query_client(
+                source_network,
+                destination_network,
+                path_prefix,
+                operators,
+            )
+            .await,
             network: source_network,
         }
     }
@@ -181,17 +203,22 @@ impl QueryCommand {
         let outpoint = self
             .generate_stub_outpoint(&self.client, &taproot_address, amount)
             .await;
-        let result = self.client.generate_presign_pegin_confirm_tx(
+        match self.client.generate_presign_pegin_confirm_tx(
             self.network,
             recipient_address,
             amount,
             &depositor_taproot_key,
             outpoint,
-        );
-        Response::new(
-            ResponseStatus::OK,
-            Some(serde_json::to_value(result).unwrap()),
-        )
+        ) {
+            Ok(result) => Response::new(
+                ResponseStatus::OK,
+                Some(serde_json::to_value(result).unwrap()),
+            ),
+            Err(err) => Response::new(
+                ResponseStatus::NOK(format!("Failed to pre-sign peg-in confirm: {err}")),
+                None,
+            ),
+        }
     }
 
     pub async fn handle_depositor(&mut self, matches: &ArgMatches) -> Response {
@@ -24,6 +24,8 @@ pub fn get_mock_chain_service(outpoint: OutPoint, operator_public_key: PublicKey
         }]),
         peg_out_burnt_events: None,
         peg_out_minted_events: None,
+        peg_out_burnt_proofs: None,
+        finalized_block_hash: None,
     };
     let mock_adaptor = MockAdaptor::new(Some(mock_adaptor_config));
     Chain::new(Box::new(mock_adaptor))
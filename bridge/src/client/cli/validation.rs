@@ -2,6 +2,7 @@ use alloy::primitives::Address;
 use bitcoin::{ecdsa, taproot, Amount, Denomination, OutPoint, PublicKey};
 use clap::ArgMatches;
 use core::str::FromStr;
+use serde_json::{Map, Value};
 
 use crate::constants::DestinationNetwork;
 
@@ -14,6 +15,7 @@ pub enum ArgType {
     Satoshis(Amount),
     EcdsaSignature(ecdsa::Signature),
     TaprootSignature(taproot::Signature),
+    GraphId(String),
 }
 
 pub fn validate(
@@ -24,62 +26,7 @@ pub fn validate(
     let mut result: Vec<ArgType> = vec![];
     for arg in args.iter() {
         match matches.get_one::<String>(arg) {
-            Some(value) => match arg.as_str() {
-                "DEPOSITOR_PUBLIC_KEY" => match PublicKey::from_str(value) {
-                    Ok(pubkey) => result.push(ArgType::DepositorPublicKey(pubkey)),
-                    Err(_) => {
-                        return Err(error_response(
-                            "Invalid public key. Use bitcoin public key format.".to_string(),
-                        ))
-                    }
-                },
-                "DESTINATION_CHAIN_ADDRESS" | "WITHDRAWER_CHAIN_ADDRESS" => {
-                    match Address::from_str(value) {
-                        Ok(address) => result.push(ArgType::ChainAddress(address)),
-                        Err(_) => {
-                            return Err(error_response(format!(
-                                "Invalid {}. Use {} address format.",
-                                arg.as_str(),
-                                destination_network
-                            )))
-                        }
-                    }
-                }
-                "OUTPOINT" => match OutPoint::from_str(value) {
-                    Ok(outpoint) => result.push(ArgType::OutPoint(outpoint)),
-                    Err(_) => {
-                        return Err(error_response(
-                            "Invalid OutPoint. Use <txid>:<vout> format.".to_string(),
-                        ))
-                    }
-                },
-                "SATS" => match Amount::from_str_in(value, Denomination::Satoshi) {
-                    Ok(amount) => result.push(ArgType::Satoshis(amount)),
-                    Err(_) => {
-                        return Err(error_response(
-                            "Invalid amount of satoshis. Use u64.".to_string(),
-                        ))
-                    }
-                },
-                "DEPOSIT" => match ecdsa::Signature::from_slice(value.as_bytes()) {
-                    Ok(sig) => result.push(ArgType::EcdsaSignature(sig)),
-                    Err(_) => {
-                        return Err(error_response(
-                            "Invalid format of ecdsa signature.".to_string(),
-                        ))
-                    }
-                },
-                "CONFIRM" | "REFUND" => match taproot::Signature::from_slice(value.as_bytes()) {
-                    Ok(sig) => result.push(ArgType::TaprootSignature(sig)),
-                    Err(_) => {
-                        return Err(error_response(format!(
-                            "Invalid format of taproot signature for {} transaction.",
-                            arg.as_str()
-                        )))
-                    }
-                },
-                _ => return Err(error_response(format!("Invalid argument: {}", arg))),
-            },
+            Some(value) => result.push(validate_arg(arg, value, destination_network)?),
             None => return Err(error_response(format!("Missing argument: {}", arg))),
         }
     }
@@ -87,6 +34,102 @@ pub fn validate(
     Ok(result)
 }
 
+/// Same as `validate`, but reads the arguments from named JSON-RPC parameters. Parameter names
+/// are the lowercase argument names, e.g. `depositor_public_key` for `DEPOSITOR_PUBLIC_KEY`.
+/// JSON strings can't carry the raw bytes of signatures, so signatures are hex encoded.
+pub fn validate_params(
+    params: &Map<String, Value>,
+    args: Vec<String>,
+    destination_network: DestinationNetwork,
+) -> Result<Vec<ArgType>, Response> {
+    let mut result: Vec<ArgType> = vec![];
+    for arg in args.iter() {
+        let name = arg.to_lowercase();
+        match params.get(&name) {
+            Some(Value::String(value)) if is_signature_arg(arg) => {
+                // Invalid hex is reported as an invalid signature.
+                let signature = hex::decode(value).unwrap_or_default();
+                result.push(validate_signature_arg(arg, &signature)?)
+            }
+            Some(Value::String(value)) => {
+                result.push(validate_arg(arg, value, destination_network)?)
+            }
+            Some(Value::Number(value)) => {
+                result.push(validate_arg(arg, &value.to_string(), destination_network)?)
+            }
+            Some(_) => return Err(error_response(format!("Invalid argument: {}", name))),
+            None => return Err(error_response(format!("Missing argument: {}", name))),
+        }
+    }
+
+    Ok(result)
+}
+
+fn validate_arg(
+    arg: &str,
+    value: &str,
+    destination_network: DestinationNetwork,
+) -> Result<ArgType, Response> {
+    match arg {
+        "DEPOSITOR_PUBLIC_KEY" => match PublicKey::from_str(value) {
+            Ok(pubkey) => Ok(ArgType::DepositorPublicKey(pubkey)),
+            Err(_) => Err(error_response(
+                "Invalid public key. Use bitcoin public key format.".to_string(),
+            )),
+        },
+        "DESTINATION_CHAIN_ADDRESS" | "WITHDRAWER_CHAIN_ADDRESS" => {
+            match Address::from_str(value) {
+                Ok(address) => Ok(ArgType::ChainAddress(address)),
+                Err(_) => Err(error_response(format!(
+                    "Invalid {}. Use {} address format.",
+                    arg, destination_network
+                ))),
+            }
+        }
+        "OUTPOINT" => match OutPoint::from_str(value) {
+            Ok(outpoint) => Ok(ArgType::OutPoint(outpoint)),
+            Err(_) => Err(error_response(
+                "Invalid OutPoint. Use <txid>:<vout> format.".to_string(),
+            )),
+        },
+        "SATS" => match Amount::from_str_in(value, Denomination::Satoshi) {
+            Ok(amount) => Ok(ArgType::Satoshis(amount)),
+            Err(_) => Err(error_response(
+                "Invalid amount of satoshis. Use u64.".to_string(),
+            )),
+        },
+        "DEPOSIT" | "CONFIRM" | "REFUND" => validate_signature_arg(arg, value.as_bytes()),
+        // Graph ids are upper case hex encoded sha256 hashes.
+        "GRAPH_ID" if value.len() == 64 && value.chars().all(|c| c.is_ascii_hexdigit()) => {
+            Ok(ArgType::GraphId(value.to_uppercase()))
+        }
+        "GRAPH_ID" => Err(error_response(
+            "Invalid graph id. Use 32 bytes hex format.".to_string(),
+        )),
+        _ => Err(error_response(format!("Invalid argument: {}", arg))),
+    }
+}
+
+fn is_signature_arg(arg: &str) -> bool { matches!(arg, "DEPOSIT" | "CONFIRM" | "REFUND") }
+
+fn validate_signature_arg(arg: &str, signature: &[u8]) -> Result<ArgType, Response> {
+    match arg {
+        "DEPOSIT" => match ecdsa::Signature::from_slice(signature) {
+            Ok(sig) => Ok(ArgType::EcdsaSignature(sig)),
+            Err(_) => Err(error_response(
+                "Invalid format of ecdsa signature.".to_string(),
+            )),
+        },
+        _ => match taproot::Signature::from_slice(signature) {
+            Ok(sig) => Ok(ArgType::TaprootSignature(sig)),
+            Err(_) => Err(error_response(format!(
+                "Invalid format of taproot signature for {} transaction.",
+                arg
+            ))),
+        },
+    }
+}
+
 fn error_response(err: String) -> Response {
     Response::new(ResponseStatus::NOK(err.to_string()), None)
 }
@@ -1,45 +1,49 @@
+use alloy::primitives::Address as EvmAddress;
 use bitcoin::{
-    absolute::Height, consensus::encode::serialize_hex, Address, Amount, Network, OutPoint,
-    PublicKey, ScriptBuf, Transaction, Txid, XOnlyPublicKey,
+    absolute::Height, consensus::encode::serialize_hex, key::Keypair, psbt::Psbt, Address, Amount,
+    Network, OutPoint, PublicKey, ScriptBuf, Transaction, Txid, XOnlyPublicKey,
 };
 use colored::Colorize;
 use esplora_client::{AsyncClient, Builder, TxStatus, Utxo};
-use futures::future::join_all;
+use futures::{future::join_all, Stream};
 use human_bytes::human_bytes;
 use musig2::SecNonce;
-use serde::{Deserialize, Serialize};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
 use serde_json::{json, Value};
 use std::{
-    collections::HashMap,
+    collections::{HashMap, HashSet},
     path::{Path, PathBuf},
 };
 
 use crate::{
-    client::{
-        chain::ethereum_adaptor::EthereumAdaptor, esplora::get_esplora_url,
-        files::DEFAULT_PATH_PREFIX,
-    },
-    commitments::CommitmentMessageId,
+    client::{esplora::get_esplora_url, files::DEFAULT_PATH_PREFIX},
+    commitments::{CommitmentMessageId, CommitmentSeed},
     common::ZkProofVerifyingKey,
     connectors::{base::TaprootConnector, connector_0::Connector0, connector_z::ConnectorZ},
-    constants::DestinationNetwork,
+    constants::{DestinationNetwork, NUM_BLOCKS_PER_DAY},
     contexts::base::generate_n_of_n_public_key,
-    error::{ClientError, Error},
+    error::{ClientError, Error, GraphError, L2Error, PsbtError},
     graphs::{
-        base::{
-            broadcast_and_verify, get_tx_statuses, GraphId, PEG_OUT_FEE, REWARD_MULTIPLIER,
-            REWARD_PRECISION,
-        },
-        peg_in::{PegInDepositorStatus, PegInVerifierStatus},
-        peg_out::PegOutOperatorStatus,
+        base::{get_tx_statuses, validate_and_broadcast, GraphId, PEG_OUT_FEE},
+        challenge_crowdfunding::ChallengeCrowdfundingStatus,
+        export::{GraphExport, GraphExportFormat},
+        operator_set::OperatorSet,
+        peg_in::{PegInDepositorStatus, PegInRecoveryStatus, PegInVerifierStatus},
+        peg_out::{PegOutOperatorStatus, PegOutVerifierStatus},
     },
+    parameters::ProtocolParameters,
     proof::get_proof,
-    scripts::generate_pay_to_pubkey_script_address,
-    serialization::{serialize, try_deserialize_slice},
+    scripts::{generate_pay_to_pubkey_script, generate_pay_to_pubkey_script_address},
     transactions::{
-        peg_in_confirm::PegInConfirmTransaction, peg_in_deposit::PegInDepositTransaction,
-        peg_in_refund::PegInRefundTransaction, pre_signed_musig2::PreSignedMusig2Transaction,
+        fee_table::{measure_vsizes, peg_out_fee, render_fee_table, stale_relay_fees},
+        peg_in_confirm::PegInConfirmTransaction,
+        peg_in_deposit::PegInDepositTransaction,
+        peg_in_refund::PegInRefundTransaction,
+        pre_signed_musig2::PreSignedMusig2Transaction,
+        psbt::{export_psbt, finalize_psbt},
+        validation::graph_prev_outs,
     },
+    utils::num_blocks_per_network,
 };
 
 use bitvm::{
@@ -58,23 +62,41 @@ use super::{
             peg_out::{generate_id as peg_out_generate_id, PegOutGraph},
         },
         transactions::{
-            base::{Input, InputWithScript},
+            base::{Input, InputWithScript, MIN_RELAY_FEE_PEG_OUT},
             pre_signed::PreSignedTransaction,
         },
     },
-    chain::{chain::Chain, chain_adaptor::ChainAdaptor},
-    data_store::data_store::DataStore,
+    chain::{
+        chain::Chain,
+        chain_adaptor::{get_chain_adaptor, ChainAdaptor},
+        l2_proof::{L2Proof, L2ProofConfig},
+    },
+    data_store::{
+        changeset::{
+            accept_depositor_submission, apply_graph_change, diff_data, diff_depositor_submission,
+            Change, Changeset,
+        },
+        data_store::{
+            get_author_file_path, get_depositor_inbox_file_path, DataFileKind, DataStore,
+        },
+        envelope::{open_envelope, open_submission_envelope},
+    },
+    events::{EventEmitter, PegInStatus, PegOutStatus, RecordedEvent, Role},
     files::{
-        get_private_data_file_path, get_private_data_from_file, save_local_private_file,
-        save_local_public_file, BRIDGE_DATA_DIRECTORY_NAME,
+        get_event_log_file_path, get_event_state_file_path, get_private_data_file_path,
+        get_private_data_from_file, save_local_private_file, save_local_public_file,
+        BRIDGE_DATA_DIRECTORY_NAME,
     },
+    schema::{deserialize_versioned, serialize_versioned, SchemaId},
     sdk::{
         query::{ClientCliQuery, GraphCliQuery},
         query_contexts::depositor_signatures::DepositorSignatures,
     },
+    wallet::funding::FundingWallet,
 };
 
-const TEN_MINUTES: u64 = 10 * 60;
+// Number of changesets pushed between two checkpoints.
+const CHECKPOINT_INTERVAL: usize = 20;
 
 pub type UtxoSet = HashMap<OutPoint, Height>;
 
@@ -95,6 +117,16 @@ impl BitVMClientPublicData {
         }
         panic!("Graph ID not found");
     }
+
+    pub fn graph(&self, graph_id: &GraphId) -> Option<&dyn BaseGraph> {
+        if let Some(peg_in) = self.peg_in_graphs.iter().find(|x| x.id() == graph_id) {
+            return Some(peg_in);
+        }
+        if let Some(peg_out) = self.peg_out_graphs.iter().find(|x| x.id() == graph_id) {
+            return Some(peg_out);
+        }
+        None
+    }
 }
 
 #[derive(Serialize, Deserialize, Eq, PartialEq)]
@@ -107,6 +139,10 @@ pub struct BitVMClientPrivateData {
     // Operator public key -> Graph ID -> Message ID -> Winternitz secret
     pub commitment_secrets:
         HashMap<PublicKey, HashMap<String, HashMap<CommitmentMessageId, WinternitzSecret>>>,
+    // Outputs provided by `fund` that are not spent yet, e.g. challenge contributions. They are
+    // not selected to fund anything else.
+    #[serde(default)]
+    pub reserved_outpoints: HashSet<OutPoint>,
 }
 
 pub struct BitVMClient {
@@ -117,18 +153,43 @@ pub struct BitVMClient {
     operator_context: Option<OperatorContext>,
     verifier_context: Option<VerifierContext>,
     withdrawer_context: Option<WithdrawerContext>,
+    n_of_n_public_keys: Vec<PublicKey>,
 
     data_store: DataStore,
     data: BitVMClientPublicData,
-    latest_processed_file_name: Option<String>,
+    latest_processed_file_names: HashMap<PublicKey, String>,
+    latest_processed_depositor_submission: Option<String>,
+    // Data as of the last push, the next changeset is made of the changes since.
+    published_data: Option<BitVMClientPublicData>,
+    changesets_since_checkpoint: usize,
     remote_file_path: String,
+    // Participants other than the verifiers whose data store entries are accepted.
+    trusted_authors: Vec<PublicKey>,
+    operator_set: OperatorSet,
     local_file_path: PathBuf,
 
     private_data: BitVMClientPrivateData,
+    commitment_seed: Option<CommitmentSeed>,
 
     chain_service: Chain,
 
     zkproof_verifying_key: Option<ZkProofVerifyingKey>,
+
+    protocol_parameters: ProtocolParameters,
+
+    // Blocks after the deposit confirmed after which a peg-in without n-of-n signatures is stuck.
+    peg_in_signing_deadline: u32,
+
+    // Address of the L2 bridge contract. If set, burns on the L2 chain must be proven before
+    // kick-off 1, or else get challenged.
+    l2_bridge_address: Option<EvmAddress>,
+
+    events: Option<EventEmitter>,
+
+    // Additional keys whose UTXOs can fund the transactions of each role.
+    funding_keys: HashMap<Role, Vec<Keypair>>,
+    // Whether funding wallets may spend UTXOs that are not confirmed yet.
+    spend_unconfirmed_utxos: bool,
 }
 
 impl BitVMClient {
@@ -141,11 +202,19 @@ impl BitVMClient {
         n_of_n_public_keys: &[PublicKey],
         depositor_secret: Option<&str>,
         operator_secret: Option<&str>,
+        operator_commitment_seed: Option<&str>,
         verifier_secret: Option<&str>,
         withdrawer_secret: Option<&str>,
         file_path_prefix: Option<&str>,
         zkproof_verifying_key: Option<ZkProofVerifyingKey>,
-    ) -> Self {
+        protocol_parameters: Option<ProtocolParameters>,
+    ) -> Result<Self, Error> {
+        let protocol_parameters =
+            protocol_parameters.unwrap_or_else(|| ProtocolParameters::for_network(source_network));
+        if !protocol_parameters.validate() {
+            panic!("Invalid protocol parameters: {:?}", protocol_parameters);
+        }
+
         let mut depositor_context = None;
         if depositor_secret.is_some() {
             depositor_context = Some(DepositorContext::new(
@@ -209,31 +278,53 @@ impl BitVMClient {
         let private_data =
             get_private_data_from_file(&get_private_data_file_path(&local_file_path));
 
-        Self {
+        let commitment_seed = operator_commitment_seed
+            .map(CommitmentSeed::from_hex)
+            .transpose()
+            .map_err(|e| Error::Client(ClientError::InvalidCommitmentSeed(e)))?;
+
+        Ok(Self {
             esplora: Builder::new(esplora_url.unwrap_or(get_esplora_url(source_network)))
                 .build_async()
-                .expect("Could not build esplora client"),
+                .map_err(Error::Esplora)?,
             source_network,
 
             depositor_context,
             operator_context,
             verifier_context,
             withdrawer_context,
+            n_of_n_public_keys: n_of_n_public_keys.to_vec(),
 
             data_store,
             data,
-            latest_processed_file_name: None,
+            latest_processed_file_names: HashMap::new(),
+            latest_processed_depositor_submission: None,
+            published_data: None,
+            changesets_since_checkpoint: 0,
             remote_file_path,
+            trusted_authors: vec![],
+            operator_set: OperatorSet::default(),
             local_file_path,
 
             private_data,
+            commitment_seed,
 
             chain_service: Chain::new(
-                chain_adaptor.unwrap_or_else(|| Box::new(EthereumAdaptor::new(None))),
+                chain_adaptor.unwrap_or_else(|| get_chain_adaptor(destination_network, None, None)),
             ),
 
             zkproof_verifying_key,
-        }
+
+            protocol_parameters,
+
+            peg_in_signing_deadline: num_blocks_per_network(source_network, NUM_BLOCKS_PER_DAY),
+
+            l2_bridge_address: None,
+
+            events: None,
+            funding_keys: HashMap::new(),
+            spend_unconfirmed_utxos: false,
+        })
     }
 
     pub fn data(&self) -> &BitVMClientPublicData { &self.data }
@@ -243,16 +334,129 @@ impl BitVMClient {
     // TODO: This should be private. Currently used in the fees test. See if it can be refactored.
     pub fn private_data(&self) -> &BitVMClientPrivateData { &self.private_data }
 
+    pub fn protocol_parameters(&self) -> &ProtocolParameters { &self.protocol_parameters }
+
+    /// Subscribes to graph status changes and confirmations of the transactions broadcast by
+    /// this client. Events are only tracked once there is a subscription, and are also appended
+    /// to the event log in the local data directory. Statuses already reported and transactions
+    /// still watched before a restart are picked up again from there.
+    pub fn subscribe_events(&mut self) -> impl Stream<Item = RecordedEvent> {
+        self.events
+            .get_or_insert_with(|| {
+                EventEmitter::new(
+                    &get_event_log_file_path(&self.local_file_path),
+                    &get_event_state_file_path(&self.local_file_path),
+                )
+            })
+            .subscribe()
+    }
+
+    pub fn event_log_path(&self) -> PathBuf { get_event_log_file_path(&self.local_file_path) }
+
+    /// Emits events for the status changes and transaction confirmations since the last update.
+    /// Called on every sync.
+    pub async fn update_events(&mut self) {
+        let Some(events) = self.events.as_mut() else {
+            return;
+        };
+
+        let mut own_peg_out_graph_ids = HashSet::new();
+        for peg_in_graph in self.data.peg_in_graphs.iter() {
+            if let Some(context) = &self.depositor_context {
+                if peg_in_graph
+                    .depositor_public_key
+                    .eq(&context.depositor_public_key)
+                {
+                    let status = peg_in_graph.depositor_status(&self.esplora).await;
+                    events.update_peg_in_status(peg_in_graph.id(), PegInStatus::Depositor(status));
+                    if let Ok(status) = peg_in_graph
+                        .recovery_status(&self.esplora, self.peg_in_signing_deadline)
+                        .await
+                    {
+                        events.update_peg_in_recovery_status(peg_in_graph.id(), status);
+                    }
+                }
+            }
+            if let Some(context) = &self.operator_context {
+                let status = peg_in_graph.operator_status(&self.esplora).await;
+                events.update_peg_in_status(peg_in_graph.id(), PegInStatus::Operator(status));
+                own_peg_out_graph_ids.insert(peg_out_generate_id(
+                    peg_in_graph,
+                    &context.operator_public_key,
+                ));
+            }
+            if let Some(context) = &self.verifier_context {
+                let peg_outs = self
+                    .data
+                    .peg_out_graphs
+                    .iter()
+                    .filter(|peg_out| peg_in_graph.peg_out_graphs.contains(peg_out.id()))
+                    .collect::<Vec<_>>();
+                let status = peg_in_graph
+                    .verifier_status_for_operators(
+                        &self.esplora,
+                        context,
+                        &peg_outs,
+                        &self.operator_set,
+                    )
+                    .await;
+                events.update_peg_in_status(peg_in_graph.id(), PegInStatus::Verifier(status));
+            }
+        }
+
+        for peg_out_graph in self.data.peg_out_graphs.iter() {
+            if own_peg_out_graph_ids.contains(peg_out_graph.id()) {
+                let status = peg_out_graph.operator_status(&self.esplora).await;
+                events.update_peg_out_status(peg_out_graph.id(), PegOutStatus::Operator(status));
+            }
+            if let Some(context) = &self.verifier_context {
+                let status = peg_out_graph.verifier_status(&self.esplora, context).await;
+                events.update_peg_out_status(peg_out_graph.id(), PegOutStatus::Verifier(status));
+
+                let contributions =
+                    peg_out_graph.challenge_contributions_of(&context.verifier_public_key);
+                if !contributions.is_empty()
+                    && matches!(
+                        peg_out_graph
+                            .challenge_crowdfunding_status(&self.esplora)
+                            .await,
+                        Ok(ChallengeCrowdfundingStatus::Closed)
+                    )
+                {
+                    events.close_challenge_crowdfunding(peg_out_graph.id(), contributions);
+                }
+            }
+            if peg_out_graph.peg_out_chain_event.is_some() {
+                let status = peg_out_graph.withdrawer_status(&self.esplora).await;
+                events.update_peg_out_status(peg_out_graph.id(), PegOutStatus::Withdrawer(status));
+            }
+        }
+
+        for txid in events.watched_transactions() {
+            if let Ok(status) = self.esplora.get_tx_status(&txid).await {
+                if status.confirmed {
+                    events.confirm_transaction(txid, status.block_height);
+                }
+            }
+        }
+    }
+
     // TODO: This fn is only used in tests. Consider refactoring, so it can be removed.
     pub fn set_chain_service(&mut self, chain_service: Chain) {
         self.chain_service = chain_service;
     }
 
     fn save_private_data(&self) {
-        save_local_private_file(&self.local_file_path, &serialize(&self.private_data));
+        save_local_private_file(
+            &self.local_file_path,
+            &serialize_versioned(SchemaId::PrivateData, &self.private_data),
+        );
     }
 
-    pub async fn sync(&mut self) { self.read_from_data_store().await; }
+    pub async fn sync(&mut self) {
+        self.read_from_data_store().await;
+        self.update_events().await;
+    }
 
     pub async fn sync_l2(&mut self) { self.read_from_l2().await; }
 
@@ -260,55 +464,265 @@ impl BitVMClient {
 
     /*
     Expected file syncing flow with data store:
-     1. Fetch the latest file                               ⎫
-     2. Fetch all files within 10 minutes (use timestamp)   ⎬ BitVMClient::sync()
-     3. Merge files                                         ⎭
+     1. Fetch the latest checkpoint of each author           ⎫
+     2. Fetch the changesets pushed after it (use timestamp) ⎬ BitVMClient::sync()
+     3. Merge the checkpoint and apply the changesets       ⎭
      4. Client modifies file and clicks save                } BitVMClient::<mutating operation>
      5. Fetch files that were created after fetching 1-2.   ⎫
      6. Merge with your file                                ⎬ BitVMClient::flush()
-     7. Push the file to the server                         ⎭
+     7. Push a changeset, or a checkpoint every so often    ⎭
     */
 
     async fn read_from_data_store(&mut self) {
+        for author in self.data_store_authors() {
+            self.read_author_from_data_store(&author).await;
+        }
+        self.read_depositor_submissions_from_data_store().await;
+    }
+
+    // Merges the peg-in graphs submitted to the depositor inbox since the latest submission
+    // processed. Depositors are not known in advance, so their namespaces can't be read.
+    async fn read_depositor_submissions_from_data_store(&mut self) {
+        let file_path = get_depositor_inbox_file_path(&self.remote_file_path);
+        let file_names = match Self::get_latest_file_names(
+            &self.data_store,
+            Some(&file_path),
+            self.latest_processed_depositor_submission.clone(),
+        )
+        .await
+        {
+            Ok(file_names) => file_names,
+            Err(err) => {
+                println!("Error: {}", err);
+                return;
+            }
+        };
+
+        for file_name in file_names.into_iter() {
+            if self.data_store.get_file_kind(&file_name) == Ok(DataFileKind::Changeset) {
+                if let Some((author, changeset)) =
+                    Self::fetch_depositor_submission(&self.data_store, &file_name, &file_path).await
+                {
+                    let result = self.merge_as_published(|client| {
+                        client.apply_depositor_submission(&author, changeset.clone())
+                    });
+                    if let Err(err) = result {
+                        eprintln!("Refused part of depositor submission {file_name}: {err}");
+                    }
+                }
+            }
+            self.latest_processed_depositor_submission = Some(file_name);
+        }
+    }
+
+    async fn fetch_depositor_submission(
+        data_store: &DataStore,
+        key: &String,
+        file_path: &str,
+    ) -> Option<(PublicKey, Changeset)> {
+        let result = data_store
+            .fetch_compressed_data_by_key(key, Some(file_path))
+            .await;
+        if let Ok((Some(content), _)) = result {
+            let submission = data_store
+                .get_file_timestamp(key)
+                .and_then(|file_timestamp| {
+                    open_submission_envelope(&content, file_timestamp)
+                        .map_err(|err| Error::DataStore(err).to_string())
+                })
+                .and_then(|(author, payload)| {
+                    deserialize_versioned(SchemaId::Changeset, payload.as_bytes())
+                        .map(|changeset| (author, changeset))
+                        .map_err(|err| Error::Schema(err).to_string())
+                });
+            match submission {
+                Ok(submission) => return Some(submission),
+                Err(err) => eprintln!("Rejected depositor submission {key}: {err}"),
+            }
+        }
+
+        None
+    }
+
+    // Adds the new peg-in graphs `author` submitted as their depositor, validated like the
+    // graphs of a checkpoint. The valid ones are added regardless, and the first refusal is
+    // returned.
+    fn apply_depositor_submission(
+        &mut self,
+        author: &PublicKey,
+        changeset: Changeset,
+    ) -> Result<(), Error> {
+        let mut refusal = None;
+        for result in accept_depositor_submission(author, changeset.changes) {
+            match result {
+                Ok(peg_in_graph) if self.data.graph(peg_in_graph.id()).is_none() => {
+                    let result = self.merge_valid_data(BitVMClientPublicData {
+                        version: self.data.version,
+                        peg_in_graphs: vec![peg_in_graph],
+                        peg_out_graphs: vec![],
+                    });
+                    if let Err(err) = result {
+                        refusal.get_or_insert(err);
+                    }
+                }
+                // already known, e.g. from a verifier's entries
+                Ok(_) => {}
+                Err(err) => {
+                    refusal.get_or_insert(err);
+                }
+            }
+        }
+
+        match refusal {
+            Some(err) => Err(err),
+            None => Ok(()),
+        }
+    }
+
+    async fn read_author_from_data_store(&mut self, author: &PublicKey) {
+        let latest_processed_file_name = self.latest_processed_file_names.get(author).cloned();
         let latest_file_names_result = Self::get_latest_file_names(
             &self.data_store,
-            Some(&self.remote_file_path),
-            self.latest_processed_file_name.clone(),
+            Some(&get_author_file_path(&self.remote_file_path, author)),
+            latest_processed_file_name.clone(),
         )
         .await;
 
-        if latest_file_names_result.is_ok() {
-            let mut latest_file_names = latest_file_names_result.unwrap();
-            if !latest_file_names.is_empty() {
-                // fetch latest valid file
-                let (latest_file, latest_file_name) = Self::fetch_latest_valid_file(
-                    &self.data_store,
-                    &mut latest_file_names,
-                    Some(&self.remote_file_path),
-                )
-                .await;
-                if latest_file.is_some() && latest_file_name.is_some() {
+        match latest_file_names_result {
+            Ok(mut latest_file_names) => {
+                if latest_processed_file_name.is_none() {
+                    // the latest checkpoint already contains the changes pushed before it
+                    if let Some(checkpoint_position) =
+                        latest_file_names.iter().rposition(|file_name| {
+                            self.data_store.get_file_kind(file_name) == Ok(DataFileKind::Checkpoint)
+                        })
+                    {
+                        latest_file_names.drain(..checkpoint_position);
+                    }
+                }
+
+                let latest_valid_file_name =
+                    Self::process_files(self, author, latest_file_names).await;
+                if let Some(file_name) = latest_valid_file_name {
                     save_local_public_file(
                         &self.local_file_path,
-                        latest_file_name.as_ref().unwrap(),
-                        &serialize(&latest_file.as_ref().unwrap()),
+                        &file_name,
+                        &serialize_versioned(SchemaId::ClientData, &self.data),
                     );
-                    self.latest_processed_file_name = latest_file_name;
-
-                    // fetch and process all the previous files if latest valid file exists
-                    let result =
-                        Self::process_files_by_timestamp(self, latest_file_names, TEN_MINUTES)
-                            .await;
-                    match result {
-                        Ok(_) => (), // println!("Ok"),
-                        Err(err) => println!("Error: {}", err),
-                    }
-
-                    self.merge_data(latest_file.unwrap()); // merge the latest data at the end
+                    self.latest_processed_file_names.insert(*author, file_name);
                 }
             }
+            Err(err) => println!("Error: {}", err),
+        }
+    }
+
+    // Authors whose data store entries are merged: the verifiers, the trusted authors and the
+    // participants of this client.
+    fn data_store_authors(&self) -> Vec<PublicKey> {
+        let mut authors = self.n_of_n_public_keys.clone();
+        authors.extend(self.trusted_authors.iter().cloned());
+        authors.extend(
+            self.depositor_context
+                .as_ref()
+                .map(|context| context.depositor_public_key),
+        );
+        authors.extend(
+            self.operator_context
+                .as_ref()
+                .map(|context| context.operator_public_key),
+        );
+        authors.extend(
+            self.verifier_context
+                .as_ref()
+                .map(|context| context.verifier_public_key),
+        );
+        authors.extend(
+            self.withdrawer_context
+                .as_ref()
+                .map(|context| context.withdrawer_public_key),
+        );
+        authors.sort();
+        authors.dedup();
+
+        authors
+    }
+
+    /// Accepts the data store entries signed by `authors`, e.g. the operators. Entries signed
+    /// by the verifiers and by this client's participants are always accepted.
+    pub fn add_trusted_authors(&mut self, authors: &[PublicKey]) {
+        self.trusted_authors.extend_from_slice(authors);
+    }
+
+    /// Configures the operators expected to create a peg-out graph for every peg-in, and the
+    /// policy selecting the one graph that may be reimbursed. Their data store entries are
+    /// accepted, see `add_trusted_authors`.
+    pub fn set_operator_set(&mut self, operator_set: OperatorSet) {
+        self.add_trusted_authors(&operator_set.operator_public_keys);
+        self.operator_set = operator_set;
+    }
+
+    pub fn operator_set(&self) -> &OperatorSet { &self.operator_set }
+
+    /// Configures the number of blocks after the deposit confirmed within which the peg-in
+    /// confirm transaction must be signed, before the depositor schedules the refund.
+    pub fn set_peg_in_signing_deadline(&mut self, blocks: u32) {
+        self.peg_in_signing_deadline = blocks;
+    }
+
+    pub fn peg_in_signing_deadline(&self) -> u32 { self.peg_in_signing_deadline }
+
+    /// Configures the L2 bridge contract the burns of peg-outs are proven to be emitted by,
+    /// against the finalized L2 block reported by the chain adaptor. The operator then only
+    /// kicks off once the burn is proven, and verifiers challenge the kick-offs whose burn can't
+    /// be proven.
+    pub fn set_l2_bridge_address(&mut self, bridge_address: EvmAddress) {
+        self.l2_bridge_address = Some(bridge_address);
+    }
+
+    pub fn l2_bridge_address(&self) -> Option<&EvmAddress> { self.l2_bridge_address.as_ref() }
+
+    // Proofs of the burns of the peg-outs of the peg-ins confirmed in `source_txids`, anchored at
+    // the finalized L2 block, with the configuration to verify them. The chain adaptor reads the
+    // finalized block apart from the node the proofs are built from, so that proofs of a forged
+    // chain don't verify.
+    async fn get_peg_out_burnt_proofs(
+        &self,
+        bridge_address: EvmAddress,
+        source_txids: &[Txid],
+    ) -> Result<(Vec<L2Proof>, L2ProofConfig), Error> {
+        let finalized_block_hash = self
+            .chain_service
+            .get_finalized_block_hash()
+            .await
+            .map_err(|e| Error::L2(L2Error::ChainService(e)))?
+            .ok_or(Error::L2(L2Error::L2ProofsUnavailable))?;
+        let proofs = self
+            .chain_service
+            .get_peg_out_burnt_proofs(source_txids, &finalized_block_hash)
+            .await
+            .map_err(|e| Error::L2(L2Error::ChainService(e)))?;
+
+        Ok((
+            proofs,
+            L2ProofConfig {
+                bridge_address,
+                finalized_block_hash,
+            },
+        ))
+    }
+
+    // Key the data pushed to the data store is signed with, the verifier's if there is one.
+    fn data_store_author(&self) -> Option<(PublicKey, Keypair)> {
+        if let Some(context) = &self.verifier_context {
+            Some((context.verifier_public_key, context.verifier_keypair))
+        } else if let Some(context) = &self.operator_context {
+            Some((context.operator_public_key, context.operator_keypair))
+        } else if let Some(context) = &self.depositor_context {
+            Some((context.depositor_public_key, context.depositor_keypair))
         } else {
-            println!("Error: {}", latest_file_names_result.unwrap_err());
+            self.withdrawer_context
+                .as_ref()
+                .map(|context| (context.withdrawer_public_key, context.withdrawer_keypair))
         }
     }
 
@@ -332,6 +746,15 @@ impl BitVMClient {
                     }
                 }
             }
+            for event in events
+                .iter()
+                .filter(|event| !self.operator_set.contains(&event.operator_public_key))
+            {
+                println!(
+                    "Peg-out event fronted by operator {} outside of the operator set: {:?}",
+                    event.operator_public_key, event
+                );
+            }
         } else {
             panic!("Get event failed from L2 chain: {:?}", peg_out_result.err());
         }
@@ -366,135 +789,117 @@ impl BitVMClient {
         }
     }
 
-    async fn filter_files_names_by_timestamp(
-        &self,
-        latest_file_names: Vec<String>,
-        period: u64,
-    ) -> Result<Vec<String>, String> {
-        if self.latest_processed_file_name.is_some() {
-            let latest_timestamp = self
-                .data_store
-                .get_file_timestamp(self.latest_processed_file_name.as_ref().unwrap())?;
-
-            let past_max_file_name = self
-                .data_store
-                .get_past_max_file_name_by_timestamp(latest_timestamp, period);
-
-            let mut previous_max_position = latest_file_names
-                .iter()
-                .position(|file_name| file_name >= &past_max_file_name);
-            if previous_max_position.is_none() {
-                previous_max_position = Some(latest_file_names.len());
-            }
-
-            let file_names_to_process = latest_file_names
-                .clone()
-                .split_off(previous_max_position.unwrap());
-
-            Ok(file_names_to_process)
-        } else {
-            Err(String::from(
-                "No latest file data. Must fetch the latest file first.",
-            ))
-        }
-    }
-
-    async fn process_files_by_timestamp(
+    // Merges the checkpoints and applies the changesets of `author` in `file_names`, oldest
+    // first. Returns the name of the latest file processed.
+    async fn process_files(
         &mut self,
-        latest_file_names: Vec<String>,
-        period: u64,
-    ) -> Result<String, String> {
-        let file_names_to_process = self
-            .filter_files_names_by_timestamp(latest_file_names, period)
-            .await?;
-
-        Self::process_files(self, file_names_to_process).await;
-
-        Ok(String::from("Files processed"))
-    }
-
-    async fn process_files(&mut self, file_names: Vec<String>) -> Option<String> {
+        author: &PublicKey,
+        file_names: Vec<String>,
+    ) -> Option<String> {
         let mut latest_valid_file_name: Option<String> = None;
-        if file_names.is_empty() {
-            // println!("No additional files to process")
-        } else {
-            // TODO: can be optimized to fetch all data at once?
-            for file_name in file_names.iter() {
-                let result = self
-                    .data_store
-                    .fetch_compressed_data_by_key(file_name, Some(&self.remote_file_path))
-                    .await; // TODO: use `fetch_by_key()` function
-                if result.is_ok() && result.as_ref().unwrap().0.is_some() {
-                    let data = try_deserialize_slice(&(result.unwrap()).0.unwrap());
-                    if data.is_ok() && Self::validate_data(data.as_ref().unwrap()) {
-                        // merge the file if the data is valid
-                        println!("Merging {} data...", { file_name });
-                        self.merge_data(data.unwrap());
-                        if latest_valid_file_name.is_none() {
-                            latest_valid_file_name = Some(file_name.clone());
+        let file_path = get_author_file_path(&self.remote_file_path, author);
+        let authors = self.data_store_authors();
+        // TODO: can be optimized to fetch all data at once?
+        for file_name in file_names.into_iter() {
+            let is_valid = match self.data_store.get_file_kind(&file_name) {
+                Ok(DataFileKind::Checkpoint) => {
+                    let (data, data_len, encoded_size) =
+                        Self::fetch_by_key::<BitVMClientPublicData>(
+                            &self.data_store,
+                            SchemaId::ClientData,
+                            &file_name,
+                            Some(&file_path),
+                            author,
+                            &authors,
+                        )
+                        .await;
+                    match data {
+                        Some(data) if Self::validate_data(&data, &self.protocol_parameters) => {
+                            println!(
+                                "Merging checkpoint {} (size: {}, compressed: {})...",
+                                file_name,
+                                human_bytes(data_len as f64),
+                                human_bytes(encoded_size as f64)
+                            );
+                            let result =
+                                self.merge_as_published(|client| client.merge_data(data.clone()));
+                            if let Err(err) = result {
+                                eprintln!("Refused part of checkpoint {file_name}: {err}");
+                            }
+                            true
                         }
-                    } else {
-                        // skip the file if the data is invalid
-                        println!("Invalid file {}, Skipping...", file_name);
+                        _ => false,
                     }
                 }
-            }
-        }
-
-        latest_valid_file_name
-    }
-
-    async fn fetch_latest_valid_file(
-        data_store: &DataStore,
-        file_names: &mut Vec<String>,
-        file_path: Option<&str>,
-    ) -> (Option<BitVMClientPublicData>, Option<String>) {
-        let mut latest_valid_file: Option<BitVMClientPublicData> = None;
-        let mut latest_valid_file_name: Option<String> = None;
-
-        while !file_names.is_empty() {
-            let file_name_result = file_names.pop();
-            if file_name_result.is_some() {
-                let file_name = file_name_result.unwrap();
-                let (latest_data, latest_data_len, encoded_size) =
-                    Self::fetch_by_key(data_store, &file_name, file_path).await;
-                if latest_data.is_some() && Self::validate_data(latest_data.as_ref().unwrap()) {
-                    // data is valid
-                    println!(
-                        "Fetched valid file: {} (size: {}, compressed: {})",
-                        file_name,
-                        human_bytes(latest_data_len as f64),
-                        human_bytes(encoded_size as f64)
-                    );
-                    latest_valid_file = latest_data;
-                    latest_valid_file_name = Some(file_name);
-                    break;
-                } else {
-                    println!("Invalid file: {}", file_name); // TODO: can be removed
+                Ok(DataFileKind::Changeset) => {
+                    let (changeset, _, _) = Self::fetch_by_key::<Changeset>(
+                        &self.data_store,
+                        SchemaId::Changeset,
+                        &file_name,
+                        Some(&file_path),
+                        author,
+                        &authors,
+                    )
+                    .await;
+                    match changeset {
+                        Some(changeset) => {
+                            println!(
+                                "Applying changeset {} ({} changes)...",
+                                file_name,
+                                changeset.changes.len()
+                            );
+                            let result = self.merge_as_published(|client| {
+                                client.apply_changeset(changeset.clone())
+                            });
+                            if let Err(err) = result {
+                                eprintln!("Refused part of changeset {file_name}: {err}");
+                            }
+                            true
+                        }
+                        None => false,
+                    }
                 }
-                // for invalid data try another file
+                Err(_) => false,
+            };
+
+            if is_valid {
+                latest_valid_file_name = Some(file_name);
+            } else {
+                // skip the file if the data is invalid
+                println!("Invalid file {}, Skipping...", file_name);
             }
         }
 
-        (latest_valid_file, latest_valid_file_name)
+        latest_valid_file_name
     }
 
-    async fn fetch_by_key(
+    // Fetches the data of `schema` signed by `author` in the file `key`, migrated to the current
+    // version of the schema.
+    async fn fetch_by_key<T: DeserializeOwned>(
         data_store: &DataStore,
+        schema: SchemaId,
         key: &String,
         file_path: Option<&str>,
-    ) -> (Option<BitVMClientPublicData>, usize, usize) {
+        author: &PublicKey,
+        trusted_authors: &[PublicKey],
+    ) -> (Option<T>, usize, usize) {
         let result = data_store
             .fetch_compressed_data_by_key(key, file_path)
             .await;
-        if result.is_ok() {
-            if let (Some(content), encoded_size) = result.unwrap() {
-                let data = try_deserialize_slice(&content);
-                if let Ok(data) = data {
-                    return (Some(data), content.len(), encoded_size);
-                } else {
-                    eprintln!("{}", data.err().unwrap());
-                }
+        if let Ok((Some(content), encoded_size)) = result {
+            let payload = data_store
+                .get_file_timestamp(key)
+                .and_then(|file_timestamp| {
+                    open_envelope(&content, author, trusted_authors, file_timestamp)
+                        .map_err(|err| Error::DataStore(err).to_string())
+                })
+                .and_then(|payload| {
+                    deserialize_versioned(schema, payload.as_bytes())
+                        .map_err(|err| Error::Schema(err).to_string())
+                });
+            match payload {
+                Ok(data) => return (Some(data), content.len(), encoded_size),
+                Err(err) => eprintln!("Rejected data file {key}: {err}"),
             }
         }
 
@@ -503,27 +908,73 @@ impl BitVMClient {
 
     async fn save_to_data_store(&mut self) {
         // read newly created data before pushing
-        let latest_file_names_result = Self::get_latest_file_names(
-            &self.data_store,
-            Some(&self.remote_file_path),
-            self.latest_processed_file_name.clone(),
-        )
-        .await;
+        self.read_from_data_store().await;
+
+        let Some((author, keypair)) = self.data_store_author() else {
+            println!("Failed to push: no key to sign the data with");
+            return;
+        };
 
-        if latest_file_names_result.is_ok() {
-            let mut latest_file_names = latest_file_names_result.unwrap();
-            latest_file_names.reverse();
-            let latest_valid_file_name = Self::process_files(self, latest_file_names).await;
-            self.latest_processed_file_name = latest_valid_file_name;
+        // Participants only read the namespaces of the verifiers and of the authors they trust,
+        // so depositors submit their peg-in graphs to the depositor inbox too. A verifier's
+        // namespace is read by everyone.
+        if let (Some(context), None) = (&self.depositor_context, &self.verifier_context) {
+            let empty_data = BitVMClientPublicData {
+                version: self.data.version,
+                peg_in_graphs: vec![],
+                peg_out_graphs: vec![],
+            };
+            let changes = diff_depositor_submission(
+                self.published_data.as_ref().unwrap_or(&empty_data),
+                &self.data,
+                &context.depositor_public_key,
+            );
+            if !changes.is_empty() {
+                let result = self
+                    .data_store
+                    .write_depositor_submission(
+                        &serialize_versioned(SchemaId::Changeset, &Changeset { changes }),
+                        &context.depositor_public_key,
+                        &context.depositor_keypair,
+                        &self.remote_file_path,
+                    )
+                    .await;
+                match result {
+                    Ok((file_name, _)) => println!("Submitted peg-in graphs: {}", file_name),
+                    Err(err) => {
+                        // retried on the next push, as nothing is marked published
+                        println!("Failed to push: {}", err);
+                        return;
+                    }
+                }
+            }
         }
 
-        // push data
-        self.data.version += 1;
+        // push data, compacting the changes into a checkpoint every so often
+        let (kind, contents) = match &self.published_data {
+            Some(published_data) if self.changesets_since_checkpoint < CHECKPOINT_INTERVAL => {
+                let changes = diff_data(published_data, &self.data);
+                if changes.is_empty() {
+                    println!("No changes to push");
+                    return;
+                }
+                (
+                    DataFileKind::Changeset,
+                    serialize_versioned(SchemaId::Changeset, &Changeset { changes }),
+                )
+            }
+            _ => {
+                self.data.version += 1;
+                (
+                    DataFileKind::Checkpoint,
+                    serialize_versioned(SchemaId::ClientData, &self.data),
+                )
+            }
+        };
 
-        let contents = serialize(&self.data);
         let result = self
             .data_store
-            .write_compressed_data(&contents.as_bytes().to_vec(), Some(&self.remote_file_path))
+            .write_signed_data(&contents, kind, &author, &keypair, &self.remote_file_path)
             .await;
         match result {
             Ok((file_name, size)) => {
@@ -533,15 +984,124 @@ impl BitVMClient {
                     human_bytes(contents.len() as f64),
                     human_bytes(size as f64)
                 );
-                save_local_public_file(&self.local_file_path, &file_name, &contents);
-                self.latest_processed_file_name = Some(file_name);
+                save_local_public_file(
+                    &self.local_file_path,
+                    &file_name,
+                    &serialize_versioned(SchemaId::ClientData, &self.data),
+                );
+                self.latest_processed_file_names.insert(author, file_name);
+                self.published_data = Some(self.data.clone());
+                self.changesets_since_checkpoint = match kind {
+                    DataFileKind::Checkpoint => 0,
+                    DataFileKind::Changeset => self.changesets_since_checkpoint + 1,
+                };
             }
             Err(err) => println!("Failed to push: {}", err),
         }
     }
 
-    pub fn validate_data(data: &BitVMClientPublicData) -> bool {
+    // Runs `merge` on the data, then on the data as of the last push, so that the data merged
+    // from other participants is not pushed back as changes of this client. Returns the result
+    // of the first run, the second one refuses the same data.
+    fn merge_as_published(
+        &mut self,
+        merge: impl Fn(&mut Self) -> Result<(), Error>,
+    ) -> Result<(), Error> {
+        let result = merge(self);
+        if let Some(published_data) = self.published_data.take() {
+            let data = std::mem::replace(&mut self.data, published_data);
+            let _ = merge(self);
+            self.published_data = Some(std::mem::replace(&mut self.data, data));
+        }
+
+        result
+    }
+
+    // Applies the changes pushed by another participant. Added graphs are validated like the
+    // graphs of a checkpoint. The valid changes are applied regardless, and the first refusal is
+    // returned.
+    fn apply_changeset(&mut self, changeset: Changeset) -> Result<(), Error> {
+        let mut refusal = None;
+        for change in changeset.changes {
+            match change {
+                Change::PegInGraphAdded(peg_in_graph) => {
+                    let result = self.merge_valid_data(BitVMClientPublicData {
+                        version: self.data.version,
+                        peg_in_graphs: vec![peg_in_graph],
+                        peg_out_graphs: vec![],
+                    });
+                    if let Err(err) = result {
+                        refusal.get_or_insert(err);
+                    }
+                }
+                Change::PegOutGraphAdded(peg_out_graph) => {
+                    let data = BitVMClientPublicData {
+                        version: self.data.version,
+                        peg_in_graphs: vec![],
+                        peg_out_graphs: vec![peg_out_graph],
+                    };
+                    if !Self::validate_data(&data, &self.protocol_parameters) {
+                        continue;
+                    }
+
+                    // only valid peg-out graphs are linked to their peg-in graph
+                    let peg_out_graph = &data.peg_out_graphs[0];
+                    let peg_in_graph =
+                        self.data.peg_in_graphs.iter_mut().find(|peg_in_graph| {
+                            peg_in_graph.id() == &peg_out_graph.peg_in_graph_id
+                        });
+                    if let Some(peg_in_graph) = peg_in_graph {
+                        if !peg_in_graph.peg_out_graphs.contains(peg_out_graph.id()) {
+                            peg_in_graph.peg_out_graphs.push(peg_out_graph.id().clone());
+                        }
+                    }
+                    if let Err(err) = self.merge_data(data) {
+                        refusal.get_or_insert(err);
+                    }
+                }
+                change => {
+                    let result = match self.data.graph(change.graph_id()) {
+                        Some(_) => apply_graph_change(
+                            self.data.graph_mut(change.graph_id()),
+                            &change,
+                            &self.n_of_n_public_keys,
+                        ),
+                        None => Err(Error::Client(ClientError::GraphNotFound(
+                            change.graph_id().clone(),
+                        ))),
+                    };
+                    if let Err(err) = result {
+                        refusal.get_or_insert(err);
+                    }
+                }
+            }
+        }
+
+        match refusal {
+            Some(err) => Err(err),
+            None => Ok(()),
+        }
+    }
+
+    fn merge_valid_data(&mut self, data: BitVMClientPublicData) -> Result<(), Error> {
+        match Self::validate_data(&data, &self.protocol_parameters) {
+            true => self.merge_data(data),
+            false => Ok(()),
+        }
+    }
+
+    pub fn validate_data(
+        data: &BitVMClientPublicData,
+        protocol_parameters: &ProtocolParameters,
+    ) -> bool {
         for peg_in_graph in data.peg_in_graphs.iter() {
+            if peg_in_graph.protocol_parameters() != protocol_parameters {
+                println!(
+                    "Encountered peg-in graph with different protocol parameters (graph ID: {})",
+                    peg_in_graph.id()
+                );
+                return false;
+            }
             if !peg_in_graph.validate() {
                 println!(
                     "Encountered invalid peg-in graph (graph ID: {})",
@@ -551,6 +1111,13 @@ impl BitVMClient {
             }
         }
         for peg_out_graph in data.peg_out_graphs.iter() {
+            if peg_out_graph.protocol_parameters() != protocol_parameters {
+                println!(
+                    "Encountered peg-out graph with different protocol parameters (graph ID: {})",
+                    peg_out_graph.id()
+                );
+                return false;
+            }
             if !peg_out_graph.validate() {
                 println!(
                     "Encountered invalid peg-out graph (graph ID: {})",
@@ -564,12 +1131,15 @@ impl BitVMClient {
         true
     }
 
-    /// Merges `data` into `self.data`.
+    /// Merges `data` into `self.data`. MuSig2 nonces and partial signatures that do not verify
+    /// are refused, the rest is merged regardless and the first refusal is returned.
     ///
     /// # Arguments
     ///
     /// * `data` - Must be valid data verified via `BitVMClient::validate_data()` function
-    pub fn merge_data(&mut self, data: BitVMClientPublicData) {
+    pub fn merge_data(&mut self, data: BitVMClientPublicData) -> Result<(), Error> {
+        let mut refusal = None;
+
         // peg-in graphs
         let mut peg_in_graphs_by_id: HashMap<String, &mut PegInGraph> = HashMap::new();
         for peg_in_graph in self.data.peg_in_graphs.iter_mut() {
@@ -580,7 +1150,9 @@ impl BitVMClient {
         for peg_in_graph in data.peg_in_graphs.iter() {
             let graph = peg_in_graphs_by_id.get_mut(peg_in_graph.id());
             if let Some(graph) = graph {
-                graph.merge(peg_in_graph);
+                if let Err(err) = graph.merge(peg_in_graph) {
+                    refusal.get_or_insert(err);
+                }
             } else {
                 peg_in_graphs_to_add.push(peg_in_graph);
             }
@@ -601,7 +1173,9 @@ impl BitVMClient {
         for peg_out_graph in data.peg_out_graphs.iter() {
             let graph = peg_out_graphs_by_id.get_mut(peg_out_graph.id());
             if let Some(graph) = graph {
-                graph.merge(peg_out_graph);
+                if let Err(err) = graph.merge(peg_out_graph, &self.n_of_n_public_keys) {
+                    refusal.get_or_insert(err);
+                }
             } else {
                 peg_out_graphs_to_add.push(peg_out_graph);
             }
@@ -610,6 +1184,11 @@ impl BitVMClient {
         for graph in peg_out_graphs_to_add.into_iter() {
             self.data.peg_out_graphs.push(graph.clone());
         }
+
+        match refusal {
+            Some(err) => Err(err),
+            None => Ok(()),
+        }
     }
 
     // fn process(&self) {
@@ -717,10 +1296,81 @@ impl BitVMClient {
                         println!("Peg-in graph {} is in status: {}", peg_in_graph_id, status);
                     }
                 }
+                if let Err(e) = self
+                    .process_peg_in_recovery_as_depositor(peg_in_graph_id)
+                    .await
+                {
+                    eprintln!("Failed to recover peg-in graph {peg_in_graph_id}: {e}");
+                }
             }
         }
     }
 
+    /// Recovery status of one of the depositor's peg-ins, see `PegInGraph::recovery_status`.
+    pub async fn peg_in_recovery_status(
+        &self,
+        peg_in_graph_id: &GraphId,
+    ) -> Result<PegInRecoveryStatus, Error> {
+        self.get_peg_in_graph(peg_in_graph_id)?
+            .recovery_status(&self.esplora, self.peg_in_signing_deadline)
+            .await
+    }
+
+    /// Broadcasts the refund of the depositor's peg-in once it is stuck without n-of-n
+    /// signatures and the connector Z timelock matured. Returns the resulting recovery status.
+    pub async fn process_peg_in_recovery_as_depositor(
+        &mut self,
+        peg_in_graph_id: &GraphId,
+    ) -> Result<PegInRecoveryStatus, Error> {
+        let Some(context) = &self.depositor_context else {
+            return Err(Error::Client(ClientError::NoUserContextDefined));
+        };
+        let peg_in_graph = self.get_peg_in_graph(peg_in_graph_id)?;
+        let is_own_peg_in = peg_in_graph.depositor_public_key == context.depositor_public_key;
+
+        let status = peg_in_graph
+            .recovery_status(&self.esplora, self.peg_in_signing_deadline)
+            .await?;
+        if status == PegInRecoveryStatus::RefundAvailable && is_own_peg_in {
+            println!("Broadcasting refund of stuck peg-in graph {peg_in_graph_id}");
+            let txid = self.broadcast_peg_in_refund(peg_in_graph_id).await?;
+            return Ok(PegInRecoveryStatus::RefundPending(txid));
+        }
+
+        Ok(status)
+    }
+
+    /// Runs the recovery of all the peg-ins of the depositor, or of `peg_in_graph_id` only.
+    pub async fn recover_peg_ins(
+        &mut self,
+        peg_in_graph_id: Option<&GraphId>,
+    ) -> Vec<(GraphId, Result<PegInRecoveryStatus, Error>)> {
+        let Some(context) = &self.depositor_context else {
+            return vec![];
+        };
+
+        let peg_in_graph_ids = self
+            .data
+            .peg_in_graphs
+            .iter()
+            .filter(|peg_in_graph| {
+                peg_in_graph.depositor_public_key == context.depositor_public_key
+                    && peg_in_graph_id.is_none_or(|id| peg_in_graph.id() == id)
+            })
+            .map(|peg_in_graph| peg_in_graph.id().clone())
+            .collect::<Vec<_>>();
+
+        let mut statuses = vec![];
+        for peg_in_graph_id in peg_in_graph_ids {
+            let status = self
+                .process_peg_in_recovery_as_depositor(&peg_in_graph_id)
+                .await;
+            statuses.push((peg_in_graph_id, status));
+        }
+
+        statuses
+    }
+
     // TODO: refactor series of method e.g. process_*_as_* to return Result in order to properly handle internal errors
     pub async fn process_peg_in_as_verifier(&mut self, peg_in_graph_id: &GraphId) {
         if let Some(ref context) = self.verifier_context {
@@ -732,7 +1382,12 @@ impl BitVMClient {
                     .filter(|peg_out| peg_in_graph.peg_out_graphs.contains(peg_out.id()))
                     .collect::<Vec<_>>();
                 let status = peg_in_graph
-                    .verifier_status(&self.esplora, context, &peg_outs_for_this_peg_in)
+                    .verifier_status_for_operators(
+                        &self.esplora,
+                        context,
+                        &peg_outs_for_this_peg_in,
+                        &self.operator_set,
+                    )
                     .await;
                 match status {
                     PegInVerifierStatus::PendingOurNonces(graph_ids) => {
@@ -744,7 +1399,9 @@ impl BitVMClient {
                     PegInVerifierStatus::PendingOurSignature(graph_ids) => {
                         println!("Pushing signature for graphs {graph_ids:?}");
                         for graph_id in graph_ids {
-                            self.push_verifier_signature(&graph_id);
+                            if let Err(e) = self.push_verifier_signature(&graph_id) {
+                                eprintln!("Failed to sign graph {graph_id}: {e}");
+                            }
                         }
                     }
                     PegInVerifierStatus::ReadyToSubmit => {
@@ -761,50 +1418,161 @@ impl BitVMClient {
 
     // TODO: refactor, see note on self.process_peg_in_as_verifier
     pub async fn process_peg_in_as_operator(&mut self, peg_in_graph_id: &GraphId) {
-        if let Some(ref context) = self.operator_context {
-            if let Ok(peg_in_graph) = self.get_peg_in_graph(peg_in_graph_id) {
+        let needs_peg_out_graph = match (
+            &self.operator_context,
+            self.get_peg_in_graph(peg_in_graph_id),
+        ) {
+            (Some(context), Ok(peg_in_graph)) => {
                 let peg_out_graph_id =
                     peg_out_generate_id(peg_in_graph, &context.operator_public_key);
-                if !peg_in_graph
+                !peg_in_graph
                     .peg_out_graphs
                     .iter()
                     .any(|x| x == &peg_out_graph_id)
-                {
-                    let deposit_amount =
-                        peg_in_graph.peg_in_deposit_transaction.tx().output[0].value;
-                    let reward_amount = deposit_amount * REWARD_MULTIPLIER / REWARD_PRECISION;
-                    let expected_peg_out_confirm_amount = reward_amount.to_sat() + PEG_OUT_FEE;
-                    let input = {
-                        // todo: don't use a random address
-                        let address = generate_pay_to_pubkey_script_address(
-                            context.network,
-                            &context.operator_public_key,
-                        );
-                        let utxos = self
-                            .esplora
-                            .get_address_utxo(address.clone())
-                            .await
-                            .unwrap();
-                        let utxo = utxos
-                            .into_iter()
-                            .find(|x| x.value.to_sat() != expected_peg_out_confirm_amount)
-                            .unwrap_or_else(|| {
-                                panic!("No utxo found with {expected_peg_out_confirm_amount} sats for address {address}")
-                            });
-                        Input {
-                            amount: utxo.value,
-                            outpoint: OutPoint {
-                                txid: utxo.txid,
-                                vout: utxo.vout,
-                            },
-                        }
-                    };
-                    self.create_peg_out_graph(
-                        peg_in_graph_id,
-                        input,
-                        CommitmentMessageId::generate_commitment_secrets(),
+            }
+            _ => false,
+        };
+
+        if needs_peg_out_graph {
+            if let Err(e) = self
+                .create_peg_out_graph_with_funding(peg_in_graph_id)
+                .await
+            {
+                eprintln!("Failed to create peg-out graph for peg-in graph {peg_in_graph_id}: {e}");
+            }
+        }
+    }
+
+    /// Challenges the peg-out graphs of a peg-in graph that kicked off without being selected by
+    /// the operator selection policy, so that only the selected graph can reach take 1 or take 2.
+    pub async fn process_contested_peg_outs_as_verifier(&mut self, peg_in_graph_id: &GraphId) {
+        let Some(context) = &self.verifier_context else {
+            return;
+        };
+        let Ok(contested_peg_out_graph_ids) = self.contested_peg_out_graphs(peg_in_graph_id).await
+        else {
+            return;
+        };
+
+        let mut challengeable_peg_out_graph_ids = Vec::new();
+        for peg_out_graph in self
+            .data
+            .peg_out_graphs
+            .iter()
+            .filter(|peg_out| contested_peg_out_graph_ids.contains(peg_out.id()))
+        {
+            if peg_out_graph.verifier_status(&self.esplora, context).await
+                == PegOutVerifierStatus::PegOutChallengeAvailable
+            {
+                challengeable_peg_out_graph_ids.push(peg_out_graph.id().clone());
+            }
+        }
+
+        for peg_out_graph_id in challengeable_peg_out_graph_ids {
+            println!(
+                "Challenging peg-out graph {peg_out_graph_id}, it was not selected for the peg-out"
+            );
+            if let Err(e) = self
+                .broadcast_challenge_with_funding(&peg_out_graph_id)
+                .await
+            {
+                eprintln!("Failed to challenge peg-out graph {peg_out_graph_id}: {e}");
+            }
+        }
+    }
+
+    /// Challenges the kicked off peg-out graphs of a peg-in graph whose burn on the L2 chain can't
+    /// be proven, or isn't the burn committed to in kick-off 1. Only done with an L2 bridge
+    /// address, see `set_l2_bridge_address`.
+    pub async fn process_unproven_peg_outs_as_verifier(&mut self, peg_in_graph_id: &GraphId) {
+        let (Some(context), Some(bridge_address)) =
+            (&self.verifier_context, self.l2_bridge_address)
+        else {
+            return;
+        };
+        let Ok(peg_in_graph) = self.get_peg_in_graph(peg_in_graph_id) else {
+            return;
+        };
+        // Not being able to build the proofs, e.g. with a chain adaptor that can't, isn't a
+        // reason to challenge.
+        let peg_in_confirm_txid = peg_in_graph.peg_in_confirm_transaction.tx().compute_txid();
+        let (burn_proofs, config) = match self
+            .get_peg_out_burnt_proofs(bridge_address, &[peg_in_confirm_txid])
+            .await
+        {
+            Ok(proofs) => proofs,
+            Err(e) => {
+                eprintln!("Failed to get the L2 burn proofs: {e}");
+                return;
+            }
+        };
+
+        let mut unproven_peg_out_graph_ids = Vec::new();
+        for peg_out_graph in self
+            .data
+            .peg_out_graphs
+            .iter()
+            .filter(|peg_out| peg_in_graph.peg_out_graphs.contains(peg_out.id()))
+        {
+            if peg_out_graph.verifier_status(&self.esplora, context).await
+                != PegOutVerifierStatus::PegOutChallengeAvailable
+            {
+                continue;
+            }
+            let kick_off_1_tx = match self.esplora.get_tx(&peg_out_graph.kick_off_1_txid()).await {
+                Ok(Some(tx)) => tx,
+                _ => {
+                    eprintln!(
+                        "Failed to get the kick-off 1 transaction of peg-out graph {}",
+                        peg_out_graph.id()
                     );
+                    continue;
                 }
+            };
+            if peg_out_graph
+                .proven_burn_event(&burn_proofs, &config, Some(&kick_off_1_tx))
+                .is_err()
+            {
+                unproven_peg_out_graph_ids.push(peg_out_graph.id().clone());
+            }
+        }
+
+        for peg_out_graph_id in unproven_peg_out_graph_ids {
+            println!(
+                "Challenging peg-out graph {peg_out_graph_id}, its burn on L2 can't be proven"
+            );
+            if let Err(e) = self
+                .broadcast_challenge_with_funding(&peg_out_graph_id)
+                .await
+            {
+                eprintln!("Failed to challenge peg-out graph {peg_out_graph_id}: {e}");
+            }
+        }
+    }
+
+    /// Broadcasts the challenge transactions whose crowdfunding reached the required amount.
+    pub async fn process_challenge_crowdfundings_as_verifier(&mut self) {
+        if self.verifier_context.is_none() {
+            return;
+        }
+
+        let mut funded_peg_out_graph_ids = Vec::new();
+        for peg_out_graph in self.data.peg_out_graphs.iter() {
+            if let Ok(ChallengeCrowdfundingStatus::Funded { .. }) = peg_out_graph
+                .challenge_crowdfunding_status(&self.esplora)
+                .await
+            {
+                funded_peg_out_graph_ids.push(peg_out_graph.id().clone());
+            }
+        }
+
+        for peg_out_graph_id in funded_peg_out_graph_ids {
+            println!("Broadcasting crowdfunded challenge of peg-out graph {peg_out_graph_id}");
+            if let Err(e) = self
+                .broadcast_crowdfunded_challenge(&peg_out_graph_id)
+                .await
+            {
+                eprintln!("Failed to challenge peg-out graph {peg_out_graph_id}: {e}");
             }
         }
     }
@@ -813,6 +1581,10 @@ impl BitVMClient {
         for peg_in_graph in self.data.peg_in_graphs.clone() {
             self.process_peg_in_as_depositor(peg_in_graph.id()).await;
             self.process_peg_in_as_verifier(peg_in_graph.id()).await;
+            self.process_contested_peg_outs_as_verifier(peg_in_graph.id())
+                .await;
+            self.process_unproven_peg_outs_as_verifier(peg_in_graph.id())
+                .await;
             self.process_peg_in_as_operator(peg_in_graph.id()).await;
         }
     }
@@ -830,7 +1602,9 @@ impl BitVMClient {
                     let _ = self.broadcast_peg_out_confirm(peg_out_graph.id()).await;
                 }
                 PegOutOperatorStatus::PegOutKickOff1Available => {
-                    let _ = self.broadcast_kick_off_1(peg_out_graph.id()).await;
+                    if let Ok(true) = self.may_kick_off(peg_out_graph.id()).await {
+                        let _ = self.broadcast_kick_off_1(peg_out_graph.id()).await;
+                    }
                 }
                 PegOutOperatorStatus::PegOutKickOff2Available => {
                     let _ = self.broadcast_kick_off_2(peg_out_graph.id()).await;
@@ -880,31 +1654,109 @@ impl BitVMClient {
                 })
                 .collect::<Vec<_>>();
             let peg_in_status = peg_in_graph
-                .verifier_status(
+                .verifier_status_for_operators(
                     &self.esplora,
                     self.verifier_context.as_ref().unwrap(),
                     &peg_outs,
+                    &self.operator_set,
                 )
                 .await;
 
-            if peg_in_status == PegInVerifierStatus::Complete {
-                for peg_out_graph in peg_outs {
-                    let peg_out_status = peg_out_graph
-                        .verifier_status(&self.esplora, self.verifier_context.as_ref().unwrap())
-                        .await;
-                    println!(
-                        "[VERIFIER]: Peg-out graph ID: {} status: {}\n",
-                        peg_out_graph.id(),
-                        peg_out_status
-                    );
-                }
+            if peg_in_status == PegInVerifierStatus::Complete {
+                for peg_out_graph in peg_outs {
+                    let peg_out_status = peg_out_graph
+                        .verifier_status(&self.esplora, self.verifier_context.as_ref().unwrap())
+                        .await;
+                    println!(
+                        "[VERIFIER]: Peg-out graph ID: {} status: {}\n",
+                        peg_out_graph.id(),
+                        peg_out_status
+                    );
+                }
+            }
+            println!(
+                "[VERIFIER]: Peg-in graph ID: {} status: {}\n",
+                peg_in_graph.id(),
+                peg_in_status
+            );
+        }
+    }
+
+    /// Peg-out graph of a peg-in graph selected by the operator selection policy to reach take 1
+    /// or take 2, if any. See `OperatorSet::select_peg_out_graph`.
+    pub async fn selected_peg_out_graph(
+        &self,
+        peg_in_graph_id: &GraphId,
+    ) -> Result<Option<GraphId>, Error> {
+        let peg_outs = self.get_peg_out_graphs_of(peg_in_graph_id)?;
+        let kick_off_1_heights = self.get_kick_off_1_heights(&peg_outs).await;
+
+        Ok(self
+            .operator_set
+            .select_peg_out_graph(&peg_outs, &kick_off_1_heights)
+            .map(|peg_out| peg_out.id().clone()))
+    }
+
+    /// Peg-out graphs of a peg-in graph that kicked off without being selected. See
+    /// `OperatorSet::contested_peg_out_graphs`.
+    pub async fn contested_peg_out_graphs(
+        &self,
+        peg_in_graph_id: &GraphId,
+    ) -> Result<Vec<GraphId>, Error> {
+        let peg_outs = self.get_peg_out_graphs_of(peg_in_graph_id)?;
+        let kick_off_1_heights = self.get_kick_off_1_heights(&peg_outs).await;
+
+        Ok(self
+            .operator_set
+            .contested_peg_out_graphs(&peg_outs, &kick_off_1_heights)
+            .into_iter()
+            .map(|peg_out| peg_out.id().clone())
+            .collect())
+    }
+
+    /// Whether the operator may kick off a peg-out graph. See `OperatorSet::may_kick_off`.
+    pub async fn may_kick_off(&self, peg_out_graph_id: &GraphId) -> Result<bool, Error> {
+        let peg_out_graph = self
+            .data
+            .peg_out_graphs
+            .iter()
+            .find(|peg_out| peg_out.id() == peg_out_graph_id)
+            .ok_or(Error::Client(ClientError::PegOutGraphNotFound(
+                peg_out_graph_id.clone(),
+            )))?;
+        let peg_outs = self.get_peg_out_graphs_of(&peg_out_graph.peg_in_graph_id)?;
+        let kick_off_1_heights = self.get_kick_off_1_heights(&peg_outs).await;
+
+        Ok(self
+            .operator_set
+            .may_kick_off(peg_out_graph, &peg_outs, &kick_off_1_heights))
+    }
+
+    fn get_peg_out_graphs_of(&self, peg_in_graph_id: &GraphId) -> Result<Vec<&PegOutGraph>, Error> {
+        let peg_in_graph = self.get_peg_in_graph(peg_in_graph_id)?;
+        Ok(self
+            .data
+            .peg_out_graphs
+            .iter()
+            .filter(|peg_out| peg_in_graph.peg_out_graphs.contains(peg_out.id()))
+            .collect())
+    }
+
+    // Block heights of the confirmed kick-off 1 transactions of `peg_outs`, by graph ID.
+    async fn get_kick_off_1_heights(&self, peg_outs: &[&PegOutGraph]) -> HashMap<GraphId, u32> {
+        let mut kick_off_1_heights = HashMap::new();
+        for peg_out in peg_outs {
+            if let Ok(TxStatus {
+                confirmed: true,
+                block_height: Some(block_height),
+                ..
+            }) = self.esplora.get_tx_status(&peg_out.kick_off_1_txid()).await
+            {
+                kick_off_1_heights.insert(peg_out.id().clone(), block_height);
             }
-            println!(
-                "[VERIFIER]: Peg-in graph ID: {} status: {}\n",
-                peg_in_graph.id(),
-                peg_in_status
-            );
         }
+
+        kick_off_1_heights
     }
 
     pub async fn create_peg_in_graph(&mut self, input: Input, evm_address: &str) -> String {
@@ -912,10 +1764,17 @@ impl BitVMClient {
             panic!("Depositor context must be initialized");
         }
 
-        let peg_in_graph =
-            PegInGraph::new(self.depositor_context.as_ref().unwrap(), input, evm_address);
+        let peg_in_graph = PegInGraph::new(
+            self.depositor_context.as_ref().unwrap(),
+            input,
+            evm_address,
+            &self.protocol_parameters,
+        );
 
-        let peg_in_graph_id = peg_in_generate_id(&peg_in_graph.peg_in_deposit_transaction);
+        let peg_in_graph_id = peg_in_generate_id(
+            &peg_in_graph.peg_in_deposit_transaction,
+            &self.protocol_parameters,
+        );
 
         let graph = self
             .data
@@ -937,7 +1796,7 @@ impl BitVMClient {
     ) -> Result<Txid, Error> {
         let graph = Self::find_peg_in_or_fail(&mut self.data, peg_in_graph_id)?;
         let tx = graph.deposit(&self.esplora).await?;
-        self.broadcast_tx(&tx).await
+        self.broadcast_tx(peg_in_graph_id, &tx).await
     }
 
     pub async fn broadcast_peg_in_refund(
@@ -946,7 +1805,7 @@ impl BitVMClient {
     ) -> Result<Txid, Error> {
         let graph = Self::find_peg_in_or_fail(&mut self.data, peg_in_graph_id)?;
         let tx = graph.refund(&self.esplora).await?;
-        self.broadcast_tx(&tx).await
+        self.broadcast_tx(peg_in_graph_id, &tx).await
     }
 
     pub async fn broadcast_peg_in_confirm(
@@ -955,7 +1814,7 @@ impl BitVMClient {
     ) -> Result<Txid, Error> {
         let graph = Self::find_peg_in_or_fail(&mut self.data, peg_in_graph_id)?;
         let tx = graph.confirm(&self.esplora).await?;
-        self.broadcast_tx(&tx).await
+        self.broadcast_tx(peg_in_graph_id, &tx).await
     }
 
     pub fn create_peg_out_graph(
@@ -975,6 +1834,9 @@ impl BitVMClient {
             .iter_mut()
             .find(|peg_in_graph| peg_in_graph.id().eq(peg_in_graph_id))
             .unwrap_or_else(|| panic!("Invalid graph ID"));
+        if peg_in_graph.protocol_parameters() != &self.protocol_parameters {
+            panic!("Peg in graph uses different protocol parameters");
+        }
 
         let peg_out_graph_id = peg_out_generate_id(peg_in_graph, operator_public_key);
         let peg_out_graph = self
@@ -991,20 +1853,154 @@ impl BitVMClient {
             peg_in_graph,
             peg_out_confirm_input,
             &commitment_secrets,
+            &self.protocol_parameters,
         );
 
         self.data.peg_out_graphs.push(peg_out_graph);
         peg_in_graph.peg_out_graphs.push(peg_out_graph_id.clone());
 
-        self.private_data.commitment_secrets = HashMap::from([(
-            *operator_public_key,
-            HashMap::from([(peg_out_graph_id.to_string(), commitment_secrets)]),
-        )]);
+        self.private_data
+            .commitment_secrets
+            .entry(*operator_public_key)
+            .or_default()
+            .insert(peg_out_graph_id.clone(), commitment_secrets);
         self.save_private_data();
 
         peg_out_graph_id
     }
 
+    /// Creates the operator's peg-out graph for a peg-in graph, funding the peg-out confirm
+    /// input from the operator's funding wallet.
+    pub async fn create_peg_out_graph_with_funding(
+        &mut self,
+        peg_in_graph_id: &str,
+    ) -> Result<String, Error> {
+        let peg_in_graph = self.get_peg_in_graph(&peg_in_graph_id.to_string())?;
+        let deposit_amount = peg_in_graph.peg_in_deposit_transaction.tx().output[0].value;
+        let reward_amount = self.protocol_parameters.reward_amount(deposit_amount);
+
+        let input = self
+            .fund(
+                Role::Operator,
+                reward_amount + Amount::from_sat(PEG_OUT_FEE),
+            )
+            .await?;
+        let commitment_secrets = self.generate_commitment_secrets(peg_in_graph_id);
+
+        Ok(self.create_peg_out_graph(peg_in_graph_id, input, commitment_secrets))
+    }
+
+    /// Returns the Winternitz commitment secrets for the operator's peg-out graph of the given
+    /// peg-in graph. If an operator commitment seed is configured the secrets are derived from it
+    /// and can be regenerated with `regenerate_commitment_secrets`, otherwise they are random and
+    /// only exist in the private data file.
+    pub fn generate_commitment_secrets(
+        &self,
+        peg_in_graph_id: &str,
+    ) -> HashMap<CommitmentMessageId, WinternitzSecret> {
+        let operator_public_key = &self
+            .operator_context
+            .as_ref()
+            .expect("Operator context must be initialized")
+            .operator_public_key;
+
+        match self.commitment_seed {
+            Some(ref seed) => {
+                let peg_in_graph = self
+                    .data
+                    .peg_in_graphs
+                    .iter()
+                    .find(|peg_in_graph| peg_in_graph.id().eq(peg_in_graph_id))
+                    .unwrap_or_else(|| panic!("Invalid graph ID"));
+                let peg_out_graph_id = peg_out_generate_id(peg_in_graph, operator_public_key);
+
+                CommitmentMessageId::derive_commitment_secrets(seed, &peg_out_graph_id)
+            }
+            None => {
+                println!(
+                    "{}",
+                    "No operator commitment seed configured, commitment secrets cannot be recovered if the private data file is lost.".yellow()
+                );
+                CommitmentMessageId::generate_commitment_secrets()
+            }
+        }
+    }
+
+    /// Re-derives the commitment secrets of one of the operator's peg-out graphs from the operator
+    /// commitment seed and stores them in the private data, e.g. after the private data file was
+    /// lost. Fails if the derived secrets do not match the public keys committed in the graph.
+    pub fn regenerate_commitment_secrets(
+        &mut self,
+        peg_out_graph_id: &String,
+    ) -> Result<(), Error> {
+        let operator_public_key = self
+            .operator_context
+            .as_ref()
+            .ok_or(Error::Client(ClientError::OperatorContextNotDefined))?
+            .operator_public_key;
+        let seed = self
+            .commitment_seed
+            .as_ref()
+            .ok_or(Error::Client(ClientError::CommitmentSeedNotDefined))?;
+        let graph = self
+            .data
+            .peg_out_graphs
+            .iter()
+            .find(|peg_out_graph| peg_out_graph.id().eq(peg_out_graph_id))
+            .ok_or(Error::Client(ClientError::PegOutGraphNotFound(
+                peg_out_graph_id.clone(),
+            )))?;
+
+        let commitment_secrets =
+            CommitmentMessageId::derive_commitment_secrets(seed, peg_out_graph_id);
+        if !graph.operator_public_key().eq(&operator_public_key)
+            || !graph.matches_commitment_secrets(&commitment_secrets)
+        {
+            return Err(Error::Client(ClientError::CommitmentSecretsMismatch(
+                peg_out_graph_id.clone(),
+            )));
+        }
+
+        self.private_data
+            .commitment_secrets
+            .entry(operator_public_key)
+            .or_default()
+            .insert(peg_out_graph_id.clone(), commitment_secrets);
+        self.save_private_data();
+
+        Ok(())
+    }
+
+    /// Regenerates the commitment secrets of every peg-out graph of this operator that has none in
+    /// the private data. Returns the IDs of the graphs that were recovered.
+    pub fn regenerate_missing_commitment_secrets(&mut self) -> Result<Vec<GraphId>, Error> {
+        let operator_public_key = self
+            .operator_context
+            .as_ref()
+            .ok_or(Error::Client(ClientError::OperatorContextNotDefined))?
+            .operator_public_key;
+        let existing_secrets = self
+            .private_data
+            .commitment_secrets
+            .get(&operator_public_key);
+        let missing_graph_ids: Vec<GraphId> = self
+            .data
+            .peg_out_graphs
+            .iter()
+            .filter(|graph| graph.operator_public_key().eq(&operator_public_key))
+            .map(|graph| graph.id().clone())
+            .filter(|graph_id| {
+                !existing_secrets.is_some_and(|secrets| secrets.contains_key(graph_id))
+            })
+            .collect();
+
+        for graph_id in missing_graph_ids.iter() {
+            self.regenerate_commitment_secrets(graph_id)?;
+        }
+
+        Ok(missing_graph_ids)
+    }
+
     pub async fn broadcast_peg_out(
         &mut self,
         peg_out_graph_id: &String,
@@ -1019,38 +2015,93 @@ impl BitVMClient {
                     input,
                 )
                 .await?;
-            self.broadcast_tx(&tx).await
+            self.broadcast_tx(peg_out_graph_id, &tx).await
         } else {
             Err(Error::Client(ClientError::OperatorContextNotDefined))
         }
     }
 
+    /// Broadcasts the peg-out transaction, funding it from the operator's funding wallet.
+    pub async fn broadcast_peg_out_with_funding(
+        &mut self,
+        peg_out_graph_id: &String,
+    ) -> Result<Txid, Error> {
+        let graph = Self::find_peg_out_or_fail(&mut self.data, peg_out_graph_id)?;
+
+        // A peg-out transaction that was created before must not be funded again.
+        let input = match (&graph.peg_out_transaction, &graph.peg_out_chain_event) {
+            (Some(peg_out_transaction), _) => Input {
+                outpoint: peg_out_transaction.tx().input[0].previous_output,
+                amount: peg_out_transaction.prev_outs()[0].value,
+            },
+            (None, Some(peg_out_chain_event)) => {
+                let amount = peg_out_chain_event.amount + Amount::from_sat(MIN_RELAY_FEE_PEG_OUT);
+                self.fund(Role::Operator, amount).await?
+            }
+            (None, None) => return Err(Error::L2(L2Error::PegOutNotInitiated)),
+        };
+
+        self.broadcast_peg_out(peg_out_graph_id, input).await
+    }
+
     pub async fn broadcast_peg_out_confirm(
         &mut self,
         peg_out_graph_id: &String,
     ) -> Result<Txid, Error> {
         let graph = Self::find_peg_out_or_fail(&mut self.data, peg_out_graph_id)?;
         let tx = graph.peg_out_confirm(&self.esplora).await?;
-        self.broadcast_tx(&tx).await
+        self.broadcast_tx(peg_out_graph_id, &tx).await
     }
 
+    /// Broadcasts kick-off 1. With an L2 bridge address, the burn of the peg-out on the L2 chain
+    /// must be proven first, see `PegOutGraph::kick_off_1_with_l2_burn_proof`.
     pub async fn broadcast_kick_off_1(&mut self, peg_out_graph_id: &String) -> Result<Txid, Error> {
+        let burn_proofs = match self.l2_bridge_address {
+            Some(bridge_address) => {
+                let peg_in_confirm_txid =
+                    Self::find_peg_out_or_fail(&mut self.data, peg_out_graph_id)?
+                        .peg_in_confirm_txid();
+                Some(
+                    self.get_peg_out_burnt_proofs(bridge_address, &[peg_in_confirm_txid])
+                        .await?,
+                )
+            }
+            None => None,
+        };
         let graph = Self::find_peg_out_or_fail(&mut self.data, peg_out_graph_id)?;
 
-        if self.operator_context.is_some() {
-            let tx = graph
-                .kick_off_1(
-                    &self.esplora,
-                    self.operator_context.as_ref().unwrap(),
-                    &self.private_data.commitment_secrets
-                        [&self.operator_context.as_ref().unwrap().operator_public_key]
-                        [peg_out_graph_id][&CommitmentMessageId::PegOutTxIdSourceNetwork],
-                    &self.private_data.commitment_secrets
-                        [&self.operator_context.as_ref().unwrap().operator_public_key]
-                        [peg_out_graph_id][&CommitmentMessageId::PegOutTxIdDestinationNetwork],
-                )
-                .await?;
-            self.broadcast_tx(&tx).await
+        if let Some(context) = &self.operator_context {
+            let commitment_secrets = &self.private_data.commitment_secrets
+                [&context.operator_public_key][peg_out_graph_id];
+            let source_network_txid_commitment_secret =
+                &commitment_secrets[&CommitmentMessageId::PegOutTxIdSourceNetwork];
+            let destination_network_txid_commitment_secret =
+                &commitment_secrets[&CommitmentMessageId::PegOutTxIdDestinationNetwork];
+            let tx = match &burn_proofs {
+                Some((burn_proofs, config)) => {
+                    graph
+                        .kick_off_1_with_l2_burn_proof(
+                            &self.esplora,
+                            context,
+                            source_network_txid_commitment_secret,
+                            destination_network_txid_commitment_secret,
+                            burn_proofs,
+                            config,
+                        )
+                        .await?
+                }
+                None => {
+                    graph
+                        .kick_off_1(
+                            &self.esplora,
+                            context,
+                            source_network_txid_commitment_secret,
+                            destination_network_txid_commitment_secret,
+                        )
+                        .await?
+                }
+            };
+            self.broadcast_tx(peg_out_graph_id, &tx).await
         } else {
             Err(Error::Client(ClientError::OperatorContextNotDefined))
         }
@@ -1069,7 +2120,7 @@ impl BitVMClient {
                         [peg_out_graph_id][&CommitmentMessageId::StartTime],
                 )
                 .await?;
-            self.broadcast_tx(&tx).await
+            self.broadcast_tx(peg_out_graph_id, &tx).await
         } else {
             Err(Error::Client(ClientError::OperatorContextNotDefined))
         }
@@ -1084,7 +2135,7 @@ impl BitVMClient {
         let tx = graph
             .start_time_timeout(&self.esplora, output_script_pubkey)
             .await?;
-        self.broadcast_tx(&tx).await
+        self.broadcast_tx(peg_out_graph_id, &tx).await
     }
 
     pub async fn broadcast_kick_off_2(&mut self, peg_out_graph_id: &String) -> Result<Txid, Error> {
@@ -1101,7 +2152,7 @@ impl BitVMClient {
                     [peg_out_graph_id][&CommitmentMessageId::SuperblockHash],
             )
             .await?;
-        self.broadcast_tx(&tx).await
+        self.broadcast_tx(peg_out_graph_id, &tx).await
     }
 
     pub async fn broadcast_kick_off_timeout(
@@ -1113,7 +2164,7 @@ impl BitVMClient {
         let tx = graph
             .kick_off_timeout(&self.esplora, output_script_pubkey)
             .await?;
-        self.broadcast_tx(&tx).await
+        self.broadcast_tx(peg_out_graph_id, &tx).await
     }
 
     pub async fn broadcast_challenge(
@@ -1133,7 +2184,7 @@ impl BitVMClient {
                     output_script_pubkey,
                 )
                 .await?;
-            self.broadcast_tx(&tx).await
+            self.broadcast_tx(peg_out_graph_id, &tx).await
         } else if self.operator_context.is_some() {
             let tx = graph
                 .challenge(
@@ -1143,7 +2194,7 @@ impl BitVMClient {
                     output_script_pubkey,
                 )
                 .await?;
-            self.broadcast_tx(&tx).await
+            self.broadcast_tx(peg_out_graph_id, &tx).await
         } else if self.verifier_context.is_some() {
             let tx = graph
                 .challenge(
@@ -1153,7 +2204,7 @@ impl BitVMClient {
                     output_script_pubkey,
                 )
                 .await?;
-            self.broadcast_tx(&tx).await
+            self.broadcast_tx(peg_out_graph_id, &tx).await
         } else if self.withdrawer_context.is_some() {
             let tx = graph
                 .challenge(
@@ -1163,19 +2214,149 @@ impl BitVMClient {
                     output_script_pubkey,
                 )
                 .await?;
-            self.broadcast_tx(&tx).await
+            self.broadcast_tx(peg_out_graph_id, &tx).await
         } else {
             Err(Error::Client(ClientError::NoUserContextDefined))
         }
     }
 
+    /// Crowdfunds the challenge transaction alone from the funding wallet of the first
+    /// configured role, the same role `broadcast_challenge` signs with. Any excess is refunded
+    /// to the wallet. The contributions of the verifiers to the crowdfunding are left unspent.
+    pub async fn broadcast_challenge_with_funding(
+        &mut self,
+        peg_out_graph_id: &String,
+    ) -> Result<Txid, Error> {
+        let role = if self.depositor_context.is_some() {
+            Role::Depositor
+        } else if self.operator_context.is_some() {
+            Role::Operator
+        } else if self.verifier_context.is_some() {
+            Role::Verifier
+        } else if self.withdrawer_context.is_some() {
+            Role::Withdrawer
+        } else {
+            return Err(Error::Client(ClientError::NoUserContextDefined));
+        };
+
+        let amount = Amount::from_sat(
+            Self::find_peg_out_or_fail(&mut self.data, peg_out_graph_id)?.min_crowdfunding_amount(),
+        );
+        let wallet = self.funding_wallet(role)?;
+        let input = self.fund(role, amount).await?;
+        let script = generate_pay_to_pubkey_script(&wallet.public_key());
+
+        let result = self
+            .broadcast_challenge(
+                peg_out_graph_id,
+                &vec![InputWithScript {
+                    outpoint: input.outpoint,
+                    amount: input.amount,
+                    script: &script,
+                }],
+                wallet.address().script_pubkey(),
+            )
+            .await;
+        if result.is_err() {
+            self.release_funding(&input.outpoint);
+        }
+
+        result
+    }
+
+    /// Contributes to the crowdfunding of the challenge transaction from the verifier's funding
+    /// wallet. `amount` is capped to the amount still missing, which is contributed by default.
+    /// The contribution is shared with the other verifiers on the next flush.
+    pub async fn contribute_to_challenge(
+        &mut self,
+        peg_out_graph_id: &String,
+        amount: Option<Amount>,
+    ) -> Result<OutPoint, Error> {
+        let keypair = self
+            .verifier_context
+            .as_ref()
+            .ok_or(Error::Client(ClientError::NoUserContextDefined))?
+            .verifier_keypair;
+
+        let graph = Self::find_peg_out_or_fail(&mut self.data, peg_out_graph_id)?;
+        let status = graph.challenge_crowdfunding_status(&self.esplora).await?;
+        let ChallengeCrowdfundingStatus::Open { .. } = status else {
+            return Err(Error::Graph(GraphError::ChallengeCrowdfundingUnavailable(
+                status,
+            )));
+        };
+        let contributions = graph.challenge_contributions(&self.esplora).await?;
+        let funded: Amount = contributions
+            .iter()
+            .map(|contribution| contribution.amount)
+            .sum();
+        let missing_amount = graph
+            .required_crowdfunding_amount(contributions.len() + 1)
+            .checked_sub(funded)
+            .unwrap_or(Amount::ZERO);
+        let amount = amount.map_or(missing_amount, |amount| amount.min(missing_amount));
+
+        let wallet = self.funding_wallet(Role::Verifier)?;
+        let input = self.fund(Role::Verifier, amount).await?;
+        let script = generate_pay_to_pubkey_script(&wallet.public_key());
+
+        let result = Self::find_peg_out_or_fail(&mut self.data, peg_out_graph_id)?
+            .contribute_to_challenge(
+                &self.esplora,
+                &InputWithScript {
+                    outpoint: input.outpoint,
+                    amount: input.amount,
+                    script: &script,
+                },
+                &keypair,
+            )
+            .await;
+        if let Err(e) = result {
+            self.release_funding(&input.outpoint);
+            return Err(e);
+        }
+        println!(
+            "Contributed {} sats to the challenge of peg-out graph {}",
+            input.amount.to_sat(),
+            peg_out_graph_id
+        );
+
+        Ok(input.outpoint)
+    }
+
+    pub async fn challenge_crowdfunding_status(
+        &self,
+        peg_out_graph_id: &GraphId,
+    ) -> Result<ChallengeCrowdfundingStatus, Error> {
+        self.data
+            .peg_out_graphs
+            .iter()
+            .find(|peg_out_graph| peg_out_graph.id() == peg_out_graph_id)
+            .ok_or(Error::Client(ClientError::PegOutGraphNotFound(
+                peg_out_graph_id.clone(),
+            )))?
+            .challenge_crowdfunding_status(&self.esplora)
+            .await
+    }
+
+    /// Broadcasts the challenge transaction spending the contributions that fund it.
+    /// Contributions that are not needed stay unspent in the wallets of their contributors.
+    pub async fn broadcast_crowdfunded_challenge(
+        &mut self,
+        peg_out_graph_id: &String,
+    ) -> Result<Txid, Error> {
+        let graph = Self::find_peg_out_or_fail(&mut self.data, peg_out_graph_id)?;
+        let tx = graph.crowdfunded_challenge(&self.esplora).await?;
+        self.broadcast_tx(peg_out_graph_id, &tx).await
+    }
+
     pub async fn broadcast_assert_initial(
         &mut self,
         peg_out_graph_id: &String,
     ) -> Result<Txid, Error> {
         let graph = Self::find_peg_out_or_fail(&mut self.data, peg_out_graph_id)?;
         let tx = graph.assert_initial(&self.esplora).await?;
-        self.broadcast_tx(&tx).await
+        self.broadcast_tx(peg_out_graph_id, &tx).await
     }
 
     pub async fn broadcast_assert_commit_1(
@@ -1193,7 +2374,7 @@ impl BitVMClient {
                 proof,
             )
             .await?;
-        self.broadcast_tx(&tx).await
+        self.broadcast_tx(peg_out_graph_id, &tx).await
     }
 
     pub async fn broadcast_assert_commit_2(
@@ -1211,7 +2392,7 @@ impl BitVMClient {
                 proof,
             )
             .await?;
-        self.broadcast_tx(&tx).await
+        self.broadcast_tx(peg_out_graph_id, &tx).await
     }
 
     pub async fn broadcast_assert_final(
@@ -1220,7 +2401,7 @@ impl BitVMClient {
     ) -> Result<Txid, Error> {
         let graph = Self::find_peg_out_or_fail(&mut self.data, peg_out_graph_id)?;
         let tx = graph.assert_final(&self.esplora).await?;
-        self.broadcast_tx(&tx).await
+        self.broadcast_tx(peg_out_graph_id, &tx).await
     }
 
     pub async fn broadcast_disprove(
@@ -1238,7 +2419,7 @@ impl BitVMClient {
                     .ok_or(Error::Client(ClientError::ZkProofVerifyingKeyNotDefined))?,
             )
             .await?;
-        self.broadcast_tx(&tx).await
+        self.broadcast_tx(peg_out_graph_id, &tx).await
     }
 
     pub async fn broadcast_disprove_chain(
@@ -1250,13 +2431,13 @@ impl BitVMClient {
         let tx = graph
             .disprove_chain(&self.esplora, output_script_pubkey)
             .await?;
-        self.broadcast_tx(&tx).await
+        self.broadcast_tx(peg_out_graph_id, &tx).await
     }
 
     pub async fn broadcast_take_1(&mut self, peg_out_graph_id: &String) -> Result<Txid, Error> {
         let graph = Self::find_peg_out_or_fail(&mut self.data, peg_out_graph_id)?;
         let tx = graph.take_1(&self.esplora).await?;
-        self.broadcast_tx(&tx).await
+        self.broadcast_tx(peg_out_graph_id, &tx).await
     }
 
     pub async fn broadcast_take_2(&mut self, peg_out_graph_id: &String) -> Result<Txid, Error> {
@@ -1264,7 +2445,265 @@ impl BitVMClient {
         let tx = graph
             .take_2(&self.esplora, self.operator_context.as_ref().unwrap())
             .await?;
-        self.broadcast_tx(&tx).await
+        self.broadcast_tx(peg_out_graph_id, &tx).await
+    }
+
+    /// Exports the transaction named `tx_name` of the given graph as a PSBT for external signing.
+    pub fn export_psbt(&self, graph_id: &GraphId, tx_name: &str) -> Result<Psbt, Error> {
+        let graph = self
+            .data
+            .graph(graph_id)
+            .ok_or(Error::Client(ClientError::GraphNotFound(graph_id.clone())))?;
+        let tx = graph.pre_signed_transaction(tx_name).ok_or(Error::Psbt(
+            PsbtError::UnknownTransaction(tx_name.to_string()),
+        ))?;
+
+        export_psbt(tx, &graph.taproot_spend_infos())
+    }
+
+    /// Exports the transactions of a graph and the connectors they spend in `format`. With
+    /// `with_status`, the transactions are colored by their confirmation status.
+    pub async fn export_graph(
+        &self,
+        graph_id: &GraphId,
+        format: GraphExportFormat,
+        with_status: bool,
+    ) -> Result<String, Error> {
+        let graph = self
+            .data
+            .graph(graph_id)
+            .ok_or(Error::Client(ClientError::GraphNotFound(graph_id.clone())))?;
+        let mut export = GraphExport::new(graph);
+        if with_status {
+            export.fetch_statuses(&self.esplora).await;
+        }
+
+        Ok(export.render(format))
+    }
+
+    /// Measures the transactions of a graph, and of its peg-in graph for a peg-out graph, and
+    /// renders the relay fee constants of `transactions::base` from their vsizes. Stale constants
+    /// and the resulting `PEG_OUT_FEE` are listed as comments.
+    pub fn fee_table(&self, graph_id: &GraphId) -> Result<String, Error> {
+        let graph = self
+            .data
+            .graph(graph_id)
+            .ok_or(Error::Client(ClientError::GraphNotFound(graph_id.clone())))?;
+        let mut graphs = vec![graph];
+        if let Some(peg_out_graph) = self
+            .data
+            .peg_out_graphs
+            .iter()
+            .find(|peg_out_graph| peg_out_graph.id() == graph_id)
+        {
+            graphs.extend(self.data.graph(&peg_out_graph.peg_in_graph_id));
+        }
+
+        let vsizes = measure_vsizes(&graphs);
+        let mut fee_table = render_fee_table(&vsizes);
+        for stale in stale_relay_fees(&vsizes) {
+            fee_table += &format!(
+                "// {} is stale: {} sats, measured {} sats\n",
+                stale.constant_name, stale.value, stale.measured_value
+            );
+        }
+        if let Some(peg_out_fee) = peg_out_fee(&vsizes) {
+            fee_table += &format!(
+                "// PEG_OUT_FEE: {} sats (currently {})\n",
+                peg_out_fee, PEG_OUT_FEE
+            );
+        }
+
+        Ok(fee_table)
+    }
+
+    /// Verifies a signed PSBT of the transaction named `tx_name` and returns the finalized
+    /// transaction.
+    pub fn import_psbt(
+        &self,
+        graph_id: &GraphId,
+        tx_name: &str,
+        psbt: &Psbt,
+    ) -> Result<Transaction, Error> {
+        let graph = self
+            .data
+            .graph(graph_id)
+            .ok_or(Error::Client(ClientError::GraphNotFound(graph_id.clone())))?;
+        let tx = graph.pre_signed_transaction(tx_name).ok_or(Error::Psbt(
+            PsbtError::UnknownTransaction(tx_name.to_string()),
+        ))?;
+
+        finalize_psbt(tx, psbt)
+    }
+
+    pub async fn broadcast_psbt(
+        &mut self,
+        graph_id: &GraphId,
+        tx_name: &str,
+        psbt: &Psbt,
+    ) -> Result<Txid, Error> {
+        let tx = self.import_psbt(graph_id, tx_name, psbt)?;
+        self.broadcast_tx(graph_id, &tx).await
+    }
+
+    /// Registers additional keys, e.g. derived funding addresses, whose UTXOs can fund the
+    /// transactions of `role`.
+    pub fn add_funding_keys(&mut self, role: Role, keypairs: Vec<Keypair>) {
+        self.funding_keys.entry(role).or_default().extend(keypairs);
+    }
+
+    /// Funding wallet of `role`. Funding outputs and change are sent to the address of the key
+    /// the role signs graphs with.
+    pub fn funding_wallet(&self, role: Role) -> Result<FundingWallet, Error> {
+        let keypair = match role {
+            Role::Depositor => self
+                .depositor_context
+                .as_ref()
+                .map(|context| context.depositor_keypair),
+            Role::Operator => self
+                .operator_context
+                .as_ref()
+                .map(|context| context.operator_keypair),
+            Role::Verifier => self
+                .verifier_context
+                .as_ref()
+                .map(|context| context.verifier_keypair),
+            Role::Withdrawer => self
+                .withdrawer_context
+                .as_ref()
+                .map(|context| context.withdrawer_keypair),
+        }
+        .ok_or(match role {
+            Role::Operator => Error::Client(ClientError::OperatorContextNotDefined),
+            _ => Error::Client(ClientError::NoUserContextDefined),
+        })?;
+
+        let mut keypairs = vec![keypair];
+        if let Some(funding_keys) = self.funding_keys.get(&role) {
+            keypairs.extend(funding_keys);
+        }
+
+        Ok(FundingWallet::new(
+            self.source_network,
+            keypairs,
+            Amount::from_sat(self.protocol_parameters.dust_amount),
+        )
+        .with_reserved(self.private_data.reserved_outpoints.clone())
+        .with_unconfirmed_utxos(self.spend_unconfirmed_utxos))
+    }
+
+    /// Allows funding wallets to spend UTXOs that are not confirmed yet. Disabled by default,
+    /// since unconfirmed transactions may still be replaced.
+    pub fn set_spend_unconfirmed_utxos(&mut self, spend_unconfirmed_utxos: bool) {
+        self.spend_unconfirmed_utxos = spend_unconfirmed_utxos;
+    }
+
+    /// Outputs provided by `fund` that are still reserved.
+    pub fn reserved_outpoints(&self) -> &HashSet<OutPoint> { &self.private_data.reserved_outpoints }
+
+    /// Provides an output of exactly `amount` spendable by `role`, broadcasting a funding
+    /// transaction if no such UTXO exists yet. The output is reserved until it is spent, or
+    /// released with `release_funding`, so that it doesn't fund anything else meanwhile.
+    pub async fn fund(&mut self, role: Role, amount: Amount) -> Result<Input, Error> {
+        self.release_spent_reservations().await?;
+
+        let funding = self
+            .funding_wallet(role)?
+            .fund(&self.esplora, amount)
+            .await?;
+        if let Some(tx) = funding.transaction {
+            let status_message =
+                validate_and_broadcast(&self.esplora, &tx, &HashMap::new()).await?;
+            println!(
+                "{} Funding txid: {}",
+                status_message,
+                tx.compute_txid().to_string().green()
+            );
+        }
+
+        self.private_data
+            .reserved_outpoints
+            .insert(funding.input.outpoint);
+        self.save_private_data();
+
+        Ok(funding.input)
+    }
+
+    /// Releases an output reserved by `fund`, e.g. when the transaction it funds can't be
+    /// broadcast.
+    pub fn release_funding(&mut self, outpoint: &OutPoint) {
+        if self.private_data.reserved_outpoints.remove(outpoint) {
+            self.save_private_data();
+        }
+    }
+
+    // Releases the reserved outputs that are spent or unknown, and the verifier's contributions
+    // to challenge crowdfundings that closed, which can be reclaimed.
+    async fn release_spent_reservations(&mut self) -> Result<(), Error> {
+        let mut released = HashSet::new();
+        for outpoint in self.private_data.reserved_outpoints.iter() {
+            let known = self
+                .esplora
+                .get_tx(&outpoint.txid)
+                .await
+                .map_err(Error::Esplora)?
+                .is_some();
+            let unspent = known
+                && self
+                    .esplora
+                    .get_output_status(&outpoint.txid, outpoint.vout as u64)
+                    .await
+                    .map_err(Error::Esplora)?
+                    .is_some_and(|status| !status.spent);
+            if !unspent {
+                released.insert(*outpoint);
+            }
+        }
+
+        if let Some(context) = &self.verifier_context {
+            for peg_out_graph in self.data.peg_out_graphs.iter() {
+                let contributions = peg_out_graph
+                    .challenge_contributions_of(&context.verifier_public_key)
+                    .into_iter()
+                    .filter(|outpoint| self.private_data.reserved_outpoints.contains(outpoint))
+                    .collect::<Vec<_>>();
+                if !contributions.is_empty()
+                    && matches!(
+                        peg_out_graph
+                            .challenge_crowdfunding_status(&self.esplora)
+                            .await,
+                        Ok(ChallengeCrowdfundingStatus::Closed)
+                    )
+                {
+                    released.extend(contributions);
+                }
+            }
+        }
+
+        if !released.is_empty() {
+            self.private_data
+                .reserved_outpoints
+                .retain(|outpoint| !released.contains(outpoint));
+            self.save_private_data();
+        }
+
+        Ok(())
+    }
+
+    /// Consolidates the spendable UTXOs of the funding wallet of `role` into one output.
+    /// Reserved outputs are left untouched.
+    pub async fn consolidate_utxos(&mut self, role: Role) -> Result<Txid, Error> {
+        self.release_spent_reservations().await?;
+
+        let wallet = self.funding_wallet(role)?;
+        let utxos = wallet.spendable_utxos(&self.esplora).await?;
+        let tx = wallet.create_consolidation_transaction(&utxos)?;
+
+        let status_message = validate_and_broadcast(&self.esplora, &tx, &HashMap::new()).await?;
+        let txid = tx.compute_txid();
+        println!("{} Txid: {}", status_message, txid.to_string().green());
+
+        Ok(txid)
     }
 
     pub async fn get_initial_utxo(&self, address: Address, amount: Amount) -> Option<Utxo> {
@@ -1324,6 +2763,31 @@ impl BitVMClient {
             .unwrap()
     }
 
+    pub fn n_of_n_public_keys(&self) -> &[PublicKey] { &self.n_of_n_public_keys }
+
+    pub fn verifier_public_key(&self) -> Option<PublicKey> {
+        self.verifier_context
+            .as_ref()
+            .map(|context| context.verifier_public_key)
+    }
+
+    pub(crate) fn verifier_keypair(&self) -> Option<Keypair> {
+        self.verifier_context
+            .as_ref()
+            .map(|context| context.verifier_keypair)
+    }
+
+    /// Whether the verifier's secret nonces for `graph_id` are stored.
+    pub fn has_secret_nonces(&self, graph_id: &GraphId) -> bool {
+        self.verifier_public_key()
+            .is_some_and(|verifier_public_key| {
+                self.private_data
+                    .secret_nonces
+                    .get(&verifier_public_key)
+                    .is_some_and(|graphs| graphs.contains_key(graph_id))
+            })
+    }
+
     pub fn push_verifier_nonces(&mut self, graph_id: &GraphId) {
         if self.verifier_context.is_none() {
             panic!("Can only be called by a verifier!");
@@ -1391,12 +2855,21 @@ impl BitVMClient {
         }
     }
 
-    async fn broadcast_tx(&self, tx: &Transaction) -> Result<Txid, Error> {
-        let status_message = broadcast_and_verify(&self.esplora, tx).await?;
+    async fn broadcast_tx(&mut self, graph_id: &GraphId, tx: &Transaction) -> Result<Txid, Error> {
+        let known_prev_outs = self
+            .data
+            .graph(graph_id)
+            .map(graph_prev_outs)
+            .unwrap_or_default();
+        let status_message = validate_and_broadcast(&self.esplora, tx, &known_prev_outs).await?;
 
         let txid = tx.compute_txid();
         println!("{} Txid: {}", status_message, txid.to_string().green());
 
+        if let Some(events) = self.events.as_mut() {
+            events.watch_transaction(graph_id, txid);
+        }
+
         Ok(txid)
     }
 
@@ -1445,6 +2918,7 @@ impl BitVMClient {
                 .as_ref()
                 .unwrap()
                 .n_of_n_taproot_public_key,
+            &self.protocol_parameters,
         );
         connector_z.generate_taproot_address()
     }
@@ -1456,7 +2930,7 @@ impl BitVMClient {
         amount: Amount,
         depositor_taproot_key: &XOnlyPublicKey,
         outpoint: OutPoint,
-    ) -> String {
+    ) -> Result<String, Error> {
         let connector_z = ConnectorZ::new(
             source_network,
             recipient_address,
@@ -1466,6 +2940,7 @@ impl BitVMClient {
                 .as_ref()
                 .unwrap()
                 .n_of_n_taproot_public_key,
+            &self.protocol_parameters,
         );
         let connector_0 = Connector0::new(
             source_network,
@@ -1492,8 +2967,8 @@ impl BitVMClient {
             self.verifier_context.as_ref().unwrap(),
             &connector_z,
             &secret_nonces_0,
-        );
-        serialize_hex(&(peg_in_confirm_tx.tx_mut()))
+        )?;
+        Ok(serialize_hex(&(peg_in_confirm_tx.tx_mut())))
     }
 
     pub fn generate_presign_pegin_deposit_tx(
@@ -1514,6 +2989,7 @@ impl BitVMClient {
                 .as_ref()
                 .unwrap()
                 .n_of_n_taproot_public_key,
+            &self.protocol_parameters,
         );
         let mut peg_in_deposit_tx = PegInDepositTransaction::new_for_validation(
             source_network,
@@ -1542,6 +3018,7 @@ impl BitVMClient {
                 .as_ref()
                 .unwrap()
                 .n_of_n_taproot_public_key,
+            &self.protocol_parameters,
         );
         let mut peg_in_refund_tx = PegInRefundTransaction::new_for_validation(
             source_network,
@@ -1552,7 +3029,7 @@ impl BitVMClient {
         serialize_hex(&(peg_in_refund_tx.tx_mut()))
     }
 
-    pub fn push_verifier_signature(&mut self, graph_id: &GraphId) {
+    pub fn push_verifier_signature(&mut self, graph_id: &GraphId) -> Result<(), Error> {
         let verifier = self
             .verifier_context
             .as_ref()
@@ -1563,7 +3040,7 @@ impl BitVMClient {
             verifier,
             &self.private_data.secret_nonces
                 [&self.verifier_context.as_ref().unwrap().verifier_public_key][graph_id],
-        );
+        )
     }
 
     // pub async fn execute_possible_txs(
@@ -1843,6 +3320,7 @@ impl ClientCliQuery for BitVMClient {
             n_of_n_taproot_public_key,
             depositor_evm_address,
             deposit_input,
+            &self.protocol_parameters,
         );
 
         Ok(json!({
@@ -1882,9 +3360,13 @@ impl ClientCliQuery for BitVMClient {
             depositor_evm_address,
             deposit_input,
             signatures,
+            &self.protocol_parameters,
         );
 
-        let peg_in_graph_id = peg_in_generate_id(&peg_in_graph.peg_in_deposit_transaction);
+        let peg_in_graph_id = peg_in_generate_id(
+            &peg_in_graph.peg_in_deposit_transaction,
+            &self.protocol_parameters,
+        );
 
         let graph = self
             .data
@@ -0,0 +1,353 @@
+use std::collections::BTreeSet;
+
+use bitcoin::{PublicKey, Transaction, XOnlyPublicKey};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    client::{
+        client::BitVMClientPublicData,
+        signing_session::messages::{
+            apply_nonces, apply_partial_signatures, collect_nonces, collect_partial_signatures,
+            InputNonce, InputPartialSignature,
+        },
+    },
+    error::{DataStoreError, Error, Musig2Blame, Musig2Error},
+    graphs::{
+        base::{BaseGraph, GraphId},
+        peg_in::PegInGraph,
+        peg_out::PegOutGraph,
+    },
+    transactions::{
+        base::merge_transactions,
+        pre_signed::PreSignedTransaction,
+        pre_signed_musig2::{verify_musig2_partial_signature, verify_public_nonce},
+    },
+};
+
+/// A single change to the public data of the bridge client.
+#[derive(Serialize, Deserialize, Clone, PartialEq, Eq, Debug)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum Change {
+    PegInGraphAdded(PegInGraph),
+    PegOutGraphAdded(PegOutGraph),
+    // The inputs of the transaction named `tx_name` of the graph gained witness data, or inputs
+    // were appended to it, see `PreSignedTransaction::accepts_appended_inputs`.
+    TransactionUpdated {
+        graph_id: GraphId,
+        tx_name: String,
+        tx: Transaction,
+    },
+    NoncesAdded {
+        graph_id: GraphId,
+        nonces: Vec<InputNonce>,
+    },
+    SignaturesAdded {
+        graph_id: GraphId,
+        signatures: Vec<InputPartialSignature>,
+    },
+}
+
+impl Change {
+    pub fn graph_id(&self) -> &GraphId {
+        match self {
+            Change::PegInGraphAdded(graph) => graph.id(),
+            Change::PegOutGraphAdded(graph) => graph.id(),
+            Change::TransactionUpdated { graph_id, .. }
+            | Change::NoncesAdded { graph_id, .. }
+            | Change::SignaturesAdded { graph_id, .. } => graph_id,
+        }
+    }
+}
+
+/// The changes a participant made since its previous data store entry. Readers apply them on
+/// top of the participant's latest checkpoint, i.e. a full copy of its public data.
+#[derive(Serialize, Deserialize, Clone, PartialEq, Eq, Debug)]
+pub struct Changeset {
+    pub changes: Vec<Change>,
+}
+
+/// Changes that turn `published` into `current`. Graphs are never removed, so only additions
+/// and updates are reported.
+pub fn diff_data(
+    published: &BitVMClientPublicData,
+    current: &BitVMClientPublicData,
+) -> Vec<Change> {
+    let mut changes = vec![];
+    for graph in current.peg_in_graphs.iter() {
+        match published.graph(graph.id()) {
+            Some(published_graph) => changes.extend(diff_graph(published_graph, graph)),
+            None => changes.push(Change::PegInGraphAdded(graph.clone())),
+        }
+    }
+    for graph in current.peg_out_graphs.iter() {
+        match published.graph(graph.id()) {
+            Some(published_graph) => changes.extend(diff_graph(published_graph, graph)),
+            None => changes.push(Change::PegOutGraphAdded(graph.clone())),
+        }
+    }
+
+    changes
+}
+
+/// Changes a depositor submits to the depositor inbox: the peg-in graphs of `depositor` added
+/// since `published`.
+pub fn diff_depositor_submission(
+    published: &BitVMClientPublicData,
+    current: &BitVMClientPublicData,
+    depositor: &PublicKey,
+) -> Vec<Change> {
+    current
+        .peg_in_graphs
+        .iter()
+        .filter(|graph| {
+            graph.depositor_public_key == *depositor && published.graph(graph.id()).is_none()
+        })
+        .map(|graph| Change::PegInGraphAdded(graph.clone()))
+        .collect()
+}
+
+/// Peg-in graphs of the changes `author` submitted to the depositor inbox. Anyone may submit,
+/// so only the peg-in graphs `author` is the depositor of are accepted, stripped of the data
+/// only other participants add, i.e. MuSig2 nonces and signatures and peg-out graphs.
+pub fn accept_depositor_submission(
+    author: &PublicKey,
+    changes: Vec<Change>,
+) -> Vec<Result<PegInGraph, Error>> {
+    changes
+        .into_iter()
+        .map(|change| match change {
+            Change::PegInGraphAdded(mut graph) if graph.depositor_public_key == *author => {
+                graph.peg_out_graphs.clear();
+                for tx in graph.musig2_transactions_mut() {
+                    tx.musig2_nonces_mut().clear();
+                    tx.musig2_nonce_signatures_mut().clear();
+                    tx.musig2_signatures_mut().clear();
+                }
+                Ok(graph)
+            }
+            change => Err(Error::DataStore(DataStoreError::UnauthorizedChange {
+                author: *author,
+                graph_id: change.graph_id().clone(),
+            })),
+        })
+        .collect()
+}
+
+// Transaction updates come first, so the nonces and partial signatures that follow are applied
+// to the transactions they were made for.
+fn diff_graph(published: &dyn BaseGraph, current: &dyn BaseGraph) -> Vec<Change> {
+    let graph_id = current.id().clone();
+    let mut changes = vec![];
+
+    for (tx_name, tx) in current.pre_signed_transactions() {
+        if published
+            .pre_signed_transaction(tx_name)
+            .is_some_and(|published_tx| published_tx.tx() != tx.tx())
+        {
+            changes.push(Change::TransactionUpdated {
+                graph_id: graph_id.clone(),
+                tx_name: tx_name.to_string(),
+                tx: tx.tx().clone(),
+            });
+        }
+    }
+
+    let published_nonces = graph_nonces(published);
+    let nonces: Vec<InputNonce> = graph_nonces(current)
+        .into_iter()
+        .filter(|nonce| !published_nonces.contains(nonce))
+        .collect();
+    if !nonces.is_empty() {
+        changes.push(Change::NoncesAdded {
+            graph_id: graph_id.clone(),
+            nonces,
+        });
+    }
+
+    let published_signatures = graph_partial_signatures(published);
+    let signatures: Vec<InputPartialSignature> = graph_partial_signatures(current)
+        .into_iter()
+        .filter(|signature| !published_signatures.contains(signature))
+        .collect();
+    if !signatures.is_empty() {
+        changes.push(Change::SignaturesAdded {
+            graph_id,
+            signatures,
+        });
+    }
+
+    changes
+}
+
+// Verifiers that pushed a nonce or a partial signature to `graph`.
+fn graph_signers(graph: &dyn BaseGraph) -> BTreeSet<PublicKey> {
+    let mut signers = BTreeSet::new();
+    for tx in graph.musig2_transactions() {
+        signers.extend(
+            tx.musig2_nonces()
+                .values()
+                .flat_map(|nonces| nonces.keys().cloned()),
+        );
+        signers.extend(
+            tx.musig2_signatures()
+                .values()
+                .flat_map(|signatures| signatures.keys().cloned()),
+        );
+    }
+
+    signers
+}
+
+fn graph_nonces(graph: &dyn BaseGraph) -> Vec<InputNonce> {
+    graph_signers(graph)
+        .iter()
+        .flat_map(|verifier| collect_nonces(graph, verifier))
+        .collect()
+}
+
+fn graph_partial_signatures(graph: &dyn BaseGraph) -> Vec<InputPartialSignature> {
+    graph_signers(graph)
+        .iter()
+        .flat_map(|verifier| collect_partial_signatures(graph, verifier))
+        .collect()
+}
+
+/// Applies `change`, which must not add a graph, to `graph`. Nonces that are not signed by one of
+/// `n_of_n_public_keys` and partial signatures that do not verify against the signer's public
+/// key and nonce are refused, like when merging a checkpoint. The valid ones are applied
+/// regardless, and the first refusal is returned. Transaction updates that do more than add
+/// witnesses, or leave the graph invalid, are refused as a whole.
+pub fn apply_graph_change(
+    graph: &mut dyn BaseGraph,
+    change: &Change,
+    n_of_n_public_keys: &[PublicKey],
+) -> Result<(), Error> {
+    match change {
+        Change::TransactionUpdated {
+            graph_id,
+            tx_name,
+            tx,
+        } => {
+            let Some(destination) = graph.pre_signed_transaction_mut(tx_name) else {
+                return Err(Error::DataStore(DataStoreError::UnknownTransaction {
+                    graph_id: graph_id.clone(),
+                    tx: tx_name.clone(),
+                }));
+            };
+            let published_tx = destination.tx().clone();
+            let merged = merge_transaction_update(destination, tx);
+            if merged && graph.validate() {
+                return Ok(());
+            }
+
+            if let Some(destination) = graph.pre_signed_transaction_mut(tx_name) {
+                *destination.tx_mut() = published_tx;
+            }
+            Err(Error::DataStore(DataStoreError::InvalidTransactionUpdate {
+                graph_id: graph_id.clone(),
+                tx: tx_name.clone(),
+            }))
+        }
+        Change::NoncesAdded { nonces, .. } => {
+            let (valid_nonces, invalid_nonces): (Vec<InputNonce>, Vec<InputNonce>) =
+                nonces.iter().cloned().partition(|nonce| {
+                    n_of_n_public_keys.contains(&nonce.verifier)
+                        && verify_public_nonce(
+                            &nonce.signature,
+                            &nonce.nonce,
+                            &XOnlyPublicKey::from(nonce.verifier),
+                        )
+                });
+            apply_nonces(graph, &valid_nonces);
+
+            match invalid_nonces.first() {
+                Some(nonce) => {
+                    let blame = Musig2Blame {
+                        txid: nonce.txid,
+                        input_index: nonce.input_index,
+                        verifier: nonce.verifier,
+                    };
+                    Err(Error::Musig2(
+                        match n_of_n_public_keys.contains(&nonce.verifier) {
+                            true => Musig2Error::InvalidNonce(blame),
+                            false => Musig2Error::UnknownSigner(blame),
+                        },
+                    ))
+                }
+                None => Ok(()),
+            }
+        }
+        Change::SignaturesAdded { signatures, .. } => {
+            let mut valid_signatures = vec![];
+            let mut refusal = None;
+            for signature in signatures {
+                let result = graph
+                    .musig2_transactions()
+                    .into_iter()
+                    .find(|tx| tx.tx().compute_txid() == signature.txid)
+                    .map(|tx| {
+                        verify_musig2_partial_signature(
+                            tx,
+                            n_of_n_public_keys,
+                            signature.input_index,
+                            &signature.verifier,
+                            signature.signature,
+                        )
+                    });
+                match result {
+                    Some(Ok(())) => valid_signatures.push(signature.clone()),
+                    Some(Err(err)) => {
+                        refusal.get_or_insert(Error::Musig2(err));
+                    }
+                    None => {
+                        refusal.get_or_insert(Error::DataStore(
+                            DataStoreError::UnknownTransaction {
+                                graph_id: graph.id().clone(),
+                                tx: signature.txid.to_string(),
+                            },
+                        ));
+                    }
+                }
+            }
+            apply_partial_signatures(graph, &valid_signatures);
+
+            match refusal {
+                Some(err) => Err(err),
+                None => Ok(()),
+            }
+        }
+        Change::PegInGraphAdded(_) | Change::PegOutGraphAdded(_) => Err(Error::DataStore(
+            DataStoreError::GraphAlreadyExists(change.graph_id().clone()),
+        )),
+    }
+}
+
+// Merges the witnesses of the inputs of `update` into the ones of `destination`, and appends
+// its other inputs if `destination` accepts appended inputs. Returns false, leaving
+// `destination` unchanged, if `update` changes anything else.
+fn merge_transaction_update(
+    destination: &mut dyn PreSignedTransaction,
+    update: &Transaction,
+) -> bool {
+    let tx = destination.tx();
+    if update.version != tx.version
+        || update.lock_time != tx.lock_time
+        || update.output != tx.output
+        || update.input.len() < tx.input.len()
+        || (update.input.len() > tx.input.len() && !destination.accepts_appended_inputs())
+        || tx
+            .input
+            .iter()
+            .zip(update.input.iter())
+            .any(|(input, updated_input)| {
+                input.previous_output != updated_input.previous_output
+                    || input.script_sig != updated_input.script_sig
+                    || input.sequence != updated_input.sequence
+            })
+    {
+        return false;
+    }
+
+    merge_transactions(destination.tx_mut(), update);
+    true
+}
@@ -1,10 +1,14 @@
+use bitcoin::{key::Keypair, PublicKey};
 use regex::Regex;
 use std::cmp::Ordering;
 use std::time::{Duration, SystemTime, UNIX_EPOCH};
 
+use crate::serialization::serialize;
+
 use super::base::DataStoreDriver;
 use super::{
     aws_s3::AwsS3,
+    envelope::SignedEnvelope,
     ftp::{ftp::Ftp, ftps::Ftps},
     sftp::Sftp,
 };
@@ -14,6 +18,18 @@ static CLIENT_MISSING_CREDENTIALS_ERROR: &str =
 
 static DEFAULT_CLIENT_DATA_SUFFIX: &str = "-bridge-client-data.json";
 
+static CHANGESET_FILE_MARKER: &str = "-changes";
+
+static DEPOSITOR_INBOX_DIRECTORY_NAME: &str = "depositors";
+
+/// Kind of the data store entries. A checkpoint holds the full public data of its author, a
+/// changeset the changes made since the author's previous entry.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum DataFileKind {
+    Checkpoint,
+    Changeset,
+}
+
 pub struct DataStore {
     client_data_suffix: String,
     client_data_regex: Regex,
@@ -32,7 +48,11 @@ impl DataStore {
         };
         Self {
             client_data_suffix: client_data_suffix.clone(),
-            client_data_regex: Regex::new(&format!(r"(\d{{13}}){}", client_data_suffix)).unwrap(),
+            client_data_regex: Regex::new(&format!(
+                r"(\d{{13}})({})?{}",
+                CHANGESET_FILE_MARKER, client_data_suffix
+            ))
+            .unwrap(),
             aws_s3: AwsS3::new(),
             ftp: Ftp::new().await,
             ftps: Ftps::new().await,
@@ -54,6 +74,15 @@ impl DataStore {
         Err(String::from("Incorrect file name"))
     }
 
+    pub fn get_file_kind(&self, file_name: &str) -> Result<DataFileKind, String> {
+        self.get_file_timestamp(file_name)?;
+        if file_name[13..].starts_with(CHANGESET_FILE_MARKER) {
+            return Ok(DataFileKind::Changeset);
+        }
+
+        Ok(DataFileKind::Checkpoint)
+    }
+
     pub async fn get_file_names(&self, file_path: Option<&str>) -> Result<Vec<String>, String> {
         match self.get_driver() {
             Ok(driver) => match driver.list_objects(file_path).await {
@@ -114,7 +143,7 @@ impl DataStore {
                     .duration_since(UNIX_EPOCH)
                     .unwrap()
                     .as_millis();
-                let file_name = self.create_file_name(time);
+                let file_name = self.create_file_name(time, DataFileKind::Checkpoint);
                 let response = driver.upload_object(&file_name, contents, file_path).await;
 
                 match response {
@@ -157,7 +186,7 @@ impl DataStore {
                     .duration_since(UNIX_EPOCH)
                     .unwrap()
                     .as_millis();
-                let file_name = self.create_file_name(time);
+                let file_name = self.create_file_name(time, DataFileKind::Checkpoint);
                 let response = driver
                     .upload_compressed_object(&file_name, contents, file_path)
                     .await;
@@ -171,6 +200,79 @@ impl DataStore {
         }
     }
 
+    /// Signs `contents` as `author` and uploads the envelope to the author's namespace under
+    /// `file_path`.
+    pub async fn write_signed_data(
+        &self,
+        contents: &str,
+        kind: DataFileKind,
+        author: &PublicKey,
+        keypair: &Keypair,
+        file_path: &str,
+    ) -> Result<(String, usize), String> {
+        self.upload_signed_data(
+            contents,
+            kind,
+            author,
+            keypair,
+            &get_author_file_path(file_path, author),
+        )
+        .await
+    }
+
+    /// Signs the changeset `contents` as the depositor `author` and uploads the envelope to the
+    /// depositor inbox under `file_path`, see `get_depositor_inbox_file_path`.
+    pub async fn write_depositor_submission(
+        &self,
+        contents: &str,
+        author: &PublicKey,
+        keypair: &Keypair,
+        file_path: &str,
+    ) -> Result<(String, usize), String> {
+        self.upload_signed_data(
+            contents,
+            DataFileKind::Changeset,
+            author,
+            keypair,
+            &get_depositor_inbox_file_path(file_path),
+        )
+        .await
+    }
+
+    async fn upload_signed_data(
+        &self,
+        contents: &str,
+        kind: DataFileKind,
+        author: &PublicKey,
+        keypair: &Keypair,
+        file_path: &str,
+    ) -> Result<(String, usize), String> {
+        match self.get_driver() {
+            Ok(driver) => {
+                let time = SystemTime::now()
+                    .duration_since(UNIX_EPOCH)
+                    .unwrap()
+                    .as_millis();
+                let envelope =
+                    SignedEnvelope::seal(*author, keypair, time as u64, contents.to_string());
+                let file_name = self.create_file_name(time, kind);
+                let response = driver
+                    .upload_compressed_object(
+                        &file_name,
+                        &serialize(&envelope).into_bytes(),
+                        Some(file_path),
+                    )
+                    .await;
+
+                match response {
+                    Ok(size) => Ok((file_name, size)),
+                    Err(_) => Err(String::from("Failed to save data file")),
+                }
+            }
+            Err(err) => Err(err.to_string()),
+        }
+    }
+
     pub fn get_past_max_file_name_by_timestamp(
         &self,
         latest_timestamp: u64,
@@ -178,11 +280,17 @@ impl DataStore {
     ) -> String {
         let past_max_timestamp =
             (Duration::from_millis(latest_timestamp) - Duration::from_secs(period)).as_millis();
-        self.create_file_name(past_max_timestamp)
+        self.create_file_name(past_max_timestamp, DataFileKind::Checkpoint)
     }
 
-    fn create_file_name(&self, timestamp: u128) -> String {
-        format!("{}{}", timestamp, self.client_data_suffix)
+    fn create_file_name(&self, timestamp: u128, kind: DataFileKind) -> String {
+        match kind {
+            DataFileKind::Checkpoint => format!("{}{}", timestamp, self.client_data_suffix),
+            DataFileKind::Changeset => format!(
+                "{}{}{}",
+                timestamp, CHANGESET_FILE_MARKER, self.client_data_suffix
+            ),
+        }
     }
 
     fn get_driver(&self) -> Result<&dyn DataStoreDriver, &str> {
@@ -199,3 +307,14 @@ impl DataStore {
         }
     }
 }
+
+/// Path of the namespace `author` writes its data to under `file_path`.
+pub fn get_author_file_path(file_path: &str, author: &PublicKey) -> String {
+    format!("{file_path}/{author}")
+}
+
+/// Path of the inbox depositors submit their peg-in graphs to under `file_path`. Depositors are
+/// not known in advance, so participants scan it instead of a namespace per depositor.
+pub fn get_depositor_inbox_file_path(file_path: &str) -> String {
+    format!("{file_path}/{DEPOSITOR_INBOX_DIRECTORY_NAME}")
+}
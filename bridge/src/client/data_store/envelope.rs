@@ -0,0 +1,112 @@
+use bitcoin::{
+    hashes::{sha256, Hash},
+    key::Keypair,
+    PublicKey, XOnlyPublicKey,
+};
+use musig2::secp256k1::{schnorr::Signature, Message};
+use serde::{Deserialize, Serialize};
+
+use crate::{error::DataStoreError, serialization::try_deserialize_slice};
+
+/// Client data uploaded to the data store, signed by its author. The timestamp is the one of
+/// the file the envelope is uploaded to, so a signed envelope cannot be replayed under a newer
+/// file name.
+#[derive(Serialize, Deserialize, Clone, PartialEq, Eq, Debug)]
+pub struct SignedEnvelope {
+    pub author: PublicKey,
+    pub timestamp: u64,
+    pub payload: String,
+    pub signature: Signature,
+}
+
+impl SignedEnvelope {
+    pub fn seal(author: PublicKey, keypair: &Keypair, timestamp: u64, payload: String) -> Self {
+        let signature = keypair.sign_schnorr(get_envelope_message(&author, timestamp, &payload));
+
+        Self {
+            author,
+            timestamp,
+            payload,
+            signature,
+        }
+    }
+
+    /// Returns the payload if the envelope was signed by `expected_author`, one of
+    /// `trusted_authors`, for the file timestamped `file_timestamp`.
+    pub fn open(
+        &self,
+        expected_author: &PublicKey,
+        trusted_authors: &[PublicKey],
+        file_timestamp: u64,
+    ) -> Result<&str, DataStoreError> {
+        if !trusted_authors.contains(&self.author) {
+            return Err(DataStoreError::UnknownAuthor(self.author));
+        }
+        if self.author != *expected_author {
+            return Err(DataStoreError::UnexpectedAuthor {
+                expected: *expected_author,
+                found: self.author,
+            });
+        }
+
+        self.open_from_any_author(file_timestamp)
+    }
+
+    /// Returns the payload if the envelope was signed by its author, whoever that is, for the
+    /// file timestamped `file_timestamp`. Callers must check what the author is allowed to
+    /// publish, e.g. depositor submissions.
+    pub fn open_from_any_author(&self, file_timestamp: u64) -> Result<&str, DataStoreError> {
+        let message = get_envelope_message(&self.author, self.timestamp, &self.payload);
+        if self
+            .signature
+            .verify(&message, &XOnlyPublicKey::from(self.author))
+            .is_err()
+        {
+            return Err(DataStoreError::InvalidSignature(self.author));
+        }
+        if self.timestamp != file_timestamp {
+            return Err(DataStoreError::Stale {
+                author: self.author,
+                timestamp: self.timestamp,
+                file_timestamp,
+            });
+        }
+
+        Ok(&self.payload)
+    }
+}
+
+/// Deserializes the envelope in `content` and opens it, see `SignedEnvelope::open`.
+pub fn open_envelope(
+    content: &[u8],
+    expected_author: &PublicKey,
+    trusted_authors: &[PublicKey],
+    file_timestamp: u64,
+) -> Result<String, DataStoreError> {
+    let envelope: SignedEnvelope =
+        try_deserialize_slice(content).map_err(|_| DataStoreError::Unsigned)?;
+    envelope
+        .open(expected_author, trusted_authors, file_timestamp)
+        .map(str::to_string)
+}
+
+/// Deserializes the envelope in `content` and opens it whoever its author is, see
+/// `SignedEnvelope::open_from_any_author`. Returns the author along with the payload.
+pub fn open_submission_envelope(
+    content: &[u8],
+    file_timestamp: u64,
+) -> Result<(PublicKey, String), DataStoreError> {
+    let envelope: SignedEnvelope =
+        try_deserialize_slice(content).map_err(|_| DataStoreError::Unsigned)?;
+    envelope
+        .open_from_any_author(file_timestamp)
+        .map(|payload| (envelope.author, payload.to_string()))
+}
+
+fn get_envelope_message(author: &PublicKey, timestamp: u64, payload: &str) -> Message {
+    let mut contents = author.to_bytes();
+    contents.extend(timestamp.to_be_bytes());
+    contents.extend(payload.as_bytes());
+    let hash = sha256::Hash::hash(&contents);
+    Message::from_digest_slice(hash.as_ref()).expect("Failed to create envelope message")
+}
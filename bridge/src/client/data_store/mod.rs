@@ -1,5 +1,7 @@
 pub mod aws_s3;
 pub mod base;
+pub mod changeset;
 pub mod data_store;
+pub mod envelope;
 pub mod ftp;
 pub mod sftp;
@@ -0,0 +1,549 @@
+use std::{
+    collections::{HashMap, HashSet},
+    fs::{self, File, OpenOptions},
+    io::{BufRead, BufReader, Write},
+    path::{Path, PathBuf},
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use bitcoin::{OutPoint, Txid};
+use futures::{stream, Stream};
+use serde::{Deserialize, Serialize};
+use tokio::sync::broadcast::{self, error::RecvError};
+
+use crate::graphs::{
+    base::GraphId,
+    peg_in::{PegInDepositorStatus, PegInOperatorStatus, PegInRecoveryStatus, PegInVerifierStatus},
+    peg_out::{PegOutOperatorStatus, PegOutVerifierStatus, PegOutWithdrawerStatus},
+};
+
+// Events published while no subscriber keeps up are dropped from the stream after this many,
+// but are still available in the event log.
+const EVENT_CHANNEL_CAPACITY: usize = 1024;
+
+#[derive(Serialize, Deserialize, Eq, PartialEq, Hash, Clone, Copy, Debug)]
+pub enum Role {
+    Depositor,
+    Operator,
+    Verifier,
+    Withdrawer,
+}
+
+#[derive(Serialize, Deserialize, PartialEq, Clone, Debug)]
+pub enum PegInStatus {
+    Depositor(PegInDepositorStatus),
+    Operator(PegInOperatorStatus),
+    Verifier(PegInVerifierStatus),
+}
+
+impl PegInStatus {
+    pub fn role(&self) -> Role {
+        match self {
+            PegInStatus::Depositor(_) => Role::Depositor,
+            PegInStatus::Operator(_) => Role::Operator,
+            PegInStatus::Verifier(_) => Role::Verifier,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, PartialEq, Clone, Debug)]
+pub enum PegOutStatus {
+    Operator(PegOutOperatorStatus),
+    Verifier(PegOutVerifierStatus),
+    Withdrawer(PegOutWithdrawerStatus),
+}
+
+impl PegOutStatus {
+    pub fn role(&self) -> Role {
+        match self {
+            PegOutStatus::Operator(_) => Role::Operator,
+            PegOutStatus::Verifier(_) => Role::Verifier,
+            PegOutStatus::Withdrawer(_) => Role::Withdrawer,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, PartialEq, Clone, Debug)]
+pub enum BridgeEvent {
+    // `old` is `None` the first time a status is observed for the graph and role.
+    PegInStatusChanged {
+        graph_id: GraphId,
+        old: Option<PegInStatus>,
+        new: PegInStatus,
+    },
+    PegOutStatusChanged {
+        graph_id: GraphId,
+        old: Option<PegOutStatus>,
+        new: PegOutStatus,
+    },
+    TxConfirmed {
+        graph_id: GraphId,
+        txid: Txid,
+        block_height: Option<u32>,
+    },
+    // Progress of the depositor recovery of a peg-in, see `PegInGraph::recovery_status`.
+    PegInRecoveryStatusChanged {
+        graph_id: GraphId,
+        old: Option<PegInRecoveryStatus>,
+        new: PegInRecoveryStatus,
+    },
+    // The challenge window closed before the crowdfunded challenge was broadcast. The
+    // `contributions` of this client can be reclaimed from its funding wallet.
+    ChallengeCrowdfundingClosed {
+        graph_id: GraphId,
+        contributions: Vec<OutPoint>,
+    },
+}
+
+/// An event together with its position in the event log.
+#[derive(Serialize, Deserialize, PartialEq, Clone, Debug)]
+pub struct RecordedEvent {
+    pub sequence: u64,
+    pub timestamp: u64, // seconds since the unix epoch
+    pub event: BridgeEvent,
+}
+
+/// Append-only log storing one JSON encoded `RecordedEvent` per line.
+pub struct EventLog {
+    path: PathBuf,
+    file: File,
+    next_sequence: u64,
+}
+
+impl EventLog {
+    pub fn open(path: &Path) -> Self {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).expect("Failed to create event log directory");
+        }
+        let next_sequence = Self::read(path)
+            .last()
+            .map_or(0, |event| event.sequence + 1);
+        let file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)
+            .unwrap_or_else(|e| panic!("Could not open event log {}: {}", path.display(), e));
+
+        Self {
+            path: path.to_path_buf(),
+            file,
+            next_sequence,
+        }
+    }
+
+    pub fn append(&mut self, event: BridgeEvent) -> RecordedEvent {
+        let recorded_event = RecordedEvent {
+            sequence: self.next_sequence,
+            timestamp: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .unwrap()
+                .as_secs(),
+            event,
+        };
+        let line = serde_json::to_string(&recorded_event).expect("Failed to serialize event");
+        writeln!(self.file, "{}", line)
+            .and_then(|_| self.file.flush())
+            .unwrap_or_else(|e| panic!("Could not write event log {}: {}", self.path.display(), e));
+        self.next_sequence += 1;
+
+        recorded_event
+    }
+
+    /// Reads all the events in the log at `path`. A missing log is empty.
+    pub fn read(path: &Path) -> Vec<RecordedEvent> {
+        let file = match File::open(path) {
+            Ok(file) => file,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return vec![],
+            Err(e) => panic!("Could not read event log {}: {}", path.display(), e),
+        };
+
+        BufReader::new(file)
+            .lines()
+            .map(|line| line.expect("Could not read event log line"))
+            .filter(|line| !line.is_empty())
+            .map(|line| serde_json::from_str(&line).expect("Could not deserialize event"))
+            .collect()
+    }
+
+    pub fn path(&self) -> &Path { &self.path }
+}
+
+// What the emitter has observed so far. It is saved after every change so that a restarted
+// client neither reports the same statuses again nor stops watching pending transactions.
+#[derive(Serialize, Deserialize, Default)]
+struct EmitterState {
+    peg_in_statuses: HashMap<GraphId, HashMap<Role, PegInStatus>>,
+    peg_out_statuses: HashMap<GraphId, HashMap<Role, PegOutStatus>>,
+    peg_in_recovery_statuses: HashMap<GraphId, PegInRecoveryStatus>,
+    watched_transactions: HashMap<Txid, GraphId>,
+    closed_challenge_crowdfundings: HashSet<GraphId>,
+}
+
+impl EmitterState {
+    fn read(path: &Path) -> Self {
+        match fs::read_to_string(path) {
+            Ok(content) => serde_json::from_str(&content).unwrap_or_else(|e| {
+                panic!(
+                    "Could not deserialize event state {}: {}",
+                    path.display(),
+                    e
+                )
+            }),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Self::default(),
+            Err(e) => panic!("Could not read event state {}: {}", path.display(), e),
+        }
+    }
+
+    // Writes a temporary file first, so that the previous state survives a crash while saving.
+    fn save(&self, path: &Path) {
+        let temp_path = path.with_extension("tmp");
+        let content = serde_json::to_string(self).expect("Failed to serialize event state");
+        fs::write(&temp_path, content)
+            .and_then(|_| fs::rename(&temp_path, path))
+            .unwrap_or_else(|e| panic!("Could not write event state {}: {}", path.display(), e));
+    }
+}
+
+/// Turns observed graph statuses and transaction confirmations into events, persists them in
+/// the event log and publishes them to all subscribers. The last observed statuses and the
+/// watched transactions are persisted at the state path.
+pub struct EventEmitter {
+    log: EventLog,
+    sender: broadcast::Sender<RecordedEvent>,
+    state_path: PathBuf,
+    state: EmitterState,
+}
+
+impl EventEmitter {
+    pub fn new(log_path: &Path, state_path: &Path) -> Self {
+        let (sender, _) = broadcast::channel(EVENT_CHANNEL_CAPACITY);
+        Self {
+            log: EventLog::open(log_path),
+            sender,
+            state_path: state_path.to_path_buf(),
+            state: EmitterState::read(state_path),
+        }
+    }
+
+    /// Stream of all events emitted after subscribing. A subscriber that falls behind by more
+    /// than `EVENT_CHANNEL_CAPACITY` events skips the missed ones, which can be recovered from
+    /// the event log using the sequence numbers.
+    pub fn subscribe(&self) -> impl Stream<Item = RecordedEvent> {
+        stream::unfold(self.sender.subscribe(), |mut receiver| async move {
+            loop {
+                match receiver.recv().await {
+                    Ok(event) => return Some((event, receiver)),
+                    Err(RecvError::Lagged(_)) => continue,
+                    Err(RecvError::Closed) => return None,
+                }
+            }
+        })
+    }
+
+    pub fn log(&self) -> &EventLog { &self.log }
+
+    pub fn update_peg_in_status(&mut self, graph_id: &GraphId, new: PegInStatus) {
+        let old = self
+            .state
+            .peg_in_statuses
+            .entry(graph_id.clone())
+            .or_default()
+            .insert(new.role(), new.clone());
+        if old.as_ref() != Some(&new) {
+            self.emit(BridgeEvent::PegInStatusChanged {
+                graph_id: graph_id.clone(),
+                old,
+                new,
+            });
+        }
+    }
+
+    pub fn update_peg_out_status(&mut self, graph_id: &GraphId, new: PegOutStatus) {
+        let old = self
+            .state
+            .peg_out_statuses
+            .entry(graph_id.clone())
+            .or_default()
+            .insert(new.role(), new.clone());
+        if old.as_ref() != Some(&new) {
+            self.emit(BridgeEvent::PegOutStatusChanged {
+                graph_id: graph_id.clone(),
+                old,
+                new,
+            });
+        }
+    }
+
+    pub fn update_peg_in_recovery_status(&mut self, graph_id: &GraphId, new: PegInRecoveryStatus) {
+        let old = self
+            .state
+            .peg_in_recovery_statuses
+            .insert(graph_id.clone(), new.clone());
+        if old.as_ref() != Some(&new) {
+            self.emit(BridgeEvent::PegInRecoveryStatusChanged {
+                graph_id: graph_id.clone(),
+                old,
+                new,
+            });
+        }
+    }
+
+    pub fn watch_transaction(&mut self, graph_id: &GraphId, txid: Txid) {
+        if self
+            .state
+            .watched_transactions
+            .insert(txid, graph_id.clone())
+            .as_ref()
+            != Some(graph_id)
+        {
+            self.save_state();
+        }
+    }
+
+    pub fn watched_transactions(&self) -> Vec<Txid> {
+        self.state.watched_transactions.keys().cloned().collect()
+    }
+
+    /// Stops watching `txid` and emits `TxConfirmed` if it was being watched.
+    pub fn confirm_transaction(&mut self, txid: Txid, block_height: Option<u32>) {
+        if let Some(graph_id) = self.state.watched_transactions.remove(&txid) {
+            self.emit(BridgeEvent::TxConfirmed {
+                graph_id,
+                txid,
+                block_height,
+            });
+        }
+    }
+
+    /// Emits `ChallengeCrowdfundingClosed` the first time it is called for `graph_id`.
+    pub fn close_challenge_crowdfunding(
+        &mut self,
+        graph_id: &GraphId,
+        contributions: Vec<OutPoint>,
+    ) {
+        if self
+            .state
+            .closed_challenge_crowdfundings
+            .insert(graph_id.clone())
+        {
+            self.emit(BridgeEvent::ChallengeCrowdfundingClosed {
+                graph_id: graph_id.clone(),
+                contributions,
+            });
+        }
+    }
+
+    fn save_state(&self) { self.state.save(&self.state_path); }
+
+    // The state is saved after the event is logged, a crash in between emits it again on restart
+    // rather than losing it.
+    fn emit(&mut self, event: BridgeEvent) {
+        let recorded_event = self.log.append(event);
+        self.save_state();
+        // Sending only fails if there are no subscribers, the event is in the log regardless.
+        let _ = self.sender.send(recorded_event);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::env::temp_dir;
+
+    use bitcoin::hashes::Hash;
+    use futures::StreamExt;
+
+    use super::*;
+
+    fn temp_log_path(name: &str) -> PathBuf {
+        let path = temp_dir()
+            .join(format!("bridge_events_{}", std::process::id()))
+            .join(name);
+        let _ = fs::remove_file(&path);
+        let _ = fs::remove_file(state_path(&path));
+        path
+    }
+
+    fn state_path(log_path: &Path) -> PathBuf { log_path.with_extension("state.json") }
+
+    fn open_emitter(log_path: &Path) -> EventEmitter {
+        EventEmitter::new(log_path, &state_path(log_path))
+    }
+
+    #[tokio::test]
+    async fn test_emits_status_changes_only() {
+        let path = temp_log_path("status_changes.jsonl");
+        let mut emitter = open_emitter(&path);
+        let mut events = Box::pin(emitter.subscribe());
+        let graph_id = "graph".to_string();
+
+        emitter.update_peg_out_status(
+            &graph_id,
+            PegOutStatus::Operator(PegOutOperatorStatus::PegOutKickOff1Available),
+        );
+        emitter.update_peg_out_status(
+            &graph_id,
+            PegOutStatus::Operator(PegOutOperatorStatus::PegOutKickOff1Available),
+        );
+        // Statuses of different roles are tracked separately.
+        emitter.update_peg_out_status(
+            &graph_id,
+            PegOutStatus::Verifier(PegOutVerifierStatus::PegOutWait),
+        );
+        emitter.update_peg_out_status(
+            &graph_id,
+            PegOutStatus::Operator(PegOutOperatorStatus::PegOutStartTimeAvailable),
+        );
+
+        let first = events.next().await.unwrap();
+        assert_eq!(first.sequence, 0);
+        assert_eq!(
+            first.event,
+            BridgeEvent::PegOutStatusChanged {
+                graph_id: graph_id.clone(),
+                old: None,
+                new: PegOutStatus::Operator(PegOutOperatorStatus::PegOutKickOff1Available),
+            }
+        );
+        let second = events.next().await.unwrap();
+        assert_eq!(second.sequence, 1);
+        let third = events.next().await.unwrap();
+        assert_eq!(
+            third.event,
+            BridgeEvent::PegOutStatusChanged {
+                graph_id,
+                old: Some(PegOutStatus::Operator(
+                    PegOutOperatorStatus::PegOutKickOff1Available
+                )),
+                new: PegOutStatus::Operator(PegOutOperatorStatus::PegOutStartTimeAvailable),
+            }
+        );
+
+        assert_eq!(EventLog::read(&path), vec![first, second, third]);
+    }
+
+    #[test]
+    fn test_confirms_watched_transactions_once() {
+        let path = temp_log_path("confirmations.jsonl");
+        let mut emitter = open_emitter(&path);
+        let graph_id = "graph".to_string();
+        let txid = Txid::all_zeros();
+
+        emitter.confirm_transaction(txid, Some(1));
+        assert!(EventLog::read(&path).is_empty());
+
+        emitter.watch_transaction(&graph_id, txid);
+        assert_eq!(emitter.watched_transactions(), vec![txid]);
+        emitter.confirm_transaction(txid, Some(2));
+        emitter.confirm_transaction(txid, Some(3));
+
+        let events = EventLog::read(&path);
+        assert_eq!(events.len(), 1);
+        assert_eq!(
+            events[0].event,
+            BridgeEvent::TxConfirmed {
+                graph_id,
+                txid,
+                block_height: Some(2),
+            }
+        );
+        assert!(emitter.watched_transactions().is_empty());
+    }
+
+    #[test]
+    fn test_notifies_closed_challenge_crowdfunding_once() {
+        let path = temp_log_path("challenge_crowdfunding.jsonl");
+        let mut emitter = open_emitter(&path);
+        let graph_id = "graph".to_string();
+        let contributions = vec![OutPoint {
+            txid: Txid::all_zeros(),
+            vout: 1,
+        }];
+
+        emitter.close_challenge_crowdfunding(&graph_id, contributions.clone());
+        emitter.close_challenge_crowdfunding(&graph_id, contributions.clone());
+
+        let events = EventLog::read(&path);
+        assert_eq!(events.len(), 1);
+        assert_eq!(
+            events[0].event,
+            BridgeEvent::ChallengeCrowdfundingClosed {
+                graph_id,
+                contributions,
+            }
+        );
+    }
+
+    #[test]
+    fn test_log_continues_sequence_after_reopening() {
+        let path = temp_log_path("reopen.jsonl");
+        let graph_id = "graph".to_string();
+
+        let mut emitter = open_emitter(&path);
+        emitter.update_peg_in_status(
+            &graph_id,
+            PegInStatus::Depositor(PegInDepositorStatus::PegInDepositWait),
+        );
+
+        let mut emitter = open_emitter(&path);
+        emitter.update_peg_in_status(
+            &graph_id,
+            PegInStatus::Depositor(PegInDepositorStatus::PegInConfirmWait),
+        );
+
+        let sequences: Vec<u64> = EventLog::read(&path)
+            .iter()
+            .map(|event| event.sequence)
+            .collect();
+        assert_eq!(sequences, vec![0, 1]);
+    }
+
+    #[test]
+    fn test_restores_state_after_reopening() {
+        let path = temp_log_path("restore.jsonl");
+        let graph_id = "graph".to_string();
+        let txid = Txid::all_zeros();
+
+        let mut emitter = open_emitter(&path);
+        emitter.update_peg_in_status(
+            &graph_id,
+            PegInStatus::Depositor(PegInDepositorStatus::PegInDepositWait),
+        );
+        emitter.watch_transaction(&graph_id, txid);
+        drop(emitter);
+
+        let mut emitter = open_emitter(&path);
+        assert_eq!(emitter.watched_transactions(), vec![txid]);
+        emitter.update_peg_in_status(
+            &graph_id,
+            PegInStatus::Depositor(PegInDepositorStatus::PegInDepositWait),
+        );
+        emitter.update_peg_in_status(
+            &graph_id,
+            PegInStatus::Depositor(PegInDepositorStatus::PegInConfirmWait),
+        );
+        emitter.confirm_transaction(txid, Some(1));
+
+        let events: Vec<BridgeEvent> = EventLog::read(&path)
+            .into_iter()
+            .map(|event| event.event)
+            .collect();
+        assert_eq!(
+            events[1..],
+            [
+                BridgeEvent::PegInStatusChanged {
+                    graph_id: graph_id.clone(),
+                    old: Some(PegInStatus::Depositor(
+                        PegInDepositorStatus::PegInDepositWait
+                    )),
+                    new: PegInStatus::Depositor(PegInDepositorStatus::PegInConfirmWait),
+                },
+                BridgeEvent::TxConfirmed {
+                    graph_id,
+                    txid,
+                    block_height: Some(1),
+                },
+            ]
+        );
+        assert!(open_emitter(&path).watched_transactions().is_empty());
+    }
+}
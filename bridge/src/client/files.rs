@@ -1,16 +1,19 @@
 use std::{
-    collections::HashMap,
+    collections::{HashMap, HashSet},
     fs,
     path::{Path, PathBuf},
 };
 
-use crate::serialization::try_deserialize;
-
-use super::client::BitVMClientPrivateData;
+use super::{
+    client::BitVMClientPrivateData,
+    schema::{deserialize_versioned, SchemaId},
+};
 
 pub const BRIDGE_DATA_DIRECTORY_NAME: &str = "bridge_data";
 pub const DEFAULT_PATH_PREFIX: &str = "default_user";
 pub const PRIVATE_DATA_FILE_NAME: &str = "secret_data.json";
+pub const EVENT_LOG_FILE_NAME: &str = "events.jsonl";
+pub const EVENT_STATE_FILE_NAME: &str = "events_state.json";
 const PRIVATE_DATA_DIRECTORY_NAME: &str = "private";
 const PUBLIC_DATA_DIRECTORY_NAME: &str = "public";
 
@@ -22,6 +25,14 @@ pub fn get_private_data_file_path(data_root_path: &Path) -> PathBuf {
     get_private_data_directory_path(data_root_path).join(PRIVATE_DATA_FILE_NAME)
 }
 
+pub fn get_event_log_file_path(data_root_path: &Path) -> PathBuf {
+    data_root_path.join(EVENT_LOG_FILE_NAME)
+}
+
+pub fn get_event_state_file_path(data_root_path: &Path) -> PathBuf {
+    data_root_path.join(EVENT_STATE_FILE_NAME)
+}
+
 fn get_public_data_directory_path(data_root_path: &Path) -> PathBuf {
     data_root_path.join(PUBLIC_DATA_DIRECTORY_NAME)
 }
@@ -44,11 +55,12 @@ pub fn create_directories_if_non_existent(data_root_path: &Path) {
 
 pub fn get_private_data_from_file(path: &Path) -> BitVMClientPrivateData {
     match read_file(path) {
-        Some(data) => try_deserialize::<BitVMClientPrivateData>(&data)
+        Some(data) => deserialize_versioned(SchemaId::PrivateData, data.as_bytes())
             .expect("Could not deserialize private data"),
         None => BitVMClientPrivateData {
             secret_nonces: HashMap::new(),
             commitment_secrets: HashMap::new(),
+            reserved_outpoints: HashSet::new(),
         },
     }
 }
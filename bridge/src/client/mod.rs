@@ -4,6 +4,12 @@ pub mod cli;
 pub mod client;
 pub mod data_store;
 pub mod esplora;
+pub mod events;
 pub mod files;
 pub mod memory_cache;
+pub mod rpc;
+pub mod schema;
 pub mod sdk;
+pub mod signing_session;
+pub mod simulator;
+pub mod wallet;
@@ -0,0 +1,393 @@
+use std::{io, time::Duration};
+
+use bitcoin::XOnlyPublicKey;
+use serde_json::{json, Map, Value};
+use tokio::{
+    io::{AsyncBufReadExt, AsyncReadExt, AsyncWriteExt, BufReader},
+    net::{TcpListener, TcpStream},
+    sync::{mpsc, oneshot},
+    time::{interval, MissedTickBehavior},
+};
+
+use super::json_rpc::{
+    JsonRpcError, JsonRpcRequest, JsonRpcResponse, INVALID_PARAMS, INVALID_REQUEST,
+    JSON_RPC_VERSION, METHOD_NOT_FOUND, PARSE_ERROR, SERVER_ERROR,
+};
+use crate::{
+    client::{
+        cli::{
+            query_response::ResponseStatus,
+            validation::{validate_params, ArgType},
+        },
+        client::BitVMClient,
+        sdk::{query::ClientCliQuery, query_contexts::depositor_signatures::DepositorSignatures},
+    },
+    constants::DestinationNetwork,
+    transactions::base::Input,
+};
+
+const MAX_BODY_SIZE: usize = 1 << 20;
+const CALL_QUEUE_SIZE: usize = 64;
+
+type RpcCall = (String, oneshot::Sender<Option<Value>>);
+
+/// JSON-RPC 2.0 server over HTTP exposing the `ClientCliQuery` methods of a long-running client.
+///
+/// The client is owned by the task running `serve`. Connections are accepted and parsed on
+/// separate tasks and forward their request bodies to it, so calls are executed one at a time
+/// and always see the state of the latest sync.
+pub struct RpcServer {
+    client: BitVMClient,
+    destination_network: DestinationNetwork,
+    sync_interval: Duration,
+}
+
+impl RpcServer {
+    pub fn new(
+        client: BitVMClient,
+        destination_network: DestinationNetwork,
+        sync_interval: Duration,
+    ) -> Self {
+        Self {
+            client,
+            destination_network,
+            sync_interval,
+        }
+    }
+
+    /// Serves requests from `listener` and syncs the client every `sync_interval`, starting
+    /// right away. Never returns.
+    pub async fn serve(mut self, listener: TcpListener) {
+        let (calls_sender, mut calls) = mpsc::channel::<RpcCall>(CALL_QUEUE_SIZE);
+        tokio::spawn(accept_connections(listener, calls_sender));
+
+        let mut sync_timer = interval(self.sync_interval);
+        sync_timer.set_missed_tick_behavior(MissedTickBehavior::Delay);
+        loop {
+            tokio::select! {
+                Some((body, reply)) = calls.recv() => {
+                    // The connection may have been closed in the meantime.
+                    let _ = reply.send(self.handle_body(&body).await);
+                }
+                _ = sync_timer.tick() => self.sync().await,
+            }
+        }
+    }
+
+    async fn sync(&mut self) {
+        self.client.sync().await;
+        self.client.sync_l2().await;
+    }
+
+    /// Handles a raw HTTP request body holding a single request or a batch. Returns `None` if
+    /// there is nothing to respond with, i.e. the body only contained notifications.
+    pub async fn handle_body(&mut self, body: &str) -> Option<Value> {
+        match serde_json::from_str::<Value>(body) {
+            Ok(Value::Array(requests)) if !requests.is_empty() => {
+                let mut responses = vec![];
+                for request in requests {
+                    if let Some(response) = self.handle_request(request).await {
+                        responses.push(response);
+                    }
+                }
+                if responses.is_empty() {
+                    None
+                } else {
+                    Some(Value::Array(responses))
+                }
+            }
+            Ok(Value::Array(_)) => Some(error_response(
+                Value::Null,
+                JsonRpcError::new(INVALID_REQUEST, "Empty batch"),
+            )),
+            Ok(request) => self.handle_request(request).await,
+            Err(err) => Some(error_response(
+                Value::Null,
+                JsonRpcError::new(PARSE_ERROR, err.to_string()),
+            )),
+        }
+    }
+
+    pub async fn handle_request(&mut self, request: Value) -> Option<Value> {
+        let request = match serde_json::from_value::<JsonRpcRequest>(request) {
+            Ok(request) if request.jsonrpc == JSON_RPC_VERSION => request,
+            Ok(request) => {
+                return Some(error_response(
+                    request.id.unwrap_or(Value::Null),
+                    JsonRpcError::new(
+                        INVALID_REQUEST,
+                        format!("Unsupported JSON-RPC version: {}", request.jsonrpc),
+                    ),
+                ))
+            }
+            Err(err) => {
+                return Some(error_response(
+                    Value::Null,
+                    JsonRpcError::new(INVALID_REQUEST, err.to_string()),
+                ))
+            }
+        };
+
+        let params = match request.params {
+            Some(Value::Object(params)) => params,
+            None | Some(Value::Null) => Map::new(),
+            Some(_) => {
+                return request.id.map(|id| {
+                    error_response(
+                        id,
+                        JsonRpcError::new(INVALID_PARAMS, "Use named parameters."),
+                    )
+                })
+            }
+        };
+
+        let result = self.call(&request.method, &params).await;
+        request
+            .id
+            .map(|id| serde_json::to_value(JsonRpcResponse::new(id, result)).unwrap())
+    }
+
+    async fn call(
+        &mut self,
+        method: &str,
+        params: &Map<String, Value>,
+    ) -> Result<Value, JsonRpcError> {
+        match method {
+            "get_unused_peg_in_graphs" => Ok(json!(self.client.get_unused_peg_in_graphs().await)),
+            "get_depositor_status" => {
+                let args = self.validate(params, &["DEPOSITOR_PUBLIC_KEY"])?;
+                let [ArgType::DepositorPublicKey(pubkey)] = &args[..] else {
+                    unreachable!()
+                };
+
+                Ok(json!(self.client.get_depositor_status(pubkey).await))
+            }
+            "get_withdrawer_status" => {
+                let args = self.validate(params, &["WITHDRAWER_CHAIN_ADDRESS"])?;
+                let [ArgType::ChainAddress(chain_address)] = &args[..] else {
+                    unreachable!()
+                };
+
+                Ok(json!(
+                    self.client
+                        .get_withdrawer_status(chain_address.to_string().as_str())
+                        .await
+                ))
+            }
+            "get_depositor_transactions" => {
+                let args = self.validate(
+                    params,
+                    &[
+                        "DEPOSITOR_PUBLIC_KEY",
+                        "DESTINATION_CHAIN_ADDRESS",
+                        "OUTPOINT",
+                        "SATS",
+                    ],
+                )?;
+                let (pubkey, chain_address, outpoint, satoshis) = match &args[..] {
+                    [arg1, arg2, arg3, arg4] => match (arg1, arg2, arg3, arg4) {
+                        (
+                            ArgType::DepositorPublicKey(pubkey),
+                            ArgType::ChainAddress(chain_address),
+                            ArgType::OutPoint(outpoint),
+                            ArgType::Satoshis(satoshis),
+                        ) => (pubkey, chain_address, outpoint, satoshis),
+                        _ => unreachable!(),
+                    },
+                    _ => unreachable!(),
+                };
+
+                self.client
+                    .get_depositor_transactions(
+                        pubkey,
+                        &XOnlyPublicKey::from(*pubkey),
+                        Input {
+                            outpoint: *outpoint,
+                            amount: *satoshis,
+                        },
+                        chain_address.to_string().as_str(),
+                    )
+                    .await
+                    .map_err(|err| JsonRpcError::new(SERVER_ERROR, err))
+            }
+            "create_peg_in_graph_with_depositor_signatures" => {
+                let args = self.validate(
+                    params,
+                    &[
+                        "DEPOSITOR_PUBLIC_KEY",
+                        "DESTINATION_CHAIN_ADDRESS",
+                        "OUTPOINT",
+                        "SATS",
+                        "DEPOSIT",
+                        "CONFIRM",
+                        "REFUND",
+                    ],
+                )?;
+                let (pubkey, chain_address, outpoint, satoshis, deposit, confirm, refund) =
+                    match &args[..] {
+                        [arg1, arg2, arg3, arg4, arg5, arg6, arg7] => {
+                            match (arg1, arg2, arg3, arg4, arg5, arg6, arg7) {
+                                (
+                                    ArgType::DepositorPublicKey(pubkey),
+                                    ArgType::ChainAddress(chain_address),
+                                    ArgType::OutPoint(outpoint),
+                                    ArgType::Satoshis(satoshis),
+                                    ArgType::EcdsaSignature(deposit),
+                                    ArgType::TaprootSignature(confirm),
+                                    ArgType::TaprootSignature(refund),
+                                ) => (
+                                    pubkey,
+                                    chain_address,
+                                    outpoint,
+                                    satoshis,
+                                    deposit,
+                                    confirm,
+                                    refund,
+                                ),
+                                _ => unreachable!(),
+                            }
+                        }
+                        _ => unreachable!(),
+                    };
+
+                // Make sure the graph is not created twice by racing peers.
+                self.sync().await;
+                let result = self
+                    .client
+                    .create_peg_in_graph_with_depositor_signatures(
+                        pubkey,
+                        &XOnlyPublicKey::from(*pubkey),
+                        Input {
+                            outpoint: *outpoint,
+                            amount: *satoshis,
+                        },
+                        chain_address.to_string().as_str(),
+                        &DepositorSignatures {
+                            deposit: *deposit,
+                            refund: *refund,
+                            confirm: *confirm,
+                        },
+                    )
+                    .await
+                    .map_err(|err| JsonRpcError::new(SERVER_ERROR, err))?;
+                self.client.flush().await;
+
+                Ok(result)
+            }
+            "broadcast" => {
+                let args = self.validate(params, &["GRAPH_ID"])?;
+                let [ArgType::GraphId(peg_in_graph_id)] = &args[..] else {
+                    unreachable!()
+                };
+
+                self.sync().await;
+                self.client
+                    .retry_broadcast_peg_in_deposit(peg_in_graph_id)
+                    .await
+                    .map_err(|err| JsonRpcError::new(SERVER_ERROR, err))
+            }
+            _ => Err(JsonRpcError::new(
+                METHOD_NOT_FOUND,
+                format!("Method not found: {}", method),
+            )),
+        }
+    }
+
+    fn validate(
+        &self,
+        params: &Map<String, Value>,
+        args: &[&str],
+    ) -> Result<Vec<ArgType>, JsonRpcError> {
+        validate_params(
+            params,
+            args.iter().map(|arg| arg.to_string()).collect(),
+            self.destination_network,
+        )
+        .map_err(|response| match response.status {
+            ResponseStatus::NOK(message) => JsonRpcError::new(INVALID_PARAMS, message),
+            ResponseStatus::OK => unreachable!(),
+        })
+    }
+}
+
+fn error_response(id: Value, error: JsonRpcError) -> Value {
+    serde_json::to_value(JsonRpcResponse::new(id, Err(error))).unwrap()
+}
+
+async fn accept_connections(listener: TcpListener, calls: mpsc::Sender<RpcCall>) {
+    loop {
+        match listener.accept().await {
+            Ok((stream, _)) => {
+                let calls = calls.clone();
+                tokio::spawn(async move {
+                    if let Err(err) = handle_connection(stream, calls).await {
+                        eprintln!("RPC connection error: {}", err);
+                    }
+                });
+            }
+            Err(err) => eprintln!("Failed to accept RPC connection: {}", err),
+        }
+    }
+}
+
+// Minimal HTTP/1.1 handling: one POST request per connection, the JSON-RPC payload is the body.
+async fn handle_connection(stream: TcpStream, calls: mpsc::Sender<RpcCall>) -> io::Result<()> {
+    let mut reader = BufReader::new(stream);
+
+    let mut request_line = String::new();
+    reader.read_line(&mut request_line).await?;
+
+    let mut content_length = None;
+    loop {
+        let mut line = String::new();
+        if reader.read_line(&mut line).await? == 0 {
+            return Ok(());
+        }
+        let line = line.trim_end();
+        if line.is_empty() {
+            break;
+        }
+        if let Some((name, value)) = line.split_once(':') {
+            if name.trim().eq_ignore_ascii_case("content-length") {
+                content_length = value.trim().parse::<usize>().ok();
+            }
+        }
+    }
+
+    if !request_line.starts_with("POST ") {
+        return write_response(reader.get_mut(), "405 Method Not Allowed", "").await;
+    }
+    let content_length = match content_length {
+        Some(content_length) if content_length <= MAX_BODY_SIZE => content_length,
+        Some(_) => return write_response(reader.get_mut(), "413 Payload Too Large", "").await,
+        None => return write_response(reader.get_mut(), "411 Length Required", "").await,
+    };
+
+    let mut body = vec![0; content_length];
+    reader.read_exact(&mut body).await?;
+
+    let (reply_sender, reply) = oneshot::channel();
+    let body = String::from_utf8_lossy(&body).into_owned();
+    if calls.send((body, reply_sender)).await.is_err() {
+        return write_response(reader.get_mut(), "503 Service Unavailable", "").await;
+    }
+
+    match reply.await {
+        Ok(Some(response)) => {
+            write_response(reader.get_mut(), "200 OK", &response.to_string()).await
+        }
+        Ok(None) => write_response(reader.get_mut(), "204 No Content", "").await,
+        Err(_) => write_response(reader.get_mut(), "500 Internal Server Error", "").await,
+    }
+}
+
+async fn write_response(stream: &mut TcpStream, status: &str, body: &str) -> io::Result<()> {
+    let response = format!(
+        "HTTP/1.1 {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        status,
+        body.len(),
+        body
+    );
+    stream.write_all(response.as_bytes()).await?;
+    stream.flush().await
+}
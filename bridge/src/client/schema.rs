@@ -0,0 +1,197 @@
+use bitcoin::Network;
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+use crate::{
+    error::SchemaError, graphs::base::GRAPH_VERSION, parameters::ProtocolParameters,
+    serialization::serialize,
+};
+
+/// Kinds of serialized data shared between the bridge clients. Each one is versioned on its own.
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Debug)]
+#[serde(rename_all = "snake_case")]
+pub enum SchemaId {
+    // `BitVMClientPublicData`, i.e. all the graphs known to a client.
+    ClientData,
+    // `Changeset` of the client data.
+    Changeset,
+    // `BitVMClientPrivateData`, i.e. the secrets of a client, only stored locally.
+    PrivateData,
+}
+
+/// Version of the data written before it was wrapped in a `VersionedData`.
+pub const LEGACY_SCHEMA_VERSION: u32 = 1;
+
+/// Migrates the JSON of some version of a schema to the next version.
+pub type Migration = fn(Value) -> Result<Value, String>;
+
+/// Serialized data along with the schema and version it was written with.
+#[derive(Serialize, Deserialize, Clone, PartialEq, Eq, Debug)]
+pub struct VersionedData {
+    pub schema: SchemaId,
+    pub version: u32,
+    pub data: Value,
+}
+
+// Migrations of `schema`, the one at index `i` turns version `i + 1` into version `i + 2`.
+fn migrations(schema: SchemaId) -> &'static [Migration] {
+    match schema {
+        SchemaId::ClientData => &[add_client_data_protocol_parameters],
+        SchemaId::Changeset => &[add_changeset_protocol_parameters],
+        SchemaId::PrivateData => &[],
+    }
+}
+
+// Version 2 graphs hold the protocol parameters they were created with.
+fn add_client_data_protocol_parameters(mut data: Value) -> Result<Value, String> {
+    for field in ["peg_in_graphs", "peg_out_graphs"] {
+        if let Some(graphs) = data.get_mut(field).and_then(Value::as_array_mut) {
+            graphs.iter_mut().try_for_each(add_protocol_parameters)?;
+        }
+    }
+    Ok(data)
+}
+
+fn add_changeset_protocol_parameters(mut data: Value) -> Result<Value, String> {
+    if let Some(changes) = data.get_mut("changes").and_then(Value::as_array_mut) {
+        changes
+            .iter_mut()
+            .filter(|change| is_graph_added(change))
+            .try_for_each(add_protocol_parameters)?;
+    }
+    Ok(data)
+}
+
+// Graphs written before the protocol parameters were configurable were created with the
+// default parameters of their network, which their ID doesn't commit to. Graphs written since,
+// still under version 1, already hold theirs.
+fn add_protocol_parameters(graph: &mut Value) -> Result<(), String> {
+    let graph = graph.as_object_mut().ok_or("Graph is not an object")?;
+    if graph.contains_key("protocol_parameters") {
+        return Ok(());
+    }
+    let network = graph
+        .get("network")
+        .cloned()
+        .ok_or("Graph without network")?;
+    let network: Network = serde_json::from_value(network).map_err(|err| err.to_string())?;
+
+    let protocol_parameters = serde_json::to_value(ProtocolParameters::for_network(network))
+        .map_err(|err| err.to_string())?;
+    graph.insert(String::from("protocol_parameters"), protocol_parameters);
+    graph.insert(String::from("legacy_id"), Value::Bool(true));
+    Ok(())
+}
+
+fn is_graph_added(change: &Value) -> bool {
+    matches!(
+        change.get("type").and_then(Value::as_str),
+        Some("peg_in_graph_added" | "peg_out_graph_added")
+    )
+}
+
+/// Version of `schema` written by this client.
+pub fn current_version(schema: SchemaId) -> u32 {
+    LEGACY_SCHEMA_VERSION + migrations(schema).len() as u32
+}
+
+/// Serializes `data` as the current version of `schema`.
+pub fn serialize_versioned(schema: SchemaId, data: &impl Serialize) -> String {
+    serialize(&VersionedData {
+        schema,
+        version: current_version(schema),
+        data: serde_json::to_value(data).expect("Failed to serialize an object to JSON"),
+    })
+}
+
+/// Deserializes `content` written with any known version of `schema`, migrating it to the
+/// current version first. Content that is not wrapped in a `VersionedData` is treated as
+/// `LEGACY_SCHEMA_VERSION`. The graphs it holds must be of the current `GRAPH_VERSION`, older
+/// ones are expected to be migrated along with the schema.
+pub fn deserialize_versioned<T: DeserializeOwned>(
+    schema: SchemaId,
+    content: &[u8],
+) -> Result<T, SchemaError> {
+    let value: Value =
+        serde_json::from_slice(content).map_err(|err| SchemaError::Invalid(err.to_string()))?;
+    let versioned_data = if value.as_object().is_some_and(is_versioned) {
+        serde_json::from_value::<VersionedData>(value)
+            .map_err(|err| SchemaError::Invalid(err.to_string()))?
+    } else {
+        VersionedData {
+            schema,
+            version: LEGACY_SCHEMA_VERSION,
+            data: value,
+        }
+    };
+    if versioned_data.schema != schema {
+        return Err(SchemaError::UnexpectedSchema {
+            expected: schema,
+            found: versioned_data.schema,
+        });
+    }
+
+    let data = migrate(schema, versioned_data.version, versioned_data.data)?;
+    check_graph_versions(schema, &data)?;
+    serde_json::from_value(data).map_err(|err| SchemaError::Invalid(err.to_string()))
+}
+
+fn is_versioned(fields: &Map<String, Value>) -> bool {
+    fields.len() == 3
+        && fields.contains_key("schema")
+        && fields.contains_key("version")
+        && fields.contains_key("data")
+}
+
+/// Migrates `data` of `schema` from `version` to the current version.
+pub fn migrate(schema: SchemaId, version: u32, data: Value) -> Result<Value, SchemaError> {
+    if version < LEGACY_SCHEMA_VERSION || version > current_version(schema) {
+        return Err(SchemaError::UnsupportedVersion { schema, version });
+    }
+
+    let pending_migrations = &migrations(schema)[(version - LEGACY_SCHEMA_VERSION) as usize..];
+    pending_migrations
+        .iter()
+        .zip(version..)
+        .try_fold(data, |data, (migration, version)| {
+            migration(data).map_err(|reason| SchemaError::Migration {
+                schema,
+                version,
+                reason,
+            })
+        })
+}
+
+fn check_graph_versions(schema: SchemaId, data: &Value) -> Result<(), SchemaError> {
+    let graphs: Vec<&Value> = match schema {
+        SchemaId::ClientData => ["peg_in_graphs", "peg_out_graphs"]
+            .iter()
+            .filter_map(|field| data.get(field).and_then(Value::as_array))
+            .flatten()
+            .collect(),
+        SchemaId::Changeset => data
+            .get("changes")
+            .and_then(Value::as_array)
+            .into_iter()
+            .flatten()
+            .filter(|change| is_graph_added(change))
+            .collect(),
+        SchemaId::PrivateData => vec![],
+    };
+
+    for graph in graphs {
+        let version = graph.get("version").and_then(Value::as_str);
+        if version != Some(GRAPH_VERSION) {
+            return Err(SchemaError::UnsupportedGraphVersion {
+                graph_id: graph
+                    .get("id")
+                    .and_then(Value::as_str)
+                    .unwrap_or_default()
+                    .to_string(),
+                version: version.unwrap_or_default().to_string(),
+            });
+        }
+    }
+
+    Ok(())
+}
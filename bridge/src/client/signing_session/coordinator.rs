@@ -0,0 +1,272 @@
+use std::{
+    collections::{BTreeSet, HashMap},
+    time::{Duration, Instant},
+};
+
+use bitcoin::{PublicKey, Txid};
+
+use super::messages::{session_inputs, InputNonce, InputPartialSignature, SessionMessage};
+use crate::graphs::base::{BaseGraph, GraphId};
+
+pub const DEFAULT_ROUND_TIMEOUT: Duration = Duration::from_secs(5 * 60);
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum SessionRound {
+    Nonces,
+    Signatures,
+    Complete,
+    Aborted,
+}
+
+/// Message to deliver to a verifier.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct Outgoing {
+    pub to: PublicKey,
+    pub message: SessionMessage,
+}
+
+struct SigningSession {
+    verifiers: Vec<PublicKey>,
+    inputs: BTreeSet<(Txid, usize)>,
+    round: SessionRound,
+    deadline: Instant,
+    nonces: HashMap<PublicKey, Vec<InputNonce>>,
+    signatures: HashMap<PublicKey, Vec<InputPartialSignature>>,
+}
+
+impl SigningSession {
+    fn missing_verifiers(&self) -> Vec<PublicKey> {
+        self.verifiers
+            .iter()
+            .filter(|verifier| match self.round {
+                SessionRound::Nonces => !self.nonces.contains_key(verifier),
+                SessionRound::Signatures => !self.signatures.contains_key(verifier),
+                SessionRound::Complete | SessionRound::Aborted => false,
+            })
+            .cloned()
+            .collect()
+    }
+
+    fn all_nonces(&self) -> Vec<InputNonce> {
+        self.verifiers
+            .iter()
+            .flat_map(|verifier| self.nonces[verifier].clone())
+            .collect()
+    }
+
+    fn all_signatures(&self) -> Vec<InputPartialSignature> {
+        self.verifiers
+            .iter()
+            .flat_map(|verifier| self.signatures[verifier].clone())
+            .collect()
+    }
+
+    // Whether `entries` cover each session input exactly once.
+    fn covers_inputs(&self, entries: impl Iterator<Item = (Txid, usize)>) -> bool {
+        let entries: Vec<(Txid, usize)> = entries.collect();
+        let unique: BTreeSet<(Txid, usize)> = entries.iter().cloned().collect();
+        entries.len() == unique.len() && unique == self.inputs
+    }
+}
+
+/// Coordinates the MuSig2 signing sessions of the verifiers, one per graph.
+///
+/// A session has a nonce round and a partial signature round. Each round ends when every
+/// verifier has submitted its share, which is then sent to all of them, or when the round
+/// timeout expires, which aborts the session. The coordinator only relays nonces and partial
+/// signatures, it holds no keys. Messages are only accepted when signed by the verifier they
+/// claim to come from. It does not do any I/O either, see `transport` for running it.
+pub struct SigningCoordinator {
+    sessions: HashMap<GraphId, SigningSession>,
+    round_timeout: Duration,
+}
+
+impl SigningCoordinator {
+    pub fn new(round_timeout: Duration) -> Self {
+        Self {
+            sessions: HashMap::new(),
+            round_timeout,
+        }
+    }
+
+    /// Opens a session for the n-of-n inputs of `graph`, replacing any previous session of
+    /// the graph.
+    pub fn open_session(&mut self, graph: &dyn BaseGraph, verifiers: &[PublicKey], now: Instant) {
+        self.open_session_for_inputs(graph.id().clone(), verifiers, session_inputs(graph), now);
+    }
+
+    pub fn open_session_for_inputs(
+        &mut self,
+        graph_id: GraphId,
+        verifiers: &[PublicKey],
+        inputs: BTreeSet<(Txid, usize)>,
+        now: Instant,
+    ) {
+        self.sessions.insert(
+            graph_id,
+            SigningSession {
+                verifiers: verifiers.to_vec(),
+                inputs,
+                round: SessionRound::Nonces,
+                deadline: now + self.round_timeout,
+                nonces: HashMap::new(),
+                signatures: HashMap::new(),
+            },
+        );
+    }
+
+    pub fn round(&self, graph_id: &GraphId) -> Option<SessionRound> {
+        self.sessions.get(graph_id).map(|session| session.round)
+    }
+
+    /// Whether all the sessions are complete or aborted.
+    pub fn is_finished(&self) -> bool {
+        self.sessions.values().all(|session| {
+            session.round == SessionRound::Complete || session.round == SessionRound::Aborted
+        })
+    }
+
+    /// Handles a message from a verifier and returns the messages to send in response.
+    pub fn handle_message(&mut self, message: SessionMessage, now: Instant) -> Vec<Outgoing> {
+        let graph_id = message.graph_id().clone();
+        let Some(&sender) = message.sender() else {
+            return vec![];
+        };
+        let reject = |reason: String| {
+            vec![Outgoing {
+                to: sender,
+                message: SessionMessage::Rejected {
+                    graph_id: graph_id.clone(),
+                    reason,
+                },
+            }]
+        };
+
+        let round_timeout = self.round_timeout;
+        let Some(session) = self.sessions.get_mut(&graph_id) else {
+            return reject(format!("No signing session for graph {graph_id}"));
+        };
+        if !session.verifiers.contains(&sender) {
+            return reject(format!("{sender} is not a verifier of the session"));
+        }
+        if !message.verify_sender_signature() {
+            return reject(format!("Message not signed by {sender}"));
+        }
+
+        match (message, session.round) {
+            (SessionMessage::Join { .. }, SessionRound::Nonces) => vec![],
+            // Catch up a verifier that reconnects after the round it missed.
+            (SessionMessage::Join { .. }, SessionRound::Signatures) => vec![Outgoing {
+                to: sender,
+                message: SessionMessage::NonceRound {
+                    graph_id,
+                    nonces: session.all_nonces(),
+                },
+            }],
+            (SessionMessage::Join { .. }, SessionRound::Complete) => vec![Outgoing {
+                to: sender,
+                message: SessionMessage::Completed {
+                    graph_id,
+                    signatures: session.all_signatures(),
+                },
+            }],
+            (SessionMessage::Nonces { nonces, .. }, SessionRound::Nonces) => {
+                if !session
+                    .covers_inputs(nonces.iter().map(|nonce| (nonce.txid, nonce.input_index)))
+                {
+                    return reject("Nonces do not match the session inputs".to_string());
+                }
+
+                session.nonces.insert(sender, nonces);
+                if !session.missing_verifiers().is_empty() {
+                    return vec![];
+                }
+
+                session.round = SessionRound::Signatures;
+                session.deadline = now + round_timeout;
+                let nonces = session.all_nonces();
+                broadcast(&session.verifiers, || SessionMessage::NonceRound {
+                    graph_id: graph_id.clone(),
+                    nonces: nonces.clone(),
+                })
+            }
+            (SessionMessage::PartialSignatures { signatures, .. }, SessionRound::Signatures) => {
+                if !session.covers_inputs(
+                    signatures
+                        .iter()
+                        .map(|signature| (signature.txid, signature.input_index)),
+                ) || signatures
+                    .iter()
+                    .any(|signature| signature.verifier != sender)
+                {
+                    return reject(
+                        "Partial signatures do not match the session inputs".to_string(),
+                    );
+                }
+
+                session.signatures.insert(sender, signatures);
+                if !session.missing_verifiers().is_empty() {
+                    return vec![];
+                }
+
+                session.round = SessionRound::Complete;
+                let signatures = session.all_signatures();
+                broadcast(&session.verifiers, || SessionMessage::Completed {
+                    graph_id: graph_id.clone(),
+                    signatures: signatures.clone(),
+                })
+            }
+            // Resubmissions of a verifier that reconnected.
+            (
+                SessionMessage::Nonces { nonces, .. },
+                SessionRound::Signatures | SessionRound::Complete,
+            ) if session.nonces.get(&sender) == Some(&nonces) => vec![],
+            (SessionMessage::PartialSignatures { signatures, .. }, SessionRound::Complete)
+                if session.signatures.get(&sender) == Some(&signatures) =>
+            {
+                vec![]
+            }
+            (_, round) => reject(format!("Unexpected message in round {round:?}")),
+        }
+    }
+
+    /// Aborts the sessions whose round deadline has passed.
+    pub fn check_timeouts(&mut self, now: Instant) -> Vec<Outgoing> {
+        let mut outgoing = vec![];
+        for (graph_id, session) in self.sessions.iter_mut() {
+            let in_progress =
+                session.round == SessionRound::Nonces || session.round == SessionRound::Signatures;
+            if !in_progress || session.deadline > now {
+                continue;
+            }
+
+            let reason = format!(
+                "Timed out in round {:?} waiting for {}",
+                session.round,
+                session
+                    .missing_verifiers()
+                    .iter()
+                    .map(|verifier| verifier.to_string())
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            );
+            session.round = SessionRound::Aborted;
+            outgoing.extend(broadcast(&session.verifiers, || SessionMessage::Aborted {
+                graph_id: graph_id.clone(),
+                reason: reason.clone(),
+            }));
+        }
+
+        outgoing
+    }
+}
+
+fn broadcast(verifiers: &[PublicKey], message: impl Fn() -> SessionMessage) -> Vec<Outgoing> {
+    verifiers
+        .iter()
+        .map(|verifier| Outgoing {
+            to: *verifier,
+            message: message(),
+        })
+        .collect()
+}
@@ -0,0 +1,313 @@
+use std::collections::BTreeSet;
+
+use bitcoin::{
+    hashes::{sha256, Hash},
+    key::Keypair,
+    PublicKey, Txid, XOnlyPublicKey,
+};
+use musig2::{
+    secp256k1::{schnorr::Signature, Message},
+    PartialSignature, PubNonce,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    error::Musig2Error,
+    graphs::base::{BaseGraph, GraphId},
+    transactions::{
+        pre_signed::PreSignedTransaction,
+        pre_signed_musig2::{
+            verify_musig2_partial_signatures, verify_public_nonce, PreSignedMusig2Transaction,
+        },
+    },
+};
+
+/// Public nonce of one verifier for one n-of-n input, with the verifier's signature over it.
+#[derive(Serialize, Deserialize, Clone, PartialEq, Eq, Debug)]
+pub struct InputNonce {
+    pub txid: Txid,
+    pub input_index: usize,
+    pub verifier: PublicKey,
+    pub nonce: PubNonce,
+    pub signature: Signature,
+}
+
+/// Partial signature of one verifier for one n-of-n input.
+#[derive(Serialize, Deserialize, Clone, PartialEq, Eq, Debug)]
+pub struct InputPartialSignature {
+    pub txid: Txid,
+    pub input_index: usize,
+    pub verifier: PublicKey,
+    pub signature: PartialSignature,
+}
+
+/// Messages of the signing session protocol. Verifiers send `Join`, `Nonces` and
+/// `PartialSignatures`, the coordinator answers with the other variants. Verifier messages are
+/// signed with the verifier's key, `Nonces` through the signature of each nonce, see
+/// `verify_sender_signature`.
+#[derive(Serialize, Deserialize, Clone, PartialEq, Eq, Debug)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum SessionMessage {
+    Join {
+        graph_id: GraphId,
+        verifier: PublicKey,
+        sender_signature: Signature,
+    },
+    Nonces {
+        graph_id: GraphId,
+        verifier: PublicKey,
+        nonces: Vec<InputNonce>,
+    },
+    PartialSignatures {
+        graph_id: GraphId,
+        verifier: PublicKey,
+        signatures: Vec<InputPartialSignature>,
+        sender_signature: Signature,
+    },
+    // The nonces of all the verifiers, ending the nonce round.
+    NonceRound {
+        graph_id: GraphId,
+        nonces: Vec<InputNonce>,
+    },
+    // The partial signatures of all the verifiers, ending the session.
+    Completed {
+        graph_id: GraphId,
+        signatures: Vec<InputPartialSignature>,
+    },
+    // Sent to a single verifier whose message was not accepted.
+    Rejected {
+        graph_id: GraphId,
+        reason: String,
+    },
+    Aborted {
+        graph_id: GraphId,
+        reason: String,
+    },
+}
+
+impl SessionMessage {
+    pub fn join(graph_id: &GraphId, verifier_keypair: &Keypair) -> Self {
+        SessionMessage::Join {
+            graph_id: graph_id.clone(),
+            verifier: verifier_keypair.public_key().into(),
+            sender_signature: verifier_keypair.sign_schnorr(get_join_message(graph_id)),
+        }
+    }
+
+    pub fn partial_signatures(
+        graph_id: &GraphId,
+        signatures: Vec<InputPartialSignature>,
+        verifier_keypair: &Keypair,
+    ) -> Self {
+        SessionMessage::PartialSignatures {
+            graph_id: graph_id.clone(),
+            verifier: verifier_keypair.public_key().into(),
+            sender_signature: verifier_keypair
+                .sign_schnorr(get_partial_signatures_message(graph_id, &signatures)),
+            signatures,
+        }
+    }
+
+    pub fn graph_id(&self) -> &GraphId {
+        match self {
+            SessionMessage::Join { graph_id, .. }
+            | SessionMessage::Nonces { graph_id, .. }
+            | SessionMessage::PartialSignatures { graph_id, .. }
+            | SessionMessage::NonceRound { graph_id, .. }
+            | SessionMessage::Completed { graph_id, .. }
+            | SessionMessage::Rejected { graph_id, .. }
+            | SessionMessage::Aborted { graph_id, .. } => graph_id,
+        }
+    }
+
+    /// Verifier that sent the message, `None` for coordinator messages.
+    pub fn sender(&self) -> Option<&PublicKey> {
+        match self {
+            SessionMessage::Join { verifier, .. }
+            | SessionMessage::Nonces { verifier, .. }
+            | SessionMessage::PartialSignatures { verifier, .. } => Some(verifier),
+            _ => None,
+        }
+    }
+
+    /// Whether the message was signed by its sender. Always false for coordinator messages.
+    pub fn verify_sender_signature(&self) -> bool {
+        match self {
+            SessionMessage::Join {
+                graph_id,
+                verifier,
+                sender_signature,
+            } => sender_signature
+                .verify(
+                    &get_join_message(graph_id),
+                    &XOnlyPublicKey::from(*verifier),
+                )
+                .is_ok(),
+            SessionMessage::Nonces {
+                verifier, nonces, ..
+            } => nonces.iter().all(|nonce| {
+                nonce.verifier == *verifier
+                    && verify_public_nonce(
+                        &nonce.signature,
+                        &nonce.nonce,
+                        &XOnlyPublicKey::from(*verifier),
+                    )
+            }),
+            SessionMessage::PartialSignatures {
+                graph_id,
+                verifier,
+                signatures,
+                sender_signature,
+            } => sender_signature
+                .verify(
+                    &get_partial_signatures_message(graph_id, signatures),
+                    &XOnlyPublicKey::from(*verifier),
+                )
+                .is_ok(),
+            _ => false,
+        }
+    }
+}
+
+fn get_join_message(graph_id: &GraphId) -> Message {
+    get_session_message(b"join", graph_id.as_bytes())
+}
+
+fn get_partial_signatures_message(
+    graph_id: &GraphId,
+    signatures: &[InputPartialSignature],
+) -> Message {
+    let payload = serde_json::to_vec(&(graph_id, signatures))
+        .expect("Failed to serialize partial signatures");
+    get_session_message(b"partial_signatures", &payload)
+}
+
+// Tags the message with its type, so that the signature of one type cannot be passed off as
+// another's.
+fn get_session_message(tag: &[u8], payload: &[u8]) -> Message {
+    let hash = sha256::Hash::hash(&[tag, b":".as_slice(), payload].concat());
+    Message::from_digest(hash.to_byte_array())
+}
+
+/// The n-of-n inputs of `graph` that every verifier must provide a nonce and a partial
+/// signature for.
+pub fn session_inputs(graph: &dyn BaseGraph) -> BTreeSet<(Txid, usize)> {
+    graph
+        .musig2_transactions()
+        .into_iter()
+        .flat_map(|tx| {
+            let txid = tx.tx().compute_txid();
+            tx.verifier_inputs()
+                .into_iter()
+                .map(move |input_index| (txid, input_index))
+        })
+        .collect()
+}
+
+/// Nonces `verifier` pushed to `graph`.
+pub fn collect_nonces(graph: &dyn BaseGraph, verifier: &PublicKey) -> Vec<InputNonce> {
+    let mut nonces = vec![];
+    for tx in graph.musig2_transactions() {
+        let txid = tx.tx().compute_txid();
+        for input_index in tx.verifier_inputs() {
+            let nonce = tx
+                .musig2_nonces()
+                .get(&input_index)
+                .and_then(|nonces| nonces.get(verifier));
+            let signature = tx
+                .musig2_nonce_signatures()
+                .get(&input_index)
+                .and_then(|signatures| signatures.get(verifier));
+            if let (Some(nonce), Some(signature)) = (nonce, signature) {
+                nonces.push(InputNonce {
+                    txid,
+                    input_index,
+                    verifier: *verifier,
+                    nonce: nonce.clone(),
+                    signature: *signature,
+                });
+            }
+        }
+    }
+
+    nonces
+}
+
+/// Partial signatures `verifier` pushed to `graph`.
+pub fn collect_partial_signatures(
+    graph: &dyn BaseGraph,
+    verifier: &PublicKey,
+) -> Vec<InputPartialSignature> {
+    let mut signatures = vec![];
+    for tx in graph.musig2_transactions() {
+        let txid = tx.tx().compute_txid();
+        for input_index in tx.verifier_inputs() {
+            if let Some(signature) = tx
+                .musig2_signatures()
+                .get(&input_index)
+                .and_then(|signatures| signatures.get(verifier))
+            {
+                signatures.push(InputPartialSignature {
+                    txid,
+                    input_index,
+                    verifier: *verifier,
+                    signature: *signature,
+                });
+            }
+        }
+    }
+
+    signatures
+}
+
+/// Adds `nonces` to the matching inputs of `graph`. Nonces for unknown inputs are ignored.
+pub fn apply_nonces(graph: &mut dyn BaseGraph, nonces: &[InputNonce]) {
+    for tx in graph.musig2_transactions_mut() {
+        let txid = tx.tx().compute_txid();
+        for nonce in nonces.iter().filter(|nonce| nonce.txid == txid) {
+            if !tx.verifier_inputs().contains(&nonce.input_index) {
+                continue;
+            }
+            tx.musig2_nonces_mut()
+                .entry(nonce.input_index)
+                .or_default()
+                .insert(nonce.verifier, nonce.nonce.clone());
+            tx.musig2_nonce_signatures_mut()
+                .entry(nonce.input_index)
+                .or_default()
+                .insert(nonce.verifier, nonce.signature);
+        }
+    }
+}
+
+/// Adds `signatures` to the matching inputs of `graph`. Signatures for unknown inputs are
+/// ignored.
+pub fn apply_partial_signatures(graph: &mut dyn BaseGraph, signatures: &[InputPartialSignature]) {
+    for tx in graph.musig2_transactions_mut() {
+        let txid = tx.tx().compute_txid();
+        for signature in signatures.iter().filter(|signature| signature.txid == txid) {
+            if !tx.verifier_inputs().contains(&signature.input_index) {
+                continue;
+            }
+            tx.musig2_signatures_mut()
+                .entry(signature.input_index)
+                .or_default()
+                .insert(signature.verifier, signature.signature);
+        }
+    }
+}
+
+/// Verifies that every verifier in `n_of_n_public_keys` contributed a valid partial signature
+/// to each n-of-n input of `graph`.
+pub fn verify_partial_signatures(
+    graph: &dyn BaseGraph,
+    n_of_n_public_keys: &[PublicKey],
+) -> Result<(), Musig2Error> {
+    for tx in graph.musig2_transactions() {
+        for input_index in tx.verifier_inputs() {
+            verify_musig2_partial_signatures(tx, n_of_n_public_keys, input_index)?;
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,4 @@
+pub mod coordinator;
+pub mod messages;
+pub mod peer;
+pub mod transport;
@@ -0,0 +1,106 @@
+use std::time::Duration;
+
+use bitcoin::PublicKey;
+use tokio::{sync::mpsc, time::timeout};
+
+use super::messages::{
+    apply_nonces, apply_partial_signatures, collect_nonces, collect_partial_signatures,
+    session_inputs, verify_partial_signatures, SessionMessage,
+};
+use crate::{
+    client::client::BitVMClient,
+    error::{ClientError, Error, SigningSessionError},
+    graphs::base::GraphId,
+};
+
+/// Takes part in the signing session of `graph_id` as the client's verifier, sending to and
+/// receiving from the coordinator over `sender` and `receiver`. Returns once all the n-of-n
+/// inputs of the graph are signed. The graph is updated in memory only, flush the client to
+/// share it. Fails with the verifiers to blame if the partial signatures of the others do not
+/// verify.
+pub async fn run_verifier_session(
+    client: &mut BitVMClient,
+    graph_id: &GraphId,
+    sender: &mpsc::Sender<SessionMessage>,
+    receiver: &mut mpsc::Receiver<SessionMessage>,
+    round_timeout: Duration,
+) -> Result<(), Error> {
+    let verifier_keypair = client
+        .verifier_keypair()
+        .ok_or(Error::SigningSession(SigningSessionError::NotAVerifier))?;
+    let verifier: PublicKey = verifier_keypair.public_key().into();
+    let graph = client
+        .data()
+        .graph(graph_id)
+        .ok_or(Error::Client(ClientError::GraphNotFound(graph_id.clone())))?;
+
+    // Keep the nonces of an earlier attempt, the other verifiers may already have them.
+    let has_nonces = collect_nonces(graph, &verifier).len() == session_inputs(graph).len();
+    if !has_nonces || !client.has_secret_nonces(graph_id) {
+        client.push_verifier_nonces(graph_id);
+    }
+    let nonces = collect_nonces(client.data_mut().graph_mut(graph_id), &verifier);
+
+    send(sender, SessionMessage::join(graph_id, &verifier_keypair)).await?;
+    send(
+        sender,
+        SessionMessage::Nonces {
+            graph_id: graph_id.clone(),
+            verifier,
+            nonces,
+        },
+    )
+    .await?;
+
+    loop {
+        let message = timeout(round_timeout, receiver.recv())
+            .await
+            .map_err(|_| Error::SigningSession(SigningSessionError::TimedOut))?
+            .ok_or(Error::SigningSession(SigningSessionError::Disconnected))?;
+        if message.graph_id() != graph_id {
+            continue;
+        }
+
+        match message {
+            SessionMessage::NonceRound { nonces, .. } => {
+                apply_nonces(client.data_mut().graph_mut(graph_id), &nonces);
+                client.push_verifier_signature(graph_id)?;
+                let signatures =
+                    collect_partial_signatures(client.data_mut().graph_mut(graph_id), &verifier);
+                send(
+                    sender,
+                    SessionMessage::partial_signatures(graph_id, signatures, &verifier_keypair),
+                )
+                .await?;
+            }
+            SessionMessage::Completed { signatures, .. } => {
+                apply_partial_signatures(client.data_mut().graph_mut(graph_id), &signatures);
+                let n_of_n_public_keys = client.n_of_n_public_keys().to_vec();
+                verify_partial_signatures(
+                    client.data_mut().graph_mut(graph_id),
+                    &n_of_n_public_keys,
+                )
+                .map_err(Error::Musig2)?;
+                // Signing again with the same nonces reproduces this verifier's partial
+                // signatures. Now that all the others are present, the inputs get finalized.
+                client.push_verifier_signature(graph_id)?;
+                return Ok(());
+            }
+            SessionMessage::Rejected { reason, .. } => {
+                return Err(Error::SigningSession(SigningSessionError::Rejected(reason)))
+            }
+            SessionMessage::Aborted { reason, .. } => {
+                return Err(Error::SigningSession(SigningSessionError::Aborted(reason)))
+            }
+            // Verifier messages are never sent by the coordinator.
+            _ => {}
+        }
+    }
+}
+
+async fn send(sender: &mpsc::Sender<SessionMessage>, message: SessionMessage) -> Result<(), Error> {
+    sender
+        .send(message)
+        .await
+        .map_err(|_| Error::SigningSession(SigningSessionError::Disconnected))
+}
@@ -0,0 +1,210 @@
+use std::{
+    collections::HashMap,
+    io,
+    time::{Duration, Instant},
+};
+
+use bitcoin::PublicKey;
+use tokio::{
+    io::{AsyncBufReadExt, AsyncWrite, AsyncWriteExt, BufReader},
+    net::{TcpListener, TcpStream, ToSocketAddrs},
+    sync::mpsc,
+    task::JoinSet,
+    time::{interval, timeout, MissedTickBehavior},
+};
+
+use super::{
+    coordinator::{Outgoing, SigningCoordinator},
+    messages::SessionMessage,
+};
+
+const CHANNEL_CAPACITY: usize = 64;
+const TIMEOUT_CHECK_INTERVAL: Duration = Duration::from_secs(1);
+// Time the verifiers get to receive the last messages of a session and disconnect.
+const DISCONNECT_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Message from a verifier, with the channel its connection receives replies on.
+pub type Incoming = (SessionMessage, mpsc::Sender<SessionMessage>);
+
+/// Runs `coordinator` on the messages from `incoming` until all its sessions are complete or
+/// aborted. The sessions must be opened beforehand.
+///
+/// Replies are routed to the connection a verifier last sent a signed message on, the replies
+/// to messages not signed by their verifier go back on the connection they came from only.
+/// Connections are not authenticated: messages cannot be forged, but a replayed `Join` of a
+/// verifier takes over its replies until the verifier sends again.
+pub async fn run_coordinator(
+    mut coordinator: SigningCoordinator,
+    mut incoming: mpsc::Receiver<Incoming>,
+) -> SigningCoordinator {
+    let mut peers: HashMap<PublicKey, mpsc::Sender<SessionMessage>> = HashMap::new();
+    let mut timeout_timer = interval(TIMEOUT_CHECK_INTERVAL);
+    timeout_timer.set_missed_tick_behavior(MissedTickBehavior::Delay);
+
+    while !coordinator.is_finished() {
+        tokio::select! {
+            received = incoming.recv() => {
+                let Some((message, reply)) = received else {
+                    break;
+                };
+                let is_signed = message.verify_sender_signature();
+                if let (Some(sender), true) = (message.sender(), is_signed) {
+                    peers.insert(*sender, reply.clone());
+                }
+                let outgoing = coordinator.handle_message(message, Instant::now());
+                if is_signed {
+                    deliver(&peers, outgoing).await;
+                } else {
+                    for Outgoing { message, .. } in outgoing {
+                        let _ = reply.send(message).await;
+                    }
+                }
+            }
+            _ = timeout_timer.tick() => {
+                let outgoing = coordinator.check_timeouts(Instant::now());
+                deliver(&peers, outgoing).await;
+            }
+        }
+    }
+
+    coordinator
+}
+
+async fn deliver(
+    peers: &HashMap<PublicKey, mpsc::Sender<SessionMessage>>,
+    outgoing: Vec<Outgoing>,
+) {
+    for Outgoing { to, message } in outgoing {
+        // Verifiers that are not connected catch up with a `Join` when they reconnect.
+        if let Some(peer) = peers.get(&to) {
+            let _ = peer.send(message).await;
+        }
+    }
+}
+
+/// Runs `coordinator` for the verifiers connecting to `listener`. Messages are exchanged as
+/// JSON, one message per line.
+pub async fn serve_coordinator(
+    listener: TcpListener,
+    coordinator: SigningCoordinator,
+) -> SigningCoordinator {
+    let (incoming_sender, incoming) = mpsc::channel(CHANNEL_CAPACITY);
+    let mut connections = JoinSet::new();
+
+    let run = run_coordinator(coordinator, incoming);
+    tokio::pin!(run);
+    let coordinator = loop {
+        tokio::select! {
+            coordinator = &mut run => break coordinator,
+            accepted = listener.accept() => match accepted {
+                Ok((stream, _)) => {
+                    let incoming_sender = incoming_sender.clone();
+                    connections.spawn(async move {
+                        if let Err(err) = relay_connection(stream, incoming_sender).await {
+                            eprintln!("Signing session connection error: {}", err);
+                        }
+                    });
+                }
+                Err(err) => eprintln!("Failed to accept signing session connection: {}", err),
+            },
+        }
+    };
+
+    let _ = timeout(DISCONNECT_TIMEOUT, async {
+        while connections.join_next().await.is_some() {}
+    })
+    .await;
+
+    coordinator
+}
+
+async fn relay_connection(stream: TcpStream, incoming: mpsc::Sender<Incoming>) -> io::Result<()> {
+    let (reader, mut writer) = stream.into_split();
+    let mut lines = BufReader::new(reader).lines();
+    let (reply_sender, mut replies) = mpsc::channel(CHANNEL_CAPACITY);
+
+    loop {
+        tokio::select! {
+            line = lines.next_line() => {
+                let Some(line) = line? else {
+                    return Ok(());
+                };
+                let message = serde_json::from_str(&line).map_err(io::Error::other)?;
+                if incoming.send((message, reply_sender.clone())).await.is_err() {
+                    return Ok(());
+                }
+            }
+            Some(message) = replies.recv() => write_message(&mut writer, &message).await?,
+        }
+    }
+}
+
+/// Connects to the coordinator at `address`. Returns the channels to send messages to the
+/// coordinator and to receive its replies.
+pub async fn connect(
+    address: impl ToSocketAddrs,
+) -> io::Result<(mpsc::Sender<SessionMessage>, mpsc::Receiver<SessionMessage>)> {
+    let stream = TcpStream::connect(address).await?;
+    let (reader, mut writer) = stream.into_split();
+    let (outgoing_sender, mut outgoing) = mpsc::channel::<SessionMessage>(CHANNEL_CAPACITY);
+    let (incoming_sender, incoming) = mpsc::channel(CHANNEL_CAPACITY);
+
+    tokio::spawn(async move {
+        while let Some(message) = outgoing.recv().await {
+            if write_message(&mut writer, &message).await.is_err() {
+                break;
+            }
+        }
+        let _ = writer.shutdown().await;
+    });
+    tokio::spawn(async move {
+        let mut lines = BufReader::new(reader).lines();
+        while let Ok(Some(line)) = lines.next_line().await {
+            match serde_json::from_str(&line) {
+                Ok(message) => {
+                    if incoming_sender.send(message).await.is_err() {
+                        break;
+                    }
+                }
+                Err(err) => {
+                    eprintln!("Invalid message from signing coordinator: {}", err);
+                    break;
+                }
+            }
+        }
+    });
+
+    Ok((outgoing_sender, incoming))
+}
+
+/// Connects an in-process verifier to a coordinator run with `run_coordinator`.
+pub fn connect_in_process(
+    coordinator: mpsc::Sender<Incoming>,
+) -> (mpsc::Sender<SessionMessage>, mpsc::Receiver<SessionMessage>) {
+    let (outgoing_sender, mut outgoing) = mpsc::channel::<SessionMessage>(CHANNEL_CAPACITY);
+    let (reply_sender, replies) = mpsc::channel(CHANNEL_CAPACITY);
+
+    tokio::spawn(async move {
+        while let Some(message) = outgoing.recv().await {
+            if coordinator
+                .send((message, reply_sender.clone()))
+                .await
+                .is_err()
+            {
+                break;
+            }
+        }
+    });
+
+    (outgoing_sender, replies)
+}
+
+async fn write_message(
+    writer: &mut (impl AsyncWrite + Unpin),
+    message: &SessionMessage,
+) -> io::Result<()> {
+    let mut line = serde_json::to_string(message).map_err(io::Error::other)?;
+    line.push('\n');
+    writer.write_all(line.as_bytes()).await?;
+    writer.flush().await
+}
@@ -0,0 +1,456 @@
+use std::{
+    io,
+    sync::{Arc, Mutex, MutexGuard},
+};
+
+use alloy::primitives::{keccak256, Address as EvmAddress, LogData, B256};
+use serde_json::{json, Value};
+use tokio::net::TcpListener;
+
+use crate::client::chain::{
+    l2_proof::rlp_encode_u64,
+    l2_proof_builder::{ordered_trie_root, rlp_encode_bytes, rlp_encode_list},
+};
+
+use super::server::{bad_request, not_found, ok, serve};
+
+const GENESIS_TIMESTAMP: u64 = 1_700_000_000;
+const BLOCK_INTERVAL_SECS: u64 = 12;
+const GAS_LIMIT: u64 = 30_000_000;
+const TRANSACTION_GAS: u64 = 21_000;
+const BASE_FEE_PER_GAS: u64 = 7;
+const EIP1559_TRANSACTION_TYPE: u8 = 2;
+const LOGS_BLOOM_LENGTH: usize = 256;
+const NONCE_LENGTH: usize = 8;
+
+// JSON-RPC error codes.
+const METHOD_NOT_FOUND: i64 = -32601;
+const INVALID_PARAMS: i64 = -32602;
+const LIMIT_EXCEEDED: i64 = -32005;
+
+/// Serves a simulated EVM chain over the subset of the Ethereum JSON-RPC API used by
+/// `EvmAdaptor`, like a local anvil node would.
+///
+/// Logs are added by the test with `add_log`, each in a block of its own, and empty blocks with
+/// `mine`. A maximum block range can be set to reject `eth_getLogs` requests like hosted
+/// providers do, and the depth of the finalized block below the tip. Blocks, their receipts and
+/// raw transactions are served with the headers and tries of a London block, so that their logs
+/// can be proven.
+pub struct EvmNodeSimulator {
+    chain: Arc<Mutex<EvmChain>>,
+    url: String,
+}
+
+pub struct EvmChain {
+    chain_id: u64,
+    blocks: Vec<EvmBlock>,
+    max_block_range: Option<u64>,
+    finality_depth: u64,
+    get_logs_ranges: Vec<(u64, u64)>,
+}
+
+struct EvmLog {
+    address: EvmAddress,
+    data: LogData,
+}
+
+struct EvmBlock {
+    parent_hash: B256,
+    logs: Vec<EvmLog>,
+    transactions_root: B256,
+    receipts_root: B256,
+    header: Vec<u8>, // RLP encoded
+}
+
+impl EvmNodeSimulator {
+    /// Starts serving a chain with only its genesis block on a random local port.
+    pub async fn start(chain_id: u64) -> io::Result<Self> {
+        let listener = TcpListener::bind("127.0.0.1:0").await?;
+        let url = format!("http://{}", listener.local_addr()?);
+        let mut chain = EvmChain {
+            chain_id,
+            blocks: vec![],
+            max_block_range: None,
+            finality_depth: 0,
+            get_logs_ranges: vec![],
+        };
+        chain.push_block(vec![]);
+        let chain = Arc::new(Mutex::new(chain));
+        let handler_chain = chain.clone();
+        tokio::spawn(serve(
+            listener,
+            "EVM node simulator",
+            Arc::new(move |method: &str, _path: &str, body: &[u8]| match method {
+                "POST" => handle_request(&mut handler_chain.lock().unwrap(), body),
+                _ => not_found(),
+            }),
+        ));
+
+        Ok(Self { chain, url })
+    }
+
+    pub fn url(&self) -> &str { &self.url }
+
+    pub fn chain(&self) -> MutexGuard<'_, EvmChain> { self.chain.lock().unwrap() }
+
+    pub fn block_number(&self) -> u64 { self.chain().block_number() }
+
+    pub fn mine(&self, blocks: u64) {
+        let mut chain = self.chain();
+        for _ in 0..blocks {
+            chain.push_block(vec![]);
+        }
+    }
+
+    /// Emits a log of `address` in a new block, returns the block number.
+    pub fn add_log(&self, address: EvmAddress, data: LogData) -> u64 {
+        self.add_logs(vec![(address, data)])
+    }
+
+    /// Emits the logs in a new block, each by a transaction of its own. Returns the block number.
+    pub fn add_logs(&self, logs: Vec<(EvmAddress, LogData)>) -> u64 {
+        let mut chain = self.chain();
+        chain.push_block(
+            logs.into_iter()
+                .map(|(address, data)| EvmLog { address, data })
+                .collect(),
+        );
+        chain.block_number()
+    }
+
+    /// Rejects `eth_getLogs` requests spanning more than `blocks` blocks.
+    pub fn set_max_block_range(&self, blocks: Option<u64>) {
+        self.chain().max_block_range = blocks;
+    }
+
+    /// Reports the block `blocks` below the tip as the safe and finalized one.
+    pub fn set_finality_depth(&self, blocks: u64) { self.chain().finality_depth = blocks; }
+
+    /// The block ranges of the `eth_getLogs` requests served so far.
+    pub fn get_logs_ranges(&self) -> Vec<(u64, u64)> { self.chain().get_logs_ranges.clone() }
+}
+
+impl EvmChain {
+    pub fn block_number(&self) -> u64 { self.blocks.len() as u64 - 1 }
+
+    pub fn block_hash(&self, number: u64) -> B256 {
+        keccak256(&self.blocks[number as usize].header)
+    }
+
+    pub fn block_timestamp(number: u64) -> u64 { GENESIS_TIMESTAMP + number * BLOCK_INTERVAL_SECS }
+
+    // Transactions only exist through their logs, one per transaction. Only their hash matters.
+    fn raw_transaction(block_number: u64, log_index: usize) -> Vec<u8> {
+        let fields = rlp_encode_list(&[
+            rlp_encode_u64(block_number),
+            rlp_encode_u64(log_index as u64),
+        ]);
+        [vec![EIP1559_TRANSACTION_TYPE], fields].concat()
+    }
+
+    pub fn transaction_hash(block_number: u64, log_index: usize) -> B256 {
+        keccak256(Self::raw_transaction(block_number, log_index))
+    }
+
+    fn receipt(log: &EvmLog, log_index: usize) -> Vec<u8> {
+        let topics = log
+            .data
+            .topics()
+            .iter()
+            .map(|topic| rlp_encode_bytes(topic.as_slice()))
+            .collect::<Vec<_>>();
+        let fields = rlp_encode_list(&[
+            rlp_encode_u64(1), // status
+            rlp_encode_u64(Self::gas_used(log_index + 1)),
+            rlp_encode_bytes(&[0; LOGS_BLOOM_LENGTH]),
+            rlp_encode_list(&[rlp_encode_list(&[
+                rlp_encode_bytes(log.address.as_slice()),
+                rlp_encode_list(&topics),
+                rlp_encode_bytes(&log.data.data),
+            ])]),
+        ]);
+        [vec![EIP1559_TRANSACTION_TYPE], fields].concat()
+    }
+
+    // Gas used by the first `transaction_count` transactions of a block.
+    fn gas_used(transaction_count: usize) -> u64 { transaction_count as u64 * TRANSACTION_GAS }
+
+    fn push_block(&mut self, logs: Vec<EvmLog>) {
+        let number = self.blocks.len() as u64;
+        let parent_hash = match number {
+            0 => B256::ZERO,
+            _ => self.block_hash(number - 1),
+        };
+        let transactions = (0..logs.len())
+            .map(|log_index| Self::raw_transaction(number, log_index))
+            .collect::<Vec<_>>();
+        let receipts = logs
+            .iter()
+            .enumerate()
+            .map(|(log_index, log)| Self::receipt(log, log_index))
+            .collect::<Vec<_>>();
+        let transactions_root = ordered_trie_root(&transactions);
+        let receipts_root = ordered_trie_root(&receipts);
+        let header = rlp_encode_list(&[
+            rlp_encode_bytes(parent_hash.as_slice()),
+            rlp_encode_bytes(keccak256(rlp_encode_list(&[])).as_slice()), // no uncles
+            rlp_encode_bytes(EvmAddress::ZERO.as_slice()),
+            rlp_encode_bytes(B256::ZERO.as_slice()), // state root
+            rlp_encode_bytes(transactions_root.as_slice()),
+            rlp_encode_bytes(receipts_root.as_slice()),
+            rlp_encode_bytes(&[0; LOGS_BLOOM_LENGTH]),
+            rlp_encode_u64(0), // difficulty
+            rlp_encode_u64(number),
+            rlp_encode_u64(GAS_LIMIT),
+            rlp_encode_u64(Self::gas_used(logs.len())),
+            rlp_encode_u64(Self::block_timestamp(number)),
+            rlp_encode_bytes(&[]),                   // extra data
+            rlp_encode_bytes(B256::ZERO.as_slice()), // mix hash
+            rlp_encode_bytes(&[0; NONCE_LENGTH]),
+            rlp_encode_u64(BASE_FEE_PER_GAS),
+        ]);
+
+        self.blocks.push(EvmBlock {
+            parent_hash,
+            logs,
+            transactions_root,
+            receipts_root,
+            header,
+        });
+    }
+
+    fn resolve_block(&self, block: Option<&Value>) -> Result<u64, String> {
+        match block.and_then(Value::as_str) {
+            None | Some("latest" | "pending") => Ok(self.block_number()),
+            Some("safe" | "finalized") => {
+                Ok(self.block_number().saturating_sub(self.finality_depth))
+            }
+            Some("earliest") => Ok(0),
+            Some(number) => parse_quantity(number),
+        }
+    }
+
+    fn get_logs(&mut self, filter: &Value) -> Result<Value, (i64, String)> {
+        let invalid_params = |message: String| (INVALID_PARAMS, message);
+        let from_block = self
+            .resolve_block(filter.get("fromBlock"))
+            .map_err(invalid_params)?;
+        let to_block = self
+            .resolve_block(filter.get("toBlock"))
+            .map_err(invalid_params)?
+            .min(self.block_number());
+        if let Some(max_block_range) = self.max_block_range {
+            if to_block >= from_block && to_block - from_block + 1 > max_block_range {
+                return Err((
+                    LIMIT_EXCEEDED,
+                    format!("query exceeds max block range {max_block_range}"),
+                ));
+            }
+        }
+        self.get_logs_ranges.push((from_block, to_block));
+
+        let addresses = filter.get("address").unwrap_or(&Value::Null);
+        let topic_filters = filter
+            .get("topics")
+            .and_then(Value::as_array)
+            .cloned()
+            .unwrap_or_default();
+
+        let mut logs = vec![];
+        for number in from_block..=to_block {
+            for (log_index, log) in self.blocks[number as usize].logs.iter().enumerate() {
+                let topics = log.data.topics();
+                if !matches_filter_set(addresses, &to_hex(log.address.as_slice()))
+                    || topic_filters
+                        .iter()
+                        .enumerate()
+                        .any(|(index, topic_filter)| {
+                            !topic_filter.is_null()
+                                && !topics.get(index).is_some_and(|topic| {
+                                    matches_filter_set(topic_filter, &to_hex(topic.as_slice()))
+                                })
+                        })
+                {
+                    continue;
+                }
+                logs.push(self.log_json(number, log_index));
+            }
+        }
+
+        Ok(Value::Array(logs))
+    }
+
+    fn log_json(&self, number: u64, log_index: usize) -> Value {
+        let log = &self.blocks[number as usize].logs[log_index];
+        json!({
+            "address": log.address,
+            "topics": log.data.topics(),
+            "data": log.data.data,
+            "blockHash": self.block_hash(number),
+            "blockNumber": format!("{number:#x}"),
+            "blockTimestamp": format!("{:#x}", Self::block_timestamp(number)),
+            "transactionHash": Self::transaction_hash(number, log_index),
+            "transactionIndex": format!("{log_index:#x}"),
+            "logIndex": format!("{log_index:#x}"),
+            "removed": false,
+        })
+    }
+
+    // Blocks after the tip don't exist yet.
+    fn existing_block(&self, block: Option<&Value>) -> Result<Option<u64>, (i64, String)> {
+        let number = self
+            .resolve_block(block)
+            .map_err(|message| (INVALID_PARAMS, message))?;
+        Ok((number <= self.block_number()).then_some(number))
+    }
+
+    fn block_json(&self, number: u64) -> Value {
+        let block = &self.blocks[number as usize];
+        let transactions = (0..block.logs.len())
+            .map(|log_index| Self::transaction_hash(number, log_index))
+            .collect::<Vec<_>>();
+        json!({
+            "hash": self.block_hash(number),
+            "parentHash": block.parent_hash,
+            "sha3Uncles": keccak256(rlp_encode_list(&[])),
+            "miner": EvmAddress::ZERO,
+            "stateRoot": B256::ZERO,
+            "transactionsRoot": block.transactions_root,
+            "receiptsRoot": block.receipts_root,
+            "logsBloom": to_hex(&[0; LOGS_BLOOM_LENGTH]),
+            "difficulty": "0x0",
+            "number": format!("{number:#x}"),
+            "gasLimit": format!("{GAS_LIMIT:#x}"),
+            "gasUsed": format!("{:#x}", Self::gas_used(block.logs.len())),
+            "timestamp": format!("{:#x}", Self::block_timestamp(number)),
+            "extraData": "0x",
+            "mixHash": B256::ZERO,
+            "nonce": to_hex(&[0; NONCE_LENGTH]),
+            "baseFeePerGas": format!("{BASE_FEE_PER_GAS:#x}"),
+            "transactions": transactions,
+        })
+    }
+
+    fn get_block_by_number(&self, params: &[Value]) -> Result<Value, (i64, String)> {
+        Ok(match self.existing_block(params.first())? {
+            Some(number) => self.block_json(number),
+            None => Value::Null,
+        })
+    }
+
+    fn get_block_by_hash(&self, params: &[Value]) -> Result<Value, (i64, String)> {
+        let hash = params
+            .first()
+            .and_then(Value::as_str)
+            .ok_or_else(|| (INVALID_PARAMS, "Missing block hash".to_string()))?;
+        Ok((0..=self.block_number())
+            .find(|number| to_hex(self.block_hash(*number).as_slice()).eq_ignore_ascii_case(hash))
+            .map_or(Value::Null, |number| self.block_json(number)))
+    }
+
+    fn get_block_receipts(&self, params: &[Value]) -> Result<Value, (i64, String)> {
+        let Some(number) = self.existing_block(params.first())? else {
+            return Ok(Value::Null);
+        };
+        let receipts = (0..self.blocks[number as usize].logs.len())
+            .map(|log_index| {
+                json!({
+                    "type": format!("{EIP1559_TRANSACTION_TYPE:#x}"),
+                    "status": "0x1",
+                    "cumulativeGasUsed": format!("{:#x}", Self::gas_used(log_index + 1)),
+                    "logsBloom": to_hex(&[0; LOGS_BLOOM_LENGTH]),
+                    "logs": [self.log_json(number, log_index)],
+                    "transactionHash": Self::transaction_hash(number, log_index),
+                    "transactionIndex": format!("{log_index:#x}"),
+                    "blockHash": self.block_hash(number),
+                    "blockNumber": format!("{number:#x}"),
+                })
+            })
+            .collect();
+
+        Ok(Value::Array(receipts))
+    }
+
+    fn get_raw_transaction(&self, params: &[Value]) -> Result<Value, (i64, String)> {
+        let Some(number) = self.existing_block(params.first())? else {
+            return Ok(Value::Null);
+        };
+        let index = params
+            .get(1)
+            .and_then(Value::as_str)
+            .ok_or_else(|| "Missing transaction index".to_string())
+            .and_then(parse_quantity)
+            .map_err(|message| (INVALID_PARAMS, message))?;
+
+        Ok(
+            match (index as usize) < self.blocks[number as usize].logs.len() {
+                true => json!(to_hex(&Self::raw_transaction(number, index as usize))),
+                false => Value::Null,
+            },
+        )
+    }
+}
+
+fn handle_request(chain: &mut EvmChain, body: &[u8]) -> (&'static str, Vec<u8>) {
+    let request: Value = match serde_json::from_slice(body) {
+        Ok(request) => request,
+        Err(err) => return bad_request(err.to_string()),
+    };
+    let id = request.get("id").cloned().unwrap_or(Value::Null);
+    let params = request
+        .get("params")
+        .and_then(Value::as_array)
+        .map(Vec::as_slice)
+        .unwrap_or_default();
+
+    let result = match request.get("method").and_then(Value::as_str) {
+        Some("eth_chainId") => Ok(json!(format!("{:#x}", chain.chain_id))),
+        Some("eth_blockNumber") => Ok(json!(format!("{:#x}", chain.block_number()))),
+        Some("eth_getLogs") => match params.first() {
+            Some(filter) => chain.get_logs(filter),
+            None => Err((INVALID_PARAMS, "Missing filter".to_string())),
+        },
+        Some("eth_getBlockByNumber") => chain.get_block_by_number(params),
+        Some("eth_getBlockByHash") => chain.get_block_by_hash(params),
+        Some("eth_getBlockReceipts") => chain.get_block_receipts(params),
+        Some("eth_getRawTransactionByBlockNumberAndIndex") => chain.get_raw_transaction(params),
+        Some(method) => Err((METHOD_NOT_FOUND, format!("Method {method} not found"))),
+        None => Err((METHOD_NOT_FOUND, "Missing method".to_string())),
+    };
+
+    let response = match result {
+        Ok(result) => json!({ "jsonrpc": "2.0", "id": id, "result": result }),
+        Err((code, message)) => json!({
+            "jsonrpc": "2.0",
+            "id": id,
+            "error": { "code": code, "message": message },
+        }),
+    };
+    ok(response.to_string())
+}
+
+fn parse_quantity(quantity: &str) -> Result<u64, String> {
+    quantity
+        .strip_prefix("0x")
+        .and_then(|hex| u64::from_str_radix(hex, 16).ok())
+        .ok_or_else(|| format!("Invalid quantity {quantity}"))
+}
+
+fn to_hex(bytes: &[u8]) -> String { format!("0x{}", hex::encode(bytes)) }
+
+// A filter value is either empty, a single value or a list of alternatives.
+fn matches_filter_set(filter: &Value, value: &str) -> bool {
+    match filter {
+        Value::Null => true,
+        Value::String(expected) => expected.eq_ignore_ascii_case(value),
+        Value::Array(alternatives) => {
+            alternatives.is_empty()
+                || alternatives.iter().any(|expected| {
+                    expected
+                        .as_str()
+                        .is_some_and(|expected| expected.eq_ignore_ascii_case(value))
+                })
+        }
+        _ => false,
+    }
+}
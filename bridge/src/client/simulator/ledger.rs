@@ -0,0 +1,562 @@
+use std::collections::{HashMap, HashSet};
+
+use bitcoin::{
+    absolute,
+    hashes::{hash160, sha256d, Hash},
+    opcodes::all::{
+        OP_CHECKSIG, OP_CHECKSIGVERIFY, OP_CSV, OP_DROP, OP_DUP, OP_ELSE, OP_ENDIF, OP_EQUALVERIFY,
+        OP_HASH160, OP_IF, OP_NOTIF, OP_PUSHNUM_1, OP_PUSHNUM_16,
+    },
+    relative,
+    script::Instruction,
+    secp256k1::Message,
+    sighash::{Prevouts, SighashCache},
+    taproot::{self, ControlBlock},
+    transaction::Version,
+    Amount, BlockHash, CompressedPublicKey, OutPoint, PublicKey, Script, ScriptBuf, Sequence,
+    Transaction, TxIn, TxOut, Txid, Witness, XOnlyPublicKey,
+};
+use bitvm::dry_run_taproot_input;
+use secp256k1::SECP256K1;
+
+use crate::error::LedgerError;
+
+// Time of the block at height 0. Blocks are exactly `BLOCK_INTERVAL` seconds apart.
+const GENESIS_TIME: u32 = 1_700_000_000;
+const BLOCK_INTERVAL: u32 = 600;
+// Unit of the time-based relative timelocks, in seconds (BIP68).
+const RELATIVE_TIME_UNIT: u32 = 512;
+// Flags of the sequence of an input and of the operand of OP_CHECKSEQUENCEVERIFY (BIP68).
+const LOCK_TIME_DISABLE_FLAG: u32 = 1 << 31;
+const LOCK_TYPE_FLAG: u32 = 1 << 22;
+
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct LedgerTransaction {
+    pub tx: Transaction,
+    // Height of the block the transaction was mined in, `None` while it is in the mempool.
+    pub block_height: Option<u32>,
+}
+
+/// In-memory Bitcoin ledger used to run the bridge protocol offline.
+///
+/// Transactions are validated as if they were included in the next block when they are
+/// submitted: their inputs must be unspent, their timelocks satisfied and their witnesses valid.
+/// Accepted transactions wait in the mempool until `mine` is called. Blocks only have a height,
+/// their hash and time are derived from it.
+#[derive(Clone, Default, Debug)]
+pub struct Ledger {
+    height: u32,
+    transactions: HashMap<Txid, LedgerTransaction>,
+    // Outputs of the known transactions, mined or not, that no known transaction spends.
+    unspent_outputs: HashMap<OutPoint, TxOut>,
+    spent_by: HashMap<OutPoint, Txid>,
+    mempool: Vec<Txid>,
+    funding_count: u32,
+}
+
+impl Ledger {
+    pub fn new() -> Self { Self::default() }
+
+    pub fn height(&self) -> u32 { self.height }
+
+    pub fn block_hash(height: u32) -> BlockHash {
+        BlockHash::from_raw_hash(sha256d::Hash::hash(&height.to_le_bytes()))
+    }
+
+    pub fn block_time(height: u32) -> u32 { GENESIS_TIME + height * BLOCK_INTERVAL }
+
+    pub fn transaction(&self, txid: &Txid) -> Option<&LedgerTransaction> {
+        self.transactions.get(txid)
+    }
+
+    pub fn mempool(&self) -> &[Txid] { &self.mempool }
+
+    /// The known transaction spending `outpoint`, mined or not.
+    pub fn spent_by(&self, outpoint: &OutPoint) -> Option<Txid> {
+        self.spent_by.get(outpoint).copied()
+    }
+
+    /// Creates an output of `amount` paying to `script_pubkey` out of thin air, mined at the
+    /// current height.
+    pub fn fund(&mut self, script_pubkey: ScriptBuf, amount: Amount) -> OutPoint {
+        // The input is never looked up, it only makes every funding transaction unique.
+        let tx = Transaction {
+            version: Version::TWO,
+            lock_time: absolute::LockTime::ZERO,
+            input: vec![TxIn {
+                previous_output: OutPoint {
+                    txid: Txid::all_zeros(),
+                    vout: self.funding_count,
+                },
+                script_sig: ScriptBuf::new(),
+                sequence: Sequence::MAX,
+                witness: Witness::new(),
+            }],
+            output: vec![TxOut {
+                value: amount,
+                script_pubkey,
+            }],
+        };
+        self.funding_count += 1;
+
+        let outpoint = OutPoint {
+            txid: tx.compute_txid(),
+            vout: 0,
+        };
+        self.unspent_outputs.insert(outpoint, tx.output[0].clone());
+        self.transactions.insert(
+            outpoint.txid,
+            LedgerTransaction {
+                tx,
+                block_height: Some(self.height),
+            },
+        );
+
+        outpoint
+    }
+
+    /// Unspent outputs paying to `script_pubkey`, including the ones of mempool transactions.
+    pub fn unspent_outputs(&self, script_pubkey: &Script) -> Vec<(OutPoint, TxOut)> {
+        let mut outputs: Vec<(OutPoint, TxOut)> = self
+            .unspent_outputs
+            .iter()
+            .filter(|(_, output)| output.script_pubkey.as_script() == script_pubkey)
+            .map(|(outpoint, output)| (*outpoint, output.clone()))
+            .collect();
+        outputs.sort_by_key(|(outpoint, _)| *outpoint);
+
+        outputs
+    }
+
+    /// Validates `tx` against the next block and adds it to the mempool.
+    pub fn submit(&mut self, tx: Transaction) -> Result<Txid, LedgerError> {
+        let txid = tx.compute_txid();
+        if self.transactions.contains_key(&txid) {
+            return Err(LedgerError::AlreadyKnown(txid));
+        }
+
+        let prevouts = self.prevouts(&tx, txid)?;
+        let input_value: Amount = prevouts.iter().map(|prevout| prevout.value).sum();
+        let output_value: Amount = tx.output.iter().map(|output| output.value).sum();
+        if input_value < output_value {
+            return Err(LedgerError::InsufficientInputValue {
+                input_value,
+                output_value,
+            });
+        }
+        self.check_timelocks(&tx)?;
+        verify_witnesses(&tx, &prevouts)?;
+
+        for input in tx.input.iter() {
+            self.unspent_outputs.remove(&input.previous_output);
+            self.spent_by.insert(input.previous_output, txid);
+        }
+        for (vout, output) in tx.output.iter().enumerate() {
+            self.unspent_outputs.insert(
+                OutPoint {
+                    txid,
+                    vout: vout as u32,
+                },
+                output.clone(),
+            );
+        }
+        self.transactions.insert(
+            txid,
+            LedgerTransaction {
+                tx,
+                block_height: None,
+            },
+        );
+        self.mempool.push(txid);
+
+        Ok(txid)
+    }
+
+    /// Mines `blocks` blocks. The first one includes all the mempool transactions.
+    pub fn mine(&mut self, blocks: u32) {
+        if blocks == 0 {
+            return;
+        }
+
+        self.height += 1;
+        for txid in self.mempool.drain(..) {
+            if let Some(transaction) = self.transactions.get_mut(&txid) {
+                transaction.block_height = Some(self.height);
+            }
+        }
+        self.height += blocks - 1;
+    }
+
+    fn prevouts(&self, tx: &Transaction, txid: Txid) -> Result<Vec<TxOut>, LedgerError> {
+        let mut outpoints = HashSet::new();
+        tx.input
+            .iter()
+            .map(|input| {
+                let outpoint = input.previous_output;
+                if !outpoints.insert(outpoint) {
+                    return Err(LedgerError::DoubleSpend {
+                        outpoint,
+                        spent_by: txid,
+                    });
+                }
+                if let Some(spent_by) = self.spent_by.get(&outpoint) {
+                    return Err(LedgerError::DoubleSpend {
+                        outpoint,
+                        spent_by: *spent_by,
+                    });
+                }
+
+                self.unspent_outputs
+                    .get(&outpoint)
+                    .cloned()
+                    .ok_or(LedgerError::MissingInput(outpoint))
+            })
+            .collect()
+    }
+
+    // Checks the absolute lock time and the BIP68 relative timelocks of the inputs, i.e. what
+    // OP_CHECKLOCKTIMEVERIFY and OP_CHECKSEQUENCEVERIFY rely on, against the next block.
+    fn check_timelocks(&self, tx: &Transaction) -> Result<(), LedgerError> {
+        let height = self.height + 1;
+        // Stands in for the median time past of the chain tip.
+        let time = Self::block_time(self.height);
+
+        if tx.is_lock_time_enabled() {
+            let lock_time = tx.lock_time.to_consensus_u32();
+            let is_final = match tx.lock_time {
+                absolute::LockTime::Blocks(_) => lock_time < height,
+                absolute::LockTime::Seconds(_) => lock_time < time,
+            };
+            if !is_final {
+                return Err(LedgerError::AbsoluteTimelock { lock_time, height });
+            }
+        }
+
+        if tx.version < Version::TWO {
+            return Ok(());
+        }
+        for (input_index, input) in tx.input.iter().enumerate() {
+            let Some(lock_time) = input.sequence.to_relative_lock_time() else {
+                continue;
+            };
+            // Outputs of mempool transactions can only be spent without a relative timelock.
+            let prevout_height = self
+                .transactions
+                .get(&input.previous_output.txid)
+                .and_then(|transaction| transaction.block_height);
+            let (required, elapsed) = match lock_time {
+                relative::LockTime::Blocks(blocks) => (
+                    blocks.value() as u32,
+                    prevout_height.map_or(0, |prevout_height| height - prevout_height),
+                ),
+                relative::LockTime::Time(time_units) => (
+                    time_units.value() as u32,
+                    prevout_height.map_or(0, |prevout_height| {
+                        (time - Self::block_time(prevout_height.saturating_sub(1)))
+                            / RELATIVE_TIME_UNIT
+                    }),
+                ),
+            };
+            if elapsed < required {
+                return Err(LedgerError::RelativeTimelock {
+                    input_index,
+                    required,
+                    elapsed,
+                });
+            }
+        }
+
+        Ok(())
+    }
+}
+
+// Taproot key path spends and the signatures of P2WPKH inputs are verified, taproot script path
+// spends are executed with `dry_run_taproot_input` and P2WSH witness scripts with
+// `execute_p2wsh_script`.
+fn verify_witnesses(tx: &Transaction, prevouts: &[TxOut]) -> Result<(), LedgerError> {
+    let mut sighash_cache = SighashCache::new(tx);
+    for (input_index, prevout) in prevouts.iter().enumerate() {
+        let witness = &tx.input[input_index].witness;
+        let result = if witness.is_empty() {
+            Err("Empty witness".to_string())
+        } else if prevout.script_pubkey.is_p2tr() {
+            verify_taproot_input(tx, input_index, prevouts, &mut sighash_cache)
+        } else if prevout.script_pubkey.is_p2wsh() {
+            verify_p2wsh_input(tx, input_index, prevout, &mut sighash_cache)
+        } else if prevout.script_pubkey.is_p2wpkh() {
+            verify_p2wpkh_input(witness, input_index, prevout, &mut sighash_cache)
+        } else {
+            Err("Unsupported output type".to_string())
+        };
+        result.map_err(|reason| LedgerError::Script {
+            input_index,
+            reason,
+        })?;
+    }
+
+    Ok(())
+}
+
+fn verify_taproot_input(
+    tx: &Transaction,
+    input_index: usize,
+    prevouts: &[TxOut],
+    sighash_cache: &mut SighashCache<&Transaction>,
+) -> Result<(), String> {
+    let witness = &tx.input[input_index].witness;
+    let output_key =
+        XOnlyPublicKey::from_slice(&prevouts[input_index].script_pubkey.as_bytes()[2..])
+            .map_err(|err| err.to_string())?;
+
+    match (witness.tapscript(), witness.taproot_control_block()) {
+        (Some(script), Some(control_block)) => {
+            let control_block =
+                ControlBlock::decode(control_block).map_err(|err| err.to_string())?;
+            if !control_block.verify_taproot_commitment(SECP256K1, output_key, script) {
+                return Err("Script is not committed to by the output".to_string());
+            }
+
+            let execute_info = dry_run_taproot_input(tx, input_index, prevouts);
+            if !execute_info.success {
+                return Err(format!(
+                    "Script execution failed: {:?} at {:?}",
+                    execute_info.error, execute_info.last_opcode
+                ));
+            }
+        }
+        _ => {
+            let signature =
+                taproot::Signature::from_slice(&witness[0]).map_err(|err| err.to_string())?;
+            let sighash = sighash_cache
+                .taproot_key_spend_signature_hash(
+                    input_index,
+                    &Prevouts::All(prevouts),
+                    signature.sighash_type,
+                )
+                .map_err(|err| err.to_string())?;
+            SECP256K1
+                .verify_schnorr(&signature.signature, &Message::from(sighash), &output_key)
+                .map_err(|err| err.to_string())?;
+        }
+    }
+
+    Ok(())
+}
+
+fn verify_p2wsh_input(
+    tx: &Transaction,
+    input_index: usize,
+    prevout: &TxOut,
+    sighash_cache: &mut SighashCache<&Transaction>,
+) -> Result<(), String> {
+    let witness = &tx.input[input_index].witness;
+    let script = Script::from_bytes(witness.last().unwrap());
+    if ScriptBuf::new_p2wsh(&script.wscript_hash()) != prevout.script_pubkey {
+        return Err("Witness script does not match the output".to_string());
+    }
+
+    let stack = witness
+        .iter()
+        .take(witness.len() - 1)
+        .map(|element| element.to_vec())
+        .collect();
+    execute_p2wsh_script(tx, input_index, prevout, script, stack, sighash_cache)
+}
+
+// Executes the witness `script` of a P2WSH input on `stack`. Only the opcodes of the P2WSH
+// scripts of the bridge are supported, e.g. `generate_timelock_script` and
+// `generate_pay_to_pubkey_hash_with_inscription_script`, the input fails on any other one.
+fn execute_p2wsh_script(
+    tx: &Transaction,
+    input_index: usize,
+    prevout: &TxOut,
+    script: &Script,
+    mut stack: Vec<Vec<u8>>,
+    sighash_cache: &mut SighashCache<&Transaction>,
+) -> Result<(), String> {
+    // Whether the branch of each enclosing OP_IF is executed.
+    let mut branches: Vec<bool> = vec![];
+    for instruction in script.instructions() {
+        let instruction = instruction.map_err(|err| err.to_string())?;
+        let executing = branches.iter().all(|executed| *executed);
+        match instruction {
+            Instruction::Op(op) if op == OP_IF || op == OP_NOTIF => {
+                let executed = executing && {
+                    let condition = pop(&mut stack)?;
+                    cast_to_bool(&condition) == (op == OP_IF)
+                };
+                branches.push(executed);
+            }
+            Instruction::Op(OP_ELSE) => {
+                let executed = branches.last_mut().ok_or("OP_ELSE without OP_IF")?;
+                *executed = !*executed;
+            }
+            Instruction::Op(OP_ENDIF) => {
+                branches.pop().ok_or("OP_ENDIF without OP_IF")?;
+            }
+            _ if !executing => {}
+            Instruction::PushBytes(bytes) => stack.push(bytes.as_bytes().to_vec()),
+            Instruction::Op(op)
+                if (OP_PUSHNUM_1.to_u8()..=OP_PUSHNUM_16.to_u8()).contains(&op.to_u8()) =>
+            {
+                stack.push(vec![op.to_u8() - OP_PUSHNUM_1.to_u8() + 1]);
+            }
+            Instruction::Op(OP_DROP) => {
+                pop(&mut stack)?;
+            }
+            Instruction::Op(OP_DUP) => {
+                let top = stack.last().ok_or("Stack underflow")?.clone();
+                stack.push(top);
+            }
+            Instruction::Op(OP_HASH160) => {
+                let element = pop(&mut stack)?;
+                stack.push(hash160::Hash::hash(&element).to_byte_array().to_vec());
+            }
+            Instruction::Op(OP_EQUALVERIFY) => {
+                if pop(&mut stack)? != pop(&mut stack)? {
+                    return Err("OP_EQUALVERIFY failed".to_string());
+                }
+            }
+            Instruction::Op(op) if op == OP_CHECKSIG || op == OP_CHECKSIGVERIFY => {
+                let key = pop(&mut stack)?;
+                let signature = pop(&mut stack)?;
+                let valid = !signature.is_empty();
+                if valid {
+                    verify_p2wsh_signature(
+                        &signature,
+                        &key,
+                        input_index,
+                        script,
+                        prevout,
+                        sighash_cache,
+                    )?;
+                }
+                if op == OP_CHECKSIG {
+                    stack.push(if valid { vec![1] } else { vec![] });
+                } else if !valid {
+                    return Err("OP_CHECKSIGVERIFY failed".to_string());
+                }
+            }
+            Instruction::Op(OP_CSV) => {
+                let lock_time = stack.last().ok_or("Stack underflow")?;
+                check_sequence(tx, input_index, decode_script_num(lock_time)?)?;
+            }
+            Instruction::Op(op) => return Err(format!("Unsupported opcode {}", op)),
+        }
+    }
+    if !branches.is_empty() {
+        return Err("OP_IF without OP_ENDIF".to_string());
+    }
+
+    // Like segwit v0 scripts, the script must leave a single true element on the stack.
+    match stack.as_slice() {
+        [result] if cast_to_bool(result) => Ok(()),
+        _ => Err("Script did not succeed".to_string()),
+    }
+}
+
+fn verify_p2wsh_signature(
+    signature: &[u8],
+    key: &[u8],
+    input_index: usize,
+    script: &Script,
+    prevout: &TxOut,
+    sighash_cache: &mut SighashCache<&Transaction>,
+) -> Result<(), String> {
+    let signature =
+        bitcoin::ecdsa::Signature::from_slice(signature).map_err(|err| err.to_string())?;
+    let key = PublicKey::from_slice(key).map_err(|err| err.to_string())?;
+    let sighash = sighash_cache
+        .p2wsh_signature_hash(input_index, script, prevout.value, signature.sighash_type)
+        .map_err(|err| err.to_string())?;
+    SECP256K1
+        .verify_ecdsa(&Message::from(sighash), &signature.signature, &key.inner)
+        .map_err(|err| err.to_string())
+}
+
+// Checks the relative timelock `lock_time` of OP_CHECKSEQUENCEVERIFY against the sequence of the
+// input (BIP112). That the chain satisfies the sequence is checked by `Ledger::check_timelocks`.
+fn check_sequence(tx: &Transaction, input_index: usize, lock_time: i64) -> Result<(), String> {
+    if lock_time < 0 {
+        return Err("Negative relative timelock".to_string());
+    }
+    let lock_time = lock_time as u32;
+    if lock_time & LOCK_TIME_DISABLE_FLAG != 0 {
+        return Ok(());
+    }
+
+    let sequence = tx.input[input_index].sequence.to_consensus_u32();
+    if tx.version < Version::TWO || sequence & LOCK_TIME_DISABLE_FLAG != 0 {
+        return Err("Input has no relative timelock".to_string());
+    }
+    let mask = LOCK_TYPE_FLAG | 0xffff;
+    let (lock_time, sequence) = (lock_time & mask, sequence & mask);
+    if (lock_time & LOCK_TYPE_FLAG) != (sequence & LOCK_TYPE_FLAG) || lock_time > sequence {
+        return Err(format!(
+            "Relative timelock {} is not satisfied by the input sequence {}",
+            lock_time, sequence
+        ));
+    }
+
+    Ok(())
+}
+
+fn pop(stack: &mut Vec<Vec<u8>>) -> Result<Vec<u8>, String> {
+    stack.pop().ok_or_else(|| "Stack underflow".to_string())
+}
+
+fn cast_to_bool(element: &[u8]) -> bool {
+    match element.split_last() {
+        // Negative zero is false as well.
+        Some((last, rest)) => rest.iter().any(|byte| *byte != 0) || (last & 0x7f) != 0,
+        None => false,
+    }
+}
+
+// Decodes a little-endian, sign-magnitude script number of up to 5 bytes, the size
+// OP_CHECKSEQUENCEVERIFY accepts.
+fn decode_script_num(bytes: &[u8]) -> Result<i64, String> {
+    if bytes.len() > 5 {
+        return Err("Script number overflow".to_string());
+    }
+    let Some((last, _)) = bytes.split_last() else {
+        return Ok(0);
+    };
+    let value = bytes
+        .iter()
+        .enumerate()
+        .fold(0i64, |value, (i, byte)| value | (*byte as i64) << (8 * i));
+    match last & 0x80 {
+        0 => Ok(value),
+        _ => Ok(-(value & !(0x80i64 << (8 * (bytes.len() - 1))))),
+    }
+}
+
+fn verify_p2wpkh_input(
+    witness: &Witness,
+    input_index: usize,
+    prevout: &TxOut,
+    sighash_cache: &mut SighashCache<&Transaction>,
+) -> Result<(), String> {
+    if witness.len() != 2 {
+        return Err("P2WPKH witness must hold a signature and a public key".to_string());
+    }
+    let key = CompressedPublicKey::from_slice(&witness[1]).map_err(|err| err.to_string())?;
+    if ScriptBuf::new_p2wpkh(&key.wpubkey_hash()) != prevout.script_pubkey {
+        return Err("Public key does not match the output".to_string());
+    }
+
+    let signature =
+        bitcoin::ecdsa::Signature::from_slice(&witness[0]).map_err(|err| err.to_string())?;
+    let sighash = sighash_cache
+        .p2wpkh_signature_hash(
+            input_index,
+            &prevout.script_pubkey,
+            prevout.value,
+            signature.sighash_type,
+        )
+        .map_err(|err| err.to_string())?;
+    SECP256K1
+        .verify_ecdsa(&Message::from(sighash), &signature.signature, &key.0)
+        .map_err(|err| err.to_string())
+}
@@ -0,0 +1,3 @@
+pub mod evm_node;
+pub mod ledger;
+pub mod server;
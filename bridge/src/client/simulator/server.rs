@@ -0,0 +1,252 @@
+use std::{
+    io,
+    str::FromStr,
+    sync::{Arc, Mutex, MutexGuard},
+};
+
+use bitcoin::{
+    consensus::encode::{deserialize_hex, serialize, serialize_hex},
+    Address, Amount, OutPoint, ScriptBuf, Txid,
+};
+use serde_json::{json, Value};
+use tokio::{
+    io::{AsyncBufReadExt, AsyncReadExt, AsyncWriteExt, BufReader},
+    net::{TcpListener, TcpStream},
+};
+
+use super::ledger::Ledger;
+
+const MAX_BODY_SIZE: usize = 4 << 20;
+
+/// Serves a `Ledger` over the subset of the Esplora REST API used by the bridge client.
+///
+/// Pass `url` as the Esplora URL of a `BitVMClient` to run it against the simulated ledger:
+/// broadcast transactions are validated and put in the mempool, and only get confirmed when the
+/// test calls `mine`. This makes scenarios with timelocks deterministic and fast.
+pub struct LedgerSimulator {
+    ledger: Arc<Mutex<Ledger>>,
+    url: String,
+}
+
+impl LedgerSimulator {
+    /// Starts serving an empty ledger on a random local port.
+    pub async fn start() -> io::Result<Self> {
+        let listener = TcpListener::bind("127.0.0.1:0").await?;
+        let url = format!("http://{}", listener.local_addr()?);
+        let ledger = Arc::new(Mutex::new(Ledger::new()));
+        let handler_ledger = ledger.clone();
+        tokio::spawn(serve(
+            listener,
+            "Ledger simulator",
+            Arc::new(move |method: &str, path: &str, body: &[u8]| {
+                handle_request(&mut handler_ledger.lock().unwrap(), method, path, body)
+            }),
+        ));
+
+        Ok(Self { ledger, url })
+    }
+
+    pub fn url(&self) -> &str { &self.url }
+
+    pub fn ledger(&self) -> MutexGuard<'_, Ledger> { self.ledger.lock().unwrap() }
+
+    pub fn height(&self) -> u32 { self.ledger().height() }
+
+    pub fn fund(&self, address: &Address, amount: Amount) -> OutPoint {
+        self.ledger().fund(address.script_pubkey(), amount)
+    }
+
+    pub fn mine(&self, blocks: u32) { self.ledger().mine(blocks) }
+}
+
+// Handles a request from its method, path and body, returns the response status and body.
+pub(super) type RequestHandler =
+    Arc<dyn Fn(&str, &str, &[u8]) -> (&'static str, Vec<u8>) + Send + Sync>;
+
+// Serves the requests accepted by `listener` with `handler`, `name` identifies the simulator in
+// error messages.
+pub(super) async fn serve(listener: TcpListener, name: &'static str, handler: RequestHandler) {
+    loop {
+        match listener.accept().await {
+            Ok((stream, _)) => {
+                let handler = handler.clone();
+                tokio::spawn(async move {
+                    if let Err(err) = handle_connection(stream, handler).await {
+                        eprintln!("{} connection error: {}", name, err);
+                    }
+                });
+            }
+            Err(err) => eprintln!("Failed to accept {} connection: {}", name, err),
+        }
+    }
+}
+
+// Minimal HTTP/1.1 handling: one request per connection.
+async fn handle_connection(stream: TcpStream, handler: RequestHandler) -> io::Result<()> {
+    let mut reader = BufReader::new(stream);
+
+    let mut request_line = String::new();
+    reader.read_line(&mut request_line).await?;
+
+    let mut content_length = 0;
+    loop {
+        let mut line = String::new();
+        if reader.read_line(&mut line).await? == 0 {
+            return Ok(());
+        }
+        let line = line.trim_end();
+        if line.is_empty() {
+            break;
+        }
+        if let Some((name, value)) = line.split_once(':') {
+            if name.trim().eq_ignore_ascii_case("content-length") {
+                content_length = value.trim().parse::<usize>().unwrap_or(0);
+            }
+        }
+    }
+    if content_length > MAX_BODY_SIZE {
+        return write_response(reader.get_mut(), "413 Payload Too Large", b"").await;
+    }
+    let mut body = vec![0; content_length];
+    reader.read_exact(&mut body).await?;
+
+    let mut request = request_line.split_whitespace();
+    let method = request.next().unwrap_or_default();
+    let path = request.next().unwrap_or_default();
+    let (status, response) = handler(method, path, &body);
+
+    write_response(reader.get_mut(), status, &response).await
+}
+
+fn handle_request(
+    ledger: &mut Ledger,
+    method: &str,
+    path: &str,
+    body: &[u8],
+) -> (&'static str, Vec<u8>) {
+    let segments: Vec<&str> = path
+        .split('?')
+        .next()
+        .unwrap_or_default()
+        .split('/')
+        .filter(|segment| !segment.is_empty())
+        .collect();
+
+    match (method, &segments[..]) {
+        ("GET", ["blocks", "tip", "height"]) => ok(ledger.height().to_string()),
+        ("GET", ["blocks", "tip", "hash"]) => ok(Ledger::block_hash(ledger.height()).to_string()),
+        ("GET", ["tx", txid, "status"]) => match Txid::from_str(txid) {
+            // Like Esplora, unknown transactions are reported as unconfirmed.
+            Ok(txid) => ok(tx_status(ledger, &txid).to_string()),
+            Err(err) => bad_request(err.to_string()),
+        },
+        ("GET", ["tx", txid, "raw"]) => match Txid::from_str(txid) {
+            Ok(txid) => match ledger.transaction(&txid) {
+                Some(transaction) => ("200 OK", serialize(&transaction.tx)),
+                None => not_found(),
+            },
+            Err(err) => bad_request(err.to_string()),
+        },
+        ("GET", ["tx", txid, "hex"]) => match Txid::from_str(txid) {
+            Ok(txid) => match ledger.transaction(&txid) {
+                Some(transaction) => ok(serialize_hex(&transaction.tx)),
+                None => not_found(),
+            },
+            Err(err) => bad_request(err.to_string()),
+        },
+        ("GET", ["tx", txid, "outspend", vout]) => match (Txid::from_str(txid), vout.parse()) {
+            (Ok(txid), Ok(vout)) => ok(output_status(ledger, &OutPoint { txid, vout }).to_string()),
+            (Err(err), _) => bad_request(err.to_string()),
+            (_, Err(err)) => bad_request(err.to_string()),
+        },
+        ("GET", ["address", address, "utxo"]) => match Address::from_str(address) {
+            Ok(address) => {
+                ok(address_utxos(ledger, &address.assume_checked().script_pubkey()).to_string())
+            }
+            Err(err) => bad_request(err.to_string()),
+        },
+        ("POST", ["tx"]) => {
+            let tx = String::from_utf8_lossy(body);
+            match deserialize_hex(tx.trim()) {
+                Ok(tx) => match ledger.submit(tx) {
+                    Ok(txid) => ok(txid.to_string()),
+                    Err(err) => bad_request(format!("{:?}", err)),
+                },
+                Err(err) => bad_request(err.to_string()),
+            }
+        }
+        _ => not_found(),
+    }
+}
+
+fn tx_status(ledger: &Ledger, txid: &Txid) -> Value {
+    match ledger
+        .transaction(txid)
+        .and_then(|transaction| transaction.block_height)
+    {
+        Some(height) => json!({
+            "confirmed": true,
+            "block_height": height,
+            "block_hash": Ledger::block_hash(height),
+            "block_time": Ledger::block_time(height),
+        }),
+        None => json!({ "confirmed": false }),
+    }
+}
+
+fn output_status(ledger: &Ledger, outpoint: &OutPoint) -> Value {
+    match ledger.spent_by(outpoint) {
+        Some(spending_txid) => {
+            let vin = ledger.transaction(&spending_txid).and_then(|transaction| {
+                transaction
+                    .tx
+                    .input
+                    .iter()
+                    .position(|input| input.previous_output == *outpoint)
+            });
+            json!({
+                "spent": true,
+                "txid": spending_txid,
+                "vin": vin,
+                "status": tx_status(ledger, &spending_txid),
+            })
+        }
+        None => json!({ "spent": false }),
+    }
+}
+
+fn address_utxos(ledger: &Ledger, script_pubkey: &ScriptBuf) -> Value {
+    Value::Array(
+        ledger
+            .unspent_outputs(script_pubkey)
+            .into_iter()
+            .map(|(outpoint, output)| {
+                json!({
+                    "txid": outpoint.txid,
+                    "vout": outpoint.vout,
+                    "status": tx_status(ledger, &outpoint.txid),
+                    "value": output.value.to_sat(),
+                })
+            })
+            .collect(),
+    )
+}
+
+pub(super) fn ok(body: String) -> (&'static str, Vec<u8>) { ("200 OK", body.into_bytes()) }
+
+pub(super) fn bad_request(message: String) -> (&'static str, Vec<u8>) {
+    ("400 Bad Request", message.into_bytes())
+}
+
+pub(super) fn not_found() -> (&'static str, Vec<u8>) { ("404 Not Found", b"Not found".to_vec()) }
+
+async fn write_response(stream: &mut TcpStream, status: &str, body: &[u8]) -> io::Result<()> {
+    let header = format!(
+        "HTTP/1.1 {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+        status,
+        body.len()
+    );
+    stream.write_all(header.as_bytes()).await?;
+    stream.write_all(body).await?;
+    stream.flush().await
+}
@@ -0,0 +1,239 @@
+use bitcoin::{Amount, OutPoint};
+
+// Upper bound on the number of branches visited by branch and bound before falling back.
+const BNB_MAX_TRIES: usize = 100_000;
+
+/// Spendable output of a funding wallet.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct WalletUtxo {
+    pub outpoint: OutPoint,
+    pub amount: Amount,
+    // Index of the wallet key the output is locked to.
+    pub key_index: usize,
+    pub confirmed: bool,
+}
+
+/// Sizes and fee rate of the transaction the coins are selected for.
+#[derive(Clone, Copy, Debug)]
+pub struct SelectionParameters {
+    pub target: Amount,
+    pub fee_rate: u64, // sat/vB
+    // vsize of the transaction without any inputs and without change.
+    pub base_vsize: u64,
+    pub input_vsize: u64,
+    pub change_output_vsize: u64,
+    // Smallest change output worth creating.
+    pub min_change: Amount,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CoinSelection {
+    pub selected: Vec<WalletUtxo>,
+    pub fee: Amount,
+    // `None` if the excess is too small for a change output and is paid as fee instead.
+    pub change: Option<Amount>,
+}
+
+/// Selects the UTXOs to fund `parameters.target`.
+///
+/// Branch and bound searches for a selection that needs no change output, wasting at most
+/// the cost of creating one. If there is none, UTXOs are selected largest first and the
+/// excess is returned as change. Returns `None` if the UTXOs can't cover the target and fees.
+pub fn select_coins(
+    utxos: &[WalletUtxo],
+    parameters: &SelectionParameters,
+) -> Option<CoinSelection> {
+    let input_fee = parameters.input_vsize * parameters.fee_rate;
+    let change_fee = parameters.change_output_vsize * parameters.fee_rate;
+
+    // Only UTXOs worth more than the fee to spend them can contribute to the target.
+    let mut candidates: Vec<(&WalletUtxo, u64)> = utxos
+        .iter()
+        .filter(|utxo| utxo.amount.to_sat() > input_fee)
+        .map(|utxo| (utxo, utxo.amount.to_sat() - input_fee))
+        .collect();
+    candidates.sort_by(|a, b| b.1.cmp(&a.1));
+    let effective_values: Vec<u64> = candidates.iter().map(|(_, value)| *value).collect();
+
+    let target = parameters.target.to_sat() + parameters.base_vsize * parameters.fee_rate;
+    let cost_of_change = change_fee + parameters.min_change.to_sat();
+
+    let (indexes, with_change) =
+        match branch_and_bound(&effective_values, target, target + cost_of_change) {
+            Some(indexes) => (indexes, false),
+            None => largest_first(&effective_values, target, target + cost_of_change)?,
+        };
+
+    let selected: Vec<WalletUtxo> = indexes.iter().map(|&i| candidates[i].0.clone()).collect();
+    let selected_amount: u64 = selected.iter().map(|utxo| utxo.amount.to_sat()).sum();
+    let fee = target - parameters.target.to_sat() + indexes.len() as u64 * input_fee;
+
+    if with_change {
+        let change = selected_amount - parameters.target.to_sat() - fee - change_fee;
+        Some(CoinSelection {
+            selected,
+            fee: Amount::from_sat(fee + change_fee),
+            change: Some(Amount::from_sat(change)),
+        })
+    } else {
+        Some(CoinSelection {
+            selected,
+            fee: Amount::from_sat(selected_amount - parameters.target.to_sat()),
+            change: None,
+        })
+    }
+}
+
+// Depth first search over the inclusion of each value, in descending order, for the subset
+// whose sum is in `[target, upper_bound]` with the least excess.
+fn branch_and_bound(values: &[u64], target: u64, upper_bound: u64) -> Option<Vec<usize>> {
+    struct Search<'a> {
+        values: &'a [u64],
+        remaining: Vec<u64>,
+        target: u64,
+        upper_bound: u64,
+        tries: usize,
+        selected: Vec<usize>,
+        best: Option<(u64, Vec<usize>)>,
+    }
+
+    impl Search<'_> {
+        fn run(&mut self, index: usize, sum: u64) {
+            if self.tries == BNB_MAX_TRIES || matches!(self.best, Some((0, _))) {
+                return;
+            }
+            self.tries += 1;
+
+            if sum > self.upper_bound {
+                return;
+            }
+            if sum >= self.target {
+                let waste = sum - self.target;
+                if self.best.as_ref().is_none_or(|(best, _)| waste < *best) {
+                    self.best = Some((waste, self.selected.clone()));
+                }
+                return;
+            }
+            if index == self.values.len() || sum + self.remaining[index] < self.target {
+                return;
+            }
+
+            self.selected.push(index);
+            self.run(index + 1, sum + self.values[index]);
+            self.selected.pop();
+            self.run(index + 1, sum);
+        }
+    }
+
+    let mut remaining = vec![0; values.len() + 1];
+    for i in (0..values.len()).rev() {
+        remaining[i] = remaining[i + 1] + values[i];
+    }
+
+    let mut search = Search {
+        values,
+        remaining,
+        target,
+        upper_bound,
+        tries: 0,
+        selected: vec![],
+        best: None,
+    };
+    search.run(0, 0);
+
+    search.best.map(|(_, selected)| selected)
+}
+
+// Selects values in descending order until they cover the target with change. Returns
+// whether a change output is needed.
+fn largest_first(
+    values: &[u64],
+    target: u64,
+    target_with_change: u64,
+) -> Option<(Vec<usize>, bool)> {
+    let mut sum = 0;
+    for (i, value) in values.iter().enumerate() {
+        sum += value;
+        if sum >= target_with_change {
+            return Some(((0..=i).collect(), true));
+        }
+    }
+    if sum >= target {
+        // Not enough for change, the excess goes to fees.
+        return Some(((0..values.len()).collect(), false));
+    }
+
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use bitcoin::{hashes::Hash, Txid};
+
+    use super::*;
+
+    fn utxos(amounts: &[u64]) -> Vec<WalletUtxo> {
+        amounts
+            .iter()
+            .enumerate()
+            .map(|(vout, &amount)| WalletUtxo {
+                outpoint: OutPoint {
+                    txid: Txid::all_zeros(),
+                    vout: vout as u32,
+                },
+                amount: Amount::from_sat(amount),
+                key_index: 0,
+                confirmed: true,
+            })
+            .collect()
+    }
+
+    fn parameters(target: u64) -> SelectionParameters {
+        SelectionParameters {
+            target: Amount::from_sat(target),
+            fee_rate: 1,
+            base_vsize: 50,
+            input_vsize: 70,
+            change_output_vsize: 43,
+            min_change: Amount::from_sat(330),
+        }
+    }
+
+    #[test]
+    fn test_exact_match_needs_no_change() {
+        // The second and third UTXOs cover 50_000 plus the fees of two inputs exactly.
+        let utxos = utxos(&[100_000, 30_070, 20_120, 5_000]);
+        let selection = select_coins(&utxos, &parameters(50_000)).unwrap();
+
+        assert_eq!(selection.change, None);
+        assert_eq!(selection.fee, Amount::from_sat(50 + 2 * 70));
+        let mut vouts: Vec<u32> = selection
+            .selected
+            .iter()
+            .map(|utxo| utxo.outpoint.vout)
+            .collect();
+        vouts.sort();
+        assert_eq!(vouts, vec![1, 2]);
+    }
+
+    #[test]
+    fn test_falls_back_to_largest_first_with_change() {
+        let utxos = utxos(&[30_000, 100_000, 20_000]);
+        let selection = select_coins(&utxos, &parameters(50_000)).unwrap();
+
+        assert_eq!(selection.selected.len(), 1);
+        assert_eq!(selection.selected[0].amount, Amount::from_sat(100_000));
+        assert_eq!(selection.fee, Amount::from_sat(50 + 70 + 43));
+        assert_eq!(
+            selection.change,
+            Some(Amount::from_sat(100_000 - 50_000 - 50 - 70 - 43))
+        );
+    }
+
+    #[test]
+    fn test_insufficient_funds() {
+        let utxos = utxos(&[30_000, 20_000, 60]);
+        assert!(select_coins(&utxos, &parameters(50_000)).is_none());
+        assert!(select_coins(&[], &parameters(1)).is_none());
+    }
+}
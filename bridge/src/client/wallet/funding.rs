@@ -0,0 +1,258 @@
+use bitcoin::{
+    absolute, key::Keypair, Address, Amount, EcdsaSighashType, Network, OutPoint, PublicKey,
+    ScriptBuf, Sequence, Transaction, TxIn, TxOut, Witness,
+};
+use esplora_client::AsyncClient;
+use std::collections::HashSet;
+
+use super::coin_selection::{select_coins, SelectionParameters, WalletUtxo};
+use crate::{
+    error::{Error, WalletError},
+    graphs::base::MIN_RELAY_FEE_RATE,
+    scripts::{generate_pay_to_pubkey_script, generate_pay_to_pubkey_script_address},
+    transactions::{base::Input, signing::populate_p2wsh_witness},
+};
+
+// Virtual sizes of the funding transaction parts, for fee estimation.
+// Version, locktime, input and output counts, segwit marker and flag.
+const TX_OVERHEAD_VSIZE: u64 = 11;
+// Outpoint, sequence and empty script sig, plus the witness of an ECDSA signature and the
+// pay-to-pubkey witness script.
+const P2WSH_PAY_TO_PUBKEY_INPUT_VSIZE: u64 = 41 + (1 + 1 + 73 + 1 + 35).div_ceil(4);
+const P2WSH_OUTPUT_VSIZE: u64 = 43;
+
+/// Output funding a graph transaction, and the transaction creating it if no UTXO of the
+/// required amount was available. The transaction still has to be broadcast.
+pub struct Funding {
+    pub input: Input,
+    pub transaction: Option<Transaction>,
+}
+
+/// Wallet funding graph transactions from the pay-to-pubkey outputs of a set of keys.
+///
+/// Graph transactions can only spend outputs locked to the first key, which signs them, so
+/// funding outputs and change are always sent to its address. The other keys, e.g. derived
+/// funding addresses, only provide UTXOs.
+///
+/// Reserved outputs, e.g. the ones already funding a graph, are never spent. Unconfirmed
+/// outputs are only spent if allowed, since they may still be replaced.
+pub struct FundingWallet {
+    network: Network,
+    keypairs: Vec<Keypair>,
+    fee_rate: u64, // sat/vB
+    // Change below this amount is left to the fee.
+    dust_amount: Amount,
+    reserved: HashSet<OutPoint>,
+    spend_unconfirmed: bool,
+}
+
+impl FundingWallet {
+    pub fn new(network: Network, keypairs: Vec<Keypair>, dust_amount: Amount) -> Self {
+        if keypairs.is_empty() {
+            panic!("Funding wallet needs at least one key");
+        }
+
+        Self {
+            network,
+            keypairs,
+            fee_rate: MIN_RELAY_FEE_RATE,
+            dust_amount,
+            reserved: HashSet::new(),
+            spend_unconfirmed: false,
+        }
+    }
+
+    pub fn with_fee_rate(mut self, fee_rate: u64) -> Self {
+        self.fee_rate = fee_rate;
+        self
+    }
+
+    pub fn with_reserved(mut self, reserved: HashSet<OutPoint>) -> Self {
+        self.reserved = reserved;
+        self
+    }
+
+    pub fn with_unconfirmed_utxos(mut self, spend_unconfirmed: bool) -> Self {
+        self.spend_unconfirmed = spend_unconfirmed;
+        self
+    }
+
+    /// Public key of the key graph transactions are signed with.
+    pub fn public_key(&self) -> PublicKey { PublicKey::new(self.keypairs[0].public_key()) }
+
+    /// Address receiving funding outputs and change.
+    pub fn address(&self) -> Address { self.address_of(0) }
+
+    pub fn addresses(&self) -> Vec<Address> {
+        (0..self.keypairs.len())
+            .map(|key_index| self.address_of(key_index))
+            .collect()
+    }
+
+    /// Unspent outputs of all the wallet keys, including unconfirmed ones.
+    pub async fn utxos(&self, esplora: &AsyncClient) -> Result<Vec<WalletUtxo>, Error> {
+        let mut utxos = vec![];
+        for (key_index, address) in self.addresses().into_iter().enumerate() {
+            let address_utxos = esplora
+                .get_address_utxo(address)
+                .await
+                .map_err(Error::Esplora)?;
+            utxos.extend(address_utxos.into_iter().map(|utxo| WalletUtxo {
+                outpoint: OutPoint {
+                    txid: utxo.txid,
+                    vout: utxo.vout,
+                },
+                amount: utxo.value,
+                key_index,
+                confirmed: utxo.status.confirmed,
+            }));
+        }
+
+        Ok(utxos)
+    }
+
+    /// Unspent outputs of all the wallet keys that can be spent, i.e. not reserved and
+    /// confirmed unless unconfirmed ones are allowed.
+    pub async fn spendable_utxos(&self, esplora: &AsyncClient) -> Result<Vec<WalletUtxo>, Error> {
+        let mut utxos = self.utxos(esplora).await?;
+        utxos.retain(|utxo| {
+            !self.reserved.contains(&utxo.outpoint) && (utxo.confirmed || self.spend_unconfirmed)
+        });
+
+        Ok(utxos)
+    }
+
+    /// Provides an output of exactly `amount` at `address()`, preferring an existing
+    /// spendable UTXO. Otherwise coins are selected for a funding transaction creating it.
+    /// Callers should reserve the returned input, and the inputs of the funding transaction
+    /// until it is broadcast, before funding anything else.
+    pub async fn fund(&self, esplora: &AsyncClient, amount: Amount) -> Result<Funding, Error> {
+        let utxos = self.spendable_utxos(esplora).await?;
+
+        if let Some(utxo) = utxos
+            .iter()
+            .find(|utxo| utxo.key_index == 0 && utxo.amount == amount)
+        {
+            return Ok(Funding {
+                input: Input {
+                    outpoint: utxo.outpoint,
+                    amount,
+                },
+                transaction: None,
+            });
+        }
+
+        let tx = self.create_funding_transaction(&utxos, amount)?;
+        Ok(Funding {
+            input: Input {
+                outpoint: OutPoint {
+                    txid: tx.compute_txid(),
+                    vout: 0,
+                },
+                amount,
+            },
+            transaction: Some(tx),
+        })
+    }
+
+    /// Creates a signed transaction paying `amount` to `address()` as output 0, with change
+    /// to `address()` as output 1 if the excess is worth more than dust.
+    pub fn create_funding_transaction(
+        &self,
+        utxos: &[WalletUtxo],
+        amount: Amount,
+    ) -> Result<Transaction, Error> {
+        let parameters = SelectionParameters {
+            target: amount,
+            fee_rate: self.fee_rate,
+            base_vsize: TX_OVERHEAD_VSIZE + P2WSH_OUTPUT_VSIZE,
+            input_vsize: P2WSH_PAY_TO_PUBKEY_INPUT_VSIZE,
+            change_output_vsize: P2WSH_OUTPUT_VSIZE,
+            min_change: self.dust_amount,
+        };
+        let selection = select_coins(utxos, &parameters).ok_or(Error::Wallet(
+            WalletError::InsufficientFunds {
+                required: amount,
+                available: utxos.iter().map(|utxo| utxo.amount).sum(),
+            },
+        ))?;
+
+        let mut outputs = vec![TxOut {
+            value: amount,
+            script_pubkey: self.address().script_pubkey(),
+        }];
+        if let Some(change) = selection.change {
+            outputs.push(TxOut {
+                value: change,
+                script_pubkey: self.address().script_pubkey(),
+            });
+        }
+
+        Ok(self.sign(&selection.selected, outputs))
+    }
+
+    /// Creates a signed transaction spending all `utxos` to a single output at `address()`.
+    pub fn create_consolidation_transaction(
+        &self,
+        utxos: &[WalletUtxo],
+    ) -> Result<Transaction, Error> {
+        let available: Amount = utxos.iter().map(|utxo| utxo.amount).sum();
+        let fee = Amount::from_sat(
+            (TX_OVERHEAD_VSIZE
+                + P2WSH_OUTPUT_VSIZE
+                + utxos.len() as u64 * P2WSH_PAY_TO_PUBKEY_INPUT_VSIZE)
+                * self.fee_rate,
+        );
+        if utxos.len() < 2 || available < fee + self.dust_amount {
+            return Err(Error::Wallet(WalletError::InsufficientFunds {
+                required: fee + self.dust_amount,
+                available,
+            }));
+        }
+
+        let output = TxOut {
+            value: available - fee,
+            script_pubkey: self.address().script_pubkey(),
+        };
+
+        Ok(self.sign(utxos, vec![output]))
+    }
+
+    fn sign(&self, utxos: &[WalletUtxo], outputs: Vec<TxOut>) -> Transaction {
+        let mut tx = Transaction {
+            version: bitcoin::transaction::Version(2),
+            lock_time: absolute::LockTime::ZERO,
+            input: utxos
+                .iter()
+                .map(|utxo| TxIn {
+                    previous_output: utxo.outpoint,
+                    script_sig: ScriptBuf::new(),
+                    sequence: Sequence::ENABLE_RBF_NO_LOCKTIME,
+                    witness: Witness::default(),
+                })
+                .collect(),
+            output: outputs,
+        };
+
+        for (input_index, utxo) in utxos.iter().enumerate() {
+            let keypair = &self.keypairs[utxo.key_index];
+            populate_p2wsh_witness(
+                &mut tx,
+                input_index,
+                EcdsaSighashType::All,
+                &generate_pay_to_pubkey_script(&PublicKey::new(keypair.public_key())),
+                utxo.amount,
+                &vec![keypair],
+            );
+        }
+
+        tx
+    }
+
+    fn address_of(&self, key_index: usize) -> Address {
+        generate_pay_to_pubkey_script_address(
+            self.network,
+            &PublicKey::new(self.keypairs[key_index].public_key()),
+        )
+    }
+}
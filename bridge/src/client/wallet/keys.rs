@@ -0,0 +1,230 @@
+use bip39::{Language, Mnemonic};
+use bitcoin::{
+    bip32::{ChildNumber, DerivationPath, Xpriv},
+    key::Keypair,
+    Address, Network, NetworkKind, PrivateKey, PublicKey, XOnlyPublicKey,
+};
+use secp256k1::SECP256K1;
+use std::str::FromStr;
+
+use crate::{
+    client::events::Role,
+    commitments::CommitmentSeed,
+    error::{Error, WalletError},
+};
+
+const BIP86_PURPOSE: u32 = 86;
+// Entropy of generated mnemonics, i.e. 24 words.
+const MNEMONIC_ENTROPY_LENGTH: usize = 32;
+
+// The operator commitment seed is taken from a hardened child of the operator account, so it
+// can't be linked to any of the public keys.
+const COMMITMENT_SEED_CHILD: u32 = 2;
+
+/// BIP32 wallet of a bridge participant. Keys of every role are derived from a single master
+/// key along paths `m/86'/<coin type>'/<role account>'/<graph index>/<index>`. Index 0 is the
+/// key the role signs the graphs of the graph index with, the following indexes are used for
+/// funding addresses. Graph index 0 is the external chain of BIP86, so its addresses are also
+/// found by other BIP86 wallets.
+pub struct HdWallet {
+    master_key: Xpriv,
+}
+
+impl HdWallet {
+    /// Restores the wallet from a BIP39 mnemonic. The words and the checksum are validated
+    /// against the English word list, so a mistyped mnemonic is rejected instead of restoring
+    /// another wallet.
+    pub fn from_mnemonic(
+        mnemonic: &str,
+        passphrase: &str,
+        network: Network,
+    ) -> Result<Self, Error> {
+        let mnemonic = Mnemonic::parse_in(Language::English, mnemonic)
+            .map_err(|e| Error::Wallet(WalletError::InvalidMnemonic(e.to_string())))?;
+        let master_key = Xpriv::new_master(network, &mnemonic.to_seed(passphrase))
+            .map_err(|e| Error::Wallet(WalletError::InvalidMasterKey(e.to_string())))?;
+
+        Ok(Self { master_key })
+    }
+
+    pub fn from_xprv(xprv: &str) -> Result<Self, Error> {
+        let master_key = Xpriv::from_str(xprv.trim())
+            .map_err(|e| Error::Wallet(WalletError::InvalidMasterKey(e.to_string())))?;
+        if master_key.depth != 0 {
+            return Err(Error::Wallet(WalletError::InvalidMasterKey(
+                "not a master key".to_string(),
+            )));
+        }
+
+        Ok(Self { master_key })
+    }
+
+    /// Generates a wallet from a new random 24 word mnemonic without passphrase. The mnemonic
+    /// is returned so it can be shown to the user for backup.
+    pub fn generate(network: Network) -> (Self, Mnemonic) {
+        let mut entropy = [0u8; MNEMONIC_ENTROPY_LENGTH];
+        rand::RngCore::fill_bytes(&mut rand::rngs::OsRng, &mut entropy);
+        let mnemonic = Mnemonic::from_entropy_in(Language::English, &entropy).unwrap();
+        let wallet = Self::from_mnemonic(&mnemonic.to_string(), "", network).unwrap();
+
+        (wallet, mnemonic)
+    }
+
+    /// Master key in xprv (mainnet) or tprv (test networks) format.
+    pub fn to_xprv(&self) -> String { self.master_key.to_string() }
+
+    pub fn account_path(&self, role: Role) -> DerivationPath {
+        let coin_type = match self.master_key.network {
+            NetworkKind::Main => 0,
+            NetworkKind::Test => 1,
+        };
+
+        DerivationPath::from(vec![
+            ChildNumber::from_hardened_idx(BIP86_PURPOSE).unwrap(),
+            ChildNumber::from_hardened_idx(coin_type).unwrap(),
+            ChildNumber::from_hardened_idx(role_account(role)).unwrap(),
+        ])
+    }
+
+    pub fn derivation_path(&self, role: Role, graph_index: u32, index: u32) -> DerivationPath {
+        self.account_path(role).extend([
+            ChildNumber::from_normal_idx(graph_index).expect("Graph index out of range"),
+            ChildNumber::from_normal_idx(index).expect("Derivation index out of range"),
+        ])
+    }
+
+    pub fn keypair(&self, role: Role, graph_index: u32, index: u32) -> Keypair {
+        self.master_key
+            .derive_priv(SECP256K1, &self.derivation_path(role, graph_index, index))
+            .expect("Failed to derive key")
+            .to_keypair(SECP256K1)
+    }
+
+    pub fn public_key(&self, role: Role, graph_index: u32, index: u32) -> PublicKey {
+        PublicKey::new(self.keypair(role, graph_index, index).public_key())
+    }
+
+    /// Hex encoded secret key the role signs the graphs of `graph_index` with, in the format
+    /// of the `keys` config.
+    pub fn signing_secret_key(&self, role: Role, graph_index: u32) -> String {
+        hex::encode(self.keypair(role, graph_index, 0).secret_bytes())
+    }
+
+    /// BIP86 key path spend address at `index`.
+    pub fn bip86_address(
+        &self,
+        role: Role,
+        graph_index: u32,
+        index: u32,
+        network: Network,
+    ) -> Address {
+        let (internal_key, _) =
+            XOnlyPublicKey::from_keypair(&self.keypair(role, graph_index, index));
+        Address::p2tr(SECP256K1, internal_key, None, network)
+    }
+
+    /// Private key at `index`, e.g. for importing a funding address into another wallet.
+    pub fn private_key(
+        &self,
+        role: Role,
+        graph_index: u32,
+        index: u32,
+        network: Network,
+    ) -> PrivateKey {
+        PrivateKey::new(self.keypair(role, graph_index, index).secret_key(), network)
+    }
+
+    /// Operator commitment seed, so the commitment secrets are restored with the wallet.
+    pub fn commitment_seed(&self) -> CommitmentSeed {
+        let path = self
+            .account_path(Role::Operator)
+            .child(ChildNumber::from_hardened_idx(COMMITMENT_SEED_CHILD).unwrap());
+        let key = self
+            .master_key
+            .derive_priv(SECP256K1, &path)
+            .expect("Failed to derive commitment seed");
+
+        CommitmentSeed::from_hex(&hex::encode(key.private_key.secret_bytes())).unwrap()
+    }
+}
+
+fn role_account(role: Role) -> u32 {
+    match role {
+        Role::Depositor => 0,
+        Role::Operator => 1,
+        Role::Verifier => 2,
+        Role::Withdrawer => 3,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MNEMONIC: &str = "abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon about";
+
+    #[test]
+    fn test_bip39_seed() {
+        // BIP39 test vector, the passphrase is "TREZOR".
+        let wallet = HdWallet::from_mnemonic(MNEMONIC, "TREZOR", Network::Bitcoin).unwrap();
+        assert_eq!(wallet.to_xprv(), "xprv9s21ZrQH143K3h3fDYiay8mocZ3afhfULfb5GX8kCBdno77K4HiA15Tg23wpbeF1pLfs1c5SPmYHrEpTuuRhxMwvKDwqdKiGJS9XFKzUsAF");
+    }
+
+    #[test]
+    fn test_bip86_address() {
+        // BIP86 test vector for m/86'/0'/0'/0/0, which is the depositor's signing key of the
+        // first graph index.
+        let wallet = HdWallet::from_mnemonic(MNEMONIC, "", Network::Bitcoin).unwrap();
+        assert_eq!(
+            wallet.derivation_path(Role::Depositor, 0, 0).to_string(),
+            "86'/0'/0'/0/0"
+        );
+        assert_eq!(
+            wallet
+                .bip86_address(Role::Depositor, 0, 0, Network::Bitcoin)
+                .to_string(),
+            "bc1p5cyxnuxmeuwuvkwfem96lqzszd02n6xdcjrs20cac6yqjjwudpxqkedrcr"
+        );
+    }
+
+    #[test]
+    fn test_roles_and_graphs_use_separate_keys() {
+        let (wallet, _) = HdWallet::generate(Network::Regtest);
+        assert_eq!(
+            wallet.derivation_path(Role::Verifier, 3, 5).to_string(),
+            "86'/1'/2'/3/5"
+        );
+        assert_ne!(
+            wallet.signing_secret_key(Role::Operator, 0),
+            wallet.signing_secret_key(Role::Verifier, 0)
+        );
+        assert_ne!(
+            wallet.signing_secret_key(Role::Operator, 0),
+            wallet.signing_secret_key(Role::Operator, 1)
+        );
+        assert_ne!(
+            wallet.commitment_seed().to_hex(),
+            wallet.signing_secret_key(Role::Operator, 0)
+        );
+    }
+
+    #[test]
+    fn test_generated_mnemonic_restores_wallet() {
+        let (wallet, mnemonic) = HdWallet::generate(Network::Regtest);
+        assert_eq!(mnemonic.word_count(), 24);
+
+        let restored =
+            HdWallet::from_mnemonic(&mnemonic.to_string(), "", Network::Regtest).unwrap();
+        assert_eq!(restored.to_xprv(), wallet.to_xprv());
+    }
+
+    #[test]
+    fn test_invalid_mnemonic() {
+        let restore = |mnemonic: &str| HdWallet::from_mnemonic(mnemonic, "", Network::Bitcoin);
+        assert!(restore("abandon about").is_err());
+        // Mistyped word.
+        assert!(restore(&MNEMONIC.replace("about", "abuot")).is_err());
+        // Valid words with an invalid checksum.
+        assert!(restore(&MNEMONIC.replace("about", "abandon")).is_err());
+    }
+}
@@ -0,0 +1,4 @@
+pub mod coin_selection;
+pub mod funding;
+pub mod keys;
+pub mod rescan;
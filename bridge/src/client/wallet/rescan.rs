@@ -0,0 +1,108 @@
+use bitcoin::{Address, Network};
+use esplora_client::AsyncClient;
+use std::collections::HashMap;
+
+use super::keys::HdWallet;
+use crate::{client::events::Role, error::Error, scripts::generate_pay_to_pubkey_script_address};
+
+/// Number of consecutive unused indexes after which a rescan stops, as in BIP44.
+pub const DEFAULT_GAP_LIMIT: u32 = 20;
+
+#[derive(Clone, Debug)]
+pub struct UsedAddress {
+    pub role: Role,
+    pub graph_index: u32,
+    pub index: u32,
+    pub address: Address,
+    // Transactions on the first page of the address history only.
+    pub tx_count: usize,
+}
+
+#[derive(Default, Debug)]
+pub struct RescanResult {
+    pub used_addresses: Vec<UsedAddress>,
+    // First index after the last used one, of each role and graph index.
+    pub next_indexes: HashMap<(Role, u32), u32>,
+    // First graph index of each role without any used address.
+    pub next_graph_indexes: HashMap<Role, u32>,
+}
+
+/// Discovers the used addresses of `roles`. The graph indexes of a role are walked until one
+/// has no used address, and the derivation indexes of every graph index until `gap_limit`
+/// consecutive indexes have no transactions. For every index both the BIP86 address and the
+/// pay-to-pubkey address the bridge funds graphs from are checked.
+pub async fn rescan(
+    esplora: &AsyncClient,
+    wallet: &HdWallet,
+    network: Network,
+    roles: &[Role],
+    gap_limit: u32,
+) -> Result<RescanResult, Error> {
+    let mut result = RescanResult::default();
+
+    for &role in roles {
+        let mut graph_index = 0;
+        loop {
+            let next_index = rescan_graph_index(
+                esplora,
+                wallet,
+                network,
+                role,
+                graph_index,
+                gap_limit,
+                &mut result.used_addresses,
+            )
+            .await?;
+            result.next_indexes.insert((role, graph_index), next_index);
+            if next_index == 0 {
+                break;
+            }
+            graph_index += 1;
+        }
+        result.next_graph_indexes.insert(role, graph_index);
+    }
+
+    Ok(result)
+}
+
+// Returns the first index after the last used one.
+async fn rescan_graph_index(
+    esplora: &AsyncClient,
+    wallet: &HdWallet,
+    network: Network,
+    role: Role,
+    graph_index: u32,
+    gap_limit: u32,
+    used_addresses: &mut Vec<UsedAddress>,
+) -> Result<u32, Error> {
+    let mut next_index = 0;
+    let mut index = 0;
+    while index < next_index + gap_limit {
+        let addresses = [
+            wallet.bip86_address(role, graph_index, index, network),
+            generate_pay_to_pubkey_script_address(
+                network,
+                &wallet.public_key(role, graph_index, index),
+            ),
+        ];
+        for address in addresses {
+            let txs = esplora
+                .scripthash_txs(&address.script_pubkey(), None)
+                .await
+                .map_err(Error::Esplora)?;
+            if !txs.is_empty() {
+                used_addresses.push(UsedAddress {
+                    role,
+                    graph_index,
+                    index,
+                    address,
+                    tx_count: txs.len(),
+                });
+                next_index = index + 1;
+            }
+        }
+        index += 1;
+    }
+
+    Ok(next_index)
+}
@@ -1,9 +1,17 @@
 use std::collections::HashMap;
 
+use bitcoin::hashes::{
+    hash160,
+    hmac::{Hmac, HmacEngine},
+    sha256, Hash, HashEngine,
+};
 use serde::{Deserialize, Serialize};
 use strum::{Display, EnumIter, IntoEnumIterator};
 
-use bitvm::{chunker::assigner::BridgeAssigner, signatures::signing_winternitz::WinternitzSecret};
+use bitvm::{
+    chunker::assigner::BridgeAssigner,
+    signatures::signing_winternitz::{WinternitzPublicKey, WinternitzSecret, LOG_D},
+};
 
 use super::{
     constants::{
@@ -70,55 +78,202 @@ impl TryFrom<String> for CommitmentMessageId {
     }
 }
 
+// Domain separation tag of the commitment secret derivation. Bump the version suffix if the
+// derivation ever changes, otherwise previously created graphs can no longer be asserted.
+const COMMITMENT_SECRET_DERIVATION_TAG: &[u8] = b"BitVM/bridge/commitment-secret/v1";
+
+pub const COMMITMENT_SEED_LENGTH: usize = 32;
+
+/// Operator master seed. All Winternitz commitment secrets of all peg-out graphs of an operator
+/// are derived from it, so backing up the seed is enough to be able to assert after losing the
+/// private data file.
+#[derive(Serialize, Deserialize, Eq, PartialEq, Clone)]
+pub struct CommitmentSeed([u8; COMMITMENT_SEED_LENGTH]);
+
+impl CommitmentSeed {
+    pub fn new() -> Self {
+        let mut buffer = [0u8; COMMITMENT_SEED_LENGTH];
+        let mut rng = rand::rngs::OsRng;
+        rand::RngCore::fill_bytes(&mut rng, &mut buffer);
+
+        CommitmentSeed(buffer)
+    }
+
+    pub fn from_hex(seed: &str) -> Result<Self, String> {
+        let bytes = hex::decode(seed.trim()).map_err(|e| format!("Invalid seed: {}", e))?;
+        let bytes: [u8; COMMITMENT_SEED_LENGTH] = bytes
+            .try_into()
+            .map_err(|_| format!("Invalid seed: expected {} bytes", COMMITMENT_SEED_LENGTH))?;
+
+        Ok(CommitmentSeed(bytes))
+    }
+
+    pub fn to_hex(&self) -> String { hex::encode(self.0) }
+
+    /// Derives the 160 bit Winternitz key material for one message of one graph:
+    /// `HMAC-SHA256(seed, tag || len(graph_id) || graph_id || len(message_id) || message_id)`
+    /// truncated to 20 bytes. Every field is length prefixed to keep the encoding unambiguous.
+    fn derive_secret_bytes(&self, graph_id: &str, message_id: &CommitmentMessageId) -> [u8; 20] {
+        let message_id = String::from(message_id.clone());
+
+        let mut engine = HmacEngine::<sha256::Hash>::new(&self.0);
+        engine.input(COMMITMENT_SECRET_DERIVATION_TAG);
+        for field in [graph_id.as_bytes(), message_id.as_bytes()] {
+            engine.input(&(field.len() as u32).to_be_bytes());
+            engine.input(field);
+        }
+        let output = Hmac::<sha256::Hash>::from_engine(engine);
+
+        output.as_byte_array()[..20].try_into().unwrap()
+    }
+}
+
+impl Default for CommitmentSeed {
+    fn default() -> Self { Self::new() }
+}
+
 impl CommitmentMessageId {
+    /// Length in bytes of the message committed to with this ID.
+    pub fn message_length(&self) -> usize {
+        match self {
+            CommitmentMessageId::PegOutTxIdSourceNetwork => SOURCE_NETWORK_TXID_LENGTH,
+            CommitmentMessageId::PegOutTxIdDestinationNetwork => DESTINATION_NETWORK_TXID_LENGTH,
+            CommitmentMessageId::StartTime => START_TIME_MESSAGE_LENGTH,
+            CommitmentMessageId::Superblock => SUPERBLOCK_MESSAGE_LENGTH,
+            CommitmentMessageId::SuperblockHash => SUPERBLOCK_HASH_MESSAGE_LENGTH,
+            CommitmentMessageId::Groth16IntermediateValues((_, size)) => *size,
+        }
+    }
+
     // btree map is a copy of chunker related commitments
-    pub fn generate_commitment_secrets() -> HashMap<CommitmentMessageId, WinternitzSecret> {
-        println!("Generating commitment secrets ...");
-        let mut commitment_map = HashMap::from([
-            (
-                CommitmentMessageId::PegOutTxIdSourceNetwork,
-                WinternitzSecret::new(SOURCE_NETWORK_TXID_LENGTH),
-            ),
-            (
-                CommitmentMessageId::PegOutTxIdDestinationNetwork,
-                WinternitzSecret::new(DESTINATION_NETWORK_TXID_LENGTH),
-            ),
-            (
-                CommitmentMessageId::StartTime,
-                WinternitzSecret::new(START_TIME_MESSAGE_LENGTH),
-            ),
-            (
-                CommitmentMessageId::Superblock,
-                WinternitzSecret::new(SUPERBLOCK_MESSAGE_LENGTH),
-            ),
-            (
-                CommitmentMessageId::SuperblockHash,
-                WinternitzSecret::new(SUPERBLOCK_HASH_MESSAGE_LENGTH),
-            ),
-        ]);
+    fn all_message_ids() -> Vec<CommitmentMessageId> {
+        let mut message_ids = vec![
+            CommitmentMessageId::PegOutTxIdSourceNetwork,
+            CommitmentMessageId::PegOutTxIdDestinationNetwork,
+            CommitmentMessageId::StartTime,
+            CommitmentMessageId::Superblock,
+            CommitmentMessageId::SuperblockHash,
+        ];
 
         // maybe variable cache is more efficient
         let all_variables = BridgeAssigner::default().all_intermediate_variables();
 
         // split variable to different connectors
         for (v, size) in all_variables {
-            commitment_map.insert(
-                CommitmentMessageId::Groth16IntermediateValues((v, size)),
-                WinternitzSecret::new(size),
-            );
+            message_ids.push(CommitmentMessageId::Groth16IntermediateValues((v, size)));
+        }
+
+        message_ids
+    }
+
+    pub fn generate_commitment_secrets() -> HashMap<CommitmentMessageId, WinternitzSecret> {
+        println!("Generating commitment secrets ...");
+        Self::all_message_ids()
+            .into_iter()
+            .map(|message_id| {
+                let secret = WinternitzSecret::new(message_id.message_length());
+                (message_id, secret)
+            })
+            .collect()
+    }
+
+    /// Deterministic counterpart of `generate_commitment_secrets`. Calling it again with the same
+    /// seed and graph ID regenerates exactly the same secrets.
+    pub fn derive_commitment_secrets(
+        seed: &CommitmentSeed,
+        graph_id: &str,
+    ) -> HashMap<CommitmentMessageId, WinternitzSecret> {
+        println!("Deriving commitment secrets for graph {} ...", graph_id);
+        Self::all_message_ids()
+            .into_iter()
+            .map(|message_id| {
+                let secret = message_id.derive_commitment_secret(seed, graph_id);
+                (message_id, secret)
+            })
+            .collect()
+    }
+
+    pub fn derive_commitment_secret(
+        &self,
+        seed: &CommitmentSeed,
+        graph_id: &str,
+    ) -> WinternitzSecret {
+        WinternitzSecret::from_bytes(
+            &seed.derive_secret_bytes(graph_id, self),
+            self.message_length(),
+        )
+    }
+}
+
+/// Recovers the message of a Winternitz witness produced by `generate_winternitz_witness`, i.e.
+/// the `(signature, digit)` pairs of the message digits in reverse order followed by the ones of
+/// the checksum digits. Returns `None` unless every digit signature hashes to `public_key` and the
+/// checksum matches the message, so only a message signed with the matching secret is recovered.
+pub fn decode_winternitz_message(
+    public_key: &WinternitzPublicKey,
+    witness: &[Vec<u8>],
+) -> Option<Vec<u8>> {
+    let max_digit = (1u32 << LOG_D) - 1;
+    let message_length = public_key.parameters.message_length() as usize;
+    let total_length = public_key.parameters.total_length() as usize;
+    if witness.len() != total_length * 2 || public_key.public_key.len() != total_length {
+        return None;
+    }
+
+    let mut digits = Vec::with_capacity(total_length);
+    for (pair, digit_public_key) in witness.chunks(2).zip(&public_key.public_key) {
+        let digit = match pair[1].as_slice() {
+            [] => 0,
+            [digit] if (*digit as u32) <= max_digit => *digit as u32,
+            _ => return None,
+        };
+        let mut hash: [u8; 20] = pair[0].as_slice().try_into().ok()?;
+        for _ in digit..max_digit {
+            hash = hash160::Hash::hash(&hash).to_byte_array();
         }
+        if hash != *digit_public_key {
+            return None;
+        }
+        digits.push(digit);
+    }
 
-        commitment_map
+    let (message_digits, checksum_digits) = digits.split_at(message_length);
+    let checksum = checksum_digits
+        .iter()
+        .fold(0, |checksum, digit| (checksum << LOG_D) | digit);
+    if checksum != max_digit * message_length as u32 - message_digits.iter().sum::<u32>() {
+        return None;
     }
+
+    // Message digits are signed least significant nibble of each byte first.
+    let message = message_digits
+        .iter()
+        .rev()
+        .collect::<Vec<_>>()
+        .chunks(2)
+        .map(|nibbles| {
+            nibbles
+                .iter()
+                .rev()
+                .fold(0u8, |byte, nibble| (byte << LOG_D) | **nibble as u8)
+        })
+        .collect();
+
+    Some(message)
 }
 
 #[cfg(test)]
 mod tests {
     use std::collections::HashMap;
 
+    use bitcoin::hashes::{hash160, Hash};
+    use bitvm::signatures::signing_winternitz::{
+        generate_winternitz_witness, WinternitzPublicKey, WinternitzSigningInputs,
+    };
+
     use crate::serialization::{deserialize, serialize};
 
-    use super::CommitmentMessageId;
+    use super::{decode_winternitz_message, CommitmentMessageId, CommitmentSeed};
 
     #[test]
     fn test_commitment_message_id_serialization() {
@@ -145,4 +300,85 @@ mod tests {
         let deserialized_messages = deserialize::<HashMap<CommitmentMessageId, &str>>(&json);
         assert_eq!(messages, deserialized_messages);
     }
+
+    #[test]
+    fn test_commitment_secret_derivation_is_deterministic() {
+        let seed = CommitmentSeed::from_hex(
+            "5ae1f8ab1a6c1f4b7a2b3c9d0e1f2a3b4c5d6e7f8091a2b3c4d5e6f708192a3b",
+        )
+        .unwrap();
+        let message_id =
+            CommitmentMessageId::Groth16IntermediateValues(("F_10_mul_c_1p0c".to_string(), 31));
+
+        let secret = message_id.derive_commitment_secret(&seed, "GRAPH_0");
+        let same_secret = message_id.derive_commitment_secret(&seed, "GRAPH_0");
+        assert!(secret == same_secret);
+
+        let restored_seed = CommitmentSeed::from_hex(&seed.to_hex()).unwrap();
+        let restored_secret = message_id.derive_commitment_secret(&restored_seed, "GRAPH_0");
+        assert!(WinternitzPublicKey::from(&secret) == WinternitzPublicKey::from(&restored_secret));
+    }
+
+    #[test]
+    fn test_commitment_secret_derivation_is_domain_separated() {
+        let seed = CommitmentSeed::new();
+
+        let secret = CommitmentMessageId::StartTime.derive_commitment_secret(&seed, "GRAPH_0");
+        let other_graph_secret =
+            CommitmentMessageId::StartTime.derive_commitment_secret(&seed, "GRAPH_1");
+        let other_message_secret =
+            CommitmentMessageId::Superblock.derive_commitment_secret(&seed, "GRAPH_0");
+        let other_seed_secret = CommitmentMessageId::StartTime
+            .derive_commitment_secret(&CommitmentSeed::new(), "GRAPH_0");
+
+        assert!(secret != other_graph_secret);
+        assert!(secret != other_message_secret);
+        assert!(secret != other_seed_secret);
+    }
+
+    #[test]
+    fn test_commitment_seed_from_invalid_hex() {
+        assert!(CommitmentSeed::from_hex("not hex").is_err());
+        assert!(CommitmentSeed::from_hex("abcd").is_err());
+    }
+
+    #[test]
+    fn test_decode_winternitz_message() {
+        let message_id = CommitmentMessageId::PegOutTxIdDestinationNetwork;
+        let secret = message_id.derive_commitment_secret(&CommitmentSeed::new(), "GRAPH_0");
+        let public_key = WinternitzPublicKey::from(&secret);
+        let message: Vec<u8> = (0..message_id.message_length() as u8)
+            .map(|i| i * 7)
+            .collect();
+        let witness = generate_winternitz_witness(&WinternitzSigningInputs {
+            message: &message,
+            signing_key: &secret,
+        })
+        .to_vec();
+
+        assert_eq!(
+            decode_winternitz_message(&public_key, &witness),
+            Some(message)
+        );
+
+        let other_public_key = WinternitzPublicKey::from(
+            &message_id.derive_commitment_secret(&CommitmentSeed::new(), "GRAPH_0"),
+        );
+        assert_eq!(decode_winternitz_message(&other_public_key, &witness), None);
+
+        // Raising a digit only takes hashing its signature, the checksum catches it.
+        let mut raised_witness = witness.clone();
+        let digit = raised_witness[1].first().copied().unwrap_or(0);
+        assert!(digit < 15);
+        raised_witness[0] = hash160::Hash::hash(&raised_witness[0])
+            .to_byte_array()
+            .to_vec();
+        raised_witness[1] = vec![digit + 1];
+        assert_eq!(
+            decode_winternitz_message(&public_key, &raised_witness),
+            None
+        );
+
+        assert_eq!(decode_winternitz_message(&public_key, &witness[2..]), None);
+    }
 }
@@ -10,6 +10,7 @@ use serde::{Deserialize, Serialize};
 
 use crate::{
     commitments::CommitmentMessageId,
+    parameters::ProtocolParameters,
     superblock::{SUPERBLOCK_HASH_MESSAGE_LENGTH, SUPERBLOCK_MESSAGE_LENGTH},
 };
 
@@ -18,12 +19,7 @@ use bitvm::signatures::signing_winternitz::{
 };
 
 use super::{
-    super::{
-        constants::{NUM_BLOCKS_PER_2_WEEKS, NUM_BLOCKS_PER_6_HOURS, NUM_BLOCKS_PER_DAY},
-        scripts::*,
-        transactions::base::Input,
-        utils::num_blocks_per_network,
-    },
+    super::{scripts::*, transactions::base::Input},
     base::*,
 };
 
@@ -44,18 +40,16 @@ impl Connector1 {
         operator_taproot_public_key: &XOnlyPublicKey,
         n_of_n_taproot_public_key: &XOnlyPublicKey,
         commitment_public_keys: &HashMap<CommitmentMessageId, WinternitzPublicKey>,
+        protocol_parameters: &ProtocolParameters,
     ) -> Self {
         Connector1 {
             network,
             operator_taproot_public_key: *operator_taproot_public_key,
             n_of_n_taproot_public_key: *n_of_n_taproot_public_key,
             commitment_public_keys: commitment_public_keys.clone(),
-            num_blocks_timelock_leaf_0: num_blocks_per_network(network, NUM_BLOCKS_PER_2_WEEKS),
-            num_blocks_timelock_leaf_1: num_blocks_per_network(
-                network,
-                NUM_BLOCKS_PER_2_WEEKS + NUM_BLOCKS_PER_DAY,
-            ),
-            num_blocks_timelock_leaf_2: num_blocks_per_network(network, NUM_BLOCKS_PER_6_HOURS),
+            num_blocks_timelock_leaf_0: protocol_parameters.connector_1_timelock_leaf_0,
+            num_blocks_timelock_leaf_1: protocol_parameters.connector_1_timelock_leaf_1,
+            num_blocks_timelock_leaf_2: protocol_parameters.connector_1_timelock_leaf_2,
         }
     }
 
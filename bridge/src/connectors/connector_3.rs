@@ -2,11 +2,7 @@ use bitcoin::{Address, Network, PublicKey, ScriptBuf, TxIn};
 use serde::{Deserialize, Serialize};
 
 use super::{
-    super::{
-        scripts::*,
-        transactions::base::Input,
-        {constants::NUM_BLOCKS_PER_3_DAYS, utils::num_blocks_per_network},
-    },
+    super::{parameters::ProtocolParameters, scripts::*, transactions::base::Input},
     base::*,
 };
 
@@ -18,11 +14,15 @@ pub struct Connector3 {
 }
 
 impl Connector3 {
-    pub fn new(network: Network, operator_public_key: &PublicKey) -> Self {
+    pub fn new(
+        network: Network,
+        operator_public_key: &PublicKey,
+        protocol_parameters: &ProtocolParameters,
+    ) -> Self {
         Connector3 {
             network,
             operator_public_key: *operator_public_key,
-            num_blocks_timelock: num_blocks_per_network(network, NUM_BLOCKS_PER_3_DAYS),
+            num_blocks_timelock: protocol_parameters.connector_3_timelock,
         }
     }
 }
@@ -2,11 +2,7 @@ use bitcoin::{Address, Network, PublicKey, ScriptBuf, TxIn};
 use serde::{Deserialize, Serialize};
 
 use super::{
-    super::{
-        scripts::*,
-        transactions::base::Input,
-        {constants::NUM_BLOCKS_PER_2_WEEKS, utils::num_blocks_per_network},
-    },
+    super::{parameters::ProtocolParameters, scripts::*, transactions::base::Input},
     base::*,
 };
 
@@ -18,11 +14,15 @@ pub struct Connector4 {
 }
 
 impl Connector4 {
-    pub fn new(network: Network, operator_public_key: &PublicKey) -> Self {
+    pub fn new(
+        network: Network,
+        operator_public_key: &PublicKey,
+        protocol_parameters: &ProtocolParameters,
+    ) -> Self {
         Connector4 {
             network,
             operator_public_key: *operator_public_key,
-            num_blocks_timelock: num_blocks_per_network(network, NUM_BLOCKS_PER_2_WEEKS),
+            num_blocks_timelock: protocol_parameters.connector_4_timelock,
         }
     }
 }
@@ -18,15 +18,13 @@ use serde::{Deserialize, Serialize};
 use crate::{
     commitments::CommitmentMessageId,
     constants::START_TIME_MESSAGE_LENGTH,
+    parameters::ProtocolParameters,
     superblock::{extract_superblock_ts_from_header, SUPERBLOCK_MESSAGE_LENGTH},
     utils::{sb_hash_from_bytes, sb_hash_from_nibbles, H256},
 };
 
 use super::{
-    super::{
-        constants::NUM_BLOCKS_PER_3_DAYS, scripts::*, transactions::base::Input,
-        utils::num_blocks_per_network,
-    },
+    super::{scripts::*, transactions::base::Input},
     base::*,
 };
 
@@ -43,12 +41,13 @@ impl ConnectorB {
         network: Network,
         n_of_n_taproot_public_key: &XOnlyPublicKey,
         commitment_public_keys: &HashMap<CommitmentMessageId, WinternitzPublicKey>,
+        protocol_parameters: &ProtocolParameters,
     ) -> Self {
         ConnectorB {
             network,
             n_of_n_taproot_public_key: *n_of_n_taproot_public_key,
             commitment_public_keys: commitment_public_keys.clone(),
-            num_blocks_timelock_1: num_blocks_per_network(network, NUM_BLOCKS_PER_3_DAYS),
+            num_blocks_timelock_1: protocol_parameters.connector_b_timelock_1,
         }
     }
 
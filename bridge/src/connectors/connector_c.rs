@@ -10,7 +10,7 @@ use crate::{
     common::ZkProofVerifyingKey,
     connectors::base::*,
     error::{ChunkerError, ConnectorError, Error},
-    transactions::base::Input,
+    transactions::{base::Input, fee_table::DUMMY_BYTE},
     utils::{
         cleanup_cache_files, read_disk_cache, remove_script_and_control_block_from_witness,
         write_disk_cache,
@@ -37,7 +37,7 @@ use bitvm::{
         common::RawWitness,
         disprove_execution::{disprove_exec, RawProof},
     },
-    signatures::signing_winternitz::WinternitzPublicKey,
+    signatures::signing_winternitz::{WinternitzPublicKey, WinternitzSecret},
 };
 
 // Specialized for assert leaves currently.
@@ -224,6 +224,34 @@ impl ConnectorC {
         .ok_or(Error::Chunker(ChunkerError::ValidProof))
     }
 
+    /// Witnesses of the leaves, in leaf order, for a default proof committed to with dummy
+    /// secrets. Only their sizes are meaningful, see `transactions::fee_table`.
+    pub fn generate_dummy_disprove_witnesses(&self) -> Vec<RawWitness> {
+        let commits_secrets = self
+            .commitment_public_keys
+            .keys()
+            .filter_map(|message_id| match message_id {
+                CommitmentMessageId::Groth16IntermediateValues((name, size)) => Some((
+                    name.clone(),
+                    WinternitzSecret::from_bytes(&[DUMMY_BYTE; 20], *size),
+                )),
+                _ => None,
+            })
+            .collect();
+        let mut assigner = BridgeAssigner::new_operator(commits_secrets);
+        let default_proof = RawProof::default();
+
+        groth16_verify_to_segments(
+            &mut assigner,
+            &default_proof.public,
+            &default_proof.proof,
+            &default_proof.vk,
+        )
+        .iter()
+        .map(|segment| segment.witness(&assigner))
+        .collect()
+    }
+
     pub fn taproot_merkle_root(&self) -> Option<TapNodeHash> {
         self.taproot_spend_info_cache()
             .map(|cache| cache.merkle_root)
@@ -33,6 +33,23 @@ pub enum DestinationNetwork {
     EthereumSepolia,
     /// Locally hosted network.
     Local,
+    /// Any other EVM network, configured at runtime.
+    Evm { chain_id: u64 },
+}
+
+pub const ETHEREUM_CHAIN_ID: u64 = 1;
+pub const ETHEREUM_SEPOLIA_CHAIN_ID: u64 = 11155111;
+
+impl DestinationNetwork {
+    /// Chain ID of an EVM network, `None` for the local network.
+    pub fn chain_id(&self) -> Option<u64> {
+        match *self {
+            DestinationNetwork::Ethereum => Some(ETHEREUM_CHAIN_ID),
+            DestinationNetwork::EthereumSepolia => Some(ETHEREUM_SEPOLIA_CHAIN_ID),
+            DestinationNetwork::Local => None,
+            DestinationNetwork::Evm { chain_id } => Some(chain_id),
+        }
+    }
 }
 
 impl fmt::Display for DestinationNetwork {
@@ -43,6 +60,7 @@ impl fmt::Display for DestinationNetwork {
             Ethereum => "ethereum",
             EthereumSepolia => "ethereum_sepolia",
             Local => "anvil_831337",
+            Evm { chain_id } => return write!(f, "evm_{}", chain_id),
         };
         write!(f, "{}", s)
     }
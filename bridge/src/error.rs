@@ -1,7 +1,8 @@
+use super::client::schema::SchemaId;
 use super::commitments::CommitmentMessageId;
-use super::graphs::base::GraphId;
+use super::graphs::{base::GraphId, challenge_crowdfunding::ChallengeCrowdfundingStatus};
 use super::transactions::{base::BaseTransaction, pre_signed::PreSignedTransaction};
-use bitcoin::Txid;
+use bitcoin::{Amount, OutPoint, PublicKey, Txid};
 use std::fmt::{self, Display};
 use strum::Display;
 
@@ -10,8 +11,12 @@ pub enum ClientError {
     NoUserContextDefined,
     OperatorContextNotDefined,
     ZkProofVerifyingKeyNotDefined,
+    CommitmentSeedNotDefined,
+    InvalidCommitmentSeed(String),
+    CommitmentSecretsMismatch(GraphId),
     PegInGraphNotFound(GraphId),
     PegOutGraphNotFound(GraphId),
+    GraphNotFound(GraphId),
 }
 
 #[derive(Debug)]
@@ -37,6 +42,8 @@ pub enum GraphError {
     PrecedingTxNotConfirmed(Vec<NamedTx>),
     PrecedingTxTimelockNotMet(NamedTx),
     WitnessNotGenerated(CommitmentMessageId),
+    ChallengeCrowdfundingUnavailable(ChallengeCrowdfundingStatus),
+    ChallengeUnderfunded { funded: Amount, required: Amount },
 }
 
 #[derive(Debug, Display)]
@@ -47,11 +54,26 @@ pub enum ConnectorError {
 #[derive(Debug)]
 pub enum TransactionError {
     AlreadyMined(Txid),
+    CrowdfundingInputsAlreadyAdded,
+    CrowdfundingInputAfterRefundOutput,
+    DuplicateCrowdfundingInput(OutPoint),
+    InsufficientCrowdfunding { funded: Amount, required: Amount },
 }
 
 #[derive(Debug)]
 pub enum L2Error {
     PegOutNotInitiated,
+    ChainService(String),
+    L2ProofNotFound,
+    L2ProofsUnavailable,       // the chain adaptor can't build proofs
+    InvalidBlockHeader(usize), // index of the header in the proof
+    BlockNotFinalized,
+    InvalidTrieProof,
+    InvalidReceipt,
+    FailedTransaction,
+    BurnEventNotFound,
+    InvalidBurnEvent(String),
+    BurnEventMismatch,
 }
 
 #[derive(Debug)]
@@ -59,6 +81,195 @@ pub enum ChunkerError {
     ValidProof,
 }
 
+#[derive(Debug)]
+pub enum PsbtError {
+    UnknownTransaction(String),
+    TransactionMismatch,
+    UnsupportedInput(usize),
+    MissingSignature(usize),
+    InvalidSignature(usize),
+    Encoding(bitcoin::psbt::Error),
+}
+
+#[derive(Debug)]
+pub enum WalletError {
+    InvalidMnemonic(String),
+    InvalidMasterKey(String),
+    MasterKeyNotDefined,
+    InsufficientFunds { required: Amount, available: Amount },
+}
+
+#[derive(Debug)]
+pub enum SigningSessionError {
+    NotAVerifier,
+    Rejected(String),
+    Aborted(String),
+    Disconnected,
+    TimedOut,
+}
+
+#[derive(Debug)]
+pub enum DataStoreError {
+    Unsigned,
+    UnknownAuthor(PublicKey),
+    UnexpectedAuthor {
+        expected: PublicKey,
+        found: PublicKey,
+    },
+    InvalidSignature(PublicKey),
+    Stale {
+        author: PublicKey,
+        timestamp: u64,
+        file_timestamp: u64,
+    },
+    UnknownTransaction {
+        graph_id: GraphId,
+        tx: String,
+    },
+    InvalidTransactionUpdate {
+        graph_id: GraphId,
+        tx: String,
+    },
+    GraphAlreadyExists(GraphId),
+    UnauthorizedChange {
+        author: PublicKey,
+        graph_id: GraphId,
+    },
+}
+
+#[derive(Debug)]
+pub enum SchemaError {
+    Invalid(String),
+    UnexpectedSchema {
+        expected: SchemaId,
+        found: SchemaId,
+    },
+    UnsupportedVersion {
+        schema: SchemaId,
+        version: u32,
+    },
+    UnsupportedGraphVersion {
+        graph_id: GraphId,
+        version: String,
+    },
+    Migration {
+        schema: SchemaId,
+        version: u32,
+        reason: String,
+    },
+}
+
+/// Reasons the ledger simulator refuses a transaction.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LedgerError {
+    AlreadyKnown(Txid),
+    MissingInput(OutPoint),
+    DoubleSpend {
+        outpoint: OutPoint,
+        spent_by: Txid,
+    },
+    InsufficientInputValue {
+        input_value: Amount,
+        output_value: Amount,
+    },
+    AbsoluteTimelock {
+        lock_time: u32,
+        height: u32,
+    },
+    RelativeTimelock {
+        input_index: usize,
+        required: u32,
+        elapsed: u32,
+    },
+    Script {
+        input_index: usize,
+        reason: String,
+    },
+}
+
+/// Reasons a finalized transaction is refused before it is broadcast.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ValidationError {
+    UnknownPrevout {
+        input_index: usize,
+        outpoint: OutPoint,
+    },
+    MissingWitness {
+        input_index: usize,
+    },
+    Script {
+        input_index: usize,
+        // Last opcode executed, e.g. `OP_CHECKSIGVERIFY`.
+        opcode: Option<String>,
+        reason: String,
+    },
+    WeightTooHigh {
+        weight: u64,
+        max_weight: u64,
+    },
+    ScriptTooLarge {
+        input_index: usize,
+        size: usize,
+        max_size: usize,
+    },
+    TooManyWitnessElements {
+        input_index: usize,
+        count: usize,
+        max_count: usize,
+    },
+    WitnessElementTooLarge {
+        input_index: usize,
+        element_index: usize,
+        size: usize,
+        max_size: usize,
+    },
+    NegativeFee {
+        input_value: Amount,
+        output_value: Amount,
+    },
+    FeeTooLow {
+        fee: Amount,
+        min_fee: Amount,
+    },
+    FeeTooHigh {
+        fee: Amount,
+        max_fee: Amount,
+    },
+}
+
+/// Verifier whose contribution to an n-of-n input failed verification.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Musig2Blame {
+    pub txid: Txid,
+    pub input_index: usize,
+    pub verifier: PublicKey,
+}
+
+#[derive(Debug)]
+pub enum Musig2Error {
+    MissingNonces {
+        txid: Txid,
+        input_index: usize,
+        verifiers: Vec<PublicKey>,
+    },
+    MissingPartialSignatures {
+        txid: Txid,
+        input_index: usize,
+        verifiers: Vec<PublicKey>,
+    },
+    UnknownSigner(Musig2Blame),
+    InvalidNonce(Musig2Blame),
+    InvalidPartialSignatures(Vec<Musig2Blame>),
+    PartialSigning {
+        txid: Txid,
+        input_index: usize,
+    },
+    Aggregation {
+        txid: Txid,
+        input_index: usize,
+    },
+}
+
 #[derive(Debug)]
 pub enum Error {
     Esplora(esplora_client::Error),
@@ -67,6 +278,13 @@ pub enum Error {
     Transaction(TransactionError),
     L2(L2Error),
     Chunker(ChunkerError),
+    Psbt(PsbtError),
+    Wallet(WalletError),
+    SigningSession(SigningSessionError),
+    Musig2(Musig2Error),
+    DataStore(DataStoreError),
+    Schema(SchemaError),
+    Validation(ValidationError),
     Other(&'static str),
 }
 
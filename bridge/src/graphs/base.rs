@@ -2,7 +2,8 @@ use std::collections::HashMap;
 
 use bitcoin::{
     policy::{DEFAULT_MIN_RELAY_TX_FEE, DUST_RELAY_TX_FEE},
-    Network, Transaction, Txid,
+    taproot::TaprootSpendInfo,
+    Network, OutPoint, ScriptBuf, Transaction, TxOut, Txid,
 };
 use esplora_client::{AsyncClient, TxStatus};
 use futures::future::join_all;
@@ -10,12 +11,18 @@ use musig2::SecNonce;
 
 use crate::{
     contexts::verifier::VerifierContext,
-    error::{Error, TransactionError},
-    transactions::base::{
-        MIN_RELAY_FEE_ASSERT_COMMIT1, MIN_RELAY_FEE_ASSERT_COMMIT2, MIN_RELAY_FEE_ASSERT_FINAL,
-        MIN_RELAY_FEE_ASSERT_INITIAL, MIN_RELAY_FEE_DISPROVE, MIN_RELAY_FEE_KICK_OFF_1,
-        MIN_RELAY_FEE_KICK_OFF_2, MIN_RELAY_FEE_PEG_IN_CONFIRM, MIN_RELAY_FEE_PEG_IN_DEPOSIT,
-        MIN_RELAY_FEE_PEG_IN_REFUND, MIN_RELAY_FEE_PEG_OUT_CONFIRM, MIN_RELAY_FEE_START_TIME,
+    error::{Error, TransactionError, ValidationError},
+    parameters::ProtocolParameters,
+    transactions::{
+        base::{
+            MIN_RELAY_FEE_ASSERT_COMMIT1, MIN_RELAY_FEE_ASSERT_COMMIT2, MIN_RELAY_FEE_ASSERT_FINAL,
+            MIN_RELAY_FEE_ASSERT_INITIAL, MIN_RELAY_FEE_DISPROVE, MIN_RELAY_FEE_KICK_OFF_1,
+            MIN_RELAY_FEE_KICK_OFF_2, MIN_RELAY_FEE_PEG_IN_CONFIRM, MIN_RELAY_FEE_PEG_IN_DEPOSIT,
+            MIN_RELAY_FEE_PEG_IN_REFUND, MIN_RELAY_FEE_PEG_OUT_CONFIRM, MIN_RELAY_FEE_START_TIME,
+        },
+        pre_signed::PreSignedTransaction,
+        pre_signed_musig2::PreSignedMusig2Transaction,
+        validation::validate_transaction,
     },
 };
 
@@ -23,6 +30,7 @@ pub const NUM_REQUIRED_OPERATORS: usize = 1;
 
 pub const GRAPH_VERSION: &str = "0.1";
 
+// Defaults for the corresponding `ProtocolParameters` fields.
 //1 btc
 pub const CROWDFUNDING_AMOUNT: f64 = 1.0;
 // for commonly used type in codebase - p2wsh txout
@@ -43,6 +51,18 @@ pub const MIN_RELAY_FEE_ASSERT_SET: u64 = MIN_RELAY_FEE_ASSERT_INITIAL
 // use largest fee from each depth
 // assert fee is big enough to cover disprove chain or take 1
 // disprove fee is big enough to cover take 2
+// `transactions::fee_table::peg_out_fee` sums the same transactions from measured vsizes
+pub const PEG_OUT_FEE_TRANSACTIONS: [&str; 9] = [
+    "PegOutConfirm",
+    "KickOff1",
+    "StartTime",
+    "KickOff2",
+    "AssertInitial",
+    "AssertCommit1",
+    "AssertCommit2",
+    "AssertFinal",
+    "Disprove",
+];
 pub const PEG_OUT_FEE: u64 = MIN_RELAY_FEE_PEG_OUT_CONFIRM // depth 0
     + MIN_RELAY_FEE_KICK_OFF_1 // depth 1
     + MIN_RELAY_FEE_START_TIME // include START_TIME tx, spent in kickoff 1
@@ -57,6 +77,7 @@ pub type GraphId = String;
 pub trait BaseGraph {
     fn network(&self) -> Network;
     fn id(&self) -> &String;
+    fn protocol_parameters(&self) -> &ProtocolParameters;
     fn push_verifier_nonces(
         &mut self,
         verifier_context: &VerifierContext,
@@ -65,7 +86,39 @@ pub trait BaseGraph {
         &mut self,
         verifier_context: &VerifierContext,
         secret_nonces: &HashMap<Txid, HashMap<usize, SecNonce>>,
-    );
+    ) -> Result<(), Error>;
+    /// Transactions of the graph with their `BaseTransaction::name`.
+    fn pre_signed_transactions(&self) -> Vec<(&'static str, &dyn PreSignedTransaction)>;
+    fn pre_signed_transactions_mut(&mut self)
+        -> Vec<(&'static str, &mut dyn PreSignedTransaction)>;
+    /// Transaction of the graph with the given `BaseTransaction::name`.
+    fn pre_signed_transaction(&self, tx_name: &str) -> Option<&dyn PreSignedTransaction> {
+        self.pre_signed_transactions()
+            .into_iter()
+            .find(|(name, _)| *name == tx_name)
+            .map(|(_, tx)| tx)
+    }
+    fn pre_signed_transaction_mut(
+        &mut self,
+        tx_name: &str,
+    ) -> Option<&mut dyn PreSignedTransaction> {
+        self.pre_signed_transactions_mut()
+            .into_iter()
+            .find(|(name, _)| *name == tx_name)
+            .map(|(_, tx)| tx)
+    }
+    /// Transactions of the graph with their `BaseTransaction::name`, spent with dummy witnesses
+    /// of the size of the ones they are broadcast with. See `transactions::fee_table`.
+    fn transaction_templates(&self) -> Vec<(&'static str, Transaction)>;
+    /// Spend info of the taproot connectors whose leaves only require signatures.
+    fn taproot_spend_infos(&self) -> Vec<TaprootSpendInfo>;
+    /// Output scripts of the connectors of the graph, with the connector names.
+    fn connector_script_pubkeys(&self) -> Vec<(String, ScriptBuf)>;
+    /// Transactions with inputs pre-signed by the verifiers' n-of-n MuSig2 key.
+    fn musig2_transactions(&self) -> Vec<&dyn PreSignedMusig2Transaction>;
+    fn musig2_transactions_mut(&mut self) -> Vec<&mut dyn PreSignedMusig2Transaction>;
+    /// Checks the graph against the one its parameters generate, and its MuSig2 nonces.
+    fn validate(&self) -> bool;
 }
 
 pub const fn max(a: u64, b: u64) -> u64 { [a, b][(a < b) as usize] }
@@ -90,7 +143,48 @@ pub async fn is_confirmed(client: &AsyncClient, txid: Txid) -> Result<bool, espl
     tx_status.map(|x| x.confirmed)
 }
 
-pub async fn broadcast_and_verify(
+/// Checks `transaction` with `validate_transaction` and broadcasts it. The outputs it spends
+/// are looked up in `known_prev_outs`, e.g. the outputs of its graph, or else fetched from
+/// `client`, e.g. funding inputs.
+pub async fn validate_and_broadcast(
+    client: &AsyncClient,
+    transaction: &Transaction,
+    known_prev_outs: &HashMap<OutPoint, TxOut>,
+) -> Result<&'static str, Error> {
+    let prev_outs = get_prev_outs(client, transaction, known_prev_outs).await?;
+    validate_transaction(transaction, &prev_outs).map_err(Error::Validation)?;
+
+    broadcast_and_verify(client, transaction).await
+}
+
+async fn get_prev_outs(
+    client: &AsyncClient,
+    transaction: &Transaction,
+    known_prev_outs: &HashMap<OutPoint, TxOut>,
+) -> Result<Vec<TxOut>, Error> {
+    let mut prev_outs = vec![];
+    for (input_index, input) in transaction.input.iter().enumerate() {
+        let outpoint = input.previous_output;
+        let prev_out = match known_prev_outs.get(&outpoint) {
+            Some(prev_out) => Some(prev_out.clone()),
+            None => client
+                .get_tx(&outpoint.txid)
+                .await
+                .map_err(Error::Esplora)?
+                .and_then(|prev_tx| prev_tx.output.get(outpoint.vout as usize).cloned()),
+        };
+        prev_outs.push(
+            prev_out.ok_or(Error::Validation(ValidationError::UnknownPrevout {
+                input_index,
+                outpoint,
+            }))?,
+        );
+    }
+
+    Ok(prev_outs)
+}
+
+async fn broadcast_and_verify(
     client: &AsyncClient,
     transaction: &Transaction,
 ) -> Result<&'static str, Error> {
@@ -0,0 +1,82 @@
+use bitcoin::{Amount, OutPoint, PublicKey, Txid};
+use serde::{Deserialize, Serialize};
+use std::fmt::{Display, Formatter, Result as FmtResult};
+
+/// A crowdfunding input of a challenge transaction that is correctly signed and whose output is
+/// still unspent.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct ChallengeContribution {
+    pub outpoint: OutPoint,
+    pub amount: Amount,
+    pub contributor: PublicKey,
+}
+
+/// Progress of the crowdfunding of the challenge transaction of a peg-out graph. Verifiers append
+/// SIGHASH_ALL|ANYONECANPAY inputs to the challenge transaction in the data store until they
+/// fund it.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub enum ChallengeCrowdfundingStatus {
+    NotOpen, // kick-off 1 not confirmed yet
+    Open { funded: Amount, required: Amount },
+    Funded { funded: Amount, required: Amount }, // challenge can be broadcast
+    Challenged(Txid),
+    Closed, // connector A spent by another transaction, contributions can be reclaimed
+}
+
+impl Display for ChallengeCrowdfundingStatus {
+    fn fmt(&self, f: &mut Formatter) -> FmtResult {
+        match self {
+            ChallengeCrowdfundingStatus::NotOpen => {
+                write!(
+                    f,
+                    "Kick-off 1 not confirmed, challenge not available. Wait..."
+                )
+            }
+            ChallengeCrowdfundingStatus::Open { funded, required } => write!(
+                f,
+                "Challenge crowdfunding open, {} of {} sats funded. Contribute?",
+                funded.to_sat(),
+                required.to_sat()
+            ),
+            ChallengeCrowdfundingStatus::Funded { funded, required } => write!(
+                f,
+                "Challenge funded, {} of {} sats. Broadcast challenge transaction?",
+                funded.to_sat(),
+                required.to_sat()
+            ),
+            ChallengeCrowdfundingStatus::Challenged(txid) => {
+                write!(f, "Challenge transaction {} broadcast. Done.", txid)
+            }
+            ChallengeCrowdfundingStatus::Closed => write!(
+                f,
+                "Challenge window closed. Contributions can be reclaimed from the wallet."
+            ),
+        }
+    }
+}
+
+/// The fewest contributions, largest first, funding the challenge transaction if they do.
+/// `required_amount` is the crowdfunding amount required for a number of contributions, which
+/// grows with the fee of every input.
+pub fn select_contributions(
+    contributions: &[ChallengeContribution],
+    required_amount: impl Fn(usize) -> Amount,
+) -> Option<Vec<ChallengeContribution>> {
+    let mut selected = contributions.to_vec();
+    selected.sort_by(|a, b| {
+        b.amount
+            .cmp(&a.amount)
+            .then_with(|| a.outpoint.cmp(&b.outpoint))
+    });
+
+    let mut funded = Amount::ZERO;
+    for (index, contribution) in selected.iter().enumerate() {
+        funded += contribution.amount;
+        if funded >= required_amount(index + 1) {
+            selected.truncate(index + 1);
+            return Some(selected);
+        }
+    }
+
+    None
+}
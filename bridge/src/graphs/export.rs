@@ -0,0 +1,242 @@
+use std::fmt::Write;
+
+use bitcoin::{relative::LockTime, Amount, Txid};
+use esplora_client::AsyncClient;
+
+use super::base::{get_tx_statuses, BaseGraph};
+
+/// Text formats a graph can be exported to.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum GraphExportFormat {
+    Dot,
+    Mermaid,
+}
+
+impl GraphExportFormat {
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "dot" => Some(GraphExportFormat::Dot),
+            "mermaid" => Some(GraphExportFormat::Mermaid),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum TxExportStatus {
+    // Not looked up.
+    Unknown,
+    // Not confirmed, possibly not broadcast yet.
+    Unconfirmed,
+    Confirmed,
+}
+
+/// A transaction of the graph, or an outside transaction one of them spends.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct TxNode {
+    pub txid: Txid,
+    // `BaseTransaction::name` of the transaction, `None` outside of the graph.
+    pub name: Option<&'static str>,
+    pub status: TxExportStatus,
+}
+
+/// An output of `from` spent by `to`.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct SpendEdge {
+    pub from: Txid,
+    pub vout: u32,
+    pub to: Txid,
+    pub connector: Option<String>,
+    pub amount: Option<Amount>,
+    // Relative timelock of the spending input, in blocks.
+    pub timelock: Option<u16>,
+}
+
+/// The transactions of a peg-in or peg-out graph and the outputs they spend from each other.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct GraphExport {
+    pub id: String,
+    pub nodes: Vec<TxNode>,
+    pub edges: Vec<SpendEdge>,
+}
+
+impl GraphExport {
+    pub fn new(graph: &dyn BaseGraph) -> Self {
+        let transactions = graph.pre_signed_transactions();
+        let connector_script_pubkeys = graph.connector_script_pubkeys();
+
+        let mut nodes: Vec<TxNode> = transactions
+            .iter()
+            .map(|(name, tx)| TxNode {
+                txid: tx.tx().compute_txid(),
+                name: Some(*name),
+                status: TxExportStatus::Unknown,
+            })
+            .collect();
+        let mut edges = vec![];
+        for (_, tx) in transactions.iter() {
+            let txid = tx.tx().compute_txid();
+            for (input_index, input) in tx.tx().input.iter().enumerate() {
+                let outpoint = input.previous_output;
+                let output = transactions
+                    .iter()
+                    .find(|(_, source)| source.tx().compute_txid() == outpoint.txid)
+                    .and_then(|(_, source)| source.tx().output.get(outpoint.vout as usize))
+                    .or_else(|| tx.prev_outs().get(input_index));
+                if !nodes.iter().any(|node| node.txid == outpoint.txid) {
+                    nodes.push(TxNode {
+                        txid: outpoint.txid,
+                        name: None,
+                        status: TxExportStatus::Unknown,
+                    });
+                }
+
+                edges.push(SpendEdge {
+                    from: outpoint.txid,
+                    vout: outpoint.vout,
+                    to: txid,
+                    connector: output.and_then(|output| {
+                        connector_script_pubkeys
+                            .iter()
+                            .find(|(_, script_pubkey)| *script_pubkey == output.script_pubkey)
+                            .map(|(name, _)| name.clone())
+                    }),
+                    amount: output.map(|output| output.value),
+                    timelock: match input.sequence.to_relative_lock_time() {
+                        Some(LockTime::Blocks(height)) if height.value() > 0 => {
+                            Some(height.value())
+                        }
+                        _ => None,
+                    },
+                });
+            }
+        }
+
+        GraphExport {
+            id: graph.id().clone(),
+            nodes,
+            edges,
+        }
+    }
+
+    /// Looks up the confirmation status of the transactions, in the graph or not.
+    pub async fn fetch_statuses(&mut self, client: &AsyncClient) {
+        let txids: Vec<Txid> = self.nodes.iter().map(|node| node.txid).collect();
+        let statuses = get_tx_statuses(client, &txids).await;
+        for (node, status) in self.nodes.iter_mut().zip(statuses) {
+            node.status = match status {
+                Ok(status) if status.confirmed => TxExportStatus::Confirmed,
+                Ok(_) => TxExportStatus::Unconfirmed,
+                Err(_) => TxExportStatus::Unknown,
+            };
+        }
+    }
+
+    pub fn render(&self, format: GraphExportFormat) -> String {
+        match format {
+            GraphExportFormat::Dot => self.to_dot(),
+            GraphExportFormat::Mermaid => self.to_mermaid(),
+        }
+    }
+
+    /// Graphviz DOT source of the graph.
+    pub fn to_dot(&self) -> String {
+        let mut dot = String::new();
+        writeln!(dot, "digraph \"{}\" {{", self.id).unwrap();
+        writeln!(dot, "  rankdir=TB;").unwrap();
+        writeln!(dot, "  node [shape=box, style=\"rounded,filled\"];").unwrap();
+        for node in self.nodes.iter() {
+            let color = match node.status {
+                TxExportStatus::Confirmed => "palegreen",
+                TxExportStatus::Unconfirmed => "lightyellow",
+                TxExportStatus::Unknown if node.name.is_some() => "white",
+                TxExportStatus::Unknown => "lightgrey",
+            };
+            writeln!(
+                dot,
+                "  \"{}\" [label=\"{}\\n{}\", fillcolor={}];",
+                node.txid,
+                node_title(node),
+                short_txid(&node.txid),
+                color
+            )
+            .unwrap();
+        }
+        for edge in self.edges.iter() {
+            writeln!(
+                dot,
+                "  \"{}\" -> \"{}\" [label=\"{}\"];",
+                edge.from,
+                edge.to,
+                edge_label(edge, "\\n")
+            )
+            .unwrap();
+        }
+        dot.push_str("}\n");
+
+        dot
+    }
+
+    /// Mermaid flowchart source of the graph.
+    pub fn to_mermaid(&self) -> String {
+        let mut mermaid = String::new();
+        writeln!(mermaid, "flowchart TD").unwrap();
+        for node in self.nodes.iter() {
+            writeln!(
+                mermaid,
+                "  {}[\"{}<br/>{}\"]",
+                mermaid_id(&node.txid),
+                node_title(node),
+                short_txid(&node.txid)
+            )
+            .unwrap();
+        }
+        for edge in self.edges.iter() {
+            writeln!(
+                mermaid,
+                "  {} -->|\"{}\"| {}",
+                mermaid_id(&edge.from),
+                edge_label(edge, "<br/>"),
+                mermaid_id(&edge.to)
+            )
+            .unwrap();
+        }
+        writeln!(mermaid, "  classDef confirmed fill:#98fb98").unwrap();
+        writeln!(mermaid, "  classDef unconfirmed fill:#ffffe0").unwrap();
+        writeln!(mermaid, "  classDef external fill:#d3d3d3").unwrap();
+        for node in self.nodes.iter() {
+            let class = match node.status {
+                TxExportStatus::Confirmed => "confirmed",
+                TxExportStatus::Unconfirmed => "unconfirmed",
+                TxExportStatus::Unknown if node.name.is_none() => "external",
+                TxExportStatus::Unknown => continue,
+            };
+            writeln!(mermaid, "  class {} {}", mermaid_id(&node.txid), class).unwrap();
+        }
+
+        mermaid
+    }
+}
+
+fn node_title(node: &TxNode) -> &'static str { node.name.unwrap_or("External") }
+
+fn short_txid(txid: &Txid) -> String { txid.to_string()[..8].to_string() }
+
+// Mermaid node IDs can't start with a digit.
+fn mermaid_id(txid: &Txid) -> String { format!("tx_{txid}") }
+
+fn edge_label(edge: &SpendEdge, separator: &str) -> String {
+    let mut lines = vec![format!(
+        "{}:{}",
+        edge.connector.as_deref().unwrap_or("output"),
+        edge.vout
+    )];
+    if let Some(amount) = edge.amount {
+        lines.push(format!("{} sats", amount.to_sat()));
+    }
+    if let Some(timelock) = edge.timelock {
+        lines.push(format!("after {timelock} blocks"));
+    }
+
+    lines.join(separator)
+}
@@ -1,3 +1,6 @@
 pub mod base;
+pub mod challenge_crowdfunding;
+pub mod export;
+pub mod operator_set;
 pub mod peg_in;
 pub mod peg_out;
@@ -0,0 +1,161 @@
+use std::collections::HashMap;
+
+use bitcoin::PublicKey;
+use serde::{Deserialize, Serialize};
+
+use super::{
+    base::{BaseGraph, GraphId, NUM_REQUIRED_OPERATORS},
+    peg_out::PegOutGraph,
+};
+
+/// How the one peg-out graph of a peg-in that may reach take 1 or take 2 is selected.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum OperatorSelectionPolicy {
+    /// The graph of the operator named by the peg-out event, i.e. the operator that fronted the
+    /// withdrawal. The earliest event wins if several operators were named.
+    #[default]
+    PegOutEvent,
+    /// The graph whose kick-off 1 confirmed first, for deployments without L2 peg-out events.
+    FirstKickOff,
+}
+
+impl OperatorSelectionPolicy {
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "peg-out-event" => Some(OperatorSelectionPolicy::PegOutEvent),
+            "first-kick-off" => Some(OperatorSelectionPolicy::FirstKickOff),
+            _ => None,
+        }
+    }
+}
+
+/// Operators expected to create a peg-out graph for every peg-in. An empty set accepts the
+/// peg-out graphs of any operator and requires `NUM_REQUIRED_OPERATORS` of them.
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq, Eq)]
+pub struct OperatorSet {
+    pub operator_public_keys: Vec<PublicKey>,
+    pub selection_policy: OperatorSelectionPolicy,
+}
+
+impl OperatorSet {
+    pub fn new(
+        operator_public_keys: &[PublicKey],
+        selection_policy: OperatorSelectionPolicy,
+    ) -> Self {
+        OperatorSet {
+            operator_public_keys: operator_public_keys.to_vec(),
+            selection_policy,
+        }
+    }
+
+    pub fn contains(&self, operator_public_key: &PublicKey) -> bool {
+        self.operator_public_keys.is_empty()
+            || self.operator_public_keys.contains(operator_public_key)
+    }
+
+    /// Peg-out graphs of `peg_outs` created by operators of the set.
+    pub fn members_peg_out_graphs<'a>(&self, peg_outs: &[&'a PegOutGraph]) -> Vec<&'a PegOutGraph> {
+        peg_outs
+            .iter()
+            .filter(|peg_out| self.contains(peg_out.operator_public_key()))
+            .copied()
+            .collect()
+    }
+
+    /// Operators of the set that haven't created a peg-out graph in `peg_outs` yet.
+    pub fn missing_operators(&self, peg_outs: &[&PegOutGraph]) -> Vec<PublicKey> {
+        self.operator_public_keys
+            .iter()
+            .filter(|operator_public_key| {
+                !peg_outs
+                    .iter()
+                    .any(|peg_out| peg_out.operator_public_key() == *operator_public_key)
+            })
+            .cloned()
+            .collect()
+    }
+
+    /// Whether `peg_outs`, the peg-out graphs of a peg-in, include one of every operator of the
+    /// set.
+    pub fn has_all_peg_out_graphs(&self, peg_outs: &[&PegOutGraph]) -> bool {
+        if self.operator_public_keys.is_empty() {
+            peg_outs.len() >= NUM_REQUIRED_OPERATORS
+        } else {
+            self.missing_operators(peg_outs).is_empty()
+        }
+    }
+
+    /// The peg-out graph of `peg_outs`, the peg-out graphs of a peg-in, selected by the policy.
+    /// `kick_off_1_heights` maps the graphs whose kick-off 1 is confirmed to its block height.
+    pub fn select_peg_out_graph<'a>(
+        &self,
+        peg_outs: &[&'a PegOutGraph],
+        kick_off_1_heights: &HashMap<GraphId, u32>,
+    ) -> Option<&'a PegOutGraph> {
+        let members_peg_outs = self.members_peg_out_graphs(peg_outs);
+        match self.selection_policy {
+            OperatorSelectionPolicy::PegOutEvent => members_peg_outs
+                .into_iter()
+                .filter_map(|peg_out| {
+                    peg_out
+                        .peg_out_chain_event
+                        .as_ref()
+                        .map(|event| (event.timestamp, peg_out))
+                })
+                .min_by(|(timestamp_a, peg_out_a), (timestamp_b, peg_out_b)| {
+                    (timestamp_a, peg_out_a.id()).cmp(&(timestamp_b, peg_out_b.id()))
+                })
+                .map(|(_, peg_out)| peg_out),
+            OperatorSelectionPolicy::FirstKickOff => members_peg_outs
+                .into_iter()
+                .filter_map(|peg_out| {
+                    kick_off_1_heights
+                        .get(peg_out.id())
+                        .map(|height| (*height, peg_out))
+                })
+                .min_by(|(height_a, peg_out_a), (height_b, peg_out_b)| {
+                    (height_a, peg_out_a.id()).cmp(&(height_b, peg_out_b.id()))
+                })
+                .map(|(_, peg_out)| peg_out),
+        }
+    }
+
+    /// Peg-out graphs of `peg_outs` that kicked off without being selected, including those of
+    /// operators outside of the set. They must be challenged, so that only the selected graph
+    /// reaches take 1 or take 2. None are contested until a graph is selected, as the graph of
+    /// the operator that fronted the withdrawal may kick off before its peg-out event is seen.
+    pub fn contested_peg_out_graphs<'a>(
+        &self,
+        peg_outs: &[&'a PegOutGraph],
+        kick_off_1_heights: &HashMap<GraphId, u32>,
+    ) -> Vec<&'a PegOutGraph> {
+        let Some(selected) = self.select_peg_out_graph(peg_outs, kick_off_1_heights) else {
+            return vec![];
+        };
+        peg_outs
+            .iter()
+            .filter(|peg_out| {
+                kick_off_1_heights.contains_key(peg_out.id()) && peg_out.id() != selected.id()
+            })
+            .copied()
+            .collect()
+    }
+
+    /// Whether the operator of `peg_out`, one of `peg_outs`, may kick it off: it must be the
+    /// selected graph or, with `FirstKickOff`, a graph of an operator of the set while none
+    /// kicked off yet.
+    pub fn may_kick_off(
+        &self,
+        peg_out: &PegOutGraph,
+        peg_outs: &[&PegOutGraph],
+        kick_off_1_heights: &HashMap<GraphId, u32>,
+    ) -> bool {
+        match self.select_peg_out_graph(peg_outs, kick_off_1_heights) {
+            Some(selected) => selected.id() == peg_out.id(),
+            None => {
+                self.selection_policy == OperatorSelectionPolicy::FirstKickOff
+                    && self.contains(peg_out.operator_public_key())
+            }
+        }
+    }
+}
@@ -1,6 +1,7 @@
 use bitcoin::{
     hex::{Case::Upper, DisplayHex},
-    Network, OutPoint, PublicKey, Transaction, Txid, XOnlyPublicKey,
+    taproot::TaprootSpendInfo,
+    Network, OutPoint, PublicKey, ScriptBuf, TapSighashType, Transaction, Txid, XOnlyPublicKey,
 };
 use esplora_client::{AsyncClient, TxStatus};
 use itertools::Itertools;
@@ -18,28 +19,36 @@ use crate::{
         query::GraphCliQuery, query_contexts::depositor_signatures::DepositorSignatures,
     },
     error::{Error, GraphError, NamedTx},
+    parameters::ProtocolParameters,
     transactions::pre_signed_musig2::PreSignedMusig2Transaction,
 };
 
 use super::{
     super::{
-        connectors::{connector_0::Connector0, connector_z::ConnectorZ},
+        connectors::{base::TaprootConnector, connector_0::Connector0, connector_z::ConnectorZ},
         contexts::{depositor::DepositorContext, verifier::VerifierContext},
         transactions::{
             base::{validate_transaction, verify_public_nonces_for_tx, BaseTransaction, Input},
+            fee_table::{
+                dummy_p2wsh_witness, dummy_schnorr_signature, dummy_script_path_witness,
+                with_witnesses,
+            },
             peg_in_confirm::PegInConfirmTransaction,
             peg_in_deposit::PegInDepositTransaction,
             peg_in_refund::PegInRefundTransaction,
             pre_signed::PreSignedTransaction,
+            validation::graph_prev_outs,
         },
     },
     base::{
-        get_tx_statuses, verify_if_not_mined, BaseGraph, GraphId, GRAPH_VERSION,
-        NUM_REQUIRED_OPERATORS,
+        get_tx_statuses, validate_and_broadcast, verify_if_not_mined, BaseGraph, GraphId,
+        GRAPH_VERSION,
     },
+    operator_set::OperatorSet,
     peg_out::{PegOutGraph, PegOutId},
 };
 
+#[derive(Serialize, Deserialize, PartialEq, Clone, Debug)]
 pub enum PegInDepositorStatus {
     PegInDepositWait,     // peg-in deposit not yet confirmed
     PegInConfirmWait, // peg-in confirm not yet confirmed, wait for operator to complete peg-in, refund not available yet
@@ -70,7 +79,7 @@ impl Display for PegInDepositorStatus {
     }
 }
 
-#[derive(Debug, PartialEq)]
+#[derive(Serialize, Deserialize, PartialEq, Clone, Debug)]
 pub enum PegInVerifierStatus {
     AwaitingDeposit,                   // no action required, wait
     AwaitingPegOutCreation,            // need operator(s) to come online to create peg-out grah
@@ -114,6 +123,7 @@ impl Display for PegInVerifierStatus {
     }
 }
 
+#[derive(Serialize, Deserialize, PartialEq, Clone, Debug)]
 pub enum PegInOperatorStatus {
     PegInWait,             // peg-in not yet complete, no action required yet, wait
     PegInConfirmAvailable, // should execute peg-in confirm
@@ -137,6 +147,73 @@ impl Display for PegInOperatorStatus {
     }
 }
 
+/// Progress of the recovery of a deposit whose peg-in confirm transaction doesn't get the n-of-n
+/// signatures in time. Heights are block heights at which the status changes.
+#[derive(Serialize, Deserialize, PartialEq, Clone, Debug)]
+pub enum PegInRecoveryStatus {
+    AwaitingDeposit,                      // peg-in deposit not yet confirmed
+    AwaitingSignatures { deadline: u32 }, // peg-in confirm not yet signed, deadline not reached
+    AwaitingConfirm,                      // peg-in confirm signed, wait for peg-in to complete
+    Stuck { refund_height: u32 },         // signing deadline passed, refund scheduled
+    RefundAvailable,                      // should execute peg-in refund
+    RefundPending(Txid),                  // peg-in refund broadcast, not yet confirmed
+    Refunded { block_height: u32 },       // peg-in failed, refund complete
+    PegInComplete,                        // peg-in complete, nothing to recover
+}
+
+impl PegInRecoveryStatus {
+    /// Whether the recovery still requires watching the deposit.
+    pub fn is_pending(&self) -> bool {
+        !matches!(
+            self,
+            PegInRecoveryStatus::AwaitingConfirm
+                | PegInRecoveryStatus::Refunded { .. }
+                | PegInRecoveryStatus::PegInComplete
+        )
+    }
+}
+
+impl Display for PegInRecoveryStatus {
+    fn fmt(&self, f: &mut Formatter) -> FmtResult {
+        match self {
+            PegInRecoveryStatus::AwaitingDeposit => {
+                write!(f, "Peg-in deposit transaction not confirmed yet. Wait...")
+            }
+            PegInRecoveryStatus::AwaitingSignatures { deadline } => write!(
+                f,
+                "Awaiting peg-in confirm signatures until block {}. Wait...",
+                deadline
+            ),
+            PegInRecoveryStatus::AwaitingConfirm => write!(
+                f,
+                "Peg-in confirm transaction signed, no recovery needed. Wait for peg-in..."
+            ),
+            PegInRecoveryStatus::Stuck { refund_height } => write!(
+                f,
+                "Peg-in stuck without signatures. Refund scheduled at block {}. Wait...",
+                refund_height
+            ),
+            PegInRecoveryStatus::RefundAvailable => {
+                write!(
+                    f,
+                    "Peg-in stuck and timed out. Broadcast refund transaction?"
+                )
+            }
+            PegInRecoveryStatus::RefundPending(txid) => write!(
+                f,
+                "Peg-in refund transaction {} not confirmed yet. Wait...",
+                txid
+            ),
+            PegInRecoveryStatus::Refunded { block_height } => write!(
+                f,
+                "Peg-in refund confirmed in block {}, funds reclaimed. Done.",
+                block_height
+            ),
+            PegInRecoveryStatus::PegInComplete => write!(f, "Peg-in complete. Done."),
+        }
+    }
+}
+
 struct PegInConnectors {
     connector_0: Connector0,
     connector_z: ConnectorZ,
@@ -147,6 +224,11 @@ pub struct PegInGraph {
     version: String,
     network: Network,
     id: String,
+    protocol_parameters: ProtocolParameters,
+    // Graphs created before the protocol parameters were configurable have an ID not committing
+    // to them, see `generate_legacy_id`.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    legacy_id: bool,
 
     pub peg_in_deposit_transaction: PegInDepositTransaction,
     pub peg_in_refund_transaction: PegInRefundTransaction,
@@ -171,16 +253,18 @@ impl BaseGraph for PegInGraph {
 
     fn id(&self) -> &String { &self.id }
 
+    fn protocol_parameters(&self) -> &ProtocolParameters { &self.protocol_parameters }
+
     fn verifier_sign(
         &mut self,
         verifier_context: &VerifierContext,
         secret_nonces: &HashMap<Txid, HashMap<usize, SecNonce>>,
-    ) {
+    ) -> Result<(), Error> {
         self.peg_in_confirm_transaction.pre_sign(
             verifier_context,
             &self.connector_z,
             &secret_nonces[&self.peg_in_confirm_transaction.tx().compute_txid()],
-        );
+        )
     }
 
     fn push_verifier_nonces(
@@ -194,15 +278,171 @@ impl BaseGraph for PegInGraph {
         )]
         .into()
     }
+
+    fn pre_signed_transactions(&self) -> Vec<(&'static str, &dyn PreSignedTransaction)> {
+        vec![
+            (
+                self.peg_in_deposit_transaction.name(),
+                &self.peg_in_deposit_transaction,
+            ),
+            (
+                self.peg_in_refund_transaction.name(),
+                &self.peg_in_refund_transaction,
+            ),
+            (
+                self.peg_in_confirm_transaction.name(),
+                &self.peg_in_confirm_transaction,
+            ),
+        ]
+    }
+
+    fn pre_signed_transactions_mut(
+        &mut self,
+    ) -> Vec<(&'static str, &mut dyn PreSignedTransaction)> {
+        vec![
+            (
+                self.peg_in_deposit_transaction.name(),
+                &mut self.peg_in_deposit_transaction,
+            ),
+            (
+                self.peg_in_refund_transaction.name(),
+                &mut self.peg_in_refund_transaction,
+            ),
+            (
+                self.peg_in_confirm_transaction.name(),
+                &mut self.peg_in_confirm_transaction,
+            ),
+        ]
+    }
+
+    fn transaction_templates(&self) -> Vec<(&'static str, Transaction)> {
+        let connector_z_spend_info = self.connector_z.generate_taproot_spend_info();
+        let deposit = &self.peg_in_deposit_transaction;
+        let refund = &self.peg_in_refund_transaction;
+        let confirm = &self.peg_in_confirm_transaction;
+
+        vec![
+            (
+                deposit.name(),
+                with_witnesses(
+                    deposit.tx(),
+                    vec![dummy_p2wsh_witness(&deposit.prev_scripts()[0], 1)],
+                ),
+            ),
+            (
+                refund.name(),
+                with_witnesses(
+                    refund.tx(),
+                    vec![dummy_script_path_witness(
+                        &connector_z_spend_info,
+                        &refund.prev_scripts()[0],
+                        vec![dummy_schnorr_signature(TapSighashType::All)],
+                    )],
+                ),
+            ),
+            (
+                confirm.name(),
+                with_witnesses(
+                    confirm.tx(),
+                    // depositor and n-of-n signatures
+                    vec![dummy_script_path_witness(
+                        &connector_z_spend_info,
+                        &confirm.prev_scripts()[0],
+                        vec![dummy_schnorr_signature(TapSighashType::All); 2],
+                    )],
+                ),
+            ),
+        ]
+    }
+
+    fn taproot_spend_infos(&self) -> Vec<TaprootSpendInfo> {
+        vec![
+            self.connector_0.generate_taproot_spend_info(),
+            self.connector_z.generate_taproot_spend_info(),
+        ]
+    }
+
+    fn connector_script_pubkeys(&self) -> Vec<(String, ScriptBuf)> {
+        vec![
+            (
+                "Connector 0".to_string(),
+                self.connector_0.generate_taproot_address().script_pubkey(),
+            ),
+            (
+                "Connector Z".to_string(),
+                self.connector_z.generate_taproot_address().script_pubkey(),
+            ),
+        ]
+    }
+
+    fn musig2_transactions(&self) -> Vec<&dyn PreSignedMusig2Transaction> {
+        vec![&self.peg_in_confirm_transaction]
+    }
+
+    fn musig2_transactions_mut(&mut self) -> Vec<&mut dyn PreSignedMusig2Transaction> {
+        vec![&mut self.peg_in_confirm_transaction]
+    }
+
+    fn validate(&self) -> bool {
+        if !self.protocol_parameters.validate() {
+            return false;
+        }
+        // Legacy graphs were only created with the default parameters of their network.
+        if self.legacy_id
+            && self.protocol_parameters != ProtocolParameters::for_network(self.network)
+        {
+            return false;
+        }
+
+        let mut ret_val = true;
+        let peg_in_graph = self.new_for_validation();
+        let id = match self.legacy_id {
+            true => generate_legacy_id(&peg_in_graph.peg_in_deposit_transaction),
+            false => peg_in_graph.id.clone(),
+        };
+        if self.id != id {
+            ret_val = false;
+        }
+        if !validate_transaction(
+            self.peg_in_deposit_transaction.tx(),
+            peg_in_graph.peg_in_deposit_transaction.tx(),
+        ) {
+            ret_val = false;
+        }
+        if !validate_transaction(
+            self.peg_in_refund_transaction.tx(),
+            peg_in_graph.peg_in_refund_transaction.tx(),
+        ) {
+            ret_val = false;
+        }
+        if !validate_transaction(
+            self.peg_in_confirm_transaction.tx(),
+            peg_in_graph.peg_in_confirm_transaction.tx(),
+        ) {
+            ret_val = false;
+        }
+
+        if !verify_public_nonces_for_tx(&self.peg_in_confirm_transaction) {
+            ret_val = false;
+        }
+
+        ret_val
+    }
 }
 
 impl PegInGraph {
-    pub fn new(context: &DepositorContext, deposit_input: Input, evm_address: &str) -> Self {
+    pub fn new(
+        context: &DepositorContext,
+        deposit_input: Input,
+        evm_address: &str,
+        protocol_parameters: &ProtocolParameters,
+    ) -> Self {
         let connectors = create_new_connectors(
             context.network,
             &context.n_of_n_taproot_public_key,
             &context.depositor_taproot_public_key,
             evm_address,
+            protocol_parameters,
         );
 
         let peg_in_deposit_transaction =
@@ -226,7 +466,9 @@ impl PegInGraph {
         PegInGraph {
             version: GRAPH_VERSION.to_string(),
             network: context.network,
-            id: generate_id(&peg_in_deposit_transaction),
+            id: generate_id(&peg_in_deposit_transaction, protocol_parameters),
+            protocol_parameters: *protocol_parameters,
+            legacy_id: false,
             peg_in_deposit_transaction,
             peg_in_refund_transaction,
             peg_in_confirm_transaction,
@@ -252,6 +494,7 @@ impl PegInGraph {
         n_of_n_taproot_public_key: &XOnlyPublicKey,
         depositor_evm_address: &str,
         deposit_input: Input,
+        protocol_parameters: &ProtocolParameters,
     ) -> Self {
         create_graph_without_signing(
             network,
@@ -262,6 +505,7 @@ impl PegInGraph {
             n_of_n_taproot_public_key,
             depositor_evm_address,
             deposit_input,
+            protocol_parameters,
         )
     }
 
@@ -276,12 +520,14 @@ impl PegInGraph {
         depositor_evm_address: &str,
         deposit_input: Input,
         signatures: &DepositorSignatures,
+        protocol_parameters: &ProtocolParameters,
     ) -> Self {
         let connectors = create_new_connectors(
             network,
             n_of_n_taproot_public_key,
             depositor_taproot_public_key,
             depositor_evm_address,
+            protocol_parameters,
         );
 
         let peg_in_deposit_transaction = PegInDepositTransaction::new_with_signature(
@@ -313,7 +559,9 @@ impl PegInGraph {
         PegInGraph {
             version: GRAPH_VERSION.to_string(),
             network,
-            id: generate_id(&peg_in_deposit_transaction),
+            id: generate_id(&peg_in_deposit_transaction, protocol_parameters),
+            protocol_parameters: *protocol_parameters,
+            legacy_id: false,
             peg_in_deposit_transaction,
             peg_in_refund_transaction,
             peg_in_confirm_transaction,
@@ -342,6 +590,7 @@ impl PegInGraph {
                 outpoint: self.peg_in_deposit_transaction.tx().input[0].previous_output, // Self-referencing
                 amount: self.peg_in_deposit_transaction.prev_outs()[0].value, // Self-referencing
             },
+            &self.protocol_parameters,
         )
     }
 
@@ -354,6 +603,24 @@ impl PegInGraph {
         client: &AsyncClient,
         verifier_context: &VerifierContext,
         peg_outs: &[&PegOutGraph],
+    ) -> PegInVerifierStatus {
+        self.verifier_status_for_operators(
+            client,
+            verifier_context,
+            peg_outs,
+            &OperatorSet::default(),
+        )
+        .await
+    }
+
+    /// Like `verifier_status`, but waits for a peg-out graph of every operator of
+    /// `operator_set` instead of `NUM_REQUIRED_OPERATORS` graphs of any operators.
+    pub async fn verifier_status_for_operators(
+        &self,
+        client: &AsyncClient,
+        verifier_context: &VerifierContext,
+        peg_outs: &[&PegOutGraph],
+        operator_set: &OperatorSet,
     ) -> PegInVerifierStatus {
         // check that the supplied peg out graphs match our expectation
         let supplied_peg_out_ids = peg_outs.iter().map(|x| x.id()).sorted().collect::<Vec<_>>();
@@ -370,7 +637,7 @@ impl PegInGraph {
             return PegInVerifierStatus::AwaitingDeposit;
         }
 
-        if peg_outs.len() < NUM_REQUIRED_OPERATORS {
+        if !operator_set.has_all_peg_out_graphs(&operator_set.members_peg_out_graphs(peg_outs)) {
             return PegInVerifierStatus::AwaitingPegOutCreation;
         }
 
@@ -500,6 +767,63 @@ impl PegInGraph {
         )
     }
 
+    /// Recovery status of the deposit. The peg-in is stuck once the peg-in confirm transaction
+    /// is still missing n-of-n signatures `signing_deadline` blocks after the deposit confirmed,
+    /// in which case the deposit can be refunded when the connector Z timelock matures.
+    pub async fn recovery_status(
+        &self,
+        client: &AsyncClient,
+        signing_deadline: u32,
+    ) -> Result<PegInRecoveryStatus, Error> {
+        let (peg_in_deposit_status, peg_in_confirm_status, peg_in_refund_status) =
+            self.get_peg_in_statuses(client).await;
+
+        let deposit_height = match peg_in_deposit_status.map_err(Error::Esplora)? {
+            TxStatus {
+                confirmed: true,
+                block_height: Some(block_height),
+                ..
+            } => block_height,
+            _ => return Ok(PegInRecoveryStatus::AwaitingDeposit),
+        };
+        if peg_in_confirm_status.map_err(Error::Esplora)?.confirmed {
+            return Ok(PegInRecoveryStatus::PegInComplete);
+        }
+        if let TxStatus {
+            confirmed: true,
+            block_height: Some(block_height),
+            ..
+        } = peg_in_refund_status.map_err(Error::Esplora)?
+        {
+            return Ok(PegInRecoveryStatus::Refunded { block_height });
+        }
+
+        // Unconfirmed and unknown transactions share the same status, only a known one is pending.
+        let refund_txid = self.peg_in_refund_transaction.tx().compute_txid();
+        if client
+            .get_tx(&refund_txid)
+            .await
+            .map_err(Error::Esplora)?
+            .is_some()
+        {
+            return Ok(PegInRecoveryStatus::RefundPending(refund_txid));
+        }
+        if self.peg_in_confirm_transaction.has_all_signatures() {
+            return Ok(PegInRecoveryStatus::AwaitingConfirm);
+        }
+
+        let height = client.get_height().await.map_err(Error::Esplora)?;
+        let deadline = deposit_height + signing_deadline;
+        let refund_height = deposit_height + self.connector_z.num_blocks_timelock_0;
+        if refund_height <= height {
+            Ok(PegInRecoveryStatus::RefundAvailable)
+        } else if deadline <= height {
+            Ok(PegInRecoveryStatus::Stuck { refund_height })
+        } else {
+            Ok(PegInRecoveryStatus::AwaitingSignatures { deadline })
+        }
+    }
+
     pub async fn deposit(&mut self, client: &AsyncClient) -> Result<Transaction, Error> {
         let txid = self.peg_in_deposit_transaction.tx().compute_txid();
         verify_if_not_mined(client, txid).await?;
@@ -569,43 +893,18 @@ impl PegInGraph {
         )
     }
 
-    pub fn validate(&self) -> bool {
-        let mut ret_val = true;
-        let peg_in_graph = self.new_for_validation();
-        if !validate_transaction(
-            self.peg_in_deposit_transaction.tx(),
-            peg_in_graph.peg_in_deposit_transaction.tx(),
-        ) {
-            ret_val = false;
-        }
-        if !validate_transaction(
-            self.peg_in_refund_transaction.tx(),
-            peg_in_graph.peg_in_refund_transaction.tx(),
-        ) {
-            ret_val = false;
-        }
-        if !validate_transaction(
-            self.peg_in_confirm_transaction.tx(),
-            peg_in_graph.peg_in_confirm_transaction.tx(),
-        ) {
-            ret_val = false;
-        }
-
-        if !verify_public_nonces_for_tx(&self.peg_in_confirm_transaction) {
-            ret_val = false;
-        }
-
-        ret_val
-    }
-
-    pub fn merge(&mut self, source_peg_in_graph: &PegInGraph) {
-        self.peg_in_confirm_transaction
-            .merge(&source_peg_in_graph.peg_in_confirm_transaction);
-
+    /// Merges `source_peg_in_graph` into `self`. The peg-out graph IDs are merged even if
+    /// MuSig2 nonces or partial signatures are refused, see `PegInConfirmTransaction::merge`.
+    pub fn merge(&mut self, source_peg_in_graph: &PegInGraph) -> Result<(), Error> {
         self.peg_out_graphs
             .extend(source_peg_in_graph.peg_out_graphs.clone());
         self.peg_out_graphs.sort();
         self.peg_out_graphs.dedup();
+
+        self.peg_in_confirm_transaction.merge(
+            &source_peg_in_graph.peg_in_confirm_transaction,
+            &self.n_of_n_public_keys,
+        )
     }
 }
 
@@ -620,8 +919,10 @@ impl GraphCliQuery for PegInGraph {
                     false => {
                         // complete deposit tx
                         let deposit_tx = self.peg_in_deposit_transaction.finalize();
-                        // broadcast deposit tx
-                        let deposit_result = client.broadcast(&deposit_tx).await;
+                        // validate and broadcast deposit tx
+                        let deposit_result =
+                            validate_and_broadcast(client, &deposit_tx, &graph_prev_outs(self))
+                                .await;
                         match deposit_result {
                             Ok(_) => Ok(()),
                             Err(e) => Err(e.to_string()),
@@ -634,7 +935,21 @@ impl GraphCliQuery for PegInGraph {
     }
 }
 
-pub fn generate_id(peg_in_deposit_transaction: &PegInDepositTransaction) -> String {
+pub fn generate_id(
+    peg_in_deposit_transaction: &PegInDepositTransaction,
+    protocol_parameters: &ProtocolParameters,
+) -> String {
+    let mut hasher = Sha256::new();
+
+    hasher.update(peg_in_deposit_transaction.tx().compute_txid().to_string());
+    hasher.update(protocol_parameters.hash());
+
+    hasher.finalize().to_hex_string(Upper)
+}
+
+// ID of the graphs created before the protocol parameters were configurable, always with the
+// default parameters of their network.
+fn generate_legacy_id(peg_in_deposit_transaction: &PegInDepositTransaction) -> String {
     let mut hasher = Sha256::new();
 
     hasher.update(peg_in_deposit_transaction.tx().compute_txid().to_string());
@@ -647,6 +962,7 @@ fn create_new_connectors(
     n_of_n_taproot_public_key: &XOnlyPublicKey,
     depositor_taproot_public_key: &XOnlyPublicKey,
     evm_address: &str,
+    protocol_parameters: &ProtocolParameters,
 ) -> PegInConnectors {
     let connector_0 = Connector0::new(network, n_of_n_taproot_public_key);
     let connector_z = ConnectorZ::new(
@@ -654,6 +970,7 @@ fn create_new_connectors(
         evm_address,
         depositor_taproot_public_key,
         n_of_n_taproot_public_key,
+        protocol_parameters,
     );
 
     PegInConnectors {
@@ -672,12 +989,14 @@ fn create_graph_without_signing(
     n_of_n_taproot_public_key: &XOnlyPublicKey,
     depositor_evm_address: &str,
     deposit_input: Input,
+    protocol_parameters: &ProtocolParameters,
 ) -> PegInGraph {
     let connectors = create_new_connectors(
         network,
         n_of_n_taproot_public_key,
         depositor_taproot_public_key,
         depositor_evm_address,
+        protocol_parameters,
     );
     let peg_in_deposit_transaction = PegInDepositTransaction::new_for_validation(
         network,
@@ -705,7 +1024,9 @@ fn create_graph_without_signing(
     PegInGraph {
         version: GRAPH_VERSION.to_string(),
         network,
-        id: generate_id(&peg_in_deposit_transaction),
+        id: generate_id(&peg_in_deposit_transaction, protocol_parameters),
+        protocol_parameters: *protocol_parameters,
+        legacy_id: false,
         peg_in_deposit_transaction,
         peg_in_refund_transaction,
         peg_in_confirm_transaction,
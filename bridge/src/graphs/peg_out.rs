@@ -2,9 +2,11 @@ use bitcoin::{
     hashes::Hash,
     hex::{Case::Upper, DisplayHex},
     key::Keypair,
-    Amount, Network, OutPoint, PublicKey, ScriptBuf, Transaction, Txid, XOnlyPublicKey,
+    taproot::TaprootSpendInfo,
+    Amount, Network, OutPoint, PublicKey, ScriptBuf, TapSighashType, Transaction, TxIn, TxOut,
+    Txid, XOnlyPublicKey,
 };
-use esplora_client::{AsyncClient, TxStatus};
+use esplora_client::{AsyncClient, OutputStatus, TxStatus};
 use musig2::SecNonce;
 use num_traits::ToPrimitive;
 use serde::{Deserialize, Serialize};
@@ -15,16 +17,17 @@ use std::{
 };
 
 use crate::{
-    commitments::CommitmentMessageId,
+    commitments::{decode_winternitz_message, CommitmentMessageId},
     common::ZkProofVerifyingKey,
     connectors::{
         connector_c::get_commit_from_assert_commit_tx, connector_d::ConnectorD,
         connector_e::ConnectorE, connector_f_1::ConnectorF1, connector_f_2::ConnectorF2,
     },
     error::{Error, GraphError, L2Error, NamedTx},
+    scripts::{generate_pay_to_pubkey_script, generate_pay_to_pubkey_script_address},
     superblock::{
         find_superblock, get_start_time_block_number, get_superblock_hash_message,
-        get_superblock_message,
+        get_superblock_message, SUPERBLOCK_MESSAGE_LENGTH,
     },
     transactions::{
         assert_transactions::{
@@ -38,6 +41,10 @@ use crate::{
                 AssertCommit2ConnectorsE, AssertCommitConnectorsF,
             },
         },
+        fee_table::{
+            dummy_key_path_witness, dummy_p2wsh_witness, dummy_schnorr_signature,
+            dummy_script_path_witness, dummy_winternitz_witness, with_witnesses, DUMMY_BYTE,
+        },
         peg_in_confirm::PEG_IN_CONFIRM_TX_NAME,
         pre_signed_musig2::PreSignedMusig2Transaction,
     },
@@ -50,14 +57,25 @@ use bitvm::signatures::signing_winternitz::{
 
 use super::{
     super::{
-        client::chain::chain::PegOutEvent,
+        client::chain::{
+            chain::{PegOutBurntEvent, PegOutEvent},
+            l2_proof::{L2Proof, L2ProofConfig},
+        },
         connectors::{
-            connector_0::Connector0, connector_1::Connector1, connector_2::Connector2,
-            connector_3::Connector3, connector_4::Connector4, connector_5::Connector5,
-            connector_6::Connector6, connector_a::ConnectorA, connector_b::ConnectorB,
+            base::{P2wshConnector, TaprootConnector},
+            connector_0::Connector0,
+            connector_1::Connector1,
+            connector_2::Connector2,
+            connector_3::Connector3,
+            connector_4::Connector4,
+            connector_5::Connector5,
+            connector_6::Connector6,
+            connector_a::ConnectorA,
+            connector_b::ConnectorB,
             connector_c::ConnectorC,
         },
         contexts::{operator::OperatorContext, verifier::VerifierContext},
+        parameters::ProtocolParameters,
         transactions::{
             base::{
                 validate_transaction, verify_public_nonces_for_tx, BaseTransaction, Input,
@@ -78,12 +96,16 @@ use super::{
             take_2::Take2Transaction,
         },
     },
-    base::{verify_if_not_mined, BaseGraph, GraphId, CROWDFUNDING_AMOUNT, GRAPH_VERSION},
+    base::{verify_if_not_mined, BaseGraph, GraphId, GRAPH_VERSION},
+    challenge_crowdfunding::{
+        select_contributions, ChallengeContribution, ChallengeCrowdfundingStatus,
+    },
     peg_in::PegInGraph,
 };
 
 pub type PegOutId = GraphId;
 
+#[derive(Serialize, Deserialize, PartialEq, Clone, Debug)]
 pub enum PegOutWithdrawerStatus {
     PegOutNotStarted, // peg-out transaction not created yet
     PegOutWait,       // peg-out not confirmed yet, wait
@@ -102,6 +124,7 @@ impl Display for PegOutWithdrawerStatus {
     }
 }
 
+#[derive(Serialize, Deserialize, PartialEq, Clone, Debug)]
 pub enum PegOutVerifierStatus {
     PegOutPendingNonces,      // should push nonces
     PegOutAwaitingNonces,     // should wait for nonces from other verifiers
@@ -170,6 +193,7 @@ impl Display for PegOutVerifierStatus {
     }
 }
 
+#[derive(Serialize, Deserialize, PartialEq, Clone, Debug)]
 pub enum PegOutOperatorStatus {
     // TODO: add assert initial and assert final
     PegOutWait,
@@ -268,6 +292,11 @@ pub struct PegOutGraph {
     version: String,
     network: Network,
     id: String,
+    protocol_parameters: ProtocolParameters,
+    // Graphs created before the protocol parameters were configurable have an ID not committing
+    // to them, see `generate_legacy_id`.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    legacy_id: bool,
 
     // state: State,
     // n_of_n_pre_signing_state: PreSigningState,
@@ -326,56 +355,60 @@ impl BaseGraph for PegOutGraph {
 
     fn id(&self) -> &String { &self.id }
 
+    fn protocol_parameters(&self) -> &ProtocolParameters { &self.protocol_parameters }
+
     fn verifier_sign(
         &mut self,
         verifier_context: &VerifierContext,
         secret_nonces: &HashMap<Txid, HashMap<usize, SecNonce>>,
-    ) {
+    ) -> Result<(), Error> {
         self.assert_initial_transaction.pre_sign(
             verifier_context,
             &self.connector_b,
             &secret_nonces[&self.assert_initial_transaction.tx().compute_txid()],
-        );
+        )?;
         self.assert_final_transaction.pre_sign(
             verifier_context,
             &self.connector_d,
             &secret_nonces[&self.assert_final_transaction.tx().compute_txid()],
-        );
+        )?;
         self.disprove_chain_transaction.pre_sign(
             verifier_context,
             &self.connector_b,
             &secret_nonces[&self.disprove_chain_transaction.tx().compute_txid()],
-        );
+        )?;
         self.disprove_transaction.pre_sign(
             verifier_context,
             &self.connector_5,
             &secret_nonces[&self.disprove_transaction.tx().compute_txid()],
-        );
+        )?;
         self.kick_off_timeout_transaction.pre_sign(
             verifier_context,
             &self.connector_1,
             &secret_nonces[&self.kick_off_timeout_transaction.tx().compute_txid()],
-        );
+        )?;
         self.start_time_timeout_transaction.pre_sign(
             verifier_context,
             &self.connector_1,
             &self.connector_2,
             &secret_nonces[&self.start_time_timeout_transaction.tx().compute_txid()],
-        );
+        )?;
         self.take_1_transaction.pre_sign(
             verifier_context,
             &self.connector_0,
             &self.connector_b,
             &secret_nonces[&self.take_1_transaction.tx().compute_txid()],
-        );
+        )?;
         self.take_2_transaction.pre_sign(
             verifier_context,
             &self.connector_0,
             &self.connector_5,
             &secret_nonces[&self.take_2_transaction.tx().compute_txid()],
-        );
+        )?;
 
         self.n_of_n_presigned = true; // TODO: set to true after collecting all n of n signatures
+
+        Ok(())
     }
 
     fn push_verifier_nonces(
@@ -391,6 +424,697 @@ impl BaseGraph for PegOutGraph {
             })
             .collect()
     }
+    fn pre_signed_transactions(&self) -> Vec<(&'static str, &dyn PreSignedTransaction)> {
+        let mut transactions: Vec<(&'static str, &dyn PreSignedTransaction)> = vec![
+            (
+                self.peg_out_confirm_transaction.name(),
+                &self.peg_out_confirm_transaction,
+            ),
+            (
+                self.assert_initial_transaction.name(),
+                &self.assert_initial_transaction,
+            ),
+            (
+                self.assert_commit_1_transaction.name(),
+                &self.assert_commit_1_transaction,
+            ),
+            (
+                self.assert_commit_2_transaction.name(),
+                &self.assert_commit_2_transaction,
+            ),
+            (
+                self.assert_final_transaction.name(),
+                &self.assert_final_transaction,
+            ),
+            (
+                self.challenge_transaction.name(),
+                &self.challenge_transaction,
+            ),
+            (
+                self.disprove_chain_transaction.name(),
+                &self.disprove_chain_transaction,
+            ),
+            (self.disprove_transaction.name(), &self.disprove_transaction),
+            (
+                self.kick_off_1_transaction.name(),
+                &self.kick_off_1_transaction,
+            ),
+            (
+                self.kick_off_2_transaction.name(),
+                &self.kick_off_2_transaction,
+            ),
+            (
+                self.kick_off_timeout_transaction.name(),
+                &self.kick_off_timeout_transaction,
+            ),
+            (
+                self.start_time_transaction.name(),
+                &self.start_time_transaction,
+            ),
+            (
+                self.start_time_timeout_transaction.name(),
+                &self.start_time_timeout_transaction,
+            ),
+            (self.take_1_transaction.name(), &self.take_1_transaction),
+            (self.take_2_transaction.name(), &self.take_2_transaction),
+        ];
+        if let Some(peg_out_transaction) = &self.peg_out_transaction {
+            transactions.push((peg_out_transaction.name(), peg_out_transaction));
+        }
+
+        transactions
+    }
+
+    fn pre_signed_transactions_mut(
+        &mut self,
+    ) -> Vec<(&'static str, &mut dyn PreSignedTransaction)> {
+        let mut transactions: Vec<(&'static str, &mut dyn PreSignedTransaction)> = vec![
+            (
+                self.peg_out_confirm_transaction.name(),
+                &mut self.peg_out_confirm_transaction,
+            ),
+            (
+                self.assert_initial_transaction.name(),
+                &mut self.assert_initial_transaction,
+            ),
+            (
+                self.assert_commit_1_transaction.name(),
+                &mut self.assert_commit_1_transaction,
+            ),
+            (
+                self.assert_commit_2_transaction.name(),
+                &mut self.assert_commit_2_transaction,
+            ),
+            (
+                self.assert_final_transaction.name(),
+                &mut self.assert_final_transaction,
+            ),
+            (
+                self.challenge_transaction.name(),
+                &mut self.challenge_transaction,
+            ),
+            (
+                self.disprove_chain_transaction.name(),
+                &mut self.disprove_chain_transaction,
+            ),
+            (
+                self.disprove_transaction.name(),
+                &mut self.disprove_transaction,
+            ),
+            (
+                self.kick_off_1_transaction.name(),
+                &mut self.kick_off_1_transaction,
+            ),
+            (
+                self.kick_off_2_transaction.name(),
+                &mut self.kick_off_2_transaction,
+            ),
+            (
+                self.kick_off_timeout_transaction.name(),
+                &mut self.kick_off_timeout_transaction,
+            ),
+            (
+                self.start_time_transaction.name(),
+                &mut self.start_time_transaction,
+            ),
+            (
+                self.start_time_timeout_transaction.name(),
+                &mut self.start_time_timeout_transaction,
+            ),
+            (self.take_1_transaction.name(), &mut self.take_1_transaction),
+            (self.take_2_transaction.name(), &mut self.take_2_transaction),
+        ];
+        if let Some(peg_out_transaction) = &mut self.peg_out_transaction {
+            transactions.push((peg_out_transaction.name(), peg_out_transaction));
+        }
+
+        transactions
+    }
+
+    fn transaction_templates(&self) -> Vec<(&'static str, Transaction)> {
+        let connector_0_spend_info = self.connector_0.generate_taproot_spend_info();
+        let connector_1_spend_info = self.connector_1.generate_taproot_spend_info();
+        let connector_2_spend_info = self.connector_2.generate_taproot_spend_info();
+        let connector_5_spend_info = self.connector_5.generate_taproot_spend_info();
+        let connector_a_spend_info = self.connector_a.generate_taproot_spend_info();
+        let connector_b_spend_info = self.connector_b.generate_taproot_spend_info();
+        let connector_c_spend_info = self.connector_c.generate_taproot_spend_info();
+        let connector_d_spend_info = self.connector_d.generate_taproot_spend_info();
+        let operator_script = generate_pay_to_pubkey_script(&self.operator_public_key);
+        // Stand-in for the outputs chosen at broadcast time, which are all P2WSH outputs.
+        let operator_script_pubkey =
+            generate_pay_to_pubkey_script_address(self.network, &self.operator_public_key)
+                .script_pubkey();
+        let commitment_witness =
+            |public_keys: &HashMap<CommitmentMessageId, WinternitzPublicKey>,
+             message_id: CommitmentMessageId| {
+                dummy_winternitz_witness(&public_keys[&message_id])
+            };
+        let assert_commit_witnesses = |connectors_e: &[ConnectorE]| {
+            connectors_e
+                .iter()
+                .map(|connector_e| {
+                    dummy_script_path_witness(
+                        &connector_e.generate_taproot_spend_info(),
+                        &connector_e.generate_taproot_leaf_script(0),
+                        connector_e
+                            .commitment_public_keys
+                            .values()
+                            .flat_map(dummy_winternitz_witness)
+                            .collect(),
+                    )
+                })
+                .collect::<Vec<_>>()
+        };
+
+        let peg_out_tx = Transaction {
+            version: bitcoin::transaction::Version(2),
+            lock_time: bitcoin::absolute::LockTime::ZERO,
+            input: vec![TxIn::default()],
+            output: vec![TxOut {
+                value: Amount::ZERO,
+                script_pubkey: operator_script_pubkey.clone(),
+            }],
+        };
+
+        let peg_out_confirm = &self.peg_out_confirm_transaction;
+
+        let kick_off_1 = &self.kick_off_1_transaction;
+        let mut kick_off_1_unlock_data = vec![dummy_schnorr_signature(TapSighashType::All)];
+        kick_off_1_unlock_data.extend(commitment_witness(
+            &self.connector_6.commitment_public_keys,
+            CommitmentMessageId::PegOutTxIdSourceNetwork,
+        ));
+        kick_off_1_unlock_data.extend(commitment_witness(
+            &self.connector_6.commitment_public_keys,
+            CommitmentMessageId::PegOutTxIdDestinationNetwork,
+        ));
+
+        let start_time = &self.start_time_transaction;
+        let mut start_time_unlock_data = vec![dummy_schnorr_signature(TapSighashType::All)];
+        start_time_unlock_data.extend(commitment_witness(
+            &self.connector_2.commitment_public_keys,
+            CommitmentMessageId::StartTime,
+        ));
+
+        let start_time_timeout = &self.start_time_timeout_transaction;
+
+        let kick_off_2 = &self.kick_off_2_transaction;
+        let mut kick_off_2_unlock_data = vec![dummy_schnorr_signature(TapSighashType::All)];
+        kick_off_2_unlock_data.extend(commitment_witness(
+            &self.connector_1.commitment_public_keys,
+            CommitmentMessageId::Superblock,
+        ));
+        kick_off_2_unlock_data.extend(commitment_witness(
+            &self.connector_1.commitment_public_keys,
+            CommitmentMessageId::SuperblockHash,
+        ));
+
+        let kick_off_timeout = &self.kick_off_timeout_transaction;
+
+        // A single crowdfunding input that covers the whole amount, without a refund output.
+        let challenge = &self.challenge_transaction;
+        let mut challenge_tx = challenge.tx().clone();
+        challenge_tx.input.push(TxIn::default());
+
+        let take_1 = &self.take_1_transaction;
+        let take_2 = &self.take_2_transaction;
+        let assert_initial = &self.assert_initial_transaction;
+        let assert_commit_1 = &self.assert_commit_1_transaction;
+        let assert_commit_2 = &self.assert_commit_2_transaction;
+        let assert_final = &self.assert_final_transaction;
+
+        let disprove_chain = &self.disprove_chain_transaction;
+        let mut disprove_chain_tx = disprove_chain.tx().clone();
+        disprove_chain_tx.output[1].script_pubkey = operator_script_pubkey.clone();
+        // Superblock bytes, committed start time and committed superblock hash
+        let mut disprove_chain_unlock_data = vec![vec![DUMMY_BYTE]; SUPERBLOCK_MESSAGE_LENGTH];
+        disprove_chain_unlock_data.extend(commitment_witness(
+            &self.connector_b.commitment_public_keys,
+            CommitmentMessageId::StartTime,
+        ));
+        disprove_chain_unlock_data.extend(commitment_witness(
+            &self.connector_b.commitment_public_keys,
+            CommitmentMessageId::SuperblockHash,
+        ));
+        disprove_chain_unlock_data.push(dummy_schnorr_signature(TapSighashType::Single));
+
+        // The disprove transaction is measured with its largest leaf of connector C.
+        let disprove = &self.disprove_transaction;
+        let mut disprove_tx = disprove.tx().clone();
+        disprove_tx.output[1].script_pubkey = operator_script_pubkey;
+        let disprove_n_of_n_witness = dummy_script_path_witness(
+            &connector_5_spend_info,
+            &disprove.prev_scripts()[0],
+            vec![dummy_schnorr_signature(TapSighashType::Single)],
+        );
+        let disprove_template = self
+            .connector_c
+            .generate_dummy_disprove_witnesses()
+            .into_iter()
+            .enumerate()
+            .map(|(leaf_index, witness)| {
+                let script = self
+                    .connector_c
+                    .generate_taproot_leaf_script(leaf_index.to_u32().unwrap());
+                with_witnesses(
+                    &disprove_tx,
+                    vec![
+                        disprove_n_of_n_witness.clone(),
+                        dummy_script_path_witness(&connector_c_spend_info, &script, witness),
+                    ],
+                )
+            })
+            .max_by_key(|tx| tx.vsize())
+            .expect("Connector C has no leaves");
+
+        vec![
+            (
+                "PegOut",
+                with_witnesses(&peg_out_tx, vec![dummy_p2wsh_witness(&operator_script, 1)]),
+            ),
+            (
+                peg_out_confirm.name(),
+                with_witnesses(
+                    peg_out_confirm.tx(),
+                    vec![dummy_p2wsh_witness(&peg_out_confirm.prev_scripts()[0], 1)],
+                ),
+            ),
+            (
+                kick_off_1.name(),
+                with_witnesses(
+                    kick_off_1.tx(),
+                    vec![dummy_script_path_witness(
+                        &self.connector_6.generate_taproot_spend_info(),
+                        &kick_off_1.prev_scripts()[0],
+                        kick_off_1_unlock_data,
+                    )],
+                ),
+            ),
+            (
+                start_time.name(),
+                with_witnesses(
+                    start_time.tx(),
+                    vec![dummy_script_path_witness(
+                        &connector_2_spend_info,
+                        &start_time.prev_scripts()[0],
+                        start_time_unlock_data,
+                    )],
+                ),
+            ),
+            (
+                start_time_timeout.name(),
+                with_witnesses(
+                    start_time_timeout.tx(),
+                    vec![
+                        dummy_script_path_witness(
+                            &connector_2_spend_info,
+                            &start_time_timeout.prev_scripts()[0],
+                            vec![dummy_schnorr_signature(TapSighashType::Single)],
+                        ),
+                        dummy_script_path_witness(
+                            &connector_1_spend_info,
+                            &start_time_timeout.prev_scripts()[1],
+                            vec![dummy_schnorr_signature(TapSighashType::None)],
+                        ),
+                    ],
+                ),
+            ),
+            (
+                kick_off_2.name(),
+                with_witnesses(
+                    kick_off_2.tx(),
+                    vec![dummy_script_path_witness(
+                        &connector_1_spend_info,
+                        &kick_off_2.prev_scripts()[0],
+                        kick_off_2_unlock_data,
+                    )],
+                ),
+            ),
+            (
+                kick_off_timeout.name(),
+                with_witnesses(
+                    kick_off_timeout.tx(),
+                    vec![dummy_script_path_witness(
+                        &connector_1_spend_info,
+                        &kick_off_timeout.prev_scripts()[0],
+                        vec![dummy_schnorr_signature(TapSighashType::Single)],
+                    )],
+                ),
+            ),
+            (
+                challenge.name(),
+                with_witnesses(
+                    &challenge_tx,
+                    vec![
+                        dummy_script_path_witness(
+                            &connector_a_spend_info,
+                            &challenge.prev_scripts()[0],
+                            vec![dummy_schnorr_signature(
+                                TapSighashType::SinglePlusAnyoneCanPay,
+                            )],
+                        ),
+                        dummy_p2wsh_witness(&operator_script, 1),
+                    ],
+                ),
+            ),
+            (
+                take_1.name(),
+                with_witnesses(
+                    take_1.tx(),
+                    vec![
+                        dummy_script_path_witness(
+                            &connector_0_spend_info,
+                            &take_1.prev_scripts()[0],
+                            vec![dummy_schnorr_signature(TapSighashType::All)],
+                        ),
+                        dummy_script_path_witness(
+                            &connector_a_spend_info,
+                            &take_1.prev_scripts()[1],
+                            vec![dummy_schnorr_signature(TapSighashType::All)],
+                        ),
+                        dummy_p2wsh_witness(&take_1.prev_scripts()[2], 1),
+                        dummy_script_path_witness(
+                            &connector_b_spend_info,
+                            &take_1.prev_scripts()[3],
+                            vec![dummy_schnorr_signature(TapSighashType::All)],
+                        ),
+                    ],
+                ),
+            ),
+            (
+                take_2.name(),
+                with_witnesses(
+                    take_2.tx(),
+                    vec![
+                        dummy_script_path_witness(
+                            &connector_0_spend_info,
+                            &take_2.prev_scripts()[0],
+                            vec![dummy_schnorr_signature(TapSighashType::All)],
+                        ),
+                        dummy_p2wsh_witness(&take_2.prev_scripts()[1], 1),
+                        dummy_script_path_witness(
+                            &connector_5_spend_info,
+                            &take_2.prev_scripts()[2],
+                            vec![dummy_schnorr_signature(TapSighashType::All)],
+                        ),
+                        dummy_key_path_witness(TapSighashType::All),
+                    ],
+                ),
+            ),
+            (
+                assert_initial.name(),
+                with_witnesses(
+                    assert_initial.tx(),
+                    vec![dummy_script_path_witness(
+                        &connector_b_spend_info,
+                        &assert_initial.prev_scripts()[0],
+                        vec![dummy_schnorr_signature(TapSighashType::All)],
+                    )],
+                ),
+            ),
+            (
+                assert_commit_1.name(),
+                with_witnesses(
+                    assert_commit_1.tx(),
+                    assert_commit_witnesses(&self.connector_e_1.connectors_e),
+                ),
+            ),
+            (
+                assert_commit_2.name(),
+                with_witnesses(
+                    assert_commit_2.tx(),
+                    assert_commit_witnesses(&self.connector_e_2.connectors_e),
+                ),
+            ),
+            (
+                assert_final.name(),
+                with_witnesses(
+                    assert_final.tx(),
+                    vec![
+                        dummy_script_path_witness(
+                            &connector_d_spend_info,
+                            &assert_final.prev_scripts()[0],
+                            vec![dummy_schnorr_signature(TapSighashType::All)],
+                        ),
+                        dummy_p2wsh_witness(&assert_final.prev_scripts()[1], 1),
+                        dummy_p2wsh_witness(&assert_final.prev_scripts()[2], 1),
+                    ],
+                ),
+            ),
+            (
+                disprove_chain.name(),
+                with_witnesses(
+                    &disprove_chain_tx,
+                    vec![dummy_script_path_witness(
+                        &connector_b_spend_info,
+                        &disprove_chain.prev_scripts()[0],
+                        disprove_chain_unlock_data,
+                    )],
+                ),
+            ),
+            (disprove.name(), disprove_template),
+        ]
+    }
+
+    // Connectors C, E and F only carry commitment scripts, which can't be signed from a PSBT.
+    fn taproot_spend_infos(&self) -> Vec<TaprootSpendInfo> {
+        vec![
+            self.connector_0.generate_taproot_spend_info(),
+            self.connector_1.generate_taproot_spend_info(),
+            self.connector_2.generate_taproot_spend_info(),
+            self.connector_5.generate_taproot_spend_info(),
+            self.connector_6.generate_taproot_spend_info(),
+            self.connector_a.generate_taproot_spend_info(),
+            self.connector_b.generate_taproot_spend_info(),
+            self.connector_d.generate_taproot_spend_info(),
+        ]
+    }
+
+    fn connector_script_pubkeys(&self) -> Vec<(String, ScriptBuf)> {
+        let mut script_pubkeys = vec![
+            (
+                "Connector 0".to_string(),
+                self.connector_0.generate_taproot_address().script_pubkey(),
+            ),
+            (
+                "Connector 1".to_string(),
+                self.connector_1.generate_taproot_address().script_pubkey(),
+            ),
+            (
+                "Connector 2".to_string(),
+                self.connector_2.generate_taproot_address().script_pubkey(),
+            ),
+            (
+                "Connector 5".to_string(),
+                self.connector_5.generate_taproot_address().script_pubkey(),
+            ),
+            (
+                "Connector 6".to_string(),
+                self.connector_6.generate_taproot_address().script_pubkey(),
+            ),
+            (
+                "Connector A".to_string(),
+                self.connector_a.generate_taproot_address().script_pubkey(),
+            ),
+            (
+                "Connector B".to_string(),
+                self.connector_b.generate_taproot_address().script_pubkey(),
+            ),
+            (
+                "Connector C".to_string(),
+                self.connector_c.generate_taproot_address().script_pubkey(),
+            ),
+            (
+                "Connector D".to_string(),
+                self.connector_d.generate_taproot_address().script_pubkey(),
+            ),
+            (
+                "Connector 3".to_string(),
+                self.connector_3.generate_address().script_pubkey(),
+            ),
+            (
+                "Connector 4".to_string(),
+                self.connector_4.generate_address().script_pubkey(),
+            ),
+            (
+                "Connector F1".to_string(),
+                self.connector_f_1.generate_address().script_pubkey(),
+            ),
+            (
+                "Connector F2".to_string(),
+                self.connector_f_2.generate_address().script_pubkey(),
+            ),
+        ];
+        for (name, connectors_e) in [
+            ("E1", &self.connector_e_1.connectors_e),
+            ("E2", &self.connector_e_2.connectors_e),
+        ] {
+            script_pubkeys.extend(connectors_e.iter().enumerate().map(|(i, connector)| {
+                (
+                    format!("Connector {name}-{i}"),
+                    connector.generate_taproot_address().script_pubkey(),
+                )
+            }));
+        }
+
+        script_pubkeys
+    }
+
+    fn musig2_transactions(&self) -> Vec<&dyn PreSignedMusig2Transaction> {
+        self.all_presigned_txs().collect()
+    }
+
+    fn musig2_transactions_mut(&mut self) -> Vec<&mut dyn PreSignedMusig2Transaction> {
+        self.all_presigned_txs_mut().collect()
+    }
+
+    fn validate(&self) -> bool {
+        if !self.protocol_parameters.validate() {
+            return false;
+        }
+        // Legacy graphs were only created with the default parameters of their network.
+        if self.legacy_id
+            && self.protocol_parameters != ProtocolParameters::for_network(self.network)
+        {
+            return false;
+        }
+
+        let mut ret_val = true;
+        let id = match self.legacy_id {
+            true => generate_legacy_id(&self.peg_in_graph_id, &self.operator_public_key),
+            false => generate_id_with_parameters(
+                &self.peg_in_graph_id,
+                &self.operator_public_key,
+                &self.protocol_parameters,
+            ),
+        };
+        if self.id != id {
+            ret_val = false;
+        }
+
+        let peg_out_graph = self.new_for_validation();
+        if !validate_transaction(
+            self.assert_initial_transaction.tx(),
+            peg_out_graph.assert_initial_transaction.tx(),
+        ) {
+            ret_val = false;
+        }
+        if !validate_transaction(
+            self.assert_commit_1_transaction.tx(),
+            peg_out_graph.assert_commit_1_transaction.tx(),
+        ) {
+            ret_val = false;
+        }
+        if !validate_transaction(
+            self.assert_commit_2_transaction.tx(),
+            peg_out_graph.assert_commit_2_transaction.tx(),
+        ) {
+            ret_val = false;
+        }
+        if !validate_transaction(
+            self.assert_final_transaction.tx(),
+            peg_out_graph.assert_final_transaction.tx(),
+        ) {
+            ret_val = false;
+        }
+        if !validate_transaction(
+            self.challenge_transaction.tx(),
+            peg_out_graph.challenge_transaction.tx(),
+        ) {
+            ret_val = false;
+        }
+        if !validate_transaction(
+            self.disprove_chain_transaction.tx(),
+            peg_out_graph.disprove_chain_transaction.tx(),
+        ) {
+            ret_val = false;
+        }
+        if !validate_transaction(
+            self.disprove_transaction.tx(),
+            peg_out_graph.disprove_transaction.tx(),
+        ) {
+            ret_val = false;
+        }
+        if !validate_transaction(
+            self.peg_out_confirm_transaction.tx(),
+            peg_out_graph.peg_out_confirm_transaction.tx(),
+        ) {
+            ret_val = false;
+        }
+        if !validate_transaction(
+            self.kick_off_1_transaction.tx(),
+            peg_out_graph.kick_off_1_transaction.tx(),
+        ) {
+            ret_val = false;
+        }
+        if !validate_transaction(
+            self.kick_off_2_transaction.tx(),
+            peg_out_graph.kick_off_2_transaction.tx(),
+        ) {
+            ret_val = false;
+        }
+        if !validate_transaction(
+            self.kick_off_timeout_transaction.tx(),
+            peg_out_graph.kick_off_timeout_transaction.tx(),
+        ) {
+            ret_val = false;
+        }
+        if !validate_transaction(
+            self.start_time_transaction.tx(),
+            peg_out_graph.start_time_transaction.tx(),
+        ) {
+            ret_val = false;
+        }
+        if !validate_transaction(
+            self.start_time_timeout_transaction.tx(),
+            peg_out_graph.start_time_timeout_transaction.tx(),
+        ) {
+            ret_val = false;
+        }
+        if !validate_transaction(
+            self.take_1_transaction.tx(),
+            peg_out_graph.take_1_transaction.tx(),
+        ) {
+            ret_val = false;
+        }
+        if !validate_transaction(
+            self.take_2_transaction.tx(),
+            peg_out_graph.take_2_transaction.tx(),
+        ) {
+            ret_val = false;
+        }
+
+        if !verify_public_nonces_for_tx(&self.assert_initial_transaction) {
+            ret_val = false;
+        }
+        if !verify_public_nonces_for_tx(&self.assert_final_transaction) {
+            ret_val = false;
+        }
+        if !verify_public_nonces_for_tx(&self.disprove_chain_transaction) {
+            ret_val = false;
+        }
+        if !verify_public_nonces_for_tx(&self.disprove_transaction) {
+            ret_val = false;
+        }
+        if !verify_public_nonces_for_tx(&self.kick_off_timeout_transaction) {
+            ret_val = false;
+        }
+        if !verify_public_nonces_for_tx(&self.start_time_transaction) {
+            ret_val = false;
+        }
+        if !verify_public_nonces_for_tx(&self.start_time_timeout_transaction) {
+            ret_val = false;
+        }
+        if !verify_public_nonces_for_tx(&self.take_1_transaction) {
+            ret_val = false;
+        }
+        if !verify_public_nonces_for_tx(&self.take_2_transaction) {
+            ret_val = false;
+        }
+
+        ret_val
+    }
 }
 
 impl PegOutGraph {
@@ -399,6 +1123,7 @@ impl PegOutGraph {
         peg_in_graph: &PegInGraph,
         peg_out_confirm_input: Input,
         commitment_secrets: &HashMap<CommitmentMessageId, WinternitzSecret>,
+        protocol_parameters: &ProtocolParameters,
     ) -> Self {
         let peg_in_confirm_transaction = peg_in_graph.peg_in_confirm_transaction_ref();
         let peg_in_confirm_txid = peg_in_confirm_transaction.tx().compute_txid();
@@ -460,6 +1185,7 @@ impl PegOutGraph {
             &connector_b_commitment_public_keys,
             &connector_e1_commitment_public_keys,
             &connector_e2_commitment_public_keys,
+            protocol_parameters,
         );
 
         let peg_out_confirm_transaction =
@@ -479,6 +1205,7 @@ impl PegOutGraph {
                 },
                 amount: peg_out_confirm_transaction.tx().output[kick_off_1_vout_0].value,
             },
+            protocol_parameters,
         );
         let kick_off_1_txid = kick_off_1_transaction.tx().compute_txid();
 
@@ -529,6 +1256,7 @@ impl PegOutGraph {
                 },
                 amount: kick_off_1_transaction.tx().output[kick_off_2_vout_0].value,
             },
+            protocol_parameters,
         );
         let kick_off_2_txid = kick_off_2_transaction.tx().compute_txid();
 
@@ -545,7 +1273,7 @@ impl PegOutGraph {
             },
         );
 
-        let input_amount_crowdfunding = Amount::from_btc(CROWDFUNDING_AMOUNT).unwrap();
+        let input_amount_crowdfunding = Amount::from_sat(protocol_parameters.crowdfunding_amount);
         let challenge_vout_0 = 0;
         let challenge_transaction = ChallengeTransaction::new(
             context,
@@ -614,6 +1342,7 @@ impl PegOutGraph {
                 },
                 amount: kick_off_2_transaction.tx().output[assert_initial_vout_0].value,
             },
+            protocol_parameters,
         );
         let assert_initial_txid = assert_initial_transaction.tx().compute_txid();
 
@@ -681,6 +1410,7 @@ impl PegOutGraph {
                 },
                 amount: assert_commit_2_transaction.tx().output[assert_final_vout_2].value,
             },
+            protocol_parameters,
         );
         let assert_final_txid = assert_final_transaction.tx().compute_txid();
 
@@ -762,7 +1492,13 @@ impl PegOutGraph {
         PegOutGraph {
             version: GRAPH_VERSION.to_string(),
             network: context.network,
-            id: generate_id(peg_in_graph, &context.operator_public_key),
+            id: generate_id_with_parameters(
+                peg_in_graph.id(),
+                &context.operator_public_key,
+                protocol_parameters,
+            ),
+            protocol_parameters: *protocol_parameters,
+            legacy_id: false,
             n_of_n_presigned: false,
             n_of_n_public_key: context.n_of_n_public_key,
             n_of_n_taproot_public_key: context.n_of_n_taproot_public_key,
@@ -819,6 +1555,7 @@ impl PegOutGraph {
             &self.connector_b.commitment_public_keys,
             &self.connector_e_1.commitment_public_keys(),
             &self.connector_e_2.commitment_public_keys(),
+            &self.protocol_parameters,
         );
 
         let peg_out_confirm_vout_0 = 0;
@@ -845,6 +1582,7 @@ impl PegOutGraph {
                 outpoint: self.kick_off_1_transaction.tx().input[kick_off_1_vout_0].previous_output, // Self-referencing
                 amount: self.kick_off_1_transaction.prev_outs()[kick_off_1_vout_0].value, // Self-referencing
             },
+            &self.protocol_parameters,
         );
         let kick_off_1_txid = kick_off_1_transaction.tx().compute_txid();
 
@@ -897,6 +1635,7 @@ impl PegOutGraph {
                 },
                 amount: kick_off_1_transaction.tx().output[kick_off_2_vout_0].value,
             },
+            &self.protocol_parameters,
         );
         let kick_off_2_txid = kick_off_2_transaction.tx().compute_txid();
 
@@ -913,7 +1652,8 @@ impl PegOutGraph {
             },
         );
 
-        let input_amount_crowdfunding = Amount::from_btc(CROWDFUNDING_AMOUNT).unwrap();
+        let input_amount_crowdfunding =
+            Amount::from_sat(self.protocol_parameters.crowdfunding_amount);
         let challenge_vout_0 = 0;
         let challenge_transaction = ChallengeTransaction::new_for_validation(
             self.network,
@@ -984,6 +1724,7 @@ impl PegOutGraph {
                 },
                 amount: kick_off_2_transaction.tx().output[assert_initial_vout_0].value,
             },
+            &self.protocol_parameters,
         );
         let assert_initial_txid = assert_initial_transaction.tx().compute_txid();
 
@@ -1050,6 +1791,7 @@ impl PegOutGraph {
                 },
                 amount: assert_commit_2_transaction.tx().output[assert_final_vout_2].value,
             },
+            &self.protocol_parameters,
         );
         let assert_final_txid = assert_final_transaction.tx().compute_txid();
 
@@ -1133,6 +1875,8 @@ impl PegOutGraph {
             version: GRAPH_VERSION.to_string(),
             network: self.network,
             id: self.id.clone(),
+            protocol_parameters: self.protocol_parameters,
+            legacy_id: self.legacy_id,
             n_of_n_presigned: false,
             n_of_n_public_key: self.n_of_n_public_key,
             n_of_n_taproot_public_key: self.n_of_n_taproot_public_key,
@@ -1543,12 +2287,58 @@ impl PegOutGraph {
         }
     }
 
-    pub async fn kick_off_1(
+    pub async fn kick_off_1(
+        &mut self,
+        client: &AsyncClient,
+        context: &OperatorContext,
+        source_network_txid_commitment_secret: &WinternitzSecret,
+        destination_network_txid_commitment_secret: &WinternitzSecret,
+    ) -> Result<Transaction, Error> {
+        let destination_network_txid = self
+            .peg_out_chain_event
+            .as_ref()
+            .ok_or(Error::L2(L2Error::PegOutNotInitiated))?
+            .tx_hash
+            .clone();
+        self.kick_off_1_committing(
+            client,
+            context,
+            source_network_txid_commitment_secret,
+            destination_network_txid_commitment_secret,
+            &destination_network_txid,
+        )
+        .await
+    }
+
+    /// Like `kick_off_1`, but only once one of `proofs` proves the burn of the peg-out on the L2
+    /// chain, see `proven_burn_event`. The hash of the burn transaction is committed.
+    pub async fn kick_off_1_with_l2_burn_proof(
+        &mut self,
+        client: &AsyncClient,
+        context: &OperatorContext,
+        source_network_txid_commitment_secret: &WinternitzSecret,
+        destination_network_txid_commitment_secret: &WinternitzSecret,
+        proofs: &[L2Proof],
+        config: &L2ProofConfig,
+    ) -> Result<Transaction, Error> {
+        let burn_event = self.proven_burn_event(proofs, config, None)?;
+        self.kick_off_1_committing(
+            client,
+            context,
+            source_network_txid_commitment_secret,
+            destination_network_txid_commitment_secret,
+            &burn_event.tx_hash,
+        )
+        .await
+    }
+
+    async fn kick_off_1_committing(
         &mut self,
         client: &AsyncClient,
         context: &OperatorContext,
         source_network_txid_commitment_secret: &WinternitzSecret,
         destination_network_txid_commitment_secret: &WinternitzSecret,
+        destination_network_txid: &[u8],
     ) -> Result<Transaction, Error> {
         verify_if_not_mined(client, self.kick_off_1_transaction.tx().compute_txid()).await?;
 
@@ -1571,12 +2361,7 @@ impl PegOutGraph {
                         signing_key: source_network_txid_commitment_secret,
                     };
                     let destination_network_txid_inputs = WinternitzSigningInputs {
-                        message: self
-                            .peg_out_chain_event
-                            .as_ref()
-                            .unwrap()
-                            .tx_hash
-                            .as_slice(),
+                        message: destination_network_txid,
                         signing_key: destination_network_txid_commitment_secret,
                     };
                     self.kick_off_1_transaction.sign(
@@ -1595,14 +2380,16 @@ impl PegOutGraph {
         }
     }
 
+    /// The challenge transaction funded alone by `crowdfundng_inputs`, with any excess refunded
+    /// to `output_script_pubkey`. The crowdfunding contributions of the graph are left as is.
     pub async fn challenge(
-        &mut self,
+        &self,
         client: &AsyncClient,
         crowdfundng_inputs: &Vec<InputWithScript<'_>>,
         keypair: &Keypair,
         output_script_pubkey: ScriptBuf,
     ) -> Result<Transaction, Error> {
-        verify_if_not_mined(client, self.challenge_transaction.tx().compute_txid()).await?;
+        self.verify_challenge_not_mined(client).await?;
 
         let kick_off_1_txid = self.kick_off_1_transaction.tx().compute_txid();
         let kick_off_1_status = client.get_tx_status(&kick_off_1_txid).await;
@@ -1610,12 +2397,15 @@ impl PegOutGraph {
         match kick_off_1_status {
             Ok(status) => match status.confirmed {
                 true => {
-                    self.challenge_transaction.add_inputs_and_output(
+                    let mut challenge_transaction =
+                        self.challenge_transaction.without_crowdfunding_inputs();
+                    challenge_transaction.add_inputs_and_output(
                         crowdfundng_inputs,
                         keypair,
                         output_script_pubkey,
-                    );
-                    Ok(self.challenge_transaction.finalize())
+                        Amount::from_sat(self.protocol_parameters.dust_amount),
+                    )?;
+                    Ok(challenge_transaction.finalize())
                 }
                 false => Err(Error::Graph(GraphError::PrecedingTxNotConfirmed(vec![
                     NamedTx::for_tx(&self.kick_off_1_transaction, status.confirmed),
@@ -1625,6 +2415,224 @@ impl PegOutGraph {
         }
     }
 
+    /// Appends `input` to the challenge transaction as a crowdfunding contribution, signed with
+    /// SIGHASH_ALL|ANYONECANPAY so that other verifiers can append theirs. Contributions reach
+    /// the other participants through the data store.
+    pub async fn contribute_to_challenge(
+        &mut self,
+        client: &AsyncClient,
+        input: &InputWithScript<'_>,
+        keypair: &Keypair,
+    ) -> Result<(), Error> {
+        match self.challenge_crowdfunding_status(client).await? {
+            ChallengeCrowdfundingStatus::Open { .. } => {}
+            status => {
+                return Err(Error::Graph(GraphError::ChallengeCrowdfundingUnavailable(
+                    status,
+                )))
+            }
+        }
+
+        self.challenge_transaction
+            .add_crowdfunding_input(input, keypair)
+    }
+
+    /// Contributions to the challenge transaction that are correctly signed and still unspent.
+    /// Contributions failing these checks are ignored.
+    pub async fn challenge_contributions(
+        &self,
+        client: &AsyncClient,
+    ) -> Result<Vec<ChallengeContribution>, Error> {
+        let mut contributions = vec![];
+        for outpoint in self.challenge_transaction.crowdfunding_outpoints() {
+            let prev_out = client
+                .get_tx(&outpoint.txid)
+                .await
+                .map_err(Error::Esplora)?
+                .and_then(|tx| tx.output.get(outpoint.vout as usize).cloned());
+            let Some(prev_out) = prev_out else {
+                continue;
+            };
+            let Some(contributor) = self
+                .challenge_transaction
+                .verify_crowdfunding_input(&outpoint, &prev_out)
+            else {
+                continue;
+            };
+            let spent = client
+                .get_output_status(&outpoint.txid, outpoint.vout as u64)
+                .await
+                .map_err(Error::Esplora)?
+                .is_some_and(|status| status.spent);
+            if !spent {
+                contributions.push(ChallengeContribution {
+                    outpoint,
+                    amount: prev_out.value,
+                    contributor,
+                });
+            }
+        }
+
+        Ok(contributions)
+    }
+
+    pub async fn challenge_crowdfunding_status(
+        &self,
+        client: &AsyncClient,
+    ) -> Result<ChallengeCrowdfundingStatus, Error> {
+        Ok(self.challenge_crowdfunding(client).await?.0)
+    }
+
+    /// The challenge transaction spending the selected contributions, once they fund it.
+    pub async fn crowdfunded_challenge(&self, client: &AsyncClient) -> Result<Transaction, Error> {
+        match self.challenge_crowdfunding(client).await? {
+            (ChallengeCrowdfundingStatus::Funded { .. }, contributions) => {
+                let outpoints = contributions
+                    .iter()
+                    .map(|contribution| contribution.outpoint)
+                    .collect::<Vec<_>>();
+                Ok(self
+                    .challenge_transaction
+                    .finalize_with_crowdfunding_inputs(&outpoints))
+            }
+            (ChallengeCrowdfundingStatus::Open { funded, required }, _) => {
+                Err(Error::Graph(GraphError::ChallengeUnderfunded {
+                    funded,
+                    required,
+                }))
+            }
+            (status, _) => Err(Error::Graph(GraphError::ChallengeCrowdfundingUnavailable(
+                status,
+            ))),
+        }
+    }
+
+    // Crowdfunding status of the challenge transaction, with the contributions selected to fund
+    // it if it is funded.
+    async fn challenge_crowdfunding(
+        &self,
+        client: &AsyncClient,
+    ) -> Result<(ChallengeCrowdfundingStatus, Vec<ChallengeContribution>), Error> {
+        let kick_off_1_status = client
+            .get_tx_status(&self.kick_off_1_txid())
+            .await
+            .map_err(Error::Esplora)?;
+        if !kick_off_1_status.confirmed {
+            return Ok((ChallengeCrowdfundingStatus::NotOpen, vec![]));
+        }
+
+        let connector_a = self.challenge_transaction.tx().input[0].previous_output;
+        if let Some(OutputStatus {
+            spent: true,
+            txid: Some(spending_txid),
+            ..
+        }) = client
+            .get_output_status(&connector_a.txid, connector_a.vout as u64)
+            .await
+            .map_err(Error::Esplora)?
+        {
+            // The challenge output is fixed, whichever contributions were spent.
+            let spending_tx = client
+                .get_tx(&spending_txid)
+                .await
+                .map_err(Error::Esplora)?;
+            let status = match spending_tx {
+                Some(tx) if tx.output.first() == self.challenge_transaction.tx().output.first() => {
+                    ChallengeCrowdfundingStatus::Challenged(spending_txid)
+                }
+                _ => ChallengeCrowdfundingStatus::Closed,
+            };
+            return Ok((status, vec![]));
+        }
+
+        let contributions = self.challenge_contributions(client).await?;
+        let funded = contributions
+            .iter()
+            .map(|contribution| contribution.amount)
+            .sum();
+        let required_amount = |input_count| {
+            self.challenge_transaction
+                .required_crowdfunding_amount(input_count)
+        };
+        Ok(
+            match select_contributions(&contributions, required_amount) {
+                Some(selected) => (
+                    ChallengeCrowdfundingStatus::Funded {
+                        funded,
+                        required: required_amount(selected.len()),
+                    },
+                    selected,
+                ),
+                None => (
+                    ChallengeCrowdfundingStatus::Open {
+                        funded,
+                        required: required_amount(contributions.len().max(1)),
+                    },
+                    vec![],
+                ),
+            },
+        )
+    }
+
+    // The challenge transaction that was broadcast, if any. Its crowdfunding inputs depend on
+    // who funded it, so it is found as the transaction spending connector A to the challenge
+    // output.
+    async fn broadcast_challenge_txid(
+        &self,
+        client: &AsyncClient,
+    ) -> Result<Option<Txid>, esplora_client::Error> {
+        let connector_a = self.challenge_transaction.tx().input[0].previous_output;
+        let Some(OutputStatus {
+            spent: true,
+            txid: Some(spending_txid),
+            ..
+        }) = client
+            .get_output_status(&connector_a.txid, connector_a.vout as u64)
+            .await?
+        else {
+            return Ok(None);
+        };
+
+        Ok(match client.get_tx(&spending_txid).await? {
+            Some(tx) if tx.output.first() == self.challenge_transaction.tx().output.first() => {
+                Some(spending_txid)
+            }
+            _ => None,
+        })
+    }
+
+    async fn challenge_tx_status(
+        &self,
+        client: &AsyncClient,
+    ) -> Result<TxStatus, esplora_client::Error> {
+        match self.broadcast_challenge_txid(client).await? {
+            Some(txid) => client.get_tx_status(&txid).await,
+            None => Ok(TxStatus {
+                confirmed: false,
+                block_height: None,
+                block_hash: None,
+                block_time: None,
+            }),
+        }
+    }
+
+    async fn verify_challenge_not_mined(&self, client: &AsyncClient) -> Result<(), Error> {
+        match self
+            .broadcast_challenge_txid(client)
+            .await
+            .map_err(Error::Esplora)?
+        {
+            Some(txid) => verify_if_not_mined(client, txid).await,
+            None => Ok(()),
+        }
+    }
+
+    /// Outpoints of the contributions of `public_key` to the challenge transaction.
+    pub fn challenge_contributions_of(&self, public_key: &PublicKey) -> Vec<OutPoint> {
+        self.challenge_transaction
+            .crowdfunding_outpoints_of(public_key)
+    }
+
     pub async fn start_time(
         &mut self,
         client: &AsyncClient,
@@ -1976,7 +2984,7 @@ impl PegOutGraph {
 
     pub async fn take_1(&mut self, client: &AsyncClient) -> Result<Transaction, Error> {
         verify_if_not_mined(client, self.take_1_transaction.tx().compute_txid()).await?;
-        verify_if_not_mined(client, self.challenge_transaction.tx().compute_txid()).await?;
+        self.verify_challenge_not_mined(client).await?;
         verify_if_not_mined(client, self.assert_initial_transaction.tx().compute_txid()).await?;
         verify_if_not_mined(client, self.disprove_chain_transaction.tx().compute_txid()).await?;
 
@@ -2066,10 +3074,112 @@ impl PegOutGraph {
 
     pub fn is_peg_out_initiated(&self) -> bool { self.peg_out_chain_event.is_some() }
 
+    /// Verifies that `proof` proves the burn, on the L2 chain, of the peg-out of this graph's
+    /// peg-in fronted by its operator, and for the withdrawer of the peg-out event if there is
+    /// one. If the kick-off 1 transaction is given, the burn transaction must also be the one its
+    /// operator committed to. Returns the proven burn event.
+    pub fn verify_l2_burn_proof(
+        &self,
+        proof: &L2Proof,
+        config: &L2ProofConfig,
+        kick_off_1_tx: Option<&Transaction>,
+    ) -> Result<PegOutBurntEvent, Error> {
+        let burn_event = proof.verify(config).map_err(Error::L2)?;
+        if burn_event.source_outpoint.txid != self.peg_in_confirm_txid
+            || burn_event.operator_public_key != self.operator_public_key
+            || self.peg_out_chain_event.as_ref().is_some_and(|event| {
+                event.withdrawer_chain_address != burn_event.withdrawer_chain_address
+            })
+            || kick_off_1_tx.is_some_and(|tx| {
+                self.committed_destination_network_txid(tx) != Some(burn_event.tx_hash.clone())
+            })
+        {
+            return Err(Error::L2(L2Error::BurnEventMismatch));
+        }
+
+        Ok(burn_event)
+    }
+
+    /// The burn event proven by the first of `proofs` that is valid for this graph, see
+    /// `verify_l2_burn_proof`.
+    pub fn proven_burn_event(
+        &self,
+        proofs: &[L2Proof],
+        config: &L2ProofConfig,
+        kick_off_1_tx: Option<&Transaction>,
+    ) -> Result<PegOutBurntEvent, Error> {
+        proofs
+            .iter()
+            .find_map(|proof| self.verify_l2_burn_proof(proof, config, kick_off_1_tx).ok())
+            .ok_or(Error::L2(L2Error::L2ProofNotFound))
+    }
+
+    /// The destination network txid, i.e. the hash of the L2 burn transaction, the operator
+    /// committed to in `kick_off_1_tx`, the signed kick-off 1 transaction of this graph. `None`
+    /// if it is another transaction or the commitment doesn't verify.
+    pub fn committed_destination_network_txid(
+        &self,
+        kick_off_1_tx: &Transaction,
+    ) -> Option<Vec<u8>> {
+        if kick_off_1_tx.compute_txid() != self.kick_off_1_txid() {
+            return None;
+        }
+
+        let public_keys = &self.connector_6.commitment_public_keys;
+        let source_network_txid_public_key =
+            public_keys.get(&CommitmentMessageId::PegOutTxIdSourceNetwork)?;
+        let destination_network_txid_public_key =
+            public_keys.get(&CommitmentMessageId::PegOutTxIdDestinationNetwork)?;
+
+        // Input 0 witness: the Schnorr signature, the source network txid commitment, the
+        // destination network txid commitment, the script and the control block.
+        let witness = kick_off_1_tx.input.first()?.witness.to_vec();
+        let destination_start =
+            1 + 2 * source_network_txid_public_key.parameters.total_length() as usize;
+        let destination_end = destination_start
+            + 2 * destination_network_txid_public_key
+                .parameters
+                .total_length() as usize;
+
+        decode_winternitz_message(
+            destination_network_txid_public_key,
+            witness.get(destination_start..destination_end)?,
+        )
+    }
+
     pub fn min_crowdfunding_amount(&self) -> u64 {
         self.challenge_transaction.min_crowdfunding_amount()
     }
 
+    pub fn required_crowdfunding_amount(&self, input_count: usize) -> Amount {
+        self.challenge_transaction
+            .required_crowdfunding_amount(input_count)
+    }
+
+    pub fn operator_public_key(&self) -> &PublicKey { &self.operator_public_key }
+
+    pub fn kick_off_1_txid(&self) -> Txid { self.kick_off_1_transaction.tx().compute_txid() }
+
+    pub fn peg_in_confirm_txid(&self) -> Txid { self.peg_in_confirm_txid }
+
+    /// Checks that `commitment_secrets` are the secrets behind the Winternitz public keys this
+    /// graph commits to in connectors 1, 2 and 6.
+    pub fn matches_commitment_secrets(
+        &self,
+        commitment_secrets: &HashMap<CommitmentMessageId, WinternitzSecret>,
+    ) -> bool {
+        self.connector_1
+            .commitment_public_keys
+            .iter()
+            .chain(self.connector_2.commitment_public_keys.iter())
+            .chain(self.connector_6.commitment_public_keys.iter())
+            .all(|(message_id, public_key)| {
+                commitment_secrets
+                    .get(message_id)
+                    .is_some_and(|secret| WinternitzPublicKey::from(secret).eq(public_key))
+            })
+    }
+
     pub async fn match_and_set_peg_out_event(
         &mut self,
         all_events: &mut Vec<PegOutEvent>,
@@ -2135,9 +3245,7 @@ impl PegOutGraph {
             .get_tx_status(&self.assert_final_transaction.tx().compute_txid())
             .await;
 
-        let challenge_status = client
-            .get_tx_status(&self.challenge_transaction.tx().compute_txid())
-            .await;
+        let challenge_status = self.challenge_tx_status(client).await;
 
         let disprove_chain_status = client
             .get_tx_status(&self.disprove_chain_transaction.tx().compute_txid())
@@ -2215,167 +3323,59 @@ impl PegOutGraph {
         )
     }
 
-    pub fn validate(&self) -> bool {
-        let mut ret_val = true;
-        let peg_out_graph = self.new_for_validation();
-        if !validate_transaction(
-            self.assert_initial_transaction.tx(),
-            peg_out_graph.assert_initial_transaction.tx(),
-        ) {
-            ret_val = false;
-        }
-        if !validate_transaction(
-            self.assert_commit_1_transaction.tx(),
-            peg_out_graph.assert_commit_1_transaction.tx(),
-        ) {
-            ret_val = false;
-        }
-        if !validate_transaction(
-            self.assert_commit_2_transaction.tx(),
-            peg_out_graph.assert_commit_2_transaction.tx(),
-        ) {
-            ret_val = false;
-        }
-        if !validate_transaction(
-            self.assert_final_transaction.tx(),
-            peg_out_graph.assert_final_transaction.tx(),
-        ) {
-            ret_val = false;
-        }
-        if !validate_transaction(
-            self.challenge_transaction.tx(),
-            peg_out_graph.challenge_transaction.tx(),
-        ) {
-            ret_val = false;
-        }
-        if !validate_transaction(
-            self.disprove_chain_transaction.tx(),
-            peg_out_graph.disprove_chain_transaction.tx(),
-        ) {
-            ret_val = false;
-        }
-        if !validate_transaction(
-            self.disprove_transaction.tx(),
-            peg_out_graph.disprove_transaction.tx(),
-        ) {
-            ret_val = false;
-        }
-        if !validate_transaction(
-            self.peg_out_confirm_transaction.tx(),
-            peg_out_graph.peg_out_confirm_transaction.tx(),
-        ) {
-            ret_val = false;
-        }
-        if !validate_transaction(
-            self.kick_off_1_transaction.tx(),
-            peg_out_graph.kick_off_1_transaction.tx(),
-        ) {
-            ret_val = false;
-        }
-        if !validate_transaction(
-            self.kick_off_2_transaction.tx(),
-            peg_out_graph.kick_off_2_transaction.tx(),
-        ) {
-            ret_val = false;
-        }
-        if !validate_transaction(
-            self.kick_off_timeout_transaction.tx(),
-            peg_out_graph.kick_off_timeout_transaction.tx(),
-        ) {
-            ret_val = false;
-        }
-        if !validate_transaction(
-            self.start_time_transaction.tx(),
-            peg_out_graph.start_time_transaction.tx(),
-        ) {
-            ret_val = false;
-        }
-        if !validate_transaction(
-            self.start_time_timeout_transaction.tx(),
-            peg_out_graph.start_time_timeout_transaction.tx(),
-        ) {
-            ret_val = false;
-        }
-        if !validate_transaction(
-            self.take_1_transaction.tx(),
-            peg_out_graph.take_1_transaction.tx(),
-        ) {
-            ret_val = false;
-        }
-        if !validate_transaction(
-            self.take_2_transaction.tx(),
-            peg_out_graph.take_2_transaction.tx(),
-        ) {
-            ret_val = false;
-        }
-
-        if !verify_public_nonces_for_tx(&self.assert_initial_transaction) {
-            ret_val = false;
-        }
-        if !verify_public_nonces_for_tx(&self.assert_final_transaction) {
-            ret_val = false;
-        }
-        if !verify_public_nonces_for_tx(&self.disprove_chain_transaction) {
-            ret_val = false;
-        }
-        if !verify_public_nonces_for_tx(&self.disprove_transaction) {
-            ret_val = false;
-        }
-        if !verify_public_nonces_for_tx(&self.kick_off_timeout_transaction) {
-            ret_val = false;
-        }
-        if !verify_public_nonces_for_tx(&self.start_time_transaction) {
-            ret_val = false;
-        }
-        if !verify_public_nonces_for_tx(&self.start_time_timeout_transaction) {
-            ret_val = false;
-        }
-        if !verify_public_nonces_for_tx(&self.take_1_transaction) {
-            ret_val = false;
-        }
-        if !verify_public_nonces_for_tx(&self.take_2_transaction) {
-            ret_val = false;
-        }
-
-        ret_val
-    }
-
-    pub fn merge(&mut self, source_peg_out_graph: &PegOutGraph) {
-        self.assert_initial_transaction
-            .merge(&source_peg_out_graph.assert_initial_transaction);
-
+    /// Merges `source_peg_out_graph` into `self`, refusing the MuSig2 nonces and partial
+    /// signatures that do not verify against `n_of_n_public_keys`. Everything else is merged
+    /// regardless, and the first refusal is returned.
+    pub fn merge(
+        &mut self,
+        source_peg_out_graph: &PegOutGraph,
+        n_of_n_public_keys: &[PublicKey],
+    ) -> Result<(), Error> {
         self.assert_commit_1_transaction
             .merge(&source_peg_out_graph.assert_commit_1_transaction);
 
         self.assert_commit_2_transaction
             .merge(&source_peg_out_graph.assert_commit_2_transaction);
 
-        self.assert_final_transaction
-            .merge(&source_peg_out_graph.assert_final_transaction);
-
         self.challenge_transaction
             .merge(&source_peg_out_graph.challenge_transaction);
 
-        self.disprove_chain_transaction
-            .merge(&source_peg_out_graph.disprove_chain_transaction);
-
-        self.disprove_transaction
-            .merge(&source_peg_out_graph.disprove_transaction);
-
-        self.kick_off_timeout_transaction
-            .merge(&source_peg_out_graph.kick_off_timeout_transaction);
-
-        self.start_time_transaction
-            .merge(&source_peg_out_graph.start_time_transaction);
-
-        self.start_time_timeout_transaction
-            .merge(&source_peg_out_graph.start_time_timeout_transaction);
-
-        self.take_1_transaction
-            .merge(&source_peg_out_graph.take_1_transaction);
-
-        self.take_2_transaction
-            .merge(&source_peg_out_graph.take_2_transaction);
+        [
+            self.assert_initial_transaction.merge(
+                &source_peg_out_graph.assert_initial_transaction,
+                n_of_n_public_keys,
+            ),
+            self.assert_final_transaction.merge(
+                &source_peg_out_graph.assert_final_transaction,
+                n_of_n_public_keys,
+            ),
+            self.disprove_chain_transaction.merge(
+                &source_peg_out_graph.disprove_chain_transaction,
+                n_of_n_public_keys,
+            ),
+            self.disprove_transaction.merge(
+                &source_peg_out_graph.disprove_transaction,
+                n_of_n_public_keys,
+            ),
+            self.kick_off_timeout_transaction.merge(
+                &source_peg_out_graph.kick_off_timeout_transaction,
+                n_of_n_public_keys,
+            ),
+            self.start_time_transaction.merge(
+                &source_peg_out_graph.start_time_transaction,
+                n_of_n_public_keys,
+            ),
+            self.start_time_timeout_transaction.merge(
+                &source_peg_out_graph.start_time_timeout_transaction,
+                n_of_n_public_keys,
+            ),
+            self.take_1_transaction
+                .merge(&source_peg_out_graph.take_1_transaction, n_of_n_public_keys),
+            self.take_2_transaction
+                .merge(&source_peg_out_graph.take_2_transaction, n_of_n_public_keys),
+        ]
+        .into_iter()
+        .collect()
     }
 
     #[allow(clippy::too_many_arguments)]
@@ -2396,6 +3396,7 @@ impl PegOutGraph {
             CommitmentMessageId,
             WinternitzPublicKey,
         >],
+        protocol_parameters: &ProtocolParameters,
     ) -> PegOutConnectors {
         let connector_0 = Connector0::new(network, n_of_n_taproot_public_key);
         let connector_1 = Connector1::new(
@@ -2403,6 +3404,7 @@ impl PegOutGraph {
             operator_taproot_public_key,
             n_of_n_taproot_public_key,
             connector_1_commitment_public_keys,
+            protocol_parameters,
         );
         let connector_2 = Connector2::new(
             network,
@@ -2410,8 +3412,8 @@ impl PegOutGraph {
             n_of_n_taproot_public_key,
             connector_2_commitment_public_keys,
         );
-        let connector_3 = Connector3::new(network, operator_public_key);
-        let connector_4 = Connector4::new(network, operator_public_key);
+        let connector_3 = Connector3::new(network, operator_public_key, protocol_parameters);
+        let connector_4 = Connector4::new(network, operator_public_key, protocol_parameters);
         let connector_5 = Connector5::new(network, n_of_n_taproot_public_key);
         let connector_6 = Connector6::new(
             network,
@@ -2427,6 +3429,7 @@ impl PegOutGraph {
             network,
             n_of_n_taproot_public_key,
             connector_b_commitment_public_keys,
+            protocol_parameters,
         );
 
         // connector c pks = connector e1 pks + connector e2 pks
@@ -2532,9 +3535,32 @@ impl PegOutGraph {
 }
 
 pub fn generate_id(peg_in_graph: &PegInGraph, operator_public_key: &PublicKey) -> String {
+    generate_id_with_parameters(
+        peg_in_graph.id(),
+        operator_public_key,
+        peg_in_graph.protocol_parameters(),
+    )
+}
+
+fn generate_id_with_parameters(
+    peg_in_graph_id: &str,
+    operator_public_key: &PublicKey,
+    protocol_parameters: &ProtocolParameters,
+) -> String {
+    let mut hasher = Sha256::new();
+
+    hasher.update(peg_in_graph_id.to_string() + &operator_public_key.to_string());
+    hasher.update(protocol_parameters.hash());
+
+    hasher.finalize().to_hex_string(Upper)
+}
+
+// ID of the graphs created before the protocol parameters were configurable, always with the
+// default parameters of their network.
+fn generate_legacy_id(peg_in_graph_id: &str, operator_public_key: &PublicKey) -> String {
     let mut hasher = Sha256::new();
 
-    hasher.update(peg_in_graph.id().to_string() + &operator_public_key.to_string());
+    hasher.update(peg_in_graph_id.to_string() + &operator_public_key.to_string());
 
     hasher.finalize().to_hex_string(Upper)
 }
@@ -0,0 +1,174 @@
+use bitcoin::{Amount, Network};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use super::{
+    constants::{
+        NUM_BLOCKS_PER_2_WEEKS, NUM_BLOCKS_PER_3_DAYS, NUM_BLOCKS_PER_6_HOURS, NUM_BLOCKS_PER_DAY,
+    },
+    graphs::base::{CROWDFUNDING_AMOUNT, DUST_AMOUNT, REWARD_MULTIPLIER, REWARD_PRECISION},
+    utils::num_blocks_per_network,
+};
+
+// BIP68 relative timelocks in blocks are limited to the lower 16 bits of nSequence.
+const MAX_RELATIVE_TIMELOCK: u32 = 0xFFFF;
+
+/// Deployment-wide protocol parameters shared by every participant of a bridge instance.
+///
+/// All timelocks are relative timelocks expressed in blocks. The parameters are committed
+/// into the peg-in and peg-out graph IDs, so graphs created with different parameters never
+/// collide and cannot be merged into each other. Graphs created before the parameters were
+/// configurable got the defaults of their network, see `ProtocolParameters::for_network`.
+#[derive(Serialize, Deserialize, Eq, PartialEq, Clone, Copy, Debug)]
+pub struct ProtocolParameters {
+    /// Connector 1, leaf 0: operator can kick off 2 after committing to a superblock.
+    pub connector_1_timelock_leaf_0: u32,
+    /// Connector 1, leaf 1: n-of-n can time out a kick-off that was never followed up.
+    pub connector_1_timelock_leaf_1: u32,
+    /// Connector 1, leaf 2: n-of-n can time out a missing start time commitment.
+    pub connector_1_timelock_leaf_2: u32,
+    /// Connector 3: operator can take 1 if kick-off 2 was not challenged.
+    pub connector_3_timelock: u32,
+    /// Connector 4: operator can take 2 after the final assert.
+    pub connector_4_timelock: u32,
+    /// Connector B, leaf 1: n-of-n can disprove the chain within this window.
+    pub connector_b_timelock_1: u32,
+    /// Connector Z, leaf 0: depositor can refund an unconfirmed peg-in.
+    pub connector_z_timelock_0: u32,
+    /// Operator reward as a fraction of the deposit, in units of `1 / REWARD_PRECISION`.
+    pub reward_multiplier: u64,
+    /// Amount in sats a challenger has to crowdfund to challenge a kick-off.
+    pub crowdfunding_amount: u64,
+    /// Amount in sats of the connector outputs that only carry a spending condition.
+    pub dust_amount: u64,
+}
+
+impl ProtocolParameters {
+    /// Default parameters for `network`. Test networks use short timelocks.
+    pub fn for_network(network: Network) -> Self {
+        ProtocolParameters {
+            connector_1_timelock_leaf_0: num_blocks_per_network(network, NUM_BLOCKS_PER_2_WEEKS),
+            connector_1_timelock_leaf_1: num_blocks_per_network(
+                network,
+                NUM_BLOCKS_PER_2_WEEKS + NUM_BLOCKS_PER_DAY,
+            ),
+            connector_1_timelock_leaf_2: num_blocks_per_network(network, NUM_BLOCKS_PER_6_HOURS),
+            connector_3_timelock: num_blocks_per_network(network, NUM_BLOCKS_PER_3_DAYS),
+            connector_4_timelock: num_blocks_per_network(network, NUM_BLOCKS_PER_2_WEEKS),
+            connector_b_timelock_1: num_blocks_per_network(network, NUM_BLOCKS_PER_3_DAYS),
+            connector_z_timelock_0: num_blocks_per_network(network, NUM_BLOCKS_PER_2_WEEKS),
+            reward_multiplier: REWARD_MULTIPLIER,
+            crowdfunding_amount: Amount::from_btc(CROWDFUNDING_AMOUNT).unwrap().to_sat(),
+            dust_amount: DUST_AMOUNT,
+        }
+    }
+
+    /// Checks that the parameters describe a usable protocol instance.
+    pub fn validate(&self) -> bool {
+        let timelocks = [
+            self.connector_1_timelock_leaf_0,
+            self.connector_1_timelock_leaf_1,
+            self.connector_1_timelock_leaf_2,
+            self.connector_3_timelock,
+            self.connector_4_timelock,
+            self.connector_b_timelock_1,
+            self.connector_z_timelock_0,
+        ];
+        if timelocks
+            .iter()
+            .any(|&timelock| timelock == 0 || timelock > MAX_RELATIVE_TIMELOCK)
+        {
+            return false;
+        }
+
+        // The kick-off timeout must not become available before the operator could kick off 2.
+        if self.connector_1_timelock_leaf_1 < self.connector_1_timelock_leaf_0 {
+            return false;
+        }
+
+        self.reward_multiplier <= REWARD_PRECISION
+            && self.dust_amount > 0
+            && self.crowdfunding_amount > self.dust_amount
+    }
+
+    /// Hash of all the parameters, used to commit to them in graph IDs.
+    pub fn hash(&self) -> [u8; 32] {
+        let mut hasher = Sha256::new();
+
+        hasher.update(self.connector_1_timelock_leaf_0.to_be_bytes());
+        hasher.update(self.connector_1_timelock_leaf_1.to_be_bytes());
+        hasher.update(self.connector_1_timelock_leaf_2.to_be_bytes());
+        hasher.update(self.connector_3_timelock.to_be_bytes());
+        hasher.update(self.connector_4_timelock.to_be_bytes());
+        hasher.update(self.connector_b_timelock_1.to_be_bytes());
+        hasher.update(self.connector_z_timelock_0.to_be_bytes());
+        hasher.update(self.reward_multiplier.to_be_bytes());
+        hasher.update(self.crowdfunding_amount.to_be_bytes());
+        hasher.update(self.dust_amount.to_be_bytes());
+
+        hasher.finalize().into()
+    }
+
+    /// Operator reward for a peg-in of `deposit_amount`.
+    pub fn reward_amount(&self, deposit_amount: Amount) -> Amount {
+        deposit_amount * self.reward_multiplier / REWARD_PRECISION
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_default_parameters_are_valid() {
+        for network in [
+            Network::Bitcoin,
+            Network::Testnet,
+            Network::Signet,
+            Network::Regtest,
+        ] {
+            assert!(ProtocolParameters::for_network(network).validate());
+        }
+    }
+
+    #[test]
+    fn test_invalid_parameters() {
+        let parameters = ProtocolParameters::for_network(Network::Bitcoin);
+
+        let mut zero_timelock = parameters;
+        zero_timelock.connector_3_timelock = 0;
+        assert!(!zero_timelock.validate());
+
+        let mut timelock_too_long = parameters;
+        timelock_too_long.connector_4_timelock = MAX_RELATIVE_TIMELOCK + 1;
+        assert!(!timelock_too_long.validate());
+
+        let mut early_kick_off_timeout = parameters;
+        early_kick_off_timeout.connector_1_timelock_leaf_1 =
+            early_kick_off_timeout.connector_1_timelock_leaf_0 - 1;
+        assert!(!early_kick_off_timeout.validate());
+
+        let mut reward_too_high = parameters;
+        reward_too_high.reward_multiplier = REWARD_PRECISION + 1;
+        assert!(!reward_too_high.validate());
+    }
+
+    #[test]
+    fn test_hash_commits_to_every_parameter() {
+        let parameters = ProtocolParameters::for_network(Network::Bitcoin);
+        let hash = parameters.hash();
+
+        let mut other = parameters;
+        other.connector_b_timelock_1 += 1;
+        assert_ne!(hash, other.hash());
+
+        let mut other = parameters;
+        other.dust_amount += 1;
+        assert_ne!(hash, other.hash());
+
+        assert_ne!(
+            hash,
+            ProtocolParameters::for_network(Network::Testnet).hash()
+        );
+    }
+}
@@ -1,6 +1,4 @@
-use bitcoin::{
-    absolute, consensus, Amount, PublicKey, ScriptBuf, TapSighashType, Transaction, TxOut,
-};
+use bitcoin::{absolute, consensus, Amount, PublicKey, ScriptBuf, Transaction, TxOut};
 use musig2::{secp256k1::schnorr::Signature, PartialSignature, PubNonce, SecNonce};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
@@ -12,7 +10,8 @@ use super::{
             connector_c::ConnectorC,
         },
         contexts::{base::BaseContext, verifier::VerifierContext},
-        graphs::base::DUST_AMOUNT,
+        error::Error,
+        parameters::ProtocolParameters,
     },
     base::*,
     pre_signed::*,
@@ -73,8 +72,16 @@ impl AssertTransaction {
         connector_b: &ConnectorB,
         connector_c: &ConnectorC,
         input_0: Input,
+        protocol_parameters: &ProtocolParameters,
     ) -> Self {
-        Self::new_for_validation(connector_4, connector_5, connector_b, connector_c, input_0)
+        Self::new_for_validation(
+            connector_4,
+            connector_5,
+            connector_b,
+            connector_c,
+            input_0,
+            protocol_parameters,
+        )
     }
 
     pub fn new_for_validation(
@@ -83,6 +90,7 @@ impl AssertTransaction {
         connector_b: &ConnectorB,
         connector_c: &ConnectorC,
         input_0: Input,
+        protocol_parameters: &ProtocolParameters,
     ) -> Self {
         let input_0_leaf = 1;
         let _input_0 = connector_b.generate_taproot_leaf_tx_in(input_0_leaf, &input_0);
@@ -90,12 +98,12 @@ impl AssertTransaction {
         let total_output_amount = input_0.amount - Amount::from_sat(MIN_RELAY_FEE_ASSERT);
 
         let _output_0 = TxOut {
-            value: Amount::from_sat(DUST_AMOUNT),
+            value: Amount::from_sat(protocol_parameters.dust_amount),
             script_pubkey: connector_4.generate_address().script_pubkey(),
         };
 
         let _output_2 = TxOut {
-            value: Amount::from_sat(DUST_AMOUNT),
+            value: Amount::from_sat(protocol_parameters.dust_amount),
             script_pubkey: connector_c.generate_taproot_address().script_pubkey(),
         };
 
@@ -127,31 +135,29 @@ impl AssertTransaction {
         context: &VerifierContext,
         connector_b: &ConnectorB,
         secret_nonce: &SecNonce,
-    ) {
+    ) -> Result<(), Error> {
         let input_index = 0;
-        pre_sign_musig2_taproot_input(
-            self,
-            context,
-            input_index,
-            TapSighashType::All,
-            secret_nonce,
-        );
+        pre_sign_musig2_taproot_input(self, context, input_index, secret_nonce)?;
 
-        // TODO: Consider verifying the final signature against the n-of-n public key and the tx.
         if self.musig2_signatures[&input_index].len() == context.n_of_n_public_keys.len() {
-            self.finalize_input_0(context, connector_b);
+            self.finalize_input_0(context, connector_b)?;
         }
+
+        Ok(())
     }
 
-    fn finalize_input_0(&mut self, context: &dyn BaseContext, connector_b: &ConnectorB) {
+    fn finalize_input_0(
+        &mut self,
+        context: &dyn BaseContext,
+        connector_b: &ConnectorB,
+    ) -> Result<(), Error> {
         let input_index = 0;
         finalize_musig2_taproot_input(
             self,
             context,
             input_index,
-            TapSighashType::All,
             connector_b.generate_taproot_spend_info(),
-        );
+        )
     }
 
     pub fn pre_sign(
@@ -159,14 +165,20 @@ impl AssertTransaction {
         context: &VerifierContext,
         connector_b: &ConnectorB,
         secret_nonces: &HashMap<usize, SecNonce>,
-    ) {
+    ) -> Result<(), Error> {
         let input_index = 0;
-        self.sign_input_0(context, connector_b, &secret_nonces[&input_index]);
+        self.sign_input_0(context, connector_b, &secret_nonces[&input_index])?;
+
+        Ok(())
     }
 
-    pub fn merge(&mut self, assert: &AssertTransaction) {
+    pub fn merge(
+        &mut self,
+        assert: &AssertTransaction,
+        n_of_n_public_keys: &[PublicKey],
+    ) -> Result<(), Error> {
         merge_transactions(&mut self.tx, &assert.tx);
-        merge_musig2_nonces_and_signatures(self, assert);
+        merge_musig2_nonces_and_signatures(self, assert, n_of_n_public_keys)
     }
 }
 
@@ -1,6 +1,5 @@
 use bitcoin::{
-    absolute, consensus, Amount, EcdsaSighashType, PublicKey, ScriptBuf, TapSighashType,
-    Transaction, TxOut,
+    absolute, consensus, Amount, EcdsaSighashType, PublicKey, ScriptBuf, Transaction, TxOut,
 };
 use musig2::{secp256k1::schnorr::Signature, PartialSignature, PubNonce, SecNonce};
 use serde::{Deserialize, Serialize};
@@ -15,7 +14,8 @@ use super::{
                 base::*, connector_4::Connector4, connector_5::Connector5, connector_c::ConnectorC,
             },
             contexts::{base::BaseContext, verifier::VerifierContext},
-            graphs::base::DUST_AMOUNT,
+            error::Error,
+            parameters::ProtocolParameters,
         },
         base::*,
         pre_signed::*,
@@ -83,6 +83,7 @@ impl AssertFinalTransaction {
         input_0: Input,
         input_1: Input,
         input_2: Input,
+        protocol_parameters: &ProtocolParameters,
     ) -> Self {
         let mut this = Self::new_for_validation(
             connector_4,
@@ -93,6 +94,7 @@ impl AssertFinalTransaction {
             input_0,
             input_1,
             input_2,
+            protocol_parameters,
         );
 
         this.sign_commit_inputs(context);
@@ -110,6 +112,7 @@ impl AssertFinalTransaction {
         input_0: Input,
         input_1: Input,
         input_2: Input,
+        protocol_parameters: &ProtocolParameters,
     ) -> Self {
         let input_0_leaf = 0;
         let _input_0 = connector_d.generate_taproot_leaf_tx_in(input_0_leaf, &input_0);
@@ -127,19 +130,19 @@ impl AssertFinalTransaction {
 
         // goes to take_2 tx
         let _output_0 = TxOut {
-            value: Amount::from_sat(DUST_AMOUNT),
+            value: Amount::from_sat(protocol_parameters.dust_amount),
             script_pubkey: connector_4.generate_address().script_pubkey(),
         };
 
         // goes to take_2 tx or disprove tx
         let _output_1 = TxOut {
-            value: total_output_amount - Amount::from_sat(DUST_AMOUNT) * 2,
+            value: total_output_amount - Amount::from_sat(protocol_parameters.dust_amount) * 2,
             script_pubkey: connector_5.generate_taproot_address().script_pubkey(),
         };
 
         // goes to take_2 tx or disprove tx
         let _output_2 = TxOut {
-            value: Amount::from_sat(DUST_AMOUNT),
+            value: Amount::from_sat(protocol_parameters.dust_amount),
             script_pubkey: connector_c.generate_taproot_address().script_pubkey(),
         };
 
@@ -186,20 +189,15 @@ impl AssertFinalTransaction {
         context: &VerifierContext,
         connector_d: &ConnectorD,
         secret_nonce: &SecNonce,
-    ) {
+    ) -> Result<(), Error> {
         let input_index = 0;
-        pre_sign_musig2_taproot_input(
-            self,
-            context,
-            input_index,
-            TapSighashType::All,
-            secret_nonce,
-        );
+        pre_sign_musig2_taproot_input(self, context, input_index, secret_nonce)?;
 
-        // TODO: Consider verifying the final signature against the n-of-n public key and the tx.
         if self.musig2_signatures[&input_index].len() == context.n_of_n_public_keys.len() {
-            self.finalize_input_0(context, connector_d);
+            self.finalize_input_0(context, connector_d)?;
         }
+
+        Ok(())
     }
 
     fn sign_commit_inputs(&mut self, context: &OperatorContext) {
@@ -214,15 +212,18 @@ impl AssertFinalTransaction {
         }
     }
 
-    fn finalize_input_0(&mut self, context: &dyn BaseContext, connector_d: &ConnectorD) {
+    fn finalize_input_0(
+        &mut self,
+        context: &dyn BaseContext,
+        connector_d: &ConnectorD,
+    ) -> Result<(), Error> {
         let input_index = 0;
         finalize_musig2_taproot_input(
             self,
             context,
             input_index,
-            TapSighashType::All,
             connector_d.generate_taproot_spend_info(),
-        );
+        )
     }
 
     pub fn pre_sign(
@@ -230,14 +231,20 @@ impl AssertFinalTransaction {
         context: &VerifierContext,
         connector_d: &ConnectorD,
         secret_nonces: &HashMap<usize, SecNonce>,
-    ) {
+    ) -> Result<(), Error> {
         let input_index = 0;
-        self.sign_input_0(context, connector_d, &secret_nonces[&input_index]);
+        self.sign_input_0(context, connector_d, &secret_nonces[&input_index])?;
+
+        Ok(())
     }
 
-    pub fn merge(&mut self, assert: &AssertFinalTransaction) {
+    pub fn merge(
+        &mut self,
+        assert: &AssertFinalTransaction,
+        n_of_n_public_keys: &[PublicKey],
+    ) -> Result<(), Error> {
         merge_transactions(&mut self.tx, &assert.tx);
-        merge_musig2_nonces_and_signatures(self, assert);
+        merge_musig2_nonces_and_signatures(self, assert, n_of_n_public_keys)
     }
 }
 
@@ -1,6 +1,4 @@
-use bitcoin::{
-    absolute, consensus, Amount, PublicKey, ScriptBuf, TapSighashType, Transaction, TxOut,
-};
+use bitcoin::{absolute, consensus, Amount, PublicKey, ScriptBuf, Transaction, TxOut};
 use musig2::{secp256k1::schnorr::Signature, PartialSignature, PubNonce, SecNonce};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
@@ -10,7 +8,8 @@ use super::{
         super::{
             connectors::{base::*, connector_b::ConnectorB, connector_d::ConnectorD},
             contexts::{base::BaseContext, verifier::VerifierContext},
-            graphs::base::DUST_AMOUNT,
+            error::Error,
+            parameters::ProtocolParameters,
         },
         base::*,
         pre_signed::*,
@@ -73,6 +72,7 @@ impl AssertInitialTransaction {
         assert_commit1_connectors_e: &AssertCommit1ConnectorsE,
         assert_commit2_connectors_e: &AssertCommit2ConnectorsE,
         input_0: Input,
+        protocol_parameters: &ProtocolParameters,
     ) -> Self {
         Self::new_for_validation(
             connector_b,
@@ -80,6 +80,7 @@ impl AssertInitialTransaction {
             assert_commit1_connectors_e,
             assert_commit2_connectors_e,
             input_0,
+            protocol_parameters,
         )
     }
 
@@ -89,7 +90,10 @@ impl AssertInitialTransaction {
         assert_commit1_connectors_e: &AssertCommit1ConnectorsE,
         assert_commit2_connectors_e: &AssertCommit2ConnectorsE,
         input_0: Input,
+        protocol_parameters: &ProtocolParameters,
     ) -> Self {
+        let dust_amount = protocol_parameters.dust_amount;
+
         let input_0_leaf = 1;
         let _input_0 = connector_b.generate_taproot_leaf_tx_in(input_0_leaf, &input_0);
 
@@ -97,11 +101,11 @@ impl AssertInitialTransaction {
 
         let assert_commit1_expense = Amount::from_sat(
             MIN_RELAY_FEE_ASSERT_COMMIT1
-                + assert_commit1_connectors_e.connectors_num() as u64 * DUST_AMOUNT,
+                + assert_commit1_connectors_e.connectors_num() as u64 * dust_amount,
         );
         let assert_commit2_expense = Amount::from_sat(
             MIN_RELAY_FEE_ASSERT_COMMIT2
-                + assert_commit2_connectors_e.connectors_num() as u64 * DUST_AMOUNT,
+                + assert_commit2_connectors_e.connectors_num() as u64 * dust_amount,
         );
         // goes to assert_final
         let _output_0 = TxOut {
@@ -114,9 +118,9 @@ impl AssertInitialTransaction {
         // simple outputs for assert_x txs
         for i in 0..assert_commit1_connectors_e.connectors_num() {
             let amount = if i == 0 {
-                MIN_RELAY_FEE_ASSERT_COMMIT1 + DUST_AMOUNT
+                MIN_RELAY_FEE_ASSERT_COMMIT1 + dust_amount
             } else {
-                DUST_AMOUNT
+                dust_amount
             };
             output.push(TxOut {
                 value: Amount::from_sat(amount),
@@ -130,9 +134,9 @@ impl AssertInitialTransaction {
         // simple outputs for assert_x txs
         for i in 0..assert_commit2_connectors_e.connectors_num() {
             let amount = if i == 0 {
-                MIN_RELAY_FEE_ASSERT_COMMIT2 + DUST_AMOUNT
+                MIN_RELAY_FEE_ASSERT_COMMIT2 + dust_amount
             } else {
-                DUST_AMOUNT
+                dust_amount
             };
             output.push(TxOut {
                 value: Amount::from_sat(amount),
@@ -166,31 +170,29 @@ impl AssertInitialTransaction {
         context: &VerifierContext,
         connector_b: &ConnectorB,
         secret_nonce: &SecNonce,
-    ) {
+    ) -> Result<(), Error> {
         let input_index = 0;
-        pre_sign_musig2_taproot_input(
-            self,
-            context,
-            input_index,
-            TapSighashType::All,
-            secret_nonce,
-        );
+        pre_sign_musig2_taproot_input(self, context, input_index, secret_nonce)?;
 
-        // TODO: Consider verifying the final signature against the n-of-n public key and the tx.
         if self.musig2_signatures[&input_index].len() == context.n_of_n_public_keys.len() {
-            self.finalize_input_0(context, connector_b);
+            self.finalize_input_0(context, connector_b)?;
         }
+
+        Ok(())
     }
 
-    fn finalize_input_0(&mut self, context: &dyn BaseContext, connector_b: &ConnectorB) {
+    fn finalize_input_0(
+        &mut self,
+        context: &dyn BaseContext,
+        connector_b: &ConnectorB,
+    ) -> Result<(), Error> {
         let input_index = 0;
         finalize_musig2_taproot_input(
             self,
             context,
             input_index,
-            TapSighashType::All,
             connector_b.generate_taproot_spend_info(),
-        );
+        )
     }
 
     pub fn pre_sign(
@@ -198,14 +200,20 @@ impl AssertInitialTransaction {
         context: &VerifierContext,
         connector_b: &ConnectorB,
         secret_nonces: &HashMap<usize, SecNonce>,
-    ) {
+    ) -> Result<(), Error> {
         let input_index = 0;
-        self.sign_input_0(context, connector_b, &secret_nonces[&input_index]);
+        self.sign_input_0(context, connector_b, &secret_nonces[&input_index])?;
+
+        Ok(())
     }
 
-    pub fn merge(&mut self, assert: &AssertInitialTransaction) {
+    pub fn merge(
+        &mut self,
+        assert: &AssertInitialTransaction,
+        n_of_n_public_keys: &[PublicKey],
+    ) -> Result<(), Error> {
         merge_transactions(&mut self.tx, &assert.tx);
-        merge_musig2_nonces_and_signatures(self, assert);
+        merge_musig2_nonces_and_signatures(self, assert, n_of_n_public_keys)
     }
 }
 
@@ -1,13 +1,17 @@
-use super::pre_signed_musig2::{verify_public_nonce, PreSignedMusig2Transaction};
-use crate::graphs::base::MIN_RELAY_FEE_RATE;
+use super::pre_signed_musig2::{
+    verify_musig2_partial_signature, verify_public_nonce, PreSignedMusig2Transaction,
+};
+use crate::{
+    error::{Error, Musig2Blame, Musig2Error},
+    graphs::base::MIN_RELAY_FEE_RATE,
+};
 use bitcoin::{Amount, OutPoint, PublicKey, Script, Transaction, Txid, XOnlyPublicKey};
-use core::cmp;
-use itertools::Itertools;
 use musig2::{secp256k1::schnorr::Signature, PubNonce};
 use std::collections::HashMap;
 
 // TODO: set to larger value to be compatible with future tx modifications
 pub const RELAY_FEE_BUFFER_MULTIPLIER: f32 = 1.0;
+// Generated by `bridge fee-table` from the measured vsizes, see `transactions::fee_table`.
 pub const MIN_RELAY_FEE_KICK_OFF_1: u64 = relay_fee(6231);
 pub const MIN_RELAY_FEE_START_TIME: u64 = relay_fee(407);
 pub const MIN_RELAY_FEE_START_TIME_TIMEOUT: u64 = relay_fee(265);
@@ -15,12 +19,11 @@ pub const MIN_RELAY_FEE_KICK_OFF_2: u64 = relay_fee(5461);
 pub const MIN_RELAY_FEE_KICK_OFF_TIMEOUT: u64 = relay_fee(182);
 pub const MIN_RELAY_FEE_TAKE_1: u64 = relay_fee(380);
 pub const MIN_RELAY_FEE_TAKE_2: u64 = relay_fee(347);
-pub const MIN_RELAY_FEE_PEG_IN_DEPOSIT: u64 = relay_fee(122);
+pub const MIN_RELAY_FEE_PEG_IN_DEPOSIT: u64 = relay_fee(123);
 pub const MIN_RELAY_FEE_PEG_IN_CONFIRM: u64 = relay_fee(173);
 pub const MIN_RELAY_FEE_PEG_IN_REFUND: u64 = relay_fee(138);
-pub const MIN_RELAY_FEE_PEG_OUT: u64 = relay_fee(122);
-pub const MIN_RELAY_FEE_PEG_OUT_CONFIRM: u64 = relay_fee(122);
-pub const MIN_RELAY_FEE_ASSERT: u64 = relay_fee(232);
+pub const MIN_RELAY_FEE_PEG_OUT: u64 = relay_fee(123);
+pub const MIN_RELAY_FEE_PEG_OUT_CONFIRM: u64 = relay_fee(123);
 pub const MIN_RELAY_FEE_ASSERT_INITIAL: u64 = relay_fee(48953);
 pub const MIN_RELAY_FEE_ASSERT_COMMIT1: u64 = relay_fee(739137);
 pub const MIN_RELAY_FEE_ASSERT_COMMIT2: u64 = relay_fee(470440);
@@ -28,6 +31,9 @@ pub const MIN_RELAY_FEE_ASSERT_FINAL: u64 = relay_fee(352);
 pub const MIN_RELAY_FEE_CHALLENGE: u64 = relay_fee(317);
 pub const MIN_RELAY_FEE_DISPROVE: u64 = relay_fee(194415);
 pub const MIN_RELAY_FEE_DISPROVE_CHAIN: u64 = relay_fee(389370);
+// End of the generated constants.
+// The single assert transaction isn't part of the graphs, so it can't be measured.
+pub const MIN_RELAY_FEE_ASSERT: u64 = relay_fee(232);
 
 pub struct Input {
     pub outpoint: OutPoint,
@@ -60,22 +66,22 @@ pub fn merge_transactions(
     destination_transaction: &mut Transaction,
     source_transaction: &Transaction,
 ) {
-    for i in destination_transaction.input.len()..source_transaction.input.len() {
-        destination_transaction
+    // Inputs are matched by outpoint, so that inputs appended concurrently by different parties
+    // (e.g. ANYONECANPAY crowdfunding inputs) are all kept.
+    for source_input in &source_transaction.input {
+        match destination_transaction
             .input
-            .push(source_transaction.input[i].clone());
-    }
-
-    for i in 0..cmp::min(
-        destination_transaction.input.len(),
-        source_transaction.input.len(),
-    ) {
-        // TODO: takes longer witness data but should combine both
-        // TODO: merge signatures after Musig2 feature is ready
-        if destination_transaction.input[i].witness.len()
-            < source_transaction.input[i].witness.len()
+            .iter_mut()
+            .find(|input| input.previous_output == source_input.previous_output)
         {
-            destination_transaction.input[i].witness = source_transaction.input[i].witness.clone();
+            Some(destination_input) => {
+                // TODO: takes longer witness data but should combine both
+                // TODO: merge signatures after Musig2 feature is ready
+                if destination_input.witness.len() < source_input.witness.len() {
+                    destination_input.witness = source_input.witness.clone();
+                }
+            }
+            None => destination_transaction.input.push(source_input.clone()),
         }
     }
 
@@ -86,40 +92,87 @@ pub fn merge_transactions(
     }
 }
 
-// assumes source_transaction is the latest
+/// Merges the nonces and partial signatures of `source_transaction`, assumed to be the latest,
+/// into `destination_transaction`. Nonces that are not signed by one of `n_of_n_public_keys` and
+/// partial signatures that do not verify against the signer's public key and nonce are refused.
+/// The valid ones are merged regardless, and the first refusal is returned.
 pub fn merge_musig2_nonces_and_signatures(
     destination_transaction: &mut dyn PreSignedMusig2Transaction,
     source_transaction: &dyn PreSignedMusig2Transaction,
-) {
-    let nonces = destination_transaction.musig2_nonces_mut();
-    merge_hash_maps(nonces, source_transaction.musig2_nonces().clone());
+    n_of_n_public_keys: &[PublicKey],
+) -> Result<(), Error> {
+    let txid = destination_transaction.tx().compute_txid();
+    let mut refusal = None;
+
+    for (&input_index, nonces) in source_transaction.musig2_nonces() {
+        for (verifier, nonce) in nonces {
+            let signature = source_transaction
+                .musig2_nonce_signatures()
+                .get(&input_index)
+                .and_then(|signatures| signatures.get(verifier))
+                .filter(|signature| {
+                    n_of_n_public_keys.contains(verifier)
+                        && verify_public_nonce(signature, nonce, &XOnlyPublicKey::from(*verifier))
+                });
+            let Some(signature) = signature else {
+                let blame = Musig2Blame {
+                    txid,
+                    input_index,
+                    verifier: *verifier,
+                };
+                refusal.get_or_insert(match n_of_n_public_keys.contains(verifier) {
+                    true => Musig2Error::InvalidNonce(blame),
+                    false => Musig2Error::UnknownSigner(blame),
+                });
+                continue;
+            };
+
+            destination_transaction
+                .musig2_nonces_mut()
+                .entry(input_index)
+                .or_default()
+                .insert(*verifier, nonce.clone());
+            destination_transaction
+                .musig2_nonce_signatures_mut()
+                .entry(input_index)
+                .or_default()
+                .insert(*verifier, *signature);
+        }
+    }
 
-    let nonce_signatures = destination_transaction.musig2_nonce_signatures_mut();
-    merge_hash_maps(
-        nonce_signatures,
-        source_transaction.musig2_nonce_signatures().clone(),
-    );
+    // Verified against the merged nonces, as the partial signatures are made after all the
+    // verifiers pushed theirs.
+    for (&input_index, signatures) in source_transaction.musig2_signatures() {
+        for (verifier, &signature) in signatures {
+            let existing_signature = destination_transaction
+                .musig2_signatures()
+                .get(&input_index)
+                .and_then(|signatures| signatures.get(verifier));
+            if existing_signature == Some(&signature) {
+                continue;
+            }
+            if let Err(err) = verify_musig2_partial_signature(
+                &*destination_transaction,
+                n_of_n_public_keys,
+                input_index,
+                verifier,
+                signature,
+            ) {
+                refusal.get_or_insert(err);
+                continue;
+            }
 
-    let signatures = destination_transaction.musig2_signatures_mut();
-    merge_hash_maps(signatures, source_transaction.musig2_signatures().clone());
-}
+            destination_transaction
+                .musig2_signatures_mut()
+                .entry(input_index)
+                .or_default()
+                .insert(*verifier, signature);
+        }
+    }
 
-// merge the nonce/signature hashmaps. We can't just do a.extend(b) since that would just overwrite the inner
-// hashmap rather than merging it
-fn merge_hash_maps<T: Clone>(
-    a: &mut HashMap<usize, HashMap<PublicKey, T>>,
-    b: HashMap<usize, HashMap<PublicKey, T>>,
-) {
-    let all_keys = a
-        .keys()
-        .chain(b.keys())
-        .unique()
-        .cloned()
-        .collect::<Vec<_>>();
-    for key in all_keys {
-        let q = a.entry(key).or_default();
-        let w = b.get(&key).cloned().unwrap_or(HashMap::new());
-        q.extend(w.clone());
+    match refusal {
+        Some(err) => Err(Error::Musig2(err)),
+        None => Ok(()),
     }
 }
 
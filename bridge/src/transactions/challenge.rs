@@ -1,11 +1,16 @@
 use bitcoin::{
-    absolute, consensus, key::Keypair, Amount, Network, PublicKey, ScriptBuf, Sequence,
-    TapSighashType, Transaction, TxIn, TxOut, Witness,
+    absolute, consensus, key::Keypair, script::Instruction, secp256k1::Message,
+    sighash::SighashCache, Amount, EcdsaSighashType, Network, OutPoint, PublicKey, Script,
+    ScriptBuf, Sequence, TapSighashType, Transaction, TxIn, TxOut, Witness,
 };
+use secp256k1::SECP256K1;
 use serde::{Deserialize, Serialize};
 use std::cmp::Ordering;
 
-use crate::{connectors::base::TaprootConnector, graphs::base::DUST_AMOUNT};
+use crate::{
+    connectors::base::TaprootConnector,
+    error::{Error, TransactionError},
+};
 
 use super::{
     super::{connectors::connector_a::ConnectorA, contexts::operator::OperatorContext, scripts::*},
@@ -14,6 +19,11 @@ use super::{
     signing::populate_p2wsh_witness,
 };
 
+// Fee of every crowdfunding input beyond the one `MIN_RELAY_FEE_CHALLENGE` accounts for: 41 bytes
+// of outpoint, empty script sig and sequence, plus a witness of an ECDSA signature and a
+// pay-to-pubkey script.
+pub const MIN_RELAY_FEE_CHALLENGE_CROWDFUNDING_INPUT: u64 = relay_fee(69);
+
 #[derive(Serialize, Deserialize, Eq, PartialEq, Clone)]
 pub struct ChallengeTransaction {
     #[serde(with = "consensus::serde::With::<consensus::serde::Hex>")]
@@ -32,6 +42,8 @@ impl PreSignedTransaction for ChallengeTransaction {
     fn prev_outs(&self) -> &Vec<TxOut> { &self.prev_outs }
 
     fn prev_scripts(&self) -> &Vec<ScriptBuf> { &self.prev_scripts }
+
+    fn accepts_appended_inputs(&self) -> bool { true }
 }
 
 impl ChallengeTransaction {
@@ -105,15 +117,19 @@ impl ChallengeTransaction {
         );
     }
 
-    // allows for aggregating multiple inputs and one refund output
+    // allows for aggregating multiple inputs and one refund output, unless the refund would be
+    // less than `dust_amount`
     pub fn add_inputs_and_output(
         &mut self,
         inputs: &Vec<InputWithScript>,
         keypair: &Keypair,
         output_script_pubkey: ScriptBuf,
-    ) {
-        if self.tx.input.len() > 1 {
-            panic!("Cannot add any more inputs or outputs.");
+        dust_amount: Amount,
+    ) -> Result<(), Error> {
+        if self.tx.input.len() > 1 || self.tx.output.len() > 1 {
+            return Err(Error::Transaction(
+                TransactionError::CrowdfundingInputsAlreadyAdded,
+            ));
         }
 
         // check total input amount
@@ -122,10 +138,17 @@ impl ChallengeTransaction {
             total_input_amount += input.amount;
         }
         match total_input_amount.cmp(&self.input_amount_crowdfunding) {
-            Ordering::Less => panic!("Total input amount too low. Add additional input."),
+            Ordering::Less => {
+                return Err(Error::Transaction(
+                    TransactionError::InsufficientCrowdfunding {
+                        funded: total_input_amount,
+                        required: self.input_amount_crowdfunding,
+                    },
+                ))
+            }
             Ordering::Greater => {
                 let discrepency = total_input_amount - self.input_amount_crowdfunding;
-                if discrepency.to_sat() >= DUST_AMOUNT {
+                if discrepency >= dust_amount {
                     // add refund output
                     let _output = TxOut {
                         value: discrepency,
@@ -162,6 +185,146 @@ impl ChallengeTransaction {
 
             input_index += 1;
         }
+
+        Ok(())
+    }
+
+    /// Appends a crowdfunding input signed with SIGHASH_ALL|ANYONECANPAY, so that other
+    /// contributors can append theirs. Unlike `add_inputs_and_output` there is no refund output,
+    /// the inputs beyond `min_crowdfunding_amount` are paid as fee.
+    pub fn add_crowdfunding_input(
+        &mut self,
+        input: &InputWithScript,
+        keypair: &Keypair,
+    ) -> Result<(), Error> {
+        if self.tx.output.len() > 1 {
+            return Err(Error::Transaction(
+                TransactionError::CrowdfundingInputAfterRefundOutput,
+            ));
+        }
+        if self
+            .tx
+            .input
+            .iter()
+            .any(|tx_in| tx_in.previous_output == input.outpoint)
+        {
+            return Err(Error::Transaction(
+                TransactionError::DuplicateCrowdfundingInput(input.outpoint),
+            ));
+        }
+
+        self.tx.input.push(TxIn {
+            previous_output: input.outpoint,
+            script_sig: ScriptBuf::new(),
+            sequence: Sequence::MAX,
+            witness: Witness::default(),
+        });
+        populate_p2wsh_witness(
+            &mut self.tx,
+            self.tx.input.len() - 1,
+            EcdsaSighashType::AllPlusAnyoneCanPay,
+            input.script,
+            input.amount,
+            &vec![keypair],
+        );
+
+        Ok(())
+    }
+
+    /// The challenge transaction without the crowdfunding inputs and refund output added so
+    /// far, e.g. the contributions merged from the data store, to be funded alone with
+    /// `add_inputs_and_output`. The signature of input 0 commits to output 0 only.
+    pub fn without_crowdfunding_inputs(&self) -> Self {
+        let mut challenge = self.clone();
+        challenge.tx.input.truncate(1);
+        challenge.tx.output.truncate(1);
+
+        challenge
+    }
+
+    /// Outpoints of the crowdfunding inputs added so far.
+    pub fn crowdfunding_outpoints(&self) -> Vec<OutPoint> {
+        self.tx.input[1..]
+            .iter()
+            .map(|input| input.previous_output)
+            .collect()
+    }
+
+    /// Outpoints of the crowdfunding inputs spending pay-to-pubkey outputs of `public_key`.
+    pub fn crowdfunding_outpoints_of(&self, public_key: &PublicKey) -> Vec<OutPoint> {
+        let script = generate_pay_to_pubkey_script(public_key);
+        self.tx.input[1..]
+            .iter()
+            .filter(|input| input.witness.last() == Some(script.as_bytes()))
+            .map(|input| input.previous_output)
+            .collect()
+    }
+
+    /// Checks that the crowdfunding input spending `outpoint` spends `prev_out`, a pay-to-pubkey
+    /// P2WSH output, with a SIGHASH_ALL|ANYONECANPAY signature of its key. Returns the key.
+    pub fn verify_crowdfunding_input(
+        &self,
+        outpoint: &OutPoint,
+        prev_out: &TxOut,
+    ) -> Option<PublicKey> {
+        let input_index = self
+            .tx
+            .input
+            .iter()
+            .skip(1)
+            .position(|input| input.previous_output == *outpoint)?
+            + 1;
+        let witness = &self.tx.input[input_index].witness;
+        if witness.len() != 2 {
+            return None;
+        }
+
+        let script = Script::from_bytes(witness.last()?);
+        if ScriptBuf::new_p2wsh(&script.wscript_hash()) != prev_out.script_pubkey {
+            return None;
+        }
+        let public_key = match script.instructions().next()? {
+            Ok(Instruction::PushBytes(bytes)) => PublicKey::from_slice(bytes.as_bytes()).ok()?,
+            _ => return None,
+        };
+        if generate_pay_to_pubkey_script(&public_key).as_script() != script {
+            return None;
+        }
+
+        let signature = bitcoin::ecdsa::Signature::from_slice(&witness[0]).ok()?;
+        if signature.sighash_type != EcdsaSighashType::AllPlusAnyoneCanPay {
+            return None;
+        }
+        let sighash = SighashCache::new(&self.tx)
+            .p2wsh_signature_hash(input_index, script, prev_out.value, signature.sighash_type)
+            .ok()?;
+        SECP256K1
+            .verify_ecdsa(
+                &Message::from(sighash),
+                &signature.signature,
+                &public_key.inner,
+            )
+            .ok()?;
+
+        Some(public_key)
+    }
+
+    /// The challenge transaction spending only the crowdfunding inputs of `outpoints`. Their
+    /// signatures stay valid as they don't commit to the other inputs.
+    pub fn finalize_with_crowdfunding_inputs(&self, outpoints: &[OutPoint]) -> Transaction {
+        let mut tx = self.tx.clone();
+        tx.input = self
+            .tx
+            .input
+            .iter()
+            .enumerate()
+            .filter(|(input_index, input)| {
+                *input_index == 0 || outpoints.contains(&input.previous_output)
+            })
+            .map(|(_, input)| input.clone())
+            .collect();
+
+        tx
     }
 
     pub fn merge(&mut self, challenge: &ChallengeTransaction) {
@@ -169,6 +332,15 @@ impl ChallengeTransaction {
     }
 
     pub fn min_crowdfunding_amount(&self) -> u64 { self.input_amount_crowdfunding.to_sat() }
+
+    /// Crowdfunding amount required to broadcast the challenge transaction with `input_count`
+    /// crowdfunding inputs.
+    pub fn required_crowdfunding_amount(&self, input_count: usize) -> Amount {
+        self.input_amount_crowdfunding
+            + Amount::from_sat(
+                MIN_RELAY_FEE_CHALLENGE_CROWDFUNDING_INPUT * input_count.saturating_sub(1) as u64,
+            )
+    }
 }
 
 impl BaseTransaction for ChallengeTransaction {
@@ -11,6 +11,7 @@ use super::{
     super::{
         connectors::{base::*, connector_5::Connector5, connector_c::ConnectorC},
         contexts::{base::BaseContext, operator::OperatorContext, verifier::VerifierContext},
+        error::Error,
         scripts::*,
     },
     base::*,
@@ -65,6 +66,9 @@ impl PreSignedMusig2Transaction for DisproveTransaction {
         &mut self.musig2_signatures
     }
     fn verifier_inputs(&self) -> Vec<usize> { vec![0] }
+    fn verifier_sighash_type(&self, _input_index: usize) -> TapSighashType {
+        TapSighashType::Single
+    }
 }
 
 impl DisproveTransaction {
@@ -138,31 +142,29 @@ impl DisproveTransaction {
         context: &VerifierContext,
         connector_5: &Connector5,
         secret_nonce: &SecNonce,
-    ) {
+    ) -> Result<(), Error> {
         let input_index = 0;
-        pre_sign_musig2_taproot_input(
-            self,
-            context,
-            input_index,
-            TapSighashType::Single,
-            secret_nonce,
-        );
+        pre_sign_musig2_taproot_input(self, context, input_index, secret_nonce)?;
 
-        // TODO: Consider verifying the final signature against the n-of-n public key and the tx.
         if self.musig2_signatures[&input_index].len() == context.n_of_n_public_keys.len() {
-            self.finalize_input_0(context, connector_5);
+            self.finalize_input_0(context, connector_5)?;
         }
+
+        Ok(())
     }
 
-    fn finalize_input_0(&mut self, context: &dyn BaseContext, connector_5: &Connector5) {
+    fn finalize_input_0(
+        &mut self,
+        context: &dyn BaseContext,
+        connector_5: &Connector5,
+    ) -> Result<(), Error> {
         let input_index = 0;
         finalize_musig2_taproot_input(
             self,
             context,
             input_index,
-            TapSighashType::Single,
             connector_5.generate_taproot_spend_info(),
-        );
+        )
     }
 
     pub fn pre_sign(
@@ -170,9 +172,11 @@ impl DisproveTransaction {
         context: &VerifierContext,
         connector_5: &Connector5,
         secret_nonces: &HashMap<usize, SecNonce>,
-    ) {
+    ) -> Result<(), Error> {
         let input_index = 0;
-        self.sign_input_0(context, connector_5, &secret_nonces[&input_index]);
+        self.sign_input_0(context, connector_5, &secret_nonces[&input_index])?;
+
+        Ok(())
     }
 
     pub fn add_input_output(
@@ -204,9 +208,13 @@ impl DisproveTransaction {
         );
     }
 
-    pub fn merge(&mut self, disprove: &DisproveTransaction) {
+    pub fn merge(
+        &mut self,
+        disprove: &DisproveTransaction,
+        n_of_n_public_keys: &[PublicKey],
+    ) -> Result<(), Error> {
         merge_transactions(&mut self.tx, &disprove.tx);
-        merge_musig2_nonces_and_signatures(self, disprove);
+        merge_musig2_nonces_and_signatures(self, disprove, n_of_n_public_keys)
     }
 }
 
@@ -12,6 +12,7 @@ use super::{
     super::{
         connectors::connector_b::ConnectorB,
         contexts::{base::BaseContext, operator::OperatorContext, verifier::VerifierContext},
+        error::Error,
         scripts::*,
     },
     base::*,
@@ -66,6 +67,9 @@ impl PreSignedMusig2Transaction for DisproveChainTransaction {
         &mut self.musig2_signatures
     }
     fn verifier_inputs(&self) -> Vec<usize> { vec![0] }
+    fn verifier_sighash_type(&self, _input_index: usize) -> TapSighashType {
+        TapSighashType::Single
+    }
 }
 
 impl DisproveChainTransaction {
@@ -115,31 +119,29 @@ impl DisproveChainTransaction {
         context: &VerifierContext,
         connector_b: &ConnectorB,
         secret_nonce: &SecNonce,
-    ) {
+    ) -> Result<(), Error> {
         let input_index = 0;
-        pre_sign_musig2_taproot_input(
-            self,
-            context,
-            input_index,
-            TapSighashType::Single,
-            secret_nonce,
-        );
+        pre_sign_musig2_taproot_input(self, context, input_index, secret_nonce)?;
 
-        // TODO: Consider verifying the final signature against the n-of-n public key and the tx.
         if self.musig2_signatures[&input_index].len() == context.n_of_n_public_keys.len() {
-            self.finalize_input_0(context, connector_b);
+            self.finalize_input_0(context, connector_b)?;
         }
+
+        Ok(())
     }
 
-    fn finalize_input_0(&mut self, context: &dyn BaseContext, connector_b: &ConnectorB) {
+    fn finalize_input_0(
+        &mut self,
+        context: &dyn BaseContext,
+        connector_b: &ConnectorB,
+    ) -> Result<(), Error> {
         let input_index = 0;
         finalize_musig2_taproot_input(
             self,
             context,
             input_index,
-            TapSighashType::Single,
             connector_b.generate_taproot_spend_info(),
-        );
+        )
     }
 
     pub fn pre_sign(
@@ -147,12 +149,14 @@ impl DisproveChainTransaction {
         context: &VerifierContext,
         connector_b: &ConnectorB,
         secret_nonces: &HashMap<usize, SecNonce>,
-    ) {
+    ) -> Result<(), Error> {
         let input_index = 0;
-        self.sign_input_0(context, connector_b, &secret_nonces[&input_index]);
+        self.sign_input_0(context, connector_b, &secret_nonces[&input_index])?;
         // TODO: We probably shouldn't finalize the witness when pre-signing (sign_input_0 calls finalize_input_0,
         // which adds a control block to the witness). Please double-check that the control block should be only added
         // after the tx is signed (see `sign()`) and ready to be broadcast.
+
+        Ok(())
     }
 
     pub fn sign(
@@ -183,9 +187,13 @@ impl DisproveChainTransaction {
         self.tx.output[output_index].script_pubkey = output_script_pubkey;
     }
 
-    pub fn merge(&mut self, disprove: &DisproveChainTransaction) {
+    pub fn merge(
+        &mut self,
+        disprove: &DisproveChainTransaction,
+        n_of_n_public_keys: &[PublicKey],
+    ) -> Result<(), Error> {
         merge_transactions(&mut self.tx, &disprove.tx);
-        merge_musig2_nonces_and_signatures(self, disprove);
+        merge_musig2_nonces_and_signatures(self, disprove, n_of_n_public_keys)
     }
 }
 
@@ -0,0 +1,234 @@
+use std::collections::BTreeMap;
+
+use bitcoin::{
+    taproot::{LeafVersion, TaprootSpendInfo},
+    Script, ScriptBuf, TapSighashType, Transaction, Witness,
+};
+use bitvm::signatures::signing_winternitz::WinternitzPublicKey;
+
+use super::base::{
+    relay_fee, MIN_RELAY_FEE_ASSERT_COMMIT1, MIN_RELAY_FEE_ASSERT_COMMIT2,
+    MIN_RELAY_FEE_ASSERT_FINAL, MIN_RELAY_FEE_ASSERT_INITIAL, MIN_RELAY_FEE_CHALLENGE,
+    MIN_RELAY_FEE_DISPROVE, MIN_RELAY_FEE_DISPROVE_CHAIN, MIN_RELAY_FEE_KICK_OFF_1,
+    MIN_RELAY_FEE_KICK_OFF_2, MIN_RELAY_FEE_KICK_OFF_TIMEOUT, MIN_RELAY_FEE_PEG_IN_CONFIRM,
+    MIN_RELAY_FEE_PEG_IN_DEPOSIT, MIN_RELAY_FEE_PEG_IN_REFUND, MIN_RELAY_FEE_PEG_OUT,
+    MIN_RELAY_FEE_PEG_OUT_CONFIRM, MIN_RELAY_FEE_START_TIME, MIN_RELAY_FEE_START_TIME_TIMEOUT,
+    MIN_RELAY_FEE_TAKE_1, MIN_RELAY_FEE_TAKE_2,
+};
+use crate::graphs::base::{BaseGraph, PEG_OUT_FEE_TRANSACTIONS};
+
+// Largest DER encoding of an ECDSA signature, plus the sighash byte.
+pub const ECDSA_SIGNATURE_SIZE: usize = 73;
+pub const SCHNORR_SIGNATURE_SIZE: usize = 64;
+// Non-zero, so that no element of a dummy witness gets the minimal encoding of 0 (empty).
+pub const DUMMY_BYTE: u8 = 1;
+
+/// The transaction each generated `MIN_RELAY_FEE_*` constant of `transactions::base` is measured
+/// on, with the name and the current value of the constant.
+pub const RELAY_FEE_CONSTANTS: [(&str, &str, u64); 20] = [
+    (
+        "KickOff1",
+        "MIN_RELAY_FEE_KICK_OFF_1",
+        MIN_RELAY_FEE_KICK_OFF_1,
+    ),
+    (
+        "StartTime",
+        "MIN_RELAY_FEE_START_TIME",
+        MIN_RELAY_FEE_START_TIME,
+    ),
+    (
+        "StartTimeTimeout",
+        "MIN_RELAY_FEE_START_TIME_TIMEOUT",
+        MIN_RELAY_FEE_START_TIME_TIMEOUT,
+    ),
+    (
+        "KickOff2",
+        "MIN_RELAY_FEE_KICK_OFF_2",
+        MIN_RELAY_FEE_KICK_OFF_2,
+    ),
+    (
+        "KickOffTimeout",
+        "MIN_RELAY_FEE_KICK_OFF_TIMEOUT",
+        MIN_RELAY_FEE_KICK_OFF_TIMEOUT,
+    ),
+    ("Take1", "MIN_RELAY_FEE_TAKE_1", MIN_RELAY_FEE_TAKE_1),
+    ("Take2", "MIN_RELAY_FEE_TAKE_2", MIN_RELAY_FEE_TAKE_2),
+    (
+        "PegInDeposit",
+        "MIN_RELAY_FEE_PEG_IN_DEPOSIT",
+        MIN_RELAY_FEE_PEG_IN_DEPOSIT,
+    ),
+    (
+        "PegInConfirm",
+        "MIN_RELAY_FEE_PEG_IN_CONFIRM",
+        MIN_RELAY_FEE_PEG_IN_CONFIRM,
+    ),
+    (
+        "PegInRefund",
+        "MIN_RELAY_FEE_PEG_IN_REFUND",
+        MIN_RELAY_FEE_PEG_IN_REFUND,
+    ),
+    ("PegOut", "MIN_RELAY_FEE_PEG_OUT", MIN_RELAY_FEE_PEG_OUT),
+    (
+        "PegOutConfirm",
+        "MIN_RELAY_FEE_PEG_OUT_CONFIRM",
+        MIN_RELAY_FEE_PEG_OUT_CONFIRM,
+    ),
+    (
+        "AssertInitial",
+        "MIN_RELAY_FEE_ASSERT_INITIAL",
+        MIN_RELAY_FEE_ASSERT_INITIAL,
+    ),
+    (
+        "AssertCommit1",
+        "MIN_RELAY_FEE_ASSERT_COMMIT1",
+        MIN_RELAY_FEE_ASSERT_COMMIT1,
+    ),
+    (
+        "AssertCommit2",
+        "MIN_RELAY_FEE_ASSERT_COMMIT2",
+        MIN_RELAY_FEE_ASSERT_COMMIT2,
+    ),
+    (
+        "AssertFinal",
+        "MIN_RELAY_FEE_ASSERT_FINAL",
+        MIN_RELAY_FEE_ASSERT_FINAL,
+    ),
+    (
+        "Challenge",
+        "MIN_RELAY_FEE_CHALLENGE",
+        MIN_RELAY_FEE_CHALLENGE,
+    ),
+    ("Disprove", "MIN_RELAY_FEE_DISPROVE", MIN_RELAY_FEE_DISPROVE),
+    (
+        "DisproveChain",
+        "MIN_RELAY_FEE_DISPROVE_CHAIN",
+        MIN_RELAY_FEE_DISPROVE_CHAIN,
+    ),
+];
+
+/// A generated relay fee constant whose value differs from the one measured.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StaleRelayFee {
+    pub constant_name: &'static str,
+    pub value: u64,
+    pub measured_vsize: usize,
+    pub measured_value: u64,
+}
+
+/// Measures the vsize of the transaction templates of `graphs`, see
+/// `BaseGraph::transaction_templates`. Transactions measured in several graphs keep the largest
+/// vsize.
+pub fn measure_vsizes(graphs: &[&dyn BaseGraph]) -> BTreeMap<&'static str, usize> {
+    let mut vsizes = BTreeMap::new();
+    for graph in graphs {
+        for (tx_name, tx) in graph.transaction_templates() {
+            let vsize = vsizes.entry(tx_name).or_insert(0);
+            *vsize = tx.vsize().max(*vsize);
+        }
+    }
+
+    vsizes
+}
+
+/// Renders the `MIN_RELAY_FEE_*` constants of `transactions::base` from the measured `vsizes`.
+/// Constants whose transaction wasn't measured are left out.
+pub fn render_fee_table(vsizes: &BTreeMap<&'static str, usize>) -> String {
+    RELAY_FEE_CONSTANTS
+        .iter()
+        .filter_map(|(tx_name, constant_name, _)| {
+            vsizes
+                .get(tx_name)
+                .map(|vsize| format!("pub const {constant_name}: u64 = relay_fee({vsize});\n"))
+        })
+        .collect()
+}
+
+/// Constants of `RELAY_FEE_CONSTANTS` that don't match the measured `vsizes`.
+pub fn stale_relay_fees(vsizes: &BTreeMap<&'static str, usize>) -> Vec<StaleRelayFee> {
+    RELAY_FEE_CONSTANTS
+        .iter()
+        .filter_map(|(tx_name, constant_name, value)| {
+            let measured_vsize = *vsizes.get(tx_name)?;
+            let measured_value = relay_fee(measured_vsize);
+            (measured_value != *value).then_some(StaleRelayFee {
+                constant_name: *constant_name,
+                value: *value,
+                measured_vsize,
+                measured_value,
+            })
+        })
+        .collect()
+}
+
+/// `PEG_OUT_FEE` computed from the measured `vsizes`, `None` if one of
+/// `PEG_OUT_FEE_TRANSACTIONS` wasn't measured.
+pub fn peg_out_fee(vsizes: &BTreeMap<&'static str, usize>) -> Option<u64> {
+    PEG_OUT_FEE_TRANSACTIONS
+        .iter()
+        .map(|tx_name| vsizes.get(tx_name).map(|vsize| relay_fee(*vsize)))
+        .sum()
+}
+
+/// Copy of `tx` whose inputs are spent with `witnesses`, in input order.
+pub fn with_witnesses(tx: &Transaction, witnesses: Vec<Witness>) -> Transaction {
+    assert_eq!(tx.input.len(), witnesses.len(), "One witness per input");
+    let mut tx = tx.clone();
+    for (input, witness) in tx.input.iter_mut().zip(witnesses) {
+        input.witness = witness;
+    }
+
+    tx
+}
+
+pub fn dummy_ecdsa_signature() -> Vec<u8> { vec![DUMMY_BYTE; ECDSA_SIGNATURE_SIZE] }
+
+pub fn dummy_schnorr_signature(sighash_type: TapSighashType) -> Vec<u8> {
+    let mut signature = vec![DUMMY_BYTE; SCHNORR_SIGNATURE_SIZE];
+    if sighash_type != TapSighashType::Default {
+        signature.push(sighash_type as u8);
+    }
+
+    signature
+}
+
+/// Winternitz signature with one hash and one single byte digit per digit of `public_key`.
+pub fn dummy_winternitz_witness(public_key: &WinternitzPublicKey) -> Vec<Vec<u8>> {
+    public_key
+        .public_key
+        .iter()
+        .flat_map(|hash| [vec![DUMMY_BYTE; hash.len()], vec![DUMMY_BYTE]])
+        .collect()
+}
+
+/// Witness of a P2WSH input spent with `signature_count` ECDSA signatures.
+pub fn dummy_p2wsh_witness(script: &Script, signature_count: usize) -> Witness {
+    let mut witness = Witness::new();
+    for _ in 0..signature_count {
+        witness.push(dummy_ecdsa_signature());
+    }
+    witness.push(script);
+
+    witness
+}
+
+pub fn dummy_key_path_witness(sighash_type: TapSighashType) -> Witness {
+    Witness::from_slice(&[dummy_schnorr_signature(sighash_type)])
+}
+
+/// Witness of a taproot input spent through the `script` leaf of `taproot_spend_info`.
+pub fn dummy_script_path_witness(
+    taproot_spend_info: &TaprootSpendInfo,
+    script: &Script,
+    unlock_data: Vec<Vec<u8>>,
+) -> Witness {
+    let control_block = taproot_spend_info
+        .control_block(&(ScriptBuf::from(script), LeafVersion::TapScript))
+        .expect("Unable to create Control block");
+
+    let mut witness = Witness::from_slice(&unlock_data);
+    witness.push(script);
+    witness.push(control_block.serialize());
+
+    witness
+}
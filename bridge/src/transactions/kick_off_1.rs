@@ -11,7 +11,7 @@ use super::{
             connector_a::ConnectorA,
         },
         contexts::operator::OperatorContext,
-        graphs::base::DUST_AMOUNT,
+        parameters::ProtocolParameters,
     },
     base::*,
     pre_signed::*,
@@ -45,6 +45,7 @@ impl KickOff1Transaction {
         connector_2: &Connector2,
         connector_6: &Connector6,
         input_0: Input,
+        protocol_parameters: &ProtocolParameters,
     ) -> Self {
         Self::new_for_validation(
             context.network,
@@ -54,9 +55,11 @@ impl KickOff1Transaction {
             connector_2,
             connector_6,
             input_0,
+            protocol_parameters,
         )
     }
 
+    #[allow(clippy::too_many_arguments)]
     pub fn new_for_validation(
         network: Network,
         operator_taproot_public_key: &XOnlyPublicKey,
@@ -65,6 +68,7 @@ impl KickOff1Transaction {
         connector_2: &Connector2,
         connector_6: &Connector6,
         input_0: Input,
+        protocol_parameters: &ProtocolParameters,
     ) -> Self {
         let connector_a = ConnectorA::new(
             network,
@@ -78,13 +82,13 @@ impl KickOff1Transaction {
         let total_output_amount = input_0.amount - Amount::from_sat(MIN_RELAY_FEE_KICK_OFF_1);
 
         let _output_0 = TxOut {
-            value: Amount::from_sat(DUST_AMOUNT),
+            value: Amount::from_sat(protocol_parameters.dust_amount),
             script_pubkey: connector_a.generate_taproot_address().script_pubkey(),
         };
 
         // fund start time relay fee here since it has no other inputs
         let _output_2 = TxOut {
-            value: Amount::from_sat(DUST_AMOUNT + MIN_RELAY_FEE_START_TIME),
+            value: Amount::from_sat(protocol_parameters.dust_amount + MIN_RELAY_FEE_START_TIME),
             script_pubkey: connector_2.generate_taproot_address().script_pubkey(),
         };
 
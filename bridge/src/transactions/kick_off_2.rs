@@ -10,7 +10,7 @@ use super::{
     super::{
         connectors::{connector_1::Connector1, connector_3::Connector3, connector_b::ConnectorB},
         contexts::operator::OperatorContext,
-        graphs::base::DUST_AMOUNT,
+        parameters::ProtocolParameters,
     },
     base::*,
     pre_signed::*,
@@ -48,6 +48,7 @@ impl KickOff2Transaction {
         connector_1: &Connector1,
         connector_b: &ConnectorB,
         input_0: Input,
+        protocol_parameters: &ProtocolParameters,
     ) -> Self {
         Self::new_for_validation(
             context.network,
@@ -55,6 +56,7 @@ impl KickOff2Transaction {
             connector_1,
             connector_b,
             input_0,
+            protocol_parameters,
         )
     }
 
@@ -64,8 +66,9 @@ impl KickOff2Transaction {
         connector_1: &Connector1,
         connector_b: &ConnectorB,
         input_0: Input,
+        protocol_parameters: &ProtocolParameters,
     ) -> Self {
-        let connector_3 = Connector3::new(network, operator_public_key);
+        let connector_3 = Connector3::new(network, operator_public_key, protocol_parameters);
 
         let input_0_leaf = 0;
         let _input_0 = connector_1.generate_taproot_leaf_tx_in(input_0_leaf, &input_0);
@@ -73,7 +76,7 @@ impl KickOff2Transaction {
         let total_output_amount = input_0.amount - Amount::from_sat(MIN_RELAY_FEE_KICK_OFF_2);
 
         let _output_0 = TxOut {
-            value: Amount::from_sat(DUST_AMOUNT),
+            value: Amount::from_sat(protocol_parameters.dust_amount),
             script_pubkey: connector_3.generate_address().script_pubkey(),
         };
 
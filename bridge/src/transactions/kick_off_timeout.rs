@@ -9,6 +9,7 @@ use super::{
     super::{
         connectors::{base::*, connector_1::Connector1},
         contexts::{base::BaseContext, operator::OperatorContext, verifier::VerifierContext},
+        error::Error,
         scripts::*,
     },
     base::*,
@@ -62,6 +63,9 @@ impl PreSignedMusig2Transaction for KickOffTimeoutTransaction {
         &mut self.musig2_signatures
     }
     fn verifier_inputs(&self) -> Vec<usize> { vec![0] }
+    fn verifier_sighash_type(&self, _input_index: usize) -> TapSighashType {
+        TapSighashType::Single
+    }
 }
 
 impl KickOffTimeoutTransaction {
@@ -110,31 +114,29 @@ impl KickOffTimeoutTransaction {
         context: &VerifierContext,
         connector_1: &Connector1,
         secret_nonce: &SecNonce,
-    ) {
+    ) -> Result<(), Error> {
         let input_index = 0;
-        pre_sign_musig2_taproot_input(
-            self,
-            context,
-            input_index,
-            TapSighashType::Single,
-            secret_nonce,
-        );
+        pre_sign_musig2_taproot_input(self, context, input_index, secret_nonce)?;
 
-        // TODO: Consider verifying the final signature against the n-of-n public key and the tx.
         if self.musig2_signatures[&input_index].len() == context.n_of_n_public_keys.len() {
-            self.finalize_input_0(context, connector_1);
+            self.finalize_input_0(context, connector_1)?;
         }
+
+        Ok(())
     }
 
-    fn finalize_input_0(&mut self, context: &dyn BaseContext, connector_1: &Connector1) {
+    fn finalize_input_0(
+        &mut self,
+        context: &dyn BaseContext,
+        connector_1: &Connector1,
+    ) -> Result<(), Error> {
         let input_index = 0;
         finalize_musig2_taproot_input(
             self,
             context,
             input_index,
-            TapSighashType::Single,
             connector_1.generate_taproot_spend_info(),
-        );
+        )
     }
 
     pub fn pre_sign(
@@ -142,9 +144,11 @@ impl KickOffTimeoutTransaction {
         context: &VerifierContext,
         connector_1: &Connector1,
         secret_nonces: &HashMap<usize, SecNonce>,
-    ) {
+    ) -> Result<(), Error> {
         let input_index = 0;
-        self.sign_input_0(context, connector_1, &secret_nonces[&input_index]);
+        self.sign_input_0(context, connector_1, &secret_nonces[&input_index])?;
+
+        Ok(())
     }
 
     pub fn add_output(&mut self, output_script_pubkey: ScriptBuf) {
@@ -152,9 +156,13 @@ impl KickOffTimeoutTransaction {
         self.tx.output[output_index].script_pubkey = output_script_pubkey;
     }
 
-    pub fn merge(&mut self, disprove: &KickOffTimeoutTransaction) {
+    pub fn merge(
+        &mut self,
+        disprove: &KickOffTimeoutTransaction,
+        n_of_n_public_keys: &[PublicKey],
+    ) -> Result<(), Error> {
         merge_transactions(&mut self.tx, &disprove.tx);
-        merge_musig2_nonces_and_signatures(self, disprove);
+        merge_musig2_nonces_and_signatures(self, disprove, n_of_n_public_keys)
     }
 }
 
@@ -4,6 +4,7 @@ pub mod base;
 pub mod challenge;
 pub mod disprove;
 pub mod disprove_chain;
+pub mod fee_table;
 pub mod kick_off_1;
 pub mod kick_off_2;
 pub mod kick_off_timeout;
@@ -14,9 +15,11 @@ pub mod peg_out;
 pub mod peg_out_confirm;
 pub mod pre_signed;
 pub mod pre_signed_musig2;
+pub mod psbt;
 pub mod signing;
 pub mod signing_musig2;
 pub mod start_time;
 pub mod start_time_timeout;
 pub mod take_1;
 pub mod take_2;
+pub mod validation;
@@ -9,6 +9,7 @@ use super::{
     super::{
         connectors::{base::*, connector_0::Connector0, connector_z::ConnectorZ},
         contexts::{base::BaseContext, depositor::DepositorContext, verifier::VerifierContext},
+        error::Error,
     },
     base::*,
     pre_signed::*,
@@ -169,31 +170,29 @@ impl PegInConfirmTransaction {
         context: &VerifierContext,
         connector_z: &ConnectorZ,
         secret_nonce: &SecNonce,
-    ) {
+    ) -> Result<(), Error> {
         let input_index = 0;
-        pre_sign_musig2_taproot_input(
-            self,
-            context,
-            input_index,
-            TapSighashType::All,
-            secret_nonce,
-        );
+        pre_sign_musig2_taproot_input(self, context, input_index, secret_nonce)?;
 
-        // TODO: Consider verifying the final signature against the n-of-n public key and the tx.
         if self.musig2_signatures[&input_index].len() == context.n_of_n_public_keys.len() {
-            self.finalize_input_0(context, connector_z);
+            self.finalize_input_0(context, connector_z)?;
         }
+
+        Ok(())
     }
 
-    fn finalize_input_0(&mut self, context: &dyn BaseContext, connector_z: &ConnectorZ) {
+    fn finalize_input_0(
+        &mut self,
+        context: &dyn BaseContext,
+        connector_z: &ConnectorZ,
+    ) -> Result<(), Error> {
         let input_index = 0;
         finalize_musig2_taproot_input(
             self,
             context,
             input_index,
-            TapSighashType::All,
             connector_z.generate_taproot_spend_info(),
-        );
+        )
     }
 
     pub fn pre_sign(
@@ -201,14 +200,20 @@ impl PegInConfirmTransaction {
         context: &VerifierContext,
         connector_z: &ConnectorZ,
         secret_nonces: &HashMap<usize, SecNonce>,
-    ) {
+    ) -> Result<(), Error> {
         let input_index = 0;
-        self.push_verifier_signature_input_0(context, connector_z, &secret_nonces[&input_index]);
+        self.push_verifier_signature_input_0(context, connector_z, &secret_nonces[&input_index])?;
+
+        Ok(())
     }
 
-    pub fn merge(&mut self, peg_in_confirm: &PegInConfirmTransaction) {
+    pub fn merge(
+        &mut self,
+        peg_in_confirm: &PegInConfirmTransaction,
+        n_of_n_public_keys: &[PublicKey],
+    ) -> Result<(), Error> {
         merge_transactions(&mut self.tx, &peg_in_confirm.tx);
-        merge_musig2_nonces_and_signatures(self, peg_in_confirm);
+        merge_musig2_nonces_and_signatures(self, peg_in_confirm, n_of_n_public_keys)
     }
 
     pub fn has_nonce_of(&self, context: &VerifierContext) -> bool {
@@ -15,6 +15,9 @@ pub trait PreSignedTransaction {
     fn tx_mut(&mut self) -> &mut Transaction;
     fn prev_outs(&self) -> &Vec<TxOut>;
     fn prev_scripts(&self) -> &Vec<ScriptBuf>;
+    // Whether participants may append inputs to the transaction, e.g. crowdfunding inputs
+    // signed with SIGHASH_ALL|ANYONECANPAY. Otherwise only the witnesses of its inputs change.
+    fn accepts_appended_inputs(&self) -> bool { false }
 }
 
 pub fn pre_sign_p2wsh_input<T: PreSignedTransaction>(
@@ -4,19 +4,21 @@ use bitcoin::{
     PublicKey, TapSighashType, XOnlyPublicKey,
 };
 use musig2::{
-    secp::MaybeScalar,
     secp256k1::{schnorr::Signature, Message},
-    BinaryEncoding, PartialSignature, PubNonce, SecNonce,
+    AggNonce, BinaryEncoding, PartialSignature, PubNonce, SecNonce,
 };
 use std::collections::HashMap;
 
 use super::{
-    super::contexts::{base::BaseContext, verifier::VerifierContext},
+    super::{
+        contexts::{base::BaseContext, verifier::VerifierContext},
+        error::{Error, Musig2Blame, Musig2Error},
+    },
     pre_signed::PreSignedTransaction,
     signing::push_taproot_leaf_script_and_control_block_to_witness,
     signing_musig2::{
         generate_aggregated_nonce, generate_nonce, generate_taproot_aggregated_signature,
-        generate_taproot_partial_signature,
+        generate_taproot_partial_signature, verify_taproot_partial_signature,
     },
 };
 
@@ -31,6 +33,8 @@ pub trait PreSignedMusig2Transaction: PreSignedTransaction {
         &mut self,
     ) -> &mut HashMap<usize, HashMap<PublicKey, PartialSignature>>;
     fn verifier_inputs(&self) -> Vec<usize>;
+    // Sighash type the verifiers sign the n-of-n input `input_index` with.
+    fn verifier_sighash_type(&self, _input_index: usize) -> TapSighashType { TapSighashType::All }
     fn has_nonces_for(&self, verifier_pubkey: PublicKey) -> bool {
         self.has_all_nonces(&[verifier_pubkey])
     }
@@ -111,30 +115,186 @@ pub fn verify_public_nonce(sig: &Signature, nonce: &PubNonce, pubkey: &XOnlyPubl
     verify_schnorr_signature(sig, &get_nonce_message(nonce), pubkey)
 }
 
+// Aggregates the nonces of `n_of_n_public_keys` pushed for `input_index`, skipping the missing ones.
+fn aggregate_input_nonces<T: PreSignedMusig2Transaction + ?Sized>(
+    tx: &T,
+    n_of_n_public_keys: &[PublicKey],
+    input_index: usize,
+) -> AggNonce {
+    let musig2_nonces = tx
+        .musig2_nonces()
+        .get(&input_index)
+        .map(|nonces| {
+            n_of_n_public_keys
+                .iter()
+                .filter_map(|public_key| nonces.get(public_key).cloned())
+                .collect()
+        })
+        .unwrap_or_default();
+
+    generate_aggregated_nonce(&musig2_nonces)
+}
+
+/// Checks that every verifier in `n_of_n_public_keys` pushed a nonce for `input_index`, signed
+/// with its key.
+pub fn verify_musig2_nonces<T: PreSignedMusig2Transaction + ?Sized>(
+    tx: &T,
+    n_of_n_public_keys: &[PublicKey],
+    input_index: usize,
+) -> Result<(), Musig2Error> {
+    let txid = tx.tx().compute_txid();
+    let nonces = tx.musig2_nonces().get(&input_index);
+    let missing_verifiers: Vec<PublicKey> = n_of_n_public_keys
+        .iter()
+        .filter(|public_key| !nonces.is_some_and(|nonces| nonces.contains_key(public_key)))
+        .cloned()
+        .collect();
+    if !missing_verifiers.is_empty() {
+        return Err(Musig2Error::MissingNonces {
+            txid,
+            input_index,
+            verifiers: missing_verifiers,
+        });
+    }
+
+    for (verifier, nonce) in nonces.into_iter().flatten() {
+        let blame = Musig2Blame {
+            txid,
+            input_index,
+            verifier: *verifier,
+        };
+        if !n_of_n_public_keys.contains(verifier) {
+            return Err(Musig2Error::UnknownSigner(blame));
+        }
+        let signature = tx
+            .musig2_nonce_signatures()
+            .get(&input_index)
+            .and_then(|signatures| signatures.get(verifier));
+        if !signature.is_some_and(|signature| {
+            verify_public_nonce(signature, nonce, &XOnlyPublicKey::from(*verifier))
+        }) {
+            return Err(Musig2Error::InvalidNonce(blame));
+        }
+    }
+
+    Ok(())
+}
+
+/// Verifies the partial signature of `verifier` for `input_index` against its public key and
+/// nonce. The nonces of all the verifiers must be present.
+pub fn verify_musig2_partial_signature<T: PreSignedMusig2Transaction + ?Sized>(
+    tx: &T,
+    n_of_n_public_keys: &[PublicKey],
+    input_index: usize,
+    verifier: &PublicKey,
+    partial_signature: PartialSignature,
+) -> Result<(), Musig2Error> {
+    let blame = Musig2Blame {
+        txid: tx.tx().compute_txid(),
+        input_index,
+        verifier: *verifier,
+    };
+    if !n_of_n_public_keys.contains(verifier) {
+        return Err(Musig2Error::UnknownSigner(blame));
+    }
+    verify_musig2_nonces(tx, n_of_n_public_keys, input_index)?;
+
+    verify_taproot_partial_signature(
+        n_of_n_public_keys,
+        tx.tx(),
+        &aggregate_input_nonces(tx, n_of_n_public_keys, input_index),
+        input_index,
+        tx.prev_outs(),
+        &tx.prev_scripts()[input_index],
+        tx.verifier_sighash_type(input_index),
+        verifier,
+        &tx.musig2_nonces()[&input_index][verifier],
+        partial_signature,
+    )
+    .map_err(|_| Musig2Error::InvalidPartialSignatures(vec![blame]))
+}
+
+/// Verifies that every verifier in `n_of_n_public_keys` contributed a valid nonce and partial
+/// signature to `input_index`. All the verifiers with an invalid partial signature are blamed.
+pub fn verify_musig2_partial_signatures<T: PreSignedMusig2Transaction + ?Sized>(
+    tx: &T,
+    n_of_n_public_keys: &[PublicKey],
+    input_index: usize,
+) -> Result<(), Musig2Error> {
+    verify_musig2_nonces(tx, n_of_n_public_keys, input_index)?;
+
+    let txid = tx.tx().compute_txid();
+    let empty = HashMap::new();
+    let signatures = tx.musig2_signatures().get(&input_index).unwrap_or(&empty);
+    if let Some(verifier) = signatures
+        .keys()
+        .find(|verifier| !n_of_n_public_keys.contains(verifier))
+    {
+        return Err(Musig2Error::UnknownSigner(Musig2Blame {
+            txid,
+            input_index,
+            verifier: *verifier,
+        }));
+    }
+    let missing_verifiers: Vec<PublicKey> = n_of_n_public_keys
+        .iter()
+        .filter(|public_key| !signatures.contains_key(public_key))
+        .cloned()
+        .collect();
+    if !missing_verifiers.is_empty() {
+        return Err(Musig2Error::MissingPartialSignatures {
+            txid,
+            input_index,
+            verifiers: missing_verifiers,
+        });
+    }
+
+    let mut blames = vec![];
+    for verifier in n_of_n_public_keys {
+        if let Err(Musig2Error::InvalidPartialSignatures(blame)) = verify_musig2_partial_signature(
+            tx,
+            n_of_n_public_keys,
+            input_index,
+            verifier,
+            signatures[verifier],
+        ) {
+            blames.extend(blame);
+        }
+    }
+    if !blames.is_empty() {
+        return Err(Musig2Error::InvalidPartialSignatures(blames));
+    }
+
+    Ok(())
+}
+
+/// Adds the verifier's partial signature of `input_index`, signed with the input's
+/// `verifier_sighash_type` over the aggregate of the nonces pushed so far.
 pub fn pre_sign_musig2_taproot_input<T: PreSignedTransaction + PreSignedMusig2Transaction>(
     tx: &mut T,
     context: &VerifierContext,
     input_index: usize,
-    sighash_type: TapSighashType,
     secret_nonce: &SecNonce,
-) {
+) -> Result<(), Error> {
     // TODO validate nonces first
 
+    let txid = tx.tx().compute_txid();
+    let sighash_type = tx.verifier_sighash_type(input_index);
     let prev_outs = &tx.prev_outs().clone();
     let script = &tx.prev_scripts()[input_index].clone();
-    let musig2_nonces = &tx.musig2_nonces()[&input_index].values().cloned().collect();
+    let aggregated_nonce = aggregate_input_nonces(tx, &context.n_of_n_public_keys, input_index);
 
     let partial_signature = generate_taproot_partial_signature(
         context,
         tx.tx_mut(),
         secret_nonce,
-        &generate_aggregated_nonce(musig2_nonces),
+        &aggregated_nonce,
         input_index,
         prev_outs,
         script,
         sighash_type,
     )
-    .unwrap(); // TODO: Add error handling.
+    .map_err(|_| Error::Musig2(Musig2Error::PartialSigning { txid, input_index }))?;
 
     let musig2_signatures = tx.musig2_signatures_mut();
     if musig2_signatures.get(&input_index).is_none() {
@@ -144,26 +304,30 @@ pub fn pre_sign_musig2_taproot_input<T: PreSignedTransaction + PreSignedMusig2Tr
         .get_mut(&input_index)
         .unwrap()
         .insert(context.verifier_public_key, partial_signature);
+
+    Ok(())
 }
 
+/// Aggregates the partial signatures of `input_index` and pushes the n-of-n signature to the
+/// witness. Fails without modifying the transaction if a verifier's nonce or partial signature
+/// is missing or invalid, blaming the verifiers at fault.
 pub fn finalize_musig2_taproot_input<T: PreSignedTransaction + PreSignedMusig2Transaction>(
     tx: &mut T,
     context: &dyn BaseContext,
     input_index: usize,
-    sighash_type: TapSighashType,
     taproot_spend_info: TaprootSpendInfo,
-) {
-    // TODO: Verify we have partial signatures from all verifiers.
-    // TODO: Verify each signature against the signers public key.
-    // See example here: https://github.com/conduition/musig2/blob/c39bfce58098d337a3ec38b54d93def8306d9953/src/signing.rs#L358C1-L366C65
+) -> Result<(), Error> {
+    let n_of_n_public_keys = context.n_of_n_public_keys();
+    verify_musig2_partial_signatures(tx, n_of_n_public_keys, input_index).map_err(Error::Musig2)?;
 
+    let txid = tx.tx().compute_txid();
+    let sighash_type = tx.verifier_sighash_type(input_index);
     let prev_outs = &tx.prev_outs().clone();
     let script = &tx.prev_scripts()[input_index].clone();
-    let musig2_nonces: &Vec<PubNonce> =
-        &tx.musig2_nonces()[&input_index].values().cloned().collect();
-    let musig2_signatures: Vec<MaybeScalar> = tx.musig2_signatures()[&input_index]
-        .values()
-        .map(|&partial_signature| PartialSignature::from(partial_signature))
+    let aggregated_nonce = aggregate_input_nonces(tx, n_of_n_public_keys, input_index);
+    let musig2_signatures: Vec<PartialSignature> = n_of_n_public_keys
+        .iter()
+        .map(|public_key| tx.musig2_signatures()[&input_index][public_key])
         .collect();
     let tx_mut = tx.tx_mut();
 
@@ -171,14 +335,14 @@ pub fn finalize_musig2_taproot_input<T: PreSignedTransaction + PreSignedMusig2Tr
     let signature = generate_taproot_aggregated_signature(
         context,
         tx_mut,
-        &generate_aggregated_nonce(musig2_nonces),
+        &aggregated_nonce,
         input_index,
         prev_outs,
         script,
         sighash_type,
-        musig2_signatures, // TODO: Is there a more elegant way of doing this?
+        musig2_signatures,
     )
-    .unwrap(); // TODO: Add error handling.
+    .map_err(|_| Error::Musig2(Musig2Error::Aggregation { txid, input_index }))?;
 
     let final_signature = bitcoin::taproot::Signature {
         signature: signature.into(),
@@ -197,4 +361,6 @@ pub fn finalize_musig2_taproot_input<T: PreSignedTransaction + PreSignedMusig2Tr
         &taproot_spend_info,
         script,
     );
+
+    Ok(())
 }
@@ -0,0 +1,233 @@
+use bitcoin::{
+    bip32::{DerivationPath, Fingerprint},
+    opcodes::all::{OP_CHECKSIG, OP_CHECKSIGADD, OP_CHECKSIGVERIFY},
+    psbt::Psbt,
+    script::Instruction,
+    secp256k1::Message,
+    sighash::{Prevouts, SighashCache},
+    taproot::{LeafVersion, TaprootSpendInfo},
+    PublicKey, Script, ScriptBuf, TapLeafHash, TapSighashType, Transaction, TxOut, Witness,
+    XOnlyPublicKey,
+};
+use secp256k1::SECP256K1;
+
+use super::pre_signed::PreSignedTransaction;
+use crate::error::{Error, PsbtError};
+
+/// Exports `tx` as a BIP174 PSBT, so the inputs can be signed by an offline signer.
+///
+/// Inputs that already carry a witness, e.g. the pre-signed n-of-n inputs, are exported as
+/// finalized. For taproot inputs the leaf script, control block, internal key and merkle root
+/// (BIP371) are taken from the spend info in `taproot_spend_infos` that matches the prevout.
+pub fn export_psbt(
+    tx: &dyn PreSignedTransaction,
+    taproot_spend_infos: &[TaprootSpendInfo],
+) -> Result<Psbt, Error> {
+    let mut unsigned_tx = tx.tx().clone();
+    let witnesses: Vec<Witness> = unsigned_tx
+        .input
+        .iter_mut()
+        .map(|input| std::mem::take(&mut input.witness))
+        .collect();
+    let mut psbt =
+        Psbt::from_unsigned_tx(unsigned_tx).map_err(|e| Error::Psbt(PsbtError::Encoding(e)))?;
+
+    for (input_index, (input, witness)) in psbt.inputs.iter_mut().zip(witnesses).enumerate() {
+        let (prev_out, script) = prevout_and_script(tx, input_index)?;
+        input.witness_utxo = Some(prev_out.clone());
+
+        if !witness.is_empty() {
+            input.final_script_witness = Some(witness);
+        } else if prev_out.script_pubkey.is_p2tr() {
+            let leaf = (script.clone(), LeafVersion::TapScript);
+            let (spend_info, control_block) = taproot_spend_infos
+                .iter()
+                .filter(|spend_info| {
+                    ScriptBuf::new_p2tr_tweaked(spend_info.output_key()) == prev_out.script_pubkey
+                })
+                .find_map(|spend_info| Some((spend_info, spend_info.control_block(&leaf)?)))
+                .ok_or(Error::Psbt(PsbtError::UnsupportedInput(input_index)))?;
+
+            let leaf_hash = TapLeafHash::from_script(script, LeafVersion::TapScript);
+            for key in checksig_keys(script) {
+                if let Ok(key) = XOnlyPublicKey::from_slice(&key) {
+                    input.tap_key_origins.insert(
+                        key,
+                        (
+                            vec![leaf_hash],
+                            (Fingerprint::default(), DerivationPath::master()),
+                        ),
+                    );
+                }
+            }
+            input.tap_scripts.insert(control_block, leaf);
+            input.tap_internal_key = Some(spend_info.internal_key());
+            input.tap_merkle_root = spend_info.merkle_root();
+        } else if prev_out.script_pubkey.is_p2wsh() {
+            input.witness_script = Some(script.clone());
+        } else if !prev_out.script_pubkey.is_p2wpkh() {
+            return Err(Error::Psbt(PsbtError::UnsupportedInput(input_index)));
+        }
+    }
+
+    Ok(psbt)
+}
+
+/// Verifies the signatures in a signed PSBT of `tx` and returns the transaction with all the
+/// witnesses finalized. The prevouts and scripts are taken from `tx`, not from the PSBT.
+///
+/// Only inputs whose script requires nothing but signatures can be finalized from a PSBT.
+pub fn finalize_psbt(tx: &dyn PreSignedTransaction, psbt: &Psbt) -> Result<Transaction, Error> {
+    if psbt.unsigned_tx.compute_txid() != tx.tx().compute_txid()
+        || psbt.inputs.len() != tx.tx().input.len()
+    {
+        return Err(Error::Psbt(PsbtError::TransactionMismatch));
+    }
+
+    let mut finalized_tx = psbt.unsigned_tx.clone();
+    for (input_index, input) in psbt.inputs.iter().enumerate() {
+        let (prev_out, script) = prevout_and_script(tx, input_index)?;
+
+        let witness = if let Some(witness) = &input.final_script_witness {
+            witness.clone()
+        } else if prev_out.script_pubkey.is_p2tr() {
+            let leaf = (script.clone(), LeafVersion::TapScript);
+            let (control_block, _) = input
+                .tap_scripts
+                .iter()
+                .find(|(_, tap_script)| **tap_script == leaf)
+                .ok_or(Error::Psbt(PsbtError::UnsupportedInput(input_index)))?;
+
+            let leaf_hash = TapLeafHash::from_script(script, LeafVersion::TapScript);
+            let mut witness = Witness::new();
+            // The last key in the script consumes the bottom-most signature.
+            for key in checksig_keys(script).iter().rev() {
+                let key = XOnlyPublicKey::from_slice(key)
+                    .map_err(|_| Error::Psbt(PsbtError::UnsupportedInput(input_index)))?;
+                let signature = input
+                    .tap_script_sigs
+                    .get(&(key, leaf_hash))
+                    .ok_or(Error::Psbt(PsbtError::MissingSignature(input_index)))?;
+
+                let prevouts = tx.prev_outs();
+                let mut sighash_cache = SighashCache::new(&psbt.unsigned_tx);
+                let sighash = if signature.sighash_type == TapSighashType::AllPlusAnyoneCanPay
+                    || signature.sighash_type == TapSighashType::SinglePlusAnyoneCanPay
+                    || signature.sighash_type == TapSighashType::NonePlusAnyoneCanPay
+                {
+                    sighash_cache.taproot_script_spend_signature_hash(
+                        input_index,
+                        &Prevouts::One(input_index, &prevouts[input_index]),
+                        leaf_hash,
+                        signature.sighash_type,
+                    )
+                } else {
+                    sighash_cache.taproot_script_spend_signature_hash(
+                        input_index,
+                        &Prevouts::All(prevouts),
+                        leaf_hash,
+                        signature.sighash_type,
+                    )
+                }
+                .map_err(|_| Error::Psbt(PsbtError::InvalidSignature(input_index)))?;
+                SECP256K1
+                    .verify_schnorr(&signature.signature, &Message::from(sighash), &key)
+                    .map_err(|_| Error::Psbt(PsbtError::InvalidSignature(input_index)))?;
+
+                witness.push(signature.to_vec());
+            }
+            witness.push(script.to_bytes());
+            witness.push(control_block.serialize());
+            witness
+        } else if prev_out.script_pubkey.is_p2wsh() || prev_out.script_pubkey.is_p2wpkh() {
+            let keys = if prev_out.script_pubkey.is_p2wsh() {
+                checksig_keys(script)
+            } else {
+                input
+                    .partial_sigs
+                    .keys()
+                    .map(|key| key.to_bytes())
+                    .collect()
+            };
+            let mut witness = Witness::new();
+            for key in keys.iter().rev() {
+                let key = PublicKey::from_slice(key)
+                    .map_err(|_| Error::Psbt(PsbtError::UnsupportedInput(input_index)))?;
+                let signature = input
+                    .partial_sigs
+                    .get(&key)
+                    .ok_or(Error::Psbt(PsbtError::MissingSignature(input_index)))?;
+
+                let mut sighash_cache = SighashCache::new(&psbt.unsigned_tx);
+                let sighash = if prev_out.script_pubkey.is_p2wsh() {
+                    sighash_cache.p2wsh_signature_hash(
+                        input_index,
+                        script,
+                        prev_out.value,
+                        signature.sighash_type,
+                    )
+                } else {
+                    sighash_cache.p2wpkh_signature_hash(
+                        input_index,
+                        &prev_out.script_pubkey,
+                        prev_out.value,
+                        signature.sighash_type,
+                    )
+                }
+                .map_err(|_| Error::Psbt(PsbtError::InvalidSignature(input_index)))?;
+                SECP256K1
+                    .verify_ecdsa(&Message::from(sighash), &signature.signature, &key.inner)
+                    .map_err(|_| Error::Psbt(PsbtError::InvalidSignature(input_index)))?;
+
+                witness.push_ecdsa_signature(signature);
+                if prev_out.script_pubkey.is_p2wpkh() {
+                    witness.push(key.to_bytes());
+                }
+            }
+            if prev_out.script_pubkey.is_p2wsh() {
+                witness.push(script.to_bytes());
+            }
+            witness
+        } else {
+            return Err(Error::Psbt(PsbtError::UnsupportedInput(input_index)));
+        };
+
+        finalized_tx.input[input_index].witness = witness;
+    }
+
+    Ok(finalized_tx)
+}
+
+fn prevout_and_script(
+    tx: &dyn PreSignedTransaction,
+    input_index: usize,
+) -> Result<(&TxOut, &ScriptBuf), Error> {
+    match (
+        tx.prev_outs().get(input_index),
+        tx.prev_scripts().get(input_index),
+    ) {
+        (Some(prev_out), Some(script)) => Ok((prev_out, script)),
+        _ => Err(Error::Psbt(PsbtError::UnsupportedInput(input_index))),
+    }
+}
+
+// Public keys in the order they are checked by `script`, i.e. the pushes directly followed by
+// a signature check.
+pub(crate) fn checksig_keys(script: &Script) -> Vec<Vec<u8>> {
+    let instructions: Vec<Instruction> = script
+        .instructions()
+        .filter_map(|instruction| instruction.ok())
+        .collect();
+
+    instructions
+        .windows(2)
+        .filter_map(|pair| match pair {
+            [Instruction::PushBytes(key), Instruction::Op(op)]
+                if *op == OP_CHECKSIG || *op == OP_CHECKSIGVERIFY || *op == OP_CHECKSIGADD =>
+            {
+                Some(key.as_bytes().to_vec())
+            }
+            _ => None,
+        })
+        .collect()
+}
@@ -2,13 +2,14 @@
 use bitcoin::{
     sighash::{Prevouts, SighashCache},
     taproot::LeafVersion,
-    Script, TapLeafHash, TapSighashType, Transaction, TxOut,
+    PublicKey, Script, TapLeafHash, TapSighashType, Transaction, TxOut,
 };
 use musig2::{
     aggregate_partial_signatures,
     errors::{SigningError, VerifyError},
     secp::{MaybeScalar, Point},
-    sign_partial, AggNonce, KeyAggContext, LiftedSignature, PartialSignature, PubNonce, SecNonce,
+    sign_partial, verify_partial, AggNonce, KeyAggContext, LiftedSignature, PartialSignature,
+    PubNonce, SecNonce,
 };
 
 use super::super::contexts::{base::BaseContext, verifier::VerifierContext};
@@ -90,6 +91,45 @@ pub fn generate_taproot_aggregated_signature(
     )
 }
 
+pub fn verify_taproot_partial_signature(
+    n_of_n_public_keys: &[PublicKey],
+    tx: &Transaction,
+    aggregated_nonce: &AggNonce,
+    input_index: usize,
+    prevouts: &[TxOut],
+    script: &Script,
+    sighash_type: TapSighashType,
+    public_key: &PublicKey,
+    public_nonce: &PubNonce,
+    partial_signature: PartialSignature,
+) -> Result<(), VerifyError> {
+    let pubkeys: Vec<Point> = Vec::from_iter(
+        n_of_n_public_keys
+            .iter()
+            .map(|&public_key| public_key.inner.into()),
+    );
+    let key_agg_ctx = KeyAggContext::new(pubkeys).unwrap();
+
+    let leaf_hash = TapLeafHash::from_script(script, LeafVersion::TapScript);
+    let sighash = SighashCache::new(tx)
+        .taproot_script_spend_signature_hash(
+            input_index,
+            &Prevouts::All(prevouts),
+            leaf_hash,
+            sighash_type,
+        )
+        .expect("Failed to construct sighash");
+
+    verify_partial(
+        &key_agg_ctx,
+        partial_signature,
+        aggregated_nonce,
+        public_key.inner,
+        public_nonce,
+        sighash,
+    )
+}
+
 // TODO: This is currently unused and can be removed. If the conversion at the start of the above functions is incorrect, try this approach.
 // pub fn to_point(public_key: PublicKey) -> Point {
 //     Point::from_slice(&public_key.to_bytes()).unwrap() // TODO: Add error handling. Also, verify this method is correct (otherwise see conversion via secp256k1::PublicKey).
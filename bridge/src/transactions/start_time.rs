@@ -9,7 +9,7 @@ use std::collections::HashMap;
 use crate::connectors::{base::TaprootConnector, connector_2::Connector2};
 
 use super::{
-    super::{contexts::operator::OperatorContext, scripts::*},
+    super::{contexts::operator::OperatorContext, error::Error, scripts::*},
     base::*,
     pre_signed::*,
     pre_signed_musig2::*,
@@ -170,9 +170,13 @@ impl StartTimeTransaction {
         );
     }
 
-    pub fn merge(&mut self, burn: &StartTimeTransaction) {
+    pub fn merge(
+        &mut self,
+        burn: &StartTimeTransaction,
+        n_of_n_public_keys: &[PublicKey],
+    ) -> Result<(), Error> {
         merge_transactions(&mut self.tx, &burn.tx);
-        merge_musig2_nonces_and_signatures(self, burn);
+        merge_musig2_nonces_and_signatures(self, burn, n_of_n_public_keys)
     }
 }
 
@@ -9,6 +9,7 @@ use super::{
     super::{
         connectors::{base::*, connector_1::Connector1, connector_2::Connector2},
         contexts::{base::BaseContext, operator::OperatorContext, verifier::VerifierContext},
+        error::Error,
         scripts::*,
     },
     base::*,
@@ -62,6 +63,12 @@ impl PreSignedMusig2Transaction for StartTimeTimeoutTransaction {
         &mut self.musig2_signatures
     }
     fn verifier_inputs(&self) -> Vec<usize> { vec![0, 1] }
+    fn verifier_sighash_type(&self, input_index: usize) -> TapSighashType {
+        match input_index {
+            0 => TapSighashType::Single,
+            _ => TapSighashType::None,
+        }
+    }
 }
 
 impl StartTimeTimeoutTransaction {
@@ -136,31 +143,29 @@ impl StartTimeTimeoutTransaction {
         context: &VerifierContext,
         connector_2: &Connector2,
         secret_nonce: &SecNonce,
-    ) {
+    ) -> Result<(), Error> {
         let input_index = 0;
-        pre_sign_musig2_taproot_input(
-            self,
-            context,
-            input_index,
-            TapSighashType::Single,
-            secret_nonce,
-        );
+        pre_sign_musig2_taproot_input(self, context, input_index, secret_nonce)?;
 
-        // TODO: Consider verifying the final signature against the n-of-n public key and the tx.
         if self.musig2_signatures[&input_index].len() == context.n_of_n_public_keys.len() {
-            self.finalize_input_0(context, connector_2);
+            self.finalize_input_0(context, connector_2)?;
         }
+
+        Ok(())
     }
 
-    fn finalize_input_0(&mut self, context: &dyn BaseContext, connector_2: &Connector2) {
+    fn finalize_input_0(
+        &mut self,
+        context: &dyn BaseContext,
+        connector_2: &Connector2,
+    ) -> Result<(), Error> {
         let input_index = 0;
         finalize_musig2_taproot_input(
             self,
             context,
             input_index,
-            TapSighashType::Single,
             connector_2.generate_taproot_spend_info(),
-        );
+        )
     }
 
     fn sign_input_1(
@@ -168,31 +173,29 @@ impl StartTimeTimeoutTransaction {
         context: &VerifierContext,
         connector_1: &Connector1,
         secret_nonce: &SecNonce,
-    ) {
+    ) -> Result<(), Error> {
         let input_index = 1;
-        pre_sign_musig2_taproot_input(
-            self,
-            context,
-            input_index,
-            TapSighashType::None,
-            secret_nonce,
-        );
+        pre_sign_musig2_taproot_input(self, context, input_index, secret_nonce)?;
 
-        // TODO: Consider verifying the final signature against the n-of-n public key and the tx.
         if self.musig2_signatures[&input_index].len() == context.n_of_n_public_keys.len() {
-            self.finalize_input_1(context, connector_1);
+            self.finalize_input_1(context, connector_1)?;
         }
+
+        Ok(())
     }
 
-    fn finalize_input_1(&mut self, context: &dyn BaseContext, connector_1: &Connector1) {
+    fn finalize_input_1(
+        &mut self,
+        context: &dyn BaseContext,
+        connector_1: &Connector1,
+    ) -> Result<(), Error> {
         let input_index = 1;
         finalize_musig2_taproot_input(
             self,
             context,
             input_index,
-            TapSighashType::None,
             connector_1.generate_taproot_spend_info(),
-        );
+        )
     }
 
     pub fn pre_sign(
@@ -201,12 +204,14 @@ impl StartTimeTimeoutTransaction {
         connector_1: &Connector1,
         connector_2: &Connector2,
         secret_nonces: &HashMap<usize, SecNonce>,
-    ) {
+    ) -> Result<(), Error> {
         let input_index = 0;
-        self.sign_input_0(context, connector_2, &secret_nonces[&input_index]);
+        self.sign_input_0(context, connector_2, &secret_nonces[&input_index])?;
 
         let input_index = 1;
-        self.sign_input_1(context, connector_1, &secret_nonces[&input_index]);
+        self.sign_input_1(context, connector_1, &secret_nonces[&input_index])?;
+
+        Ok(())
     }
 
     pub fn add_output(&mut self, output_script_pubkey: ScriptBuf) {
@@ -214,9 +219,13 @@ impl StartTimeTimeoutTransaction {
         self.tx.output[output_index].script_pubkey = output_script_pubkey;
     }
 
-    pub fn merge(&mut self, burn: &StartTimeTimeoutTransaction) {
+    pub fn merge(
+        &mut self,
+        burn: &StartTimeTimeoutTransaction,
+        n_of_n_public_keys: &[PublicKey],
+    ) -> Result<(), Error> {
         merge_transactions(&mut self.tx, &burn.tx);
-        merge_musig2_nonces_and_signatures(self, burn);
+        merge_musig2_nonces_and_signatures(self, burn, n_of_n_public_keys)
     }
 }
 
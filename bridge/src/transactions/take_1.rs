@@ -13,6 +13,7 @@ use super::{
             connector_b::ConnectorB,
         },
         contexts::{base::BaseContext, operator::OperatorContext, verifier::VerifierContext},
+        error::Error,
         scripts::*,
     },
     base::*,
@@ -174,31 +175,29 @@ impl Take1Transaction {
         context: &VerifierContext,
         connector_0: &Connector0,
         secret_nonce: &SecNonce,
-    ) {
+    ) -> Result<(), Error> {
         let input_index = 0;
-        pre_sign_musig2_taproot_input(
-            self,
-            context,
-            input_index,
-            TapSighashType::All,
-            secret_nonce,
-        );
+        pre_sign_musig2_taproot_input(self, context, input_index, secret_nonce)?;
 
-        // TODO: Consider verifying the final signature against the n-of-n public key and the tx.
         if self.musig2_signatures[&input_index].len() == context.n_of_n_public_keys.len() {
-            self.finalize_input_0(context, connector_0);
+            self.finalize_input_0(context, connector_0)?;
         }
+
+        Ok(())
     }
 
-    fn finalize_input_0(&mut self, context: &dyn BaseContext, connector_0: &Connector0) {
+    fn finalize_input_0(
+        &mut self,
+        context: &dyn BaseContext,
+        connector_0: &Connector0,
+    ) -> Result<(), Error> {
         let input_index = 0;
         finalize_musig2_taproot_input(
             self,
             context,
             input_index,
-            TapSighashType::All,
             connector_0.generate_taproot_spend_info(),
-        );
+        )
     }
 
     fn sign_input_1(&mut self, context: &OperatorContext, connector_a: &ConnectorA) {
@@ -227,31 +226,29 @@ impl Take1Transaction {
         context: &VerifierContext,
         connector_b: &ConnectorB,
         secret_nonce: &SecNonce,
-    ) {
+    ) -> Result<(), Error> {
         let input_index = 3;
-        pre_sign_musig2_taproot_input(
-            self,
-            context,
-            input_index,
-            TapSighashType::All,
-            secret_nonce,
-        );
+        pre_sign_musig2_taproot_input(self, context, input_index, secret_nonce)?;
 
-        // TODO: Consider verifying the final signature against the n-of-n public key and the tx.
         if self.musig2_signatures[&input_index].len() == context.n_of_n_public_keys.len() {
-            self.finalize_input_3(context, connector_b);
+            self.finalize_input_3(context, connector_b)?;
         }
+
+        Ok(())
     }
 
-    fn finalize_input_3(&mut self, context: &dyn BaseContext, connector_b: &ConnectorB) {
+    fn finalize_input_3(
+        &mut self,
+        context: &dyn BaseContext,
+        connector_b: &ConnectorB,
+    ) -> Result<(), Error> {
         let input_index = 3;
         finalize_musig2_taproot_input(
             self,
             context,
             input_index,
-            TapSighashType::All,
             connector_b.generate_taproot_spend_info(),
-        );
+        )
     }
 
     pub fn pre_sign(
@@ -260,17 +257,23 @@ impl Take1Transaction {
         connector_0: &Connector0,
         connector_b: &ConnectorB,
         secret_nonces: &HashMap<usize, SecNonce>,
-    ) {
+    ) -> Result<(), Error> {
         let input_index = 0;
-        self.sign_input_0(context, connector_0, &secret_nonces[&input_index]);
+        self.sign_input_0(context, connector_0, &secret_nonces[&input_index])?;
 
         let input_index = 3;
-        self.sign_input_3(context, connector_b, &secret_nonces[&input_index]);
+        self.sign_input_3(context, connector_b, &secret_nonces[&input_index])?;
+
+        Ok(())
     }
 
-    pub fn merge(&mut self, take_1: &Take1Transaction) {
+    pub fn merge(
+        &mut self,
+        take_1: &Take1Transaction,
+        n_of_n_public_keys: &[PublicKey],
+    ) -> Result<(), Error> {
         merge_transactions(&mut self.tx, &take_1.tx);
-        merge_musig2_nonces_and_signatures(self, take_1);
+        merge_musig2_nonces_and_signatures(self, take_1, n_of_n_public_keys)
     }
 }
 
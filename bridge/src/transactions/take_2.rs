@@ -13,6 +13,7 @@ use super::{
             connector_c::ConnectorC,
         },
         contexts::{base::BaseContext, operator::OperatorContext, verifier::VerifierContext},
+        error::Error,
         scripts::*,
     },
     base::*,
@@ -173,31 +174,29 @@ impl Take2Transaction {
         context: &VerifierContext,
         connector_0: &Connector0,
         secret_nonce: &SecNonce,
-    ) {
+    ) -> Result<(), Error> {
         let input_index = 0;
-        pre_sign_musig2_taproot_input(
-            self,
-            context,
-            input_index,
-            TapSighashType::All,
-            secret_nonce,
-        );
+        pre_sign_musig2_taproot_input(self, context, input_index, secret_nonce)?;
 
-        // TODO: Consider verifying the final signature against the n-of-n public key and the tx.
         if self.musig2_signatures[&input_index].len() == context.n_of_n_public_keys.len() {
-            self.finalize_input_0(context, connector_0);
+            self.finalize_input_0(context, connector_0)?;
         }
+
+        Ok(())
     }
 
-    fn finalize_input_0(&mut self, context: &dyn BaseContext, connector_0: &Connector0) {
+    fn finalize_input_0(
+        &mut self,
+        context: &dyn BaseContext,
+        connector_0: &Connector0,
+    ) -> Result<(), Error> {
         let input_index = 0;
         finalize_musig2_taproot_input(
             self,
             context,
             input_index,
-            TapSighashType::All,
             connector_0.generate_taproot_spend_info(),
-        );
+        )
     }
 
     fn sign_input_1(&mut self, context: &OperatorContext) {
@@ -215,31 +214,29 @@ impl Take2Transaction {
         context: &VerifierContext,
         connector_5: &Connector5,
         secret_nonce: &SecNonce,
-    ) {
+    ) -> Result<(), Error> {
         let input_index = 2;
-        pre_sign_musig2_taproot_input(
-            self,
-            context,
-            input_index,
-            TapSighashType::All,
-            secret_nonce,
-        );
+        pre_sign_musig2_taproot_input(self, context, input_index, secret_nonce)?;
 
-        // TODO: Consider verifying the final signature against the n-of-n public key and the tx.
         if self.musig2_signatures[&input_index].len() == context.n_of_n_public_keys.len() {
-            self.finalize_input_2(context, connector_5);
+            self.finalize_input_2(context, connector_5)?;
         }
+
+        Ok(())
     }
 
-    fn finalize_input_2(&mut self, context: &dyn BaseContext, connector_5: &Connector5) {
+    fn finalize_input_2(
+        &mut self,
+        context: &dyn BaseContext,
+        connector_5: &Connector5,
+    ) -> Result<(), Error> {
         let input_index = 2;
         finalize_musig2_taproot_input(
             self,
             context,
             input_index,
-            TapSighashType::All,
             connector_5.generate_taproot_spend_info(),
-        );
+        )
     }
 
     fn sign_input_3(&mut self, context: &OperatorContext, connector_c: &ConnectorC) {
@@ -263,21 +260,27 @@ impl Take2Transaction {
         connector_0: &Connector0,
         connector_5: &Connector5,
         secret_nonces: &HashMap<usize, SecNonce>,
-    ) {
+    ) -> Result<(), Error> {
         let input_index = 0;
-        self.sign_input_0(context, connector_0, &secret_nonces[&input_index]);
+        self.sign_input_0(context, connector_0, &secret_nonces[&input_index])?;
 
         let input_index = 2;
-        self.sign_input_2(context, connector_5, &secret_nonces[&input_index]);
+        self.sign_input_2(context, connector_5, &secret_nonces[&input_index])?;
+
+        Ok(())
     }
 
     pub fn sign(&mut self, context: &OperatorContext, connector_c: &ConnectorC) {
         self.sign_input_3(context, connector_c);
     }
 
-    pub fn merge(&mut self, take_2: &Take2Transaction) {
+    pub fn merge(
+        &mut self,
+        take_2: &Take2Transaction,
+        n_of_n_public_keys: &[PublicKey],
+    ) -> Result<(), Error> {
         merge_transactions(&mut self.tx, &take_2.tx);
-        merge_musig2_nonces_and_signatures(self, take_2);
+        merge_musig2_nonces_and_signatures(self, take_2, n_of_n_public_keys)
     }
 }
 
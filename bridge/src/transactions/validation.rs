@@ -0,0 +1,207 @@
+use std::collections::HashMap;
+
+use bitcoin::{Amount, OutPoint, Transaction, TxOut, Weight, Witness};
+use bitvm::dry_run_taproot_input;
+
+use crate::{
+    error::ValidationError,
+    graphs::base::{BaseGraph, MIN_RELAY_FEE_RATE},
+};
+
+// Fee rate above which a fee is considered a mistake, same as the default `maxfeerate` of
+// Bitcoin Core's `sendrawtransaction` (0.1 BTC/kvB).
+pub const MAX_FEE_RATE: u64 = 10_000;
+// The assert and disprove transactions are larger than `MAX_STANDARD_TX_WEIGHT`, so only the
+// consensus limit applies.
+pub const MAX_TRANSACTION_WEIGHT: Weight = Weight::MAX_BLOCK;
+// Size of the stack a tapscript can start executing with.
+pub const MAX_TAPSCRIPT_STACK_ELEMENTS: usize = 1000;
+pub const MAX_STANDARD_P2WSH_SCRIPT_SIZE: usize = 3600;
+pub const MAX_STANDARD_P2WSH_STACK_ELEMENTS: usize = 100;
+// Standardness limit of the stack elements of P2WSH spends only, Bitcoin Core doesn't apply it to
+// tapscript spends.
+pub const MAX_STANDARD_WITNESS_ELEMENT_SIZE: usize = 80;
+// Consensus limit of the stack elements of both P2WSH and tapscript spends.
+pub const MAX_SCRIPT_ELEMENT_SIZE: usize = 520;
+
+/// Checks a finalized transaction before it is broadcast: the witness of every input, the
+/// weight and the fee. The taproot script path inputs are executed with `dry_run_taproot_input`
+/// against `prev_outs`, the outputs spent by the inputs of `tx` in the same order.
+pub fn validate_transaction(tx: &Transaction, prev_outs: &[TxOut]) -> Result<(), ValidationError> {
+    if prev_outs.len() < tx.input.len() {
+        return Err(ValidationError::UnknownPrevout {
+            input_index: prev_outs.len(),
+            outpoint: tx.input[prev_outs.len()].previous_output,
+        });
+    }
+
+    for (input_index, (input, prev_out)) in tx.input.iter().zip(prev_outs).enumerate() {
+        if input.witness.is_empty() {
+            return Err(ValidationError::MissingWitness { input_index });
+        }
+        if prev_out.script_pubkey.is_p2tr() {
+            validate_tapscript_witness(&input.witness, input_index)?;
+        } else if prev_out.script_pubkey.is_p2wsh() {
+            validate_p2wsh_witness(&input.witness, input_index)?;
+        }
+    }
+
+    let weight = tx.weight();
+    if weight > MAX_TRANSACTION_WEIGHT {
+        return Err(ValidationError::WeightTooHigh {
+            weight: weight.to_wu(),
+            max_weight: MAX_TRANSACTION_WEIGHT.to_wu(),
+        });
+    }
+
+    validate_fee(tx, prev_outs)?;
+
+    for (input_index, prev_out) in prev_outs.iter().enumerate() {
+        if !prev_out.script_pubkey.is_p2tr() || tx.input[input_index].witness.tapscript().is_none()
+        {
+            continue;
+        }
+        let execute_info = dry_run_taproot_input(tx, input_index, prev_outs);
+        if !execute_info.success {
+            return Err(ValidationError::Script {
+                input_index,
+                opcode: execute_info
+                    .last_opcode
+                    .map(|opcode| format!("{:?}", opcode)),
+                reason: format!("{:?}", execute_info.error),
+            });
+        }
+    }
+
+    Ok(())
+}
+
+/// Outputs known to `graph`: the ones its transactions spend and the ones they create.
+pub fn graph_prev_outs(graph: &dyn BaseGraph) -> HashMap<OutPoint, TxOut> {
+    let mut prev_outs = HashMap::new();
+    for (_, tx) in graph.pre_signed_transactions() {
+        for (input, prev_out) in tx.tx().input.iter().zip(tx.prev_outs()) {
+            prev_outs.insert(input.previous_output, prev_out.clone());
+        }
+        let txid = tx.tx().compute_txid();
+        for (vout, output) in tx.tx().output.iter().enumerate() {
+            prev_outs.insert(
+                OutPoint {
+                    txid,
+                    vout: vout as u32,
+                },
+                output.clone(),
+            );
+        }
+    }
+
+    prev_outs
+}
+
+fn validate_tapscript_witness(
+    witness: &Witness,
+    input_index: usize,
+) -> Result<(), ValidationError> {
+    let Some(script) = witness.tapscript() else {
+        // Key path spend.
+        return Ok(());
+    };
+    // Tapscripts are only bounded by the block weight.
+    let max_size = MAX_TRANSACTION_WEIGHT.to_wu() as usize;
+    if script.len() > max_size {
+        return Err(ValidationError::ScriptTooLarge {
+            input_index,
+            size: script.len(),
+            max_size,
+        });
+    }
+
+    // Everything before the script, the control block and the optional annex.
+    let stack_len = witness.len()
+        - if witness.taproot_annex().is_some() {
+            3
+        } else {
+            2
+        };
+    validate_stack(
+        witness,
+        stack_len,
+        input_index,
+        MAX_TAPSCRIPT_STACK_ELEMENTS,
+        MAX_SCRIPT_ELEMENT_SIZE,
+    )
+}
+
+fn validate_p2wsh_witness(witness: &Witness, input_index: usize) -> Result<(), ValidationError> {
+    let script_size = witness.last().map_or(0, |script| script.len());
+    if script_size > MAX_STANDARD_P2WSH_SCRIPT_SIZE {
+        return Err(ValidationError::ScriptTooLarge {
+            input_index,
+            size: script_size,
+            max_size: MAX_STANDARD_P2WSH_SCRIPT_SIZE,
+        });
+    }
+
+    validate_stack(
+        witness,
+        witness.len() - 1,
+        input_index,
+        MAX_STANDARD_P2WSH_STACK_ELEMENTS,
+        MAX_STANDARD_WITNESS_ELEMENT_SIZE,
+    )
+}
+
+// Checks the first `stack_len` elements of `witness`, the initial stack of the script.
+fn validate_stack(
+    witness: &Witness,
+    stack_len: usize,
+    input_index: usize,
+    max_count: usize,
+    max_element_size: usize,
+) -> Result<(), ValidationError> {
+    if stack_len > max_count {
+        return Err(ValidationError::TooManyWitnessElements {
+            input_index,
+            count: stack_len,
+            max_count,
+        });
+    }
+    if let Some((element_index, element)) = witness
+        .iter()
+        .take(stack_len)
+        .enumerate()
+        .find(|(_, element)| element.len() > max_element_size)
+    {
+        return Err(ValidationError::WitnessElementTooLarge {
+            input_index,
+            element_index,
+            size: element.len(),
+            max_size: max_element_size,
+        });
+    }
+
+    Ok(())
+}
+
+fn validate_fee(tx: &Transaction, prev_outs: &[TxOut]) -> Result<(), ValidationError> {
+    let input_value: Amount = prev_outs.iter().map(|prev_out| prev_out.value).sum();
+    let output_value: Amount = tx.output.iter().map(|output| output.value).sum();
+    let Some(fee) = input_value.checked_sub(output_value) else {
+        return Err(ValidationError::NegativeFee {
+            input_value,
+            output_value,
+        });
+    };
+
+    let vsize = tx.vsize() as u64;
+    let min_fee = Amount::from_sat(vsize * MIN_RELAY_FEE_RATE);
+    if fee < min_fee {
+        return Err(ValidationError::FeeTooLow { fee, min_fee });
+    }
+    let max_fee = Amount::from_sat(vsize * MAX_FEE_RATE);
+    if fee > max_fee {
+        return Err(ValidationError::FeeTooHigh { fee, max_fee });
+    }
+
+    Ok(())
+}
@@ -41,21 +41,26 @@ async fn test_assert_tx_success() {
         &config.connector_b,
         &config.connector_c,
         Input { outpoint, amount },
+        &config.protocol_parameters,
     );
 
     let secret_nonces_0 = assert_tx.push_nonces(&config.verifier_0_context);
     let secret_nonces_1 = assert_tx.push_nonces(&config.verifier_1_context);
 
-    assert_tx.pre_sign(
-        &config.verifier_0_context,
-        &config.connector_b,
-        &secret_nonces_0,
-    );
-    assert_tx.pre_sign(
-        &config.verifier_1_context,
-        &config.connector_b,
-        &secret_nonces_1,
-    );
+    assert_tx
+        .pre_sign(
+            &config.verifier_0_context,
+            &config.connector_b,
+            &secret_nonces_0,
+        )
+        .unwrap();
+    assert_tx
+        .pre_sign(
+            &config.verifier_1_context,
+            &config.connector_b,
+            &secret_nonces_1,
+        )
+        .unwrap();
 
     println!(
         "tx output before finalize: {:?}",
@@ -79,21 +79,26 @@ async fn test_assert_final_tx_success() {
             outpoint: funding_outpoint2,
             amount: input_value2,
         },
+        &config.protocol_parameters,
     );
 
     let secret_nonces_0 = assert_final_tx.push_nonces(&config.verifier_0_context);
     let secret_nonces_1 = assert_final_tx.push_nonces(&config.verifier_1_context);
 
-    assert_final_tx.pre_sign(
-        &config.verifier_0_context,
-        &config.connector_d,
-        &secret_nonces_0,
-    );
-    assert_final_tx.pre_sign(
-        &config.verifier_1_context,
-        &config.connector_d,
-        &secret_nonces_1,
-    );
+    assert_final_tx
+        .pre_sign(
+            &config.verifier_0_context,
+            &config.connector_d,
+            &secret_nonces_0,
+        )
+        .unwrap();
+    assert_final_tx
+        .pre_sign(
+            &config.verifier_1_context,
+            &config.connector_d,
+            &secret_nonces_1,
+        )
+        .unwrap();
 
     let tx = assert_final_tx.finalize();
     check_tx_output_sum(
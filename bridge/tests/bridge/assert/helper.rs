@@ -2,6 +2,7 @@ use bitcoin::{Amount, Network, Transaction, Txid};
 use bridge::{
     connectors::{base::TaprootConnector, connector_b::ConnectorB, connector_d::ConnectorD},
     contexts::verifier::VerifierContext,
+    parameters::ProtocolParameters,
     transactions::{
         assert_transactions::{
             assert_initial::AssertInitialTransaction,
@@ -50,6 +51,7 @@ pub async fn fund_create_and_mine_assert_initial_tx(
             outpoint,
             amount: input_amount,
         },
+        &config.protocol_parameters,
     )
     .await;
 
@@ -66,6 +68,7 @@ pub async fn create_and_mine_assert_initial_tx(
     assert_commit_connectors_e_1: &AssertCommit1ConnectorsE,
     assert_commit_connectors_e_2: &AssertCommit2ConnectorsE,
     input: Input,
+    protocol_parameters: &ProtocolParameters,
 ) -> (Transaction, Txid) {
     let mut assert_initial_tx = AssertInitialTransaction::new(
         connector_b,
@@ -73,13 +76,18 @@ pub async fn create_and_mine_assert_initial_tx(
         assert_commit_connectors_e_1,
         assert_commit_connectors_e_2,
         input,
+        protocol_parameters,
     );
 
     let secret_nonces_0 = assert_initial_tx.push_nonces(verifier_0_context);
     let secret_nonces_1 = assert_initial_tx.push_nonces(verifier_1_context);
 
-    assert_initial_tx.pre_sign(verifier_0_context, connector_b, &secret_nonces_0);
-    assert_initial_tx.pre_sign(verifier_1_context, connector_b, &secret_nonces_1);
+    assert_initial_tx
+        .pre_sign(verifier_0_context, connector_b, &secret_nonces_0)
+        .unwrap();
+    assert_initial_tx
+        .pre_sign(verifier_1_context, connector_b, &secret_nonces_1)
+        .unwrap();
 
     let tx = assert_initial_tx.finalize();
     let tx_id = tx.compute_txid();
@@ -2,18 +2,21 @@ use std::str::FromStr;
 
 use bitcoin::{Amount, OutPoint, ScriptBuf, Sequence, TxIn, TxOut, Txid};
 use bridge::{
+    connectors::base::TaprootConnector,
+    error::{Error, Musig2Blame, Musig2Error},
     scripts::{generate_pay_to_pubkey_script, generate_pay_to_pubkey_script_address},
     transactions::{
-        base::{Input, InputWithScript, MIN_RELAY_FEE_CHALLENGE},
+        base::{Input, InputWithScript, MIN_RELAY_FEE_CHALLENGE, MIN_RELAY_FEE_PEG_IN_CONFIRM},
         challenge::ChallengeTransaction,
+        peg_in_confirm::PegInConfirmTransaction,
         pre_signed::PreSignedTransaction,
+        pre_signed_musig2::{finalize_musig2_taproot_input, PreSignedMusig2Transaction},
     },
 };
 
-use crate::bridge::setup::{setup_test, INITIAL_AMOUNT};
+use crate::bridge::setup::{setup_test, SetupConfig, INITIAL_AMOUNT};
 
 #[tokio::test]
-// TODO: test merging signatures after Musig2 feature is ready
 async fn test_merge_add_new_input_and_output() {
     // Arrange
     let config = setup_test().await;
@@ -40,15 +43,18 @@ async fn test_merge_add_new_input_and_output() {
     .script_pubkey();
     let input_script =
         generate_pay_to_pubkey_script(&config.depositor_context.depositor_public_key);
-    source_challenge_tx.add_inputs_and_output(
-        &vec![InputWithScript {
-            outpoint,
-            amount: amount * 2,
-            script: &input_script,
-        }],
-        &config.depositor_context.depositor_keypair,
-        refund_script.clone(),
-    );
+    source_challenge_tx
+        .add_inputs_and_output(
+            &vec![InputWithScript {
+                outpoint,
+                amount: amount * 2,
+                script: &input_script,
+            }],
+            &config.depositor_context.depositor_keypair,
+            refund_script.clone(),
+            Amount::from_sat(config.protocol_parameters.dust_amount),
+        )
+        .unwrap();
 
     let input_length_before = destination_challenge_tx.tx().input.len();
     let output_length_before = destination_challenge_tx.tx().output.len();
@@ -76,3 +82,164 @@ async fn test_merge_add_new_input_and_output() {
     assert!(destination_challenge_tx.tx().input[1].eq(&added_input));
     assert!(destination_challenge_tx.tx().output[1].eq(&added_output));
 }
+
+#[tokio::test]
+async fn test_merge_refuses_invalid_partial_signature() {
+    // Arrange
+    let config = setup_test().await;
+    let verifier_0 = config.verifier_0_context.verifier_public_key;
+    let verifier_1 = config.verifier_1_context.verifier_public_key;
+    let mut destination_peg_in_confirm_tx = create_peg_in_confirm_tx(&config);
+    let secret_nonces_0 = destination_peg_in_confirm_tx.push_nonces(&config.verifier_0_context);
+    destination_peg_in_confirm_tx.push_nonces(&config.verifier_1_context);
+
+    let mut source_peg_in_confirm_tx = destination_peg_in_confirm_tx.clone();
+    source_peg_in_confirm_tx
+        .pre_sign(
+            &config.verifier_0_context,
+            &config.connector_z,
+            &secret_nonces_0,
+        )
+        .unwrap();
+    // Verifier 0's partial signature passed off as verifier 1's.
+    let partial_signature = source_peg_in_confirm_tx.musig2_signatures()[&0][&verifier_0];
+    source_peg_in_confirm_tx
+        .musig2_signatures_mut()
+        .get_mut(&0)
+        .unwrap()
+        .insert(verifier_1, partial_signature);
+
+    // Act
+    let result = destination_peg_in_confirm_tx.merge(
+        &source_peg_in_confirm_tx,
+        &config.verifier_0_context.n_of_n_public_keys,
+    );
+
+    // Assert
+    assert!(matches!(
+        result,
+        Err(Error::Musig2(Musig2Error::InvalidPartialSignatures(ref blames)))
+            if blames.iter().all(|blame| blame.verifier == verifier_1)
+    ));
+    let merged_signatures = &destination_peg_in_confirm_tx.musig2_signatures()[&0];
+    assert_eq!(merged_signatures.get(&verifier_0), Some(&partial_signature));
+    assert!(!merged_signatures.contains_key(&verifier_1));
+}
+
+#[tokio::test]
+async fn test_finalize_blames_invalid_partial_signature() {
+    // Arrange
+    let config = setup_test().await;
+    let verifier_0 = config.verifier_0_context.verifier_public_key;
+    let verifier_1 = config.verifier_1_context.verifier_public_key;
+    let mut peg_in_confirm_tx = create_peg_in_confirm_tx(&config);
+    let secret_nonces_0 = peg_in_confirm_tx.push_nonces(&config.verifier_0_context);
+    peg_in_confirm_tx.push_nonces(&config.verifier_1_context);
+    peg_in_confirm_tx
+        .pre_sign(
+            &config.verifier_0_context,
+            &config.connector_z,
+            &secret_nonces_0,
+        )
+        .unwrap();
+
+    let missing_signature = finalize_musig2_taproot_input(
+        &mut peg_in_confirm_tx,
+        &config.verifier_0_context,
+        0,
+        config.connector_z.generate_taproot_spend_info(),
+    );
+    assert!(matches!(
+        missing_signature,
+        Err(Error::Musig2(Musig2Error::MissingPartialSignatures { ref verifiers, .. }))
+            if verifiers == &vec![verifier_1]
+    ));
+
+    let partial_signature = peg_in_confirm_tx.musig2_signatures()[&0][&verifier_0];
+    peg_in_confirm_tx
+        .musig2_signatures_mut()
+        .get_mut(&0)
+        .unwrap()
+        .insert(verifier_1, partial_signature);
+    let witness_length_before = peg_in_confirm_tx.tx().input[0].witness.len();
+
+    // Act
+    let result = finalize_musig2_taproot_input(
+        &mut peg_in_confirm_tx,
+        &config.verifier_0_context,
+        0,
+        config.connector_z.generate_taproot_spend_info(),
+    );
+
+    // Assert
+    let expected_blame = Musig2Blame {
+        txid: peg_in_confirm_tx.tx().compute_txid(),
+        input_index: 0,
+        verifier: verifier_1,
+    };
+    assert!(matches!(
+        result,
+        Err(Error::Musig2(Musig2Error::InvalidPartialSignatures(ref blames)))
+            if blames == &vec![expected_blame]
+    ));
+    assert_eq!(
+        peg_in_confirm_tx.tx().input[0].witness.len(),
+        witness_length_before
+    );
+}
+
+#[tokio::test]
+async fn test_pre_sign_returns_finalize_error() {
+    // Arrange
+    let config = setup_test().await;
+    let verifier_0 = config.verifier_0_context.verifier_public_key;
+    let verifier_1 = config.verifier_1_context.verifier_public_key;
+    let mut peg_in_confirm_tx = create_peg_in_confirm_tx(&config);
+    peg_in_confirm_tx.push_nonces(&config.verifier_0_context);
+    let secret_nonces_1 = peg_in_confirm_tx.push_nonces(&config.verifier_1_context);
+    let mut signed_tx = peg_in_confirm_tx.clone();
+    signed_tx
+        .pre_sign(
+            &config.verifier_1_context,
+            &config.connector_z,
+            &secret_nonces_1,
+        )
+        .unwrap();
+    // Verifier 1's partial signature passed off as verifier 0's.
+    let partial_signature = signed_tx.musig2_signatures()[&0][&verifier_1];
+    peg_in_confirm_tx
+        .musig2_signatures_mut()
+        .entry(0)
+        .or_default()
+        .insert(verifier_0, partial_signature);
+
+    // Act
+    let result = peg_in_confirm_tx.pre_sign(
+        &config.verifier_1_context,
+        &config.connector_z,
+        &secret_nonces_1,
+    );
+
+    // Assert
+    assert!(matches!(
+        result,
+        Err(Error::Musig2(Musig2Error::InvalidPartialSignatures(ref blames)))
+            if blames.iter().all(|blame| blame.verifier == verifier_0)
+    ));
+}
+
+fn create_peg_in_confirm_tx(config: &SetupConfig) -> PegInConfirmTransaction {
+    let outpoint = OutPoint {
+        txid: Txid::from_str("0e6719ac074b0e3cac76d057643506faa1c266b322aa9cf4c6f635fe63b14327")
+            .unwrap(),
+        vout: 0,
+    };
+    let amount = Amount::from_sat(INITIAL_AMOUNT + MIN_RELAY_FEE_PEG_IN_CONFIRM);
+
+    PegInConfirmTransaction::new(
+        &config.depositor_context,
+        &config.connector_0,
+        &config.connector_z,
+        Input { outpoint, amount },
+    )
+}
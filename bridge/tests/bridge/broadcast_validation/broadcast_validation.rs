@@ -0,0 +1,137 @@
+use bitcoin::{Amount, Transaction, TxOut, Witness};
+use bridge::{
+    error::ValidationError,
+    graphs::peg_in::PegInGraph,
+    transactions::{
+        base::{BaseTransaction, MIN_RELAY_FEE_PEG_IN_CONFIRM, MIN_RELAY_FEE_PEG_IN_DEPOSIT},
+        pre_signed::PreSignedTransaction,
+        validation::{
+            graph_prev_outs, validate_transaction, MAX_FEE_RATE, MAX_SCRIPT_ELEMENT_SIZE,
+            MAX_STANDARD_WITNESS_ELEMENT_SIZE,
+        },
+    },
+};
+
+use crate::bridge::{
+    helper::{create_peg_in_graph, sign_graph},
+    setup::{setup_test, SetupConfig, INITIAL_AMOUNT},
+};
+
+#[tokio::test]
+async fn test_validate_peg_in_deposit_fee() {
+    let config = setup_test().await;
+    let peg_in_graph = create_signed_peg_in_graph(&config);
+    let deposit_tx = peg_in_graph.peg_in_deposit_transaction.finalize();
+    let prev_outs = graph_prev_outs(&peg_in_graph);
+    let funding_prev_out = prev_outs[&deposit_tx.input[0].previous_output].clone();
+    assert_eq!(
+        validate_transaction(&deposit_tx, &[funding_prev_out.clone()]),
+        Ok(())
+    );
+
+    let output_value = deposit_tx.output[0].value;
+    let with_value = |value: Amount| TxOut {
+        value,
+        script_pubkey: funding_prev_out.script_pubkey.clone(),
+    };
+    assert!(matches!(
+        validate_transaction(
+            &deposit_tx,
+            &[with_value(output_value - Amount::from_sat(1))]
+        ),
+        Err(ValidationError::NegativeFee { .. })
+    ));
+    assert!(matches!(
+        validate_transaction(&deposit_tx, &[with_value(output_value)]),
+        Err(ValidationError::FeeTooLow { .. })
+    ));
+    let max_fee = Amount::from_sat(deposit_tx.vsize() as u64 * MAX_FEE_RATE);
+    assert!(matches!(
+        validate_transaction(
+            &deposit_tx,
+            &[with_value(output_value + max_fee + Amount::from_sat(1))]
+        ),
+        Err(ValidationError::FeeTooHigh { .. })
+    ));
+}
+
+#[tokio::test]
+async fn test_validate_peg_in_confirm_witness() {
+    let config = setup_test().await;
+    let peg_in_graph = create_signed_peg_in_graph(&config);
+    let confirm_tx = peg_in_graph.peg_in_confirm_transaction.finalize();
+    let prev_outs = peg_in_graph.peg_in_confirm_transaction.prev_outs().clone();
+
+    assert_eq!(
+        validate_transaction(&confirm_tx, &[]),
+        Err(ValidationError::UnknownPrevout {
+            input_index: 0,
+            outpoint: confirm_tx.input[0].previous_output
+        })
+    );
+
+    let mut unsigned_tx = confirm_tx.clone();
+    unsigned_tx.input[0].witness.clear();
+    assert_eq!(
+        validate_transaction(&unsigned_tx, &prev_outs),
+        Err(ValidationError::MissingWitness { input_index: 0 })
+    );
+
+    let oversized_tx = with_witness(&confirm_tx, |witness| {
+        witness.insert(0, vec![0; MAX_SCRIPT_ELEMENT_SIZE + 1])
+    });
+    assert_eq!(
+        validate_transaction(&oversized_tx, &prev_outs),
+        Err(ValidationError::WitnessElementTooLarge {
+            input_index: 0,
+            element_index: 0,
+            size: MAX_SCRIPT_ELEMENT_SIZE + 1,
+            max_size: MAX_SCRIPT_ELEMENT_SIZE
+        })
+    );
+
+    // The standardness limit of P2WSH stack elements doesn't apply to tapscript spends.
+    let non_standard_p2wsh_element_tx = with_witness(&confirm_tx, |witness| {
+        witness.insert(0, vec![0; MAX_STANDARD_WITNESS_ELEMENT_SIZE + 1])
+    });
+    assert!(!matches!(
+        validate_transaction(&non_standard_p2wsh_element_tx, &prev_outs),
+        Err(ValidationError::WitnessElementTooLarge { .. })
+    ));
+
+    // Corrupted signature, the fee is raised so that only the script can fail.
+    let forged_tx = with_witness(&confirm_tx, |witness| witness[0][0] ^= 1);
+    let funded_prev_outs = vec![TxOut {
+        value: prev_outs[0].value + Amount::from_sat(forged_tx.vsize() as u64),
+        script_pubkey: prev_outs[0].script_pubkey.clone(),
+    }];
+    assert!(matches!(
+        validate_transaction(&forged_tx, &funded_prev_outs),
+        Err(ValidationError::Script {
+            input_index: 0,
+            opcode: Some(_),
+            ..
+        })
+    ));
+}
+
+fn with_witness(tx: &Transaction, update: impl FnOnce(&mut Vec<Vec<u8>>)) -> Transaction {
+    let mut tx = tx.clone();
+    let mut witness = tx.input[0].witness.to_vec();
+    update(&mut witness);
+    tx.input[0].witness = Witness::from_slice(&witness);
+
+    tx
+}
+
+fn create_signed_peg_in_graph(config: &SetupConfig) -> PegInGraph {
+    let mut peg_in_graph = create_peg_in_graph(
+        config,
+        Amount::from_sat(
+            INITIAL_AMOUNT + MIN_RELAY_FEE_PEG_IN_DEPOSIT + MIN_RELAY_FEE_PEG_IN_CONFIRM,
+        ),
+    );
+    sign_graph(config, &mut peg_in_graph);
+
+    peg_in_graph
+}
@@ -67,22 +67,25 @@ async fn test_challenge_tx() {
         input_amount_crowdfunding_total,
     );
 
-    challenge_tx.add_inputs_and_output(
-        &vec![
-            InputWithScript {
-                outpoint: crowdfunding_outpoints[0],
-                amount: amount_1,
-                script: &generate_pay_to_pubkey_script(crowdfunding_public_key),
-            },
-            InputWithScript {
-                outpoint: crowdfunding_outpoints[1],
-                amount: amount_1,
-                script: &generate_pay_to_pubkey_script(crowdfunding_public_key),
-            },
-        ],
-        crowdfunding_keypair,
-        refund_address.script_pubkey(),
-    );
+    challenge_tx
+        .add_inputs_and_output(
+            &vec![
+                InputWithScript {
+                    outpoint: crowdfunding_outpoints[0],
+                    amount: amount_1,
+                    script: &generate_pay_to_pubkey_script(crowdfunding_public_key),
+                },
+                InputWithScript {
+                    outpoint: crowdfunding_outpoints[1],
+                    amount: amount_1,
+                    script: &generate_pay_to_pubkey_script(crowdfunding_public_key),
+                },
+            ],
+            crowdfunding_keypair,
+            refund_address.script_pubkey(),
+            Amount::from_sat(config.protocol_parameters.dust_amount),
+        )
+        .unwrap();
 
     let tx = challenge_tx.finalize();
     check_tx_output_sum(two_thirds_of_initial_amount * 2 + DUST_AMOUNT, &tx);
@@ -0,0 +1,350 @@
+use bitcoin::{Amount, OutPoint, TxOut};
+use bridge::{
+    client::simulator::server::LedgerSimulator,
+    connectors::base::TaprootConnector,
+    contexts::verifier::VerifierContext,
+    error::{Error, GraphError, TransactionError},
+    graphs::{
+        base::{DUST_AMOUNT, PEG_OUT_FEE},
+        challenge_crowdfunding::{
+            select_contributions, ChallengeContribution, ChallengeCrowdfundingStatus,
+        },
+        peg_in::PegInGraph,
+        peg_out::PegOutGraph,
+    },
+    scripts::{generate_pay_to_pubkey_script, generate_pay_to_pubkey_script_address},
+    transactions::{
+        base::{
+            BaseTransaction, Input, InputWithScript, MIN_RELAY_FEE_CHALLENGE,
+            MIN_RELAY_FEE_PEG_IN_CONFIRM, MIN_RELAY_FEE_PEG_IN_DEPOSIT,
+        },
+        challenge::{ChallengeTransaction, MIN_RELAY_FEE_CHALLENGE_CROWDFUNDING_INPUT},
+    },
+};
+
+use crate::bridge::{
+    helper::generate_stub_outpoint,
+    setup::{setup_test_with_esplora_url, SetupConfig, INITIAL_AMOUNT},
+};
+
+#[tokio::test]
+async fn test_challenge_crowdfunding_by_several_verifiers() {
+    let simulator = LedgerSimulator::start().await.unwrap();
+    let config = setup_test_with_esplora_url(simulator.url()).await;
+    let esplora = &config.client_0.esplora;
+
+    let amount_0 = Amount::from_sat(DUST_AMOUNT + MIN_RELAY_FEE_CHALLENGE);
+    let outpoint_0 = simulator.fund(&config.connector_a.generate_taproot_address(), amount_0);
+    let challenge_tx = ChallengeTransaction::new(
+        &config.operator_context,
+        &config.connector_a,
+        Input {
+            outpoint: outpoint_0,
+            amount: amount_0,
+        },
+        Amount::from_sat(INITIAL_AMOUNT),
+    );
+    let required_amount = |input_count| challenge_tx.required_crowdfunding_amount(input_count);
+    assert_eq!(required_amount(1), Amount::from_sat(INITIAL_AMOUNT));
+    assert_eq!(
+        required_amount(2),
+        Amount::from_sat(INITIAL_AMOUNT + MIN_RELAY_FEE_CHALLENGE_CROWDFUNDING_INPUT)
+    );
+
+    // Each verifier contributes a share to its own copy of the partial challenge transaction.
+    let amount_a = Amount::from_sat(INITIAL_AMOUNT / 2);
+    let amount_b = required_amount(2) - amount_a;
+    let amount_c = Amount::from_sat(DUST_AMOUNT);
+    let mut challenge_tx_0 = challenge_tx.clone();
+    let (outpoint_a, prev_out_a) = contribute(
+        &simulator,
+        &config.verifier_0_context,
+        &mut challenge_tx_0,
+        amount_a,
+    );
+    let mut challenge_tx_1 = challenge_tx.clone();
+    let (outpoint_b, prev_out_b) = contribute(
+        &simulator,
+        &config.verifier_1_context,
+        &mut challenge_tx_1,
+        amount_b,
+    );
+    let (outpoint_c, prev_out_c) = contribute(
+        &simulator,
+        &config.verifier_0_context,
+        &mut challenge_tx_1,
+        amount_c,
+    );
+
+    // Merging keeps the inputs appended concurrently.
+    challenge_tx_0.merge(&challenge_tx_1);
+    assert_eq!(
+        challenge_tx_0.crowdfunding_outpoints(),
+        vec![outpoint_a, outpoint_b, outpoint_c]
+    );
+    assert_eq!(
+        challenge_tx_0.crowdfunding_outpoints_of(&config.verifier_0_context.verifier_public_key),
+        vec![outpoint_a, outpoint_c]
+    );
+    assert_eq!(
+        challenge_tx_0.verify_crowdfunding_input(&outpoint_b, &prev_out_b),
+        Some(config.verifier_1_context.verifier_public_key)
+    );
+    assert!(challenge_tx_0
+        .verify_crowdfunding_input(&outpoint_b, &prev_out_a)
+        .is_none());
+
+    let contributions = [
+        (outpoint_a, &prev_out_a, &config.verifier_0_context),
+        (outpoint_b, &prev_out_b, &config.verifier_1_context),
+        (outpoint_c, &prev_out_c, &config.verifier_0_context),
+    ]
+    .iter()
+    .map(|(outpoint, prev_out, context)| ChallengeContribution {
+        outpoint: *outpoint,
+        amount: prev_out.value,
+        contributor: context.verifier_public_key,
+    })
+    .collect::<Vec<_>>();
+    assert!(select_contributions(&contributions[..1], required_amount).is_none());
+    let selected = select_contributions(&contributions, required_amount).unwrap();
+    let mut selected_outpoints = selected
+        .iter()
+        .map(|contribution| contribution.outpoint)
+        .collect::<Vec<_>>();
+    selected_outpoints.sort();
+    let mut expected_outpoints = vec![outpoint_a, outpoint_b];
+    expected_outpoints.sort();
+    assert_eq!(selected_outpoints, expected_outpoints);
+
+    // The signatures of the selected contributions stay valid without the unneeded one.
+    let tx = challenge_tx_0.finalize_with_crowdfunding_inputs(&selected_outpoints);
+    assert_eq!(tx.input.len(), 3);
+    assert!(esplora.broadcast(&tx).await.is_ok());
+    simulator.mine(1);
+
+    let connector_a_status = esplora
+        .get_output_status(&outpoint_0.txid, outpoint_0.vout as u64)
+        .await
+        .unwrap()
+        .unwrap();
+    assert!(connector_a_status.spent);
+    assert_eq!(connector_a_status.txid, Some(tx.compute_txid()));
+    let contribution_c_status = esplora
+        .get_output_status(&outpoint_c.txid, outpoint_c.vout as u64)
+        .await
+        .unwrap()
+        .unwrap();
+    assert!(!contribution_c_status.spent);
+}
+
+#[tokio::test]
+async fn test_challenge_crowdfunding_not_open_before_kick_off_1() {
+    let simulator = LedgerSimulator::start().await.unwrap();
+    let config = setup_test_with_esplora_url(simulator.url()).await;
+    let esplora = &config.client_0.esplora;
+
+    let mut peg_out_graph = create_peg_out_graph(&config, &simulator).await;
+    assert_eq!(
+        peg_out_graph
+            .challenge_crowdfunding_status(esplora)
+            .await
+            .unwrap(),
+        ChallengeCrowdfundingStatus::NotOpen
+    );
+
+    let amount = Amount::from_sat(peg_out_graph.min_crowdfunding_amount());
+    let verifier_address = generate_pay_to_pubkey_script_address(
+        config.verifier_0_context.network,
+        &config.verifier_0_context.verifier_public_key,
+    );
+    let outpoint = simulator.fund(&verifier_address, amount);
+    let result = peg_out_graph
+        .contribute_to_challenge(
+            esplora,
+            &InputWithScript {
+                outpoint,
+                amount,
+                script: &generate_pay_to_pubkey_script(
+                    &config.verifier_0_context.verifier_public_key,
+                ),
+            },
+            &config.verifier_0_context.verifier_keypair,
+        )
+        .await;
+    assert!(matches!(
+        result,
+        Err(Error::Graph(GraphError::ChallengeCrowdfundingUnavailable(
+            ChallengeCrowdfundingStatus::NotOpen
+        )))
+    ));
+    assert!(peg_out_graph
+        .challenge_contributions_of(&config.verifier_0_context.verifier_public_key)
+        .is_empty());
+    assert!(matches!(
+        peg_out_graph.crowdfunded_challenge(esplora).await,
+        Err(Error::Graph(GraphError::ChallengeCrowdfundingUnavailable(
+            ChallengeCrowdfundingStatus::NotOpen
+        )))
+    ));
+}
+
+#[tokio::test]
+async fn test_challenge_crowdfunding_mixed_with_direct_challenge() {
+    let simulator = LedgerSimulator::start().await.unwrap();
+    let config = setup_test_with_esplora_url(simulator.url()).await;
+    let esplora = &config.client_0.esplora;
+
+    let amount_0 = Amount::from_sat(DUST_AMOUNT + MIN_RELAY_FEE_CHALLENGE);
+    let outpoint_0 = simulator.fund(&config.connector_a.generate_taproot_address(), amount_0);
+    let mut challenge_tx = ChallengeTransaction::new(
+        &config.operator_context,
+        &config.connector_a,
+        Input {
+            outpoint: outpoint_0,
+            amount: amount_0,
+        },
+        Amount::from_sat(INITIAL_AMOUNT),
+    );
+    let (outpoint_a, _) = contribute(
+        &simulator,
+        &config.verifier_0_context,
+        &mut challenge_tx,
+        Amount::from_sat(INITIAL_AMOUNT / 2),
+    );
+
+    // The depositor funds the challenge alone, with a refund output.
+    let depositor_public_key = config.depositor_context.depositor_public_key;
+    let depositor_address = generate_pay_to_pubkey_script_address(
+        config.depositor_context.network,
+        &depositor_public_key,
+    );
+    let amount = Amount::from_sat(INITIAL_AMOUNT + 2 * DUST_AMOUNT);
+    let outpoint = simulator.fund(&depositor_address, amount);
+    let script = generate_pay_to_pubkey_script(&depositor_public_key);
+    let inputs = vec![InputWithScript {
+        outpoint,
+        amount,
+        script: &script,
+    }];
+    let add_inputs_and_output = |challenge_tx: &mut ChallengeTransaction| {
+        challenge_tx.add_inputs_and_output(
+            &inputs,
+            &config.depositor_context.depositor_keypair,
+            depositor_address.script_pubkey(),
+            Amount::from_sat(DUST_AMOUNT),
+        )
+    };
+    assert!(matches!(
+        add_inputs_and_output(&mut challenge_tx.clone()),
+        Err(Error::Transaction(
+            TransactionError::CrowdfundingInputsAlreadyAdded
+        ))
+    ));
+    let mut direct_challenge_tx = challenge_tx.without_crowdfunding_inputs();
+    add_inputs_and_output(&mut direct_challenge_tx).unwrap();
+
+    // Once merged, the refund output prevents further contributions.
+    let mut merged_challenge_tx = challenge_tx.clone();
+    merged_challenge_tx.merge(&direct_challenge_tx);
+    let address_b = generate_pay_to_pubkey_script_address(
+        config.verifier_1_context.network,
+        &config.verifier_1_context.verifier_public_key,
+    );
+    let outpoint_b = simulator.fund(&address_b, Amount::from_sat(INITIAL_AMOUNT / 2));
+    assert!(matches!(
+        merged_challenge_tx.add_crowdfunding_input(
+            &InputWithScript {
+                outpoint: outpoint_b,
+                amount: Amount::from_sat(INITIAL_AMOUNT / 2),
+                script: &generate_pay_to_pubkey_script(
+                    &config.verifier_1_context.verifier_public_key
+                ),
+            },
+            &config.verifier_1_context.verifier_keypair,
+        ),
+        Err(Error::Transaction(
+            TransactionError::CrowdfundingInputAfterRefundOutput
+        ))
+    ));
+
+    let tx = direct_challenge_tx.finalize();
+    assert_eq!(tx.input.len(), 2);
+    assert_eq!(tx.output.len(), 2);
+    assert!(esplora.broadcast(&tx).await.is_ok());
+    simulator.mine(1);
+    let contribution_a_status = esplora
+        .get_output_status(&outpoint_a.txid, outpoint_a.vout as u64)
+        .await
+        .unwrap()
+        .unwrap();
+    assert!(!contribution_a_status.spent);
+}
+
+// Funds an output of `amount` for `context` and contributes it to `challenge_tx`.
+fn contribute(
+    simulator: &LedgerSimulator,
+    context: &VerifierContext,
+    challenge_tx: &mut ChallengeTransaction,
+    amount: Amount,
+) -> (OutPoint, TxOut) {
+    let address =
+        generate_pay_to_pubkey_script_address(context.network, &context.verifier_public_key);
+    let outpoint = simulator.fund(&address, amount);
+    challenge_tx
+        .add_crowdfunding_input(
+            &InputWithScript {
+                outpoint,
+                amount,
+                script: &generate_pay_to_pubkey_script(&context.verifier_public_key),
+            },
+            &context.verifier_keypair,
+        )
+        .unwrap();
+
+    (
+        outpoint,
+        TxOut {
+            value: amount,
+            script_pubkey: address.script_pubkey(),
+        },
+    )
+}
+
+async fn create_peg_out_graph(config: &SetupConfig, simulator: &LedgerSimulator) -> PegOutGraph {
+    let amount = Amount::from_sat(
+        INITIAL_AMOUNT + MIN_RELAY_FEE_PEG_IN_DEPOSIT + MIN_RELAY_FEE_PEG_IN_CONFIRM,
+    );
+    let deposit_funding_utxo_address = generate_pay_to_pubkey_script_address(
+        config.depositor_context.network,
+        &config.depositor_context.depositor_public_key,
+    );
+    simulator.fund(&deposit_funding_utxo_address, amount);
+    let outpoint =
+        generate_stub_outpoint(&config.client_0, &deposit_funding_utxo_address, amount).await;
+    let peg_in_graph = PegInGraph::new(
+        &config.depositor_context,
+        Input { outpoint, amount },
+        &config.depositor_evm_address,
+        &config.protocol_parameters,
+    );
+
+    let peg_out_confirm_amount = Amount::from_sat(INITIAL_AMOUNT + PEG_OUT_FEE);
+    let peg_out_confirm_outpoint = simulator.fund(
+        &generate_pay_to_pubkey_script_address(
+            config.operator_context.network,
+            &config.operator_context.operator_public_key,
+        ),
+        peg_out_confirm_amount,
+    );
+    PegOutGraph::new(
+        &config.operator_context,
+        &peg_in_graph,
+        Input {
+            outpoint: peg_out_confirm_outpoint,
+            amount: peg_out_confirm_amount,
+        },
+        &config.commitment_secrets,
+        &config.protocol_parameters,
+    )
+}
@@ -0,0 +1,144 @@
+use std::str::FromStr;
+
+use bitcoin::{Amount, Network, OutPoint, Txid};
+use bridge::{
+    client::client::BitVMClient,
+    constants::DestinationNetwork,
+    error::{ClientError, Error},
+    graphs::base::PEG_OUT_FEE,
+    transactions::base::Input,
+};
+
+use crate::bridge::setup::{setup_test, INITIAL_AMOUNT};
+
+#[tokio::test]
+async fn test_regenerate_commitment_secrets_from_seed() {
+    let mut config = setup_test().await;
+
+    let amount = Amount::from_sat(INITIAL_AMOUNT + PEG_OUT_FEE);
+    let peg_in_input = Input {
+        outpoint: OutPoint {
+            txid: Txid::from_str(
+                "0e6719ac074b0e3cac76d057643506faa1c266b322aa9cf4c6f635fe63b14327",
+            )
+            .unwrap(),
+            vout: 0,
+        },
+        amount,
+    };
+    let peg_out_confirm_input = Input {
+        outpoint: OutPoint {
+            txid: Txid::from_str(
+                "4e254eab8a41f14f56491813a7100cebe305d84edf09488001d9dd3d180a4900",
+            )
+            .unwrap(),
+            vout: 0,
+        },
+        amount,
+    };
+
+    let peg_in_graph_id = config
+        .client_0
+        .create_peg_in_graph(peg_in_input, &config.depositor_evm_address)
+        .await;
+    let commitment_secrets = config
+        .client_0
+        .generate_commitment_secrets(&peg_in_graph_id);
+    assert!(
+        commitment_secrets
+            == config
+                .client_0
+                .generate_commitment_secrets(&peg_in_graph_id)
+    );
+
+    let peg_out_graph_id = config.client_0.create_peg_out_graph(
+        &peg_in_graph_id,
+        peg_out_confirm_input,
+        commitment_secrets.clone(),
+    );
+
+    // client 1 shares the operator key and commitment seed, but has never seen the graph's
+    // commitment secrets, as if its private data file was lost
+    config
+        .client_1
+        .merge_data(config.client_0.data().clone())
+        .unwrap();
+    config
+        .client_1
+        .regenerate_commitment_secrets(&peg_out_graph_id)
+        .expect("Commitment secrets should be regenerated from the seed");
+
+    let regenerated_secrets = &config.client_1.private_data().commitment_secrets
+        [&config.operator_context.operator_public_key][&peg_out_graph_id];
+    assert!(regenerated_secrets == &commitment_secrets);
+}
+
+#[tokio::test]
+async fn test_regenerate_commitment_secrets_rejects_random_secrets() {
+    let mut config = setup_test().await;
+
+    let amount = Amount::from_sat(INITIAL_AMOUNT + PEG_OUT_FEE);
+    let peg_in_input = Input {
+        outpoint: OutPoint {
+            txid: Txid::from_str(
+                "1e6719ac074b0e3cac76d057643506faa1c266b322aa9cf4c6f635fe63b14327",
+            )
+            .unwrap(),
+            vout: 0,
+        },
+        amount,
+    };
+    let peg_out_confirm_input = Input {
+        outpoint: OutPoint {
+            txid: Txid::from_str(
+                "5e254eab8a41f14f56491813a7100cebe305d84edf09488001d9dd3d180a4900",
+            )
+            .unwrap(),
+            vout: 0,
+        },
+        amount,
+    };
+
+    let peg_in_graph_id = config
+        .client_0
+        .create_peg_in_graph(peg_in_input, &config.depositor_evm_address)
+        .await;
+    // the setup's commitment secrets are random and not derived from the operator seed
+    let peg_out_graph_id = config.client_0.create_peg_out_graph(
+        &peg_in_graph_id,
+        peg_out_confirm_input,
+        config.commitment_secrets.clone(),
+    );
+
+    assert!(config
+        .client_0
+        .regenerate_commitment_secrets(&peg_out_graph_id)
+        .is_err());
+}
+
+#[tokio::test]
+async fn test_client_rejects_invalid_commitment_seed() {
+    let config = setup_test().await;
+
+    let result = BitVMClient::new(
+        None,
+        Network::Regtest,
+        DestinationNetwork::Local,
+        None,
+        &config.verifier_0_context.n_of_n_public_keys,
+        None,
+        None,
+        Some("not a hex seed"),
+        None,
+        None,
+        Some("test_invalid_commitment_seed"),
+        None,
+        None,
+    )
+    .await;
+
+    assert!(matches!(
+        result,
+        Err(Error::Client(ClientError::InvalidCommitmentSeed(_)))
+    ));
+}
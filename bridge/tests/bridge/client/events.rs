@@ -0,0 +1,89 @@
+use bitcoin::Amount;
+use futures::{FutureExt, Stream, StreamExt};
+
+use bridge::{
+    client::events::{BridgeEvent, EventLog, PegInStatus, RecordedEvent},
+    graphs::{base::PEG_IN_FEE, peg_in::PegInDepositorStatus},
+    scripts::generate_pay_to_pubkey_script_address,
+    transactions::base::Input,
+};
+
+use serial_test::serial;
+
+use crate::bridge::{
+    faucet::{Faucet, FaucetType},
+    helper::{generate_stub_outpoint, wait_for_confirmation},
+    setup::{setup_test, INITIAL_AMOUNT},
+};
+
+fn drain(events: &mut (impl Stream<Item = RecordedEvent> + Unpin)) -> Vec<RecordedEvent> {
+    let mut received = vec![];
+    while let Some(Some(event)) = events.next().now_or_never() {
+        received.push(event);
+    }
+    received
+}
+
+fn depositor_status(events: &[RecordedEvent], graph_id: &String) -> Option<PegInStatus> {
+    events.iter().rev().find_map(|event| match &event.event {
+        BridgeEvent::PegInStatusChanged {
+            graph_id: id,
+            new: new @ PegInStatus::Depositor(_),
+            ..
+        } if id == graph_id => Some(new.clone()),
+        _ => None,
+    })
+}
+
+#[tokio::test]
+#[serial]
+async fn test_events_for_peg_in_deposit() {
+    let config = setup_test().await;
+    let mut client = config.client_0;
+    let mut events = Box::pin(client.subscribe_events());
+    let logged_events = EventLog::read(&client.event_log_path()).len();
+
+    let amount = Amount::from_sat(INITIAL_AMOUNT + PEG_IN_FEE);
+    let address = generate_pay_to_pubkey_script_address(
+        config.depositor_context.network,
+        &config.depositor_context.depositor_public_key,
+    );
+    let faucet = Faucet::new(FaucetType::EsploraRegtest);
+    faucet.fund_input(&address, amount).await.wait().await;
+    let outpoint = generate_stub_outpoint(&client, &address, amount).await;
+
+    let graph_id = client
+        .create_peg_in_graph(Input { outpoint, amount }, &config.depositor_evm_address)
+        .await;
+    let deposit_txid = client
+        .broadcast_peg_in_deposit(&graph_id)
+        .await
+        .expect("Failed to broadcast peg-in deposit");
+
+    client.update_events().await;
+    let mut received = drain(&mut events);
+    assert_eq!(
+        depositor_status(&received, &graph_id),
+        Some(PegInStatus::Depositor(
+            PegInDepositorStatus::PegInDepositWait
+        ))
+    );
+
+    wait_for_confirmation(config.network).await;
+    client.update_events().await;
+    let confirmed = drain(&mut events);
+    assert!(confirmed.iter().any(|event| matches!(
+        &event.event,
+        BridgeEvent::TxConfirmed { graph_id: id, txid, .. } if id == &graph_id && txid == &deposit_txid
+    )));
+    assert_eq!(
+        depositor_status(&confirmed, &graph_id),
+        Some(PegInStatus::Depositor(
+            PegInDepositorStatus::PegInConfirmWait
+        ))
+    );
+
+    received.extend(confirmed);
+    let log = EventLog::read(&client.event_log_path());
+    assert_eq!(log[logged_events..], received[..]);
+}
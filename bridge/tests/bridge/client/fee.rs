@@ -234,6 +234,8 @@ async fn test_peg_out_fees() {
         }]),
         peg_out_burnt_events: None,
         peg_out_minted_events: None,
+        peg_out_burnt_proofs: None,
+        finalized_block_hash: None,
     };
     let adaptor = MockAdaptor::new(Some(mock_adaptor_config));
     let chain_service = Chain::new(Box::new(adaptor));
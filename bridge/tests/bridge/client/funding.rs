@@ -0,0 +1,73 @@
+use bitcoin::Amount;
+use bridge::{
+    client::{client::BitVMClient, events::Role, simulator::server::LedgerSimulator},
+    error::{Error, WalletError},
+};
+
+use crate::bridge::setup::setup_test_with_esplora_url;
+
+// The simulator creates the same outputs on every run, so reservations persisted by a previous
+// run must not be reused.
+fn release_all_funding(client: &mut BitVMClient) {
+    for outpoint in client.reserved_outpoints().clone() {
+        client.release_funding(&outpoint);
+    }
+}
+
+#[tokio::test]
+async fn test_fund_does_not_reuse_reserved_outputs() {
+    let simulator = LedgerSimulator::start().await.unwrap();
+    let mut config = setup_test_with_esplora_url(simulator.url()).await;
+    let client = &mut config.client_0;
+    release_all_funding(client);
+
+    let amount = Amount::from_sat(50_000);
+    let address = client.funding_wallet(Role::Operator).unwrap().address();
+    let outpoint_0 = simulator.fund(&address, amount);
+    let outpoint_1 = simulator.fund(&address, amount);
+
+    let input_0 = client.fund(Role::Operator, amount).await.unwrap();
+    let input_1 = client.fund(Role::Operator, amount).await.unwrap();
+    assert_ne!(input_0.outpoint, input_1.outpoint);
+    for input in [&input_0, &input_1] {
+        assert!([outpoint_0, outpoint_1].contains(&input.outpoint));
+        assert!(client.reserved_outpoints().contains(&input.outpoint));
+    }
+
+    assert!(matches!(
+        client.fund(Role::Operator, amount).await,
+        Err(Error::Wallet(WalletError::InsufficientFunds { .. }))
+    ));
+
+    client.release_funding(&input_0.outpoint);
+    let input = client.fund(Role::Operator, amount).await.unwrap();
+    assert_eq!(input.outpoint, input_0.outpoint);
+}
+
+#[tokio::test]
+async fn test_fund_skips_unconfirmed_outputs_unless_allowed() {
+    let simulator = LedgerSimulator::start().await.unwrap();
+    let mut config = setup_test_with_esplora_url(simulator.url()).await;
+    let client = &mut config.client_0;
+    release_all_funding(client);
+
+    let address = client.funding_wallet(Role::Operator).unwrap().address();
+    simulator.fund(&address, Amount::from_sat(100_000));
+
+    // Leaves the unconfirmed change of the funding transaction as the only spendable output.
+    let input = client
+        .fund(Role::Operator, Amount::from_sat(40_000))
+        .await
+        .unwrap();
+    assert!(client.reserved_outpoints().contains(&input.outpoint));
+
+    let amount = Amount::from_sat(20_000);
+    assert!(matches!(
+        client.fund(Role::Operator, amount).await,
+        Err(Error::Wallet(WalletError::InsufficientFunds { .. }))
+    ));
+
+    client.set_spend_unconfirmed_utxos(true);
+    let input = client.fund(Role::Operator, amount).await.unwrap();
+    assert_eq!(input.amount, amount);
+}
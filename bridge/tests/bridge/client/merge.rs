@@ -24,7 +24,7 @@ async fn test_merge_add_new_graph() {
     assert_eq!(data.peg_in_graphs.len(), 1);
     assert_eq!(data.peg_out_graphs.len(), 1);
 
-    client.merge_data(new_data);
+    client.merge_data(new_data).unwrap();
 
     let merged_data = client.data();
 
@@ -83,6 +83,7 @@ async fn setup_and_create_graphs() -> (BitVMClient, PegInGraph, PegOutGraph) {
             amount: Amount::from_sat(INITIAL_AMOUNT),
         },
         &config.depositor_evm_address,
+        &config.protocol_parameters,
     );
 
     let new_peg_out_graph = PegOutGraph::new(
@@ -93,6 +94,7 @@ async fn setup_and_create_graphs() -> (BitVMClient, PegInGraph, PegOutGraph) {
             amount,
         },
         &config.commitment_secrets,
+        &config.protocol_parameters,
     );
 
     (config.client_0, new_peg_in_graph, new_peg_out_graph)
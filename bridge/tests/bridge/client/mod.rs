@@ -1,4 +1,7 @@
+pub mod commitment_secrets;
+pub mod events;
 pub mod fee;
+pub mod funding;
 pub mod merge;
 pub mod musig2_keys;
 pub mod musig2_peg_in;
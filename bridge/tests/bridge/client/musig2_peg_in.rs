@@ -78,7 +78,9 @@ async fn test_musig2_peg_in() {
     depositor_operator_verifier_0_client.sync().await;
 
     println!("Verifier 0: Pre-signing...");
-    depositor_operator_verifier_0_client.push_verifier_signature(&graph_id);
+    depositor_operator_verifier_0_client
+        .push_verifier_signature(&graph_id)
+        .unwrap();
 
     println!("Verifier 0: Saving state changes to remote...");
     depositor_operator_verifier_0_client.flush().await;
@@ -88,7 +90,9 @@ async fn test_musig2_peg_in() {
     verifier_1_client.sync().await;
 
     println!("Verifier 1: Pre-signing...");
-    verifier_1_client.push_verifier_signature(&graph_id);
+    verifier_1_client
+        .push_verifier_signature(&graph_id)
+        .unwrap();
 
     println!("Verifier 1: Saving state changes to remote...");
     verifier_1_client.flush().await;
@@ -620,6 +620,8 @@ async fn simulate_peg_out_from_l2(
         }]),
         peg_out_burnt_events: None,
         peg_out_minted_events: None,
+        peg_out_burnt_proofs: None,
+        finalized_block_hash: None,
     };
     let mock_adaptor = MockAdaptor::new(Some(mock_adaptor_config));
     let chain_service = Chain::new(Box::new(mock_adaptor));
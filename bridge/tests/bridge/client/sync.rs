@@ -1,7 +1,15 @@
-use bitcoin::Amount;
+use std::{
+    str::FromStr,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use bitcoin::{Amount, OutPoint, Txid};
 
 use bridge::{
-    graphs::base::PEG_OUT_FEE, scripts::generate_pay_to_pubkey_script_address,
+    client::{cli::query_command::create_query_client, client::BitVMClient},
+    constants::DestinationNetwork,
+    graphs::base::{BaseGraph, PEG_OUT_FEE},
+    scripts::generate_pay_to_pubkey_script_address,
     transactions::base::Input,
 };
 
@@ -56,3 +64,212 @@ async fn test_sync() {
     println!("Save to remote");
     config.client_0.flush().await;
 }
+
+#[tokio::test]
+async fn test_sync_depositor_peg_in_to_verifier() {
+    let config = setup_test().await;
+    let n_of_n_public_keys = &config.verifier_0_context.n_of_n_public_keys;
+    let depositor_secret = config
+        .depositor_context
+        .depositor_keypair
+        .display_secret()
+        .to_string();
+    let verifier_secret = config
+        .verifier_1_context
+        .verifier_keypair
+        .display_secret()
+        .to_string();
+
+    let mut depositor_client = BitVMClient::new(
+        None,
+        config.network,
+        DestinationNetwork::Local,
+        None,
+        n_of_n_public_keys,
+        Some(&depositor_secret),
+        None,
+        None,
+        None,
+        None,
+        Some("test_depositor_only"),
+        None,
+        Some(config.protocol_parameters),
+    )
+    .await
+    .unwrap();
+    let mut verifier_client = BitVMClient::new(
+        None,
+        config.network,
+        DestinationNetwork::Local,
+        None,
+        n_of_n_public_keys,
+        None,
+        None,
+        None,
+        Some(&verifier_secret),
+        None,
+        Some("test_verifier_only"),
+        None,
+        Some(config.protocol_parameters),
+    )
+    .await
+    .unwrap();
+
+    // A new deposit on every run, so the verifier can't know the graph from a previous run.
+    let outpoint = OutPoint {
+        txid: Txid::from_str("0e6719ac074b0e3cac76d057643506faa1c266b322aa9cf4c6f635fe63b14327")
+            .unwrap(),
+        vout: SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_secs() as u32,
+    };
+    let amount = Amount::from_sat(INITIAL_AMOUNT + PEG_OUT_FEE);
+
+    println!("Depositor creates a peg-in graph and saves it to remote");
+    depositor_client.sync().await;
+    let peg_in_graph_id = depositor_client
+        .create_peg_in_graph(Input { outpoint, amount }, &config.depositor_evm_address)
+        .await;
+    depositor_client.flush().await;
+
+    println!("Verifier reads it from remote");
+    verifier_client.sync().await;
+    let peg_in_graph = verifier_client
+        .data()
+        .peg_in_graphs
+        .iter()
+        .find(|graph| graph.id() == &peg_in_graph_id);
+    assert!(peg_in_graph.is_some_and(|graph| {
+        graph.depositor_public_key == config.depositor_context.depositor_public_key
+    }));
+}
+
+#[tokio::test]
+async fn test_sync_operator_changeset_to_query_client() {
+    let config = setup_test().await;
+    // The n-of-n set of the query clients.
+    let n_of_n_public_keys = vec![config.verifier_0_context.verifier_public_key];
+    let depositor_secret = config
+        .depositor_context
+        .depositor_keypair
+        .display_secret()
+        .to_string();
+    let operator_secret = config
+        .operator_context
+        .operator_keypair
+        .display_secret()
+        .to_string();
+
+    let mut depositor_client = BitVMClient::new(
+        None,
+        config.network,
+        DestinationNetwork::Local,
+        None,
+        &n_of_n_public_keys,
+        Some(&depositor_secret),
+        None,
+        None,
+        None,
+        None,
+        Some("test_depositor_for_query"),
+        None,
+        Some(config.protocol_parameters),
+    )
+    .await
+    .unwrap();
+    let mut operator_client = BitVMClient::new(
+        None,
+        config.network,
+        DestinationNetwork::Local,
+        None,
+        &n_of_n_public_keys,
+        None,
+        Some(&operator_secret),
+        None,
+        None,
+        None,
+        Some("test_operator_for_query"),
+        None,
+        Some(config.protocol_parameters),
+    )
+    .await
+    .unwrap();
+
+    // A new deposit on every run, so the query clients can't know the graphs from a previous run.
+    let timestamp = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap()
+        .as_secs() as u32;
+    let txid =
+        Txid::from_str("0e6719ac074b0e3cac76d057643506faa1c266b322aa9cf4c6f635fe63b14327").unwrap();
+    let amount = Amount::from_sat(INITIAL_AMOUNT + PEG_OUT_FEE);
+
+    println!("Depositor submits a peg-in graph to the depositor inbox");
+    depositor_client.sync().await;
+    let peg_in_graph_id = depositor_client
+        .create_peg_in_graph(
+            Input {
+                outpoint: OutPoint {
+                    txid,
+                    vout: timestamp,
+                },
+                amount,
+            },
+            &config.depositor_evm_address,
+        )
+        .await;
+    depositor_client.flush().await;
+
+    println!("Operator pushes a peg-out graph in a changeset");
+    operator_client.sync().await;
+    // Publish the operator's data first, so that the peg-out graph is pushed as a changeset.
+    operator_client.flush().await;
+    let peg_out_graph_id = operator_client.create_peg_out_graph(
+        &peg_in_graph_id,
+        Input {
+            outpoint: OutPoint {
+                txid,
+                vout: timestamp + 1,
+            },
+            amount,
+        },
+        config.commitment_secrets,
+    );
+    operator_client.flush().await;
+
+    println!("Query clients read it from remote");
+    let mut query_client = create_query_client(
+        config.network,
+        DestinationNetwork::Local,
+        Some("test_query_with_operators"),
+        &[config.operator_context.operator_public_key],
+    )
+    .await;
+    let mut untrusting_query_client = create_query_client(
+        config.network,
+        DestinationNetwork::Local,
+        Some("test_query_without_operators"),
+        &[],
+    )
+    .await;
+    query_client.sync().await;
+    untrusting_query_client.sync().await;
+
+    for client in [&query_client, &untrusting_query_client] {
+        assert!(client
+            .data()
+            .peg_in_graphs
+            .iter()
+            .any(|graph| graph.id() == &peg_in_graph_id));
+    }
+    let has_peg_out_graph = |client: &BitVMClient| {
+        client
+            .data()
+            .peg_out_graphs
+            .iter()
+            .any(|graph| graph.id() == &peg_out_graph_id)
+    };
+    assert!(has_peg_out_graph(&query_client));
+    assert!(!has_peg_out_graph(&untrusting_query_client));
+}
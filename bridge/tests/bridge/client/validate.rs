@@ -1,11 +1,25 @@
 use std::str::FromStr;
 
-use bitcoin::{Amount, OutPoint, Txid};
+use bitcoin::{
+    hex::{Case::Upper, DisplayHex},
+    Amount, Network, OutPoint, Txid,
+};
+use serde_json::Value;
+use sha2::{Digest, Sha256};
 
 use bridge::{
-    client::client::{BitVMClient, BitVMClientPublicData},
-    graphs::{base::PEG_OUT_FEE, peg_in::PegInGraph, peg_out::PegOutGraph},
+    client::{
+        client::{BitVMClient, BitVMClientPublicData},
+        schema::{deserialize_versioned, SchemaId},
+    },
+    graphs::{
+        base::{BaseGraph, PEG_OUT_FEE},
+        peg_in::PegInGraph,
+        peg_out::PegOutGraph,
+    },
+    parameters::ProtocolParameters,
     scripts::generate_burn_script,
+    serialization::{deserialize, serialize},
     transactions::{base::Input, pre_signed::PreSignedTransaction},
 };
 
@@ -13,16 +27,16 @@ use crate::bridge::setup::{setup_test, INITIAL_AMOUNT};
 
 #[tokio::test]
 async fn test_validate_success() {
-    let (data, _) = setup_and_create_graphs().await;
+    let (data, _, protocol_parameters) = setup_and_create_graphs().await;
 
-    let is_data_valid = BitVMClient::validate_data(&data);
+    let is_data_valid = BitVMClient::validate_data(&data, &protocol_parameters);
 
     assert!(is_data_valid);
 }
 
 #[tokio::test]
 async fn test_validate_invalid_previous_output() {
-    let (mut data, peg_in_outpoint) = setup_and_create_graphs().await;
+    let (mut data, peg_in_outpoint, protocol_parameters) = setup_and_create_graphs().await;
 
     let changed_outpoint = OutPoint {
         txid: peg_in_outpoint.txid,
@@ -32,60 +46,147 @@ async fn test_validate_invalid_previous_output() {
     let deposit_tx = data.peg_in_graphs[1].peg_in_deposit_transaction.tx_mut();
     deposit_tx.input[0].previous_output = changed_outpoint;
 
-    let is_data_valid = BitVMClient::validate_data(&data);
+    let is_data_valid = BitVMClient::validate_data(&data, &protocol_parameters);
 
     assert!(!is_data_valid);
 }
 
 #[tokio::test]
 async fn test_validate_invalid_script_sig() {
-    let (mut data, _) = setup_and_create_graphs().await;
+    let (mut data, _, protocol_parameters) = setup_and_create_graphs().await;
 
     let deposit_tx = data.peg_in_graphs[1].peg_in_deposit_transaction.tx_mut();
     deposit_tx.input[0].script_sig = generate_burn_script();
 
-    let is_data_valid = BitVMClient::validate_data(&data);
+    let is_data_valid = BitVMClient::validate_data(&data, &protocol_parameters);
 
     assert!(!is_data_valid);
 }
 
 #[tokio::test]
 async fn test_validate_invalid_sequence() {
-    let (mut data, _) = setup_and_create_graphs().await;
+    let (mut data, _, protocol_parameters) = setup_and_create_graphs().await;
 
     let deposit_tx = data.peg_in_graphs[1].peg_in_deposit_transaction.tx_mut();
     deposit_tx.input[0].sequence = bitcoin::Sequence(100);
 
-    let is_data_valid = BitVMClient::validate_data(&data);
+    let is_data_valid = BitVMClient::validate_data(&data, &protocol_parameters);
 
     assert!(!is_data_valid);
 }
 
 #[tokio::test]
 async fn test_validate_invalid_value() {
-    let (mut data, _) = setup_and_create_graphs().await;
+    let (mut data, _, protocol_parameters) = setup_and_create_graphs().await;
 
     let deposit_tx = data.peg_in_graphs[1].peg_in_deposit_transaction.tx_mut();
     deposit_tx.output[0].value = Amount::from_sat(1);
 
-    let is_data_valid = BitVMClient::validate_data(&data);
+    let is_data_valid = BitVMClient::validate_data(&data, &protocol_parameters);
 
     assert!(!is_data_valid);
 }
 
 #[tokio::test]
 async fn test_validate_invalid_script_pubkey() {
-    let (mut data, _) = setup_and_create_graphs().await;
+    let (mut data, _, protocol_parameters) = setup_and_create_graphs().await;
 
     let deposit_tx = data.peg_in_graphs[1].peg_in_deposit_transaction.tx_mut();
     deposit_tx.output[0].script_pubkey = generate_burn_script();
 
-    let is_data_valid = BitVMClient::validate_data(&data);
+    let is_data_valid = BitVMClient::validate_data(&data, &protocol_parameters);
+
+    assert!(!is_data_valid);
+}
+
+#[tokio::test]
+async fn test_validate_different_protocol_parameters() {
+    let (data, _, protocol_parameters) = setup_and_create_graphs().await;
+
+    let mut other_protocol_parameters = protocol_parameters;
+    other_protocol_parameters.connector_3_timelock += 1;
+
+    let is_data_valid = BitVMClient::validate_data(&data, &other_protocol_parameters);
 
     assert!(!is_data_valid);
 }
 
-async fn setup_and_create_graphs() -> (BitVMClientPublicData, OutPoint) {
+#[tokio::test]
+async fn test_validate_tampered_protocol_parameters() {
+    let (mut data, _, protocol_parameters) = setup_and_create_graphs().await;
+
+    let timelock = protocol_parameters.connector_3_timelock;
+    let json = serialize(&data.peg_out_graphs[0]).replace(
+        &format!("\"connector_3_timelock\":{}", timelock),
+        &format!("\"connector_3_timelock\":{}", timelock + 1),
+    );
+    data.peg_out_graphs[0] = deserialize::<PegOutGraph>(&json);
+
+    assert!(!data.peg_out_graphs[0].validate());
+}
+
+#[tokio::test]
+async fn test_validate_graphs_stored_without_protocol_parameters() {
+    let (data, _, _) = setup_and_create_graphs().await;
+
+    // The graphs as stored before the protocol parameters were configurable, with IDs not
+    // committing to them.
+    let mut content = serialize(&data);
+    for peg_in_graph in &data.peg_in_graphs {
+        let deposit_txid = peg_in_graph.peg_in_deposit_transaction.tx().compute_txid();
+        content = content.replace(peg_in_graph.id(), &legacy_id(&deposit_txid.to_string()));
+    }
+    let mut json: Value = serde_json::from_str(&content).unwrap();
+    for peg_out_graph in json["peg_out_graphs"].as_array_mut().unwrap() {
+        let peg_in_graph_id = peg_out_graph["peg_in_graph_id"].as_str().unwrap();
+        let operator_public_key = peg_out_graph["operator_public_key"].as_str().unwrap();
+        peg_out_graph["id"] = Value::from(legacy_id(&format!(
+            "{peg_in_graph_id}{operator_public_key}"
+        )));
+    }
+    for field in ["peg_in_graphs", "peg_out_graphs"] {
+        for graph in json[field].as_array_mut().unwrap() {
+            graph
+                .as_object_mut()
+                .unwrap()
+                .remove("protocol_parameters")
+                .unwrap();
+        }
+    }
+
+    let data = deserialize_versioned::<BitVMClientPublicData>(
+        SchemaId::ClientData,
+        json.to_string().as_bytes(),
+    )
+    .unwrap();
+    let regtest_parameters = ProtocolParameters::for_network(Network::Regtest);
+    assert_ne!(
+        regtest_parameters,
+        ProtocolParameters::for_network(Network::Bitcoin)
+    );
+    for peg_in_graph in &data.peg_in_graphs {
+        assert_eq!(*peg_in_graph.protocol_parameters(), regtest_parameters);
+        assert!(peg_in_graph.validate());
+    }
+    let peg_out_graph = &data.peg_out_graphs[0];
+    assert_eq!(*peg_out_graph.protocol_parameters(), regtest_parameters);
+    assert!(peg_out_graph.validate());
+    assert!(BitVMClient::validate_data(&data, &regtest_parameters));
+
+    // The ID of legacy graphs doesn't commit to their parameters, only the default ones are valid.
+    let timelock = regtest_parameters.connector_3_timelock;
+    let json = serialize(peg_out_graph).replace(
+        &format!("\"connector_3_timelock\":{}", timelock),
+        &format!("\"connector_3_timelock\":{}", timelock + 1),
+    );
+    assert!(!deserialize::<PegOutGraph>(&json).validate());
+}
+
+// SHA-256 of `preimage`, as the graph IDs were generated before they committed to the protocol
+// parameters.
+fn legacy_id(preimage: &str) -> String { Sha256::digest(preimage.as_bytes()).to_hex_string(Upper) }
+
+async fn setup_and_create_graphs() -> (BitVMClientPublicData, OutPoint, ProtocolParameters) {
     let config = setup_test().await;
 
     let amount_0 = Amount::from_sat(INITIAL_AMOUNT + PEG_OUT_FEE + 1);
@@ -108,6 +209,7 @@ async fn setup_and_create_graphs() -> (BitVMClientPublicData, OutPoint) {
             amount: amount_0,
         },
         &config.depositor_evm_address,
+        &config.protocol_parameters,
     );
 
     let peg_in_graph_1 = PegInGraph::new(
@@ -117,6 +219,7 @@ async fn setup_and_create_graphs() -> (BitVMClientPublicData, OutPoint) {
             amount: amount_1,
         },
         &config.depositor_evm_address,
+        &config.protocol_parameters,
     );
 
     let peg_out_graph = PegOutGraph::new(
@@ -127,6 +230,7 @@ async fn setup_and_create_graphs() -> (BitVMClientPublicData, OutPoint) {
             amount: amount_0,
         },
         &config.commitment_secrets,
+        &config.protocol_parameters,
     );
 
     let data = BitVMClientPublicData {
@@ -135,5 +239,5 @@ async fn setup_and_create_graphs() -> (BitVMClientPublicData, OutPoint) {
         peg_out_graphs: vec![peg_out_graph],
     };
 
-    (data, peg_in_outpoint)
+    (data, peg_in_outpoint, config.protocol_parameters)
 }
@@ -0,0 +1,208 @@
+use bitcoin::Amount;
+use bridge::{
+    client::{
+        client::BitVMClientPublicData,
+        data_store::changeset::{
+            accept_depositor_submission, apply_graph_change, diff_data, diff_depositor_submission,
+            Change,
+        },
+    },
+    error::{DataStoreError, Error, Musig2Error},
+    graphs::base::{BaseGraph, PEG_OUT_FEE},
+    transactions::{
+        pre_signed::PreSignedTransaction, pre_signed_musig2::PreSignedMusig2Transaction,
+    },
+};
+
+use crate::bridge::{
+    helper::{create_peg_in_graph, sign_graph},
+    setup::{setup_test, INITIAL_AMOUNT},
+};
+
+#[tokio::test]
+async fn test_changeset_round_trip() {
+    // Arrange
+    let config = setup_test().await;
+    let n_of_n_public_keys = &config.verifier_0_context.n_of_n_public_keys;
+    let peg_in_graph = create_peg_in_graph(&config, Amount::from_sat(INITIAL_AMOUNT + PEG_OUT_FEE));
+    let published_data = BitVMClientPublicData {
+        version: 1,
+        peg_in_graphs: vec![peg_in_graph.clone()],
+        peg_out_graphs: vec![],
+    };
+
+    let mut current_data = published_data.clone();
+    sign_graph(&config, &mut current_data.peg_in_graphs[0]);
+
+    // Act
+    let changes = diff_data(&published_data, &current_data);
+    let mut applied_graph = peg_in_graph;
+    for change in changes.iter() {
+        apply_graph_change(&mut applied_graph, change, n_of_n_public_keys).unwrap();
+    }
+
+    // Assert
+    assert!(changes
+        .iter()
+        .any(|change| matches!(change, Change::NoncesAdded { .. })));
+    assert!(changes
+        .iter()
+        .any(|change| matches!(change, Change::SignaturesAdded { .. })));
+    assert!(applied_graph == current_data.peg_in_graphs[0]);
+    assert!(diff_data(&current_data, &current_data).is_empty());
+
+    let empty_data = BitVMClientPublicData {
+        version: 1,
+        peg_in_graphs: vec![],
+        peg_out_graphs: vec![],
+    };
+    assert!(matches!(
+        diff_data(&empty_data, &current_data).as_slice(),
+        [Change::PegInGraphAdded(graph)] if graph == &current_data.peg_in_graphs[0]
+    ));
+}
+
+#[tokio::test]
+async fn test_changeset_refuses_invalid_partial_signature() {
+    // Arrange
+    let config = setup_test().await;
+    let n_of_n_public_keys = &config.verifier_0_context.n_of_n_public_keys;
+    let verifier_0 = config.verifier_0_context.verifier_public_key;
+    let verifier_1 = config.verifier_1_context.verifier_public_key;
+    let mut peg_in_graph =
+        create_peg_in_graph(&config, Amount::from_sat(INITIAL_AMOUNT + PEG_OUT_FEE));
+    let secret_nonces_0 = peg_in_graph.push_verifier_nonces(&config.verifier_0_context);
+    peg_in_graph.push_verifier_nonces(&config.verifier_1_context);
+    let published_data = BitVMClientPublicData {
+        version: 1,
+        peg_in_graphs: vec![peg_in_graph.clone()],
+        peg_out_graphs: vec![],
+    };
+
+    let mut current_data = published_data.clone();
+    current_data.peg_in_graphs[0]
+        .verifier_sign(&config.verifier_0_context, &secret_nonces_0)
+        .unwrap();
+    let mut changes = diff_data(&published_data, &current_data);
+    // Verifier 0's partial signatures passed off as verifier 1's.
+    for change in changes.iter_mut() {
+        if let Change::SignaturesAdded { signatures, .. } = change {
+            let forged_signatures: Vec<_> = signatures
+                .iter()
+                .map(|signature| {
+                    let mut forged_signature = signature.clone();
+                    forged_signature.verifier = verifier_1;
+                    forged_signature
+                })
+                .collect();
+            signatures.extend(forged_signatures);
+        }
+    }
+
+    // Act
+    let results: Vec<_> = changes
+        .iter()
+        .map(|change| apply_graph_change(&mut peg_in_graph, change, n_of_n_public_keys))
+        .collect();
+
+    // Assert
+    assert!(results.iter().any(|result| matches!(
+        result,
+        Err(Error::Musig2(Musig2Error::InvalidPartialSignatures(blames)))
+            if blames.iter().all(|blame| blame.verifier == verifier_1)
+    )));
+    for tx in peg_in_graph.musig2_transactions() {
+        for signatures in tx.musig2_signatures().values() {
+            assert!(signatures.contains_key(&verifier_0));
+            assert!(!signatures.contains_key(&verifier_1));
+        }
+    }
+}
+
+#[tokio::test]
+async fn test_changeset_refuses_transaction_update_beyond_witnesses() {
+    // Arrange
+    let config = setup_test().await;
+    let n_of_n_public_keys = &config.verifier_0_context.n_of_n_public_keys;
+    let mut peg_in_graph =
+        create_peg_in_graph(&config, Amount::from_sat(INITIAL_AMOUNT + PEG_OUT_FEE));
+    let published_graph = peg_in_graph.clone();
+    let (tx_name, tx) = peg_in_graph.pre_signed_transactions()[0];
+    let graph_id = peg_in_graph.id().clone();
+
+    let mut tx_with_output = tx.tx().clone();
+    tx_with_output.output.push(tx_with_output.output[0].clone());
+    let mut tx_with_input = tx.tx().clone();
+    let mut appended_input = tx_with_input.input[0].clone();
+    appended_input.previous_output.vout += 1;
+    tx_with_input.input.push(appended_input);
+    let changes = [tx_with_output, tx_with_input].map(|tx| Change::TransactionUpdated {
+        graph_id: graph_id.clone(),
+        tx_name: tx_name.to_string(),
+        tx,
+    });
+
+    // Act
+    let results: Vec<_> = changes
+        .iter()
+        .map(|change| apply_graph_change(&mut peg_in_graph, change, n_of_n_public_keys))
+        .collect();
+
+    // Assert
+    for result in results {
+        assert!(matches!(
+            result,
+            Err(Error::DataStore(DataStoreError::InvalidTransactionUpdate { graph_id: id, tx }))
+                if id == graph_id && tx == tx_name
+        ));
+    }
+    assert!(peg_in_graph == published_graph);
+}
+
+#[tokio::test]
+async fn test_depositor_submission_only_accepts_own_peg_in_graphs() {
+    // Arrange
+    let config = setup_test().await;
+    let depositor = config.depositor_context.depositor_public_key;
+    let verifier_0 = config.verifier_0_context.verifier_public_key;
+    let mut peg_in_graph =
+        create_peg_in_graph(&config, Amount::from_sat(INITIAL_AMOUNT + PEG_OUT_FEE));
+    peg_in_graph.push_verifier_nonces(&config.verifier_0_context);
+    assert!(!peg_in_graph.musig2_transactions()[0]
+        .musig2_nonces()
+        .is_empty());
+    let empty_data = BitVMClientPublicData {
+        version: 1,
+        peg_in_graphs: vec![],
+        peg_out_graphs: vec![],
+    };
+    let current_data = BitVMClientPublicData {
+        version: 1,
+        peg_in_graphs: vec![peg_in_graph.clone()],
+        peg_out_graphs: vec![],
+    };
+
+    // Act
+    let changes = diff_depositor_submission(&empty_data, &current_data, &depositor);
+    let accepted = accept_depositor_submission(&depositor, changes.clone());
+    let refused = accept_depositor_submission(&verifier_0, changes.clone());
+
+    // Assert
+    assert_eq!(changes.len(), 1);
+    assert!(diff_depositor_submission(&empty_data, &current_data, &verifier_0).is_empty());
+    assert!(diff_depositor_submission(&current_data, &current_data, &depositor).is_empty());
+    // The depositor can't add verifier nonces on their behalf.
+    assert!(matches!(
+        accepted.as_slice(),
+        [Ok(graph)] if graph.id() == peg_in_graph.id()
+            && graph
+                .musig2_transactions()
+                .iter()
+                .all(|tx| tx.musig2_nonces().is_empty())
+    ));
+    assert!(matches!(
+        refused.as_slice(),
+        [Err(Error::DataStore(DataStoreError::UnauthorizedChange { author, graph_id }))]
+            if *author == verifier_0 && graph_id == peg_in_graph.id()
+    ));
+}
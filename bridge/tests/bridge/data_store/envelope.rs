@@ -0,0 +1,80 @@
+use bridge::{
+    client::data_store::envelope::{open_envelope, SignedEnvelope},
+    error::DataStoreError,
+    serialization::serialize,
+};
+
+use crate::bridge::setup::setup_test;
+
+const TIMESTAMP: u64 = 1721392247764;
+
+#[tokio::test]
+async fn test_envelope_signed_by_trusted_author() {
+    let config = setup_test().await;
+    let author = config.verifier_0_context.verifier_public_key;
+    let trusted_authors = config.verifier_0_context.n_of_n_public_keys.clone();
+
+    let envelope = SignedEnvelope::seal(
+        author,
+        &config.verifier_0_context.verifier_keypair,
+        TIMESTAMP,
+        "{\"version\":1}".to_string(),
+    );
+    let content = serialize(&envelope).into_bytes();
+
+    let payload = open_envelope(&content, &author, &trusted_authors, TIMESTAMP).unwrap();
+    assert_eq!(payload, "{\"version\":1}");
+}
+
+#[tokio::test]
+async fn test_envelope_rejected() {
+    let config = setup_test().await;
+    let author = config.verifier_0_context.verifier_public_key;
+    let trusted_authors = config.verifier_0_context.n_of_n_public_keys.clone();
+    let envelope = SignedEnvelope::seal(
+        author,
+        &config.verifier_0_context.verifier_keypair,
+        TIMESTAMP,
+        "{\"version\":1}".to_string(),
+    );
+
+    // Unsigned data.
+    assert!(matches!(
+        open_envelope(b"{\"version\":1}", &author, &trusted_authors, TIMESTAMP),
+        Err(DataStoreError::Unsigned)
+    ));
+
+    // Author outside of the trusted set.
+    let operator = config.operator_context.operator_public_key;
+    let untrusted = SignedEnvelope::seal(
+        operator,
+        &config.operator_context.operator_keypair,
+        TIMESTAMP,
+        "{\"version\":1}".to_string(),
+    );
+    assert!(matches!(
+        untrusted.open(&operator, &trusted_authors, TIMESTAMP),
+        Err(DataStoreError::UnknownAuthor(key)) if key == operator
+    ));
+
+    // Envelope of another author's namespace.
+    let verifier_1 = config.verifier_1_context.verifier_public_key;
+    assert!(matches!(
+        envelope.open(&verifier_1, &trusted_authors, TIMESTAMP),
+        Err(DataStoreError::UnexpectedAuthor { .. })
+    ));
+
+    // Payload modified after signing.
+    let mut tampered = envelope.clone();
+    tampered.payload = "{\"version\":2}".to_string();
+    assert!(matches!(
+        tampered.open(&author, &trusted_authors, TIMESTAMP),
+        Err(DataStoreError::InvalidSignature(key)) if key == author
+    ));
+
+    // Envelope replayed under a newer file name.
+    assert!(matches!(
+        envelope.open(&author, &trusted_authors, TIMESTAMP + 1),
+        Err(DataStoreError::Stale { .. })
+    ));
+}
@@ -1,3 +1,5 @@
+pub mod changeset;
+pub mod envelope;
 pub mod ftp;
 pub mod ftps;
 pub mod sftp;
@@ -228,12 +228,16 @@ async fn create_graph() -> (
 
     println!("Verifier 0 pre-sign peg-out");
     depositor_operator_verifier_0_client.sync().await;
-    depositor_operator_verifier_0_client.push_verifier_signature(&peg_out_graph_id);
+    depositor_operator_verifier_0_client
+        .push_verifier_signature(&peg_out_graph_id)
+        .unwrap();
     depositor_operator_verifier_0_client.flush().await;
 
     println!("Verifier 1 pre-sign peg-out");
     verifier_1_client.sync().await;
-    verifier_1_client.push_verifier_signature(&peg_out_graph_id);
+    verifier_1_client
+        .push_verifier_signature(&peg_out_graph_id)
+        .unwrap();
     verifier_1_client.flush().await;
 
     (
@@ -276,11 +280,11 @@ async fn create_peg_in_graph(
     client_1.flush().await;
 
     client_0.sync().await;
-    client_0.push_verifier_signature(&graph_id);
+    client_0.push_verifier_signature(&graph_id).unwrap();
     client_0.flush().await;
 
     client_1.sync().await;
-    client_1.push_verifier_signature(&graph_id);
+    client_1.push_verifier_signature(&graph_id).unwrap();
     client_1.flush().await;
 
     // Wait for peg-in deposit transaction to be mined
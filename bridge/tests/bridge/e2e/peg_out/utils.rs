@@ -55,14 +55,16 @@ pub async fn create_peg_in_graph(
     println!("{}", "Sync verifier 0".bold().magenta());
     depositor_verifier_0.sync().await;
     println!("{}", "Generate verifier 0 signatures".bold().magenta());
-    depositor_verifier_0.push_verifier_signature(&graph_id);
+    depositor_verifier_0
+        .push_verifier_signature(&graph_id)
+        .unwrap();
     println!("{}", "Flush verifier 0 signatures".bold().magenta());
     depositor_verifier_0.flush().await;
 
     println!("{}", "Sync verifier 1".bold().blue());
     verifier_1.sync().await;
     println!("{}", "Generate verifier 1 signatures".bold().blue());
-    verifier_1.push_verifier_signature(&graph_id);
+    verifier_1.push_verifier_signature(&graph_id).unwrap();
     println!("{}", "Flush verifier 1 signatures".bold().blue());
     verifier_1.flush().await;
     println!("{}", "PEG-IN ceremony finish".bold().yellow());
@@ -185,14 +187,18 @@ pub async fn create_peg_out_graph() -> (
     println!("{}", "Sync verifier 0".bold().magenta());
     verifier_0_operator_depositor.sync().await;
     println!("{}", "Generate verifier 0 signatures".bold().magenta());
-    verifier_0_operator_depositor.push_verifier_signature(&peg_out_graph_id);
+    verifier_0_operator_depositor
+        .push_verifier_signature(&peg_out_graph_id)
+        .unwrap();
     println!("{}", "Flush verifier 0 signatures".bold().magenta());
     verifier_0_operator_depositor.flush().await;
 
     println!("{}", "Sync verifier 1".bold().blue());
     verifier_1.sync().await;
     println!("{}", "Generate verifier 1 signatures".bold().blue());
-    verifier_1.push_verifier_signature(&peg_out_graph_id);
+    verifier_1
+        .push_verifier_signature(&peg_out_graph_id)
+        .unwrap();
     println!("{}", "Flush verifier 1 signatures".bold().blue());
     verifier_1.flush().await;
     println!("{}", "PEG-OUT ceremony finish".bold().yellow());
@@ -0,0 +1,546 @@
+use std::{env::temp_dir, fs, path::PathBuf};
+
+use alloy::{
+    json_abi::JsonAbi,
+    primitives::{Address as EvmAddress, Bytes, B256, U256},
+    sol,
+    sol_types::SolEvent,
+    transports::http::reqwest::Url,
+};
+use bitcoin::{hashes::Hash, Address, Amount, Network, OutPoint, PublicKey, Txid};
+use bridge::{
+    client::{
+        chain::{
+            chain_adaptor::{get_chain_adaptor, ChainAdaptor},
+            ethereum_adaptor::IBridge,
+            evm_adaptor::{EvmAdaptor, EvmAdaptorConfig, EvmEventAbi},
+            l2_proof::L2ProofConfig,
+        },
+        simulator::evm_node::{EvmChain, EvmNodeSimulator},
+    },
+    constants::{DestinationNetwork, ETHEREUM_CHAIN_ID, ETHEREUM_SEPOLIA_CHAIN_ID},
+    contexts::base::generate_keys_from_secret,
+    error::L2Error,
+};
+
+const CHAIN_ID: u64 = 31337;
+const BRIDGE_ADDRESS: &str = "0x76d05F58D14c0838EC630C8140eDC5aB7CD159Dc";
+const WITHDRAWER_ADDRESS: &str = "0x0d2b8f3E6fA5C4e6bD2F7d1e9c0A3B5d7F9e1A3c";
+const OPERATOR_SECRET: &str = "d6a3b5c1f0e24978a1c3e5f7092b4d6f8a0c2e4f6b8d0f1a3c5e7092b4d6f8a1";
+const WITHDRAWER_SECRET: &str = "2c4e6a8b0d1f3e5a7c9b1d3f5e7a9c0b2d4f6e8a0c1b3d5f7e9a2c4b6d8f0e1a";
+const CONFIRMATIONS: u64 = 2;
+const MAX_BLOCK_RANGE: u64 = 10;
+const AMOUNT: u64 = 100_000;
+
+// A bridge contract declaring `PegOutBurnt` differently than `IBridge`.
+sol!(
+    interface ICustomBridge {
+        struct Outpoint {
+            bytes32 txId;
+            uint256 vOut;
+        }
+        event PegOutBurnt(
+            bytes operator_pubKey,
+            address indexed withdrawer,
+            uint256 indexed amount,
+            Outpoint source_outpoint,
+            uint64 nonce
+        );
+    }
+);
+
+const CUSTOM_PEG_OUT_BURNT_ABI: &str = r#"{
+    "type": "event",
+    "name": "PegOutBurnt",
+    "inputs": [
+        { "name": "operator_pubKey", "type": "bytes", "indexed": false },
+        { "name": "withdrawer", "type": "address", "indexed": true },
+        { "name": "amount", "type": "uint256", "indexed": true },
+        {
+            "name": "source_outpoint",
+            "type": "tuple",
+            "indexed": false,
+            "components": [
+                { "name": "txId", "type": "bytes32" },
+                { "name": "vOut", "type": "uint256" }
+            ]
+        },
+        { "name": "nonce", "type": "uint64", "indexed": false }
+    ],
+    "anonymous": false
+}"#;
+
+#[tokio::test]
+async fn test_evm_adaptor_reads_confirmed_events() {
+    let simulator = EvmNodeSimulator::start(CHAIN_ID).await.unwrap();
+    let adaptor = EvmAdaptor::new(adaptor_config(&simulator, None));
+    let operator_public_key = operator_public_key();
+    let peg_out_outpoint = stub_outpoint(0x11);
+    let burnt_outpoint = stub_outpoint(0x22);
+
+    let peg_out_block = simulator.add_log(
+        bridge_address(),
+        peg_out_initiated(&peg_out_outpoint, &withdrawer_destination_address()).encode_log_data(),
+    );
+    // Not emitted by the bridge contract.
+    simulator.add_log(
+        EvmAddress::repeat_byte(0x33),
+        peg_out_burnt(&burnt_outpoint).encode_log_data(),
+    );
+    // Withdrawals to other than P2PKH addresses aren't supported.
+    simulator.add_log(
+        bridge_address(),
+        peg_out_initiated(
+            &stub_outpoint(0x44),
+            "bcrt1qw508d6qejxtdg4y5r3zarvary0c5xw7kygt080",
+        )
+        .encode_log_data(),
+    );
+    let burnt_block = simulator.add_log(
+        bridge_address(),
+        peg_out_burnt(&burnt_outpoint).encode_log_data(),
+    );
+
+    // The last blocks aren't confirmed yet.
+    assert_eq!(adaptor.get_peg_out_init_event().await.unwrap().len(), 1);
+    assert!(adaptor.get_peg_out_burnt_event().await.unwrap().is_empty());
+
+    simulator.mine(CONFIRMATIONS);
+    let peg_out_events = adaptor.get_peg_out_init_event().await.unwrap();
+    assert_eq!(peg_out_events.len(), 1);
+    let peg_out_event = &peg_out_events[0];
+    assert_eq!(peg_out_event.source_outpoint, peg_out_outpoint);
+    assert_eq!(peg_out_event.amount, Amount::from_sat(AMOUNT));
+    assert_eq!(peg_out_event.operator_public_key, operator_public_key);
+    assert_eq!(
+        peg_out_event.withdrawer_destination_address,
+        withdrawer_destination_address()
+    );
+    assert_eq!(
+        peg_out_event.timestamp as u64,
+        EvmChain::block_timestamp(peg_out_block)
+    );
+    assert_eq!(
+        peg_out_event.tx_hash,
+        EvmChain::transaction_hash(peg_out_block, 0).to_vec()
+    );
+
+    let burnt_events = adaptor.get_peg_out_burnt_event().await.unwrap();
+    assert_eq!(burnt_events.len(), 1);
+    assert_eq!(burnt_events[0].source_outpoint, burnt_outpoint);
+    assert_eq!(
+        burnt_events[0].withdrawer_chain_address,
+        WITHDRAWER_ADDRESS
+            .parse::<EvmAddress>()
+            .unwrap()
+            .to_string()
+    );
+    assert_eq!(
+        burnt_events[0].tx_hash,
+        EvmChain::transaction_hash(burnt_block, 0).to_vec()
+    );
+    assert!(adaptor.get_peg_in_minted_event().await.unwrap().is_empty());
+}
+
+#[tokio::test]
+async fn test_evm_adaptor_scans_in_block_ranges() {
+    let simulator = EvmNodeSimulator::start(CHAIN_ID).await.unwrap();
+    simulator.set_max_block_range(Some(MAX_BLOCK_RANGE));
+    let adaptor = EvmAdaptor::new(adaptor_config(&simulator, None));
+
+    simulator.mine(24);
+    adaptor.get_peg_out_init_event().await.unwrap();
+    assert_eq!(
+        simulator.get_logs_ranges(),
+        vec![(0, 9), (10, 19), (20, 22)]
+    );
+    assert_eq!(adaptor.next_block().await, Some(23));
+
+    // Only the blocks confirmed since the previous scan are requested.
+    adaptor.get_peg_out_burnt_event().await.unwrap();
+    assert_eq!(simulator.get_logs_ranges().len(), 3);
+    simulator.mine(5);
+    adaptor.get_peg_in_minted_event().await.unwrap();
+    assert_eq!(simulator.get_logs_ranges()[3..], [(23, 27)]);
+
+    // Ranges larger than the provider accepts fail without moving the cursor.
+    let wide_range_adaptor = EvmAdaptor::new(EvmAdaptorConfig {
+        max_block_range: MAX_BLOCK_RANGE + 1,
+        ..adaptor_config(&simulator, None)
+    });
+    let result = wide_range_adaptor.get_peg_out_init_event().await;
+    assert!(result.is_err_and(|e| e.contains("max block range")));
+    assert_eq!(wide_range_adaptor.next_block().await, Some(0));
+}
+
+#[tokio::test]
+async fn test_evm_adaptor_resumes_from_persisted_cursor() {
+    let simulator = EvmNodeSimulator::start(CHAIN_ID).await.unwrap();
+    let cursor_path = temp_cursor_path("resumes.json");
+    let outpoint = stub_outpoint(0x55);
+    simulator.add_log(bridge_address(), peg_out_burnt(&outpoint).encode_log_data());
+    simulator.mine(CONFIRMATIONS);
+
+    let adaptor = EvmAdaptor::new(adaptor_config(&simulator, Some(cursor_path.clone())));
+    assert_eq!(adaptor.get_peg_out_burnt_event().await.unwrap().len(), 1);
+    let scanned_ranges = simulator.get_logs_ranges().len();
+    drop(adaptor);
+
+    // A new adaptor keeps the events read and continues from the cursor.
+    simulator.mine(1);
+    let adaptor = EvmAdaptor::new(adaptor_config(&simulator, Some(cursor_path.clone())));
+    let burnt_events = adaptor.get_peg_out_burnt_event().await.unwrap();
+    assert_eq!(burnt_events.len(), 1);
+    assert_eq!(burnt_events[0].source_outpoint, outpoint);
+    assert_eq!(simulator.get_logs_ranges()[scanned_ranges..], [(2, 2)]);
+
+    // The cursor of another bridge contract isn't used.
+    let other_bridge_adaptor = EvmAdaptor::new(EvmAdaptorConfig {
+        bridge_address: EvmAddress::repeat_byte(0x66),
+        ..adaptor_config(&simulator, Some(cursor_path))
+    });
+    assert!(other_bridge_adaptor
+        .get_peg_out_burnt_event()
+        .await
+        .is_err());
+}
+
+#[tokio::test]
+async fn test_evm_adaptor_rejects_node_of_other_chain() {
+    let simulator = EvmNodeSimulator::start(CHAIN_ID).await.unwrap();
+    let adaptor = EvmAdaptor::new(EvmAdaptorConfig {
+        chain_id: 1,
+        ..adaptor_config(&simulator, None)
+    });
+
+    let result = adaptor.get_peg_out_init_event().await;
+    assert!(result.is_err_and(|e| e.contains("chain ID")));
+    assert!(simulator.get_logs_ranges().is_empty());
+}
+
+#[tokio::test]
+async fn test_evm_adaptor_custom_event_abi() {
+    let simulator = EvmNodeSimulator::start(CHAIN_ID).await.unwrap();
+    let outpoint = stub_outpoint(0x77);
+    let custom_event = ICustomBridge::PegOutBurnt {
+        operator_pubKey: Bytes::from(operator_public_key().to_bytes()),
+        withdrawer: WITHDRAWER_ADDRESS.parse().unwrap(),
+        amount: U256::from(AMOUNT),
+        source_outpoint: ICustomBridge::Outpoint {
+            txId: event_tx_id(&outpoint),
+            vOut: U256::from(outpoint.vout),
+        },
+        nonce: 7,
+    };
+    simulator.add_log(bridge_address(), custom_event.encode_log_data());
+    simulator.mine(CONFIRMATIONS);
+
+    // Not decoded with the `IBridge` declaration of the event.
+    let default_abi_adaptor = EvmAdaptor::new(adaptor_config(&simulator, None));
+    assert!(default_abi_adaptor
+        .get_peg_out_burnt_event()
+        .await
+        .unwrap()
+        .is_empty());
+
+    let mut event_abi = EvmEventAbi::default();
+    let custom_abi =
+        serde_json::from_str::<JsonAbi>(&format!("[{CUSTOM_PEG_OUT_BURNT_ABI}]")).unwrap();
+    event_abi.peg_out_burnt = custom_abi.event("PegOutBurnt").unwrap()[0].clone();
+    let adaptor = EvmAdaptor::new(EvmAdaptorConfig {
+        event_abi,
+        ..adaptor_config(&simulator, None)
+    });
+    let burnt_events = adaptor.get_peg_out_burnt_event().await.unwrap();
+    assert_eq!(burnt_events.len(), 1);
+    assert_eq!(burnt_events[0].source_outpoint, outpoint);
+    assert_eq!(burnt_events[0].amount, Amount::from_sat(AMOUNT));
+    assert_eq!(burnt_events[0].operator_public_key, operator_public_key());
+
+    assert!(
+        EvmEventAbi::from_json(&format!("[{CUSTOM_PEG_OUT_BURNT_ABI}]"))
+            .is_err_and(|e| e.contains("PegOutInitiated"))
+    );
+}
+
+#[tokio::test]
+async fn test_evm_adaptor_proves_peg_out_burnt_events() {
+    let simulator = EvmNodeSimulator::start(CHAIN_ID).await.unwrap();
+    simulator.set_finality_depth(CONFIRMATIONS);
+    let adaptor = EvmAdaptor::new(adaptor_config(&simulator, None));
+    let outpoint = stub_outpoint(0x11);
+    let other_outpoint = stub_outpoint(0x22);
+
+    // Enough transactions in the block for its tries to have branch and extension nodes, and
+    // multi-byte keys.
+    let mut logs = (0..130u8)
+        .map(|byte| {
+            (
+                EvmAddress::repeat_byte(0x33),
+                peg_out_burnt(&stub_outpoint(byte)).encode_log_data(),
+            )
+        })
+        .collect::<Vec<_>>();
+    logs[3] = (
+        bridge_address(),
+        peg_out_burnt(&other_outpoint).encode_log_data(),
+    );
+    logs[129] = (bridge_address(), peg_out_burnt(&outpoint).encode_log_data());
+    let burnt_block = simulator.add_logs(logs);
+    simulator.mine(CONFIRMATIONS);
+
+    let finalized_block_hash = adaptor.get_finalized_block_hash().await.unwrap().unwrap();
+    let finalized_block = simulator.block_number() - CONFIRMATIONS;
+    assert_eq!(
+        finalized_block_hash,
+        simulator.chain().block_hash(finalized_block)
+    );
+
+    let proofs = adaptor
+        .get_peg_out_burnt_proofs(&[outpoint.txid], &finalized_block_hash)
+        .await
+        .unwrap();
+    assert_eq!(proofs.len(), 1);
+    assert_eq!(proofs[0].transaction_index, 129);
+    assert_eq!(
+        proofs[0].block_headers.len() as u64,
+        finalized_block - burnt_block + 1
+    );
+    let config = L2ProofConfig {
+        bridge_address: bridge_address(),
+        finalized_block_hash,
+    };
+    let burnt_event = proofs[0].verify(&config).unwrap();
+    assert_eq!(burnt_event.source_outpoint, outpoint);
+    assert_eq!(
+        burnt_event.tx_hash,
+        EvmChain::transaction_hash(burnt_block, 129).to_vec()
+    );
+
+    // Burns after the finalized block aren't proven.
+    let late_outpoint = stub_outpoint(0x44);
+    simulator.add_log(
+        bridge_address(),
+        peg_out_burnt(&late_outpoint).encode_log_data(),
+    );
+    simulator.mine(CONFIRMATIONS);
+    assert!(adaptor
+        .get_peg_out_burnt_proofs(&[late_outpoint.txid], &finalized_block_hash)
+        .await
+        .unwrap()
+        .is_empty());
+
+    // Proofs of burns declared differently than `IBridge` wouldn't verify.
+    let mut event_abi = EvmEventAbi::default();
+    let custom_abi =
+        serde_json::from_str::<JsonAbi>(&format!("[{CUSTOM_PEG_OUT_BURNT_ABI}]")).unwrap();
+    event_abi.peg_out_burnt = custom_abi.event("PegOutBurnt").unwrap()[0].clone();
+    let adaptor = EvmAdaptor::new(EvmAdaptorConfig {
+        event_abi,
+        ..adaptor_config(&simulator, None)
+    });
+    assert_eq!(adaptor.get_finalized_block_hash().await.unwrap(), None);
+    assert!(adaptor
+        .get_peg_out_burnt_proofs(&[outpoint.txid], &finalized_block_hash)
+        .await
+        .unwrap()
+        .is_empty());
+
+    // Nor are any burns without a finality node.
+    let adaptor = EvmAdaptor::new(EvmAdaptorConfig {
+        finality_rpc_url: None,
+        ..adaptor_config(&simulator, None)
+    });
+    assert_eq!(adaptor.get_finalized_block_hash().await.unwrap(), None);
+    assert!(adaptor
+        .get_peg_out_burnt_proofs(&[outpoint.txid], &finalized_block_hash)
+        .await
+        .unwrap()
+        .is_empty());
+}
+
+#[tokio::test]
+async fn test_evm_adaptor_rejects_proofs_of_forged_chain() {
+    let finality_node = EvmNodeSimulator::start(CHAIN_ID).await.unwrap();
+    let forging_node = EvmNodeSimulator::start(CHAIN_ID).await.unwrap();
+    finality_node.set_finality_depth(CONFIRMATIONS);
+    let outpoint = stub_outpoint(0x77);
+
+    // The node the proofs are built from serves a fork of the chain with a burn that never
+    // happened.
+    finality_node.mine(CONFIRMATIONS + 1);
+    let forged_block =
+        forging_node.add_log(bridge_address(), peg_out_burnt(&outpoint).encode_log_data());
+    forging_node.mine(CONFIRMATIONS);
+
+    let adaptor = EvmAdaptor::new(EvmAdaptorConfig {
+        finality_rpc_url: Some(finality_node.url().parse::<Url>().unwrap()),
+        ..adaptor_config(&forging_node, None)
+    });
+    let finalized_block_hash = adaptor.get_finalized_block_hash().await.unwrap().unwrap();
+    assert_eq!(
+        finalized_block_hash,
+        finality_node.chain().block_hash(forged_block)
+    );
+    assert_ne!(
+        finalized_block_hash,
+        forging_node.chain().block_hash(forged_block)
+    );
+    assert_eq!(adaptor.get_peg_out_burnt_event().await.unwrap().len(), 1);
+
+    // The forging node doesn't have the finalized block.
+    assert!(adaptor
+        .get_peg_out_burnt_proofs(&[outpoint.txid], &finalized_block_hash)
+        .await
+        .is_err());
+
+    // Proofs anchored at its own finalized block don't verify.
+    let forged_proofs = adaptor
+        .get_peg_out_burnt_proofs(
+            &[outpoint.txid],
+            &forging_node.chain().block_hash(forged_block),
+        )
+        .await
+        .unwrap();
+    assert_eq!(forged_proofs.len(), 1);
+    let config = L2ProofConfig {
+        bridge_address: bridge_address(),
+        finalized_block_hash,
+    };
+    assert!(matches!(
+        forged_proofs[0].verify(&config),
+        Err(L2Error::BlockNotFinalized)
+    ));
+}
+
+#[tokio::test]
+async fn test_get_chain_adaptor_for_evm_network() {
+    let simulator = EvmNodeSimulator::start(CHAIN_ID).await.unwrap();
+    let outpoint = stub_outpoint(0x88);
+    simulator.add_log(bridge_address(), peg_out_burnt(&outpoint).encode_log_data());
+    simulator.mine(CONFIRMATIONS);
+
+    let network = DestinationNetwork::Evm { chain_id: CHAIN_ID };
+    assert_eq!(network.to_string(), format!("evm_{CHAIN_ID}"));
+    let adaptor = get_chain_adaptor(network, None, Some(adaptor_config(&simulator, None)));
+    let burnt_events = adaptor.get_peg_out_burnt_event().await.unwrap();
+    assert_eq!(burnt_events.len(), 1);
+    assert_eq!(burnt_events[0].source_outpoint, outpoint);
+}
+
+#[tokio::test]
+async fn test_get_chain_adaptor_for_ethereum_networks() {
+    for (network, chain_id) in [
+        (DestinationNetwork::Ethereum, ETHEREUM_CHAIN_ID),
+        (
+            DestinationNetwork::EthereumSepolia,
+            ETHEREUM_SEPOLIA_CHAIN_ID,
+        ),
+    ] {
+        assert_eq!(network.chain_id(), Some(chain_id));
+        let simulator = EvmNodeSimulator::start(chain_id).await.unwrap();
+        let outpoint = stub_outpoint(0x99);
+        simulator.add_log(bridge_address(), peg_out_burnt(&outpoint).encode_log_data());
+        simulator.mine(CONFIRMATIONS);
+
+        let config = EvmAdaptorConfig {
+            chain_id,
+            ..adaptor_config(&simulator, None)
+        };
+        let adaptor = get_chain_adaptor(network, None, Some(config));
+        let burnt_events = adaptor.get_peg_out_burnt_event().await.unwrap();
+        assert_eq!(burnt_events.len(), 1);
+        assert_eq!(burnt_events[0].source_outpoint, outpoint);
+        assert!(adaptor.get_finalized_block_hash().await.unwrap().is_some());
+    }
+    assert_eq!(DestinationNetwork::Local.chain_id(), None);
+}
+
+#[test]
+#[should_panic(expected = "EVM adaptor config of another chain")]
+fn test_get_chain_adaptor_rejects_config_of_another_chain() {
+    let config = EvmAdaptorConfig {
+        chain_id: CHAIN_ID,
+        rpc_url: "http://127.0.0.1:8545".parse::<Url>().unwrap(),
+        finality_rpc_url: None,
+        bridge_address: bridge_address(),
+        bridge_creation_block: 0,
+        confirmations: CONFIRMATIONS,
+        max_block_range: MAX_BLOCK_RANGE,
+        event_abi: EvmEventAbi::default(),
+        cursor_path: None,
+    };
+    get_chain_adaptor(DestinationNetwork::Ethereum, None, Some(config));
+}
+
+fn adaptor_config(simulator: &EvmNodeSimulator, cursor_path: Option<PathBuf>) -> EvmAdaptorConfig {
+    EvmAdaptorConfig {
+        chain_id: CHAIN_ID,
+        rpc_url: simulator.url().parse::<Url>().unwrap(),
+        finality_rpc_url: Some(simulator.url().parse::<Url>().unwrap()),
+        bridge_address: bridge_address(),
+        bridge_creation_block: 0,
+        confirmations: CONFIRMATIONS,
+        max_block_range: MAX_BLOCK_RANGE,
+        event_abi: EvmEventAbi::default(),
+        cursor_path,
+    }
+}
+
+fn temp_cursor_path(name: &str) -> PathBuf {
+    let path = temp_dir()
+        .join(format!("bridge_evm_adaptor_{}", std::process::id()))
+        .join(name);
+    let _ = fs::remove_file(&path);
+    path
+}
+
+fn bridge_address() -> EvmAddress { BRIDGE_ADDRESS.parse().unwrap() }
+
+fn operator_public_key() -> PublicKey {
+    generate_keys_from_secret(Network::Regtest, OPERATOR_SECRET).1
+}
+
+fn withdrawer_destination_address() -> String {
+    let (_, withdrawer_public_key) = generate_keys_from_secret(Network::Regtest, WITHDRAWER_SECRET);
+    Address::p2pkh(withdrawer_public_key, Network::Regtest).to_string()
+}
+
+fn stub_outpoint(byte: u8) -> OutPoint {
+    OutPoint {
+        txid: Txid::from_byte_array([byte; 32]),
+        vout: byte as u32,
+    }
+}
+
+// The source outpoint txid of peg-out events is in reversed byte order.
+fn event_tx_id(outpoint: &OutPoint) -> B256 {
+    let mut tx_id = outpoint.txid.to_byte_array();
+    tx_id.reverse();
+    B256::from(tx_id)
+}
+
+fn peg_out_initiated(outpoint: &OutPoint, destination_address: &str) -> IBridge::PegOutInitiated {
+    IBridge::PegOutInitiated {
+        withdrawer: WITHDRAWER_ADDRESS.parse().unwrap(),
+        destination_address: destination_address.to_string(),
+        source_outpoint: IBridge::Outpoint {
+            txId: event_tx_id(outpoint),
+            vOut: U256::from(outpoint.vout),
+        },
+        amount: U256::from(AMOUNT),
+        operator_pubKey: Bytes::from(operator_public_key().to_bytes()),
+    }
+}
+
+fn peg_out_burnt(outpoint: &OutPoint) -> IBridge::PegOutBurnt {
+    IBridge::PegOutBurnt {
+        withdrawer: WITHDRAWER_ADDRESS.parse().unwrap(),
+        source_outpoint: IBridge::Outpoint {
+            txId: event_tx_id(outpoint),
+            vOut: U256::from(outpoint.vout),
+        },
+        amount: U256::from(AMOUNT),
+        operator_pubKey: Bytes::from(operator_public_key().to_bytes()),
+    }
+}
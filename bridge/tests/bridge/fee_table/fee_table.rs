@@ -0,0 +1,87 @@
+use std::str::FromStr;
+
+use bitcoin::{Amount, OutPoint, Txid};
+use bridge::{
+    graphs::{
+        base::{BaseGraph, PEG_OUT_FEE},
+        peg_out::PegOutGraph,
+    },
+    transactions::{
+        base::{BaseTransaction, Input},
+        fee_table::{
+            measure_vsizes, peg_out_fee, render_fee_table, stale_relay_fees, RELAY_FEE_CONSTANTS,
+        },
+    },
+};
+
+use crate::bridge::{
+    helper::{create_peg_in_graph, sign_graph},
+    setup::{setup_test, INITIAL_AMOUNT},
+};
+
+#[tokio::test]
+async fn test_relay_fee_constants_are_up_to_date() {
+    let config = setup_test().await;
+    let peg_in_graph = create_peg_in_graph(&config, Amount::from_sat(INITIAL_AMOUNT + PEG_OUT_FEE));
+    let peg_out_graph = PegOutGraph::new(
+        &config.operator_context,
+        &peg_in_graph,
+        Input {
+            outpoint: OutPoint {
+                txid: Txid::from_str(
+                    "4e254eab8a41f14f56491813a7100cebe305d84edf09488001d9dd3d180a4900",
+                )
+                .unwrap(),
+                vout: 0,
+            },
+            amount: Amount::from_sat(INITIAL_AMOUNT + PEG_OUT_FEE),
+        },
+        &config.commitment_secrets,
+        &config.protocol_parameters,
+    );
+
+    let vsizes = measure_vsizes(&[&peg_in_graph, &peg_out_graph]);
+    for (tx_name, _, _) in RELAY_FEE_CONSTANTS {
+        assert!(vsizes.contains_key(tx_name), "{tx_name} wasn't measured");
+    }
+
+    let stale = stale_relay_fees(&vsizes);
+    assert!(
+        stale.is_empty(),
+        "Stale relay fee constants: {:?}\nRegenerate them with `bridge fee-table`:\n{}",
+        stale,
+        render_fee_table(&vsizes)
+    );
+    assert_eq!(peg_out_fee(&vsizes), Some(PEG_OUT_FEE));
+}
+
+#[tokio::test]
+async fn test_transaction_templates_cover_signed_transactions() {
+    let config = setup_test().await;
+    let mut peg_in_graph =
+        create_peg_in_graph(&config, Amount::from_sat(INITIAL_AMOUNT + PEG_OUT_FEE));
+    sign_graph(&config, &mut peg_in_graph);
+
+    let templates = peg_in_graph.transaction_templates();
+    let template_vsize = |tx_name: &str| {
+        templates
+            .iter()
+            .find(|(name, _)| *name == tx_name)
+            .map(|(_, tx)| tx.vsize())
+            .unwrap()
+    };
+
+    // The dummy signatures are never smaller than the real ones.
+    let deposit_tx = peg_in_graph.peg_in_deposit_transaction.finalize();
+    assert!(template_vsize("PegInDeposit") >= deposit_tx.vsize());
+    let confirm_tx = peg_in_graph.peg_in_confirm_transaction.finalize();
+    assert_eq!(template_vsize("PegInConfirm"), confirm_tx.vsize());
+    let refund_tx = peg_in_graph.peg_in_refund_transaction.finalize();
+    assert_eq!(template_vsize("PegInRefund"), refund_tx.vsize());
+
+    // Templates only differ from the transactions in their witnesses.
+    for (tx_name, template) in &templates {
+        let tx = peg_in_graph.pre_signed_transaction(tx_name).unwrap().tx();
+        assert_eq!(template.compute_txid(), tx.compute_txid());
+    }
+}
@@ -0,0 +1,97 @@
+use std::str::FromStr;
+
+use bitcoin::{Amount, OutPoint, Txid};
+use bridge::{
+    graphs::{
+        base::{BaseGraph, PEG_OUT_FEE},
+        export::{GraphExport, GraphExportFormat, TxExportStatus},
+        peg_in::PegInGraph,
+        peg_out::PegOutGraph,
+    },
+    transactions::base::Input,
+};
+
+use crate::bridge::setup::{setup_test, INITIAL_AMOUNT};
+
+#[tokio::test]
+async fn test_export_peg_out_graph() {
+    let config = setup_test().await;
+    let amount = Amount::from_sat(INITIAL_AMOUNT + PEG_OUT_FEE);
+    let peg_in_graph = PegInGraph::new(
+        &config.depositor_context,
+        Input {
+            outpoint: OutPoint {
+                txid: Txid::from_str(
+                    "0e6719ac074b0e3cac76d057643506faa1c266b322aa9cf4c6f635fe63b14327",
+                )
+                .unwrap(),
+                vout: 0,
+            },
+            amount,
+        },
+        &config.depositor_evm_address,
+        &config.protocol_parameters,
+    );
+    let peg_out_graph = PegOutGraph::new(
+        &config.operator_context,
+        &peg_in_graph,
+        Input {
+            outpoint: OutPoint {
+                txid: Txid::from_str(
+                    "4e254eab8a41f14f56491813a7100cebe305d84edf09488001d9dd3d180a4900",
+                )
+                .unwrap(),
+                vout: 0,
+            },
+            amount,
+        },
+        &config.commitment_secrets,
+        &config.protocol_parameters,
+    );
+
+    let export = GraphExport::new(&peg_out_graph);
+
+    // Every transaction of the graph is a node.
+    for (name, tx) in peg_out_graph.pre_signed_transactions() {
+        let txid = tx.tx().compute_txid();
+        assert!(export
+            .nodes
+            .iter()
+            .any(|node| node.txid == txid && node.name == Some(name)));
+    }
+    assert!(export
+        .nodes
+        .iter()
+        .all(|node| node.status == TxExportStatus::Unknown));
+
+    // The take transactions spend the peg-in deposit through connector 0 of the peg-in confirm
+    // transaction, outside of the peg-out graph.
+    let peg_in_confirm_txid = peg_in_graph.peg_in_confirm_transaction.tx().compute_txid();
+    let peg_in_confirm_node = export
+        .nodes
+        .iter()
+        .find(|node| node.txid == peg_in_confirm_txid)
+        .unwrap();
+    assert_eq!(peg_in_confirm_node.name, None);
+    let take_1_txid = peg_out_graph
+        .pre_signed_transaction("Take1")
+        .unwrap()
+        .tx()
+        .compute_txid();
+    assert!(export
+        .edges
+        .iter()
+        .any(|edge| edge.from == peg_in_confirm_txid
+            && edge.to == take_1_txid
+            && edge.connector.as_deref() == Some("Connector 0")));
+
+    assert!(export.edges.iter().any(|edge| edge.timelock.is_some()));
+    assert!(export.edges.iter().any(|edge| edge.amount.is_some()));
+
+    let dot = export.render(GraphExportFormat::Dot);
+    assert!(dot.starts_with(&format!("digraph \"{}\"", peg_out_graph.id())));
+    assert!(dot.contains("Connector 0:0"));
+    let mermaid = export.render(GraphExportFormat::Mermaid);
+    assert!(mermaid.starts_with("flowchart TD"));
+    assert!(mermaid.contains(&format!("tx_{peg_in_confirm_txid}")));
+}
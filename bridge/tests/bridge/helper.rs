@@ -14,12 +14,14 @@ use bitcoin::{PubkeyHash, PublicKey, Txid};
 use bridge::client::chain::chain::PegOutEvent;
 use bridge::proof::get_proof;
 use bridge::{
-    client::client::BitVMClient,
+    client::{client::BitVMClient, simulator::server::LedgerSimulator},
     graphs::{
         base::{BaseGraph, REWARD_MULTIPLIER, REWARD_PRECISION},
         peg_in::PegInGraph,
         peg_out::PegOutGraph,
     },
+    scripts::generate_pay_to_pubkey_script_address,
+    transactions::base::Input,
     utils::{num_blocks_per_network, read_disk_cache, write_disk_cache},
 };
 
@@ -28,6 +30,8 @@ use colored::Colorize;
 use rand::{RngCore, SeedableRng};
 use tokio::time::sleep;
 
+use crate::bridge::setup::SetupConfig;
+
 // Test environment config file and its variables
 const TEST_ENV_FILE: &str = ".env.test";
 const REGTEST_BLOCK_TIME: &str = "REGTEST_BLOCK_TIME";
@@ -214,6 +218,56 @@ pub fn find_peg_in_graph_by_peg_out(
     }
 }
 
+// Deposit outpoint of the peg-in graphs that are not broadcast.
+const STUB_DEPOSIT_TXID: &str = "0e6719ac074b0e3cac76d057643506faa1c266b322aa9cf4c6f635fe63b14327";
+
+/// Peg-in graph of the test depositor spending a stub output of `amount`.
+pub fn create_peg_in_graph(config: &SetupConfig, amount: Amount) -> PegInGraph {
+    let outpoint = OutPoint {
+        txid: Txid::from_str(STUB_DEPOSIT_TXID).unwrap(),
+        vout: 0,
+    };
+
+    PegInGraph::new(
+        &config.depositor_context,
+        Input { outpoint, amount },
+        &config.depositor_evm_address,
+        &config.protocol_parameters,
+    )
+}
+
+/// Peg-in graph of the test depositor spending an output of `amount` funded on `simulator`.
+pub fn create_funded_peg_in_graph(
+    config: &SetupConfig,
+    simulator: &LedgerSimulator,
+    amount: Amount,
+) -> PegInGraph {
+    let deposit_funding_utxo_address = generate_pay_to_pubkey_script_address(
+        config.depositor_context.network,
+        &config.depositor_context.depositor_public_key,
+    );
+    let outpoint = simulator.fund(&deposit_funding_utxo_address, amount);
+
+    PegInGraph::new(
+        &config.depositor_context,
+        Input { outpoint, amount },
+        &config.depositor_evm_address,
+        &config.protocol_parameters,
+    )
+}
+
+/// Pushes the MuSig2 nonces and partial signatures of both test verifiers to `graph`.
+pub fn sign_graph(config: &SetupConfig, graph: &mut dyn BaseGraph) {
+    let secret_nonces_0 = graph.push_verifier_nonces(&config.verifier_0_context);
+    let secret_nonces_1 = graph.push_verifier_nonces(&config.verifier_1_context);
+    graph
+        .verifier_sign(&config.verifier_0_context, &secret_nonces_0)
+        .unwrap();
+    graph
+        .verifier_sign(&config.verifier_1_context, &secret_nonces_1)
+        .unwrap();
+}
+
 pub fn get_superblock_header() -> Header {
     Header {
         version: Version::from_consensus(0x200d2000),
@@ -70,16 +70,20 @@ async fn test_peg_in_success() {
     let secret_nonces_0 = peg_in_confirm.push_nonces(&config.verifier_0_context);
     let secret_nonces_1 = peg_in_confirm.push_nonces(&config.verifier_1_context);
 
-    peg_in_confirm.pre_sign(
-        &config.verifier_0_context,
-        &config.connector_z,
-        &secret_nonces_0,
-    );
-    peg_in_confirm.pre_sign(
-        &config.verifier_1_context,
-        &config.connector_z,
-        &secret_nonces_1,
-    );
+    peg_in_confirm
+        .pre_sign(
+            &config.verifier_0_context,
+            &config.connector_z,
+            &secret_nonces_0,
+        )
+        .unwrap();
+    peg_in_confirm
+        .pre_sign(
+            &config.verifier_1_context,
+            &config.connector_z,
+            &secret_nonces_1,
+        )
+        .unwrap();
 
     let peg_in_confirm_tx = peg_in_confirm.finalize();
     let confirm_txid = peg_in_confirm_tx.compute_txid();
@@ -279,8 +283,8 @@ async fn get_pegin_input(config: &SetupConfig, sats: u64) -> Input {
 async fn test_peg_in_graph_automatic_verifier() {
     // helper functions
     let sync = |a: &mut BitVMClient, b: &mut BitVMClient| {
-        a.merge_data(b.data().clone());
-        b.merge_data(a.data().clone());
+        a.merge_data(b.data().clone()).unwrap();
+        b.merge_data(a.data().clone()).unwrap();
     };
     let graph = |client: &BitVMClient| client.data().peg_in_graphs[0].clone();
     let pegouts_of = |client: &BitVMClient| {
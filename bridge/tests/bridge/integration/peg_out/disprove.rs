@@ -69,6 +69,7 @@ async fn test_disprove_success() {
         &kick_off_2_funding_utxo_address,
         kick_off_2_input_amount,
         &config.commitment_secrets,
+        &config.protocol_parameters,
     )
     .await;
 
@@ -92,6 +93,7 @@ async fn test_disprove_success() {
         &config.assert_commit_connectors_e_1,
         &config.assert_commit_connectors_e_2,
         assert_initial_input_0,
+        &config.protocol_parameters,
     )
     .await;
 
@@ -203,21 +205,26 @@ async fn test_disprove_success() {
         assert_final_input_0,
         assert_final_input_1,
         assert_final_input_2,
+        &config.protocol_parameters,
     );
 
     let secret_nonces_0 = assert_final.push_nonces(&config.verifier_0_context);
     let secret_nonces_1 = assert_final.push_nonces(&config.verifier_1_context);
 
-    assert_final.pre_sign(
-        &config.verifier_0_context,
-        &config.connector_d,
-        &secret_nonces_0,
-    );
-    assert_final.pre_sign(
-        &config.verifier_1_context,
-        &config.connector_d,
-        &secret_nonces_1,
-    );
+    assert_final
+        .pre_sign(
+            &config.verifier_0_context,
+            &config.connector_d,
+            &secret_nonces_0,
+        )
+        .unwrap();
+    assert_final
+        .pre_sign(
+            &config.verifier_1_context,
+            &config.connector_d,
+            &secret_nonces_1,
+        )
+        .unwrap();
 
     let assert_final_tx = assert_final.finalize();
     let assert_final_txid = assert_final_tx.compute_txid();
@@ -273,16 +280,20 @@ async fn test_disprove_success() {
     let secret_nonces_0 = disprove.push_nonces(&config.verifier_0_context);
     let secret_nonces_1 = disprove.push_nonces(&config.verifier_1_context);
 
-    disprove.pre_sign(
-        &config.verifier_0_context,
-        &config.connector_5,
-        &secret_nonces_0,
-    );
-    disprove.pre_sign(
-        &config.verifier_1_context,
-        &config.connector_5,
-        &secret_nonces_1,
-    );
+    disprove
+        .pre_sign(
+            &config.verifier_0_context,
+            &config.connector_5,
+            &secret_nonces_0,
+        )
+        .unwrap();
+    disprove
+        .pre_sign(
+            &config.verifier_1_context,
+            &config.connector_5,
+            &secret_nonces_1,
+        )
+        .unwrap();
 
     let reward_address = generate_pay_to_pubkey_script_address(
         config.withdrawer_context.network,
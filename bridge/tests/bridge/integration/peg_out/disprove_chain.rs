@@ -46,6 +46,7 @@ async fn test_disprove_chain_success() {
         &kick_off_2_funding_utxo_address,
         kick_off_2_input_amount,
         &config.commitment_secrets,
+        &config.protocol_parameters,
     )
     .await;
 
@@ -68,16 +69,20 @@ async fn test_disprove_chain_success() {
     let secret_nonces_0 = disprove_chain.push_nonces(&config.verifier_0_context);
     let secret_nonces_1 = disprove_chain.push_nonces(&config.verifier_1_context);
 
-    disprove_chain.pre_sign(
-        &config.verifier_0_context,
-        &config.connector_b,
-        &secret_nonces_0,
-    );
-    disprove_chain.pre_sign(
-        &config.verifier_1_context,
-        &config.connector_b,
-        &secret_nonces_1,
-    );
+    disprove_chain
+        .pre_sign(
+            &config.verifier_0_context,
+            &config.connector_b,
+            &secret_nonces_0,
+        )
+        .unwrap();
+    disprove_chain
+        .pre_sign(
+            &config.verifier_1_context,
+            &config.connector_b,
+            &secret_nonces_1,
+        )
+        .unwrap();
 
     let reward_address = generate_pay_to_pubkey_script_address(
         config.withdrawer_context.network,
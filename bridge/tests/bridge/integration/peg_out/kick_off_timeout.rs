@@ -49,6 +49,7 @@ async fn test_kick_off_timeout_success() {
         &config.connector_6,
         kick_off_1_input_amount,
         &config.commitment_secrets,
+        &config.protocol_parameters,
     )
     .await;
 
@@ -76,23 +77,29 @@ async fn test_kick_off_timeout_success() {
         &config.operator_context.operator_taproot_public_key, // Verifiers get this via remote storage.
         &config.verifier_0_context.n_of_n_taproot_public_key,
         &config.connector_1.commitment_public_keys, // Verifiers get this via remote storage.
+        &config.protocol_parameters,
     );
-    kick_off_timeout.pre_sign(
-        &config.verifier_0_context,
-        &verifier_0_connector_1,
-        &secret_nonces_0,
-    );
+    kick_off_timeout
+        .pre_sign(
+            &config.verifier_0_context,
+            &verifier_0_connector_1,
+            &secret_nonces_0,
+        )
+        .unwrap();
     let verifier_1_connector_1 = Connector1::new(
         config.verifier_0_context.network,
         &config.operator_context.operator_taproot_public_key,
         &config.verifier_0_context.n_of_n_taproot_public_key,
         &config.connector_1.commitment_public_keys,
+        &config.protocol_parameters,
     );
-    kick_off_timeout.pre_sign(
-        &config.verifier_1_context,
-        &verifier_1_connector_1,
-        &secret_nonces_1,
-    );
+    kick_off_timeout
+        .pre_sign(
+            &config.verifier_1_context,
+            &verifier_1_connector_1,
+            &secret_nonces_1,
+        )
+        .unwrap();
 
     let reward_address = generate_pay_to_pubkey_script_address(
         config.withdrawer_context.network,
@@ -47,6 +47,7 @@ async fn test_start_time_success() {
         &config.connector_6,
         kick_off_1_input_amount,
         &config.commitment_secrets,
+        &config.protocol_parameters,
     )
     .await;
 
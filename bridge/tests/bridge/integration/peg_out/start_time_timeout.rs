@@ -53,6 +53,7 @@ async fn test_start_time_timeout_success() {
         &config.connector_6,
         kick_off_1_input_amount,
         &config.commitment_secrets,
+        &config.protocol_parameters,
     )
     .await;
 
@@ -84,18 +85,22 @@ async fn test_start_time_timeout_success() {
     let secret_nonces_0 = start_time_timeout.push_nonces(&config.verifier_0_context);
     let secret_nonces_1 = start_time_timeout.push_nonces(&config.verifier_1_context);
 
-    start_time_timeout.pre_sign(
-        &config.verifier_0_context,
-        &config.connector_1,
-        &config.connector_2,
-        &secret_nonces_0,
-    );
-    start_time_timeout.pre_sign(
-        &config.verifier_1_context,
-        &config.connector_1,
-        &config.connector_2,
-        &secret_nonces_1,
-    );
+    start_time_timeout
+        .pre_sign(
+            &config.verifier_0_context,
+            &config.connector_1,
+            &config.connector_2,
+            &secret_nonces_0,
+        )
+        .unwrap();
+    start_time_timeout
+        .pre_sign(
+            &config.verifier_1_context,
+            &config.connector_1,
+            &config.connector_2,
+            &secret_nonces_1,
+        )
+        .unwrap();
 
     let reward_address = generate_pay_to_pubkey_script_address(
         config.withdrawer_context.network,
@@ -73,6 +73,7 @@ async fn test_take_1_success() {
         &config.connector_6,
         kick_off_1_input_amount,
         &config.commitment_secrets,
+        &config.protocol_parameters,
     )
     .await;
 
@@ -90,6 +91,7 @@ async fn test_take_1_success() {
         &config.connector_1,
         &config.connector_b,
         kick_off_2_input_0,
+        &config.protocol_parameters,
     );
     let superblock_header = get_superblock_header();
     kick_off_2.sign(
@@ -162,18 +164,22 @@ async fn test_take_1_success() {
     let secret_nonces_0 = take_1.push_nonces(&config.verifier_0_context);
     let secret_nonces_1 = take_1.push_nonces(&config.verifier_1_context);
 
-    take_1.pre_sign(
-        &config.verifier_0_context,
-        &config.connector_0,
-        &config.connector_b,
-        &secret_nonces_0,
-    );
-    take_1.pre_sign(
-        &config.verifier_1_context,
-        &config.connector_0,
-        &config.connector_b,
-        &secret_nonces_1,
-    );
+    take_1
+        .pre_sign(
+            &config.verifier_0_context,
+            &config.connector_0,
+            &config.connector_b,
+            &secret_nonces_0,
+        )
+        .unwrap();
+    take_1
+        .pre_sign(
+            &config.verifier_1_context,
+            &config.connector_0,
+            &config.connector_b,
+            &secret_nonces_1,
+        )
+        .unwrap();
 
     let take_1_tx = take_1.finalize();
     let take_1_txid = take_1_tx.compute_txid();
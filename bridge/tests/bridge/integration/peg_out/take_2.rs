@@ -72,6 +72,7 @@ async fn test_take_2_success() {
         &config.connector_b,
         &config.connector_c,
         assert_input_amount,
+        &config.protocol_parameters,
     )
     .await;
 
@@ -124,18 +125,22 @@ async fn test_take_2_success() {
     let secret_nonces_0 = take_2.push_nonces(&config.verifier_0_context);
     let secret_nonces_1 = take_2.push_nonces(&config.verifier_1_context);
 
-    take_2.pre_sign(
-        &config.verifier_0_context,
-        &config.connector_0,
-        &config.connector_5,
-        &secret_nonces_0,
-    );
-    take_2.pre_sign(
-        &config.verifier_1_context,
-        &config.connector_0,
-        &config.connector_5,
-        &secret_nonces_1,
-    );
+    take_2
+        .pre_sign(
+            &config.verifier_0_context,
+            &config.connector_0,
+            &config.connector_5,
+            &secret_nonces_0,
+        )
+        .unwrap();
+    take_2
+        .pre_sign(
+            &config.verifier_1_context,
+            &config.connector_0,
+            &config.connector_5,
+            &secret_nonces_1,
+        )
+        .unwrap();
 
     take_2.sign(&config.operator_context, &config.connector_c);
 
@@ -11,6 +11,7 @@ use bridge::{
         connector_b::ConnectorB, connector_c::ConnectorC, connector_z::ConnectorZ,
     },
     contexts::{depositor::DepositorContext, operator::OperatorContext, verifier::VerifierContext},
+    parameters::ProtocolParameters,
     superblock::{get_superblock_hash_message, get_superblock_message},
     transactions::{
         assert::AssertTransaction,
@@ -35,6 +36,7 @@ pub async fn create_and_mine_kick_off_1_tx(
     connector_6: &Connector6,
     input_amount: Amount,
     commitment_secrets: &HashMap<CommitmentMessageId, WinternitzSecret>,
+    protocol_parameters: &ProtocolParameters,
 ) -> (Transaction, Txid) {
     let kick_off_1_funding_outpoint =
         generate_stub_outpoint(client, kick_off_1_funding_utxo_address, input_amount).await;
@@ -48,6 +50,7 @@ pub async fn create_and_mine_kick_off_1_tx(
         connector_2,
         connector_6,
         kick_off_1_input,
+        protocol_parameters,
     );
 
     let ethereum_txid = "8b274fbb76c72f66c467c976c61d5ac212620e036818b5986a33f7b557cb2de8";
@@ -86,6 +89,7 @@ pub async fn create_and_mine_kick_off_2_tx(
     kick_off_2_funding_utxo_address: &Address,
     input_amount: Amount,
     commitment_secrets: &HashMap<CommitmentMessageId, WinternitzSecret>,
+    protocol_parameters: &ProtocolParameters,
 ) -> (Transaction, Txid) {
     let kick_off_2_funding_outpoint =
         generate_stub_outpoint(client, kick_off_2_funding_utxo_address, input_amount).await;
@@ -98,6 +102,7 @@ pub async fn create_and_mine_kick_off_2_tx(
         &connector_1,
         &connector_b,
         kick_off_2_input,
+        protocol_parameters,
     );
     let superblock_header = get_superblock_header();
     kick_off_2.sign(
@@ -134,6 +139,7 @@ pub async fn create_and_mine_assert_tx(
     connector_b: &ConnectorB,
     connector_c: &ConnectorC,
     input_amount: Amount,
+    protocol_parameters: &ProtocolParameters,
 ) -> (Transaction, Txid) {
     // create assert tx
     let assert_funding_outpoint =
@@ -148,13 +154,18 @@ pub async fn create_and_mine_assert_tx(
         connector_b,
         connector_c,
         assert_input,
+        protocol_parameters,
     );
 
     let secret_nonces_0 = assert.push_nonces(verifier_0_context);
     let secret_nonces_1 = assert.push_nonces(verifier_1_context);
 
-    assert.pre_sign(verifier_0_context, connector_b, &secret_nonces_0);
-    assert.pre_sign(verifier_1_context, connector_b, &secret_nonces_1);
+    assert
+        .pre_sign(verifier_0_context, connector_b, &secret_nonces_0)
+        .unwrap();
+    assert
+        .pre_sign(verifier_1_context, connector_b, &secret_nonces_1)
+        .unwrap();
 
     let assert_tx = assert.finalize();
     let assert_txid = assert_tx.compute_txid();
@@ -190,8 +201,12 @@ pub async fn create_and_mine_peg_in_confirm_tx(
     let secret_nonces_0 = peg_in_confirm.push_nonces(verifier_0_context);
     let secret_nonces_1 = peg_in_confirm.push_nonces(verifier_1_context);
 
-    peg_in_confirm.pre_sign(verifier_0_context, connector_z, &secret_nonces_0);
-    peg_in_confirm.pre_sign(verifier_1_context, connector_z, &secret_nonces_1);
+    peg_in_confirm
+        .pre_sign(verifier_0_context, connector_z, &secret_nonces_0)
+        .unwrap();
+    peg_in_confirm
+        .pre_sign(verifier_1_context, connector_z, &secret_nonces_1)
+        .unwrap();
 
     let peg_in_confirm_tx = peg_in_confirm.finalize();
     let peg_in_confirm_txid = peg_in_confirm_tx.compute_txid();
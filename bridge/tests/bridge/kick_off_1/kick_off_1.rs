@@ -46,6 +46,7 @@ async fn test_kick_off_1_tx_success() {
         &config.connector_2,
         &config.connector_6,
         input,
+        &config.protocol_parameters,
     );
     let ethereum_txid = "8b274fbb76c72f66c467c976c61d5ac212620e036818b5986a33f7b557cb2de8";
     let bitcoin_txid = "8b4cce4a1a9522392c095df6416533d89e1e6ac7bdf8ab3c1685426b321ed182";
@@ -49,6 +49,7 @@ async fn test_kick_off_2_tx_success() {
             outpoint: funding_outpoint0,
             amount: input_value0,
         },
+        &config.protocol_parameters,
     );
 
     let superblock_header = get_superblock_header();
@@ -49,16 +49,20 @@ async fn test_kick_off_timeout_tx_success() {
     let secret_nonces_0 = kick_off_timeout_tx.push_nonces(&config.verifier_0_context);
     let secret_nonces_1 = kick_off_timeout_tx.push_nonces(&config.verifier_1_context);
 
-    kick_off_timeout_tx.pre_sign(
-        &config.verifier_0_context,
-        &config.connector_1,
-        &secret_nonces_0,
-    );
-    kick_off_timeout_tx.pre_sign(
-        &config.verifier_1_context,
-        &config.connector_1,
-        &secret_nonces_1,
-    );
+    kick_off_timeout_tx
+        .pre_sign(
+            &config.verifier_0_context,
+            &config.connector_1,
+            &secret_nonces_0,
+        )
+        .unwrap();
+    kick_off_timeout_tx
+        .pre_sign(
+            &config.verifier_1_context,
+            &config.connector_1,
+            &secret_nonces_1,
+        )
+        .unwrap();
 
     let reward_address = generate_pay_to_pubkey_script_address(
         config.withdrawer_context.network,
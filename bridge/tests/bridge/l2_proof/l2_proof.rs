@@ -0,0 +1,451 @@
+use alloy::{
+    primitives::{keccak256, Address as EvmAddress, Bytes, B256, U256},
+    sol_types::SolEvent,
+};
+use bitcoin::{hashes::Hash, Amount, Network, OutPoint, PublicKey, Txid};
+use bridge::{
+    client::{
+        chain::{
+            ethereum_adaptor::IBridge,
+            l2_proof::{L2Proof, L2ProofConfig},
+        },
+        simulator::server::LedgerSimulator,
+    },
+    commitments::CommitmentMessageId,
+    contexts::base::generate_keys_from_secret,
+    error::{Error, L2Error},
+    graphs::{
+        base::{BaseGraph, PEG_OUT_FEE},
+        peg_in::PegInGraph,
+        peg_out::PegOutGraph,
+    },
+    scripts::generate_pay_to_pubkey_script_address,
+    transactions::base::{Input, MIN_RELAY_FEE_PEG_IN_CONFIRM, MIN_RELAY_FEE_PEG_IN_DEPOSIT},
+};
+
+use crate::bridge::{
+    helper::generate_stub_outpoint,
+    setup::{setup_test_with_esplora_url, SetupConfig, INITIAL_AMOUNT},
+};
+
+const OTHER_OPERATOR_SECRET: &str =
+    "5e7f9a1b3c5d7e9f0a2b4c6d8e0f1a3b5c7d9e1f3a5b7c9d0e2f4a6b8c0d2e4f";
+const BRIDGE_ADDRESS: &str = "0x76d05F58D14c0838EC630C8140eDC5aB7CD159Dc";
+const WITHDRAWER_ADDRESS: &str = "0x0d2b8f3E6fA5C4e6bD2F7d1e9c0A3B5d7F9e1A3c";
+const BLOCK_TIMESTAMP: u64 = 1_722_328_130;
+const TRANSACTION_INDEX: u64 = 3;
+// An EIP-1559 transaction, only its hash matters to the bridge.
+const TRANSACTION: &[u8] = &[0x02, 0xc5, 0x01, 0x02, 0x03, 0x04, 0x05];
+
+#[test]
+fn test_l2_proof_verify() {
+    let source_outpoint = stub_source_outpoint();
+    let operator_public_key = stub_operator_public_key();
+    let (proof, config) = build_proof(
+        &burn_event(source_outpoint, &operator_public_key),
+        &ProofOptions::default(),
+    );
+
+    let event = proof.verify(&config).unwrap();
+    assert_eq!(event.source_outpoint, source_outpoint);
+    assert_eq!(event.operator_public_key, operator_public_key);
+    assert_eq!(event.amount, Amount::from_sat(INITIAL_AMOUNT));
+    assert_eq!(
+        event.withdrawer_chain_address,
+        WITHDRAWER_ADDRESS
+            .parse::<EvmAddress>()
+            .unwrap()
+            .to_string()
+    );
+    assert_eq!(event.timestamp as u64, BLOCK_TIMESTAMP);
+    assert_eq!(event.tx_hash, keccak256(TRANSACTION).to_vec());
+}
+
+#[test]
+fn test_l2_proof_verify_fails_for_block_not_finalized() {
+    let event = burn_event(stub_source_outpoint(), &stub_operator_public_key());
+    let (proof, mut config) = build_proof(&event, &ProofOptions::default());
+
+    config.finalized_block_hash = B256::repeat_byte(0x11);
+    assert!(matches!(
+        proof.verify(&config),
+        Err(L2Error::BlockNotFinalized)
+    ));
+
+    // The block of the burn is only proven by its descendants up to the finalized block.
+    let (mut proof, config) = build_proof(&event, &ProofOptions::default());
+    proof.block_headers.pop();
+    assert!(matches!(
+        proof.verify(&config),
+        Err(L2Error::BlockNotFinalized)
+    ));
+}
+
+#[test]
+fn test_l2_proof_verify_fails_for_broken_header_chain() {
+    let event = burn_event(stub_source_outpoint(), &stub_operator_public_key());
+    let (mut proof, config) = build_proof(&event, &ProofOptions::default());
+
+    proof.block_headers[0] = block_header(
+        B256::ZERO,
+        B256::repeat_byte(0x22),
+        B256::repeat_byte(0x33),
+        BLOCK_TIMESTAMP,
+    );
+    assert!(matches!(
+        proof.verify(&config),
+        Err(L2Error::InvalidBlockHeader(0))
+    ));
+}
+
+#[test]
+fn test_l2_proof_verify_fails_for_invalid_trie_proof() {
+    let event = burn_event(stub_source_outpoint(), &stub_operator_public_key());
+
+    // The proof of another transaction index.
+    let (mut proof, config) = build_proof(&event, &ProofOptions::default());
+    proof.transaction_index += 1;
+    assert!(matches!(
+        proof.verify(&config),
+        Err(L2Error::InvalidTrieProof)
+    ));
+
+    // A receipt that isn't in the receipts trie.
+    let (mut proof, config) = build_proof(&event, &ProofOptions::default());
+    let leaf = proof.receipt_proof.last_mut().unwrap();
+    let last = leaf.len() - 1;
+    leaf[last] ^= 0x01;
+    assert!(matches!(
+        proof.verify(&config),
+        Err(L2Error::InvalidTrieProof)
+    ));
+
+    let (mut proof, config) = build_proof(&event, &ProofOptions::default());
+    proof.transaction_proof.clear();
+    assert!(matches!(
+        proof.verify(&config),
+        Err(L2Error::InvalidTrieProof)
+    ));
+}
+
+#[test]
+fn test_l2_proof_verify_fails_for_failed_transaction() {
+    let event = burn_event(stub_source_outpoint(), &stub_operator_public_key());
+    let (proof, config) = build_proof(
+        &event,
+        &ProofOptions {
+            failed_transaction: true,
+            ..Default::default()
+        },
+    );
+
+    assert!(matches!(
+        proof.verify(&config),
+        Err(L2Error::FailedTransaction)
+    ));
+}
+
+#[test]
+fn test_l2_proof_verify_fails_for_event_of_other_contract() {
+    let event = burn_event(stub_source_outpoint(), &stub_operator_public_key());
+    let (proof, config) = build_proof(
+        &event,
+        &ProofOptions {
+            emitter: Some(EvmAddress::repeat_byte(0x44)),
+            ..Default::default()
+        },
+    );
+    assert!(matches!(
+        proof.verify(&config),
+        Err(L2Error::BurnEventNotFound)
+    ));
+
+    let (mut proof, config) = build_proof(&event, &ProofOptions::default());
+    proof.log_index = 1;
+    assert!(matches!(
+        proof.verify(&config),
+        Err(L2Error::BurnEventNotFound)
+    ));
+}
+
+#[tokio::test]
+async fn test_peg_out_graph_verify_l2_burn_proof() {
+    let simulator = LedgerSimulator::start().await.unwrap();
+    let config = setup_test_with_esplora_url(simulator.url()).await;
+    let (peg_in_graph, mut peg_out_graph) = create_graphs(&config, &simulator).await;
+    let peg_in_confirm_outpoint = OutPoint {
+        txid: peg_in_graph.peg_in_confirm_transaction.tx().compute_txid(),
+        vout: 0,
+    };
+
+    let (proof, l2_proof_config) = build_proof(
+        &burn_event(
+            peg_in_confirm_outpoint,
+            &config.operator_context.operator_public_key,
+        ),
+        &ProofOptions::default(),
+    );
+    let event = peg_out_graph
+        .verify_l2_burn_proof(&proof, &l2_proof_config, None)
+        .unwrap();
+    assert_eq!(event.source_outpoint, peg_in_confirm_outpoint);
+
+    // Without the burn transaction committed in a kick-off 1 transaction of the graph.
+    assert!(matches!(
+        peg_out_graph.verify_l2_burn_proof(
+            &proof,
+            &l2_proof_config,
+            Some(peg_in_graph.peg_in_confirm_transaction.tx())
+        ),
+        Err(Error::L2(L2Error::BurnEventMismatch))
+    ));
+
+    // The burn of another peg-in, proven in another block.
+    let (other_peg_in_proof, other_peg_in_config) = build_proof(
+        &burn_event(
+            stub_source_outpoint(),
+            &config.operator_context.operator_public_key,
+        ),
+        &ProofOptions::default(),
+    );
+    assert!(matches!(
+        peg_out_graph.verify_l2_burn_proof(&other_peg_in_proof, &other_peg_in_config, None),
+        Err(Error::L2(L2Error::BurnEventMismatch))
+    ));
+
+    // The burn reimbursing another operator.
+    let (other_operator_proof, other_operator_config) = build_proof(
+        &burn_event(peg_in_confirm_outpoint, &stub_operator_public_key()),
+        &ProofOptions::default(),
+    );
+    assert!(matches!(
+        peg_out_graph.verify_l2_burn_proof(&other_operator_proof, &other_operator_config, None),
+        Err(Error::L2(L2Error::BurnEventMismatch))
+    ));
+
+    assert_eq!(
+        peg_out_graph
+            .proven_burn_event(
+                &[other_peg_in_proof.clone(), proof.clone()],
+                &l2_proof_config,
+                None
+            )
+            .unwrap(),
+        event
+    );
+    assert!(matches!(
+        peg_out_graph.proven_burn_event(&[other_peg_in_proof], &other_peg_in_config, None),
+        Err(Error::L2(L2Error::L2ProofNotFound))
+    ));
+
+    // Kick-off 1 isn't created without a proven burn.
+    let result = peg_out_graph
+        .kick_off_1_with_l2_burn_proof(
+            &config.client_0.esplora,
+            &config.operator_context,
+            &config.commitment_secrets[&CommitmentMessageId::PegOutTxIdSourceNetwork],
+            &config.commitment_secrets[&CommitmentMessageId::PegOutTxIdDestinationNetwork],
+            &[other_operator_proof],
+            &other_operator_config,
+        )
+        .await;
+    assert!(matches!(result, Err(Error::L2(L2Error::L2ProofNotFound))));
+}
+
+#[derive(Default)]
+struct ProofOptions {
+    failed_transaction: bool,
+    emitter: Option<EvmAddress>,
+}
+
+fn stub_source_outpoint() -> OutPoint {
+    OutPoint {
+        txid: Txid::from_byte_array([0x55; 32]),
+        vout: 0,
+    }
+}
+
+fn stub_operator_public_key() -> PublicKey {
+    generate_keys_from_secret(Network::Regtest, OTHER_OPERATOR_SECRET).1
+}
+
+fn burn_event(source_outpoint: OutPoint, operator_public_key: &PublicKey) -> IBridge::PegOutBurnt {
+    // The source outpoint txid of peg-out events is in reversed byte order.
+    let mut tx_id = source_outpoint.txid.to_byte_array();
+    tx_id.reverse();
+    IBridge::PegOutBurnt {
+        withdrawer: WITHDRAWER_ADDRESS.parse().unwrap(),
+        source_outpoint: IBridge::Outpoint {
+            txId: B256::from(tx_id),
+            vOut: U256::from(source_outpoint.vout),
+        },
+        amount: U256::from(INITIAL_AMOUNT),
+        operator_pubKey: Bytes::from(operator_public_key.to_bytes()),
+    }
+}
+
+// Proves `event` as the only log of the transaction `TRANSACTION_INDEX` of a block finalized by
+// its child.
+fn build_proof(event: &IBridge::PegOutBurnt, options: &ProofOptions) -> (L2Proof, L2ProofConfig) {
+    let bridge_address = BRIDGE_ADDRESS.parse::<EvmAddress>().unwrap();
+    let log_data = event.encode_log_data();
+    let log = rlp_list(&[
+        rlp_string(options.emitter.unwrap_or(bridge_address).as_slice()),
+        rlp_list(
+            &log_data
+                .topics()
+                .iter()
+                .map(|topic| rlp_string(topic.as_slice()))
+                .collect::<Vec<_>>(),
+        ),
+        rlp_string(&log_data.data),
+    ]);
+    let status = if options.failed_transaction { 0 } else { 1 };
+    let receipt = [
+        vec![0x02], // typed receipt of an EIP-1559 transaction
+        rlp_list(&[
+            rlp_u64(status),
+            rlp_u64(21_000),
+            rlp_string(&[0; 256]),
+            rlp_list(&[log]),
+        ]),
+    ]
+    .concat();
+
+    let key = rlp_u64(TRANSACTION_INDEX);
+    let (transactions_root, transaction_proof) = trie_proof(&key, TRANSACTION);
+    let (receipts_root, receipt_proof) = trie_proof(&key, &receipt);
+
+    let burn_block_header = block_header(
+        B256::repeat_byte(0x66),
+        transactions_root,
+        receipts_root,
+        BLOCK_TIMESTAMP,
+    );
+    let finalized_block_header = block_header(
+        keccak256(&burn_block_header),
+        B256::repeat_byte(0x77),
+        B256::repeat_byte(0x88),
+        BLOCK_TIMESTAMP + 12,
+    );
+    let config = L2ProofConfig {
+        bridge_address,
+        finalized_block_hash: keccak256(&finalized_block_header),
+    };
+
+    (
+        L2Proof {
+            block_headers: vec![burn_block_header, finalized_block_header],
+            transaction_index: TRANSACTION_INDEX,
+            transaction_proof,
+            receipt_proof,
+            log_index: 0,
+        },
+        config,
+    )
+}
+
+fn block_header(
+    parent_hash: B256,
+    transactions_root: B256,
+    receipts_root: B256,
+    timestamp: u64,
+) -> Vec<u8> {
+    rlp_list(&[
+        rlp_string(parent_hash.as_slice()),
+        rlp_string(B256::ZERO.as_slice()),       // ommers hash
+        rlp_string(EvmAddress::ZERO.as_slice()), // beneficiary
+        rlp_string(B256::ZERO.as_slice()),       // state root
+        rlp_string(transactions_root.as_slice()),
+        rlp_string(receipts_root.as_slice()),
+        rlp_string(&[0; 256]), // logs bloom
+        rlp_u64(0),            // difficulty
+        rlp_u64(1_000),        // number
+        rlp_u64(30_000_000),   // gas limit
+        rlp_u64(21_000),       // gas used
+        rlp_u64(timestamp),
+        rlp_string(&[]),                   // extra data
+        rlp_string(B256::ZERO.as_slice()), // mix hash
+        rlp_string(&[0; 8]),               // nonce
+    ])
+}
+
+// A trie holding `value` at the single byte `key`: a branch node on the first nibble of the key
+// pointing to a leaf node with the second one.
+fn trie_proof(key: &[u8], value: &[u8]) -> (B256, Vec<Vec<u8>>) {
+    let [key_byte] = key else {
+        panic!("Only single byte keys are supported");
+    };
+    let leaf = rlp_list(&[rlp_string(&[0x30 | (key_byte & 0x0f)]), rlp_string(value)]);
+
+    let mut branch_items = vec![rlp_string(&[]); 17];
+    branch_items[(key_byte >> 4) as usize] = rlp_string(keccak256(&leaf).as_slice());
+    let branch = rlp_list(&branch_items);
+
+    (keccak256(&branch), vec![branch, leaf])
+}
+
+fn rlp_u64(value: u64) -> Vec<u8> {
+    let bytes = value.to_be_bytes();
+    rlp_string(&bytes[bytes.iter().take_while(|byte| **byte == 0).count()..])
+}
+
+fn rlp_string(bytes: &[u8]) -> Vec<u8> {
+    match bytes {
+        [byte] if *byte < 0x80 => vec![*byte],
+        _ => [rlp_length_prefix(0x80, bytes.len()), bytes.to_vec()].concat(),
+    }
+}
+
+fn rlp_list(items: &[Vec<u8>]) -> Vec<u8> {
+    let payload = items.concat();
+    [rlp_length_prefix(0xc0, payload.len()), payload].concat()
+}
+
+fn rlp_length_prefix(offset: u8, length: usize) -> Vec<u8> {
+    if length < 56 {
+        return vec![offset + length as u8];
+    }
+    let bytes = length.to_be_bytes();
+    let bytes = &bytes[bytes.iter().take_while(|byte| **byte == 0).count()..];
+    [vec![offset + 55 + bytes.len() as u8], bytes.to_vec()].concat()
+}
+
+async fn create_graphs(
+    config: &SetupConfig,
+    simulator: &LedgerSimulator,
+) -> (PegInGraph, PegOutGraph) {
+    let amount = Amount::from_sat(
+        INITIAL_AMOUNT + MIN_RELAY_FEE_PEG_IN_DEPOSIT + MIN_RELAY_FEE_PEG_IN_CONFIRM,
+    );
+    let deposit_funding_utxo_address = generate_pay_to_pubkey_script_address(
+        config.depositor_context.network,
+        &config.depositor_context.depositor_public_key,
+    );
+    simulator.fund(&deposit_funding_utxo_address, amount);
+    let outpoint =
+        generate_stub_outpoint(&config.client_0, &deposit_funding_utxo_address, amount).await;
+    let mut peg_in_graph = PegInGraph::new(
+        &config.depositor_context,
+        Input { outpoint, amount },
+        &config.depositor_evm_address,
+        &config.protocol_parameters,
+    );
+
+    let peg_out_graph = PegOutGraph::new(
+        &config.operator_context,
+        &peg_in_graph,
+        Input {
+            outpoint: OutPoint {
+                txid: peg_in_graph.peg_in_deposit_transaction.tx().compute_txid(),
+                vout: 1,
+            },
+            amount: Amount::from_sat(INITIAL_AMOUNT + PEG_OUT_FEE),
+        },
+        &config.commitment_secrets,
+        &config.protocol_parameters,
+    );
+    peg_in_graph.peg_out_graphs.push(peg_out_graph.id().clone());
+
+    (peg_in_graph, peg_out_graph)
+}
@@ -0,0 +1 @@
+pub mod l2_proof;
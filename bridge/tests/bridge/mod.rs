@@ -1,20 +1,32 @@
 pub mod assert;
 pub mod base;
+pub mod broadcast_validation;
 pub mod challenge;
+pub mod challenge_crowdfunding;
 pub mod client;
 pub mod data_store;
 pub mod disprove;
 pub mod disprove_chain;
 pub mod e2e;
+pub mod evm_adaptor;
 pub mod faucet;
+pub mod fee_table;
+pub mod graph_export;
 pub mod helper;
 pub mod integration;
 pub mod kick_off_1;
 pub mod kick_off_2;
 pub mod kick_off_timeout;
+pub mod l2_proof;
+pub mod operator_set;
 pub mod peg_in;
+pub mod psbt;
+pub mod rpc;
+pub mod schema;
 pub mod serialization;
 pub mod setup;
+pub mod signing_session;
+pub mod simulator;
 pub mod start_time;
 pub mod start_time_timeout;
 pub mod take_1;
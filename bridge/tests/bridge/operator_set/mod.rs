@@ -0,0 +1 @@
+pub mod operator_set;
@@ -0,0 +1,291 @@
+use std::collections::HashMap;
+
+use bitcoin::{Amount, OutPoint, PublicKey};
+use bridge::{
+    client::{chain::chain::PegOutEvent, simulator::server::LedgerSimulator},
+    contexts::operator::OperatorContext,
+    graphs::{
+        base::{BaseGraph, PEG_OUT_FEE},
+        operator_set::{OperatorSelectionPolicy, OperatorSet},
+        peg_in::{PegInGraph, PegInVerifierStatus},
+        peg_out::PegOutGraph,
+    },
+    scripts::generate_p2pkh_address,
+    transactions::base::{Input, MIN_RELAY_FEE_PEG_IN_CONFIRM, MIN_RELAY_FEE_PEG_IN_DEPOSIT},
+};
+
+use crate::bridge::{
+    helper::create_funded_peg_in_graph,
+    setup::{setup_test_with_esplora_url, SetupConfig, INITIAL_AMOUNT},
+};
+
+const OPERATOR_0_SECRET: &str = "d6a3b5c1f0e24978a1c3e5f7092b4d6f8a0c2e4f6b8d0f1a3c5e7092b4d6f8a1";
+const OPERATOR_1_SECRET: &str = "5e7f9a1b3c5d7e9f0a2b4c6d8e0f1a3b5c7d9e1f3a5b7c9d0e2f4a6b8c0d2e4f";
+const OPERATOR_2_SECRET: &str = "2c4e6a8b0d1f3e5a7c9b1d3f5e7a9c0b2d4f6e8a0c1b3d5f7e9a2c4b6d8f0e1a";
+const OUTSIDE_OPERATOR_SECRET: &str =
+    "8b0d2f4a6c8e0a1c3e5a7c9e1b3d5f7a9c1e3b5d7f9a0c2e4b6d8f0a2c4e6b8d";
+
+#[tokio::test]
+async fn test_verifier_awaits_peg_out_graphs_of_all_operators() {
+    let simulator = LedgerSimulator::start().await.unwrap();
+    let config = setup_test_with_esplora_url(simulator.url()).await;
+    let esplora = &config.client_0.esplora;
+    let operator_contexts = operator_contexts(&config);
+    let operator_set = OperatorSet::new(
+        &operator_public_keys(&operator_contexts),
+        OperatorSelectionPolicy::PegOutEvent,
+    );
+
+    let mut peg_in_graph = create_peg_in_graph(&config, &simulator);
+    let deposit_tx = peg_in_graph.deposit(esplora).await.unwrap();
+    esplora.broadcast(&deposit_tx).await.unwrap();
+    simulator.mine(1);
+
+    let peg_out_graphs = create_peg_out_graphs(&config, &mut peg_in_graph, &operator_contexts);
+    let peg_outs = peg_out_graphs.iter().collect::<Vec<_>>();
+
+    // The peg-out graph of the third operator is missing.
+    let mut partial_peg_in_graph = peg_in_graph.clone();
+    partial_peg_in_graph.peg_out_graphs.pop();
+    assert_eq!(
+        operator_set.missing_operators(&peg_outs[..2]),
+        vec![operator_contexts[2].operator_public_key]
+    );
+    assert_eq!(
+        partial_peg_in_graph
+            .verifier_status_for_operators(
+                esplora,
+                &config.verifier_0_context,
+                &peg_outs[..2],
+                &operator_set,
+            )
+            .await,
+        PegInVerifierStatus::AwaitingPegOutCreation
+    );
+    // Without an operator set a single peg-out graph is enough.
+    assert_eq!(
+        partial_peg_in_graph
+            .verifier_status(esplora, &config.verifier_0_context, &peg_outs[..2])
+            .await,
+        PegInVerifierStatus::PendingOurNonces(vec![peg_in_graph.id().clone()])
+    );
+
+    assert!(operator_set.missing_operators(&peg_outs).is_empty());
+    assert_eq!(
+        peg_in_graph
+            .verifier_status_for_operators(
+                esplora,
+                &config.verifier_0_context,
+                &peg_outs,
+                &operator_set,
+            )
+            .await,
+        PegInVerifierStatus::PendingOurNonces(vec![peg_in_graph.id().clone()])
+    );
+}
+
+#[tokio::test]
+async fn test_operator_selection_by_peg_out_event() {
+    let simulator = LedgerSimulator::start().await.unwrap();
+    let config = setup_test_with_esplora_url(simulator.url()).await;
+    let operator_contexts = operator_contexts(&config);
+    let operator_set = OperatorSet::new(
+        &operator_public_keys(&operator_contexts),
+        OperatorSelectionPolicy::PegOutEvent,
+    );
+
+    let mut peg_in_graph = create_peg_in_graph(&config, &simulator);
+    let mut peg_out_graphs = create_peg_out_graphs(&config, &mut peg_in_graph, &operator_contexts);
+    let outside_operator_context = OperatorContext::new(
+        config.network,
+        OUTSIDE_OPERATOR_SECRET,
+        &config.operator_context.n_of_n_public_keys,
+    );
+    peg_out_graphs.extend(create_peg_out_graphs(
+        &config,
+        &mut peg_in_graph,
+        &[outside_operator_context],
+    ));
+
+    // Nothing is selected before the peg-out, so no kick-off is contested nor allowed.
+    let peg_outs = peg_out_graphs.iter().collect::<Vec<_>>();
+    let early_kick_off_1_heights = HashMap::from([(peg_out_graphs[1].id().clone(), 101)]);
+    assert!(operator_set
+        .select_peg_out_graph(&peg_outs, &early_kick_off_1_heights)
+        .is_none());
+    assert!(operator_set
+        .contested_peg_out_graphs(&peg_outs, &early_kick_off_1_heights)
+        .is_empty());
+    assert!(!operator_set.may_kick_off(&peg_out_graphs[1], &peg_outs, &HashMap::new()));
+
+    // The second operator fronts the withdrawal. The operator outside of the set fronting it
+    // too, earlier, isn't selected.
+    let mut events = vec![
+        peg_out_event(&config, &peg_in_graph, &peg_out_graphs[3], 1722328120),
+        peg_out_event(&config, &peg_in_graph, &peg_out_graphs[1], 1722328130),
+    ];
+    for peg_out_graph in peg_out_graphs.iter_mut() {
+        peg_out_graph
+            .match_and_set_peg_out_event(&mut events)
+            .await
+            .unwrap();
+    }
+    assert!(events.is_empty());
+    assert_eq!(
+        peg_out_graphs[1]
+            .peg_out_chain_event
+            .as_ref()
+            .unwrap()
+            .operator_public_key,
+        operator_contexts[1].operator_public_key
+    );
+
+    let peg_outs = peg_out_graphs.iter().collect::<Vec<_>>();
+    let selected = operator_set
+        .select_peg_out_graph(&peg_outs, &HashMap::new())
+        .unwrap();
+    assert_eq!(selected.id(), peg_out_graphs[1].id());
+    assert!(operator_set.may_kick_off(&peg_out_graphs[1], &peg_outs, &HashMap::new()));
+    assert!(!operator_set.may_kick_off(&peg_out_graphs[0], &peg_outs, &HashMap::new()));
+    assert!(!operator_set.may_kick_off(&peg_out_graphs[3], &peg_outs, &HashMap::new()));
+
+    // Only the kick-offs of the other operators are contested.
+    let kick_off_1_heights = HashMap::from([
+        (peg_out_graphs[0].id().clone(), 110),
+        (peg_out_graphs[1].id().clone(), 105),
+        (peg_out_graphs[3].id().clone(), 101),
+    ]);
+    let contested = operator_set.contested_peg_out_graphs(&peg_outs, &kick_off_1_heights);
+    let contested_ids = contested.iter().map(|graph| graph.id()).collect::<Vec<_>>();
+    assert_eq!(
+        contested_ids,
+        vec![peg_out_graphs[0].id(), peg_out_graphs[3].id()]
+    );
+}
+
+#[tokio::test]
+async fn test_operator_selection_by_first_kick_off() {
+    let simulator = LedgerSimulator::start().await.unwrap();
+    let config = setup_test_with_esplora_url(simulator.url()).await;
+    let operator_contexts = operator_contexts(&config);
+    let operator_set = OperatorSet::new(
+        &operator_public_keys(&operator_contexts),
+        OperatorSelectionPolicy::FirstKickOff,
+    );
+
+    let mut peg_in_graph = create_peg_in_graph(&config, &simulator);
+    let peg_out_graphs = create_peg_out_graphs(&config, &mut peg_in_graph, &operator_contexts);
+    let peg_outs = peg_out_graphs.iter().collect::<Vec<_>>();
+
+    assert!(operator_set
+        .select_peg_out_graph(&peg_outs, &HashMap::new())
+        .is_none());
+    assert!(operator_set
+        .contested_peg_out_graphs(&peg_outs, &HashMap::new())
+        .is_empty());
+    // Any operator of the set may kick off first.
+    assert!(peg_outs.iter().all(|peg_out| operator_set.may_kick_off(
+        peg_out,
+        &peg_outs,
+        &HashMap::new()
+    )));
+
+    let kick_off_1_heights = HashMap::from([
+        (peg_out_graphs[2].id().clone(), 103),
+        (peg_out_graphs[0].id().clone(), 107),
+    ]);
+    let selected = operator_set
+        .select_peg_out_graph(&peg_outs, &kick_off_1_heights)
+        .unwrap();
+    assert_eq!(selected.id(), peg_out_graphs[2].id());
+    assert!(!operator_set.may_kick_off(&peg_out_graphs[1], &peg_outs, &kick_off_1_heights));
+
+    let contested = operator_set.contested_peg_out_graphs(&peg_outs, &kick_off_1_heights);
+    assert_eq!(contested.len(), 1);
+    assert_eq!(contested[0].id(), peg_out_graphs[0].id());
+}
+
+fn operator_contexts(config: &SetupConfig) -> Vec<OperatorContext> {
+    let n_of_n_public_keys = &config.operator_context.n_of_n_public_keys;
+    vec![
+        OperatorContext::new(config.network, OPERATOR_0_SECRET, n_of_n_public_keys),
+        OperatorContext::new(config.network, OPERATOR_1_SECRET, n_of_n_public_keys),
+        OperatorContext::new(config.network, OPERATOR_2_SECRET, n_of_n_public_keys),
+    ]
+}
+
+fn operator_public_keys(operator_contexts: &[OperatorContext]) -> Vec<PublicKey> {
+    operator_contexts
+        .iter()
+        .map(|context| context.operator_public_key)
+        .collect()
+}
+
+fn create_peg_in_graph(config: &SetupConfig, simulator: &LedgerSimulator) -> PegInGraph {
+    create_funded_peg_in_graph(
+        config,
+        simulator,
+        Amount::from_sat(
+            INITIAL_AMOUNT + MIN_RELAY_FEE_PEG_IN_DEPOSIT + MIN_RELAY_FEE_PEG_IN_CONFIRM,
+        ),
+    )
+}
+
+// One peg-out graph per operator, added to the peg-out graphs of `peg_in_graph`.
+fn create_peg_out_graphs(
+    config: &SetupConfig,
+    peg_in_graph: &mut PegInGraph,
+    operator_contexts: &[OperatorContext],
+) -> Vec<PegOutGraph> {
+    operator_contexts
+        .iter()
+        .enumerate()
+        .map(|(i, operator_context)| {
+            let peg_out_graph = PegOutGraph::new(
+                operator_context,
+                peg_in_graph,
+                Input {
+                    outpoint: OutPoint {
+                        txid: peg_in_graph.peg_in_deposit_transaction.tx().compute_txid(),
+                        vout: 1 + i as u32,
+                    },
+                    amount: Amount::from_sat(INITIAL_AMOUNT + PEG_OUT_FEE),
+                },
+                &config.commitment_secrets,
+                &config.protocol_parameters,
+            );
+            peg_in_graph.peg_out_graphs.push(peg_out_graph.id().clone());
+
+            peg_out_graph
+        })
+        .collect()
+}
+
+fn peg_out_event(
+    config: &SetupConfig,
+    peg_in_graph: &PegInGraph,
+    peg_out_graph: &PegOutGraph,
+    timestamp: u32,
+) -> PegOutEvent {
+    let peg_in_confirm_tx = peg_in_graph.peg_in_confirm_transaction.tx();
+    PegOutEvent {
+        source_outpoint: OutPoint {
+            txid: peg_in_confirm_tx.compute_txid(),
+            vout: 0,
+        },
+        amount: peg_in_confirm_tx.output[0].value,
+        timestamp,
+        withdrawer_chain_address: config.withdrawer_evm_address.clone(),
+        withdrawer_destination_address: generate_p2pkh_address(
+            config.withdrawer_context.network,
+            &config.withdrawer_context.withdrawer_public_key,
+        )
+        .to_string(),
+        withdrawer_public_key_hash: config
+            .withdrawer_context
+            .withdrawer_public_key
+            .pubkey_hash(),
+        operator_public_key: *peg_out_graph.operator_public_key(),
+        tx_hash: [0u8; 32].into(),
+    }
+}
@@ -1,3 +1,4 @@
 pub mod peg_in_confirm;
 pub mod peg_in_deposit;
+pub mod peg_in_recovery;
 pub mod peg_in_refund;
@@ -43,16 +43,20 @@ async fn test_peg_in_confirm_tx_success() {
     let secret_nonces_0 = peg_in_confirm_tx.push_nonces(&config.verifier_0_context);
     let secret_nonces_1 = peg_in_confirm_tx.push_nonces(&config.verifier_1_context);
 
-    peg_in_confirm_tx.pre_sign(
-        &config.verifier_0_context,
-        &config.connector_z,
-        &secret_nonces_0,
-    );
-    peg_in_confirm_tx.pre_sign(
-        &config.verifier_1_context,
-        &config.connector_z,
-        &secret_nonces_1,
-    );
+    peg_in_confirm_tx
+        .pre_sign(
+            &config.verifier_0_context,
+            &config.connector_z,
+            &secret_nonces_0,
+        )
+        .unwrap();
+    peg_in_confirm_tx
+        .pre_sign(
+            &config.verifier_1_context,
+            &config.connector_z,
+            &secret_nonces_1,
+        )
+        .unwrap();
 
     let tx = peg_in_confirm_tx.finalize();
     check_tx_output_sum(INITIAL_AMOUNT, &tx);
@@ -0,0 +1,114 @@
+use bitcoin::Amount;
+use futures::{FutureExt, StreamExt};
+
+use bridge::{
+    client::{events::BridgeEvent, simulator::server::LedgerSimulator},
+    graphs::{base::PEG_IN_FEE, peg_in::PegInRecoveryStatus},
+    scripts::generate_pay_to_pubkey_script_address,
+    transactions::base::Input,
+};
+
+use crate::bridge::{
+    helper::generate_stub_outpoint,
+    setup::{setup_test_with_esplora_url, INITIAL_AMOUNT},
+};
+
+#[tokio::test]
+async fn test_peg_in_recovery_refunds_stuck_peg_in() {
+    let simulator = LedgerSimulator::start().await.unwrap();
+    let config = setup_test_with_esplora_url(simulator.url()).await;
+    let mut client = config.client_0;
+    let mut events = Box::pin(client.subscribe_events());
+    client.set_peg_in_signing_deadline(1);
+
+    let amount = Amount::from_sat(INITIAL_AMOUNT + PEG_IN_FEE);
+    let address = generate_pay_to_pubkey_script_address(
+        config.depositor_context.network,
+        &config.depositor_context.depositor_public_key,
+    );
+    simulator.fund(&address, amount);
+    let outpoint = generate_stub_outpoint(&client, &address, amount).await;
+    let graph_id = client
+        .create_peg_in_graph(Input { outpoint, amount }, &config.depositor_evm_address)
+        .await;
+
+    assert_eq!(
+        client.peg_in_recovery_status(&graph_id).await.unwrap(),
+        PegInRecoveryStatus::AwaitingDeposit
+    );
+    client.broadcast_peg_in_deposit(&graph_id).await.unwrap();
+    simulator.mine(1);
+    client.update_events().await;
+
+    // No verifier signs the peg-in confirm transaction.
+    let deposit_height = client.esplora.get_height().await.unwrap();
+    assert_eq!(
+        client
+            .process_peg_in_recovery_as_depositor(&graph_id)
+            .await
+            .unwrap(),
+        PegInRecoveryStatus::AwaitingSignatures {
+            deadline: deposit_height + 1
+        }
+    );
+
+    simulator.mine(1);
+    let refund_height = deposit_height + config.connector_z.num_blocks_timelock_0;
+    assert_eq!(
+        client
+            .process_peg_in_recovery_as_depositor(&graph_id)
+            .await
+            .unwrap(),
+        PegInRecoveryStatus::Stuck { refund_height }
+    );
+    client.update_events().await;
+
+    simulator.mine(refund_height - deposit_height - 1);
+    assert_eq!(
+        client.peg_in_recovery_status(&graph_id).await.unwrap(),
+        PegInRecoveryStatus::RefundAvailable
+    );
+    let refund_txid = match client.recover_peg_ins(Some(&graph_id)).await.pop() {
+        Some((_, Ok(PegInRecoveryStatus::RefundPending(txid)))) => txid,
+        status => panic!("Peg-in refund not broadcast: {status:?}"),
+    };
+    assert_eq!(
+        client.peg_in_recovery_status(&graph_id).await.unwrap(),
+        PegInRecoveryStatus::RefundPending(refund_txid)
+    );
+    client.update_events().await;
+
+    simulator.mine(1);
+    assert_eq!(
+        client.peg_in_recovery_status(&graph_id).await.unwrap(),
+        PegInRecoveryStatus::Refunded {
+            block_height: refund_height + 1
+        }
+    );
+    client.update_events().await;
+
+    let mut recovery_statuses = vec![];
+    while let Some(Some(event)) = events.next().now_or_never() {
+        if let BridgeEvent::PegInRecoveryStatusChanged {
+            graph_id: id, new, ..
+        } = event.event
+        {
+            if id == graph_id {
+                recovery_statuses.push(new);
+            }
+        }
+    }
+    assert_eq!(
+        recovery_statuses,
+        vec![
+            PegInRecoveryStatus::AwaitingSignatures {
+                deadline: deposit_height + 1
+            },
+            PegInRecoveryStatus::Stuck { refund_height },
+            PegInRecoveryStatus::RefundPending(refund_txid),
+            PegInRecoveryStatus::Refunded {
+                block_height: refund_height + 1
+            },
+        ]
+    );
+}
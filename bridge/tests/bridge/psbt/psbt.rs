@@ -0,0 +1,138 @@
+use bitcoin::{
+    absolute::LockTime,
+    hashes::Hash,
+    psbt::Psbt,
+    secp256k1::Message,
+    sighash::{Prevouts, SighashCache},
+    taproot::{self, LeafVersion},
+    Amount, OutPoint, TapLeafHash, TapSighashType, Txid,
+};
+use secp256k1::SECP256K1;
+
+use bridge::{
+    connectors::base::TaprootConnector,
+    error::{Error, PsbtError},
+    transactions::{
+        base::{BaseTransaction, Input, MIN_RELAY_FEE_PEG_IN_REFUND},
+        peg_in_refund::PegInRefundTransaction,
+        pre_signed::PreSignedTransaction,
+        psbt::{export_psbt, finalize_psbt},
+    },
+};
+
+use crate::bridge::setup::{setup_test, INITIAL_AMOUNT};
+
+fn stub_input() -> Input {
+    Input {
+        outpoint: OutPoint {
+            txid: Txid::all_zeros(),
+            vout: 0,
+        },
+        amount: Amount::from_sat(INITIAL_AMOUNT + MIN_RELAY_FEE_PEG_IN_REFUND),
+    }
+}
+
+fn sign_input_0(psbt: &mut Psbt, keypair: &bitcoin::key::Keypair) {
+    let script = psbt.inputs[0]
+        .tap_scripts
+        .values()
+        .next()
+        .unwrap()
+        .0
+        .clone();
+    let leaf_hash = TapLeafHash::from_script(&script, LeafVersion::TapScript);
+    let prev_outs = [psbt.inputs[0].witness_utxo.clone().unwrap()];
+    let sighash = SighashCache::new(&psbt.unsigned_tx)
+        .taproot_script_spend_signature_hash(
+            0,
+            &Prevouts::All(&prev_outs),
+            leaf_hash,
+            TapSighashType::All,
+        )
+        .unwrap();
+    let signature = taproot::Signature {
+        signature: SECP256K1.sign_schnorr_no_aux_rand(&Message::from(sighash), keypair),
+        sighash_type: TapSighashType::All,
+    };
+
+    psbt.inputs[0]
+        .tap_script_sigs
+        .insert((keypair.x_only_public_key().0, leaf_hash), signature);
+}
+
+#[tokio::test]
+async fn test_psbt_export_sign_and_finalize() {
+    let config = setup_test().await;
+
+    let unsigned_tx = PegInRefundTransaction::new_for_validation(
+        config.network,
+        &config.depositor_context.depositor_public_key,
+        &config.connector_z,
+        stub_input(),
+    );
+
+    let psbt = export_psbt(
+        &unsigned_tx,
+        &[config.connector_z.generate_taproot_spend_info()],
+    )
+    .unwrap();
+    assert_eq!(
+        psbt.inputs[0].witness_utxo.as_ref(),
+        Some(&unsigned_tx.prev_outs()[0])
+    );
+    assert_eq!(
+        psbt.inputs[0].tap_scripts.values().next().unwrap().0,
+        unsigned_tx.prev_scripts()[0]
+    );
+    assert!(psbt.inputs[0].tap_internal_key.is_some());
+    assert!(psbt.inputs[0]
+        .tap_key_origins
+        .contains_key(&config.depositor_context.depositor_taproot_public_key));
+
+    // Round trip through the binary encoding, as an external signer would.
+    let mut psbt = Psbt::deserialize(&psbt.serialize()).unwrap();
+    sign_input_0(&mut psbt, &config.depositor_context.depositor_keypair);
+
+    let tx = finalize_psbt(&unsigned_tx, &psbt).unwrap();
+    let expected_tx =
+        PegInRefundTransaction::new(&config.depositor_context, &config.connector_z, stub_input())
+            .finalize();
+    assert_eq!(tx, expected_tx);
+}
+
+#[tokio::test]
+async fn test_psbt_finalize_rejects_missing_and_invalid_signatures() {
+    let config = setup_test().await;
+
+    let unsigned_tx = PegInRefundTransaction::new_for_validation(
+        config.network,
+        &config.depositor_context.depositor_public_key,
+        &config.connector_z,
+        stub_input(),
+    );
+    let mut psbt = export_psbt(
+        &unsigned_tx,
+        &[config.connector_z.generate_taproot_spend_info()],
+    )
+    .unwrap();
+
+    assert!(matches!(
+        finalize_psbt(&unsigned_tx, &psbt),
+        Err(Error::Psbt(PsbtError::MissingSignature(0)))
+    ));
+
+    // A signature under the depositor key that commits to a different transaction.
+    let mut other_psbt = psbt.clone();
+    other_psbt.unsigned_tx.lock_time = LockTime::from_consensus(1);
+    sign_input_0(&mut other_psbt, &config.depositor_context.depositor_keypair);
+    psbt.inputs[0].tap_script_sigs = other_psbt.inputs[0].tap_script_sigs.clone();
+    assert!(matches!(
+        finalize_psbt(&unsigned_tx, &psbt),
+        Err(Error::Psbt(PsbtError::InvalidSignature(0)))
+    ));
+
+    assert!(matches!(
+        finalize_psbt(&unsigned_tx, &other_psbt),
+        Err(Error::Psbt(PsbtError::TransactionMismatch))
+    ));
+}
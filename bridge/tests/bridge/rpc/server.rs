@@ -0,0 +1,230 @@
+use std::time::Duration;
+
+use bitcoin::{hex::DisplayHex, Amount};
+use serde_json::{json, Value};
+use tokio::{
+    io::{AsyncReadExt, AsyncWriteExt},
+    net::{TcpListener, TcpStream},
+};
+
+use bridge::{
+    client::{
+        rpc::{
+            json_rpc::{INVALID_PARAMS, INVALID_REQUEST, METHOD_NOT_FOUND, PARSE_ERROR},
+            server::RpcServer,
+        },
+        simulator::server::LedgerSimulator,
+    },
+    constants::DestinationNetwork,
+    graphs::{
+        base::{BaseGraph, PEG_IN_FEE},
+        peg_in::PegInGraph,
+    },
+    scripts::generate_pay_to_pubkey_script_address,
+    transactions::{base::Input, pre_signed::PreSignedTransaction},
+};
+
+use crate::bridge::setup::{setup_test, setup_test_with_esplora_url, INITIAL_AMOUNT};
+
+const SYNC_INTERVAL: Duration = Duration::from_secs(60);
+
+async fn setup_server() -> RpcServer {
+    let config = setup_test().await;
+    RpcServer::new(config.client_0, DestinationNetwork::Local, SYNC_INTERVAL)
+}
+
+fn error_code(response: &Value) -> i64 { response["error"]["code"].as_i64().unwrap() }
+
+#[tokio::test]
+async fn test_rpc_server_rejects_invalid_requests() {
+    let mut server = setup_server().await;
+
+    let response = server.handle_body("{").await.unwrap();
+    assert_eq!(error_code(&response), PARSE_ERROR);
+    assert_eq!(response["id"], Value::Null);
+
+    let response = server.handle_body("[]").await.unwrap();
+    assert_eq!(error_code(&response), INVALID_REQUEST);
+
+    let response = server
+        .handle_body(r#"{"jsonrpc":"1.0","method":"get_unused_peg_in_graphs","id":1}"#)
+        .await
+        .unwrap();
+    assert_eq!(error_code(&response), INVALID_REQUEST);
+
+    let response = server
+        .handle_body(r#"{"jsonrpc":"2.0","method":"unknown","id":2}"#)
+        .await
+        .unwrap();
+    assert_eq!(error_code(&response), METHOD_NOT_FOUND);
+    assert_eq!(response["id"], json!(2));
+
+    // Notifications are executed but never answered.
+    let response = server
+        .handle_body(r#"{"jsonrpc":"2.0","method":"unknown"}"#)
+        .await;
+    assert!(response.is_none());
+}
+
+#[tokio::test]
+async fn test_rpc_server_validates_params() {
+    let mut server = setup_server().await;
+
+    let response = server
+        .handle_body(r#"{"jsonrpc":"2.0","method":"get_depositor_status","id":1}"#)
+        .await
+        .unwrap();
+    assert_eq!(error_code(&response), INVALID_PARAMS);
+    assert_eq!(
+        response["error"]["message"],
+        "Missing argument: depositor_public_key"
+    );
+
+    let response = server
+        .handle_body(
+            r#"{"jsonrpc":"2.0","method":"get_depositor_status","params":{"depositor_public_key":"02"},"id":1}"#,
+        )
+        .await
+        .unwrap();
+    assert_eq!(error_code(&response), INVALID_PARAMS);
+    assert_eq!(
+        response["error"]["message"],
+        "Invalid public key. Use bitcoin public key format."
+    );
+
+    let response = server
+        .handle_body(r#"{"jsonrpc":"2.0","method":"broadcast","params":["00"],"id":1}"#)
+        .await
+        .unwrap();
+    assert_eq!(error_code(&response), INVALID_PARAMS);
+
+    let response = server
+        .handle_body(
+            r#"{"jsonrpc":"2.0","method":"broadcast","params":{"graph_id":"not a graph id"},"id":1}"#,
+        )
+        .await
+        .unwrap();
+    assert_eq!(error_code(&response), INVALID_PARAMS);
+}
+
+#[tokio::test]
+async fn test_rpc_server_creates_peg_in_graph_with_depositor_signatures() {
+    let simulator = LedgerSimulator::start().await.unwrap();
+    let config = setup_test_with_esplora_url(simulator.url()).await;
+    let amount = Amount::from_sat(INITIAL_AMOUNT + PEG_IN_FEE);
+    let address = generate_pay_to_pubkey_script_address(
+        config.depositor_context.network,
+        &config.depositor_context.depositor_public_key,
+    );
+    let outpoint = simulator.fund(&address, amount);
+    simulator.mine(1);
+
+    // The depositor signs the transactions of its peg-in graph on its own.
+    let peg_in_graph = PegInGraph::new(
+        &config.depositor_context,
+        Input { outpoint, amount },
+        &config.depositor_evm_address,
+        &config.protocol_parameters,
+    );
+    let signature = |tx: &bitcoin::Transaction| tx.input[0].witness[0].to_lower_hex_string();
+    let params = json!({
+        "depositor_public_key": config.depositor_context.depositor_public_key.to_string(),
+        "destination_chain_address": config.depositor_evm_address,
+        "outpoint": outpoint.to_string(),
+        "sats": amount.to_sat(),
+        "deposit": signature(peg_in_graph.peg_in_deposit_transaction.tx()),
+        "confirm": signature(peg_in_graph.peg_in_confirm_transaction.tx()),
+        "refund": signature(peg_in_graph.peg_in_refund_transaction.tx()),
+    });
+
+    let mut server = RpcServer::new(config.client_0, DestinationNetwork::Local, SYNC_INTERVAL);
+    let request = json!({
+        "jsonrpc": "2.0",
+        "method": "create_peg_in_graph_with_depositor_signatures",
+        "params": params,
+        "id": 1,
+    });
+    let response = server.handle_body(&request.to_string()).await.unwrap();
+    assert_eq!(response["result"]["graph_id"], peg_in_graph.id().as_str());
+    // The ledger verified the deposit signature.
+    let deposit_txid = peg_in_graph.peg_in_deposit_transaction.tx().compute_txid();
+    assert!(simulator.ledger().mempool().contains(&deposit_txid));
+
+    // Signatures are hex encoded, not raw characters.
+    let mut params = params;
+    params["deposit"] = Value::from("not a signature");
+    let request = json!({
+        "jsonrpc": "2.0",
+        "method": "create_peg_in_graph_with_depositor_signatures",
+        "params": params,
+        "id": 2,
+    });
+    let response = server.handle_body(&request.to_string()).await.unwrap();
+    assert_eq!(error_code(&response), INVALID_PARAMS);
+    assert_eq!(
+        response["error"]["message"],
+        "Invalid format of ecdsa signature."
+    );
+}
+
+#[tokio::test]
+async fn test_rpc_server_batch() {
+    let mut server = setup_server().await;
+
+    let response = server
+        .handle_body(
+            r#"[
+                {"jsonrpc":"2.0","method":"get_unused_peg_in_graphs","id":1},
+                {"jsonrpc":"2.0","method":"get_unused_peg_in_graphs"},
+                {"jsonrpc":"2.0","method":"unknown","id":3}
+            ]"#,
+        )
+        .await
+        .unwrap();
+
+    let responses = response.as_array().unwrap();
+    assert_eq!(responses.len(), 2);
+    assert!(responses[0]["result"].is_array());
+    assert_eq!(responses[0]["id"], json!(1));
+    assert_eq!(error_code(&responses[1]), METHOD_NOT_FOUND);
+}
+
+#[tokio::test]
+async fn test_rpc_server_over_http() {
+    let server = setup_server().await;
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let address = listener.local_addr().unwrap();
+
+    let request = async {
+        let body = r#"{"jsonrpc":"2.0","method":"get_unused_peg_in_graphs","id":"query"}"#;
+        let mut stream = TcpStream::connect(address).await.unwrap();
+        stream
+            .write_all(
+                format!(
+                    "POST / HTTP/1.1\r\nHost: {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\n\r\n{}",
+                    address,
+                    body.len(),
+                    body
+                )
+                .as_bytes(),
+            )
+            .await
+            .unwrap();
+
+        let mut response = String::new();
+        stream.read_to_string(&mut response).await.unwrap();
+        response
+    };
+
+    let response = tokio::select! {
+        _ = server.serve(listener) => unreachable!(),
+        response = request => response,
+    };
+
+    assert!(response.starts_with("HTTP/1.1 200 OK\r\n"));
+    let (_, body) = response.split_once("\r\n\r\n").unwrap();
+    let body: Value = serde_json::from_str(body).unwrap();
+    assert_eq!(body["jsonrpc"], "2.0");
+    assert_eq!(body["id"], "query");
+    assert!(body["result"].is_array());
+}
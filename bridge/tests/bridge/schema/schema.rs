@@ -0,0 +1,175 @@
+use bitcoin::{Amount, Network};
+use bridge::{
+    client::{
+        client::{BitVMClientPrivateData, BitVMClientPublicData},
+        data_store::changeset::{Change, Changeset},
+        schema::{
+            current_version, deserialize_versioned, migrate, serialize_versioned, SchemaId,
+            VersionedData, LEGACY_SCHEMA_VERSION,
+        },
+    },
+    error::SchemaError,
+    graphs::base::{BaseGraph, PEG_OUT_FEE},
+    parameters::ProtocolParameters,
+};
+
+use crate::bridge::{
+    helper::create_peg_in_graph,
+    setup::{setup_test, INITIAL_AMOUNT},
+};
+
+// Snapshots of the data written before it was versioned, holding a peg-in graph of the test
+// depositor with its unsigned transactions, as written before the protocol parameters were.
+const CLIENT_DATA_V1: &str = include_str!("fixtures/client_data_v1.json");
+const CHANGESET_V1: &str = include_str!("fixtures/changeset_v1.json");
+const PRIVATE_DATA_V1: &str = include_str!("fixtures/private_data_v1.json");
+// The same graph, with a graph version no client wrote.
+const CLIENT_DATA_GRAPH_V0_0: &str = include_str!("fixtures/client_data_graph_v0_0.json");
+const CLIENT_DATA_V99: &str = include_str!("fixtures/client_data_v99.json");
+
+const FIXTURE_GRAPH_ID: &str = "A65523692F94D87BCE72FE547ED56CEF7316205FB5D413CBB2D0AB6363717869";
+
+#[tokio::test]
+async fn test_load_fixtures() {
+    let config = setup_test().await;
+
+    let data = deserialize_versioned::<BitVMClientPublicData>(
+        SchemaId::ClientData,
+        CLIENT_DATA_V1.as_bytes(),
+    )
+    .unwrap();
+    assert_eq!(data.version, 7);
+    assert!(data.peg_out_graphs.is_empty());
+    let [peg_in_graph] = data.peg_in_graphs.as_slice() else {
+        panic!("Expected a single peg-in graph");
+    };
+    assert_eq!(peg_in_graph.id(), FIXTURE_GRAPH_ID);
+    assert_eq!(
+        peg_in_graph.depositor_public_key,
+        config.depositor_context.depositor_public_key
+    );
+    assert_eq!(
+        peg_in_graph.depositor_evm_address,
+        config.depositor_evm_address
+    );
+    // Migrated to version 2, with the parameters of the regtest graph it was created with.
+    assert_eq!(
+        *peg_in_graph.protocol_parameters(),
+        ProtocolParameters::for_network(Network::Regtest)
+    );
+    assert!(peg_in_graph.validate());
+    let migrated_data = migrate(
+        SchemaId::ClientData,
+        LEGACY_SCHEMA_VERSION,
+        serde_json::from_str(CLIENT_DATA_V1).unwrap(),
+    )
+    .unwrap();
+    let migrated_graph = &migrated_data["peg_in_graphs"][0];
+    assert_eq!(migrated_graph["legacy_id"], true);
+    assert_eq!(
+        migrated_graph["protocol_parameters"]["connector_z_timelock_0"],
+        ProtocolParameters::for_network(Network::Regtest).connector_z_timelock_0
+    );
+
+    // Written back as the current version, it is loaded as is.
+    assert_eq!(
+        current_version(SchemaId::ClientData),
+        LEGACY_SCHEMA_VERSION + 1
+    );
+    let content = serialize_versioned(SchemaId::ClientData, &data);
+    let reloaded_data =
+        deserialize_versioned::<BitVMClientPublicData>(SchemaId::ClientData, content.as_bytes())
+            .unwrap();
+    assert!(reloaded_data == data);
+    assert!(reloaded_data.peg_in_graphs[0].validate());
+
+    let changeset =
+        deserialize_versioned::<Changeset>(SchemaId::Changeset, CHANGESET_V1.as_bytes()).unwrap();
+    assert!(matches!(
+        changeset.changes.as_slice(),
+        [Change::PegInGraphAdded(graph)] if graph == peg_in_graph
+    ));
+    // The same changeset, wrapped as version 1.
+    let content = serde_json::to_string(&VersionedData {
+        schema: SchemaId::Changeset,
+        version: LEGACY_SCHEMA_VERSION,
+        data: serde_json::from_str(CHANGESET_V1).unwrap(),
+    })
+    .unwrap();
+    let changeset =
+        deserialize_versioned::<Changeset>(SchemaId::Changeset, content.as_bytes()).unwrap();
+    assert!(matches!(
+        changeset.changes.as_slice(),
+        [Change::PegInGraphAdded(graph)] if graph == peg_in_graph && graph.validate()
+    ));
+
+    let private_data = deserialize_versioned::<BitVMClientPrivateData>(
+        SchemaId::PrivateData,
+        PRIVATE_DATA_V1.as_bytes(),
+    )
+    .unwrap();
+    assert!(private_data.secret_nonces.is_empty());
+    assert!(private_data.reserved_outpoints.is_empty());
+}
+
+#[test]
+fn test_load_rejects_unknown_versions_and_schema() {
+    assert!(matches!(
+        deserialize_versioned::<BitVMClientPublicData>(
+            SchemaId::ClientData,
+            CLIENT_DATA_V99.as_bytes()
+        ),
+        Err(SchemaError::UnsupportedVersion {
+            schema: SchemaId::ClientData,
+            version: 99
+        })
+    ));
+
+    assert!(matches!(
+        deserialize_versioned::<Changeset>(SchemaId::Changeset, CLIENT_DATA_V99.as_bytes()),
+        Err(SchemaError::UnexpectedSchema {
+            expected: SchemaId::Changeset,
+            found: SchemaId::ClientData
+        })
+    ));
+
+    assert!(matches!(
+        deserialize_versioned::<BitVMClientPublicData>(
+            SchemaId::ClientData,
+            CLIENT_DATA_GRAPH_V0_0.as_bytes()
+        ),
+        Err(SchemaError::UnsupportedGraphVersion { graph_id, version })
+            if graph_id == FIXTURE_GRAPH_ID && version == "0.0"
+    ));
+}
+
+#[tokio::test]
+async fn test_round_trip_client_data_with_graphs() {
+    let config = setup_test().await;
+    let data = BitVMClientPublicData {
+        version: 3,
+        peg_in_graphs: vec![create_peg_in_graph(
+            &config,
+            Amount::from_sat(INITIAL_AMOUNT + PEG_OUT_FEE),
+        )],
+        peg_out_graphs: vec![],
+    };
+
+    // Data written before it was versioned.
+    let legacy_content = serde_json::to_vec(&data).unwrap();
+    let loaded_data =
+        deserialize_versioned::<BitVMClientPublicData>(SchemaId::ClientData, &legacy_content)
+            .unwrap();
+    assert!(loaded_data == data);
+
+    let content = serialize_versioned(SchemaId::ClientData, &data);
+    let versioned_data: serde_json::Value = serde_json::from_str(&content).unwrap();
+    assert_eq!(
+        versioned_data["version"],
+        current_version(SchemaId::ClientData)
+    );
+    let loaded_data =
+        deserialize_versioned::<BitVMClientPublicData>(SchemaId::ClientData, content.as_bytes())
+            .unwrap();
+    assert!(loaded_data == data);
+}
@@ -33,6 +33,7 @@ async fn test_peg_in_graph_serialization() {
         &config.depositor_context,
         Input { outpoint, amount },
         &config.depositor_evm_address,
+        &config.protocol_parameters,
     );
 
     let json = serialize(&peg_in_graph);
@@ -44,6 +44,7 @@ async fn test_peg_out_graph_serialization() {
         &config.depositor_context,
         Input { outpoint, amount },
         &config.depositor_evm_address,
+        &config.protocol_parameters,
     );
 
     let kick_off_outpoint =
@@ -57,6 +58,7 @@ async fn test_peg_out_graph_serialization() {
             amount: kick_off_amount,
         },
         &config.commitment_secrets,
+        &config.protocol_parameters,
     );
 
     let json = serialize(&peg_out_graph);
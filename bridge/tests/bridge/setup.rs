@@ -23,6 +23,7 @@ use bridge::{
         base::generate_keys_from_secret, depositor::DepositorContext, operator::OperatorContext,
         verifier::VerifierContext, withdrawer::WithdrawerContext,
     },
+    parameters::ProtocolParameters,
     serialization::serialize,
     superblock::{SUPERBLOCK_HASH_MESSAGE_LENGTH, SUPERBLOCK_MESSAGE_LENGTH},
     transactions::assert_transactions::utils::{
@@ -44,6 +45,8 @@ const VERIFIER_0_SECRET: &str = "ee0817eac0c13aa8ee2dd3256304041f09f0499d1089b56
 const VERIFIER_1_SECRET: &str = "fc294c70faf210d4d0807ea7a3dba8f7e41700d90c119e1ae82a0687d89d297f";
 const DEPOSITOR_SECRET: &str = "b8f17ea979be24199e7c3fec71ee88914d92fd4ca508443f765d56ce024ef1d7";
 const WITHDRAWER_SECRET: &str = "fffd54f6d8f8ad470cb507fd4b6e9b3ea26b4221a4900cc5ad5916ce67c02f1e";
+const OPERATOR_COMMITMENT_SEED: &str =
+    "9c4f1ab2d7e03f6a58c1e2b7940d6f3a2e8b5c17d09a4f6e3b2c1d0e9f8a7b6c";
 
 const DEPOSITOR_EVM_ADDRESS: &str = "0x70997970C51812dc3A010C7d01b50e0d17dc79C8"; // l2 local test network account 1
 const WITHDRAWER_EVM_ADDRESS: &str = "0x3C44CdDdB6a900fa2b585dd299e03d12FA4293BC"; // l2 local test network account 2
@@ -60,6 +63,7 @@ pub struct SetupConfig {
     pub verifier_0_context: VerifierContext,
     pub verifier_1_context: VerifierContext,
     pub withdrawer_context: WithdrawerContext,
+    pub protocol_parameters: ProtocolParameters,
     pub connector_a: ConnectorA,
     pub connector_b: ConnectorB,
     pub connector_d: ConnectorD,
@@ -88,6 +92,7 @@ pub struct SetupConfigFull {
     pub verifier_0_context: VerifierContext,
     pub verifier_1_context: VerifierContext,
     pub withdrawer_context: WithdrawerContext,
+    pub protocol_parameters: ProtocolParameters,
     pub connector_a: ConnectorA,
     pub connector_b: ConnectorB,
     pub connector_c: ConnectorC,
@@ -164,6 +169,7 @@ pub async fn setup_test_full() -> SetupConfigFull {
         verifier_0_context: config.verifier_0_context,
         verifier_1_context: config.verifier_1_context,
         withdrawer_context: config.withdrawer_context,
+        protocol_parameters: config.protocol_parameters,
         connector_a: config.connector_a,
         connector_b: config.connector_b,
         connector_c: connector_c,
@@ -188,6 +194,12 @@ pub async fn setup_test_full() -> SetupConfigFull {
 }
 
 pub async fn setup_test() -> SetupConfig {
+    setup_test_with_esplora_url(get_esplora_url(Network::Regtest)).await
+}
+
+/// Same as `setup_test`, with the clients querying the Esplora API at `esplora_url`, e.g. the one
+/// of a `LedgerSimulator`.
+pub async fn setup_test_with_esplora_url(esplora_url: &str) -> SetupConfig {
     let source_network = Network::Regtest;
     let destination_network = DestinationNetwork::Local;
 
@@ -210,38 +222,46 @@ pub async fn setup_test() -> SetupConfig {
     let withdrawer_context =
         WithdrawerContext::new(source_network, WITHDRAWER_SECRET, &n_of_n_public_keys);
 
+    let protocol_parameters = ProtocolParameters::for_network(source_network);
+
     let valid_proof = get_valid_proof();
     let invalid_proof = invalidate_proof(&valid_proof);
 
     let client_0 = BitVMClient::new(
-        Some(get_esplora_url(source_network)),
+        Some(esplora_url),
         source_network,
         destination_network,
         Some(get_chain_adaptor(destination_network, None, None)),
         &n_of_n_public_keys,
         Some(DEPOSITOR_SECRET),
         Some(OPERATOR_SECRET),
+        Some(OPERATOR_COMMITMENT_SEED),
         Some(VERIFIER_0_SECRET),
         Some(WITHDRAWER_SECRET),
         Some("test_client_0"),
         Some(valid_proof.vk.clone()),
+        Some(protocol_parameters),
     )
-    .await;
+    .await
+    .unwrap();
 
     let client_1 = BitVMClient::new(
-        Some(get_esplora_url(source_network)),
+        Some(esplora_url),
         source_network,
         destination_network,
         Some(get_chain_adaptor(destination_network, None, None)),
         &n_of_n_public_keys,
         Some(DEPOSITOR_SECRET),
         Some(OPERATOR_SECRET),
+        Some(OPERATOR_COMMITMENT_SEED),
         Some(VERIFIER_1_SECRET),
         Some(WITHDRAWER_SECRET),
         Some("test_client_1"),
         Some(valid_proof.vk.clone()),
+        Some(protocol_parameters),
     )
-    .await;
+    .await
+    .unwrap();
 
     let connector_a = ConnectorA::new(
         source_network,
@@ -263,6 +283,7 @@ pub async fn setup_test() -> SetupConfig {
                 ),
             ),
         ]),
+        &protocol_parameters,
     );
     let connector_d = ConnectorD::new(source_network, &operator_context.n_of_n_taproot_public_key);
 
@@ -279,6 +300,7 @@ pub async fn setup_test() -> SetupConfig {
         DEPOSITOR_EVM_ADDRESS,
         &depositor_context.depositor_taproot_public_key,
         &operator_context.n_of_n_taproot_public_key,
+        &protocol_parameters,
     );
     let connector_0 = Connector0::new(source_network, &operator_context.n_of_n_taproot_public_key);
 
@@ -298,6 +320,7 @@ pub async fn setup_test() -> SetupConfig {
                 ),
             ),
         ]),
+        &protocol_parameters,
     );
     let connector_2 = Connector2::new(
         source_network,
@@ -308,8 +331,16 @@ pub async fn setup_test() -> SetupConfig {
             WinternitzPublicKey::from(&commitment_secrets[&CommitmentMessageId::StartTime]),
         )]),
     );
-    let connector_3 = Connector3::new(source_network, &operator_context.operator_public_key);
-    let connector_4 = Connector4::new(source_network, &operator_context.operator_public_key);
+    let connector_3 = Connector3::new(
+        source_network,
+        &operator_context.operator_public_key,
+        &protocol_parameters,
+    );
+    let connector_4 = Connector4::new(
+        source_network,
+        &operator_context.operator_public_key,
+        &protocol_parameters,
+    );
     let connector_5 = Connector5::new(source_network, &operator_context.n_of_n_taproot_public_key);
     let connector_6 = Connector6::new(
         source_network,
@@ -339,6 +370,7 @@ pub async fn setup_test() -> SetupConfig {
         verifier_0_context,
         verifier_1_context,
         withdrawer_context,
+        protocol_parameters,
         connector_a,
         connector_b,
         connector_d,
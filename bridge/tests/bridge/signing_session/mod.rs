@@ -0,0 +1 @@
+pub mod signing_session;
@@ -0,0 +1,348 @@
+use std::{
+    collections::BTreeSet,
+    str::FromStr,
+    time::{Duration, Instant},
+};
+
+use bitcoin::{hashes::Hash, key::Keypair, Amount, OutPoint, PublicKey, Txid};
+use bridge::{
+    client::signing_session::{
+        coordinator::{SessionRound, SigningCoordinator},
+        messages::{InputNonce, InputPartialSignature, SessionMessage},
+        peer::run_verifier_session,
+        transport::{connect, connect_in_process, run_coordinator, serve_coordinator},
+    },
+    graphs::base::{BaseGraph, GraphId, PEG_IN_FEE},
+    transactions::{
+        base::Input,
+        pre_signed::PreSignedTransaction,
+        pre_signed_musig2::{get_nonce_message, PreSignedMusig2Transaction},
+        signing_musig2::generate_nonce,
+    },
+};
+use musig2::secp::{MaybeScalar, Scalar};
+use tokio::{net::TcpListener, sync::mpsc};
+
+use crate::bridge::setup::{setup_test, SetupConfig, INITIAL_AMOUNT};
+
+const ROUND_TIMEOUT: Duration = Duration::from_secs(60);
+
+#[tokio::test]
+async fn test_signing_session_in_process() {
+    let (mut config, graph_id) = setup_and_create_peg_in_graph().await;
+
+    let mut coordinator = SigningCoordinator::new(ROUND_TIMEOUT);
+    coordinator.open_session(
+        config.client_0.data().graph(&graph_id).unwrap(),
+        &config.verifier_0_context.n_of_n_public_keys,
+        Instant::now(),
+    );
+    let (incoming_sender, incoming) = mpsc::channel(16);
+    let coordinator_task = tokio::spawn(run_coordinator(coordinator, incoming));
+
+    let (sender_0, mut receiver_0) = connect_in_process(incoming_sender.clone());
+    let (sender_1, mut receiver_1) = connect_in_process(incoming_sender);
+    let (result_0, result_1) = tokio::join!(
+        run_verifier_session(
+            &mut config.client_0,
+            &graph_id,
+            &sender_0,
+            &mut receiver_0,
+            ROUND_TIMEOUT
+        ),
+        run_verifier_session(
+            &mut config.client_1,
+            &graph_id,
+            &sender_1,
+            &mut receiver_1,
+            ROUND_TIMEOUT
+        ),
+    );
+    result_0.unwrap();
+    result_1.unwrap();
+
+    let coordinator = coordinator_task.await.unwrap();
+    assert_eq!(coordinator.round(&graph_id), Some(SessionRound::Complete));
+    assert_peg_in_confirm_signed(&config, &graph_id);
+}
+
+#[tokio::test]
+async fn test_signing_session_over_tcp() {
+    let (mut config, graph_id) = setup_and_create_peg_in_graph().await;
+
+    let mut coordinator = SigningCoordinator::new(ROUND_TIMEOUT);
+    coordinator.open_session(
+        config.client_0.data().graph(&graph_id).unwrap(),
+        &config.verifier_0_context.n_of_n_public_keys,
+        Instant::now(),
+    );
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let address = listener.local_addr().unwrap();
+    let coordinator_task = tokio::spawn(serve_coordinator(listener, coordinator));
+
+    let (sender_0, mut receiver_0) = connect(address).await.unwrap();
+    let (sender_1, mut receiver_1) = connect(address).await.unwrap();
+    let (result_0, result_1) = tokio::join!(
+        run_verifier_session(
+            &mut config.client_0,
+            &graph_id,
+            &sender_0,
+            &mut receiver_0,
+            ROUND_TIMEOUT
+        ),
+        run_verifier_session(
+            &mut config.client_1,
+            &graph_id,
+            &sender_1,
+            &mut receiver_1,
+            ROUND_TIMEOUT
+        ),
+    );
+    result_0.unwrap();
+    result_1.unwrap();
+
+    // Disconnect so the coordinator stops waiting for the verifiers.
+    drop((sender_0, sender_1, receiver_0, receiver_1));
+    let coordinator = coordinator_task.await.unwrap();
+    assert_eq!(coordinator.round(&graph_id), Some(SessionRound::Complete));
+    assert_peg_in_confirm_signed(&config, &graph_id);
+}
+
+#[tokio::test]
+async fn test_signing_session_rejects_invalid_nonces_and_times_out() {
+    let config = setup_test().await;
+    let verifier_0 = config.verifier_0_context.verifier_public_key;
+    let verifier_1 = config.verifier_1_context.verifier_public_key;
+    let graph_id: GraphId = "GRAPH".to_string();
+    let inputs = BTreeSet::from([(Txid::all_zeros(), 0)]);
+
+    let now = Instant::now();
+    let mut coordinator = SigningCoordinator::new(ROUND_TIMEOUT);
+    coordinator.open_session_for_inputs(graph_id.clone(), &[verifier_0, verifier_1], inputs, now);
+
+    // Nonce signed by another verifier.
+    let secret_nonce = generate_nonce();
+    let nonce = InputNonce {
+        txid: Txid::all_zeros(),
+        input_index: 0,
+        verifier: verifier_0,
+        nonce: secret_nonce.public_nonce(),
+        signature: config
+            .verifier_1_context
+            .verifier_keypair
+            .sign_schnorr(get_nonce_message(&secret_nonce.public_nonce())),
+    };
+    let outgoing = coordinator.handle_message(
+        SessionMessage::Nonces {
+            graph_id: graph_id.clone(),
+            verifier: verifier_0,
+            nonces: vec![nonce.clone()],
+        },
+        now,
+    );
+    assert_eq!(outgoing.len(), 1);
+    assert_eq!(outgoing[0].to, verifier_0);
+    assert!(matches!(
+        outgoing[0].message,
+        SessionMessage::Rejected { .. }
+    ));
+
+    // The same input twice.
+    let outgoing = coordinator.handle_message(
+        SessionMessage::Nonces {
+            graph_id: graph_id.clone(),
+            verifier: verifier_0,
+            nonces: vec![nonce.clone(), nonce],
+        },
+        now,
+    );
+    assert!(matches!(
+        outgoing[0].message,
+        SessionMessage::Rejected { .. }
+    ));
+    assert_eq!(coordinator.round(&graph_id), Some(SessionRound::Nonces));
+
+    assert!(coordinator
+        .check_timeouts(now + ROUND_TIMEOUT - Duration::from_secs(1))
+        .is_empty());
+    let outgoing = coordinator.check_timeouts(now + ROUND_TIMEOUT);
+    assert_eq!(outgoing.len(), 2);
+    assert!(outgoing
+        .iter()
+        .all(|outgoing| matches!(outgoing.message, SessionMessage::Aborted { .. })));
+    assert_eq!(coordinator.round(&graph_id), Some(SessionRound::Aborted));
+    assert!(coordinator.is_finished());
+}
+
+#[tokio::test]
+async fn test_signing_session_rejects_forged_verifier_messages() {
+    let config = setup_test().await;
+    let keypair_0 = config.verifier_0_context.verifier_keypair;
+    let keypair_1 = config.verifier_1_context.verifier_keypair;
+    let verifier_0 = config.verifier_0_context.verifier_public_key;
+    let verifier_1 = config.verifier_1_context.verifier_public_key;
+    let graph_id: GraphId = "GRAPH".to_string();
+    let inputs = BTreeSet::from([(Txid::all_zeros(), 0)]);
+
+    let now = Instant::now();
+    let mut coordinator = SigningCoordinator::new(ROUND_TIMEOUT);
+    coordinator.open_session_for_inputs(graph_id.clone(), &[verifier_0, verifier_1], inputs, now);
+
+    // Verifier 1 joining in the name of verifier 0.
+    let forged_join = impersonate(SessionMessage::join(&graph_id, &keypair_1), verifier_0);
+    let outgoing = coordinator.handle_message(forged_join, now);
+    assert_eq!(outgoing.len(), 1);
+    assert!(matches!(
+        outgoing[0].message,
+        SessionMessage::Rejected { .. }
+    ));
+
+    for (keypair, verifier) in [(keypair_0, verifier_0), (keypair_1, verifier_1)] {
+        coordinator.handle_message(
+            SessionMessage::Nonces {
+                graph_id: graph_id.clone(),
+                verifier,
+                nonces: vec![signed_nonce(&keypair, verifier)],
+            },
+            now,
+        );
+    }
+    assert_eq!(coordinator.round(&graph_id), Some(SessionRound::Signatures));
+
+    // Verifier 1 submitting partial signatures in the name of verifier 0.
+    let forged_signatures = impersonate(
+        SessionMessage::partial_signatures(
+            &graph_id,
+            vec![partial_signature(verifier_0, Scalar::one().into())],
+            &keypair_1,
+        ),
+        verifier_0,
+    );
+    let outgoing = coordinator.handle_message(forged_signatures, now);
+    assert_eq!(outgoing.len(), 1);
+    assert!(matches!(
+        outgoing[0].message,
+        SessionMessage::Rejected { .. }
+    ));
+    assert_eq!(coordinator.round(&graph_id), Some(SessionRound::Signatures));
+
+    let mut outgoing = vec![];
+    for (keypair, verifier) in [(keypair_0, verifier_0), (keypair_1, verifier_1)] {
+        outgoing = coordinator.handle_message(
+            SessionMessage::partial_signatures(
+                &graph_id,
+                vec![partial_signature(verifier, MaybeScalar::Zero)],
+                &keypair,
+            ),
+            now,
+        );
+    }
+    assert_eq!(coordinator.round(&graph_id), Some(SessionRound::Complete));
+    assert_eq!(outgoing.len(), 2);
+    let SessionMessage::Completed { signatures, .. } = &outgoing[0].message else {
+        panic!(
+            "Expected the session to complete, got {:?}",
+            outgoing[0].message
+        );
+    };
+    assert_eq!(
+        signatures,
+        &vec![
+            partial_signature(verifier_0, MaybeScalar::Zero),
+            partial_signature(verifier_1, MaybeScalar::Zero),
+        ]
+    );
+}
+
+fn signed_nonce(keypair: &Keypair, verifier: PublicKey) -> InputNonce {
+    let secret_nonce = generate_nonce();
+    InputNonce {
+        txid: Txid::all_zeros(),
+        input_index: 0,
+        verifier,
+        nonce: secret_nonce.public_nonce(),
+        signature: keypair.sign_schnorr(get_nonce_message(&secret_nonce.public_nonce())),
+    }
+}
+
+fn partial_signature(verifier: PublicKey, signature: MaybeScalar) -> InputPartialSignature {
+    InputPartialSignature {
+        txid: Txid::all_zeros(),
+        input_index: 0,
+        verifier,
+        signature,
+    }
+}
+
+// Claims `message` comes from `verifier`, keeping the signature of its actual sender.
+fn impersonate(message: SessionMessage, verifier: PublicKey) -> SessionMessage {
+    match message {
+        SessionMessage::Join {
+            graph_id,
+            sender_signature,
+            ..
+        } => SessionMessage::Join {
+            graph_id,
+            verifier,
+            sender_signature,
+        },
+        SessionMessage::PartialSignatures {
+            graph_id,
+            signatures,
+            sender_signature,
+            ..
+        } => SessionMessage::PartialSignatures {
+            graph_id,
+            verifier,
+            signatures,
+            sender_signature,
+        },
+        message => message,
+    }
+}
+
+async fn setup_and_create_peg_in_graph() -> (SetupConfig, GraphId) {
+    let mut config = setup_test().await;
+
+    let input = Input {
+        outpoint: OutPoint {
+            txid: Txid::from_str(
+                "0e6719ac074b0e3cac76d057643506faa1c266b322aa9cf4c6f635fe63b14327",
+            )
+            .unwrap(),
+            vout: 0,
+        },
+        amount: Amount::from_sat(INITIAL_AMOUNT + PEG_IN_FEE),
+    };
+    let graph_id = config
+        .client_0
+        .create_peg_in_graph(input, &config.depositor_evm_address)
+        .await;
+    let data = config.client_0.data().clone();
+    config.client_1.merge_data(data).unwrap();
+
+    (config, graph_id)
+}
+
+fn assert_peg_in_confirm_signed(config: &SetupConfig, graph_id: &GraphId) {
+    let confirm_txs: Vec<_> = [&config.client_0, &config.client_1]
+        .into_iter()
+        .map(|client| {
+            client
+                .data()
+                .peg_in_graphs
+                .iter()
+                .find(|graph| graph.id() == graph_id)
+                .unwrap()
+                .peg_in_confirm_transaction
+                .clone()
+        })
+        .collect();
+
+    for confirm_tx in &confirm_txs {
+        assert_eq!(confirm_tx.musig2_signatures()[&0].len(), 2);
+        // Depositor and n-of-n signatures, leaf script and control block.
+        assert!(confirm_tx.tx().input[0].witness.len() >= 4);
+    }
+    assert_eq!(confirm_txs[0].tx(), confirm_txs[1].tx());
+}
@@ -0,0 +1,613 @@
+use bitcoin::{
+    absolute, transaction::Version, Amount, OutPoint, ScriptBuf, Sequence, Transaction, TxIn,
+    Witness,
+};
+use bridge::{
+    client::{
+        chain::chain::PegOutEvent,
+        simulator::{ledger::Ledger, server::LedgerSimulator},
+    },
+    commitments::CommitmentMessageId,
+    error::{Error, GraphError, LedgerError, TransactionError},
+    graphs::{
+        base::PEG_OUT_FEE, challenge_crowdfunding::ChallengeCrowdfundingStatus, peg_in::PegInGraph,
+        peg_out::PegOutGraph,
+    },
+    scripts::{
+        generate_p2pkh_address, generate_pay_to_pubkey_script,
+        generate_pay_to_pubkey_script_address, generate_timelock_script,
+    },
+    superblock::get_start_time_block_number,
+    transactions::base::{
+        BaseTransaction, Input, InputWithScript, MIN_RELAY_FEE_PEG_IN_CONFIRM,
+        MIN_RELAY_FEE_PEG_IN_DEPOSIT, MIN_RELAY_FEE_PEG_IN_REFUND, MIN_RELAY_FEE_PEG_OUT,
+    },
+};
+use esplora_client::AsyncClient;
+
+use crate::bridge::{
+    helper::{create_funded_peg_in_graph, sign_graph},
+    setup::{setup_test_with_esplora_url, SetupConfig, INITIAL_AMOUNT},
+};
+
+#[tokio::test]
+async fn test_simulator_peg_in_success() {
+    let simulator = LedgerSimulator::start().await.unwrap();
+    let config = setup_test_with_esplora_url(simulator.url()).await;
+    let esplora = &config.client_0.esplora;
+    let mut peg_in_graph = create_peg_in_graph(
+        &config,
+        &simulator,
+        INITIAL_AMOUNT + MIN_RELAY_FEE_PEG_IN_DEPOSIT + MIN_RELAY_FEE_PEG_IN_CONFIRM,
+    );
+
+    // peg-in deposit
+    let deposit_tx = peg_in_graph.deposit(esplora).await.unwrap();
+    assert!(esplora.broadcast(&deposit_tx).await.is_ok());
+    assert!(matches!(
+        peg_in_graph.confirm(esplora).await,
+        Err(Error::Graph(GraphError::PrecedingTxNotConfirmed(_)))
+    ));
+    simulator.mine(1);
+
+    // peg-in confirm
+    let confirm_tx = peg_in_graph.confirm(esplora).await.unwrap();
+    let confirm_txid = confirm_tx.compute_txid();
+    assert!(esplora.broadcast(&confirm_tx).await.is_ok());
+    assert!(
+        !esplora
+            .get_tx_status(&confirm_txid)
+            .await
+            .unwrap()
+            .confirmed
+    );
+    simulator.mine(1);
+
+    let confirm_status = esplora.get_tx_status(&confirm_txid).await.unwrap();
+    assert!(confirm_status.confirmed);
+    assert_eq!(confirm_status.block_height, Some(simulator.height()));
+    assert_eq!(
+        esplora.get_tx(&confirm_txid).await.unwrap(),
+        Some(confirm_tx)
+    );
+    assert!(peg_in_graph.deposit(esplora).await.is_err());
+}
+
+#[tokio::test]
+async fn test_simulator_peg_in_refund_timelock() {
+    let simulator = LedgerSimulator::start().await.unwrap();
+    let config = setup_test_with_esplora_url(simulator.url()).await;
+    let esplora = &config.client_0.esplora;
+    let mut peg_in_graph = create_peg_in_graph(
+        &config,
+        &simulator,
+        INITIAL_AMOUNT + MIN_RELAY_FEE_PEG_IN_DEPOSIT + MIN_RELAY_FEE_PEG_IN_REFUND,
+    );
+
+    let deposit_tx = peg_in_graph.deposit(esplora).await.unwrap();
+    assert!(esplora.broadcast(&deposit_tx).await.is_ok());
+    simulator.mine(1);
+
+    // The refund can only be mined `connector_z_timelock_0` blocks after the deposit.
+    let refund_tx = peg_in_graph.refund(esplora).await.unwrap();
+    let timelock = config.protocol_parameters.connector_z_timelock_0;
+    simulator.mine(timelock - 2);
+    let refund_result = esplora.broadcast(&refund_tx).await;
+    assert!(refund_result
+        .unwrap_err()
+        .to_string()
+        .contains("RelativeTimelock"));
+
+    simulator.mine(1);
+    assert!(esplora.broadcast(&refund_tx).await.is_ok());
+    simulator.mine(1);
+
+    let depositor_address = generate_pay_to_pubkey_script_address(
+        config.depositor_context.network,
+        &config.depositor_context.depositor_public_key,
+    );
+    let depositor_utxos = esplora.get_address_utxo(depositor_address).await.unwrap();
+    assert_eq!(depositor_utxos.len(), 1);
+    assert_eq!(depositor_utxos[0].txid, refund_tx.compute_txid());
+    assert_eq!(depositor_utxos[0].value, refund_tx.output[0].value);
+    assert!(depositor_utxos[0].status.confirmed);
+}
+
+#[tokio::test]
+async fn test_ledger_rejects_invalid_transactions() {
+    let simulator = LedgerSimulator::start().await.unwrap();
+    let config = setup_test_with_esplora_url(simulator.url()).await;
+    let peg_in_graph = create_peg_in_graph(
+        &config,
+        &simulator,
+        INITIAL_AMOUNT + MIN_RELAY_FEE_PEG_IN_DEPOSIT + MIN_RELAY_FEE_PEG_IN_CONFIRM,
+    );
+    let mut ledger: Ledger = simulator.ledger().clone();
+    let deposit_tx = peg_in_graph.peg_in_deposit_transaction.finalize();
+    let deposit_outpoint = deposit_tx.input[0].previous_output;
+    let confirm_tx = peg_in_graph.peg_in_confirm_transaction.finalize();
+    let refund_tx = peg_in_graph.peg_in_refund_transaction.finalize();
+
+    assert_eq!(
+        ledger.submit(confirm_tx),
+        Err(LedgerError::MissingInput(OutPoint {
+            txid: deposit_tx.compute_txid(),
+            vout: 0
+        }))
+    );
+
+    let mut overspending_tx = deposit_tx.clone();
+    overspending_tx.output[0].value += Amount::from_sat(MIN_RELAY_FEE_PEG_IN_DEPOSIT + 1);
+    assert!(matches!(
+        ledger.submit(overspending_tx),
+        Err(LedgerError::InsufficientInputValue { .. })
+    ));
+
+    // The depositor signature commits to the outputs.
+    let mut tampered_tx = deposit_tx.clone();
+    tampered_tx.output[0].value -= Amount::from_sat(1);
+    assert!(matches!(
+        ledger.submit(tampered_tx.clone()),
+        Err(LedgerError::Script { input_index: 0, .. })
+    ));
+
+    let deposit_txid = ledger.submit(deposit_tx.clone()).unwrap();
+    assert_eq!(
+        ledger.submit(deposit_tx),
+        Err(LedgerError::AlreadyKnown(deposit_txid))
+    );
+    assert_eq!(
+        ledger.submit(tampered_tx),
+        Err(LedgerError::DoubleSpend {
+            outpoint: deposit_outpoint,
+            spent_by: deposit_txid
+        })
+    );
+
+    ledger.mine(1);
+    assert_eq!(
+        ledger.submit(refund_tx),
+        Err(LedgerError::RelativeTimelock {
+            input_index: 0,
+            required: config.protocol_parameters.connector_z_timelock_0,
+            elapsed: 1
+        })
+    );
+
+    // P2WSH witness scripts are executed, including their timelocks.
+    let timelock_script = generate_timelock_script(&config.operator_context.operator_public_key, 1);
+    let timelock_outpoint = ledger.fund(
+        ScriptBuf::new_p2wsh(&timelock_script.wscript_hash()),
+        Amount::from_sat(INITIAL_AMOUNT),
+    );
+    let mut unsigned_tx = Transaction {
+        version: Version::TWO,
+        lock_time: absolute::LockTime::ZERO,
+        input: vec![TxIn {
+            previous_output: timelock_outpoint,
+            script_sig: ScriptBuf::new(),
+            sequence: Sequence::MAX,
+            witness: Witness::from_slice(&[vec![], timelock_script.to_bytes()]),
+        }],
+        output: vec![],
+    };
+    assert!(matches!(
+        ledger.submit(unsigned_tx.clone()),
+        Err(LedgerError::Script { input_index: 0, reason }) if reason.contains("relative timelock")
+    ));
+    unsigned_tx.input[0].sequence = Sequence::from_height(1);
+    assert!(matches!(
+        ledger.submit(unsigned_tx),
+        Err(LedgerError::Script { input_index: 0, reason }) if reason == "Script did not succeed"
+    ));
+}
+
+#[tokio::test]
+async fn test_simulator_peg_out_take_1() {
+    let simulator = LedgerSimulator::start().await.unwrap();
+    let config = setup_test_with_esplora_url(simulator.url()).await;
+    let esplora = &config.client_0.esplora;
+    let mut peg_out_graph = kick_off_peg_out(&config, &simulator).await;
+
+    // start time
+    let start_time_block_number = get_start_time_block_number(config.network);
+    simulator.mine(start_time_block_number.saturating_sub(simulator.height()));
+    let start_time_tx = peg_out_graph
+        .start_time(
+            esplora,
+            &config.operator_context,
+            &config.commitment_secrets[&CommitmentMessageId::StartTime],
+        )
+        .await
+        .unwrap();
+    broadcast_and_mine(&simulator, esplora, &start_time_tx).await;
+
+    // kick-off 2
+    simulator.mine(config.protocol_parameters.connector_1_timelock_leaf_0);
+    let kick_off_2_tx = kick_off_2(&mut peg_out_graph, &config).await.unwrap();
+    broadcast_and_mine(&simulator, esplora, &kick_off_2_tx).await;
+
+    // take 1
+    assert!(matches!(
+        peg_out_graph.take_1(esplora).await,
+        Err(Error::Graph(GraphError::PrecedingTxTimelockNotMet(_)))
+    ));
+    simulator.mine(config.protocol_parameters.connector_3_timelock);
+    let take_1_tx = peg_out_graph.take_1(esplora).await.unwrap();
+    broadcast_and_mine(&simulator, esplora, &take_1_tx).await;
+
+    assert!(
+        esplora
+            .get_tx_status(&take_1_tx.compute_txid())
+            .await
+            .unwrap()
+            .confirmed
+    );
+    assert!(peg_out_graph.take_1(esplora).await.is_err());
+}
+
+#[tokio::test]
+async fn test_simulator_peg_out_challenge_and_disprove() {
+    let simulator = LedgerSimulator::start().await.unwrap();
+    let config = setup_test_with_esplora_url(simulator.url()).await;
+    let esplora = &config.client_0.esplora;
+    let mut peg_out_graph = kick_off_peg_out(&config, &simulator).await;
+
+    // challenge
+    let depositor_script =
+        generate_pay_to_pubkey_script(&config.depositor_context.depositor_public_key);
+    let crowdfunding_amount = Amount::from_sat(peg_out_graph.min_crowdfunding_amount());
+    let crowdfunding_outpoint = simulator.fund(
+        &generate_pay_to_pubkey_script_address(
+            config.depositor_context.network,
+            &config.depositor_context.depositor_public_key,
+        ),
+        crowdfunding_amount,
+    );
+    let challenge_tx = peg_out_graph
+        .challenge(
+            esplora,
+            &vec![InputWithScript {
+                outpoint: crowdfunding_outpoint,
+                amount: crowdfunding_amount,
+                script: &depositor_script,
+            }],
+            &config.depositor_context.depositor_keypair,
+            depositor_script.clone(),
+        )
+        .await
+        .unwrap();
+    broadcast_and_mine(&simulator, esplora, &challenge_tx).await;
+
+    // kick-off 2, the challenge prevents take 1
+    simulator.mine(config.protocol_parameters.connector_1_timelock_leaf_0);
+    let kick_off_2_tx = kick_off_2(&mut peg_out_graph, &config).await.unwrap();
+    broadcast_and_mine(&simulator, esplora, &kick_off_2_tx).await;
+    simulator.mine(config.protocol_parameters.connector_3_timelock);
+    assert!(matches!(
+        peg_out_graph.take_1(esplora).await,
+        Err(Error::Transaction(TransactionError::AlreadyMined(txid)))
+            if txid == challenge_tx.compute_txid()
+    ));
+
+    // assert with an invalid proof
+    simulator.mine(config.protocol_parameters.connector_b_timelock_1);
+    let assert_initial_tx = peg_out_graph.assert_initial(esplora).await.unwrap();
+    broadcast_and_mine(&simulator, esplora, &assert_initial_tx).await;
+    let assert_commit_1_tx = peg_out_graph
+        .assert_commit_1(esplora, &config.commitment_secrets, &config.invalid_proof)
+        .await
+        .unwrap();
+    let assert_commit_2_tx = peg_out_graph
+        .assert_commit_2(esplora, &config.commitment_secrets, &config.invalid_proof)
+        .await
+        .unwrap();
+    assert!(esplora.broadcast(&assert_commit_1_tx).await.is_ok());
+    broadcast_and_mine(&simulator, esplora, &assert_commit_2_tx).await;
+    let assert_final_tx = peg_out_graph.assert_final(esplora).await.unwrap();
+    broadcast_and_mine(&simulator, esplora, &assert_final_tx).await;
+
+    // disprove
+    let verifier_script =
+        generate_pay_to_pubkey_script(&config.verifier_0_context.verifier_public_key);
+    let disprove_tx = peg_out_graph
+        .disprove(esplora, verifier_script, &config.invalid_proof.vk)
+        .await
+        .unwrap();
+    broadcast_and_mine(&simulator, esplora, &disprove_tx).await;
+
+    assert!(
+        esplora
+            .get_tx_status(&disprove_tx.compute_txid())
+            .await
+            .unwrap()
+            .confirmed
+    );
+    simulator.mine(config.protocol_parameters.connector_4_timelock);
+    assert!(matches!(
+        peg_out_graph.take_2(esplora, &config.operator_context).await,
+        Err(Error::Transaction(TransactionError::AlreadyMined(txid)))
+            if txid == disprove_tx.compute_txid()
+    ));
+}
+
+#[tokio::test]
+async fn test_simulator_peg_out_challenge_alongside_crowdfunding() {
+    let simulator = LedgerSimulator::start().await.unwrap();
+    let config = setup_test_with_esplora_url(simulator.url()).await;
+    let esplora = &config.client_0.esplora;
+    let mut peg_out_graph = kick_off_peg_out(&config, &simulator).await;
+
+    // A verifier contributes part of the crowdfunding.
+    let verifier_public_key = config.verifier_0_context.verifier_public_key;
+    let contribution_amount = Amount::from_sat(peg_out_graph.min_crowdfunding_amount() / 2);
+    let contribution_outpoint = simulator.fund(
+        &generate_pay_to_pubkey_script_address(config.network, &verifier_public_key),
+        contribution_amount,
+    );
+    peg_out_graph
+        .contribute_to_challenge(
+            esplora,
+            &InputWithScript {
+                outpoint: contribution_outpoint,
+                amount: contribution_amount,
+                script: &generate_pay_to_pubkey_script(&verifier_public_key),
+            },
+            &config.verifier_0_context.verifier_keypair,
+        )
+        .await
+        .unwrap();
+
+    // The depositor funds the challenge alone, with a refund.
+    let depositor_script =
+        generate_pay_to_pubkey_script(&config.depositor_context.depositor_public_key);
+    let crowdfunding_amount = Amount::from_sat(
+        peg_out_graph.min_crowdfunding_amount() + 2 * config.protocol_parameters.dust_amount,
+    );
+    let crowdfunding_outpoint = simulator.fund(
+        &generate_pay_to_pubkey_script_address(
+            config.depositor_context.network,
+            &config.depositor_context.depositor_public_key,
+        ),
+        crowdfunding_amount,
+    );
+    let challenge_tx = peg_out_graph
+        .challenge(
+            esplora,
+            &vec![InputWithScript {
+                outpoint: crowdfunding_outpoint,
+                amount: crowdfunding_amount,
+                script: &depositor_script,
+            }],
+            &config.depositor_context.depositor_keypair,
+            depositor_script.clone(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(challenge_tx.input.len(), 2);
+    assert_eq!(challenge_tx.output.len(), 2);
+    broadcast_and_mine(&simulator, esplora, &challenge_tx).await;
+
+    // The contribution is left unspent and the graph finds the challenge.
+    assert!(!esplora
+        .get_output_status(
+            &contribution_outpoint.txid,
+            contribution_outpoint.vout as u64
+        )
+        .await
+        .unwrap()
+        .is_some_and(|status| status.spent));
+    assert_eq!(
+        peg_out_graph
+            .challenge_crowdfunding_status(esplora)
+            .await
+            .unwrap(),
+        ChallengeCrowdfundingStatus::Challenged(challenge_tx.compute_txid())
+    );
+    assert!(matches!(
+        peg_out_graph.take_1(esplora).await,
+        Err(Error::Transaction(TransactionError::AlreadyMined(txid)))
+            if txid == challenge_tx.compute_txid()
+    ));
+}
+
+#[tokio::test]
+async fn test_simulator_peg_out_kick_off_timeout() {
+    let simulator = LedgerSimulator::start().await.unwrap();
+    let config = setup_test_with_esplora_url(simulator.url()).await;
+    let esplora = &config.client_0.esplora;
+    let mut peg_out_graph = kick_off_peg_out(&config, &simulator).await;
+
+    // The operator does not follow up with kick-off 2 in time.
+    let verifier_script_pubkey = generate_pay_to_pubkey_script_address(
+        config.verifier_0_context.network,
+        &config.verifier_0_context.verifier_public_key,
+    )
+    .script_pubkey();
+    assert!(matches!(
+        peg_out_graph
+            .kick_off_timeout(esplora, verifier_script_pubkey.clone())
+            .await,
+        Err(Error::Graph(GraphError::PrecedingTxTimelockNotMet(_)))
+    ));
+    simulator.mine(config.protocol_parameters.connector_1_timelock_leaf_1);
+    let kick_off_timeout_tx = peg_out_graph
+        .kick_off_timeout(esplora, verifier_script_pubkey)
+        .await
+        .unwrap();
+    broadcast_and_mine(&simulator, esplora, &kick_off_timeout_tx).await;
+
+    let kick_off_2_tx = kick_off_2(&mut peg_out_graph, &config).await.unwrap();
+    assert!(esplora
+        .broadcast(&kick_off_2_tx)
+        .await
+        .unwrap_err()
+        .to_string()
+        .contains("DoubleSpend"));
+    assert!(peg_out_graph.take_1(esplora).await.is_err());
+}
+
+#[tokio::test]
+async fn test_simulator_kick_off_1_destination_network_txid_commitment() {
+    let simulator = LedgerSimulator::start().await.unwrap();
+    let config = setup_test_with_esplora_url(simulator.url()).await;
+    let esplora = &config.client_0.esplora;
+    let peg_out_graph = kick_off_peg_out(&config, &simulator).await;
+
+    let kick_off_1_tx = esplora
+        .get_tx(&peg_out_graph.kick_off_1_txid())
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(
+        peg_out_graph.committed_destination_network_txid(&kick_off_1_tx),
+        Some(
+            peg_out_graph
+                .peg_out_chain_event
+                .as_ref()
+                .unwrap()
+                .tx_hash
+                .clone()
+        )
+    );
+
+    // Not the kick-off 1 transaction of the graph.
+    let peg_out_confirm_tx = esplora
+        .get_tx(&kick_off_1_tx.input[0].previous_output.txid)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(
+        peg_out_graph.committed_destination_network_txid(&peg_out_confirm_tx),
+        None
+    );
+}
+
+fn create_peg_in_graph(config: &SetupConfig, simulator: &LedgerSimulator, sats: u64) -> PegInGraph {
+    let mut peg_in_graph = create_funded_peg_in_graph(config, simulator, Amount::from_sat(sats));
+    sign_graph(config, &mut peg_in_graph);
+
+    peg_in_graph
+}
+
+// Runs a peg-in and the peg-out of it fronted by the operator, up to kick-off 1, which is mined.
+async fn kick_off_peg_out(config: &SetupConfig, simulator: &LedgerSimulator) -> PegOutGraph {
+    let esplora = &config.client_0.esplora;
+    let mut peg_in_graph = create_peg_in_graph(
+        config,
+        simulator,
+        INITIAL_AMOUNT + MIN_RELAY_FEE_PEG_IN_DEPOSIT + MIN_RELAY_FEE_PEG_IN_CONFIRM,
+    );
+    let mut peg_out_graph = create_peg_out_graph(config, simulator, &peg_in_graph);
+
+    // peg-in
+    let deposit_tx = peg_in_graph.deposit(esplora).await.unwrap();
+    broadcast_and_mine(simulator, esplora, &deposit_tx).await;
+    let confirm_tx = peg_in_graph.confirm(esplora).await.unwrap();
+    broadcast_and_mine(simulator, esplora, &confirm_tx).await;
+
+    // peg-out, fronted by the operator
+    let operator_address = generate_pay_to_pubkey_script_address(
+        config.operator_context.network,
+        &config.operator_context.operator_public_key,
+    );
+    let event = PegOutEvent {
+        source_outpoint: OutPoint {
+            txid: confirm_tx.compute_txid(),
+            vout: 0,
+        },
+        amount: confirm_tx.output[0].value,
+        timestamp: 1722328130u32,
+        withdrawer_chain_address: config.withdrawer_evm_address.clone(),
+        withdrawer_destination_address: generate_p2pkh_address(
+            config.withdrawer_context.network,
+            &config.withdrawer_context.withdrawer_public_key,
+        )
+        .to_string(),
+        withdrawer_public_key_hash: config
+            .withdrawer_context
+            .withdrawer_public_key
+            .pubkey_hash(),
+        operator_public_key: config.operator_context.operator_public_key,
+        tx_hash: [0u8; 32].into(),
+    };
+    let peg_out_amount = event.amount + Amount::from_sat(MIN_RELAY_FEE_PEG_OUT);
+    peg_out_graph.peg_out_chain_event = Some(event);
+    let peg_out_tx = peg_out_graph
+        .peg_out(
+            esplora,
+            &config.operator_context,
+            Input {
+                outpoint: simulator.fund(&operator_address, peg_out_amount),
+                amount: peg_out_amount,
+            },
+        )
+        .await
+        .unwrap();
+    broadcast_and_mine(simulator, esplora, &peg_out_tx).await;
+    let peg_out_confirm_tx = peg_out_graph.peg_out_confirm(esplora).await.unwrap();
+    broadcast_and_mine(simulator, esplora, &peg_out_confirm_tx).await;
+
+    // kick-off 1
+    let kick_off_1_tx = peg_out_graph
+        .kick_off_1(
+            esplora,
+            &config.operator_context,
+            &config.commitment_secrets[&CommitmentMessageId::PegOutTxIdSourceNetwork],
+            &config.commitment_secrets[&CommitmentMessageId::PegOutTxIdDestinationNetwork],
+        )
+        .await
+        .unwrap();
+    broadcast_and_mine(simulator, esplora, &kick_off_1_tx).await;
+
+    peg_out_graph
+}
+
+// Creates the peg-out graph of the operator for `peg_in_graph`, pre-signed by both verifiers.
+fn create_peg_out_graph(
+    config: &SetupConfig,
+    simulator: &LedgerSimulator,
+    peg_in_graph: &PegInGraph,
+) -> PegOutGraph {
+    let peg_out_confirm_amount = Amount::from_sat(INITIAL_AMOUNT + PEG_OUT_FEE);
+    let peg_out_confirm_outpoint = simulator.fund(
+        &generate_pay_to_pubkey_script_address(
+            config.operator_context.network,
+            &config.operator_context.operator_public_key,
+        ),
+        peg_out_confirm_amount,
+    );
+    let mut peg_out_graph = PegOutGraph::new(
+        &config.operator_context,
+        peg_in_graph,
+        Input {
+            outpoint: peg_out_confirm_outpoint,
+            amount: peg_out_confirm_amount,
+        },
+        &config.commitment_secrets,
+        &config.protocol_parameters,
+    );
+    sign_graph(config, &mut peg_out_graph);
+
+    peg_out_graph
+}
+
+async fn kick_off_2(
+    peg_out_graph: &mut PegOutGraph,
+    config: &SetupConfig,
+) -> Result<Transaction, Error> {
+    peg_out_graph
+        .kick_off_2(
+            &config.client_0.esplora,
+            &config.operator_context,
+            &config.commitment_secrets[&CommitmentMessageId::Superblock],
+            &config.commitment_secrets[&CommitmentMessageId::SuperblockHash],
+        )
+        .await
+}
+
+async fn broadcast_and_mine(simulator: &LedgerSimulator, esplora: &AsyncClient, tx: &Transaction) {
+    let result = esplora.broadcast(tx).await;
+    assert!(result.is_ok(), "Failed to broadcast: {:?}", result);
+    simulator.mine(1);
+}
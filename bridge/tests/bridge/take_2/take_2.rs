@@ -82,18 +82,22 @@ async fn test_take_2_tx_success() {
     let secret_nonces_0 = take_2_tx.push_nonces(&config.verifier_0_context);
     let secret_nonces_1 = take_2_tx.push_nonces(&config.verifier_1_context);
 
-    take_2_tx.pre_sign(
-        &config.verifier_0_context,
-        &config.connector_0,
-        &config.connector_5,
-        &secret_nonces_0,
-    );
-    take_2_tx.pre_sign(
-        &config.verifier_1_context,
-        &config.connector_0,
-        &config.connector_5,
-        &secret_nonces_1,
-    );
+    take_2_tx
+        .pre_sign(
+            &config.verifier_0_context,
+            &config.connector_0,
+            &config.connector_5,
+            &secret_nonces_0,
+        )
+        .unwrap();
+    take_2_tx
+        .pre_sign(
+            &config.verifier_1_context,
+            &config.connector_0,
+            &config.connector_5,
+            &secret_nonces_1,
+        )
+        .unwrap();
 
     take_2_tx.sign(&config.operator_context, &config.connector_c);
 
@@ -3,7 +3,11 @@ use std::str::FromStr;
 use bitcoin::{Amount, OutPoint, Txid};
 
 use bridge::{
-    graphs::{base::PEG_OUT_FEE, peg_in::PegInGraph, peg_out::PegOutGraph},
+    graphs::{
+        base::{BaseGraph, PEG_OUT_FEE},
+        peg_in::PegInGraph,
+        peg_out::PegOutGraph,
+    },
     scripts::generate_burn_script,
     transactions::{base::Input, pre_signed::PreSignedTransaction},
 };
@@ -108,6 +112,7 @@ async fn setup_and_create_graphs() -> (PegInGraph, PegOutGraph, OutPoint) {
             amount,
         },
         &config.depositor_evm_address,
+        &config.protocol_parameters,
     );
 
     let peg_out_graph = PegOutGraph::new(
@@ -118,6 +123,7 @@ async fn setup_and_create_graphs() -> (PegInGraph, PegOutGraph, OutPoint) {
             amount,
         },
         &config.commitment_secrets,
+        &config.protocol_parameters,
     );
 
     (peg_in_graph, peg_out_graph, peg_in_outpoint)
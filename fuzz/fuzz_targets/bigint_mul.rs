@@ -0,0 +1,22 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use arbitrary::Arbitrary;
+
+use bitvm::bigint::{U254, U64};
+
+/// Operands as little-endian bytes, truncated to the bit size of the big integer.
+#[derive(Debug, Arbitrary)]
+struct Operands {
+    a: [u8; 32],
+    b: [u8; 32],
+    // U254 multiplication is expensive, so only every other input exercises it
+    use_u254: bool,
+}
+
+fuzz_target!(|data: Operands| {
+    if data.use_u254 {
+        U254::test_mul_givenbytes(&data.a, &data.b);
+    }
+    U64::test_mul_givenbytes(&data.a[..8], &data.b[..8]);
+});
@@ -0,0 +1,18 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use arbitrary::Arbitrary;
+
+use bitvm::bigint::{U254, U64};
+
+/// Operands as little-endian bytes, truncated to the bit size of the big integer.
+#[derive(Debug, Arbitrary)]
+struct Operands {
+    a: [u8; 32],
+    b: [u8; 32],
+}
+
+fuzz_target!(|data: Operands| {
+    U254::test_sub_givenbytes(&data.a, &data.b);
+    U64::test_sub_givenbytes(&data.a[..8], &data.b[..8]);
+});
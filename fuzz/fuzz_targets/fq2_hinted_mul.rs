@@ -0,0 +1,17 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use arbitrary::Arbitrary;
+
+use bitvm::bn254::fq2::Fq2;
+
+/// Operands as little-endian bytes of the base field coefficients, reduced modulo the field order.
+#[derive(Debug, Arbitrary)]
+struct Operands {
+    a: [u8; 64],
+    b: [u8; 64],
+}
+
+fuzz_target!(|data: Operands| {
+    Fq2::test_hinted_mul_givenbytes(&data.a, &data.b);
+});
@@ -0,0 +1,24 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use arbitrary::{Arbitrary, Unstructured, Result};
+
+use bitvm::hash::sha256::test_sha256_givenbyteslice;
+
+/// This struct will hold up to 256 bytes of fuzz data.
+#[derive(Debug)]
+struct LimitedBytes(Vec<u8>);
+
+impl<'a> Arbitrary<'a> for LimitedBytes {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        // `sha256` supports inputs shorter than 512 bytes, confine it further to keep executions fast
+        let size = u.int_in_range(0..=256)?;
+        let mut bytes = vec![0u8; size];
+        u.fill_buffer(&mut bytes)?;
+        Ok(LimitedBytes(bytes))
+    }
+}
+
+fuzz_target!(|data: LimitedBytes| {
+    test_sha256_givenbyteslice(&data.0);
+});
@@ -0,0 +1,30 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use arbitrary::{Arbitrary, Unstructured, Result};
+
+use bitvm::hash::sha256_u4_stack::test_sha256_stack_givenbyteslice;
+
+/// This struct will hold between 1 and 256 bytes of fuzz data and the table options.
+#[derive(Debug)]
+struct Input {
+    bytes: Vec<u8>,
+    use_add_table: bool,
+    use_full_xor: bool,
+}
+
+impl<'a> Arbitrary<'a> for Input {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        let use_add_table = u.arbitrary()?;
+        let use_full_xor = u.arbitrary()?;
+        // `sha256_stack` needs at least one byte of message
+        let size = u.int_in_range(1..=256)?;
+        let mut bytes = vec![0u8; size];
+        u.fill_buffer(&mut bytes)?;
+        Ok(Input { bytes, use_add_table, use_full_xor })
+    }
+}
+
+fuzz_target!(|data: Input| {
+    test_sha256_stack_givenbyteslice(&data.bytes, data.use_add_table, data.use_full_xor);
+});
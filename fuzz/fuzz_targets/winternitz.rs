@@ -0,0 +1,29 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use arbitrary::{Arbitrary, Unstructured, Result};
+
+use bitvm::signatures::winternitz::test_winternitz_givenbytes;
+
+/// Random secret key, message of 1 to 64 bytes and block length in the supported range.
+#[derive(Debug)]
+struct Input {
+    secret_key: [u8; 20],
+    message: Vec<u8>,
+    block_length: u32,
+}
+
+impl<'a> Arbitrary<'a> for Input {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        let secret_key = u.arbitrary()?;
+        let block_length = u.int_in_range(4..=8)?;
+        let size = u.int_in_range(1..=64)?;
+        let mut message = vec![0u8; size];
+        u.fill_buffer(&mut message)?;
+        Ok(Input { secret_key, message, block_length })
+    }
+}
+
+fuzz_target!(|data: Input| {
+    test_winternitz_givenbytes(&data.secret_key, &data.message, data.block_length);
+});